@@ -38,6 +38,8 @@ pub struct DeviceInfo {
     pub has_pairing_request: bool,
     /// Last seen timestamp (UNIX timestamp)
     pub last_seen: i64,
+    /// Seconds the device has been continuously connected, 0 if not connected
+    pub connected_seconds: u64,
     /// Supported incoming plugin capabilities
     pub incoming_capabilities: Vec<String>,
     /// Supported outgoing plugin capabilities
@@ -152,6 +154,18 @@ pub struct SyncFolderInfo {
     pub strategy: String,
 }
 
+/// File recently received from a device
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
+pub struct ReceivedFileInfo {
+    pub path: String,
+    pub name: String,
+    #[allow(dead_code)]
+    pub device_id: String,
+    pub device_name: String,
+    /// Unix timestamp in seconds
+    pub received_at: u64,
+}
+
 impl Default for RemoteDesktopSettings {
     fn default() -> Self {
         Self {
@@ -302,11 +316,8 @@ pub enum DaemonEvent {
     },
     /// Plugin event
     PluginEvent {
-        #[allow(dead_code)]
         device_id: String,
-        #[allow(dead_code)]
         plugin: String,
-        #[allow(dead_code)]
         data: String,
     },
     /// Device plugin state changed
@@ -387,6 +398,9 @@ trait CConnect {
     /// Request pairing with a device
     async fn pair_device(&self, device_id: &str) -> zbus::fdo::Result<()>;
 
+    /// Accept a pairing request from a device
+    async fn accept_pairing(&self, device_id: &str) -> zbus::fdo::Result<()>;
+
     /// Unpair a device
     async fn unpair_device(&self, device_id: &str) -> zbus::fdo::Result<()>;
 
@@ -546,6 +560,12 @@ trait CConnect {
     /// Get list of synced folders for a device
     async fn get_sync_folders(&self, device_id: String) -> zbus::fdo::Result<Vec<SyncFolderInfo>>;
 
+    /// Get files recently received from devices, newest first
+    async fn get_recent_received_files(
+        &self,
+        limit: u32,
+    ) -> zbus::fdo::Result<Vec<ReceivedFileInfo>>;
+
     /// Signal: Device was added
     #[zbus(signal)]
     fn device_added(device_id: &str, device_info: DeviceInfo) -> zbus::fdo::Result<()>;
@@ -1021,6 +1041,15 @@ impl DbusClient {
             .context("Failed to pair device")
     }
 
+    /// Accept a pairing request from a device
+    pub async fn accept_pairing(&self, device_id: &str) -> Result<()> {
+        info!("Accepting pairing request from device {}", device_id);
+        self.proxy
+            .accept_pairing(device_id)
+            .await
+            .context("Failed to accept pairing")
+    }
+
     /// Unpair a device
     pub async fn unpair_device(&self, device_id: &str) -> Result<()> {
         info!("Unpairing device {}", device_id);
@@ -1507,6 +1536,14 @@ impl DbusClient {
             .context("Failed to call get_sync_folders")
     }
 
+    /// Get files recently received from devices that still exist
+    pub async fn get_recent_received_files(&self, limit: u32) -> Result<Vec<ReceivedFileInfo>> {
+        self.proxy
+            .get_recent_received_files(limit)
+            .await
+            .context("Failed to call get_recent_received_files")
+    }
+
     /// Add a run command
     pub async fn add_run_command(
         &self,
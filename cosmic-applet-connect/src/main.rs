@@ -11,7 +11,7 @@ use messages::{Message, NotificationType, OperationType};
 use state::{
     ActiveScreenShare, AppNotification, CameraStats, DeviceState, FocusTarget, HistoryEvent,
     ReceivedFile, SystemInfo, TransferState, ViewMode, MAX_DISPLAYED_HISTORY_ITEMS,
-    MAX_RECEIVED_FILES_HISTORY,
+    MAX_POPUP_RECEIVED_FILES, MAX_RECEIVED_FILES_HISTORY,
 };
 
 use cosmic::{
@@ -376,6 +376,7 @@ struct CConnectApplet {
     // File transfers
     active_transfers: HashMap<String, TransferState>,
     received_files_history: Vec<ReceivedFile>,
+    recent_received_files: Vec<dbus_client::ReceivedFileInfo>,
     // Renaming state
     renaming_device: Option<String>,
     nickname_input: String,
@@ -533,6 +534,24 @@ async fn fetch_mpris_players() -> Vec<String> {
     }
 }
 
+async fn fetch_recent_received_files() -> Vec<dbus_client::ReceivedFileInfo> {
+    let Ok((client, _)) = DbusClient::connect().await else {
+        tracing::warn!("Failed to connect to daemon for recent received files");
+        return Vec::new();
+    };
+
+    match client
+        .get_recent_received_files(MAX_POPUP_RECEIVED_FILES as u32)
+        .await
+    {
+        Ok(files) => files,
+        Err(e) => {
+            tracing::error!("Failed to get recent received files: {}", e);
+            Vec::new()
+        }
+    }
+}
+
 /// Opens a file picker dialog and returns device_id with selected file paths
 async fn open_file_picker(device_id: String, multiple: bool) -> Option<(String, Vec<String>)> {
     use ashpd::desktop::file_chooser::OpenFileRequest;
@@ -655,13 +674,7 @@ where
 
 /// Converts a D-Bus DeviceInfo to our internal DeviceState
 fn convert_device_info(info: &dbus_client::DeviceInfo) -> DeviceState {
-    let device_type = match info.device_type.as_str() {
-        "phone" => DeviceType::Phone,
-        "tablet" => DeviceType::Tablet,
-        "laptop" => DeviceType::Laptop,
-        "tv" => DeviceType::Tv,
-        _ => DeviceType::Desktop,
-    };
+    let device_type = DeviceType::from_identity_str(&info.device_type);
 
     let connection_state = if info.is_connected {
         ConnectionState::Connected
@@ -671,6 +684,8 @@ fn convert_device_info(info: &dbus_client::DeviceInfo) -> DeviceState {
 
     let pairing_status = if info.is_paired {
         PairingStatus::Paired
+    } else if info.has_pairing_request {
+        PairingStatus::RequestedByPeer
     } else {
         PairingStatus::Unpaired
     };
@@ -691,8 +706,17 @@ fn convert_device_info(info: &dbus_client::DeviceInfo) -> DeviceState {
         },
         host: None,
         port: None,
+        last_endpoint: None,
         certificate_fingerprint: None,
         certificate_data: None,
+        rtt: Default::default(),
+        connected_at: if info.is_connected {
+            std::time::Instant::now()
+                .checked_sub(std::time::Duration::from_secs(info.connected_seconds))
+        } else {
+            None
+        },
+        beacon_expires_at: None,
     };
 
     DeviceState {
@@ -745,6 +769,7 @@ impl cosmic::Application for CConnectApplet {
             mpris_album_art: HashMap::new(),
             active_transfers: std::collections::HashMap::new(),
             received_files_history: Vec::new(),
+            recent_received_files: Vec::new(),
             renaming_device: None,
             nickname_input: String::new(),
             history: Vec::new(),
@@ -822,6 +847,9 @@ impl cosmic::Application for CConnectApplet {
                     Task::perform(fetch_mpris_players(), |players| {
                         cosmic::Action::App(Message::MprisPlayersUpdated(players))
                     }),
+                    Task::perform(fetch_recent_received_files(), |files| {
+                        cosmic::Action::App(Message::RecentReceivedFilesLoaded(files))
+                    }),
                 ])
             }
             Message::SetViewMode(mode) => {
@@ -887,6 +915,20 @@ impl cosmic::Application for CConnectApplet {
                     ),
                 ])
             }
+            Message::AcceptPairing(device_id) => {
+                let id = device_id.clone();
+                Task::batch(vec![
+                    Task::done(cosmic::Action::App(Message::OperationStarted(
+                        device_id.clone(),
+                        OperationType::Pair,
+                    ))),
+                    device_operation_with_completion(
+                        device_id,
+                        OperationType::Pair,
+                        move |client, _| async move { client.accept_pairing(&id).await },
+                    ),
+                ])
+            }
             Message::UnpairDevice(device_id) => {
                 let id = device_id.clone();
                 Task::batch(vec![
@@ -2046,6 +2088,25 @@ impl cosmic::Application for CConnectApplet {
                 }
                 Task::none()
             }
+            Message::RecentReceivedFilesLoaded(files) => {
+                self.recent_received_files = files;
+                Task::none()
+            }
+            Message::OpenReceivedFile(path) => {
+                let file_path = std::path::Path::new(&path);
+                if file_path.exists() {
+                    if let Err(e) = std::process::Command::new("xdg-open")
+                        .arg(file_path)
+                        .spawn()
+                    {
+                        tracing::error!("Failed to open file: {}", e);
+                    }
+                } else {
+                    tracing::warn!("File not found: {:?}", file_path);
+                    self.recent_received_files.retain(|file| file.path != path);
+                }
+                Task::none()
+            }
             Message::RevealTransferFile(filename) => {
                 self.context_menu_transfer = None;
                 let downloads_dir = std::env::var("HOME")
@@ -2925,16 +2986,57 @@ impl CConnectApplet {
                     details: format!("Device {} wants to pair", device_id),
                 });
             }
-            dbus_client::DaemonEvent::PairingStatusChanged {
-                device_id: _,
-                status,
-            } => {
+            dbus_client::DaemonEvent::PairingStatusChanged { device_id, status } => {
+                let device_state = self
+                    .devices
+                    .iter_mut()
+                    .find(|d| d.device.info.device_id == *device_id);
+                let name = device_state
+                    .as_ref()
+                    .map(|d| d.device.info.device_name.clone())
+                    .unwrap_or_else(|| "Unknown".to_string());
+
+                let requested = match status.as_str() {
+                    "requested_by_us" => Some(PairingStatus::RequestedByUs),
+                    "requested_by_peer" => Some(PairingStatus::RequestedByPeer),
+                    _ => None,
+                };
+                if let (Some(requested), Some(device_state)) = (requested, device_state) {
+                    device_state.device.pairing_status = requested;
+                }
+
                 self.history.push(HistoryEvent {
                     timestamp,
                     event_type: "Pairing Status".to_string(),
-                    device_name: "Unknown".to_string(),
+                    device_name: name.clone(),
                     details: status.clone(),
                 });
+
+                // Prompt for the side that has to act next
+                match requested {
+                    Some(PairingStatus::RequestedByUs) => {
+                        return cosmic::task::message(cosmic::Action::App(
+                            Message::ShowNotification(
+                                format!("Waiting for {} to accept pairing", name),
+                                NotificationType::Info,
+                                None,
+                            ),
+                        ));
+                    }
+                    Some(PairingStatus::RequestedByPeer) => {
+                        return cosmic::task::message(cosmic::Action::App(
+                            Message::ShowNotification(
+                                format!("{} wants to pair", name),
+                                NotificationType::Info,
+                                Some((
+                                    "Accept".into(),
+                                    Box::new(Message::AcceptPairing(device_id.to_string())),
+                                )),
+                            ),
+                        ));
+                    }
+                    _ => {}
+                }
             }
             dbus_client::DaemonEvent::ScreenShareRequested { device_id } => {
                 self.history.push(HistoryEvent {
@@ -2981,6 +3083,42 @@ impl CConnectApplet {
                     )),
                 )));
             }
+            dbus_client::DaemonEvent::PluginEvent {
+                device_id,
+                plugin,
+                data,
+            } if plugin == "notification" => {
+                // Notifications suppressed by Do Not Disturb are only logged
+                let Ok(event) = serde_json::from_str::<serde_json::Value>(data) else {
+                    return Task::none();
+                };
+                if event.get("event").and_then(|v| v.as_str()) != Some("suppressed") {
+                    return Task::none();
+                }
+
+                let name = self
+                    .devices
+                    .iter()
+                    .find(|d| d.device.info.device_id == *device_id)
+                    .map(|d| d.device.info.device_name.clone())
+                    .unwrap_or_else(|| "Unknown".to_string());
+                let app_name = event.get("appName").and_then(|v| v.as_str()).unwrap_or("");
+                let title = event.get("title").and_then(|v| v.as_str()).unwrap_or("");
+
+                self.history.push(HistoryEvent {
+                    timestamp,
+                    event_type: "Notification (Do Not Disturb)".to_string(),
+                    device_name: name,
+                    details: format!("{}: {}", app_name, title),
+                });
+
+                // Keep history bounded
+                if self.history.len() > 50 {
+                    self.history.remove(0);
+                }
+
+                return Task::none();
+            }
             dbus_client::DaemonEvent::ScreenShareStarted {
                 device_id,
                 is_sender,
@@ -44,6 +44,7 @@ pub enum Message {
     DeviceEvent(dbus_client::DaemonEvent),
     SearchChanged(String),
     PairDevice(String),
+    AcceptPairing(String),
     UnpairDevice(String),
     RefreshDevices,
     SendPing(String),
@@ -203,6 +204,8 @@ pub enum Message {
         String,
     ), // id, device, file, cur, tot, dir
     TransferComplete(String, String, String, bool, String), // id, device, file, success, error
+    RecentReceivedFilesLoaded(Vec<dbus_client::ReceivedFileInfo>),
+    OpenReceivedFile(String), // path
     // File Sync
     LoadSyncFolders(String),
     SyncFoldersLoaded(String, Vec<dbus_client::SyncFolderInfo>),
@@ -8,7 +8,7 @@ pub use camera::CameraStats;
 pub use device::{AppNotification, DeviceState, FocusTarget, HistoryEvent, ViewMode};
 pub use screen_share::ActiveScreenShare;
 pub use system::SystemInfo;
-pub use transfer::{ReceivedFile, TransferState, MAX_DISPLAYED_HISTORY_ITEMS, MAX_RECEIVED_FILES_HISTORY};
+pub use transfer::{ReceivedFile, TransferState, MAX_DISPLAYED_HISTORY_ITEMS, MAX_POPUP_RECEIVED_FILES, MAX_RECEIVED_FILES_HISTORY};
 
 // Re-export NotificationType from messages module for device module
 pub use crate::messages::NotificationType;
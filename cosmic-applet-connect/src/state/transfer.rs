@@ -28,3 +28,6 @@ pub const MAX_RECEIVED_FILES_HISTORY: usize = 50;
 
 /// Number of recent files to display in the UI
 pub const MAX_DISPLAYED_HISTORY_ITEMS: usize = 10;
+
+/// Number of recently received files listed in the popup
+pub const MAX_POPUP_RECEIVED_FILES: usize = 5;
@@ -8,6 +8,7 @@ use cosmic::{
     Element,
 };
 
+use cosmic_connect_protocol::plugins::battery::BatteryState;
 use cosmic_connect_protocol::{ConnectionState, Device, DeviceType, PairingStatus};
 
 use crate::{
@@ -173,7 +174,7 @@ impl CConnectApplet {
 
         let display_name = nickname.unwrap_or(&device.info.device_name);
 
-        // Metadata row: Status • Battery • Last Seen
+        // Metadata row: Status • Battery • Connected For / Last Seen
         let mut metadata_row = row![connection_status_styled_text(
             device.connection_state,
             device.pairing_status
@@ -189,11 +190,11 @@ impl CConnectApplet {
                     .class(theme::Text::Color(theme_muted_color())),
             );
 
-            let battery_icon = battery_icon_name(level, device_state.is_charging);
+            let battery = BatteryState::new(level.into(), device_state.is_charging);
             metadata_row = metadata_row.push(
                 row![
-                    icon::from_name(battery_icon).size(ICON_XS),
-                    cosmic::widget::text::caption(format!("{}%", level)),
+                    icon::from_name(battery.icon_name()).size(ICON_XS),
+                    cosmic::widget::text::caption(battery.label()),
                 ]
                 .spacing(space_xxxs())
                 .align_y(cosmic::iced::Alignment::Center),
@@ -208,6 +209,19 @@ impl CConnectApplet {
                 metadata_row.push(icon::from_name("process-working-symbolic").size(ICON_XS));
         }
 
+        // Add connection uptime if connected
+        if let Some(duration) = device.connected_duration() {
+            metadata_row = metadata_row.push(
+                text("•")
+                    .size(ICON_XS)
+                    .class(theme::Text::Color(theme_muted_color())),
+            );
+            metadata_row = metadata_row.push(cosmic::widget::text::caption(format!(
+                "Connected for {}",
+                format_uptime(duration.as_secs())
+            )));
+        }
+
         // Add last seen if disconnected
         if !device.is_connected() && device.last_seen > 0 {
             let last_seen_text = format_last_seen(device.last_seen);
@@ -836,6 +850,7 @@ pub(crate) fn device_type_icon(device_type: DeviceType) -> &'static str {
         DeviceType::Desktop => "computer-symbolic",
         DeviceType::Laptop => "laptop-symbolic",
         DeviceType::Tv => "tv-symbolic",
+        DeviceType::Unknown => "network-wireless-symbolic",
     }
 }
 
@@ -849,6 +864,8 @@ pub(crate) fn connection_status_styled_text<'a>(
         (ConnectionState::Connecting, _) => "Connecting...",
         (ConnectionState::Failed, _) => "Connection failed",
         (ConnectionState::Disconnected, PairingStatus::Paired) => "Disconnected",
+        (ConnectionState::Disconnected, PairingStatus::RequestedByUs) => "Waiting for approval",
+        (ConnectionState::Disconnected, PairingStatus::RequestedByPeer) => "Wants to pair",
         (ConnectionState::Disconnected, _) => "Not paired",
     };
 
@@ -865,20 +882,6 @@ pub(crate) fn connection_status_styled_text<'a>(
         .into()
 }
 
-/// Returns the appropriate battery icon name based on charge level and charging state
-pub(crate) fn battery_icon_name(level: u8, is_charging: bool) -> &'static str {
-    if is_charging {
-        "battery-good-charging-symbolic"
-    } else {
-        match level {
-            80..=100 => "battery-full-symbolic",
-            50..=79 => "battery-good-symbolic",
-            20..=49 => "battery-low-symbolic",
-            _ => "battery-caution-symbolic",
-        }
-    }
-}
-
 /// Categorize a device based on its state
 pub(crate) fn categorize_device(device_state: &DeviceState) -> DeviceCategory {
     let device = &device_state.device;
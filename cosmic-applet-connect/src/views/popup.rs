@@ -595,6 +595,7 @@ impl CConnectApplet {
             mpris_section,
             camera_section,
             self.transfers_view(),
+            self.recent_files_view(),
             divider::horizontal::default(),
             scrollable(content).height(Length::Fill),
         ]
@@ -262,6 +262,60 @@ impl CConnectApplet {
             .into()
     }
 
+    /// Files recently received from devices, with click-to-open
+    pub fn recent_files_view(&self) -> Element<'_, Message> {
+        if self.recent_received_files.is_empty() {
+            return Element::from(cosmic::widget::Space::new(0, 0));
+        }
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let mut files_col = column![cosmic::widget::text::body("Recently Received")
+            .class(theme::Text::Color(theme_accent_color()))]
+        .spacing(space_xxs());
+
+        for file in self
+            .recent_received_files
+            .iter()
+            .take(MAX_POPUP_RECEIVED_FILES)
+        {
+            let time_str = Self::format_elapsed(std::time::Duration::from_secs(
+                now.saturating_sub(file.received_at),
+            ));
+
+            files_col = files_col.push(
+                button::custom(
+                    row![
+                        icon::from_name(Self::file_type_icon(&file.name)).size(ICON_S),
+                        column![
+                            cosmic::widget::text::body(&file.name),
+                            cosmic::widget::text::caption(format!(
+                                "from {} • {}",
+                                file.device_name, time_str
+                            )),
+                        ]
+                        .spacing(space_xxxs())
+                        .width(Length::Fill),
+                    ]
+                    .spacing(space_xxs())
+                    .align_y(cosmic::iced::Alignment::Center),
+                )
+                .padding(space_xxxs())
+                .width(Length::Fill)
+                .class(cosmic::theme::Button::MenuItem)
+                .on_press(Message::OpenReceivedFile(file.path.clone())),
+            );
+        }
+
+        container(files_col)
+            .padding(space_xs())
+            .width(Length::Fill)
+            .into()
+    }
+
     /// Formats elapsed time into human-readable format
     pub(crate) fn format_elapsed(elapsed: std::time::Duration) -> String {
         let secs = elapsed.as_secs();
@@ -0,0 +1,271 @@
+//! Low Battery Alerts
+//!
+//! Watches battery updates from paired devices and decides when to warn on
+//! the desktop that a device is running low.
+//!
+//! An alert is raised once when the level drops to the configured threshold
+//! (or the device reports a low-battery threshold event) while not charging.
+//! It is not raised again until the device charges or recovers above the
+//! threshold, and a cooldown keeps a level hovering around the threshold from
+//! raising a new alert every time it dips. Plugging the device in clears the
+//! alert and the cooldown.
+
+use crate::config::BatteryAlertConfig;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Change in a device's low-battery alert
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatteryAlert {
+    /// Battery dropped to the threshold; warn at this level
+    Low(u8),
+    /// Battery is charging or recovered; clear the warning
+    Cleared {
+        /// Notification shown for the alert, if any
+        notification_id: Option<u32>,
+    },
+}
+
+/// Alert state for a single device
+#[derive(Debug, Default)]
+struct DeviceAlertState {
+    /// Whether an alert is currently active
+    active: bool,
+    /// Level at which the active alert was raised
+    alert_level: u8,
+    /// When the last alert was raised
+    last_raised: Option<Instant>,
+    /// Notification shown for the active alert
+    notification_id: Option<u32>,
+}
+
+/// Low battery alert state for all devices
+#[derive(Debug, Default)]
+pub struct BatteryMonitor {
+    devices: HashMap<String, DeviceAlertState>,
+}
+
+impl BatteryMonitor {
+    /// Create a monitor with no alerts
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Process a battery update from a device
+    ///
+    /// `threshold_event` is set when the device itself reports that it
+    /// crossed its low-battery threshold.
+    pub fn update(
+        &mut self,
+        config: &BatteryAlertConfig,
+        device_id: &str,
+        level: u8,
+        charging: bool,
+        threshold_event: bool,
+        now: Instant,
+    ) -> Option<BatteryAlert> {
+        if !config.enabled {
+            return None;
+        }
+
+        let state = self.devices.entry(device_id.to_string()).or_default();
+
+        if charging {
+            // Plugging in is deliberate, so the next drop may alert right away
+            state.last_raised = None;
+        }
+
+        if state.active {
+            // A threshold event may alert above our own threshold, so only
+            // count it as recovered once the level rises past both
+            let recovered = charging || level > config.threshold.max(state.alert_level);
+            if !recovered {
+                return None;
+            }
+            state.active = false;
+            return Some(BatteryAlert::Cleared {
+                notification_id: state.notification_id.take(),
+            });
+        }
+
+        if charging || (level > config.threshold && !threshold_event) {
+            return None;
+        }
+
+        let cooldown = Duration::from_secs(config.cooldown_secs);
+        if let Some(last) = state.last_raised {
+            if now.saturating_duration_since(last) < cooldown {
+                return None;
+            }
+        }
+
+        state.active = true;
+        state.alert_level = level;
+        state.last_raised = Some(now);
+        Some(BatteryAlert::Low(level))
+    }
+
+    /// Remember the notification shown for a device's active alert
+    pub fn set_notification_id(&mut self, device_id: &str, notification_id: u32) {
+        if let Some(state) = self.devices.get_mut(device_id) {
+            state.notification_id = Some(notification_id);
+        }
+    }
+
+    /// Forget a device, e.g. when it is unpaired
+    ///
+    /// Returns the notification shown for its active alert, if any.
+    pub fn remove_device(&mut self, device_id: &str) -> Option<u32> {
+        self.devices
+            .remove(device_id)
+            .and_then(|state| state.notification_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> BatteryAlertConfig {
+        BatteryAlertConfig {
+            enabled: true,
+            threshold: 15,
+            cooldown_secs: 600,
+            persistent: false,
+        }
+    }
+
+    #[test]
+    fn test_crossing_threshold_alerts_once() {
+        let config = config();
+        let mut monitor = BatteryMonitor::new();
+        let now = Instant::now();
+
+        assert_eq!(
+            monitor.update(&config, "phone", 30, false, false, now),
+            None
+        );
+        assert_eq!(
+            monitor.update(&config, "phone", 15, false, false, now),
+            Some(BatteryAlert::Low(15))
+        );
+
+        // Still low: no repeated alerts
+        assert_eq!(
+            monitor.update(&config, "phone", 14, false, false, now),
+            None
+        );
+        assert_eq!(
+            monitor.update(
+                &config,
+                "phone",
+                10,
+                false,
+                true,
+                now + Duration::from_secs(3600)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_charging_clears_and_rearms() {
+        let config = config();
+        let mut monitor = BatteryMonitor::new();
+        let now = Instant::now();
+
+        monitor.update(&config, "phone", 12, false, false, now);
+        monitor.set_notification_id("phone", 42);
+
+        assert_eq!(
+            monitor.update(&config, "phone", 12, true, false, now),
+            Some(BatteryAlert::Cleared {
+                notification_id: Some(42)
+            })
+        );
+        assert_eq!(monitor.update(&config, "phone", 13, true, false, now), None);
+
+        // Unplugged while still low: alert again without waiting for cooldown
+        assert_eq!(
+            monitor.update(&config, "phone", 13, false, false, now),
+            Some(BatteryAlert::Low(13))
+        );
+    }
+
+    #[test]
+    fn test_hovering_respects_cooldown() {
+        let config = config();
+        let mut monitor = BatteryMonitor::new();
+        let start = Instant::now();
+
+        monitor.update(&config, "phone", 15, false, false, start);
+
+        // Recovery above the threshold clears the alert
+        assert_eq!(
+            monitor.update(&config, "phone", 16, false, false, start),
+            Some(BatteryAlert::Cleared {
+                notification_id: None
+            })
+        );
+
+        // Dipping again within the cooldown stays quiet
+        let later = start + Duration::from_secs(60);
+        assert_eq!(
+            monitor.update(&config, "phone", 15, false, false, later),
+            None
+        );
+
+        // After the cooldown, a new dip alerts again
+        let much_later = start + Duration::from_secs(601);
+        assert_eq!(
+            monitor.update(&config, "phone", 15, false, false, much_later),
+            Some(BatteryAlert::Low(15))
+        );
+    }
+
+    #[test]
+    fn test_threshold_event_and_disabled() {
+        let mut config = config();
+        let mut monitor = BatteryMonitor::new();
+        let now = Instant::now();
+
+        // The device's own low-battery event counts even above our threshold
+        assert_eq!(
+            monitor.update(&config, "phone", 20, false, true, now),
+            Some(BatteryAlert::Low(20))
+        );
+
+        // Later updates without the event do not count as recovery
+        assert_eq!(
+            monitor.update(&config, "phone", 19, false, false, now),
+            None
+        );
+        assert_eq!(
+            monitor.update(&config, "phone", 21, false, false, now),
+            Some(BatteryAlert::Cleared {
+                notification_id: None
+            })
+        );
+
+        config.enabled = false;
+        assert_eq!(monitor.update(&config, "tablet", 5, false, true, now), None);
+    }
+
+    #[test]
+    fn test_removed_device_starts_over() {
+        let config = config();
+        let mut monitor = BatteryMonitor::new();
+        let now = Instant::now();
+
+        monitor.update(&config, "phone", 10, false, false, now);
+        monitor.set_notification_id("phone", 7);
+        assert_eq!(monitor.remove_device("phone"), Some(7));
+        assert_eq!(monitor.remove_device("phone"), None);
+
+        // Paired again, it alerts without waiting for the cooldown
+        assert_eq!(
+            monitor.update(&config, "phone", 10, false, false, now),
+            Some(BatteryAlert::Low(10))
+        );
+    }
+}
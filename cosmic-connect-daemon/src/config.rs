@@ -3,7 +3,9 @@
 //! Configuration management for the CConnect daemon.
 
 use anyhow::{Context, Result};
-use cosmic_connect_protocol::TransportPreference;
+use cosmic_connect_protocol::fs_utils::create_private_dir;
+use cosmic_connect_protocol::plugins::findmyphone::{self, RingConfig};
+use cosmic_connect_protocol::{AdaptiveRateConfig, Identity, TransportPreference};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -29,6 +31,26 @@ pub struct Config {
     #[serde(default)]
     pub notification_listener: NotificationListenerConfig,
 
+    /// Do Not Disturb configuration
+    #[serde(default)]
+    pub do_not_disturb: DoNotDisturbConfig,
+
+    /// Low battery alert configuration
+    #[serde(default)]
+    pub battery_alert: BatteryAlertConfig,
+
+    /// Mirrored phone notification configuration
+    #[serde(default)]
+    pub mirrored_notifications: MirroredNotificationConfig,
+
+    /// Per-app filter for forwarded and mirrored notifications
+    #[serde(default)]
+    pub notification_app_filter: NotificationAppFilter,
+
+    /// Developer and debugging options
+    #[serde(default)]
+    pub debug: DebugConfig,
+
     /// Storage paths
     pub paths: PathConfig,
 }
@@ -69,6 +91,22 @@ pub struct NetworkConfig {
     /// Device timeout in seconds (how long before a device is considered offline)
     #[serde(default = "default_device_timeout")]
     pub device_timeout: u64,
+
+    /// Forget unpaired devices not seen for this many days (0 = never)
+    #[serde(default = "default_stale_device_days")]
+    pub stale_device_days: u64,
+
+    /// Ignore discovery broadcasts from loopback addresses
+    #[serde(default = "default_false")]
+    pub ignore_loopback_discovery: bool,
+
+    /// Network interfaces to discover devices on, e.g. `["wlan0"]` (all if unset)
+    #[serde(default)]
+    pub discovery_interfaces: Option<Vec<String>>,
+
+    /// Throttle outgoing file transfers when the link degrades (off if unset)
+    #[serde(default)]
+    pub adaptive_transfer_rate: Option<AdaptiveRateConfig>,
 }
 
 /// Transport configuration
@@ -185,6 +223,110 @@ pub struct NotificationListenerConfig {
     pub max_body_length: usize,
 }
 
+/// Do Not Disturb configuration
+///
+/// Controls whether mirrored phone notifications and telephony pop-ups are
+/// raised on this desktop.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoNotDisturbConfig {
+    /// Suppress pop-ups for mirrored notifications and calls
+    #[serde(default = "default_false")]
+    pub enabled: bool,
+
+    /// Also suppress pop-ups while the desktop's own Do Not Disturb is on
+    #[serde(default = "default_true")]
+    pub follow_desktop: bool,
+
+    /// Keep a history entry for suppressed notifications
+    #[serde(default = "default_true")]
+    pub log_suppressed: bool,
+}
+
+/// Low battery alert configuration
+///
+/// Controls the desktop warning raised when a paired device runs low.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatteryAlertConfig {
+    /// Warn when a device's battery runs low
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Battery level (percent) at or below which to warn
+    #[serde(default = "default_battery_alert_threshold")]
+    pub threshold: u8,
+
+    /// Minimum time between warnings for the same device (seconds)
+    #[serde(default = "default_battery_alert_cooldown")]
+    pub cooldown_secs: u64,
+
+    /// Keep the warning on screen until the device charges or recovers
+    #[serde(default = "default_false")]
+    pub persistent: bool,
+}
+
+/// How mirrored phone notifications are grouped on the desktop
+///
+/// Notifications in the same group are shown as one notification listing the
+/// recent ones, instead of stacking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationGrouping {
+    /// Every notification gets its own pop-up
+    None,
+    /// One notification per app
+    App,
+    /// One notification per conversation, or per app outside of messaging apps
+    #[default]
+    Conversation,
+}
+
+/// Mirrored phone notification configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MirroredNotificationConfig {
+    /// How notifications are grouped
+    #[serde(default)]
+    pub grouping: NotificationGrouping,
+}
+
+/// How the apps of a [`NotificationAppFilter`] are treated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationFilterMode {
+    /// Only notifications from the listed apps pass
+    Allowlist,
+    /// Notifications from the listed apps are blocked
+    #[default]
+    Denylist,
+}
+
+/// Per-app notification filter
+///
+/// Consulted before a desktop notification is forwarded to a device and
+/// before a phone notification is raised on the desktop. Apps are matched
+/// case-insensitively against the app name and the app identifier (the
+/// desktop entry on this side, the package name on the phone).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotificationAppFilter {
+    /// Whether `apps` is an allowlist or a denylist
+    #[serde(default)]
+    pub mode: NotificationFilterMode,
+
+    /// App names, desktop entries or package names
+    #[serde(default)]
+    pub apps: Vec<String>,
+}
+
+/// Developer and debugging options
+///
+/// Everything here is off by default and meant for plugin development and
+/// testing, not regular use.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DebugConfig {
+    /// Allow sending arbitrary packets to devices over DBus
+    #[serde(default = "default_false")]
+    pub allow_custom_packets: bool,
+}
+
 /// Plugin configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginConfig {
@@ -200,6 +342,11 @@ pub struct PluginConfig {
     #[serde(default = "default_true")]
     pub enable_notification: bool,
 
+    /// Characters of a mirrored notification body shown before it is cut
+    /// off with an ellipsis (0 for no limit)
+    #[serde(default = "default_notification_max_chars")]
+    pub notification_max_chars: usize,
+
     /// Enable share plugin
     #[serde(default = "default_true")]
     pub enable_share: bool,
@@ -208,6 +355,10 @@ pub struct PluginConfig {
     #[serde(default = "default_true")]
     pub enable_clipboard: bool,
 
+    /// Send desktop clipboard changes automatically (otherwise only on `PushClipboard`)
+    #[serde(default = "default_true")]
+    pub clipboard_auto_sync: bool,
+
     /// Enable MPRIS plugin
     #[serde(default = "default_true")]
     pub enable_mpris: bool,
@@ -260,6 +411,10 @@ pub struct PluginConfig {
     #[serde(default = "default_false")]
     pub enable_power: bool,
 
+    /// Enable SystemState plugin (remote DND and brightness, opt-in per device)
+    #[serde(default = "default_false")]
+    pub enable_system_state: bool,
+
     /// Enable ClipboardHistory plugin (persistent clipboard history)
     #[serde(default = "default_true")]
     pub enable_clipboardhistory: bool,
@@ -295,6 +450,29 @@ pub struct PluginConfig {
     /// Enable Camera plugin (remote camera/webcam access)
     #[serde(default = "default_false")]
     pub enable_camera: bool,
+
+    /// Enable Photo plugin (ask the phone to take a photo)
+    #[serde(default = "default_true")]
+    pub enable_photo: bool,
+
+    /// Sound played when a paired device asks this desktop to ring
+    #[serde(default = "default_find_my_device_sound")]
+    pub find_my_device_sound: PathBuf,
+
+    /// Volume (0-150) forced on the default output while ringing
+    ///
+    /// The previous volume is restored when the ring stops. Unset keeps the
+    /// current system volume.
+    #[serde(default)]
+    pub find_my_device_volume: Option<u8>,
+
+    /// Show an on-screen display when a paired phone's volume changes
+    #[serde(default = "default_false")]
+    pub show_volume_osd: bool,
+
+    /// Seconds a plugin may take to handle a packet before it is abandoned
+    #[serde(default = "default_handler_timeout")]
+    pub handler_timeout: u64,
 }
 
 /// Storage paths configuration
@@ -330,6 +508,10 @@ fn default_device_timeout() -> u64 {
     30
 }
 
+fn default_stale_device_days() -> u64 {
+    7
+}
+
 fn default_tcp_timeout() -> u64 {
     10
 }
@@ -346,10 +528,30 @@ fn default_false() -> bool {
     false
 }
 
+fn default_battery_alert_threshold() -> u8 {
+    cosmic_connect_protocol::plugins::battery::LOW_BATTERY_THRESHOLD
+}
+
+fn default_battery_alert_cooldown() -> u64 {
+    1800 // 30 minutes
+}
+
+fn default_handler_timeout() -> u64 {
+    cosmic_connect_protocol::plugins::DEFAULT_HANDLER_TIMEOUT.as_secs()
+}
+
 fn default_max_body_length() -> usize {
     2000
 }
 
+fn default_notification_max_chars() -> usize {
+    cosmic_connect_protocol::plugins::notification::DEFAULT_MAX_BODY_CHARS
+}
+
+fn default_find_my_device_sound() -> PathBuf {
+    PathBuf::from(findmyphone::DEFAULT_RING_SOUND)
+}
+
 impl Default for NetworkConfig {
     fn default() -> Self {
         Self {
@@ -358,6 +560,10 @@ impl Default for NetworkConfig {
             transfer_port_end: default_transfer_port_end(),
             discovery_interval: default_discovery_interval(),
             device_timeout: default_device_timeout(),
+            stale_device_days: default_stale_device_days(),
+            ignore_loopback_discovery: false,
+            discovery_interfaces: None,
+            adaptive_transfer_rate: None,
         }
     }
 }
@@ -375,6 +581,27 @@ impl Default for NotificationListenerConfig {
     }
 }
 
+impl Default for DoNotDisturbConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            follow_desktop: true,
+            log_suppressed: true,
+        }
+    }
+}
+
+impl Default for BatteryAlertConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold: default_battery_alert_threshold(),
+            cooldown_secs: default_battery_alert_cooldown(),
+            persistent: false,
+        }
+    }
+}
+
 impl Default for TransportConfig {
     fn default() -> Self {
         Self {
@@ -396,6 +623,14 @@ impl Default for TransportConfig {
     }
 }
 
+impl NetworkConfig {
+    /// How long unpaired devices are remembered, `None` if forever
+    pub fn stale_device_age(&self) -> Option<Duration> {
+        (self.stale_device_days > 0)
+            .then(|| Duration::from_secs(self.stale_device_days * 24 * 60 * 60))
+    }
+}
+
 impl TransportConfig {
     /// Get TCP timeout as Duration
     pub fn tcp_timeout(&self) -> Duration {
@@ -422,6 +657,30 @@ impl TransportConfig {
     }
 }
 
+impl NotificationAppFilter {
+    /// Check whether a notification from the given app passes the filter
+    ///
+    /// Empty identifiers count as missing. A notification without any
+    /// identifier cannot be listed, so it is blocked by an allowlist and
+    /// passes a denylist.
+    pub fn allows(&self, app_name: Option<&str>, app_id: Option<&str>) -> bool {
+        let listed = [app_name, app_id]
+            .into_iter()
+            .flatten()
+            .filter(|identifier| !identifier.is_empty())
+            .any(|identifier| {
+                self.apps
+                    .iter()
+                    .any(|app| app.eq_ignore_ascii_case(identifier))
+            });
+
+        match self.mode {
+            NotificationFilterMode::Allowlist => listed,
+            NotificationFilterMode::Denylist => !listed,
+        }
+    }
+}
+
 impl Default for PluginConfig {
     fn default() -> Self {
         Self {
@@ -429,8 +688,10 @@ impl Default for PluginConfig {
             enable_ping: true,
             enable_battery: true,
             enable_notification: true,
+            notification_max_chars: default_notification_max_chars(),
             enable_share: true,
             enable_clipboard: true,
+            clipboard_auto_sync: true,
             enable_mpris: true,
 
             // Advanced plugins - disabled by default to reduce discovery packet size
@@ -447,6 +708,7 @@ impl Default for PluginConfig {
             enable_screenshot: true,  // Desktop-to-desktop screenshot capture
             enable_remotedesktop: false, // Security: disabled by default, requires explicit opt-in
             enable_power: false,      // Security: power control disabled by default
+            enable_system_state: false, // Remote DND/brightness is opt-in
             enable_clipboardhistory: true, // Clipboard history with sync
             enable_macro: false,      // Security: automation disabled by default
             enable_chat: true,        // Instant messaging enabled by default
@@ -456,6 +718,41 @@ impl Default for PluginConfig {
             enable_mousekeyboardshare: false, // Mouse/keyboard share disabled by default (requires input capture)
             enable_networkshare: true,        // SFTP mounting enabled by default
             enable_camera: false,             // Camera disabled by default (opt-in feature)
+            enable_photo: true,               // Photos are only taken on request
+
+            find_my_device_sound: default_find_my_device_sound(),
+            find_my_device_volume: None,
+            show_volume_osd: false, // Remote volume OSD is opt-in
+            handler_timeout: default_handler_timeout(),
+        }
+    }
+}
+
+impl PluginConfig {
+    /// Ring configuration for the Find My Phone plugin
+    pub fn ring_config(&self) -> RingConfig {
+        RingConfig {
+            sound: self.find_my_device_sound.clone(),
+            volume_override: self.find_my_device_volume.map(|v| v.min(150)),
+        }
+    }
+
+    /// Validate plugin settings, replacing unusable values with defaults
+    fn validate(&mut self) {
+        if let Err(reason) = findmyphone::validate_ring_sound(&self.find_my_device_sound) {
+            tracing::warn!(
+                "Invalid find_my_device_sound ({}), using default {}",
+                reason,
+                findmyphone::DEFAULT_RING_SOUND
+            );
+            self.find_my_device_sound = default_find_my_device_sound();
+        }
+        if self.handler_timeout == 0 {
+            tracing::warn!(
+                "Invalid handler_timeout of 0 seconds, using default {}",
+                default_handler_timeout()
+            );
+            self.handler_timeout = default_handler_timeout();
         }
     }
 }
@@ -490,6 +787,11 @@ impl Default for Config {
             transport: TransportConfig::default(),
             plugins: PluginConfig::default(),
             notification_listener: NotificationListenerConfig::default(),
+            do_not_disturb: DoNotDisturbConfig::default(),
+            battery_alert: BatteryAlertConfig::default(),
+            mirrored_notifications: MirroredNotificationConfig::default(),
+            notification_app_filter: NotificationAppFilter::default(),
+            debug: DebugConfig::default(),
             paths: PathConfig {
                 config_dir,
                 data_dir,
@@ -512,8 +814,9 @@ impl Config {
         if config_path.exists() {
             let contents =
                 fs::read_to_string(&config_path).context("Failed to read config file")?;
-            let config: Config =
+            let mut config: Config =
                 toml::from_str(&contents).context("Failed to parse config file")?;
+            config.plugins.validate();
             Ok(config)
         } else {
             // Create default config
@@ -540,7 +843,8 @@ impl Config {
     pub fn ensure_directories(&self) -> Result<()> {
         fs::create_dir_all(&self.paths.config_dir).context("Failed to create config directory")?;
         fs::create_dir_all(&self.paths.data_dir).context("Failed to create data directory")?;
-        fs::create_dir_all(&self.paths.cert_dir)
+        // The certificate directory holds the private key; keep it owner-only
+        create_private_dir(&self.paths.cert_dir)
             .context("Failed to create certificate directory")?;
         Ok(())
     }
@@ -560,50 +864,29 @@ impl Config {
         self.paths.data_dir.join("devices.json")
     }
 
-    /// Get the device ID file path (for persisting auto-generated device IDs)
-    pub fn device_id_path(&self) -> PathBuf {
+    /// Get the device ID file path used by older releases
+    ///
+    /// The device ID now lives next to the device certificate; this file is
+    /// only read to migrate existing installations.
+    pub fn legacy_device_id_path(&self) -> PathBuf {
         self.paths.data_dir.join("device_id")
     }
 
-    /// Load device ID from config or saved file
+    /// Load the device identity
     ///
     /// Priority:
     /// 1. Config file device_id setting
-    /// 2. Saved device_id file
-    /// 3. None (caller should generate new)
-    pub fn load_device_id(&self) -> Option<String> {
-        // First check config
+    /// 2. Device ID stored next to the certificate
+    /// 3. Device ID saved by older releases (migrated and moved)
+    /// 4. Newly generated device ID
+    pub fn load_identity(&self) -> Result<Identity> {
         if let Some(ref id) = self.device.device_id {
-            return Some(id.clone());
-        }
-
-        // Then check saved file
-        let device_id_path = self.device_id_path();
-        if device_id_path.exists() {
-            if let Ok(id) = fs::read_to_string(&device_id_path) {
-                let id = id.trim().to_string();
-                if !id.is_empty() {
-                    tracing::info!("Loaded device ID from {}", device_id_path.display());
-                    return Some(id);
-                }
-            }
-        }
-
-        None
-    }
-
-    /// Save a generated device ID to file
-    pub fn save_device_id(&self, device_id: &str) -> Result<()> {
-        let device_id_path = self.device_id_path();
-
-        // Ensure parent directory exists
-        if let Some(parent) = device_id_path.parent() {
-            fs::create_dir_all(parent).context("Failed to create data directory")?;
+            return Identity::from_device_id(id).context("Invalid device_id in config");
         }
 
-        fs::write(&device_id_path, device_id).context("Failed to save device ID")?;
-        tracing::info!("Saved device ID to {}", device_id_path.display());
-        Ok(())
+        let legacy_id = fs::read_to_string(self.legacy_device_id_path()).ok();
+        Identity::load_or_generate(&self.paths.cert_dir, legacy_id.as_deref().map(str::trim))
+            .context("Failed to load device ID")
     }
 }
 
@@ -628,6 +911,18 @@ mod tests {
         assert_eq!(parsed.network.discovery_port, config.network.discovery_port);
     }
 
+    #[test]
+    fn test_stale_device_age() {
+        let mut network = NetworkConfig::default();
+        assert_eq!(
+            network.stale_device_age(),
+            Some(Duration::from_secs(7 * 24 * 60 * 60))
+        );
+
+        network.stale_device_days = 0;
+        assert_eq!(network.stale_device_age(), None);
+    }
+
     #[test]
     fn test_transport_config_defaults() {
         let transport = TransportConfig::default();
@@ -706,4 +1001,155 @@ mod tests {
         assert!(!config.notification_listener.enabled);
         assert_eq!(config.notification_listener.max_body_length, 2000);
     }
+
+    #[test]
+    fn test_find_my_device_sound_defaults() {
+        let config = PluginConfig::default();
+        assert_eq!(
+            config.find_my_device_sound,
+            PathBuf::from(findmyphone::DEFAULT_RING_SOUND)
+        );
+        assert!(config.find_my_device_volume.is_none());
+        assert_eq!(config.ring_config(), RingConfig::default());
+    }
+
+    #[test]
+    fn test_volume_osd_disabled_by_default() {
+        assert!(!PluginConfig::default().show_volume_osd);
+
+        let config: PluginConfig = toml::from_str("show_volume_osd = true").unwrap();
+        assert!(config.show_volume_osd);
+    }
+
+    #[test]
+    fn test_find_my_device_sound_validation() {
+        let dir = std::env::temp_dir().join("cconnect-test-ring-sound");
+        fs::create_dir_all(&dir).unwrap();
+        let sound = dir.join("ring.wav");
+        fs::write(&sound, b"RIFF").unwrap();
+
+        let mut config = PluginConfig {
+            find_my_device_sound: sound.clone(),
+            find_my_device_volume: Some(200),
+            ..Default::default()
+        };
+        config.validate();
+        assert_eq!(config.find_my_device_sound, sound);
+        assert_eq!(config.ring_config().volume_override, Some(150));
+
+        config.find_my_device_sound = dir.join("missing.wav");
+        config.validate();
+        assert_eq!(config.find_my_device_sound, default_find_my_device_sound());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_do_not_disturb_defaults() {
+        let config = Config::default();
+        assert!(!config.do_not_disturb.enabled);
+        assert!(config.do_not_disturb.follow_desktop);
+        assert!(config.do_not_disturb.log_suppressed);
+
+        // Older config files without the section still parse
+        let parsed: DoNotDisturbConfig = toml::from_str("").unwrap();
+        assert!(!parsed.enabled);
+        assert!(parsed.log_suppressed);
+    }
+
+    #[test]
+    fn test_battery_alert_defaults() {
+        let config = Config::default();
+        assert!(config.battery_alert.enabled);
+        assert_eq!(config.battery_alert.threshold, 15);
+        assert!(!config.battery_alert.persistent);
+
+        let parsed: BatteryAlertConfig = toml::from_str("threshold = 20").unwrap();
+        assert_eq!(parsed.threshold, 20);
+        assert_eq!(parsed.cooldown_secs, 1800);
+    }
+
+    #[test]
+    fn test_mirrored_notification_grouping() {
+        let config = Config::default();
+        assert_eq!(
+            config.mirrored_notifications.grouping,
+            NotificationGrouping::Conversation
+        );
+
+        let parsed: MirroredNotificationConfig = toml::from_str("grouping = \"app\"").unwrap();
+        assert_eq!(parsed.grouping, NotificationGrouping::App);
+    }
+
+    #[test]
+    fn test_notification_app_filter_allowlist() {
+        let filter: NotificationAppFilter = toml::from_str(
+            r#"
+            mode = "allowlist"
+            apps = ["Signal", "org.mozilla.firefox"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(filter.mode, NotificationFilterMode::Allowlist);
+
+        assert!(filter.allows(Some("signal"), None));
+        assert!(filter.allows(Some("Firefox"), Some("org.mozilla.firefox")));
+        assert!(!filter.allows(Some("Slack"), Some("com.slack")));
+        // Unknown apps cannot be on the list
+        assert!(!filter.allows(None, None));
+        assert!(!filter.allows(Some(""), None));
+    }
+
+    #[test]
+    fn test_notification_app_filter_denylist() {
+        let filter = NotificationAppFilter {
+            mode: NotificationFilterMode::Denylist,
+            apps: vec!["Slack".to_string(), "com.spotify.music".to_string()],
+        };
+
+        assert!(!filter.allows(Some("slack"), None));
+        assert!(!filter.allows(Some("Spotify"), Some("com.spotify.music")));
+        assert!(filter.allows(Some("Signal"), None));
+        // Unknown apps cannot be on the list
+        assert!(filter.allows(None, None));
+        assert!(filter.allows(Some(""), Some("")));
+
+        // The default filter lets everything through
+        assert!(Config::default()
+            .notification_app_filter
+            .allows(Some("Slack"), None));
+    }
+
+    #[test]
+    fn test_load_identity_migrates_legacy_device_id() {
+        let dir = std::env::temp_dir().join("cconnect-test-identity");
+        fs::remove_dir_all(&dir).ok();
+
+        let mut config = Config::default();
+        config.paths.data_dir = dir.join("data");
+        config.paths.cert_dir = dir.join("certs");
+        fs::create_dir_all(&config.paths.data_dir).unwrap();
+        fs::write(
+            config.legacy_device_id_path(),
+            "01234567-89ab-cdef-0123-456789abcdef\n",
+        )
+        .unwrap();
+
+        let identity = config.load_identity().unwrap();
+        assert_eq!(identity.device_id(), "01234567_89ab_cdef_0123_456789abcdef");
+        assert!(Identity::path(&config.paths.cert_dir).exists());
+
+        // Stable across runs, even once the legacy file is gone
+        fs::remove_file(config.legacy_device_id_path()).unwrap();
+        assert_eq!(config.load_identity().unwrap(), identity);
+
+        // An explicit config setting takes priority
+        config.device.device_id = Some("0123456789abcdef0123456789abcdef".to_string());
+        assert_eq!(
+            config.load_identity().unwrap().device_id(),
+            "0123456789abcdef0123456789abcdef"
+        );
+
+        fs::remove_dir_all(&dir).ok();
+    }
 }
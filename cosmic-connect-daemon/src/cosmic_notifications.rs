@@ -3,12 +3,31 @@
 //! Integrates CConnect events with COSMIC Desktop's notification system
 //! using the freedesktop.org DBus notification specification.
 
+use crate::config::{Config, NotificationGrouping};
+use crate::do_not_disturb;
 use anyhow::{Context, Result};
-use std::collections::HashMap;
+use async_trait::async_trait;
+use cosmic_connect_protocol::plugins::notification::{truncate_body, DEFAULT_MAX_BODY_CHARS};
+use cosmic_connect_protocol::plugins::notifier::{
+    notification_service_available, ActionTarget, NotificationAction, NotificationHandle,
+    NotificationSpec, NotificationUrgency, Notifier,
+};
+use cosmic_connect_protocol::{DeviceType, ProtocolError};
+use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 use tracing::debug;
 use zbus::Connection;
 
+/// Desktop entry mirrored notifications are attributed to
+const DESKTOP_ENTRY: &str = "org.cosmicde.CosmicConnect";
+
+/// Hint carrying the group of a mirrored notification
+///
+/// Notification servers that stack by tag use it; replacement itself goes
+/// through `replaces_id`, which every server supports.
+const GROUP_TAG_HINT: &str = "x-dunst-stack-tag";
+
 /// Notification metadata stored for action callbacks
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -28,6 +47,12 @@ pub struct CosmicNotifier {
     connection: Connection,
     /// Metadata for active notifications (for link actions)
     metadata: Arc<RwLock<HashMap<u32, NotificationMetadata>>>,
+    /// How mirrored notifications are grouped
+    grouping: NotificationGrouping,
+    /// Shown notification of each group
+    groups: NotificationGroups,
+    /// Characters of a mirrored body shown before it is cut off (0 for no limit)
+    max_body_chars: usize,
 }
 
 /// Notification urgency level
@@ -47,6 +72,7 @@ pub enum Urgency {
 #[derive(Debug, Clone)]
 pub struct NotificationBuilder {
     app_name: String,
+    replaces_id: u32,
     summary: String,
     body: String,
     icon: String,
@@ -61,6 +87,7 @@ impl NotificationBuilder {
     pub fn new(summary: impl Into<String>) -> Self {
         Self {
             app_name: "CConnect".to_string(),
+            replaces_id: 0,
             summary: summary.into(),
             body: String::new(),
             icon: "phone-symbolic".to_string(),
@@ -182,12 +209,38 @@ impl NotificationBuilder {
     }
 
     /// Set a custom hint
-    #[allow(dead_code)]
     pub fn hint(mut self, key: impl Into<String>, value: zbus::zvariant::Value<'static>) -> Self {
         self.hints.insert(key.into(), value);
         self
     }
 
+    /// Replace a shown notification instead of adding a new one
+    pub fn replaces(mut self, notification_id: u32) -> Self {
+        self.replaces_id = notification_id;
+        self
+    }
+
+    /// Attribute the notification to an app on the phone
+    ///
+    /// The app name goes in the `x-kde-appName` hint; the notification itself
+    /// keeps the CConnect app name so the notification listener does not
+    /// forward it back to the phone.
+    pub fn mirrored_app(mut self, app_name: &str) -> Self {
+        use zbus::zvariant::Value;
+
+        if !app_name.is_empty() {
+            self.hints.insert(
+                "x-kde-appName".to_string(),
+                Value::Str(app_name.to_string().into()),
+            );
+        }
+        self.hints.insert(
+            "desktop-entry".to_string(),
+            Value::Str(DESKTOP_ENTRY.into()),
+        );
+        self
+    }
+
     /// Build and return the notification parameters
     fn build(mut self) -> NotificationParams {
         // Add urgency hint
@@ -196,11 +249,10 @@ impl NotificationBuilder {
             zbus::zvariant::Value::U8(self.urgency as u8),
         );
 
-        // Add category hint for CConnect notifications
-        self.hints.insert(
-            "category".to_string(),
-            zbus::zvariant::Value::Str("kde-connect".into()),
-        );
+        // Add category hint for CConnect notifications unless one was set
+        self.hints
+            .entry("category".to_string())
+            .or_insert_with(|| zbus::zvariant::Value::Str("kde-connect".into()));
 
         // Flatten actions into a single Vec<String>
         let actions_flat: Vec<String> = self
@@ -211,7 +263,7 @@ impl NotificationBuilder {
 
         NotificationParams {
             app_name: self.app_name,
-            replaces_id: 0,
+            replaces_id: self.replaces_id,
             icon: self.icon,
             summary: self.summary,
             body: self.body,
@@ -249,9 +301,29 @@ impl CosmicNotifier {
         Ok(Self {
             connection,
             metadata: Arc::new(RwLock::new(HashMap::new())),
+            grouping: NotificationGrouping::default(),
+            groups: NotificationGroups::default(),
+            max_body_chars: DEFAULT_MAX_BODY_CHARS,
         })
     }
 
+    /// Set how mirrored notifications are grouped
+    pub fn with_grouping(mut self, grouping: NotificationGrouping) -> Self {
+        self.grouping = grouping;
+        self
+    }
+
+    /// Set how many characters of a mirrored body are shown (0 for no limit)
+    pub fn with_max_body_chars(mut self, max_body_chars: usize) -> Self {
+        self.max_body_chars = max_body_chars;
+        self
+    }
+
+    /// Whether a notification server is running on the session bus
+    pub async fn is_available(&self) -> bool {
+        notification_service_available(&self.connection).await
+    }
+
     /// Send a notification to COSMIC Desktop
     ///
     /// # Example
@@ -327,21 +399,33 @@ impl CosmicNotifier {
         Ok(notif_id)
     }
 
-    /// Send a ping notification from a device
-    pub async fn notify_ping(&self, device_name: &str, message: Option<&str>) -> Result<u32> {
-        let body = if let Some(msg) = message {
-            format!("\"{}\"", msg)
-        } else {
-            format!("Ping from {}", device_name)
+    /// Send a mirrored notification, replacing the one shown for its group
+    ///
+    /// `entry` is a one-line description of the notification. When the group
+    /// has recent notifications, they are listed along with it in the body.
+    async fn send_grouped(
+        &self,
+        builder: NotificationBuilder,
+        group: Option<String>,
+        app_name: &str,
+        entry: String,
+        notification_id: Option<String>,
+    ) -> Result<u32> {
+        let Some(tag) = group else {
+            return self.send_with_metadata(builder, notification_id).await;
         };
 
-        self.send(
-            NotificationBuilder::new(format!("Ping from {}", device_name))
-                .body(body)
-                .icon("phone-symbolic")
-                .timeout(5000),
-        )
-        .await
+        let update = self.groups.add(&tag, entry, Instant::now());
+        let mut builder = builder.replaces(update.replaces_id).hint(
+            GROUP_TAG_HINT,
+            zbus::zvariant::Value::Str(tag.clone().into()),
+        );
+        if let Some(body) = update.body(app_name) {
+            builder = builder.body(body);
+        }
+        let id = self.send_with_metadata(builder, notification_id).await?;
+        self.groups.record(tag, id);
+        Ok(id)
     }
 
     /// Send a notification forwarded from a device
@@ -359,7 +443,8 @@ impl CosmicNotifier {
 
         let mut builder = NotificationBuilder::new(summary)
             .icon("phone-symbolic")
-            .timeout(10000);
+            .timeout(10000)
+            .mirrored_app(app_name);
 
         // Use rich body if available, otherwise plain text
         if let Some(html) = rich_body {
@@ -371,15 +456,19 @@ impl CosmicNotifier {
             };
             builder = builder.body(body);
         } else {
+            let text = truncate_body(text, self.max_body_chars);
             let body = if !app_name.is_empty() {
                 format!("{}\n{}", app_name, text)
             } else {
-                text.to_string()
+                text.into_owned()
             };
             builder = builder.body(body);
         }
 
-        self.send(builder).await
+        let entry = group_entry(title, text, self.max_body_chars);
+        let group = group_tag(self.grouping, device_name, app_name, None);
+        self.send_grouped(builder, group, app_name, entry, None)
+            .await
     }
 
     /// Send a rich notification from a device
@@ -398,15 +487,18 @@ impl CosmicNotifier {
         links: Vec<String>,
     ) -> Result<u32> {
         let summary = format!("{} ({})", title, device_name);
+        let entry = group_entry(title, text, self.max_body_chars);
+        let text = truncate_body(text, self.max_body_chars);
         let body_text = if !app_name.is_empty() {
             format!("{}\n{}", app_name, text)
         } else {
-            text.to_string()
+            text.into_owned()
         };
 
         let mut builder = NotificationBuilder::new(summary)
             .icon("phone-symbolic")
-            .timeout(10000);
+            .timeout(10000)
+            .mirrored_app(app_name);
 
         // Use rich body if available, otherwise plain text
         if let Some(html) = rich_body {
@@ -426,13 +518,23 @@ impl CosmicNotifier {
             builder = builder.action(action_id, format!("Open Link {}", idx + 1));
         }
 
-        self.send_with_metadata(builder, Some(notification_id.to_string()))
-            .await
+        let group = group_tag(self.grouping, device_name, app_name, None);
+        self.send_grouped(
+            builder,
+            group,
+            app_name,
+            entry,
+            Some(notification_id.to_string()),
+        )
+        .await
     }
 
     /// Send a messaging notification with potentially actionable web URL
     ///
     /// If `rich_body` is provided, it will be sanitized and used instead of plain text.
+    /// Messages of the same conversation are shown in one notification listing
+    /// the recent ones, depending on the configured grouping.
+    #[allow(clippy::too_many_arguments)]
     pub async fn notify_messaging(
         &self,
         device_name: &str,
@@ -441,6 +543,7 @@ impl CosmicNotifier {
         message: &str,
         rich_body: Option<&str>,
         web_url: Option<&str>,
+        conversation_id: Option<&str>,
     ) -> Result<u32> {
         let summary = format!("{} ({})", sender, device_name);
 
@@ -449,13 +552,18 @@ impl CosmicNotifier {
             let sanitized = NotificationBuilder::sanitize_html(html);
             format!("{}\n{}", app_name, sanitized)
         } else {
-            format!("{}\n{}", app_name, message)
+            format!(
+                "{}\n{}",
+                app_name,
+                truncate_body(message, self.max_body_chars)
+            )
         };
 
         let mut builder = NotificationBuilder::new(summary)
             .body(body)
             .icon("mail-message-new-symbolic")
-            .timeout(15000); // Messaging notifications stay longer
+            .timeout(15000) // Messaging notifications stay longer
+            .mirrored_app(app_name);
 
         if let Some(url) = web_url {
             builder = builder.action(format!("open_web:{}", url), "Open in Web");
@@ -463,7 +571,10 @@ impl CosmicNotifier {
 
         builder = builder.action("reply", "Reply");
 
-        self.send(builder).await
+        let entry = group_entry(sender, message, self.max_body_chars);
+        let group = group_tag(self.grouping, device_name, app_name, conversation_id);
+        self.send_grouped(builder, group, app_name, entry, None)
+            .await
     }
 
     /// Send a pairing request notification
@@ -480,6 +591,29 @@ impl CosmicNotifier {
         .await
     }
 
+    /// Send a notification that a paired device has to be re-verified
+    ///
+    /// Shown when the device presents a new certificate, e.g. after a reset.
+    pub async fn notify_repair_required(
+        &self,
+        device_name: &str,
+        fingerprint: &str,
+    ) -> Result<u32> {
+        self.send(
+            NotificationBuilder::new("Re-pairing Required")
+                .body(format!(
+                    "{} presented a new certificate. Accept only if this fingerprint matches the one shown on the device:\n{}",
+                    device_name, fingerprint
+                ))
+                .icon("security-medium-symbolic")
+                .urgency(Urgency::Normal)
+                .timeout(0) // Don't auto-dismiss pairing requests
+                .action("accept", "Accept")
+                .action("reject", "Reject"),
+        )
+        .await
+    }
+
     /// Send a file received notification
     pub async fn notify_file_received(
         &self,
@@ -499,13 +633,75 @@ impl CosmicNotifier {
     }
 
     /// Send a battery low warning from a device
-    pub async fn notify_battery_low(&self, device_name: &str, level: u8) -> Result<u32> {
+    ///
+    /// A persistent warning stays on screen until it is closed, e.g. when the
+    /// device starts charging.
+    pub async fn notify_battery_low(
+        &self,
+        device_name: &str,
+        device_type: DeviceType,
+        level: u8,
+        persistent: bool,
+    ) -> Result<u32> {
+        let (urgency, timeout) = if persistent {
+            (Urgency::Critical, 0)
+        } else {
+            (Urgency::Normal, 10000)
+        };
+
         self.send(
             NotificationBuilder::new(format!("{} Battery Low", device_name))
-                .body(format!("Battery level: {}%", level))
+                .body(battery_low_body(device_type, level))
                 .icon("battery-low-symbolic")
-                .urgency(Urgency::Normal)
-                .timeout(10000),
+                .urgency(urgency)
+                .timeout(timeout),
+        )
+        .await
+    }
+
+    /// Show an on-screen display of a device's volume
+    ///
+    /// Uses the synchronous hint so repeated changes replace the previous OSD
+    /// instead of stacking up.
+    pub async fn notify_volume_osd(
+        &self,
+        device_name: &str,
+        volume: i32,
+        max_volume: i32,
+        muted: bool,
+        timeout_ms: i32,
+    ) -> Result<u32> {
+        use zbus::zvariant::Value;
+
+        let percent = if max_volume > 0 {
+            (volume * 100 / max_volume).clamp(0, 100)
+        } else {
+            0
+        };
+        let icon = match (muted, percent) {
+            (true, _) | (false, 0) => "audio-volume-muted-symbolic",
+            (false, 1..=33) => "audio-volume-low-symbolic",
+            (false, 34..=66) => "audio-volume-medium-symbolic",
+            _ => "audio-volume-high-symbolic",
+        };
+        let body = if muted {
+            "Muted".to_string()
+        } else {
+            format!("{}%", percent)
+        };
+
+        self.send(
+            NotificationBuilder::new(format!("{} Volume", device_name))
+                .body(body)
+                .icon(icon)
+                .urgency(Urgency::Low)
+                .timeout(timeout_ms)
+                .hint("value", Value::I32(percent))
+                .hint(
+                    "x-canonical-private-synchronous",
+                    Value::Str("cconnect-volume".into()),
+                )
+                .hint("transient", Value::Bool(true)),
         )
         .await
     }
@@ -741,7 +937,6 @@ impl CosmicNotifier {
     }
 
     /// Close a notification by ID
-    #[allow(dead_code)]
     pub async fn close(&self, notification_id: u32) -> Result<()> {
         let proxy = zbus::Proxy::new(
             &self.connection,
@@ -852,6 +1047,324 @@ impl CosmicNotifier {
     }
 }
 
+/// Body of a battery low warning, e.g. "Phone battery at 15%"
+fn battery_low_body(device_type: DeviceType, level: u8) -> String {
+    let kind = match device_type {
+        DeviceType::Phone => "Phone",
+        DeviceType::Tablet => "Tablet",
+        DeviceType::Laptop => "Laptop",
+        DeviceType::Desktop => "Desktop",
+        DeviceType::Tv => "TV",
+        DeviceType::Unknown => return format!("Battery at {}%", level),
+    };
+    format!("{} battery at {}%", kind, level)
+}
+
+/// Most notification groups whose shown notification is remembered at once
+const MAX_TRACKED_GROUPS: usize = 256;
+
+/// Tag grouping a mirrored notification, or `None` to show it on its own
+///
+/// Tags are scoped to the device, so two phones never replace each other's
+/// notifications. Without a conversation ID, conversation grouping falls back
+/// to grouping by app.
+pub fn group_tag(
+    grouping: NotificationGrouping,
+    device_name: &str,
+    app_name: &str,
+    conversation_id: Option<&str>,
+) -> Option<String> {
+    if app_name.is_empty() {
+        return None;
+    }
+
+    match (grouping, conversation_id.filter(|id| !id.is_empty())) {
+        (NotificationGrouping::None, _) => None,
+        (NotificationGrouping::Conversation, Some(conversation_id)) => Some(format!(
+            "cconnect/{}/{}/{}",
+            device_name, app_name, conversation_id
+        )),
+        _ => Some(format!("cconnect/{}/{}", device_name, app_name)),
+    }
+}
+
+/// Most recent notifications listed in a group's notification
+const MAX_GROUP_LINES: usize = 5;
+
+/// Most notifications of a group counted towards its summary
+const MAX_GROUP_ENTRIES: usize = 50;
+
+/// How long a notification is listed in its group's notification
+///
+/// Closing a notification is not reported, so after this a new one in the
+/// group starts over instead of listing notifications long dismissed.
+const GROUP_WINDOW: Duration = Duration::from_secs(300);
+
+/// One-line description of a notification in its group's notification
+fn group_entry(title: &str, text: &str, max_chars: usize) -> String {
+    let text = truncate_body(text, max_chars).replace('\n', " ");
+    if title.is_empty() {
+        text
+    } else {
+        format!("{}: {}", title, text)
+    }
+}
+
+/// Notification shown for a group and its recent notifications
+#[derive(Debug, Default)]
+struct ShownGroup {
+    id: u32,
+    entries: VecDeque<(Instant, String)>,
+}
+
+/// Result of adding a notification to its group
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupUpdate {
+    /// Notification to replace, or 0 to add a new one
+    pub replaces_id: u32,
+    /// Recent notifications of the group, oldest first, ending with the new one
+    pub lines: Vec<String>,
+    /// Recent notifications not listed
+    pub hidden: usize,
+}
+
+impl GroupUpdate {
+    /// Body listing the group's recent notifications
+    ///
+    /// `None` if the notification is the only one of its group, so it is
+    /// shown as is.
+    pub fn body(&self, app_name: &str) -> Option<String> {
+        if self.lines.len() < 2 {
+            return None;
+        }
+
+        let mut body = String::from(app_name);
+        if self.hidden > 0 {
+            body.push_str(&format!("\n… {} earlier", self.hidden));
+        }
+        for line in &self.lines {
+            body.push('\n');
+            body.push_str(line);
+        }
+        Some(body)
+    }
+}
+
+/// Shown notification of each group, by group tag
+#[derive(Debug, Clone, Default)]
+pub struct NotificationGroups {
+    groups: Arc<RwLock<HashMap<String, ShownGroup>>>,
+}
+
+impl NotificationGroups {
+    /// Add a notification to its group
+    ///
+    /// Replacing a notification that was closed in the meantime shows a new
+    /// one, so closed notifications need not be forgotten.
+    pub fn add(&self, tag: &str, entry: String, now: Instant) -> GroupUpdate {
+        let Ok(mut groups) = self.groups.write() else {
+            return GroupUpdate {
+                replaces_id: 0,
+                lines: vec![entry],
+                hidden: 0,
+            };
+        };
+
+        if groups.len() >= MAX_TRACKED_GROUPS && !groups.contains_key(tag) {
+            let oldest = groups
+                .iter()
+                .min_by_key(|(_, group)| group.id)
+                .map(|(tag, _)| tag.clone());
+            if let Some(oldest) = oldest {
+                groups.remove(&oldest);
+            }
+        }
+
+        let group = groups.entry(tag.to_string()).or_default();
+        group
+            .entries
+            .retain(|(added, _)| now.saturating_duration_since(*added) < GROUP_WINDOW);
+        if group.entries.len() >= MAX_GROUP_ENTRIES {
+            group.entries.pop_front();
+        }
+        group.entries.push_back((now, entry));
+
+        let hidden = group.entries.len().saturating_sub(MAX_GROUP_LINES);
+        GroupUpdate {
+            replaces_id: group.id,
+            lines: group
+                .entries
+                .iter()
+                .skip(hidden)
+                .map(|(_, line)| line.clone())
+                .collect(),
+            hidden,
+        }
+    }
+
+    /// Remember the notification shown for a group
+    pub fn record(&self, tag: String, notification_id: u32) {
+        if let Ok(mut groups) = self.groups.write() {
+            groups.entry(tag).or_default().id = notification_id;
+        }
+    }
+}
+
+/// Most plugin notifications whose actions are remembered at once
+///
+/// Notifications that expire without an action being invoked are never
+/// reported back, so the oldest entries are dropped beyond this.
+const MAX_TRACKED_ACTIONS: usize = 64;
+
+/// Actions of shown plugin notifications, by notification ID
+///
+/// Shared between the [`PluginNotifier`] that records them and the action
+/// listener that carries them out.
+#[derive(Debug, Clone, Default)]
+pub struct PluginNotificationActions {
+    actions: Arc<RwLock<HashMap<u32, (Option<String>, Vec<NotificationAction>)>>>,
+}
+
+impl PluginNotificationActions {
+    /// Remember the actions of a shown notification
+    pub fn register(&self, notification_id: u32, spec: &NotificationSpec) {
+        if spec.actions.is_empty() {
+            return;
+        }
+
+        if let Ok(mut actions) = self.actions.write() {
+            if actions.len() >= MAX_TRACKED_ACTIONS {
+                if let Some(oldest) = actions.keys().min().copied() {
+                    actions.remove(&oldest);
+                }
+            }
+            actions.insert(
+                notification_id,
+                (spec.device_id.clone(), spec.actions.clone()),
+            );
+        }
+    }
+
+    /// Look up an invoked action and forget the notification
+    ///
+    /// Returns the device the notification is about and the action target,
+    /// or `None` if the notification was not raised by a plugin.
+    pub fn take(
+        &self,
+        notification_id: u32,
+        action_key: &str,
+    ) -> Option<(Option<String>, ActionTarget)> {
+        let (device_id, actions) = self.actions.write().ok()?.remove(&notification_id)?;
+        let action = actions.into_iter().find(|action| action.id == action_key)?;
+        Some((device_id, action.target))
+    }
+
+    /// Forget a notification
+    pub fn remove(&self, notification_id: u32) {
+        if let Ok(mut actions) = self.actions.write() {
+            actions.remove(&notification_id);
+        }
+    }
+}
+
+/// Notification backend handed to protocol plugins
+///
+/// Raises plugin notifications through [`CosmicNotifier`] and applies the
+/// daemon's Do Not Disturb setting to call notifications, which then come
+/// back as [`NotificationHandle::NONE`]. Actions of shown notifications are
+/// recorded in [`PluginNotificationActions`].
+#[derive(Debug, Clone)]
+pub struct PluginNotifier {
+    notifier: Arc<CosmicNotifier>,
+    config: Arc<tokio::sync::RwLock<Config>>,
+    actions: PluginNotificationActions,
+}
+
+impl PluginNotifier {
+    /// Create a plugin notifier sharing the daemon's client, config and action registry
+    pub fn new(
+        notifier: Arc<CosmicNotifier>,
+        config: Arc<tokio::sync::RwLock<Config>>,
+        actions: PluginNotificationActions,
+    ) -> Self {
+        Self {
+            notifier,
+            config,
+            actions,
+        }
+    }
+
+    /// Whether Do Not Disturb hides notifications of this category
+    async fn suppressed(&self, category: Option<&str>) -> bool {
+        if !category.is_some_and(|category| category.starts_with("call.")) {
+            return false;
+        }
+
+        let config = self.config.read().await;
+        let desktop_dnd =
+            config.do_not_disturb.follow_desktop && do_not_disturb::desktop_do_not_disturb();
+        do_not_disturb::is_active(&config.do_not_disturb, desktop_dnd)
+    }
+}
+
+/// Convert a plugin notification into a builder
+fn builder_from_spec(spec: &NotificationSpec) -> NotificationBuilder {
+    let urgency = match spec.urgency {
+        NotificationUrgency::Low => Urgency::Low,
+        NotificationUrgency::Normal => Urgency::Normal,
+        NotificationUrgency::Critical => Urgency::Critical,
+    };
+
+    let mut builder = NotificationBuilder::new(spec.summary.as_str())
+        .body(spec.body.as_str())
+        .icon(spec.icon.as_str())
+        .urgency(urgency)
+        .timeout(spec.timeout_ms);
+    if let Some(category) = &spec.category {
+        builder = builder.hint("category", zbus::zvariant::Value::from(category.clone()));
+    }
+    if let Some(sound) = &spec.sound {
+        builder = builder.hint("sound-name", zbus::zvariant::Value::from(sound.clone()));
+    }
+    for action in &spec.actions {
+        builder = builder.action(action.id.as_str(), action.label.as_str());
+    }
+    builder
+}
+
+#[async_trait]
+impl Notifier for PluginNotifier {
+    async fn notify(
+        &self,
+        spec: NotificationSpec,
+    ) -> cosmic_connect_protocol::Result<NotificationHandle> {
+        if self.suppressed(spec.category.as_deref()).await {
+            debug!("Do Not Disturb active - suppressing '{}'", spec.summary);
+            return Ok(NotificationHandle::NONE);
+        }
+
+        let id = self
+            .notifier
+            .send(builder_from_spec(&spec))
+            .await
+            .map_err(|e| ProtocolError::Plugin(e.to_string()))?;
+        self.actions.register(id, &spec);
+        Ok(NotificationHandle::new(id))
+    }
+
+    async fn close(&self, handle: NotificationHandle) -> cosmic_connect_protocol::Result<()> {
+        if !handle.is_shown() {
+            return Ok(());
+        }
+
+        self.actions.remove(handle.id());
+        self.notifier
+            .close(handle.id())
+            .await
+            .map_err(|e| ProtocolError::Plugin(e.to_string()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -892,6 +1405,172 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_builder_from_spec_keeps_category() {
+        let spec = NotificationSpec::new("Incoming call")
+            .body("Alice")
+            .urgency(NotificationUrgency::Critical)
+            .category("call.incoming")
+            .timeout(0);
+
+        let params = builder_from_spec(&spec).build();
+
+        assert_eq!(params.summary, "Incoming call");
+        assert_eq!(params.body, "Alice");
+        assert_eq!(params.timeout, 0);
+        assert_eq!(
+            params.hints.get("category"),
+            Some(&zbus::zvariant::Value::from("call.incoming"))
+        );
+        assert_eq!(
+            params.hints.get("urgency"),
+            Some(&zbus::zvariant::Value::U8(2))
+        );
+        assert!(!params.hints.contains_key("sound-name"));
+    }
+
+    #[test]
+    fn test_builder_from_spec_plays_sound() {
+        let spec = NotificationSpec::new("Ping from Phone").sound("message-new-instant");
+
+        let params = builder_from_spec(&spec).build();
+
+        assert_eq!(
+            params.hints.get("sound-name"),
+            Some(&zbus::zvariant::Value::from("message-new-instant"))
+        );
+    }
+
+    #[test]
+    fn test_plugin_notification_actions() {
+        let spec = NotificationSpec::new("Ping from Phone")
+            .device("phone")
+            .action(NotificationAction::new(
+                "open-manager",
+                "Open manager",
+                ActionTarget::OpenDevice,
+            ));
+        assert_eq!(
+            builder_from_spec(&spec).build().actions,
+            vec!["open-manager".to_string(), "Open manager".to_string()]
+        );
+
+        let actions = PluginNotificationActions::default();
+        actions.register(7, &spec);
+        assert!(actions.take(8, "open-manager").is_none());
+        assert_eq!(
+            actions.take(7, "open-manager"),
+            Some((Some("phone".to_string()), ActionTarget::OpenDevice))
+        );
+        // Invoking forgets the notification
+        assert!(actions.take(7, "open-manager").is_none());
+
+        for id in 0..(MAX_TRACKED_ACTIONS as u32 + 1) {
+            actions.register(id, &spec);
+        }
+        assert!(actions.take(0, "open-manager").is_none());
+        let newest = MAX_TRACKED_ACTIONS as u32;
+        assert!(actions.take(newest, "open-manager").is_some());
+    }
+
+    #[test]
+    fn test_same_conversation_reuses_notification() {
+        let groups = NotificationGroups::default();
+        let grouping = NotificationGrouping::Conversation;
+        let now = Instant::now();
+
+        let first = group_tag(grouping, "Pixel", "Signal", Some("conv_1")).unwrap();
+        let update = groups.add(&first, "Alice: Hi".to_string(), now);
+        assert_eq!(update.replaces_id, 0);
+        assert_eq!(update.body("Signal"), None);
+        groups.record(first.clone(), 42);
+
+        // The next message in the conversation replaces the shown one,
+        // listing both
+        let second = group_tag(grouping, "Pixel", "Signal", Some("conv_1")).unwrap();
+        assert_eq!(second, first);
+        let update = groups.add(&second, "Alice: Lunch?".to_string(), now);
+        assert_eq!(
+            update.body("Signal").as_deref(),
+            Some("Signal\nAlice: Hi\nAlice: Lunch?")
+        );
+        let params = NotificationBuilder::new("Alice (Pixel)")
+            .mirrored_app("Signal")
+            .replaces(update.replaces_id)
+            .build();
+        assert_eq!(params.replaces_id, 42);
+        assert!(params.hints.contains_key("x-kde-appName"));
+        assert!(params.hints.contains_key("desktop-entry"));
+        assert_eq!(params.app_name, "CConnect");
+
+        // Other conversations and devices get their own notification
+        let other = group_tag(grouping, "Pixel", "Signal", Some("conv_2")).unwrap();
+        assert_eq!(
+            groups.add(&other, "Bob: Hey".to_string(), now).replaces_id,
+            0
+        );
+        let other_device = group_tag(grouping, "Tablet", "Signal", Some("conv_1")).unwrap();
+        assert_ne!(other_device, first);
+
+        // Without a conversation ID, notifications group by app
+        assert_eq!(
+            group_tag(grouping, "Pixel", "Gmail", None),
+            group_tag(NotificationGrouping::App, "Pixel", "Gmail", Some("thread"))
+        );
+        assert_eq!(
+            group_tag(
+                NotificationGrouping::None,
+                "Pixel",
+                "Signal",
+                Some("conv_1")
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_group_lists_recent_notifications() {
+        let groups = NotificationGroups::default();
+        let start = Instant::now();
+
+        for i in 0..MAX_GROUP_LINES + 2 {
+            groups.add("cconnect/Pixel/Gmail", format!("Mail {}", i), start);
+        }
+        let update = groups.add("cconnect/Pixel/Gmail", "Latest".to_string(), start);
+        assert_eq!(update.lines.len(), MAX_GROUP_LINES);
+        assert_eq!(update.lines.last().map(String::as_str), Some("Latest"));
+        assert_eq!(update.hidden, 3);
+        assert!(update
+            .body("Gmail")
+            .unwrap()
+            .starts_with("Gmail\n… 3 earlier\nMail 3"));
+
+        // Long after, the group starts over
+        let later = start + GROUP_WINDOW;
+        let update = groups.add("cconnect/Pixel/Gmail", "New".to_string(), later);
+        assert_eq!(update.lines, vec!["New".to_string()]);
+        assert_eq!(update.body("Gmail"), None);
+    }
+
+    #[test]
+    fn test_group_entry() {
+        assert_eq!(group_entry("Alice", "Hi\nthere", 0), "Alice: Hi there");
+        assert_eq!(group_entry("", "Hi", 0), "Hi");
+    }
+
+    #[test]
+    fn test_battery_low_body() {
+        assert_eq!(
+            battery_low_body(DeviceType::Phone, 15),
+            "Phone battery at 15%"
+        );
+        assert_eq!(
+            battery_low_body(DeviceType::Tablet, 7),
+            "Tablet battery at 7%"
+        );
+        assert_eq!(battery_low_body(DeviceType::Unknown, 10), "Battery at 10%");
+    }
+
     #[test]
     fn test_urgency_values() {
         assert_eq!(Urgency::Low as u8, 0);
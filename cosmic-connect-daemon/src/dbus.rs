@@ -6,14 +6,23 @@
 //! Provides IPC between the background daemon and COSMIC panel applet.
 //! Exposes device management, pairing, and plugin actions via DBus.
 
+use crate::device_updates::{DeviceSnapshot, DeviceUpdateCoalescer, DEVICE_UPDATE_WINDOW};
+use crate::event_bus::EventBus;
+use crate::received_files::{ReceivedFile, ReceivedFiles};
+use crate::transfers::{NewTransfer, TransferDirection, TransferItem, TransferManager};
 use anyhow::{Context, Result};
 use cosmic_connect_protocol::plugins::filesync::{
     ConflictStrategy as FilesyncConflictStrategy, FileSyncPlugin, SyncFolder as FilesyncFolder,
 };
-use cosmic_connect_protocol::{ConnectionManager, Device, DeviceManager, PluginManager};
+use cosmic_connect_protocol::plugins::metrics::PluginMetricsSnapshot;
+use cosmic_connect_protocol::{
+    AdaptiveRateConfig, AdaptiveRateController, ConnectionManager, Device, DeviceEvent,
+    DeviceManager, PluginManager,
+};
+use futures::StreamExt;
 use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use tokio::runtime::Handle;
 use tokio::sync::RwLock;
@@ -21,55 +30,6 @@ use tracing::{debug, error, info, warn};
 use zbus::object_server::{InterfaceRef, SignalEmitter};
 use zbus::{connection, interface, Connection};
 
-/// Tracks active file transfers with cancellation support
-pub struct TransferManager {
-    /// Map of transfer_id -> cancellation flag
-    active_transfers: Arc<RwLock<HashMap<String, Arc<AtomicBool>>>>,
-}
-
-impl TransferManager {
-    /// Create a new transfer manager
-    pub fn new() -> Self {
-        Self {
-            active_transfers: Arc::new(RwLock::new(HashMap::new())),
-        }
-    }
-
-    /// Register a new transfer and get its cancellation flag
-    pub async fn register_transfer(&self, transfer_id: String) -> Arc<AtomicBool> {
-        let cancel_flag = Arc::new(AtomicBool::new(false));
-        self.active_transfers
-            .write()
-            .await
-            .insert(transfer_id, cancel_flag.clone());
-        cancel_flag
-    }
-
-    /// Cancel a transfer by ID
-    pub async fn cancel_transfer(&self, transfer_id: &str) -> bool {
-        if let Some(cancel_flag) = self.active_transfers.read().await.get(transfer_id) {
-            cancel_flag.store(true, Ordering::SeqCst);
-            info!("Transfer {} marked for cancellation", transfer_id);
-            true
-        } else {
-            warn!("Transfer {} not found", transfer_id);
-            false
-        }
-    }
-
-    /// Remove a completed or cancelled transfer
-    pub async fn remove_transfer(&self, transfer_id: &str) {
-        self.active_transfers.write().await.remove(transfer_id);
-        debug!("Transfer {} removed from tracking", transfer_id);
-    }
-}
-
-impl Default for TransferManager {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 /// DBus service name
 pub const SERVICE_NAME: &str = "com.system76.CosmicConnect";
 
@@ -99,6 +59,8 @@ pub struct DeviceInfo {
     pub has_pairing_request: bool,
     /// Last seen timestamp (UNIX timestamp)
     pub last_seen: i64,
+    /// Seconds the device has been continuously connected, 0 if not connected
+    pub connected_seconds: u64,
     /// Supported incoming plugin capabilities
     pub incoming_capabilities: Vec<String>,
     /// Supported outgoing plugin capabilities
@@ -116,12 +78,53 @@ impl From<&Device> for DeviceInfo {
             is_connected: device.is_connected(),
             has_pairing_request: false, // Will be updated by caller if needed
             last_seen: device.last_seen as i64,
+            connected_seconds: device
+                .connected_duration()
+                .map_or(0, |duration| duration.as_secs()),
             incoming_capabilities: device.info.incoming_capabilities.clone(),
             outgoing_capabilities: device.info.outgoing_capabilities.clone(),
         }
     }
 }
 
+/// Number of device ID characters appended to duplicate device names
+const DEVICE_NAME_SUFFIX_LEN: usize = 4;
+
+/// How often link quality is re-read while an adaptive-rate transfer runs
+const TRANSFER_QUALITY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Make advertised device names unique for display
+///
+/// When several devices share a name, each gets the last characters of its
+/// ID appended, e.g. "Pixel (a1b2)". Devices with a nickname are left alone,
+/// the UIs show the nickname instead.
+pub(crate) fn disambiguate_device_names<'a>(
+    infos: impl IntoIterator<Item = &'a mut DeviceInfo>,
+    has_nickname: impl Fn(&str) -> bool,
+) {
+    let infos: Vec<&mut DeviceInfo> = infos
+        .into_iter()
+        .filter(|info| !has_nickname(&info.id))
+        .collect();
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for info in &infos {
+        *counts.entry(info.name.clone()).or_default() += 1;
+    }
+
+    for info in infos {
+        if counts.get(&info.name).is_some_and(|&count| count > 1) {
+            let start = info
+                .id
+                .char_indices()
+                .rev()
+                .nth(DEVICE_NAME_SUFFIX_LEN - 1)
+                .map_or(0, |(index, _)| index);
+            info.name = format!("{} ({})", info.name, &info.id[start..]);
+        }
+    }
+}
+
 /// Battery status for DBus serialization
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
 pub struct BatteryStatus {
@@ -186,6 +189,51 @@ pub struct DaemonMetrics {
     pub bandwidth_bps: f64,
 }
 
+/// Counters of a plugin for one device for DBus serialization
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
+pub struct PluginMetricsInfo {
+    /// Packets handed to the plugin
+    pub packets_handled: u64,
+    /// Errors returned by the plugin while handling packets
+    pub errors: u64,
+    /// Payload bytes sent or received
+    pub bytes_transferred: u64,
+    /// Desktop notifications raised
+    pub notifications_raised: u64,
+}
+
+impl From<PluginMetricsSnapshot> for PluginMetricsInfo {
+    fn from(snapshot: PluginMetricsSnapshot) -> Self {
+        Self {
+            packets_handled: snapshot.packets_handled,
+            errors: snapshot.errors,
+            bytes_transferred: snapshot.bytes_transferred,
+            notifications_raised: snapshot.notifications_raised,
+        }
+    }
+}
+
+/// Pinned certificate of a paired device for DBus serialization
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
+pub struct PairedCertificateInfo {
+    /// Device ID
+    pub device_id: String,
+    /// SHA256 fingerprint of the certificate
+    pub fingerprint: String,
+    /// When the device was paired (UNIX timestamp, 0 if unknown)
+    pub paired_at: u64,
+}
+
+impl From<cosmic_connect_protocol::PairedCertificate> for PairedCertificateInfo {
+    fn from(certificate: cosmic_connect_protocol::PairedCertificate) -> Self {
+        Self {
+            device_id: certificate.device_id,
+            fingerprint: certificate.fingerprint,
+            paired_at: certificate.paired_at.unwrap_or(0),
+        }
+    }
+}
+
 /// Sync Folder configuration for DBus serialization
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
 pub struct SyncFolderInfo {
@@ -204,6 +252,33 @@ impl From<&FilesyncFolder> for SyncFolderInfo {
     }
 }
 
+/// Received file for DBus serialization
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
+pub struct ReceivedFileInfo {
+    /// Where the file was saved
+    pub path: String,
+    /// File name
+    pub name: String,
+    /// Device the file came from
+    pub device_id: String,
+    /// Name of the device the file came from
+    pub device_name: String,
+    /// When the download finished (Unix timestamp in seconds)
+    pub received_at: u64,
+}
+
+impl From<ReceivedFile> for ReceivedFileInfo {
+    fn from(file: ReceivedFile) -> Self {
+        Self {
+            path: file.path.to_string_lossy().to_string(),
+            name: file.name,
+            device_id: file.device_id,
+            device_name: file.device_name,
+            received_at: file.received_at,
+        }
+    }
+}
+
 /// DBus interface for CConnect daemon
 pub struct CConnectInterface {
     /// Device manager
@@ -228,6 +303,8 @@ pub struct CConnectInterface {
     config: Arc<RwLock<crate::config::Config>>,
     /// Transfer manager for tracking and cancelling file transfers
     transfer_manager: Arc<TransferManager>,
+    /// Files recently downloaded from devices
+    received_files: Arc<RwLock<ReceivedFiles>>,
     /// Tokio runtime handle for spawning async tasks from zbus executor
     tokio_handle: Handle,
 }
@@ -246,6 +323,7 @@ impl CConnectInterface {
         dbus_connection: Connection,
         metrics: Option<Arc<RwLock<crate::diagnostics::Metrics>>>,
         config: Arc<RwLock<crate::config::Config>>,
+        event_bus: EventBus,
         tokio_handle: Handle,
     ) -> Self {
         Self {
@@ -259,11 +337,38 @@ impl CConnectInterface {
             dbus_connection,
             metrics,
             config,
-            transfer_manager: Arc::new(TransferManager::new()),
+            transfer_manager: Arc::new(TransferManager::with_event_bus(event_bus)),
+            received_files: Arc::new(RwLock::new(ReceivedFiles::new())),
             tokio_handle,
         }
     }
 
+    /// Build the DBus information of all known devices, keyed by device ID
+    ///
+    /// Duplicate device names are disambiguated, see [`disambiguate_device_names`].
+    async fn device_infos(&self) -> HashMap<String, DeviceInfo> {
+        let device_manager = self.device_manager.read().await;
+        let pending_requests = self.pending_pairing_requests.read().await;
+
+        let mut result = HashMap::new();
+        for device in device_manager.devices() {
+            let device_id = device.id().to_string();
+            let mut info = DeviceInfo::from(device);
+
+            info.has_pairing_request = pending_requests.contains_key(&device_id);
+            result.insert(device_id, info);
+        }
+
+        let registry = self.device_config_registry.read().await;
+        disambiguate_device_names(result.values_mut(), |device_id| {
+            registry
+                .get(device_id)
+                .is_some_and(|config| config.nickname.is_some())
+        });
+
+        result
+    }
+
     /// Emit a device plugin state changed signal
     async fn emit_plugin_state_changed(&self, device_id: &str, plugin_name: &str, enabled: bool) {
         let object_server = self.dbus_connection.object_server();
@@ -294,41 +399,316 @@ impl CConnectInterface {
             );
         }
     }
-}
 
-/// Attempt to manually connect to a device at the specified address
-async fn attempt_manual_connection(
-    connection_manager: &Arc<RwLock<ConnectionManager>>,
-    device_manager: &Arc<RwLock<cosmic_connect_protocol::DeviceManager>>,
-    addr: std::net::SocketAddr,
-    temp_id: &str,
-) -> Result<String> {
-    // Try to connect - this will trigger identity exchange
-    let conn_mgr = connection_manager.read().await;
-    conn_mgr.connect(temp_id, addr).await?;
-    drop(conn_mgr);
-
-    // Wait a bit for identity exchange to complete
-    // In a real implementation, we'd listen for ConnectionEvent::Connected
-    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-
-    // Check if we got a real device ID from the identity exchange
-    let dev_mgr = device_manager.read().await;
-
-    // Look for a device with this transport address
-    for device in dev_mgr.devices() {
-        if let (Some(host), Some(port)) = (&device.host, device.port) {
-            // Parse the host to an IP address and compare with the target socket address
-            if let Ok(ip) = host.parse::<std::net::IpAddr>() {
-                let device_addr = std::net::SocketAddr::new(ip, port);
-                if device_addr == addr {
-                    return Ok(device.id().to_string());
-                }
+    /// Accept or reject a pending file offer from any device
+    ///
+    /// Returns false if no device has a pending offer with this transfer ID.
+    async fn resolve_file_offer(&self, transfer_id: &str, accept: bool) -> bool {
+        use cosmic_connect_protocol::plugins::share::SharePlugin;
+
+        let device_ids: Vec<String> = {
+            let device_manager = self.device_manager.read().await;
+            device_manager.device_ids().cloned().collect()
+        };
+
+        let plugin_manager = self.plugin_manager.read().await;
+        for device_id in device_ids {
+            let Some(share) = plugin_manager
+                .get_device_plugin(&device_id, "share")
+                .and_then(|plugin| plugin.as_any().downcast_ref::<SharePlugin>())
+            else {
+                continue;
+            };
+
+            let resolved = if accept {
+                share.accept_transfer(transfer_id).await
+            } else {
+                share.reject_transfer(transfer_id).await
+            };
+            if resolved {
+                return true;
             }
         }
+
+        false
     }
 
-    Err(anyhow::anyhow!("Device did not respond with identity"))
+    /// Send a file to a device in the background
+    ///
+    /// Returns the ID of the queued transfer.
+    async fn start_file_share(
+        &self,
+        device_id: String,
+        path: String,
+    ) -> Result<String, zbus::fdo::Error> {
+        info!(
+            "DBus: ShareFile called for {} with path '{}'",
+            device_id, path
+        );
+
+        // Validate device exists and is connected
+        let device_manager = self.device_manager.read().await;
+        let device = device_manager
+            .get_device(&device_id)
+            .ok_or_else(|| zbus::fdo::Error::Failed(format!("Device not found: {}", device_id)))?;
+
+        if !device.is_connected() {
+            return Err(zbus::fdo::Error::Failed("Device not connected".to_string()));
+        }
+
+        drop(device_manager);
+
+        if !crate::network_policy::connection_allowed(
+            &self.device_config_registry,
+            &crate::network_policy::NetworkManagerSource,
+            &device_id,
+        )
+        .await
+        {
+            return Err(zbus::fdo::Error::Failed(
+                "File transfers to this device are not allowed on the current network".to_string(),
+            ));
+        }
+
+        // Validate file exists (using std::fs which doesn't require tokio runtime)
+        if !std::path::Path::new(&path).exists() {
+            return Err(zbus::fdo::Error::Failed(format!(
+                "File not found: {}",
+                path
+            )));
+        }
+
+        // Generate unique transfer ID
+        let timestamp_millis = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_else(|_| std::time::Duration::from_secs(0))
+            .as_millis();
+        let transfer_id = format!("{}_{}", device_id, timestamp_millis);
+
+        // Queue the transfer and get its cancellation flag
+        let cancel_flag = self
+            .transfer_manager
+            .register_transfer(NewTransfer {
+                transfer_id: transfer_id.clone(),
+                direction: TransferDirection::Sending,
+                device_id: device_id.clone(),
+                path: PathBuf::from(&path),
+                total_bytes: std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+            })
+            .await;
+
+        // Clone all needed values for the spawned task
+        let file_path = path.clone();
+        let device_id_clone = device_id.clone();
+        let transfer_id_clone = transfer_id.clone();
+        let dbus_conn = self.dbus_connection.clone();
+        let transfer_manager = self.transfer_manager.clone();
+        let conn_manager = self.connection_manager.clone();
+        let tokio_handle = self.tokio_handle.clone();
+        let device_manager = self.device_manager.clone();
+        let adaptive_rate = self.config.read().await.network.adaptive_transfer_rate;
+
+        // Spawn the entire file transfer operation on tokio runtime
+        // This ensures all tokio operations have access to the runtime
+        // We use self.tokio_handle.spawn() because the zbus executor doesn't have a tokio runtime context
+        self.tokio_handle.spawn(async move {
+            use cosmic_connect_protocol::plugins::share::{FileShareInfo, SharePlugin};
+            use cosmic_connect_protocol::{FileTransferInfo, TlsPayloadServer};
+
+            // Extract file metadata (inside tokio runtime)
+            let file_info = match FileTransferInfo::from_path(&file_path).await {
+                Ok(info) => info,
+                Err(e) => {
+                    warn!("Failed to read file metadata: {}", e);
+                    transfer_manager
+                        .finish_transfer(&transfer_id_clone, false)
+                        .await;
+                    return;
+                }
+            };
+
+            info!(
+                "DBus: Sharing file '{}' ({} bytes) to {}",
+                file_info.filename, file_info.size, device_id_clone
+            );
+
+            // Get TLS config from connection manager
+            let tls_config = {
+                let conn_mgr = conn_manager.read().await;
+                conn_mgr.tls_config()
+            };
+
+            // Create TLS payload server on available port (inside tokio runtime)
+            let server = match TlsPayloadServer::new(tls_config).await {
+                Ok(s) => s,
+                Err(e) => {
+                    warn!("Failed to create TLS payload server: {}", e);
+                    transfer_manager
+                        .finish_transfer(&transfer_id_clone, false)
+                        .await;
+                    return;
+                }
+            };
+            let port = server.port();
+
+            info!("DBus: TLS Payload server listening on port {}", port);
+
+            // Create share packet with file info and payload transfer port
+            let share_info: FileShareInfo = file_info.clone().into();
+            let plugin = SharePlugin::new();
+            let packet = plugin.create_file_packet(share_info, port);
+
+            // Send packet via ConnectionManager
+            let conn_mgr = conn_manager.read().await;
+            if let Err(e) = conn_mgr.send_packet(&device_id_clone, &packet).await {
+                warn!("Failed to send share packet: {}", e);
+                transfer_manager
+                    .finish_transfer(&transfer_id_clone, false)
+                    .await;
+                return;
+            }
+            drop(conn_mgr);
+
+            info!(
+                "DBus: Share packet sent to {}, waiting for connection",
+                device_id_clone
+            );
+
+            let filename = file_info.filename.clone();
+
+            // Create progress callback, the queue publishes progress for DBus signals
+            let tid = transfer_id_clone.clone();
+            let cancel_flag_inner = cancel_flag.clone();
+            let handle_inner = tokio_handle.clone();
+            let transfer_manager_inner = transfer_manager.clone();
+
+            let progress_callback =
+                Box::new(move |bytes_transferred: u64, total_bytes: u64| -> bool {
+                    // Check if transfer is cancelled
+                    if cancel_flag_inner.load(Ordering::SeqCst) {
+                        info!("Transfer {} cancelled by user", tid);
+                        return false; // Stop transfer
+                    }
+
+                    let tid_clone = tid.clone();
+                    let transfer_manager_clone = transfer_manager_inner.clone();
+
+                    // Record progress (non-blocking)
+                    // Use the handle to spawn since we may be called from a non-tokio context
+                    handle_inner.spawn(async move {
+                        transfer_manager_clone
+                            .update_progress(&tid_clone, bytes_transferred, total_bytes)
+                            .await;
+                    });
+
+                    true // Continue transfer
+                });
+
+            // Attach progress callback and start transfer
+            let server_with_progress = server.with_progress(progress_callback);
+            let result = send_payload(
+                server_with_progress,
+                &file_path,
+                &device_manager,
+                &device_id_clone,
+                adaptive_rate,
+            )
+            .await;
+
+            // Determine completion status
+            let (success, error_msg) = if cancel_flag.load(Ordering::SeqCst) {
+                (false, "Transfer cancelled by user".to_string())
+            } else {
+                (
+                    result.is_ok(),
+                    result
+                        .as_ref()
+                        .err()
+                        .map(|e| e.to_string())
+                        .unwrap_or_default(),
+                )
+            };
+
+            // Emit completion signal
+            if let Ok(object_server) = dbus_conn
+                .object_server()
+                .interface::<_, CConnectInterface>(OBJECT_PATH)
+                .await
+            {
+                let _ = CConnectInterface::transfer_complete(
+                    object_server.signal_emitter(),
+                    &transfer_id_clone,
+                    &device_id_clone,
+                    &filename,
+                    success,
+                    &error_msg,
+                )
+                .await;
+            }
+
+            // Keep the outcome in the queue
+            transfer_manager
+                .finish_transfer(&transfer_id_clone, success)
+                .await;
+
+            if success {
+                info!(
+                    "File transfer completed successfully for device {}",
+                    device_id_clone
+                );
+            } else {
+                warn!(
+                    "File transfer failed for device {}: {}",
+                    device_id_clone, error_msg
+                );
+            }
+        });
+
+        info!(
+            "DBus: File sharing initiated for {} (transfer_id: {})",
+            device_id, transfer_id
+        );
+        Ok(transfer_id)
+    }
+}
+
+/// Send a file from a payload server to a device
+///
+/// All outgoing payloads of the daemon go through here. With an adaptive
+/// rate configured, the transfer is throttled while the device's link is
+/// degraded, re-reading its connection quality during the transfer.
+async fn send_payload(
+    server: cosmic_connect_protocol::TlsPayloadServer,
+    path: &str,
+    device_manager: &Arc<RwLock<DeviceManager>>,
+    device_id: &str,
+    adaptive_rate: Option<AdaptiveRateConfig>,
+) -> cosmic_connect_protocol::Result<()> {
+    let Some(rate_config) = adaptive_rate else {
+        return server.send_file(path).await;
+    };
+
+    let controller = AdaptiveRateController::new(rate_config);
+    let server = server.with_rate_limit(controller.limiter());
+
+    let device_manager = device_manager.clone();
+    let device_id = device_id.to_string();
+    let quality_monitor = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TRANSFER_QUALITY_INTERVAL);
+        loop {
+            interval.tick().await;
+            let quality = device_manager
+                .read()
+                .await
+                .get_device(&device_id)
+                .and_then(|device| device.connection_quality());
+            controller.observe(quality);
+        }
+    });
+
+    let result = server.send_file(path).await;
+    quality_monitor.abort();
+    result
 }
 
 /// Parse vCard data to extract contact information
@@ -370,19 +750,7 @@ impl CConnectInterface {
     async fn list_devices(&self) -> zbus::fdo::Result<HashMap<String, DeviceInfo>> {
         debug!("DBus: ListDevices called");
 
-        let device_manager = self.device_manager.read().await;
-        let devices = device_manager.devices();
-
-        let pending_requests = self.pending_pairing_requests.read().await;
-
-        let mut result = HashMap::new();
-        for device in devices {
-            let device_id = device.id().to_string();
-            let mut info = DeviceInfo::from(device);
-
-            info.has_pairing_request = pending_requests.contains_key(&device_id);
-            result.insert(device_id, info);
-        }
+        let result = self.device_infos().await;
 
         info!("DBus: Returning {} devices", result.len());
         Ok(result)
@@ -398,19 +766,10 @@ impl CConnectInterface {
     async fn get_device(&self, device_id: String) -> Result<DeviceInfo, zbus::fdo::Error> {
         debug!("DBus: GetDevice called for {}", device_id);
 
-        let device_manager = self.device_manager.read().await;
-        let device = device_manager
-            .get_device(&device_id)
-            .ok_or_else(|| zbus::fdo::Error::Failed(format!("Device not found: {}", device_id)))?;
-
-        let mut info = DeviceInfo::from(device);
-        info.has_pairing_request = self
-            .pending_pairing_requests
-            .read()
+        self.device_infos()
             .await
-            .contains_key(&device_id);
-
-        Ok(info)
+            .remove(&device_id)
+            .ok_or_else(|| zbus::fdo::Error::Failed(format!("Device not found: {}", device_id)))
     }
 
     /// Request pairing with a device
@@ -516,6 +875,80 @@ impl CConnectInterface {
         Ok(())
     }
 
+    /// Forget unpaired devices that have not been seen for a while
+    ///
+    /// Uses the configured age, or seven days when automatic pruning is off.
+    ///
+    /// # Returns
+    /// IDs of the removed devices
+    async fn remove_stale_devices(&self) -> Result<Vec<String>, zbus::fdo::Error> {
+        info!("DBus: RemoveStaleDevices called");
+
+        let max_age = self
+            .config
+            .read()
+            .await
+            .network
+            .stale_device_age()
+            .unwrap_or(cosmic_connect_protocol::device::DEFAULT_STALE_DEVICE_AGE);
+
+        self.device_manager
+            .write()
+            .await
+            .remove_stale_devices(max_age)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to remove stale devices: {}", e)))
+    }
+
+    /// List the certificates pinned for paired devices
+    ///
+    /// # Returns
+    /// Device ID, fingerprint and pairing date of every pinned certificate
+    async fn list_paired_certificates(
+        &self,
+    ) -> Result<Vec<PairedCertificateInfo>, zbus::fdo::Error> {
+        debug!("DBus: ListPairedCertificates called");
+
+        let pairing_service = self.pairing_service.as_ref().ok_or_else(|| {
+            zbus::fdo::Error::Failed("Pairing service not initialized".to_string())
+        })?;
+
+        let certificates = pairing_service
+            .read()
+            .await
+            .paired_certificates()
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to list certificates: {}", e)))?;
+
+        Ok(certificates.into_iter().map(Into::into).collect())
+    }
+
+    /// Revoke the pinned certificate of a device and unpair it
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID to revoke
+    ///
+    /// # Returns
+    /// Success or error message
+    async fn revoke_certificate(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
+        info!("DBus: RevokeCertificate called for {}", device_id);
+
+        let pairing_service = self.pairing_service.as_ref().ok_or_else(|| {
+            zbus::fdo::Error::Failed("Pairing service not initialized".to_string())
+        })?;
+
+        pairing_service
+            .read()
+            .await
+            .revoke_certificate(&device_id)
+            .await
+            .map_err(|e| {
+                zbus::fdo::Error::Failed(format!("Failed to revoke certificate: {}", e))
+            })?;
+
+        info!("Certificate of device {} revoked", device_id);
+        Ok(())
+    }
+
     /// Accept a pairing request from a device
     ///
     /// # Arguments
@@ -581,6 +1014,9 @@ impl CConnectInterface {
 
     /// Connect to a device at a specific address
     ///
+    /// Probes the address for a device and adds it like a discovered one, for
+    /// devices UDP discovery doesn't reach.
+    ///
     /// # Arguments
     /// * `address` - The IP:port or hostname:port to connect to (defaults to port 1716 if not specified)
     ///
@@ -610,26 +1046,14 @@ impl CConnectInterface {
 
         info!("Attempting manual connection to {}", socket_addr);
 
-        // Spawn a task to attempt connection
-        // We'll connect and wait for the remote device to send its identity
+        // Spawn a task to probe and connect, the identity exchange can take a while
         let connection_manager = self.connection_manager.clone();
-        let device_manager = self.device_manager.clone();
 
         tokio::spawn(async move {
-            // Create a temporary device ID for the connection attempt
-            // The real device ID will be obtained from the identity packet
-            let temp_id = format!("manual_{}", socket_addr);
-
-            match attempt_manual_connection(
-                &connection_manager,
-                &device_manager,
-                socket_addr,
-                &temp_id,
-            )
-            .await
-            {
-                Ok(device_id) => {
-                    info!("Successfully connected to device: {}", device_id);
+            let conn_mgr = connection_manager.read().await;
+            match conn_mgr.add_device_by_address(socket_addr).await {
+                Ok(info) => {
+                    info!("Successfully connected to device: {}", info.device_id);
                 }
                 Err(e) => {
                     warn!("Failed to connect to {}: {}", socket_addr, e);
@@ -640,6 +1064,75 @@ impl CConnectInterface {
         Ok(())
     }
 
+    /// Add a device by IP address, for networks where discovery fails
+    ///
+    /// Probes the address for a device, adds it to the device list and
+    /// connects to it, so it can be paired like a discovered device.
+    ///
+    /// # Arguments
+    /// * `ip` - IPv4 or IPv6 address of the device
+    /// * `port` - TCP port of the device, 0 for the default 1716
+    ///
+    /// # Returns
+    /// The ID of the added device
+    async fn add_device_by_address(
+        &self,
+        ip: String,
+        port: u16,
+    ) -> Result<String, zbus::fdo::Error> {
+        info!("DBus: AddDeviceByAddress called with {}:{}", ip, port);
+
+        let ip: std::net::IpAddr = ip
+            .trim()
+            .parse()
+            .map_err(|_| zbus::fdo::Error::Failed(format!("Invalid IP address: {}", ip)))?;
+        let port = if port == 0 { 1716 } else { port };
+        let addr = std::net::SocketAddr::new(ip, port);
+
+        // The probe needs the Tokio runtime, which the zbus executor lacks
+        let connection_manager = self.connection_manager.clone();
+        let info = self
+            .tokio_handle
+            .spawn(async move {
+                let conn_mgr = connection_manager.read().await;
+                conn_mgr.add_device_by_address(addr).await
+            })
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Tokio task failed: {}", e)))?
+            .map_err(|e| {
+                zbus::fdo::Error::Failed(format!("No device could be added at {}: {}", addr, e))
+            })?;
+
+        info!(
+            "Added device {} ({}) at {}",
+            info.device_name, info.device_id, addr
+        );
+        Ok(info.device_id)
+    }
+
+    /// Disconnect from a device without unpairing it
+    ///
+    /// Closes the connection and cancels the device's transfers. The device
+    /// is not reconnected automatically until it connects again.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID to disconnect from
+    async fn disconnect_device(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
+        info!("DBus: DisconnectDevice called for {}", device_id);
+
+        // Waiting for the connection to close needs the Tokio runtime
+        let connection_manager = self.connection_manager.clone();
+        let id = device_id.clone();
+        self.tokio_handle
+            .spawn(async move { connection_manager.read().await.disconnect(&id).await })
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Tokio task failed: {}", e)))?
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to disconnect: {}", e)))?;
+
+        info!("DBus: Disconnected from {}", device_id);
+        Ok(())
+    }
+
     /// Get device connection state
     ///
     /// # Arguments
@@ -713,6 +1206,48 @@ impl CConnectInterface {
         Ok(())
     }
 
+    /// Send an arbitrary packet to a device
+    ///
+    /// For plugin development and testing. Only available when
+    /// `debug.allow_custom_packets` is enabled in the daemon configuration.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID to send the packet to
+    /// * `packet_type` - Packet type, e.g. `cconnect.experimental.hello`
+    /// * `body` - Packet body as a JSON object
+    async fn send_custom_packet(
+        &self,
+        device_id: String,
+        packet_type: String,
+        body: String,
+    ) -> Result<(), zbus::fdo::Error> {
+        info!(
+            "DBus: SendCustomPacket called for {} with type '{}'",
+            device_id, packet_type
+        );
+
+        if !self.config.read().await.debug.allow_custom_packets {
+            return Err(zbus::fdo::Error::Failed(
+                "Custom packets are disabled, enable debug.allow_custom_packets".to_string(),
+            ));
+        }
+
+        let body: serde_json::Value = serde_json::from_str(&body)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Invalid packet body: {}", e)))?;
+        if !body.is_object() {
+            return Err(zbus::fdo::Error::Failed(
+                "Packet body must be a JSON object".to_string(),
+            ));
+        }
+
+        let packet = cosmic_connect_protocol::Packet::new(packet_type, body);
+        let conn_manager = self.connection_manager.read().await;
+        conn_manager
+            .send_custom_packet(&device_id, &packet)
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to send packet: {}", e)))
+    }
+
     /// Trigger find phone on a device
     ///
     /// # Arguments
@@ -767,26 +1302,30 @@ impl CConnectInterface {
 
         if !device.is_connected() {
             return Err(zbus::fdo::Error::Failed("Device not connected".to_string()));
-        }
-
-        drop(device_manager);
-
-        // Create telephony mute packet
-        use cosmic_connect_protocol::Packet;
-        use serde_json::json;
-
-        let packet = Packet::new("cconnect.telephony.mute", json!({}));
-
-        // Send packet via ConnectionManager
-        let conn_manager = self.connection_manager.read().await;
-        conn_manager
-            .send_packet(&device_id, &packet)
-            .await
-            .map_err(|e| {
-                zbus::fdo::Error::Failed(format!("Failed to send mute call request: {}", e))
+        }
+
+        drop(device_manager);
+
+        // The plugin only sends the request while the phone is ringing
+        use cosmic_connect_protocol::plugins::telephony::TelephonyPlugin;
+
+        let plugin_manager = self.plugin_manager.read().await;
+        let telephony = plugin_manager
+            .get_device_plugin(&device_id, "telephony")
+            .and_then(|plugin| plugin.as_any().downcast_ref::<TelephonyPlugin>())
+            .ok_or_else(|| {
+                zbus::fdo::Error::Failed("Telephony plugin not available for device".to_string())
             })?;
 
-        info!("DBus: Mute call request sent successfully to {}", device_id);
+        let sent = telephony.mute_call().await.map_err(|e| {
+            zbus::fdo::Error::Failed(format!("Failed to send mute call request: {}", e))
+        })?;
+
+        if sent {
+            info!("DBus: Mute call request sent successfully to {}", device_id);
+        } else {
+            info!("DBus: {} is not ringing, nothing to mute", device_id);
+        }
         Ok(())
     }
 
@@ -821,9 +1360,13 @@ impl CConnectInterface {
         drop(device_manager);
 
         // Create SMS request packet
+        use cosmic_connect_protocol::plugins::reply::prepare_reply;
         use cosmic_connect_protocol::Packet;
         use serde_json::json;
 
+        let message = prepare_reply(&message)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Cannot send SMS: {}", e)))?;
+
         let packet = Packet::new(
             "cconnect.sms.request",
             json!({
@@ -1104,18 +1647,16 @@ impl CConnectInterface {
         Ok(())
     }
 
-    /// Share a file with a device
+    /// Ask a device to take a photo
+    ///
+    /// The photo is downloaded once the user takes it on the device, which
+    /// is announced with the `PhotoReceived` signal.
     ///
     /// # Arguments
-    /// * `device_id` - The device ID to share with
-    /// * `path` - Absolute path to the file to share
-    async fn share_file(&self, device_id: String, path: String) -> Result<(), zbus::fdo::Error> {
-        info!(
-            "DBus: ShareFile called for {} with path '{}'",
-            device_id, path
-        );
+    /// * `device_id` - The device ID to take the photo with
+    async fn take_photo(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
+        info!("DBus: TakePhoto called for {}", device_id);
 
-        // Validate device exists and is connected
         let device_manager = self.device_manager.read().await;
         let device = device_manager
             .get_device(&device_id)
@@ -1127,196 +1668,66 @@ impl CConnectInterface {
 
         drop(device_manager);
 
-        // Validate file exists (using std::fs which doesn't require tokio runtime)
-        if !std::path::Path::new(&path).exists() {
-            return Err(zbus::fdo::Error::Failed(format!(
-                "File not found: {}",
-                path
-            )));
-        }
-
-        // Generate unique transfer ID
-        let timestamp_millis = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_else(|_| std::time::Duration::from_secs(0))
-            .as_millis();
-        let transfer_id = format!("{}_{}", device_id, timestamp_millis);
-
-        // Register transfer and get cancellation flag
-        let cancel_flag = self
-            .transfer_manager
-            .register_transfer(transfer_id.clone())
-            .await;
-
-        // Clone all needed values for the spawned task
-        let file_path = path.clone();
-        let device_id_clone = device_id.clone();
-        let transfer_id_clone = transfer_id.clone();
-        let dbus_conn = self.dbus_connection.clone();
-        let transfer_manager = self.transfer_manager.clone();
-        let conn_manager = self.connection_manager.clone();
-        let tokio_handle = self.tokio_handle.clone();
-
-        // Spawn the entire file transfer operation on tokio runtime
-        // This ensures all tokio operations have access to the runtime
-        // We use self.tokio_handle.spawn() because the zbus executor doesn't have a tokio runtime context
-        self.tokio_handle.spawn(async move {
-            use cosmic_connect_protocol::plugins::share::{FileShareInfo, SharePlugin};
-            use cosmic_connect_protocol::{FileTransferInfo, TlsPayloadServer};
-
-            // Extract file metadata (inside tokio runtime)
-            let file_info = match FileTransferInfo::from_path(&file_path).await {
-                Ok(info) => info,
-                Err(e) => {
-                    warn!("Failed to read file metadata: {}", e);
-                    return;
-                }
-            };
-
-            info!(
-                "DBus: Sharing file '{}' ({} bytes) to {}",
-                file_info.filename, file_info.size, device_id_clone
-            );
-
-            // Get TLS config from connection manager
-            let tls_config = {
-                let conn_mgr = conn_manager.read().await;
-                conn_mgr.tls_config()
-            };
-
-            // Create TLS payload server on available port (inside tokio runtime)
-            let server = match TlsPayloadServer::new(tls_config).await {
-                Ok(s) => s,
-                Err(e) => {
-                    warn!("Failed to create TLS payload server: {}", e);
-                    return;
-                }
-            };
-            let port = server.port();
-
-            info!("DBus: TLS Payload server listening on port {}", port);
-
-            // Create share packet with file info and payload transfer port
-            let share_info: FileShareInfo = file_info.clone().into();
-            let plugin = SharePlugin::new();
-            let packet = plugin.create_file_packet(share_info, port);
-
-            // Send packet via ConnectionManager
-            let conn_mgr = conn_manager.read().await;
-            if let Err(e) = conn_mgr.send_packet(&device_id_clone, &packet).await {
-                warn!("Failed to send share packet: {}", e);
-                return;
-            }
-            drop(conn_mgr);
-
-            info!(
-                "DBus: Share packet sent to {}, waiting for connection",
-                device_id_clone
-            );
-
-            let filename = file_info.filename.clone();
+        use cosmic_connect_protocol::plugins::photo::PhotoPlugin;
 
-            // Create progress callback that emits DBus signals
-            let conn = dbus_conn.clone();
-            let tid = transfer_id_clone.clone();
-            let did = device_id_clone.clone();
-            let fname = filename.clone();
-            let cancel_flag_inner = cancel_flag.clone();
-            let handle_inner = tokio_handle.clone();
+        let packet = PhotoPlugin::create_photo_request();
 
-            let progress_callback =
-                Box::new(move |bytes_transferred: u64, total_bytes: u64| -> bool {
-                    // Check if transfer is cancelled
-                    if cancel_flag_inner.load(Ordering::SeqCst) {
-                        info!("Transfer {} cancelled by user", tid);
-                        return false; // Stop transfer
-                    }
+        let conn_manager = self.connection_manager.read().await;
+        conn_manager
+            .send_packet(&device_id, &packet)
+            .await
+            .map_err(|e| {
+                zbus::fdo::Error::Failed(format!("Failed to send photo request: {}", e))
+            })?;
 
-                    let conn_clone = conn.clone();
-                    let tid_clone = tid.clone();
-                    let did_clone = did.clone();
-                    let fname_clone = fname.clone();
+        info!("DBus: Photo request sent successfully to {}", device_id);
+        Ok(())
+    }
 
-                    // Emit progress signal (non-blocking)
-                    // Use the handle to spawn since we may be called from a non-tokio context
-                    handle_inner.spawn(async move {
-                        if let Ok(object_server) = conn_clone
-                            .object_server()
-                            .interface::<_, CConnectInterface>(OBJECT_PATH)
-                            .await
-                        {
-                            let _ = CConnectInterface::transfer_progress(
-                                object_server.signal_emitter(),
-                                &tid_clone,
-                                &did_clone,
-                                &fname_clone,
-                                bytes_transferred,
-                                total_bytes,
-                                "sending",
-                            )
-                            .await;
-                        }
-                    });
+    /// Share a file with a device
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID to share with
+    /// * `path` - Absolute path to the file to share
+    async fn share_file(&self, device_id: String, path: String) -> Result<(), zbus::fdo::Error> {
+        self.start_file_share(device_id, path).await.map(|_| ())
+    }
 
-                    true // Continue transfer
-                });
+    /// Push the desktop clipboard to a device
+    ///
+    /// Sends the current clipboard content once, also when automatic
+    /// clipboard sync is off. Meant to be bound to a keyboard shortcut.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID to push the clipboard to
+    async fn push_clipboard(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
+        info!("DBus: PushClipboard called for {}", device_id);
 
-            // Attach progress callback and start transfer
-            let server_with_progress = server.with_progress(progress_callback);
-            let result = server_with_progress.send_file(&file_path).await;
+        let device_manager = self.device_manager.read().await;
+        let device = device_manager
+            .get_device(&device_id)
+            .ok_or_else(|| zbus::fdo::Error::Failed(format!("Device not found: {}", device_id)))?;
 
-            // Determine completion status
-            let (success, error_msg) = if cancel_flag.load(Ordering::SeqCst) {
-                (false, "Transfer cancelled by user".to_string())
-            } else {
-                (
-                    result.is_ok(),
-                    result
-                        .as_ref()
-                        .err()
-                        .map(|e| e.to_string())
-                        .unwrap_or_default(),
-                )
-            };
+        if !device.is_connected() {
+            return Err(zbus::fdo::Error::Failed("Device not connected".to_string()));
+        }
 
-            // Emit completion signal
-            if let Ok(object_server) = dbus_conn
-                .object_server()
-                .interface::<_, CConnectInterface>(OBJECT_PATH)
-                .await
-            {
-                let _ = CConnectInterface::transfer_complete(
-                    object_server.signal_emitter(),
-                    &transfer_id_clone,
-                    &device_id_clone,
-                    &filename,
-                    success,
-                    &error_msg,
-                )
-                .await;
-            }
+        drop(device_manager);
 
-            // Remove transfer from manager
-            transfer_manager.remove_transfer(&transfer_id_clone).await;
+        use cosmic_connect_protocol::plugins::clipboard::ClipboardPlugin;
 
-            if success {
-                info!(
-                    "File transfer completed successfully for device {}",
-                    device_id_clone
-                );
-            } else {
-                warn!(
-                    "File transfer failed for device {}: {}",
-                    device_id_clone, error_msg
-                );
-            }
-        });
+        let plugin_manager = self.plugin_manager.read().await;
+        let clipboard = plugin_manager
+            .get_device_plugin(&device_id, "clipboard")
+            .and_then(|plugin| plugin.as_any().downcast_ref::<ClipboardPlugin>())
+            .ok_or_else(|| {
+                zbus::fdo::Error::Failed("Clipboard plugin not available for device".to_string())
+            })?;
 
-        info!(
-            "DBus: File sharing initiated for {} (transfer_id: {})",
-            device_id, transfer_id
-        );
-        Ok(())
+        clipboard
+            .push_clipboard()
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to push clipboard: {}", e)))
     }
 
     /// Share text or URL with a device
@@ -1394,32 +1805,117 @@ impl CConnectInterface {
         Ok(())
     }
 
-    /// Cancel an active file transfer
+    /// Cancel an active file transfer
+    ///
+    /// # Arguments
+    /// * `transfer_id` - The transfer ID to cancel
+    ///
+    /// # Returns
+    /// * `Ok(())` if transfer was cancelled or not found
+    /// * `Err` if cancellation failed
+    async fn cancel_transfer(&self, transfer_id: String) -> Result<(), zbus::fdo::Error> {
+        info!(
+            "DBus: CancelTransfer called for transfer_id: {}",
+            transfer_id
+        );
+
+        let cancelled = self.transfer_manager.cancel_transfer(&transfer_id).await;
+
+        if cancelled {
+            info!("Transfer {} marked for cancellation", transfer_id);
+            Ok(())
+        } else {
+            // Transfer not found - this is not an error, it may have already completed
+            debug!(
+                "Transfer {} not found (may have already completed)",
+                transfer_id
+            );
+            Ok(())
+        }
+    }
+
+    /// List the file transfer queue
+    ///
+    /// # Returns
+    /// Pending, active and recently finished transfers, oldest first
+    async fn list_transfers(&self) -> zbus::fdo::Result<Vec<TransferItem>> {
+        debug!("DBus: ListTransfers called");
+        Ok(self.transfer_manager.list().await)
+    }
+
+    /// Send the file of a failed or cancelled transfer again
+    ///
+    /// The old transfer is replaced in the queue by the new one.
+    ///
+    /// # Arguments
+    /// * `transfer_id` - The transfer ID to retry
+    ///
+    /// # Returns
+    /// The ID of the new transfer
+    async fn retry_transfer(&self, transfer_id: String) -> Result<String, zbus::fdo::Error> {
+        info!(
+            "DBus: RetryTransfer called for transfer_id: {}",
+            transfer_id
+        );
+
+        let (device_id, path) = self
+            .transfer_manager
+            .retry_source(&transfer_id)
+            .await
+            .ok_or_else(|| {
+                zbus::fdo::Error::Failed(format!(
+                    "Transfer {} is not a failed or cancelled outgoing transfer",
+                    transfer_id
+                ))
+            })?;
+
+        let new_id = self
+            .start_file_share(device_id, path.to_string_lossy().into_owned())
+            .await?;
+        self.transfer_manager.remove_transfer(&transfer_id).await;
+
+        Ok(new_id)
+    }
+
+    /// Accept a pending incoming file and start downloading it
     ///
-    /// # Arguments
-    /// * `transfer_id` - The transfer ID to cancel
+    /// Only offers announced via `FileOfferReceived` can be accepted.
     ///
-    /// # Returns
-    /// * `Ok(())` if transfer was cancelled or not found
-    /// * `Err` if cancellation failed
-    async fn cancel_transfer(&self, transfer_id: String) -> Result<(), zbus::fdo::Error> {
+    /// # Arguments
+    /// * `transfer_id` - The transfer ID from `FileOfferReceived`
+    async fn accept_transfer(&self, transfer_id: String) -> Result<(), zbus::fdo::Error> {
         info!(
-            "DBus: CancelTransfer called for transfer_id: {}",
+            "DBus: AcceptTransfer called for transfer_id: {}",
             transfer_id
         );
 
-        let cancelled = self.transfer_manager.cancel_transfer(&transfer_id).await;
-
-        if cancelled {
-            info!("Transfer {} marked for cancellation", transfer_id);
+        if self.resolve_file_offer(&transfer_id, true).await {
             Ok(())
         } else {
-            // Transfer not found - this is not an error, it may have already completed
-            debug!(
-                "Transfer {} not found (may have already completed)",
+            Err(zbus::fdo::Error::Failed(format!(
+                "No pending file offer: {}",
                 transfer_id
-            );
+            )))
+        }
+    }
+
+    /// Reject a pending incoming file without downloading it
+    ///
+    /// # Arguments
+    /// * `transfer_id` - The transfer ID from `FileOfferReceived`
+    async fn reject_transfer(&self, transfer_id: String) -> Result<(), zbus::fdo::Error> {
+        info!(
+            "DBus: RejectTransfer called for transfer_id: {}",
+            transfer_id
+        );
+
+        if self.resolve_file_offer(&transfer_id, false).await {
             Ok(())
+        } else {
+            Err(zbus::fdo::Error::Failed(format!(
+                "No pending file offer: {}",
+                transfer_id
+            )))
         }
     }
 
@@ -1609,6 +2105,24 @@ impl CConnectInterface {
         }
     }
 
+    /// Get files recently received from devices
+    ///
+    /// Returns up to `limit` files, newest first. Files that were moved or
+    /// deleted since they were received are left out.
+    async fn get_recent_received_files(
+        &self,
+        limit: u32,
+    ) -> Result<Vec<ReceivedFileInfo>, zbus::fdo::Error> {
+        debug!("DBus: GetRecentReceivedFiles called (limit {})", limit);
+
+        let mut received_files = self.received_files.write().await;
+        Ok(received_files
+            .recent(limit as usize)
+            .into_iter()
+            .map(ReceivedFileInfo::from)
+            .collect())
+    }
+
     /// Get battery status from a device
     ///
     /// # Arguments
@@ -1652,6 +2166,35 @@ impl CConnectInterface {
         })
     }
 
+    /// Get the connection quality of a device
+    ///
+    /// Derived from a rolling average of round-trip times measured with
+    /// periodic RTT probes, independent of the phone's cellular signal.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID to query
+    ///
+    /// # Returns
+    /// Average RTT in milliseconds and quality ("excellent", "good" or "poor")
+    async fn get_connection_quality(
+        &self,
+        device_id: String,
+    ) -> Result<(u32, String), zbus::fdo::Error> {
+        debug!("DBus: GetConnectionQuality called for {}", device_id);
+
+        let device_manager = self.device_manager.read().await;
+        let device = device_manager
+            .get_device(&device_id)
+            .ok_or_else(|| zbus::fdo::Error::Failed(format!("Device not found: {}", device_id)))?;
+
+        match (device.rtt_ms(), device.connection_quality()) {
+            (Some(rtt_ms), Some(quality)) => Ok((rtt_ms, quality.as_str().to_string())),
+            _ => Err(zbus::fdo::Error::Failed(
+                "No round-trip time measured for device".to_string(),
+            )),
+        }
+    }
+
     /// Get screen share statistics from a device
     ///
     /// # Arguments
@@ -1927,6 +2470,46 @@ impl CConnectInterface {
         Ok(())
     }
 
+    /// Set how a device is shown in the manager
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID
+    /// * `accent_color` - Accent color as `#rrggbb` (empty string to clear)
+    /// * `custom_icon` - Icon name to show instead of the type icon (empty string to clear)
+    async fn set_device_appearance(
+        &self,
+        device_id: String,
+        accent_color: String,
+        custom_icon: String,
+    ) -> Result<(), zbus::fdo::Error> {
+        info!(
+            "DBus: SetDeviceAppearance called for {}: color='{}' icon='{}'",
+            device_id, accent_color, custom_icon
+        );
+
+        let is_hex_color = accent_color.len() == 7
+            && accent_color.starts_with('#')
+            && accent_color[1..].chars().all(|c| c.is_ascii_hexdigit());
+        if !accent_color.is_empty() && !is_hex_color {
+            return Err(zbus::fdo::Error::Failed(format!(
+                "Invalid accent color '{}', expected #rrggbb",
+                accent_color
+            )));
+        }
+
+        let mut registry = self.device_config_registry.write().await;
+        let config = registry.get_or_create(&device_id);
+
+        config.accent_color = (!accent_color.is_empty()).then_some(accent_color);
+        config.custom_icon = (!custom_icon.is_empty()).then_some(custom_icon);
+
+        registry.save().map_err(|e| {
+            zbus::fdo::Error::Failed(format!("Failed to save device config: {}", e))
+        })?;
+
+        Ok(())
+    }
+
     /// Set notification preference for a device
     ///
     /// # Arguments
@@ -1938,37 +2521,305 @@ impl CConnectInterface {
         preference: String,
     ) -> Result<(), zbus::fdo::Error> {
         info!(
-            "DBus: SetDeviceNotificationPreference called for {}: {}",
-            device_id, preference
+            "DBus: SetDeviceNotificationPreference called for {}: {}",
+            device_id, preference
+        );
+
+        // Parse the preference string
+        let pref = match preference.to_lowercase().as_str() {
+            "all" => crate::device_config::NotificationPreference::All,
+            "important" => crate::device_config::NotificationPreference::Important,
+            "none" => crate::device_config::NotificationPreference::None,
+            _ => {
+                return Err(zbus::fdo::Error::Failed(format!(
+                    "Invalid notification preference: {}. Must be 'all', 'important', or 'none'",
+                    preference
+                )));
+            }
+        };
+
+        let mut registry = self.device_config_registry.write().await;
+        let config = registry.get_or_create(&device_id);
+
+        config.set_notification_preference(pref);
+
+        registry.save().map_err(|e| {
+            zbus::fdo::Error::Failed(format!("Failed to save device config: {}", e))
+        })?;
+
+        info!(
+            "DBus: Notification preference for device {} set to {:?}",
+            device_id, pref
+        );
+
+        Ok(())
+    }
+
+    /// Set how files received from a device are handled
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID
+    /// * `policy` - The file receive policy ("auto_accept", "prompt", or "reject")
+    async fn set_device_file_receive_policy(
+        &self,
+        device_id: String,
+        policy: String,
+    ) -> Result<(), zbus::fdo::Error> {
+        use cosmic_connect_protocol::plugins::share::{FileReceivePolicy, SharePlugin};
+
+        info!(
+            "DBus: SetDeviceFileReceivePolicy called for {}: {}",
+            device_id, policy
+        );
+
+        let policy = match policy.to_lowercase().as_str() {
+            "auto_accept" => FileReceivePolicy::AutoAccept,
+            "prompt" => FileReceivePolicy::Prompt,
+            "reject" => FileReceivePolicy::Reject,
+            _ => {
+                return Err(zbus::fdo::Error::Failed(format!(
+                    "Invalid file receive policy: {}. Must be 'auto_accept', 'prompt', or 'reject'",
+                    policy
+                )));
+            }
+        };
+
+        {
+            let mut registry = self.device_config_registry.write().await;
+            registry
+                .get_or_create(&device_id)
+                .set_file_receive_policy(policy);
+
+            registry.save().map_err(|e| {
+                zbus::fdo::Error::Failed(format!("Failed to save device config: {}", e))
+            })?;
+        }
+
+        // Apply to the running plugin so the change takes effect immediately
+        let mut plugin_manager = self.plugin_manager.write().await;
+        if let Some(share) = plugin_manager
+            .get_device_plugin_mut(&device_id, "share")
+            .and_then(|plugin| plugin.as_any_mut().downcast_mut::<SharePlugin>())
+        {
+            share.set_receive_policy(policy);
+        }
+
+        info!(
+            "DBus: File receive policy for device {} set to {:?}",
+            device_id, policy
+        );
+
+        Ok(())
+    }
+
+    /// Set whether browsing a device's files mounts them automatically
+    ///
+    /// When disabled, browsing reports the SFTP credentials and mount command
+    /// through the PluginEvent signal instead of mounting with sshfs.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID
+    /// * `enabled` - Whether to mount automatically
+    async fn set_device_sftp_auto_mount(
+        &self,
+        device_id: String,
+        enabled: bool,
+    ) -> Result<(), zbus::fdo::Error> {
+        use cosmic_connect_protocol::plugins::networkshare::NetworkSharePlugin;
+
+        info!(
+            "DBus: SetDeviceSftpAutoMount called for {}: {}",
+            device_id, enabled
+        );
+
+        {
+            let mut registry = self.device_config_registry.write().await;
+            registry
+                .get_or_create(&device_id)
+                .set_sftp_auto_mount(enabled);
+
+            registry.save().map_err(|e| {
+                zbus::fdo::Error::Failed(format!("Failed to save device config: {}", e))
+            })?;
+        }
+
+        // Apply to the running plugin so the next browse uses it
+        let mut plugin_manager = self.plugin_manager.write().await;
+        if let Some(networkshare) = plugin_manager
+            .get_device_plugin_mut(&device_id, "networkshare")
+            .and_then(|plugin| plugin.as_any_mut().downcast_mut::<NetworkSharePlugin>())
+        {
+            networkshare.set_auto_mount(enabled);
+        }
+
+        Ok(())
+    }
+
+    /// Browse a device's files over SFTP
+    ///
+    /// Asks the device to start its SFTP server. The outcome is reported
+    /// through the PluginEvent signal of the `networkshare` plugin: a
+    /// `mounted` event with the mount point, or a `credentials` event when
+    /// auto-mount is disabled for the device.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID
+    async fn browse_device(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
+        use cosmic_connect_protocol::plugins::networkshare::NetworkSharePlugin;
+
+        info!("DBus: BrowseDevice called for {}", device_id);
+
+        let mut plugin_manager = self.plugin_manager.write().await;
+        let networkshare = plugin_manager
+            .get_device_plugin_mut(&device_id, "networkshare")
+            .and_then(|plugin| plugin.as_any_mut().downcast_mut::<NetworkSharePlugin>())
+            .ok_or_else(|| {
+                zbus::fdo::Error::Failed(format!(
+                    "File browsing is not available for device {}",
+                    device_id
+                ))
+            })?;
+
+        networkshare
+            .browse()
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to request browsing: {}", e)))
+    }
+
+    /// Get the SFTP password of a device's share, for mounting manually
+    ///
+    /// The `credentials` event reported when auto-mount is disabled leaves
+    /// the password out since signals are broadcast on the bus.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID
+    async fn get_device_sftp_password(
+        &self,
+        device_id: String,
+    ) -> Result<String, zbus::fdo::Error> {
+        use cosmic_connect_protocol::plugins::networkshare::NetworkSharePlugin;
+
+        debug!("DBus: GetDeviceSftpPassword called for {}", device_id);
+
+        let plugin_manager = self.plugin_manager.read().await;
+        let networkshare = plugin_manager
+            .get_device_plugin(&device_id, "networkshare")
+            .and_then(|plugin| plugin.as_any().downcast_ref::<NetworkSharePlugin>())
+            .ok_or_else(|| {
+                zbus::fdo::Error::Failed(format!(
+                    "File browsing is not available for device {}",
+                    device_id
+                ))
+            })?;
+
+        networkshare
+            .get_share(&device_id)
+            .await
+            .map(|info| info.password)
+            .ok_or_else(|| {
+                zbus::fdo::Error::Failed(format!("Device {} has not shared its files", device_id))
+            })
+    }
+
+    /// Unmount a device's files mounted by browsing
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID
+    ///
+    /// # Returns
+    /// `false` if the device's files were not mounted
+    async fn unmount_device(&self, device_id: String) -> Result<bool, zbus::fdo::Error> {
+        use cosmic_connect_protocol::plugins::networkshare::NetworkSharePlugin;
+
+        info!("DBus: UnmountDevice called for {}", device_id);
+
+        // fusermount runs on the Tokio runtime, zbus uses its own executor
+        let plugin_manager = self.plugin_manager.clone();
+        self.tokio_handle
+            .spawn(async move {
+                let mut plugin_manager = plugin_manager.write().await;
+                match plugin_manager
+                    .get_device_plugin_mut(&device_id, "networkshare")
+                    .and_then(|plugin| plugin.as_any_mut().downcast_mut::<NetworkSharePlugin>())
+                {
+                    Some(networkshare) => networkshare.unmount(&device_id).await,
+                    None => Ok(false),
+                }
+            })
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Tokio task failed: {}", e)))?
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to unmount: {}", e)))
+    }
+
+    /// Set which networks a device may be connected and transfer files over
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID
+    /// * `policy` - The connection policy ("any" or "wifi_only")
+    async fn set_device_connection_policy(
+        &self,
+        device_id: String,
+        policy: String,
+    ) -> Result<(), zbus::fdo::Error> {
+        use crate::network_policy::ConnectionPolicy;
+
+        info!(
+            "DBus: SetDeviceConnectionPolicy called for {}: {}",
+            device_id, policy
+        );
+
+        let policy = match policy.to_lowercase().as_str() {
+            "any" => ConnectionPolicy::Any,
+            "wifi_only" => ConnectionPolicy::WifiOnly,
+            _ => {
+                return Err(zbus::fdo::Error::Failed(format!(
+                    "Invalid connection policy: {}. Must be 'any' or 'wifi_only'",
+                    policy
+                )));
+            }
+        };
+
+        let mut registry = self.device_config_registry.write().await;
+        registry
+            .get_or_create(&device_id)
+            .set_connection_policy(policy);
+
+        registry.save().map_err(|e| {
+            zbus::fdo::Error::Failed(format!("Failed to save device config: {}", e))
+        })?;
+
+        info!(
+            "DBus: Connection policy for device {} set to {:?}",
+            device_id, policy
+        );
+
+        Ok(())
+    }
+
+    /// Enable or disable forwarding desktop notifications to a device
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID
+    /// * `enabled` - Whether local notifications are mirrored to the device
+    async fn set_device_notification_forwarding(
+        &self,
+        device_id: String,
+        enabled: bool,
+    ) -> Result<(), zbus::fdo::Error> {
+        info!(
+            "DBus: SetDeviceNotificationForwarding called for {}: {}",
+            device_id, enabled
         );
 
-        // Parse the preference string
-        let pref = match preference.to_lowercase().as_str() {
-            "all" => crate::device_config::NotificationPreference::All,
-            "important" => crate::device_config::NotificationPreference::Important,
-            "none" => crate::device_config::NotificationPreference::None,
-            _ => {
-                return Err(zbus::fdo::Error::Failed(format!(
-                    "Invalid notification preference: {}. Must be 'all', 'important', or 'none'",
-                    preference
-                )));
-            }
-        };
-
         let mut registry = self.device_config_registry.write().await;
-        let config = registry.get_or_create(&device_id);
-
-        config.set_notification_preference(pref);
+        registry
+            .get_or_create(&device_id)
+            .set_forward_notifications(enabled);
 
         registry.save().map_err(|e| {
             zbus::fdo::Error::Failed(format!("Failed to save device config: {}", e))
         })?;
 
-        info!(
-            "DBus: Notification preference for device {} set to {:?}",
-            device_id, pref
-        );
-
         Ok(())
     }
 
@@ -2573,6 +3424,40 @@ impl CConnectInterface {
         }
     }
 
+    /// Resume a screen share stopped by a session lock
+    ///
+    /// Screen shares are stopped when the session locks and are not resumed
+    /// on unlock; this restarts the share with its previous configuration.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID the share was stopped for
+    async fn resume_screen_share(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
+        info!("DBus: ResumeScreenShare called for {}", device_id);
+
+        let mut plugin_manager = self.plugin_manager.write().await;
+
+        if let Some(plugin) = plugin_manager.get_device_plugin_mut(&device_id, "screenshare") {
+            use cosmic_connect_protocol::plugins::screenshare::ScreenSharePlugin;
+
+            if let Some(screenshare) = plugin.as_any_mut().downcast_mut::<ScreenSharePlugin>() {
+                screenshare.resume_after_lock().await.map_err(|e| {
+                    zbus::fdo::Error::Failed(format!("Failed to resume screen share: {}", e))
+                })?;
+
+                info!("Screen share resumed for device {}", device_id);
+                Ok(())
+            } else {
+                Err(zbus::fdo::Error::Failed(
+                    "Plugin is not ScreenSharePlugin".to_string(),
+                ))
+            }
+        } else {
+            Err(zbus::fdo::Error::Failed(
+                "ScreenShare plugin not found".to_string(),
+            ))
+        }
+    }
+
     /// Request remote device to share their screen with us
     ///
     /// Sends a request to the remote device asking them to share their screen.
@@ -2753,6 +3638,48 @@ impl CConnectInterface {
         })
     }
 
+    /// Get per-plugin counters of a device
+    ///
+    /// Returns a map from plugin name to its counters. Counters are kept
+    /// across reconnects and are empty for devices that never connected.
+    async fn get_device_plugin_metrics(
+        &self,
+        device_id: String,
+    ) -> Result<HashMap<String, PluginMetricsInfo>, zbus::fdo::Error> {
+        debug!("DBus: GetDevicePluginMetrics called for {}", device_id);
+
+        let plugin_manager = self.plugin_manager.read().await;
+        Ok(plugin_manager
+            .device_plugin_metrics(&device_id)
+            .into_iter()
+            .map(|(plugin, snapshot)| (plugin, snapshot.into()))
+            .collect())
+    }
+
+    /// Get the plugins of a device that are loaded but not working
+    ///
+    /// Returns a map from plugin name to the reason it is degraded, e.g. a
+    /// plugin that timed out handling several packets in a row. Empty when
+    /// all plugins work or the device is not connected.
+    async fn get_degraded_plugins(
+        &self,
+        device_id: String,
+    ) -> Result<HashMap<String, String>, zbus::fdo::Error> {
+        use cosmic_connect_protocol::plugins::PluginStatus;
+
+        debug!("DBus: GetDegradedPlugins called for {}", device_id);
+
+        let plugin_manager = self.plugin_manager.read().await;
+        Ok(plugin_manager
+            .device_plugin_statuses(&device_id)
+            .into_iter()
+            .filter_map(|(plugin, status)| match status {
+                PluginStatus::Active => None,
+                PluginStatus::Degraded(reason) => Some((plugin, reason)),
+            })
+            .collect())
+    }
+
     /// Get list of available MPRIS media players
     ///
     /// Returns list of player names that can be controlled.
@@ -2876,6 +3803,36 @@ impl CConnectInterface {
         Ok(())
     }
 
+    /// Move an MPRIS player to an absolute position in the current track
+    ///
+    /// # Arguments
+    /// * `player` - Player name
+    /// * `position_microseconds` - Position from the start of the track
+    async fn mpris_set_position(
+        &self,
+        player: String,
+        position_microseconds: i64,
+    ) -> Result<(), zbus::fdo::Error> {
+        info!(
+            "DBus: MprisSetPosition called: {} - {}μs",
+            player, position_microseconds
+        );
+
+        let Some(mpris_manager) = &self.mpris_manager else {
+            return Err(zbus::fdo::Error::Failed(
+                "MPRIS manager not available".to_string(),
+            ));
+        };
+
+        mpris_manager
+            .seek_to(&player, position_microseconds)
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Set position failed: {}", e)))?;
+
+        info!("DBus: Position set for {}", player);
+        Ok(())
+    }
+
     /// Raise MPRIS player window (bring to front)
     ///
     /// # Arguments
@@ -2961,6 +3918,40 @@ impl CConnectInterface {
         Ok(())
     }
 
+    /// Enable or disable Do Not Disturb
+    ///
+    /// While enabled, mirrored phone notifications and telephony pop-ups are
+    /// not raised on this desktop. Suppressed notifications are still reported
+    /// through the PluginEvent signal when history logging is enabled.
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether Do Not Disturb should be active
+    async fn set_do_not_disturb(&self, enabled: bool) -> Result<(), zbus::fdo::Error> {
+        info!("DBus: SetDoNotDisturb called: {}", enabled);
+
+        let mut config = self.config.write().await;
+        config.do_not_disturb.enabled = enabled;
+
+        config
+            .save()
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to save config: {}", e)))?;
+
+        info!(
+            "DBus: Do Not Disturb {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+        Ok(())
+    }
+
+    /// Get Do Not Disturb state
+    ///
+    /// # Returns
+    /// `true` if Do Not Disturb is enabled
+    async fn get_do_not_disturb(&self) -> bool {
+        debug!("DBus: GetDoNotDisturb called");
+        self.config.read().await.do_not_disturb.enabled
+    }
+
     /// Get global plugin status
     ///
     /// Returns a map of plugin names to their enabled status.
@@ -3590,6 +4581,23 @@ impl CConnectInterface {
         state: &str,
     ) -> zbus::Result<()>;
 
+    /// Signal: Device status changed
+    ///
+    /// Emitted with a snapshot after a device's battery or signal changed.
+    /// Changes arriving in quick succession are coalesced into a single
+    /// signal.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID
+    /// * `snapshot` - JSON object with the latest known `batteryLevel`,
+    ///   `isCharging`, `signalStrength` and `networkType`
+    #[zbus(signal)]
+    async fn device_changed(
+        signal_emitter: &SignalEmitter<'_>,
+        device_id: &str,
+        snapshot: &str,
+    ) -> zbus::Result<()>;
+
     /// Signal: Pairing request received
     ///
     /// Emitted when a device requests to pair with us.
@@ -3703,6 +4711,46 @@ impl CConnectInterface {
         error_message: &str,
     ) -> zbus::Result<()>;
 
+    /// Signal: Incoming file awaiting a decision
+    ///
+    /// Emitted when a device sends a file while its receive policy is "prompt".
+    /// Answer with `AcceptTransfer` or `RejectTransfer`.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID
+    /// * `transfer_id` - Transfer ID to accept or reject
+    /// * `filename` - Name of the offered file
+    /// * `size` - File size in bytes
+    #[zbus(signal)]
+    async fn file_offer_received(
+        signal_emitter: &SignalEmitter<'_>,
+        device_id: &str,
+        transfer_id: &str,
+        filename: &str,
+        size: i64,
+    ) -> zbus::Result<()>;
+
+    /// Signal: Photo received
+    ///
+    /// Emitted when a photo requested with `TakePhoto` has been downloaded.
+    #[zbus(signal)]
+    async fn photo_received(
+        signal_emitter: &SignalEmitter<'_>,
+        device_id: &str,
+        path: &str,
+    ) -> zbus::Result<()>;
+
+    /// Signal: Clipboard pushed
+    ///
+    /// Emitted when the desktop clipboard was pushed to a device with
+    /// `PushClipboard`.
+    #[zbus(signal)]
+    async fn clipboard_pushed(
+        signal_emitter: &SignalEmitter<'_>,
+        device_id: &str,
+        chars: u32,
+    ) -> zbus::Result<()>;
+
     /// Signal: Screen share requested
     ///
     /// Emitted when a remote device requests to share its screen with us (incoming).
@@ -3790,6 +4838,7 @@ impl DbusServer {
     /// * `pairing_service` - Optional pairing service reference
     /// * `mpris_manager` - Optional MPRIS manager for local media player control
     /// * `config` - Daemon configuration (for settings management)
+    /// * `event_bus` - Bus file transfer events are published on
     ///
     /// # Returns
     /// DBus server instance with active connection
@@ -3804,6 +4853,7 @@ impl DbusServer {
         pending_pairing_requests: Arc<RwLock<std::collections::HashMap<String, bool>>>,
         metrics: Option<Arc<RwLock<crate::diagnostics::Metrics>>>,
         config: Arc<RwLock<crate::config::Config>>,
+        event_bus: EventBus,
     ) -> Result<Self> {
         info!("Starting DBus server on {}", SERVICE_NAME);
 
@@ -3816,6 +4866,12 @@ impl DbusServer {
         // Clone device_manager and connection_manager for the Open interface before moving to CConnectInterface
         let device_manager_for_open = device_manager.clone();
         let connection_manager_for_open = connection_manager.clone();
+        let device_config_registry_for_open = device_config_registry.clone();
+        let config_for_open = config.clone();
+
+        // Device changes are followed for the DeviceChanged signal
+        let device_events = device_manager.read().await.watch();
+        let snapshot_plugins = plugin_manager.clone();
 
         // Create interface with connection reference
         // We pass the current Tokio handle so that zbus handlers can spawn tasks on the tokio runtime
@@ -3830,6 +4886,7 @@ impl DbusServer {
             connection.clone(),
             metrics,
             config,
+            event_bus,
             Handle::current(),
         );
 
@@ -3841,8 +4898,12 @@ impl DbusServer {
             .context("Failed to serve interface")?;
 
         // Register the Open interface on a separate path
-        let open_interface =
-            OpenInterface::new(device_manager_for_open, connection_manager_for_open);
+        let open_interface = OpenInterface::new(
+            device_manager_for_open,
+            connection_manager_for_open,
+            device_config_registry_for_open,
+            config_for_open,
+        );
         connection
             .object_server()
             .at("/com/system76/CosmicConnect/Open", open_interface)
@@ -3859,6 +4920,35 @@ impl DbusServer {
 
         info!("DBus server started successfully");
 
+        let (device_updates, mut changes) = DeviceUpdateCoalescer::new(DEVICE_UPDATE_WINDOW);
+        tokio::spawn(async move {
+            let mut device_events = Box::pin(device_events);
+            while let Some(event) = device_events.next().await {
+                if !matches!(event, DeviceEvent::Removed(_)) {
+                    device_updates.record(event.device_id());
+                }
+            }
+        });
+
+        let signal_connection = connection.clone();
+        tokio::spawn(async move {
+            // Most device changes leave battery and signal alone
+            let mut sent: HashMap<String, DeviceSnapshot> = HashMap::new();
+            while let Some(device_id) = changes.recv().await {
+                let snapshot =
+                    DeviceSnapshot::read(&*snapshot_plugins.read().await, &device_id).await;
+                if sent.get(&device_id) == Some(&snapshot) {
+                    continue;
+                }
+                if let Err(e) =
+                    Self::emit_device_changed(&signal_connection, &device_id, &snapshot).await
+                {
+                    warn!("Failed to emit DeviceChanged signal: {}", e);
+                }
+                sent.insert(device_id, snapshot);
+            }
+        });
+
         Ok(Self { connection })
     }
 
@@ -3878,17 +4968,23 @@ impl DbusServer {
     }
 
     /// Emit a device_added signal
-    pub async fn emit_device_added(&self, device: &Device) -> Result<()> {
-        let device_info = DeviceInfo::from(device);
+    ///
+    /// The device information is built like `ListDevices` builds it, so a
+    /// name shared with another device is disambiguated.
+    pub async fn emit_device_added(&self, device_id: &str) -> Result<()> {
         let object_server = self.connection.object_server();
         let iface_ref = object_server
             .interface::<_, CConnectInterface>(OBJECT_PATH)
             .await?;
 
-        CConnectInterface::device_added(iface_ref.signal_emitter(), device.id(), device_info)
-            .await?;
+        let Some(device_info) = iface_ref.get().await.device_infos().await.remove(device_id) else {
+            debug!("Device {} is gone, not emitting DeviceAdded", device_id);
+            return Ok(());
+        };
 
-        debug!("Emitted DeviceAdded signal for {}", device.id());
+        CConnectInterface::device_added(iface_ref.signal_emitter(), device_id, device_info).await?;
+
+        debug!("Emitted DeviceAdded signal for {}", device_id);
         Ok(())
     }
 
@@ -3923,6 +5019,24 @@ impl DbusServer {
         Ok(())
     }
 
+    /// Emit a device_changed signal
+    async fn emit_device_changed(
+        connection: &Connection,
+        device_id: &str,
+        snapshot: &DeviceSnapshot,
+    ) -> Result<()> {
+        let snapshot = serde_json::to_string(snapshot)?;
+        let iface_ref = connection
+            .object_server()
+            .interface::<_, CConnectInterface>(OBJECT_PATH)
+            .await?;
+
+        CConnectInterface::device_changed(iface_ref.signal_emitter(), device_id, &snapshot).await?;
+
+        debug!("Emitted DeviceChanged signal for {}", device_id);
+        Ok(())
+    }
+
     /// Emit a pairing_request signal
     pub async fn emit_pairing_request(&self, device_id: &str) -> Result<()> {
         let object_server = self.connection.object_server();
@@ -3995,7 +5109,6 @@ impl DbusServer {
     }
 
     /// Emit a transfer_progress signal
-    #[allow(dead_code)]
     pub async fn emit_transfer_progress(
         &self,
         transfer_id: &str,
@@ -4029,7 +5142,6 @@ impl DbusServer {
     }
 
     /// Emit a transfer_complete signal
-    #[allow(dead_code)]
     pub async fn emit_transfer_complete(
         &self,
         transfer_id: &str,
@@ -4056,6 +5168,112 @@ impl DbusServer {
         Ok(())
     }
 
+    /// Emit a file_offer_received signal (incoming file awaits accept/reject)
+    pub async fn emit_file_offer_received(
+        &self,
+        device_id: &str,
+        transfer_id: &str,
+        filename: &str,
+        size: i64,
+    ) -> Result<()> {
+        let iface_ref = self.interface_ref().await?;
+        CConnectInterface::file_offer_received(
+            iface_ref.signal_emitter(),
+            device_id,
+            transfer_id,
+            filename,
+            size,
+        )
+        .await?;
+        debug!(
+            "Emitted FileOfferReceived signal for {} ({})",
+            filename, transfer_id
+        );
+        Ok(())
+    }
+
+    /// Remember a file downloaded from a device
+    pub async fn record_received_file(&self, file: ReceivedFile) -> Result<()> {
+        let iface_ref = self.interface_ref().await?;
+        let iface = iface_ref.get().await;
+        debug!("Recording received file {:?}", file.path);
+        iface.received_files.write().await.record(file);
+        Ok(())
+    }
+
+    /// List a file downloaded from a device as a completed transfer
+    ///
+    /// An empty `transfer_id` gets one made up like those of sent files.
+    pub async fn record_received_transfer(
+        &self,
+        transfer_id: &str,
+        device_id: &str,
+        path: &std::path::Path,
+    ) -> Result<()> {
+        let transfer_id = if transfer_id.is_empty() {
+            let timestamp_millis = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis();
+            format!("{}_{}", device_id, timestamp_millis)
+        } else {
+            transfer_id.to_string()
+        };
+        let total_bytes = tokio::fs::metadata(path)
+            .await
+            .map(|m| m.len())
+            .unwrap_or(0);
+
+        let transfer_manager = {
+            let iface_ref = self.interface_ref().await?;
+            let iface = iface_ref.get().await;
+            iface.transfer_manager.clone()
+        };
+        transfer_manager
+            .register_transfer(NewTransfer {
+                transfer_id: transfer_id.clone(),
+                direction: TransferDirection::Receiving,
+                device_id: device_id.to_string(),
+                path: path.to_path_buf(),
+                total_bytes,
+            })
+            .await;
+        transfer_manager.finish_transfer(&transfer_id, true).await;
+
+        let filename = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.emit_transfer_complete(&transfer_id, device_id, &filename, true, "")
+            .await
+    }
+
+    /// Emit a photo_received signal
+    pub async fn emit_photo_received(&self, device_id: &str, path: &std::path::Path) -> Result<()> {
+        let iface_ref = self.interface_ref().await?;
+        CConnectInterface::photo_received(
+            iface_ref.signal_emitter(),
+            device_id,
+            &path.to_string_lossy(),
+        )
+        .await?;
+        debug!("Emitted PhotoReceived signal for {}", device_id);
+        Ok(())
+    }
+
+    /// Emit a clipboard_pushed signal
+    pub async fn emit_clipboard_pushed(&self, device_id: &str, chars: usize) -> Result<()> {
+        let iface_ref = self.interface_ref().await?;
+        CConnectInterface::clipboard_pushed(
+            iface_ref.signal_emitter(),
+            device_id,
+            u32::try_from(chars).unwrap_or(u32::MAX),
+        )
+        .await?;
+        debug!("Emitted ClipboardPushed signal for {}", device_id);
+        Ok(())
+    }
+
     /// Emit a screen_share_requested signal (remote wants to share their screen with us)
     pub async fn emit_screen_share_requested(&self, device_id: &str) -> Result<()> {
         let iface_ref = self.interface_ref().await?;
@@ -4182,6 +5400,10 @@ pub struct OpenInterface {
     device_manager: Arc<RwLock<DeviceManager>>,
     /// Connection manager for sending packets
     connection_manager: Arc<RwLock<ConnectionManager>>,
+    /// Device configuration registry (for per-network transfer rules)
+    device_config_registry: Arc<RwLock<crate::device_config::DeviceConfigRegistry>>,
+    /// Daemon configuration (for transfer settings)
+    config: Arc<RwLock<crate::config::Config>>,
 }
 
 impl OpenInterface {
@@ -4189,10 +5411,14 @@ impl OpenInterface {
     pub fn new(
         device_manager: Arc<RwLock<DeviceManager>>,
         connection_manager: Arc<RwLock<ConnectionManager>>,
+        device_config_registry: Arc<RwLock<crate::device_config::DeviceConfigRegistry>>,
+        config: Arc<RwLock<crate::config::Config>>,
     ) -> Self {
         Self {
             device_manager,
             connection_manager,
+            device_config_registry,
+            config,
         }
     }
 
@@ -4320,6 +5546,7 @@ impl OpenInterface {
     /// Returns error if:
     /// - File does not exist or cannot be read
     /// - Device is not found or not connected
+    /// - File transfers to the device are not allowed on the current network
     /// - File transfer fails to initiate
     #[allow(unused_variables)]
     async fn open_file_on_phone(
@@ -4348,11 +5575,25 @@ impl OpenInterface {
         let device = self.get_target_device(device_id_opt).await?;
         debug!("Opening file on device: {}", device.name());
 
+        if !crate::network_policy::connection_allowed(
+            &self.device_config_registry,
+            &crate::network_policy::NetworkManagerSource,
+            device.id(),
+        )
+        .await
+        {
+            return Err(zbus::fdo::Error::Failed(
+                "File transfers to this device are not allowed on the current network".to_string(),
+            ));
+        }
+
         // Spawn file transfer task
         let file_path_clone = path.clone();
         let device_id_clone = device.id().to_string();
         let device_name = device.name().to_string();
         let conn_manager = self.connection_manager.clone();
+        let device_manager = self.device_manager.clone();
+        let adaptive_rate = self.config.read().await.network.adaptive_transfer_rate;
 
         tokio::spawn(async move {
             use cosmic_connect_protocol::plugins::share::{FileShareInfo, SharePlugin};
@@ -4412,7 +5653,15 @@ impl OpenInterface {
             debug!("Sent file open packet to {}", device_name);
 
             // Send the file payload
-            match server.send_file(&file_path_clone).await {
+            match send_payload(
+                server,
+                &file_path_clone,
+                &device_manager,
+                &device_id_clone,
+                adaptive_rate,
+            )
+            .await
+            {
                 Ok(_) => {
                     info!(
                         "Successfully transferred file '{}' to {} for opening",
@@ -4491,3 +5740,44 @@ mod open_interface_tests {
         assert_eq!(packet.body["url"], "https://example.com");
     }
 }
+
+#[cfg(test)]
+mod device_name_tests {
+    use super::*;
+    use cosmic_connect_protocol::{DeviceInfo as ProtocolDeviceInfo, DeviceType};
+
+    fn device_info(id: &str, name: &str) -> DeviceInfo {
+        let mut info = ProtocolDeviceInfo::new(name, DeviceType::Phone, 1716);
+        info.device_id = id.to_string();
+        DeviceInfo::from(&Device::from_discovery(info))
+    }
+
+    #[test]
+    fn test_duplicate_names_are_disambiguated() {
+        let mut infos = vec![
+            device_info("phone_1111aaaa", "Pixel"),
+            device_info("phone_2222bbbb", "Pixel"),
+            device_info("tablet_3333cccc", "Tablet"),
+        ];
+
+        disambiguate_device_names(infos.iter_mut(), |_| false);
+
+        assert_eq!(infos[0].name, "Pixel (aaaa)");
+        assert_eq!(infos[1].name, "Pixel (bbbb)");
+        assert_ne!(infos[0].name, infos[1].name);
+        assert_eq!(infos[2].name, "Tablet");
+    }
+
+    #[test]
+    fn test_nicknamed_devices_are_not_suffixed() {
+        let mut infos = vec![
+            device_info("phone_1111aaaa", "Pixel"),
+            device_info("phone_2222bbbb", "Pixel"),
+        ];
+
+        disambiguate_device_names(infos.iter_mut(), |id| id == "phone_1111aaaa");
+
+        assert_eq!(infos[0].name, "Pixel");
+        assert_eq!(infos[1].name, "Pixel");
+    }
+}
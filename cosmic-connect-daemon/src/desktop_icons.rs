@@ -21,6 +21,7 @@ fn get_device_icon(device_type: DeviceType) -> &'static str {
         DeviceType::Desktop => "computer-symbolic",
         DeviceType::Laptop => "laptop-symbolic",
         DeviceType::Tv => "video-display-symbolic",
+        DeviceType::Unknown => "network-wireless-symbolic",
     }
 }
 
@@ -87,12 +88,16 @@ Terminal=false
 Categories=Network;FileTransfer;
 Keywords=phone;device;sync;transfer;connect;
 MimeType=application/octet-stream;text/plain;image/*;video/*;audio/*;
-Actions=SendFile;Ping;Find;Browse;
+Actions=SendFile;SendClipboard;Ping;Find;Browse;
 
 [Desktop Action SendFile]
 Name=Send File
 Exec=cosmic-connect-manager --select-device {device_id} --tab share
 
+[Desktop Action SendClipboard]
+Name=Send Clipboard
+Exec=cosmic-connect-manager --select-device {device_id} --device-action clipboard
+
 [Desktop Action Ping]
 Name=Ping Device
 Exec=cosmic-connect-manager --select-device {device_id} --device-action ping
@@ -327,6 +332,28 @@ mod tests {
         assert_eq!(get_device_icon(DeviceType::Desktop), "computer-symbolic");
         assert_eq!(get_device_icon(DeviceType::Laptop), "laptop-symbolic");
         assert_eq!(get_device_icon(DeviceType::Tv), "video-display-symbolic");
+        assert_eq!(
+            get_device_icon(DeviceType::Unknown),
+            "network-wireless-symbolic"
+        );
+    }
+
+    #[test]
+    fn test_identity_device_type_icons() {
+        let cases = [
+            ("desktop", DeviceType::Desktop, "computer-symbolic"),
+            ("laptop", DeviceType::Laptop, "laptop-symbolic"),
+            ("phone", DeviceType::Phone, "phone-symbolic"),
+            ("smartphone", DeviceType::Phone, "phone-symbolic"),
+            ("tablet", DeviceType::Tablet, "tablet-symbolic"),
+            ("tv", DeviceType::Tv, "video-display-symbolic"),
+            ("car", DeviceType::Unknown, "network-wireless-symbolic"),
+        ];
+
+        for (identity_str, device_type, icon) in cases {
+            assert_eq!(DeviceType::from_identity_str(identity_str), device_type);
+            assert_eq!(get_device_icon(device_type), icon, "{}", identity_str);
+        }
     }
 
     #[test]
@@ -344,6 +371,7 @@ mod tests {
 
         // Verify actions
         assert!(content.contains("[Desktop Action SendFile]"));
+        assert!(content.contains("[Desktop Action SendClipboard]"));
         assert!(content.contains("[Desktop Action Ping]"));
         assert!(content.contains("[Desktop Action Find]"));
         assert!(content.contains("[Desktop Action Browse]"));
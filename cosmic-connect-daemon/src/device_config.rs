@@ -1,13 +1,21 @@
 //! Per-Device Configuration
 //!
 //! Manages configuration settings specific to individual devices,
-//! including per-device plugin enable/disable settings.
+//! including per-device plugin enable/disable settings and the settings
+//! plugins store for themselves.
 
+use crate::network_policy::ConnectionPolicy;
 use anyhow::{Context, Result};
+use async_trait::async_trait;
+use cosmic_connect_protocol::plugins::settings::PluginSettingsStore;
+use cosmic_connect_protocol::plugins::share::FileReceivePolicy;
+use cosmic_connect_protocol::ProtocolError;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
 /// Notification preference for a device
@@ -60,6 +68,25 @@ pub struct DeviceConfig {
     #[serde(default)]
     pub notification_preference: NotificationPreference,
 
+    /// How files received from this device are handled
+    #[serde(default)]
+    pub file_receive_policy: FileReceivePolicy,
+
+    /// Networks this device may be connected and transfer files over
+    #[serde(default)]
+    pub connection_policy: ConnectionPolicy,
+
+    /// Mount the device's files with sshfs when browsing them
+    ///
+    /// When disabled, browsing only reports the SFTP credentials and mount
+    /// command so the user can mount manually.
+    #[serde(default = "default_true")]
+    pub sftp_auto_mount: bool,
+
+    /// Forward desktop notifications to this device (opt-in)
+    #[serde(default)]
+    pub forward_notifications: bool,
+
     /// MAC address for Wake-on-LAN
     #[serde(default)]
     pub mac_address: Option<String>,
@@ -67,6 +94,18 @@ pub struct DeviceConfig {
     /// RemoteDesktop plugin-specific settings
     #[serde(default)]
     pub remotedesktop_settings: Option<RemoteDesktopSettings>,
+
+    /// Settings plugins store for this device, by plugin name
+    #[serde(default)]
+    pub plugin_config: HashMap<String, serde_json::Value>,
+
+    /// Accent color for this device in the manager, as `#rrggbb`
+    #[serde(default)]
+    pub accent_color: Option<String>,
+
+    /// Icon name shown for this device instead of its type icon
+    #[serde(default)]
+    pub custom_icon: Option<String>,
 }
 
 /// Per-device plugin configuration
@@ -173,8 +212,15 @@ impl DeviceConfig {
             auto_connect: true,
             show_notifications: true,
             notification_preference: NotificationPreference::default(),
+            file_receive_policy: FileReceivePolicy::default(),
+            connection_policy: ConnectionPolicy::default(),
+            sftp_auto_mount: true,
+            forward_notifications: false,
             mac_address: None,
             remotedesktop_settings: None,
+            plugin_config: HashMap::new(),
+            accent_color: None,
+            custom_icon: None,
         }
     }
 
@@ -328,11 +374,62 @@ impl DeviceConfig {
     pub fn set_notification_preference(&mut self, preference: NotificationPreference) {
         self.notification_preference = preference;
     }
+
+    /// Get file receive policy for this device
+    pub fn get_file_receive_policy(&self) -> FileReceivePolicy {
+        self.file_receive_policy
+    }
+
+    /// Set file receive policy for this device
+    pub fn set_file_receive_policy(&mut self, policy: FileReceivePolicy) {
+        self.file_receive_policy = policy;
+    }
+
+    /// Get the network connection policy for this device
+    pub fn get_connection_policy(&self) -> ConnectionPolicy {
+        self.connection_policy
+    }
+
+    /// Set the network connection policy for this device
+    pub fn set_connection_policy(&mut self, policy: ConnectionPolicy) {
+        self.connection_policy = policy;
+    }
+
+    /// Check whether browsing the device's files mounts them automatically
+    pub fn sftp_auto_mounts(&self) -> bool {
+        self.sftp_auto_mount
+    }
+
+    /// Enable or disable mounting the device's files when browsing them
+    pub fn set_sftp_auto_mount(&mut self, enabled: bool) {
+        self.sftp_auto_mount = enabled;
+    }
+
+    /// Check whether desktop notifications are forwarded to this device
+    pub fn forwards_notifications(&self) -> bool {
+        self.forward_notifications
+    }
+
+    /// Enable or disable forwarding desktop notifications to this device
+    pub fn set_forward_notifications(&mut self, enabled: bool) {
+        self.forward_notifications = enabled;
+    }
+
+    /// Get the settings a plugin stored for this device
+    pub fn get_plugin_config(&self, plugin_name: &str) -> Option<&serde_json::Value> {
+        self.plugin_config.get(plugin_name)
+    }
+
+    /// Set the settings of a plugin for this device
+    pub fn set_plugin_config(&mut self, plugin_name: &str, settings: serde_json::Value) {
+        self.plugin_config.insert(plugin_name.to_string(), settings);
+    }
 }
 
 /// Device configuration registry
 ///
 /// Manages per-device configurations with persistence.
+#[derive(Debug)]
 pub struct DeviceConfigRegistry {
     /// Device configurations indexed by device ID
     configs: HashMap<String, DeviceConfig>,
@@ -394,6 +491,14 @@ impl DeviceConfigRegistry {
         self.configs.get(device_id)
     }
 
+    /// Check whether desktop notifications are forwarded to a device
+    ///
+    /// Devices without a configuration have not opted in.
+    pub fn forwards_notifications(&self, device_id: &str) -> bool {
+        self.get(device_id)
+            .is_some_and(|config| config.forwards_notifications())
+    }
+
     /// Update device configuration
     #[allow(dead_code)]
     pub fn update(&mut self, device_id: &str, config: DeviceConfig) {
@@ -431,6 +536,50 @@ impl DeviceConfigRegistry {
     }
 }
 
+/// Plugin settings store backed by the device configuration registry
+///
+/// Every save is written to disk right away.
+#[derive(Debug)]
+pub struct DeviceConfigSettingsStore {
+    registry: Arc<RwLock<DeviceConfigRegistry>>,
+}
+
+impl DeviceConfigSettingsStore {
+    /// Create a store on top of the shared registry
+    pub fn new(registry: Arc<RwLock<DeviceConfigRegistry>>) -> Self {
+        Self { registry }
+    }
+}
+
+#[async_trait]
+impl PluginSettingsStore for DeviceConfigSettingsStore {
+    async fn load(&self, device_id: &str, plugin: &str) -> Option<serde_json::Value> {
+        let registry = self.registry.read().await;
+        registry
+            .get(device_id)
+            .and_then(|config| config.get_plugin_config(plugin))
+            .cloned()
+    }
+
+    async fn save(
+        &self,
+        device_id: &str,
+        plugin: &str,
+        settings: serde_json::Value,
+    ) -> cosmic_connect_protocol::Result<()> {
+        let mut registry = self.registry.write().await;
+        registry
+            .get_or_create(device_id)
+            .set_plugin_config(plugin, settings);
+        registry.save().map_err(|e| {
+            ProtocolError::Configuration(format!(
+                "Failed to save settings of plugin {} for device {}: {:#}",
+                plugin, device_id, e
+            ))
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -475,6 +624,74 @@ mod tests {
         assert_eq!(parsed.plugins.enable_battery, Some(false));
     }
 
+    #[test]
+    fn test_file_receive_policy() {
+        let mut config = DeviceConfig::new("test-device".to_string());
+        assert_eq!(
+            config.get_file_receive_policy(),
+            FileReceivePolicy::AutoAccept
+        );
+
+        config.set_file_receive_policy(FileReceivePolicy::Prompt);
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains("\"file_receive_policy\":\"prompt\""));
+
+        let parsed: DeviceConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.get_file_receive_policy(), FileReceivePolicy::Prompt);
+
+        // Configs saved before the setting existed keep auto-accepting
+        let legacy = json.replace(",\"file_receive_policy\":\"prompt\"", "");
+        let parsed: DeviceConfig = serde_json::from_str(&legacy).unwrap();
+        assert_eq!(
+            parsed.get_file_receive_policy(),
+            FileReceivePolicy::AutoAccept
+        );
+    }
+
+    #[test]
+    fn test_connection_policy() {
+        let mut config = DeviceConfig::new("test-device".to_string());
+        assert_eq!(config.get_connection_policy(), ConnectionPolicy::Any);
+
+        config.set_connection_policy(ConnectionPolicy::WifiOnly);
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains("\"connection_policy\":\"wifi_only\""));
+
+        let parsed: DeviceConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.get_connection_policy(), ConnectionPolicy::WifiOnly);
+    }
+
+    #[test]
+    fn test_forward_notifications_is_opt_in() {
+        let mut config = DeviceConfig::new("test-device".to_string());
+        assert!(!config.forwards_notifications());
+
+        config.set_forward_notifications(true);
+        let json = serde_json::to_string(&config).unwrap();
+        let parsed: DeviceConfig = serde_json::from_str(&json).unwrap();
+        assert!(parsed.forwards_notifications());
+
+        let legacy = json.replace(",\"forward_notifications\":true", "");
+        let parsed: DeviceConfig = serde_json::from_str(&legacy).unwrap();
+        assert!(!parsed.forwards_notifications());
+    }
+
+    #[test]
+    fn test_sftp_auto_mount_defaults_on() {
+        let mut config = DeviceConfig::new("test-device".to_string());
+        assert!(config.sftp_auto_mounts());
+
+        config.set_sftp_auto_mount(false);
+        let json = serde_json::to_string(&config).unwrap();
+        let parsed: DeviceConfig = serde_json::from_str(&json).unwrap();
+        assert!(!parsed.sftp_auto_mounts());
+
+        // Configs saved before the setting existed keep auto-mounting
+        let legacy = json.replace(",\"sftp_auto_mount\":false", "");
+        let parsed: DeviceConfig = serde_json::from_str(&legacy).unwrap();
+        assert!(parsed.sftp_auto_mounts());
+    }
+
     #[test]
     fn test_device_registry() {
         let temp_dir = std::env::temp_dir().join("cconnect-test");
@@ -502,4 +719,43 @@ mod tests {
         // Cleanup
         fs::remove_dir_all(&temp_dir).ok();
     }
+
+    #[tokio::test]
+    async fn test_plugin_settings_round_trip() {
+        use cosmic_connect_protocol::plugins::settings::PluginSettings;
+
+        let temp_dir = std::env::temp_dir().join("cconnect-test-plugin-settings");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let registry = Arc::new(RwLock::new(DeviceConfigRegistry::new(&temp_dir)));
+        let store = Arc::new(DeviceConfigSettingsStore::new(registry));
+
+        let mut settings = PluginSettings::load(store.clone(), "device-1", "runcommand").await;
+        settings
+            .save_config(&serde_json::json!({ "commands": { "lock": "loginctl lock-session" } }))
+            .await
+            .unwrap();
+
+        // Reload from disk
+        let mut reloaded = DeviceConfigRegistry::new(&temp_dir);
+        reloaded.load().unwrap();
+        let store = Arc::new(DeviceConfigSettingsStore::new(Arc::new(RwLock::new(
+            reloaded,
+        ))));
+
+        let settings = PluginSettings::load(store.clone(), "device-1", "runcommand").await;
+        assert_eq!(
+            settings.value(),
+            Some(&serde_json::json!({ "commands": { "lock": "loginctl lock-session" } }))
+        );
+
+        // Settings are scoped per device and per plugin
+        let other_device = PluginSettings::load(store.clone(), "device-2", "runcommand").await;
+        assert!(other_device.value().is_none());
+        let other_plugin = PluginSettings::load(store, "device-1", "clipboard").await;
+        assert!(other_plugin.value().is_none());
+
+        // Cleanup
+        fs::remove_dir_all(&temp_dir).ok();
+    }
 }
@@ -0,0 +1,188 @@
+//! Coalesced Device Updates
+//!
+//! Battery and signal of a device often change several times in quick
+//! succession, e.g. a phone reports battery and connectivity right after
+//! connecting. The daemon follows device changes through
+//! [`DeviceManager::watch`](cosmic_connect_protocol::DeviceManager::watch);
+//! instead of one DBus signal per change, changes are collected per device
+//! and a single `DeviceChanged` signal is emitted once the window after the
+//! first change has passed.
+//!
+//! The signal carries a snapshot of the latest battery and signal status
+//! reported by the device, read when the window ends. Connection state is
+//! reported by `DeviceStateChanged` only.
+
+use cosmic_connect_protocol::plugins::battery::BatteryStatus;
+use cosmic_connect_protocol::plugins::connectivity_report::{ConnectivityReportPlugin, SignalInfo};
+use cosmic_connect_protocol::PluginManager;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Time changes to a device are collected before a signal is emitted
+pub const DEVICE_UPDATE_WINDOW: Duration = Duration::from_millis(250);
+
+/// Latest known status of a device, sent as JSON with `DeviceChanged`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceSnapshot {
+    /// Battery level percentage (0-100)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub battery_level: Option<u8>,
+    /// Whether the device is charging
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_charging: Option<bool>,
+    /// Signal strength (0-4)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signal_strength: Option<i32>,
+    /// Network type (WiFi, 4G, 5G, ...)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_type: Option<String>,
+}
+
+impl DeviceSnapshot {
+    /// Status last reported to the device's battery and connectivity plugins
+    pub async fn read(plugin_manager: &PluginManager, device_id: &str) -> Self {
+        let battery = plugin_manager.get_device_battery_status(device_id);
+        let signals = match plugin_manager
+            .get_device_plugin(device_id, "connectivity_report")
+            .and_then(|plugin| plugin.as_any().downcast_ref::<ConnectivityReportPlugin>())
+        {
+            Some(plugin) => plugin.get_signal_strengths().await,
+            None => HashMap::new(),
+        };
+        Self::from_reports(battery, signals)
+    }
+
+    /// Snapshot of a battery report and the signal of each of the device's SIMs
+    fn from_reports(battery: Option<BatteryStatus>, signals: HashMap<String, SignalInfo>) -> Self {
+        let battery = battery.filter(BatteryStatus::has_battery);
+        // Report the best of the device's SIMs
+        let signal = signals
+            .into_values()
+            .max_by_key(|signal| signal.signal_strength);
+
+        Self {
+            battery_level: battery
+                .as_ref()
+                .map(|battery| battery.current_charge.clamp(0, 100) as u8),
+            is_charging: battery.map(|battery| battery.is_charging),
+            signal_strength: signal.as_ref().map(|signal| signal.signal_strength),
+            network_type: signal.map(|signal| signal.network_type),
+        }
+    }
+}
+
+/// Merges rapid device changes into one notification per window
+///
+/// IDs of changed devices are delivered on the receiver returned by
+/// [`DeviceUpdateCoalescer::new`].
+#[derive(Clone)]
+pub struct DeviceUpdateCoalescer {
+    window: Duration,
+    scheduled: Arc<Mutex<HashSet<String>>>,
+    sender: mpsc::UnboundedSender<String>,
+}
+
+impl DeviceUpdateCoalescer {
+    /// Create a coalescer collecting changes for `window`
+    pub fn new(window: Duration) -> (Self, mpsc::UnboundedReceiver<String>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let coalescer = Self {
+            window,
+            scheduled: Arc::new(Mutex::new(HashSet::new())),
+            sender,
+        };
+        (coalescer, receiver)
+    }
+
+    /// Record a change to a device
+    ///
+    /// The first change after a flush schedules the next one; later changes
+    /// in the same window are merged into it.
+    pub fn record(&self, device_id: &str) {
+        if !self.scheduled.lock().unwrap().insert(device_id.to_string()) {
+            return;
+        }
+
+        let coalescer = self.clone();
+        let device_id = device_id.to_string();
+        tokio::spawn(async move {
+            tokio::time::sleep(coalescer.window).await;
+            coalescer.scheduled.lock().unwrap().remove(&device_id);
+            let _ = coalescer.sender.send(device_id);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const WINDOW: Duration = Duration::from_millis(50);
+
+    #[tokio::test]
+    async fn test_rapid_changes_are_coalesced() {
+        let (coalescer, mut changes) = DeviceUpdateCoalescer::new(WINDOW);
+
+        for _ in 0..4 {
+            coalescer.record("phone");
+        }
+        assert_eq!(changes.recv().await.unwrap(), "phone");
+
+        // One signal for the whole burst
+        tokio::time::sleep(WINDOW * 2).await;
+        assert!(changes.try_recv().is_err());
+
+        // A later change starts a new window
+        coalescer.record("phone");
+        assert_eq!(changes.recv().await.unwrap(), "phone");
+    }
+
+    #[tokio::test]
+    async fn test_devices_are_coalesced_separately() {
+        let (coalescer, mut changes) = DeviceUpdateCoalescer::new(WINDOW);
+
+        coalescer.record("phone");
+        coalescer.record("tablet");
+
+        let mut received = vec![changes.recv().await.unwrap(), changes.recv().await.unwrap()];
+        received.sort();
+        assert_eq!(received, vec!["phone", "tablet"]);
+    }
+
+    #[test]
+    fn test_snapshot_from_reports() {
+        let signals = HashMap::from([
+            ("1".to_string(), SignalInfo::new("3G", 1)),
+            ("2".to_string(), SignalInfo::new("5G", 3)),
+        ]);
+        assert_eq!(
+            DeviceSnapshot::from_reports(Some(BatteryStatus::new(64, true, 0)), signals),
+            DeviceSnapshot {
+                battery_level: Some(64),
+                is_charging: Some(true),
+                signal_strength: Some(3),
+                network_type: Some("5G".to_string()),
+            }
+        );
+
+        assert_eq!(
+            DeviceSnapshot::from_reports(Some(BatteryStatus::no_battery()), HashMap::new()),
+            DeviceSnapshot::default()
+        );
+    }
+
+    #[test]
+    fn test_snapshot_json_omits_unknown_fields() {
+        let snapshot =
+            DeviceSnapshot::from_reports(Some(BatteryStatus::new(42, false, 0)), HashMap::new());
+        assert_eq!(
+            serde_json::to_value(&snapshot).unwrap(),
+            json!({ "batteryLevel": 42, "isCharging": false })
+        );
+    }
+}
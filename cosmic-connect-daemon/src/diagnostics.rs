@@ -91,6 +91,13 @@ pub enum DiagnosticCommand {
         lines: usize,
     },
 
+    /// Turn Do Not Disturb on or off
+    DoNotDisturb {
+        /// New state (on/off)
+        #[arg(value_parser = clap::builder::BoolishValueParser::new())]
+        enabled: bool,
+    },
+
     /// Show performance metrics
     Metrics {
         /// Update interval in seconds
@@ -204,13 +211,11 @@ impl Metrics {
     }
 
     /// Update connection count
-    #[allow(dead_code)]
     pub fn update_connections(&mut self, count: usize) {
         self.active_connections = count;
     }
 
     /// Update paired device count
-    #[allow(dead_code)]
     pub fn update_paired_devices(&mut self, count: usize) {
         self.paired_devices = count;
     }
@@ -222,7 +227,6 @@ impl Metrics {
     }
 
     /// Record plugin error
-    #[allow(dead_code)]
     pub fn record_plugin_error(&mut self) {
         self.plugin_errors += 1;
     }
@@ -0,0 +1,163 @@
+//! Do Not Disturb
+//!
+//! Daemon-wide switch that suppresses locally raised pop-ups for mirrored
+//! phone notifications and telephony events. Suppressed events can still be
+//! logged so they show up in the applet history.
+//!
+//! The switch is independent of the desktop, but when `follow_desktop` is set
+//! the COSMIC notification daemon's own Do Not Disturb state is honored too.
+
+use crate::config::DoNotDisturbConfig;
+use cosmic_connect_protocol::Packet;
+use std::path::PathBuf;
+
+/// Packet types whose local pop-ups are suppressed while Do Not Disturb is on
+const SUPPRESSIBLE_PACKET_TYPES: &[&str] = &["cconnect.notification", "cconnect.telephony"];
+
+/// What to do with an incoming event that would raise a pop-up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Disposition {
+    /// Show the pop-up as usual
+    Raise,
+    /// Do not show the pop-up, but record it in the history
+    Log,
+    /// Do not show the pop-up and do not record it
+    Suppress,
+}
+
+/// Whether Do Not Disturb is currently in effect
+///
+/// `desktop_dnd` is the desktop's own Do Not Disturb state, only consulted
+/// when `follow_desktop` is enabled.
+pub fn is_active(config: &DoNotDisturbConfig, desktop_dnd: bool) -> bool {
+    config.enabled || (config.follow_desktop && desktop_dnd)
+}
+
+/// Decide how an incoming packet should be presented
+///
+/// `desktop_dnd` is the desktop's own Do Not Disturb state, only consulted
+/// when `follow_desktop` is enabled.
+pub fn disposition(config: &DoNotDisturbConfig, desktop_dnd: bool, packet: &Packet) -> Disposition {
+    let active = is_active(config, desktop_dnd);
+    let suppressible = SUPPRESSIBLE_PACKET_TYPES
+        .iter()
+        .any(|packet_type| packet.is_type(packet_type));
+
+    match (active && suppressible, config.log_suppressed) {
+        (false, _) => Disposition::Raise,
+        (true, true) => Disposition::Log,
+        (true, false) => Disposition::Suppress,
+    }
+}
+
+/// Path of the COSMIC notifications Do Not Disturb setting
+fn desktop_dnd_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| {
+        dir.join("cosmic")
+            .join("com.system76.CosmicNotifications")
+            .join("v1")
+            .join("do_not_disturb")
+    })
+}
+
+/// Read the desktop's Do Not Disturb state
+///
+/// Returns false when the setting is missing or unreadable.
+pub fn desktop_do_not_disturb() -> bool {
+    desktop_dnd_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .map(|value| value.trim() == "true")
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn notification_packet() -> Packet {
+        Packet::new(
+            "kdeconnect.notification",
+            json!({
+                "id": "0|com.example|1",
+                "appName": "Example",
+                "title": "Hello",
+                "text": "World",
+            }),
+        )
+    }
+
+    #[test]
+    fn test_dnd_on_logs_but_does_not_raise() {
+        let config = DoNotDisturbConfig {
+            enabled: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            disposition(&config, false, &notification_packet()),
+            Disposition::Log
+        );
+    }
+
+    #[test]
+    fn test_dnd_off_raises() {
+        let config = DoNotDisturbConfig::default();
+
+        assert_eq!(
+            disposition(&config, false, &notification_packet()),
+            Disposition::Raise
+        );
+    }
+
+    #[test]
+    fn test_dnd_without_logging_suppresses() {
+        let config = DoNotDisturbConfig {
+            enabled: true,
+            log_suppressed: false,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            disposition(&config, false, &notification_packet()),
+            Disposition::Suppress
+        );
+    }
+
+    #[test]
+    fn test_dnd_covers_telephony() {
+        let config = DoNotDisturbConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let packet = Packet::new("cconnect.telephony", json!({ "event": "ringing" }));
+
+        assert_eq!(disposition(&config, false, &packet), Disposition::Log);
+    }
+
+    #[test]
+    fn test_dnd_ignores_other_packets() {
+        let config = DoNotDisturbConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let packet = Packet::new("cconnect.battery", json!({ "currentCharge": 10 }));
+
+        assert_eq!(disposition(&config, false, &packet), Disposition::Raise);
+    }
+
+    #[test]
+    fn test_desktop_dnd_followed() {
+        let mut config = DoNotDisturbConfig::default();
+        assert_eq!(
+            disposition(&config, true, &notification_packet()),
+            Disposition::Log
+        );
+
+        config.follow_desktop = false;
+        assert_eq!(
+            disposition(&config, true, &notification_packet()),
+            Disposition::Raise
+        );
+    }
+}
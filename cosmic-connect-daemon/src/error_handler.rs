@@ -168,6 +168,19 @@ impl ErrorHandler {
                 notifier.notify_certificate_error(device_name, &msg).await?;
             }
 
+            ProtocolError::TlsHandshake(err) if error.is_unknown_certificate() => {
+                notifier
+                    .notify_error_with_recovery(
+                        "Re-pairing Required",
+                        &format!(
+                            "{} rejected the secure connection ({}).\nPair the device again to reconnect.",
+                            device_name, err
+                        ),
+                        Some(("pair", "Pair Device")),
+                    )
+                    .await?;
+            }
+
             ProtocolError::ProtocolVersionMismatch(msg) => {
                 notifier.notify_protocol_mismatch(device_name, msg).await?;
             }
@@ -0,0 +1,414 @@
+//! Daemon Event Bus
+//!
+//! Bounded channel between daemon subsystems. Discovery, connection and
+//! pairing events are translated to [`DaemonEvent`]s and published here,
+//! along with plugin failures and file transfer progress. Subsystems that
+//! react to them subscribe to the kinds they care about:
+//! - the DBus signal layer emits DeviceAdded, PairingRequest,
+//!   PairingStatusChanged and TransferProgress
+//! - performance metrics count connections, paired devices and plugin errors
+//!
+//! Each subscriber has its own bounded queue. Publishing waits for room in
+//! every matching queue, so a slow subscriber slows the publisher down
+//! instead of growing memory or losing events. Subscribers whose receiver
+//! was dropped are removed on the next publish.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! let bus = EventBus::new(EVENT_BUS_CAPACITY);
+//! let mut pairing = bus.subscribe(&[EventKind::Pairing]);
+//!
+//! bus.publish(DaemonEvent::Unpaired { device_id: "phone".into() }).await;
+//! assert!(matches!(pairing.recv().await, Some(DaemonEvent::Unpaired { .. })));
+//! ```
+
+use crate::transfers::TransferDirection;
+use cosmic_connect_protocol::{
+    connection::ConnectionEvent, discovery::DiscoveryEvent, pairing::PairingEvent,
+};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tracing::debug;
+
+/// Events queued per subscriber before publishing waits
+pub const EVENT_BUS_CAPACITY: usize = 64;
+
+/// Category of a daemon event, used to filter subscriptions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    /// Devices appearing on or leaving the network
+    Device,
+    /// Connections opening and closing
+    Connection,
+    /// Pairing requests and results
+    Pairing,
+    /// File transfer progress
+    Transfer,
+    /// Plugin failures
+    Plugin,
+}
+
+/// Event shared between daemon subsystems
+#[derive(Debug, Clone, PartialEq)]
+pub enum DaemonEvent {
+    /// A device was discovered
+    DeviceDiscovered {
+        device_id: String,
+        device_name: String,
+    },
+    /// A device stopped announcing itself
+    DeviceLost { device_id: String },
+    /// A connection to a device was established
+    Connected { device_id: String },
+    /// A connection to a device was closed
+    Disconnected {
+        device_id: String,
+        /// Whether the connection is being replaced by a new one
+        reconnect: bool,
+    },
+    /// A device asked to pair, or to be verified again after a reset
+    PairingRequested {
+        device_id: String,
+        device_name: String,
+    },
+    /// The pairing status of a device changed, e.g. to "paired" or "rejected"
+    PairingStatusChanged { device_id: String, status: String },
+    /// A device was unpaired
+    Unpaired { device_id: String },
+    /// Bytes of a file transfer were sent or received
+    TransferProgress {
+        device_id: String,
+        transfer_id: String,
+        filename: String,
+        direction: TransferDirection,
+        transferred: u64,
+        total: u64,
+    },
+    /// A file transfer finished
+    TransferFinished {
+        device_id: String,
+        transfer_id: String,
+        success: bool,
+    },
+    /// A plugin failed to handle a packet
+    PluginError {
+        device_id: String,
+        packet_type: String,
+        message: String,
+    },
+}
+
+impl DaemonEvent {
+    /// Category of the event
+    pub fn kind(&self) -> EventKind {
+        match self {
+            Self::DeviceDiscovered { .. } | Self::DeviceLost { .. } => EventKind::Device,
+            Self::Connected { .. } | Self::Disconnected { .. } => EventKind::Connection,
+            Self::PairingRequested { .. }
+            | Self::PairingStatusChanged { .. }
+            | Self::Unpaired { .. } => EventKind::Pairing,
+            Self::TransferProgress { .. } | Self::TransferFinished { .. } => EventKind::Transfer,
+            Self::PluginError { .. } => EventKind::Plugin,
+        }
+    }
+
+    /// Translate a discovery event
+    ///
+    /// Repeated announcements of a known device are not published.
+    pub fn from_discovery(event: &DiscoveryEvent) -> Option<Self> {
+        match event {
+            DiscoveryEvent::DeviceDiscovered { info, .. } => Some(Self::DeviceDiscovered {
+                device_id: info.device_id.clone(),
+                device_name: info.device_name.clone(),
+            }),
+            DiscoveryEvent::DeviceTimeout { device_id } => Some(Self::DeviceLost {
+                device_id: device_id.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Translate a connection event
+    ///
+    /// Received packets are not published, plugins handle them.
+    pub fn from_connection(event: &ConnectionEvent) -> Option<Self> {
+        match event {
+            ConnectionEvent::Connected { device_id, .. } => Some(Self::Connected {
+                device_id: device_id.clone(),
+            }),
+            ConnectionEvent::Disconnected {
+                device_id,
+                reconnect,
+                ..
+            } => Some(Self::Disconnected {
+                device_id: device_id.clone(),
+                reconnect: *reconnect,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Translate a pairing event
+    ///
+    /// Timeouts and errors are reported by the error handler instead.
+    pub fn from_pairing(event: &PairingEvent) -> Option<Self> {
+        let status_changed = |device_id: &String, status: &str| Self::PairingStatusChanged {
+            device_id: device_id.clone(),
+            status: status.to_string(),
+        };
+
+        match event {
+            PairingEvent::RequestReceived {
+                device_id,
+                device_name,
+                ..
+            }
+            | PairingEvent::RePairRequired {
+                device_id,
+                device_name,
+                ..
+            } => Some(Self::PairingRequested {
+                device_id: device_id.clone(),
+                device_name: device_name.clone(),
+            }),
+            PairingEvent::PairingAccepted { device_id, .. } => {
+                Some(status_changed(device_id, "paired"))
+            }
+            PairingEvent::PairingRejected { device_id, .. } => {
+                Some(status_changed(device_id, "rejected"))
+            }
+            PairingEvent::StatusChanged { device_id, status } => {
+                Some(status_changed(device_id, status.as_str()))
+            }
+            PairingEvent::DeviceUnpaired { device_id } => Some(Self::Unpaired {
+                device_id: device_id.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DaemonEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DeviceDiscovered {
+                device_id,
+                device_name,
+            } => write!(f, "{} ({}) discovered", device_name, device_id),
+            Self::DeviceLost { device_id } => write!(f, "{} lost", device_id),
+            Self::Connected { device_id } => write!(f, "{} connected", device_id),
+            Self::Disconnected {
+                device_id,
+                reconnect,
+            } => write!(f, "{} disconnected (reconnect: {})", device_id, reconnect),
+            Self::PairingRequested {
+                device_id,
+                device_name,
+            } => write!(f, "{} ({}) requested pairing", device_name, device_id),
+            Self::PairingStatusChanged { device_id, status } => {
+                write!(f, "{} pairing status {}", device_id, status)
+            }
+            Self::Unpaired { device_id } => write!(f, "{} unpaired", device_id),
+            Self::TransferProgress {
+                device_id,
+                transfer_id,
+                filename,
+                direction,
+                transferred,
+                total,
+            } => write!(
+                f,
+                "transfer {} of {} {} {}: {}/{} bytes",
+                transfer_id,
+                filename,
+                direction.as_str(),
+                device_id,
+                transferred,
+                total
+            ),
+            Self::TransferFinished {
+                device_id,
+                transfer_id,
+                success,
+            } => write!(
+                f,
+                "transfer {} with {} finished (success: {})",
+                transfer_id, device_id, success
+            ),
+            Self::PluginError {
+                device_id,
+                packet_type,
+                message,
+            } => write!(
+                f,
+                "{} packet from {} failed: {}",
+                packet_type, device_id, message
+            ),
+        }
+    }
+}
+
+/// Queue of a subscriber and the kinds it receives
+struct Subscriber {
+    kinds: Vec<EventKind>,
+    sender: mpsc::Sender<DaemonEvent>,
+}
+
+/// Bounded event bus shared by daemon subsystems
+///
+/// Clones publish to and subscribe on the same bus.
+#[derive(Clone)]
+pub struct EventBus {
+    capacity: usize,
+    subscribers: Arc<Mutex<Vec<Subscriber>>>,
+}
+
+impl EventBus {
+    /// Create a bus queueing up to `capacity` events per subscriber
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Subscribe to events of the given kinds
+    ///
+    /// The subscriber only sees events published after subscribing.
+    pub fn subscribe(&self, kinds: &[EventKind]) -> mpsc::Receiver<DaemonEvent> {
+        let (sender, receiver) = mpsc::channel(self.capacity);
+        self.subscribers.lock().unwrap().push(Subscriber {
+            kinds: kinds.to_vec(),
+            sender,
+        });
+        receiver
+    }
+
+    /// Publish an event to every subscriber of its kind
+    ///
+    /// Waits while a subscriber's queue is full. Returns the number of
+    /// subscribers the event was delivered to.
+    pub async fn publish(&self, event: DaemonEvent) -> usize {
+        let kind = event.kind();
+        let senders: Vec<_> = self
+            .subscribers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|subscriber| subscriber.kinds.contains(&kind))
+            .map(|subscriber| subscriber.sender.clone())
+            .collect();
+        debug!("Publishing event: {}", event);
+
+        let mut delivered = 0;
+        let mut closed = false;
+        for sender in senders {
+            if sender.send(event.clone()).await.is_ok() {
+                delivered += 1;
+            } else {
+                closed = true;
+            }
+        }
+
+        if closed {
+            self.subscribers
+                .lock()
+                .unwrap()
+                .retain(|subscriber| !subscriber.sender.is_closed());
+        }
+        delivered
+    }
+
+    /// Publish the translation of a subsystem event, if it has one
+    pub async fn publish_translated(&self, event: Option<DaemonEvent>) {
+        if let Some(event) = event {
+            self.publish(event).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn paired(device_id: &str) -> DaemonEvent {
+        DaemonEvent::PairingStatusChanged {
+            device_id: device_id.to_string(),
+            status: "paired".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_events_reach_every_subscriber_of_their_kind() {
+        let bus = EventBus::new(EVENT_BUS_CAPACITY);
+        let mut first = bus.subscribe(&[EventKind::Pairing]);
+        let mut second = bus.subscribe(&[EventKind::Pairing, EventKind::Connection]);
+        let mut devices = bus.subscribe(&[EventKind::Device]);
+
+        assert_eq!(bus.publish(paired("phone")).await, 2);
+        assert_eq!(first.recv().await, Some(paired("phone")));
+        assert_eq!(second.recv().await, Some(paired("phone")));
+        assert!(devices.try_recv().is_err());
+
+        // Dropped subscribers are removed
+        drop(first);
+        assert_eq!(bus.publish(paired("tablet")).await, 1);
+        assert_eq!(bus.subscribers.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_applies_backpressure() {
+        let bus = EventBus::new(2);
+        let mut slow = bus.subscribe(&[EventKind::Pairing]);
+
+        bus.publish(paired("1")).await;
+        bus.publish(paired("2")).await;
+
+        // The queue is full, the next publish waits for the subscriber
+        let publisher = tokio::spawn({
+            let bus = bus.clone();
+            async move { bus.publish(paired("3")).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!publisher.is_finished());
+
+        assert_eq!(slow.recv().await, Some(paired("1")));
+        assert_eq!(publisher.await.unwrap(), 1);
+
+        // Nothing was dropped
+        assert_eq!(slow.recv().await, Some(paired("2")));
+        assert_eq!(slow.recv().await, Some(paired("3")));
+    }
+
+    #[test]
+    fn test_pairing_translation() {
+        let requested = PairingEvent::RePairRequired {
+            device_id: "phone".to_string(),
+            device_name: "Pixel".to_string(),
+            old_fingerprint: "aa".to_string(),
+            new_fingerprint: "bb".to_string(),
+        };
+        assert_eq!(
+            DaemonEvent::from_pairing(&requested),
+            Some(DaemonEvent::PairingRequested {
+                device_id: "phone".to_string(),
+                device_name: "Pixel".to_string(),
+            })
+        );
+
+        let accepted = PairingEvent::PairingAccepted {
+            device_id: "phone".to_string(),
+            device_name: "Pixel".to_string(),
+            certificate_fingerprint: "bb".to_string(),
+        };
+        assert_eq!(DaemonEvent::from_pairing(&accepted), Some(paired("phone")));
+
+        let timeout = PairingEvent::PairingTimeout {
+            device_id: "phone".to_string(),
+        };
+        assert_eq!(DaemonEvent::from_pairing(&timeout), None);
+    }
+}
@@ -1,38 +1,50 @@
+mod battery_monitor;
 mod config;
 mod cosmic_notifications;
 mod dbus;
 mod desktop_icons;
 mod device_config;
+mod device_updates;
 mod diagnostics;
+mod do_not_disturb;
 mod error_handler;
+mod event_bus;
+mod manager_activation;
 mod mpris_manager;
+mod network_policy;
 mod notification_image;
 mod notification_listener;
+mod received_files;
+mod transfers;
 
 use anyhow::{Context, Result};
 use clap::Parser;
 use cosmic_connect_protocol::{
-    connection::{ConnectionConfig, ConnectionEvent, ConnectionManager},
+    connection::{BackoffJitter, ConnectionConfig, ConnectionEvent, ConnectionManager},
     discovery::{
         default_additional_broadcast_addrs, DiscoveryConfig, DiscoveryEvent, DiscoveryService,
     },
+    fs_utils,
     pairing::{PairingConfig, PairingEvent, PairingService, PairingStatus},
     plugins::{
         audiostream::AudioStreamPluginFactory,
         battery::BatteryPluginFactory,
         camera::CameraPluginFactory,
         chat::ChatPluginFactory,
-        clipboard::ClipboardPluginFactory,
+        clipboard::{ClipboardEvent, ClipboardPluginFactory},
         clipboardhistory::ClipboardHistoryPluginFactory,
         connectivity_report::ConnectivityReportPluginFactory,
         contacts::{ContactsPlugin, ContactsPluginFactory},
         filesync::FileSyncPluginFactory,
         findmyphone::FindMyPhonePluginFactory,
         lock::LockPluginFactory,
+        logind_backend::LogindBackend,
         mousekeyboardshare::MouseKeyboardSharePluginFactory,
         mpris::MprisPluginFactory,
         networkshare::NetworkSharePluginFactory,
         notification::NotificationPluginFactory,
+        notifier::{ActionTarget, FallbackNotifier, LogNotifier},
+        photo::PhotoPluginFactory,
         ping::PingPluginFactory,
         power::PowerPluginFactory,
         presenter::PresenterPluginFactory,
@@ -42,13 +54,14 @@ use cosmic_connect_protocol::{
         screenshare::ScreenSharePluginFactory,
         screenshot::ScreenshotPluginFactory,
         share::SharePluginFactory,
+        system_state::SystemStatePluginFactory,
         systemmonitor::SystemMonitorPluginFactory,
-        systemvolume::SystemVolumePluginFactory,
-        telephony::TelephonyPluginFactory,
+        systemvolume::{SystemVolumePluginFactory, VOLUME_OSD_TIMEOUT},
+        telephony::{TelephonyPlugin, TelephonyPluginFactory},
         wol::WolPluginFactory,
         PluginManager,
     },
-    CertificateInfo, DeviceInfo, DeviceManager, DeviceType, Packet, TransportManager,
+    CertificateInfo, DeviceInfo, DeviceManager, DeviceType, Identity, Packet, TransportManager,
     TransportManagerConfig, TransportManagerEvent,
 };
 use dbus::DbusServer;
@@ -56,7 +69,10 @@ use diagnostics::{BuildInfo, Cli, DiagnosticCommand, Metrics};
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 
 use base64::{engine::general_purpose, Engine as _};
+use cosmic_connect_protocol::plugins::networkshare::SftpEvent;
+use cosmic_connect_protocol::plugins::photo::PhotoEvent;
 use cosmic_connect_protocol::plugins::remotedesktop::RemoteDesktopPluginFactory;
+use cosmic_connect_protocol::plugins::share::FileReceivePolicy;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
@@ -66,6 +82,8 @@ use config::Config;
 
 use error_handler::ErrorHandler;
 
+use event_bus::{DaemonEvent, EventBus, EventKind};
+
 use notification_listener::{CapturedNotification, NotificationListener};
 
 /// Main daemon state
@@ -120,6 +138,9 @@ struct Daemon {
     /// Map of notification IDs to device IDs for pairing notifications
     pairing_notifications: Arc<RwLock<std::collections::HashMap<u32, String>>>,
 
+    /// Actions of notifications raised by plugins
+    plugin_notification_actions: cosmic_notifications::PluginNotificationActions,
+
     /// Map of device IDs to pending pairing request status
     pending_pairing_requests: Arc<RwLock<std::collections::HashMap<String, bool>>>,
 
@@ -135,12 +156,21 @@ struct Daemon {
     /// Packet receiver for plugins (wrapped in Mutex to allow extraction)
     packet_receiver: Arc<tokio::sync::Mutex<Option<Receiver<(String, Packet)>>>>,
 
-    /// Track connection attempts for exponential backoff (device_id -> (last_attempt, failure_count))
+    /// Track connection attempts for exponential backoff (device_id -> (next_attempt, failure_count))
     connection_attempts: Arc<RwLock<std::collections::HashMap<String, (std::time::Instant, u32)>>>,
 
+    /// Source of the active network type for per-device connection policies
+    network_source: Arc<dyn network_policy::NetworkTypeSource>,
+
+    /// Low battery alert state for connected devices
+    battery_monitor: Arc<RwLock<battery_monitor::BatteryMonitor>>,
+
     /// Receiver for captured notifications from the notification listener
     notification_receiver:
         Arc<tokio::sync::Mutex<Option<tokio::sync::mpsc::UnboundedReceiver<CapturedNotification>>>>,
+
+    /// Events shared between subsystems
+    event_bus: EventBus,
 }
 
 impl Daemon {
@@ -157,9 +187,13 @@ impl Daemon {
             warn!("Failed to initialize error handler: {}", e);
         }
 
+        // Load or generate the device identity
+        let identity = config.load_identity()?;
+        info!("Using device ID: {}", identity.device_id());
+
         // Load or generate certificate
-        let certificate =
-            Self::load_or_generate_certificate(&config).context("Failed to load certificate")?;
+        let certificate = Self::load_or_generate_certificate(&config, &identity)
+            .context("Failed to load certificate")?;
         // Create device info
         let device_type = match config.device.device_type.as_str() {
             "laptop" => DeviceType::Laptop,
@@ -169,44 +203,28 @@ impl Daemon {
             _ => DeviceType::Desktop,
         };
 
-        let device_info = if let Some(device_id) = config.load_device_id() {
-            // Use saved or configured device ID
-            info!("Using existing device ID: {}", device_id);
-            DeviceInfo::with_id(
-                &device_id,
-                &config.device.name,
-                device_type,
-                config.network.discovery_port,
-            )
-        } else {
-            // Generate new device ID and save it
-            let info = DeviceInfo::new(
-                &config.device.name,
-                device_type,
-                config.network.discovery_port,
-            );
-            info!("Generated new device ID: {}", info.device_id);
-            if let Err(e) = config.save_device_id(&info.device_id) {
-                warn!(
-                    "Failed to save device ID: {}. ID will change on next restart.",
-                    e
-                );
-            }
-            info
-        };
+        let device_info = DeviceInfo::with_id(
+            identity.device_id(),
+            &config.device.name,
+            device_type,
+            config.network.discovery_port,
+        );
 
         // Create plugin manager
-        let plugin_manager = Arc::new(RwLock::new(PluginManager::new()));
+        let mut plugin_manager = PluginManager::new();
+        plugin_manager.set_handler_timeout(Duration::from_secs(config.plugins.handler_timeout));
+        let plugin_manager = Arc::new(RwLock::new(plugin_manager));
 
         // Create packet channel for plugins
         let (packet_sender, packet_receiver) = channel(100);
         let packet_receiver = Arc::new(tokio::sync::Mutex::new(Some(packet_receiver)));
 
-        // Create device manager
-        let device_manager = Arc::new(RwLock::new(
-            DeviceManager::new(config.device_registry_path())
-                .context("Failed to create device manager")?,
-        ));
+        // Create device manager, discovery beacons keep devices reachable
+        // until they time out
+        let mut device_manager = DeviceManager::new(config.device_registry_path())
+            .context("Failed to create device manager")?;
+        device_manager.set_beacon_ttl(Duration::from_secs(config.network.device_timeout));
+        let device_manager = Arc::new(RwLock::new(device_manager));
 
         // Create and load device configuration registry
         let mut device_config_registry =
@@ -229,6 +247,7 @@ impl Daemon {
                 .context("Invalid listen address")?,
             keep_alive_interval: Duration::from_secs(30),
             connection_timeout: Duration::from_secs(60),
+            reconnect_jitter: BackoffJitter::Full,
         };
 
         // Create connection manager (not started yet)
@@ -274,7 +293,11 @@ impl Daemon {
         let cosmic_notifier = match cosmic_notifications::CosmicNotifier::new().await {
             Ok(notifier) => {
                 info!("COSMIC notifications client initialized");
-                Some(Arc::new(notifier))
+                Some(Arc::new(
+                    notifier
+                        .with_grouping(config.mirrored_notifications.grouping)
+                        .with_max_body_chars(config.plugins.notification_max_chars),
+                ))
             }
             Err(e) => {
                 warn!("Failed to initialize COSMIC notifications: {}", e);
@@ -320,39 +343,53 @@ impl Daemon {
             dbus_server: None,
             mpris_manager,
             pairing_notifications: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            plugin_notification_actions: cosmic_notifications::PluginNotificationActions::default(),
             pending_pairing_requests: Arc::new(RwLock::new(std::collections::HashMap::new())),
             metrics: None,
             dump_packets: false,
             packet_sender,
             packet_receiver,
             connection_attempts: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            network_source: Arc::new(network_policy::NetworkManagerSource),
+            battery_monitor: Arc::new(RwLock::new(battery_monitor::BatteryMonitor::new())),
             notification_receiver: Arc::new(tokio::sync::Mutex::new(None)),
+            event_bus: EventBus::new(event_bus::EVENT_BUS_CAPACITY),
         })
     }
 
     /// Load or generate device certificate
-    fn load_or_generate_certificate(config: &Config) -> Result<CertificateInfo> {
+    fn load_or_generate_certificate(
+        config: &Config,
+        identity: &Identity,
+    ) -> Result<CertificateInfo> {
         let cert_path = config.certificate_path();
         let key_path = config.private_key_path();
 
         if cert_path.exists() && key_path.exists() {
             info!("Loading existing certificate from {:?}", cert_path);
+            for path in [&key_path, &cert_path] {
+                if fs_utils::restrict_to_owner(path)
+                    .context("Failed to restrict certificate permissions")?
+                {
+                    warn!(
+                        "{:?} was readable by other users; restricted to owner",
+                        path
+                    );
+                }
+            }
             CertificateInfo::load_from_files(&cert_path, &key_path)
                 .context("Failed to load certificate")
         } else {
             info!("Generating new device certificate");
-            let device_id = config
-                .device
-                .device_id
-                .as_deref()
-                .unwrap_or("cconnect-device");
-
-            let cert =
-                CertificateInfo::generate(device_id).context("Failed to generate certificate")?;
+            let cert = CertificateInfo::generate(identity.device_id())
+                .context("Failed to generate certificate")?;
 
             // Save certificate
             cert.save_to_files(&cert_path, &key_path)
                 .context("Failed to save certificate")?;
+            fs_utils::restrict_to_owner(&key_path)
+                .and_then(|_| fs_utils::restrict_to_owner(&cert_path))
+                .context("Failed to restrict certificate permissions")?;
 
             info!("Certificate saved to {:?}", cert_path);
             Ok(cert)
@@ -364,6 +401,31 @@ impl Daemon {
         let mut manager = self.plugin_manager.write().await;
         let config = self.config.read().await;
 
+        // Plugins raise their own notifications through the COSMIC client,
+        // falling back to the log when no notification server is running
+        let log_notifier = Arc::new(LogNotifier::new());
+        if let Some(notifier) = &self.cosmic_notifier {
+            if !notifier.is_available().await {
+                warn!("No notification server running - plugin notifications will be logged");
+            }
+            let plugin_notifier = Arc::new(cosmic_notifications::PluginNotifier::new(
+                notifier.clone(),
+                self.config.clone(),
+                self.plugin_notification_actions.clone(),
+            ));
+            manager.set_notifier(Arc::new(FallbackNotifier::new(
+                plugin_notifier,
+                log_notifier,
+            )));
+        } else {
+            manager.set_notifier(log_notifier);
+        }
+
+        // Plugin settings are persisted with the per-device configuration
+        manager.set_settings_store(Arc::new(device_config::DeviceConfigSettingsStore::new(
+            self.device_config_registry.clone(),
+        )));
+
         info!("Registering plugin factories...");
 
         // Register enabled plugin factories
@@ -426,7 +488,9 @@ impl Daemon {
         if config.plugins.enable_findmyphone {
             info!("Registering Find My Phone plugin factory");
             manager
-                .register_factory(Arc::new(FindMyPhonePluginFactory))
+                .register_factory(Arc::new(FindMyPhonePluginFactory::with_config(
+                    config.plugins.ring_config(),
+                )))
                 .context("Failed to register Find My Phone plugin factory")?;
         }
 
@@ -493,6 +557,13 @@ impl Daemon {
                 .context("Failed to register Power plugin factory")?;
         }
 
+        if config.plugins.enable_system_state {
+            info!("Registering SystemState plugin factory");
+            manager
+                .register_factory(Arc::new(SystemStatePluginFactory))
+                .context("Failed to register SystemState plugin factory")?;
+        }
+
         if config.plugins.enable_clipboardhistory {
             info!("Registering ClipboardHistory plugin factory");
             manager
@@ -564,6 +635,13 @@ impl Daemon {
                 .context("Failed to register Camera plugin factory")?;
         }
 
+        if config.plugins.enable_photo {
+            info!("Registering Photo plugin factory");
+            manager
+                .register_factory(Arc::new(PhotoPluginFactory))
+                .context("Failed to register Photo plugin factory")?;
+        }
+
         info!(
             "All plugin factories registered ({} total)",
             manager.factory_count()
@@ -578,15 +656,12 @@ impl Daemon {
 
         let config = self.config.read().await;
 
-        // Get capabilities from plugin manager
-        let manager = self.plugin_manager.read().await;
-        let incoming = manager.get_all_incoming_capabilities();
-        let outgoing = manager.get_all_outgoing_capabilities();
-        drop(manager);
-
-        // Update self.device_info with capabilities
-        self.device_info.incoming_capabilities = incoming;
-        self.device_info.outgoing_capabilities = outgoing;
+        // Advertise the registered plugins' capabilities
+        self.plugin_manager
+            .read()
+            .await
+            .advertise_capabilities(&mut self.device_info)
+            .await;
 
         // Update connection manager with new capabilities
         self.connection_manager
@@ -600,12 +675,16 @@ impl Daemon {
             device_timeout: Duration::from_secs(config.network.device_timeout),
             enable_timeout_check: true,
             additional_broadcast_addrs: default_additional_broadcast_addrs(),
+            ignore_loopback: config.network.ignore_loopback_discovery,
+            interfaces: config.network.discovery_interfaces.clone(),
         };
         drop(config);
 
-        // Create discovery service
+        // Create discovery service, rebuilding the broadcast capabilities
+        // from the plugin manager so they track plugin changes
         let mut discovery_service = DiscoveryService::new(device_info, discovery_config)
-            .context("Failed to create discovery service")?;
+            .context("Failed to create discovery service")?
+            .with_plugin_manager(self.plugin_manager.clone());
 
         // Subscribe to discovery events
         let mut event_rx = discovery_service.subscribe().await;
@@ -630,8 +709,12 @@ impl Daemon {
         let error_handler = self.error_handler.clone();
         let connection_manager = self.connection_manager.clone();
         let connection_attempts = self.connection_attempts.clone();
+        let device_config_registry = self.device_config_registry.clone();
+        let network_source = self.network_source.clone();
+        let event_bus = self.event_bus.clone();
         tokio::spawn(async move {
             while let Some(event) = event_rx.recv().await {
+                let bus_event = DaemonEvent::from_discovery(&event);
                 if let Err(e) = Self::handle_discovery_event(
                     event,
                     &device_manager,
@@ -639,11 +722,14 @@ impl Daemon {
                     &error_handler,
                     &connection_manager,
                     &connection_attempts,
+                    &device_config_registry,
+                    network_source.as_ref(),
                 )
                 .await
                 {
                     error!("Error handling discovery event: {}", e);
                 }
+                event_bus.publish_translated(bus_event).await;
             }
             info!("Discovery event handler stopped");
         });
@@ -692,7 +778,6 @@ impl Daemon {
 
         // Spawn task to handle pairing events
         let device_manager = self.device_manager.clone();
-        let dbus_server = self.dbus_server.clone();
         let cosmic_notifier = self.cosmic_notifier.clone();
         let pairing_notifications = self.pairing_notifications.clone();
         let pending_pairing_requests = self.pending_pairing_requests.clone();
@@ -700,12 +785,15 @@ impl Daemon {
         let plugin_manager = self.plugin_manager.clone();
         let packet_sender = self.packet_sender.clone();
         let tls_config = self.tls_config.clone();
+        let device_config_registry = self.device_config_registry.clone();
+        let battery_monitor = self.battery_monitor.clone();
+        let event_bus = self.event_bus.clone();
         tokio::spawn(async move {
             while let Some(event) = event_rx.recv().await {
+                let bus_event = DaemonEvent::from_pairing(&event);
                 if let Err(e) = Self::handle_pairing_event(
                     event,
                     &device_manager,
-                    &dbus_server,
                     &cosmic_notifier,
                     &pairing_notifications,
                     &pending_pairing_requests,
@@ -713,11 +801,14 @@ impl Daemon {
                     &plugin_manager,
                     &packet_sender,
                     &tls_config,
+                    &device_config_registry,
+                    &battery_monitor,
                 )
                 .await
                 {
                     error!("Error handling pairing event: {}", e);
                 }
+                event_bus.publish_translated(bus_event).await;
             }
             info!("Pairing event handler stopped");
         });
@@ -732,7 +823,6 @@ impl Daemon {
     async fn handle_pairing_event(
         event: PairingEvent,
         device_manager: &Arc<RwLock<DeviceManager>>,
-        dbus_server: &Option<Arc<DbusServer>>,
         cosmic_notifier: &Option<Arc<cosmic_notifications::CosmicNotifier>>,
         pairing_notifications: &Arc<RwLock<std::collections::HashMap<u32, String>>>,
         pending_pairing_requests: &Arc<RwLock<std::collections::HashMap<String, bool>>>,
@@ -740,6 +830,8 @@ impl Daemon {
         plugin_manager: &Arc<RwLock<PluginManager>>,
         packet_sender: &Sender<(String, Packet)>,
         tls_config: &Arc<cosmic_connect_protocol::TlsConfig>,
+        device_config_registry: &Arc<RwLock<device_config::DeviceConfigRegistry>>,
+        battery_monitor: &Arc<RwLock<battery_monitor::BatteryMonitor>>,
     ) -> Result<()> {
         match event {
             PairingEvent::RequestSent {
@@ -769,13 +861,6 @@ impl Daemon {
                     .insert(device_id.clone(), true);
                 info!("Added {} to pending pairing requests", device_id);
 
-                // Emit DBus signal for pairing request
-                if let Some(dbus) = dbus_server {
-                    if let Err(e) = dbus.emit_pairing_request(&device_id).await {
-                        warn!("Failed to emit PairingRequest signal: {}", e);
-                    }
-                }
-
                 // Show COSMIC notification for pairing request
                 if let Some(notifier) = cosmic_notifier {
                     info!("Sending pairing request notification for {}", device_name);
@@ -797,6 +882,38 @@ impl Daemon {
                     warn!("COSMIC notifier not available for pairing request");
                 }
             }
+            PairingEvent::RePairRequired {
+                device_id,
+                device_name,
+                old_fingerprint,
+                new_fingerprint,
+            } => {
+                warn!(
+                    "{} ({}) presented a new certificate - fingerprint: {} (was {})",
+                    device_name, device_id, new_fingerprint, old_fingerprint
+                );
+
+                // Re-verifying goes through the usual pairing request
+                pending_pairing_requests
+                    .write()
+                    .await
+                    .insert(device_id.clone(), true);
+
+                if let Some(notifier) = cosmic_notifier {
+                    match notifier
+                        .notify_repair_required(&device_name, &new_fingerprint)
+                        .await
+                    {
+                        Ok(notification_id) => {
+                            let mut notifications = pairing_notifications.write().await;
+                            notifications.insert(notification_id, device_id.clone());
+                        }
+                        Err(e) => {
+                            warn!("Failed to send re-pairing notification: {}", e);
+                        }
+                    }
+                }
+            }
             PairingEvent::PairingAccepted {
                 device_id,
                 device_name,
@@ -851,6 +968,33 @@ impl Daemon {
                                         "Set TLS config on SharePlugin for device {}",
                                         device_id
                                     );
+
+                                    if let Some(device_config) =
+                                        device_config_registry.read().await.get(&device_id)
+                                    {
+                                        share_plugin.set_receive_policy(
+                                            device_config.get_file_receive_policy(),
+                                        );
+                                    }
+                                }
+                            }
+
+                            Self::apply_sftp_auto_mount(
+                                &mut plug_manager,
+                                &device_id,
+                                device_config_registry,
+                            )
+                            .await;
+
+                            // Photos are downloaded over TLS like shared files
+                            if let Some(plugin) =
+                                plug_manager.get_device_plugin_mut(&device_id, "photo")
+                            {
+                                use cosmic_connect_protocol::plugins::photo::PhotoPlugin;
+                                if let Some(photo_plugin) =
+                                    plugin.as_any_mut().downcast_mut::<PhotoPlugin>()
+                                {
+                                    photo_plugin.set_tls_config(tls_config.clone());
                                 }
                             }
                         }
@@ -873,12 +1017,6 @@ impl Daemon {
                         }
                     }
                 }
-
-                if let Some(dbus) = dbus_server {
-                    if let Err(e) = dbus.emit_pairing_status_changed(&device_id, "paired").await {
-                        warn!("Failed to emit PairingStatusChanged signal: {}", e);
-                    }
-                }
             }
             PairingEvent::PairingRejected { device_id, reason } => {
                 info!(
@@ -886,15 +1024,6 @@ impl Daemon {
                     device_id, reason
                 );
                 Self::clear_pending_pairing_request(pending_pairing_requests, &device_id).await;
-
-                if let Some(dbus) = dbus_server {
-                    if let Err(e) = dbus
-                        .emit_pairing_status_changed(&device_id, "rejected")
-                        .await
-                    {
-                        warn!("Failed to emit PairingStatusChanged signal: {}", e);
-                    }
-                }
             }
             PairingEvent::StatusChanged { device_id, status } => {
                 debug!("Pairing status changed for {}: {:?}", device_id, status);
@@ -920,6 +1049,14 @@ impl Daemon {
                 } else {
                     info!("Removed desktop icon for device {}", device_id);
                 }
+
+                // Drop its low battery warning
+                let notification_id = battery_monitor.write().await.remove_device(&device_id);
+                if let (Some(id), Some(notifier)) = (notification_id, cosmic_notifier) {
+                    if let Err(e) = notifier.close(id).await {
+                        debug!("Failed to close low battery notification: {}", e);
+                    }
+                }
             }
             PairingEvent::PairingTimeout { device_id } => {
                 warn!("Pairing request timed out for device {}", device_id);
@@ -941,6 +1078,24 @@ impl Daemon {
         Ok(())
     }
 
+    /// Browsing mounts the device's files unless mounted manually
+    async fn apply_sftp_auto_mount(
+        plug_manager: &mut PluginManager,
+        device_id: &str,
+        device_config_registry: &Arc<RwLock<device_config::DeviceConfigRegistry>>,
+    ) {
+        use cosmic_connect_protocol::plugins::networkshare::NetworkSharePlugin;
+
+        if let (Some(networkshare), Some(device_config)) = (
+            plug_manager
+                .get_device_plugin_mut(device_id, "networkshare")
+                .and_then(|plugin| plugin.as_any_mut().downcast_mut::<NetworkSharePlugin>()),
+            device_config_registry.read().await.get(device_id),
+        ) {
+            networkshare.set_auto_mount(device_config.sftp_auto_mounts());
+        }
+    }
+
     /// Clear a pending pairing request from the tracking map
     async fn clear_pending_pairing_request(
         pending_pairing_requests: &Arc<RwLock<std::collections::HashMap<String, bool>>>,
@@ -989,6 +1144,8 @@ impl Daemon {
             let config = self.config.clone();
             let error_handler = Some(self.error_handler.clone());
             let tls_config = self.tls_config.clone();
+            let battery_monitor = self.battery_monitor.clone();
+            let event_bus = self.event_bus.clone();
             tokio::spawn(async move {
                 while let Some(event) = event_rx.recv().await {
                     // Convert TransportManagerEvent to ConnectionEvent
@@ -1048,6 +1205,7 @@ impl Daemon {
                     };
 
                     // Handle the converted event
+                    let bus_event = DaemonEvent::from_connection(&connection_event);
                     if let Err(e) = Self::handle_connection_event(
                         connection_event,
                         &device_manager,
@@ -1063,11 +1221,14 @@ impl Daemon {
                         &config,
                         &error_handler,
                         &tls_config,
+                        &battery_monitor,
+                        &event_bus,
                     )
                     .await
                     {
                         error!("Error handling connection event: {}", e);
                     }
+                    event_bus.publish_translated(bus_event).await;
                 }
                 info!("Transport event handler stopped");
             });
@@ -1105,8 +1266,11 @@ impl Daemon {
             let config = self.config.clone();
             let error_handler = Some(self.error_handler.clone());
             let tls_config = self.tls_config.clone();
+            let battery_monitor = self.battery_monitor.clone();
+            let event_bus = self.event_bus.clone();
             tokio::spawn(async move {
                 while let Some(event) = event_rx.recv().await {
+                    let bus_event = DaemonEvent::from_connection(&event);
                     if let Err(e) = Self::handle_connection_event(
                         event,
                         &device_manager,
@@ -1122,11 +1286,14 @@ impl Daemon {
                         &config,
                         &error_handler,
                         &tls_config,
+                        &battery_monitor,
+                        &event_bus,
                     )
                     .await
                     {
                         error!("Error handling connection event: {}", e);
                     }
+                    event_bus.publish_translated(bus_event).await;
                 }
                 info!("Connection event handler stopped");
             });
@@ -1151,17 +1318,73 @@ impl Daemon {
             self.pending_pairing_requests.clone(),
             self.metrics.clone(),
             self.config.clone(),
+            self.event_bus.clone(),
         )
         .await
         .context("Failed to start DBus server")?;
 
         info!("DBus server started on {}", dbus::SERVICE_NAME);
 
-        self.dbus_server = Some(Arc::new(dbus_server));
+        let dbus_server = Arc::new(dbus_server);
+        self.dbus_server = Some(dbus_server.clone());
+        self.start_dbus_signals(dbus_server);
 
         Ok(())
     }
 
+    /// Emit DBus signals for device, pairing and transfer events from the event bus
+    fn start_dbus_signals(&self, dbus: Arc<DbusServer>) {
+        let mut events =
+            self.event_bus
+                .subscribe(&[EventKind::Device, EventKind::Pairing, EventKind::Transfer]);
+        tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                match event {
+                    DaemonEvent::DeviceDiscovered { device_id, .. } => {
+                        if let Err(e) = dbus.emit_device_added(&device_id).await {
+                            warn!("Failed to emit DeviceAdded signal: {}", e);
+                        }
+                    }
+                    DaemonEvent::PairingRequested { device_id, .. } => {
+                        if let Err(e) = dbus.emit_pairing_request(&device_id).await {
+                            warn!("Failed to emit PairingRequest signal: {}", e);
+                        }
+                    }
+                    DaemonEvent::PairingStatusChanged { device_id, status } => {
+                        if let Err(e) = dbus.emit_pairing_status_changed(&device_id, &status).await
+                        {
+                            warn!("Failed to emit PairingStatusChanged signal: {}", e);
+                        }
+                    }
+                    DaemonEvent::TransferProgress {
+                        device_id,
+                        transfer_id,
+                        filename,
+                        direction,
+                        transferred,
+                        total,
+                    } => {
+                        if let Err(e) = dbus
+                            .emit_transfer_progress(
+                                &transfer_id,
+                                &device_id,
+                                &filename,
+                                transferred,
+                                total,
+                                direction.as_str(),
+                            )
+                            .await
+                        {
+                            warn!("Failed to emit TransferProgress signal: {}", e);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            debug!("DBus signal forwarding stopped");
+        });
+    }
+
     /// Start MPRIS player monitoring
     async fn start_mpris_monitoring(&self) -> Result<()> {
         let Some(mpris_manager) = &self.mpris_manager else {
@@ -1170,6 +1393,11 @@ impl Daemon {
 
         info!("Starting MPRIS player discovery and monitoring...");
 
+        // Watch before listing so players started meanwhile are not missed
+        if let Err(e) = mpris_manager.watch_players().await {
+            warn!("Failed to watch for MPRIS players: {}", e);
+        }
+
         let players = match mpris_manager.discover_players().await {
             Ok(players) => players,
             Err(e) => {
@@ -1188,6 +1416,136 @@ impl Daemon {
             }
         }
 
+        self.start_mpris_push(mpris_manager);
+
+        Ok(())
+    }
+
+    /// Push local playback changes to connected devices
+    ///
+    /// Keeps the phone's media widget live without it polling with
+    /// `requestNowPlaying`.
+    fn start_mpris_push(&self, mpris_manager: &Arc<mpris_manager::MprisManager>) {
+        let mut changes = mpris_manager.subscribe();
+        let device_manager = self.device_manager.clone();
+        let plugin_manager = self.plugin_manager.clone();
+        let connection_manager = self.connection_manager.clone();
+
+        tokio::spawn(async move {
+            use cosmic_connect_protocol::plugins::mpris::MprisPlugin;
+            use tokio::sync::broadcast::error::RecvError;
+
+            loop {
+                let state = match changes.recv().await {
+                    Ok(state) => state,
+                    Err(RecvError::Lagged(skipped)) => {
+                        debug!("Skipped {} MPRIS changes", skipped);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+
+                let connected_devices: Vec<String> = device_manager
+                    .read()
+                    .await
+                    .devices()
+                    .filter(|d| d.is_connected())
+                    .map(|d| d.id().to_string())
+                    .collect();
+                if connected_devices.is_empty() {
+                    continue;
+                }
+
+                let (status, metadata) = Self::convert_player_state(&state);
+                let mut packets = Vec::new();
+                {
+                    let plug_manager = plugin_manager.read().await;
+                    for device_id in connected_devices {
+                        if let Some(mpris_plugin) = plug_manager
+                            .get_device_plugin(&device_id, "mpris")
+                            .and_then(|p| p.as_any().downcast_ref::<MprisPlugin>())
+                        {
+                            let packet = mpris_plugin.create_status_packet(
+                                state.name.clone(),
+                                status.clone(),
+                                metadata.clone(),
+                            );
+                            packets.push((device_id, packet));
+                        }
+                    }
+                }
+
+                let conn_manager = connection_manager.read().await;
+                for (device_id, packet) in packets {
+                    if let Err(e) = conn_manager.send_packet(&device_id, &packet).await {
+                        warn!("Failed to push MPRIS state to {}: {}", device_id, e);
+                    }
+                }
+                debug!("Pushed MPRIS state of {}", state.name);
+            }
+        });
+    }
+
+    /// Forget unpaired devices not seen for a while, now and every few hours
+    async fn start_stale_device_pruning(&self) -> Result<()> {
+        let Some(max_age) = self.config.read().await.network.stale_device_age() else {
+            info!("Stale device pruning disabled");
+            return Ok(());
+        };
+
+        info!("Pruning unpaired devices not seen for {:?}", max_age);
+        let device_manager = self.device_manager.clone();
+        tokio::spawn(async move {
+            // The first tick completes immediately, pruning at startup
+            let mut interval = tokio::time::interval(Duration::from_secs(6 * 60 * 60));
+            loop {
+                interval.tick().await;
+                match device_manager.write().await.remove_stale_devices(max_age) {
+                    Ok(removed) if !removed.is_empty() => {
+                        info!("Removed {} stale devices", removed.len());
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Failed to remove stale devices: {}", e),
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Stop screen shares when the session locks
+    async fn start_session_lock_monitor(&self) -> Result<()> {
+        if !self.config.read().await.plugins.enable_screenshare {
+            return Ok(());
+        }
+
+        let mut logind = LogindBackend::new();
+        let mut events = match logind.lock_events().await {
+            Ok(events) => events,
+            Err(e) => {
+                warn!(
+                    "Failed to watch session lock, screen shares continue while locked: {}",
+                    e
+                );
+                return Ok(());
+            }
+        };
+
+        info!("Watching session lock to stop screen shares");
+        let plugin_manager = self.plugin_manager.clone();
+        tokio::spawn(async move {
+            use futures::StreamExt;
+
+            while let Some(event) = events.next().await {
+                debug!("Session lock event: {:?}", event);
+                plugin_manager
+                    .write()
+                    .await
+                    .handle_session_lock_event(event)
+                    .await;
+            }
+        });
+
         Ok(())
     }
 
@@ -1197,6 +1555,10 @@ impl Daemon {
             info!("Clipboard plugin disabled, skipping clipboard monitor");
             return Ok(());
         }
+        if !config.plugins.clipboard_auto_sync {
+            info!("Clipboard auto-sync disabled, the clipboard is only sent on request");
+            return Ok(());
+        }
 
         info!("Starting clipboard monitor...");
 
@@ -1301,6 +1663,8 @@ impl Daemon {
             let notifier_clone = notifier.clone();
             let pairing_service = self.pairing_service.clone();
             let pairing_notifications = self.pairing_notifications.clone();
+            let plugin_actions = self.plugin_notification_actions.clone();
+            let plugin_manager = self.plugin_manager.clone();
             let _device_manager = self.device_manager.clone();
 
             tokio::spawn(async move {
@@ -1316,6 +1680,46 @@ impl Daemon {
                                 notification_id, action_key
                             );
 
+                            // Actions of plugin notifications mute a ringing call or
+                            // open the manager
+                            if let Some((device_id, target)) =
+                                plugin_actions.take(notification_id, &action_key)
+                            {
+                                info!("Handling plugin notification action '{}'", action_key);
+                                if target == ActionTarget::MuteCall {
+                                    let Some(device_id) = device_id else {
+                                        warn!("Mute call action without a device");
+                                        continue;
+                                    };
+                                    let manager = plugin_manager.read().await;
+                                    let telephony = manager
+                                        .get_device_plugin(&device_id, "telephony")
+                                        .and_then(|p| p.as_any().downcast_ref::<TelephonyPlugin>());
+                                    match telephony {
+                                        Some(telephony) => {
+                                            if let Err(e) = telephony.mute_call().await {
+                                                error!("Failed to mute call: {}", e);
+                                            }
+                                        }
+                                        None => warn!(
+                                            "Telephony plugin not available for {}",
+                                            device_id
+                                        ),
+                                    }
+                                    continue;
+                                }
+                                if let Err(e) = manager_activation::handle_notification_action(
+                                    &manager_activation::DbusManagerActivator,
+                                    device_id.as_deref(),
+                                    &target,
+                                )
+                                .await
+                                {
+                                    error!("Failed to handle notification action: {}", e);
+                                }
+                                continue;
+                            }
+
                             // Check if this is a pairing notification
                             let device_id = {
                                 let notifications = pairing_notifications.read().await;
@@ -1415,6 +1819,7 @@ impl Daemon {
             enabled: config.notification_listener.enabled,
             excluded_apps: config.notification_listener.excluded_apps.clone(),
             included_apps: config.notification_listener.included_apps.clone(),
+            app_filter: config.notification_app_filter.clone(),
             include_transient: config.notification_listener.include_transient,
             include_low_urgency: config.notification_listener.include_low_urgency,
             max_body_length: config.notification_listener.max_body_length,
@@ -1442,6 +1847,7 @@ impl Daemon {
                 let device_manager = self.device_manager.clone();
                 let plugin_manager = self.plugin_manager.clone();
                 let connection_manager = self.connection_manager.clone();
+                let device_config_registry = self.device_config_registry.clone();
                 let notification_receiver_mutex = self.notification_receiver.clone();
 
                 tokio::spawn(async move {
@@ -1476,39 +1882,32 @@ impl Daemon {
                             continue;
                         }
 
-                        // Actions are already in the correct format
-                        let actions = &notification.actions;
-
-                        // Process notification image if present
+                        // Process notification image and app icon if present
                         let image_bytes = Self::process_notification_image(&notification).await;
+                        let icon_bytes = Self::process_notification_icon(&notification).await;
 
-                        // Create notification packet using NotificationPlugin
-                        use cosmic_connect_protocol::plugins::notification::{
-                            NotificationPlugin, NotificationUrgency,
-                        };
-
-                        // Map urgency from notification hints
-                        let urgency = Some(NotificationUrgency::from_byte(notification.urgency()));
-
-                        let packet = NotificationPlugin::create_desktop_notification_packet(
-                            &notification.app_name,
-                            &notification.summary,
-                            &notification.body,
-                            notification.timestamp as i64,
-                            image_bytes.as_deref(),
-                            actions,
-                            urgency,
-                            notification.category(),
-                            None, // app_icon - could be enhanced later
-                        );
+                        let packet =
+                            notification.to_packet(image_bytes.as_deref(), icon_bytes.as_deref());
 
-                        // Forward to each device that supports notifications
+                        // Forward to each device that opted in and supports notifications
                         for device_id in &devices {
+                            if !device_config_registry
+                                .read()
+                                .await
+                                .forwards_notifications(device_id)
+                            {
+                                trace!(
+                                    "Notification forwarding not enabled for device {}, skipping",
+                                    device_id
+                                );
+                                continue;
+                            }
+
                             // Check if device supports notification capability
                             let supports_notifications = {
                                 let plug_manager = plugin_manager.read().await;
                                 plug_manager
-                                    .get_device_plugin(device_id, "cconnect.notification")
+                                    .get_device_plugin(device_id, "notification")
                                     .is_some()
                             };
 
@@ -1547,6 +1946,32 @@ impl Daemon {
         Ok(())
     }
 
+    /// Process the app icon of a captured notification
+    ///
+    /// Uses raw icon data from the `icon_data` hint, or the app icon when it
+    /// is an image path. Icon theme names are not resolved.
+    ///
+    /// Returns PNG-encoded icon bytes if successful, None otherwise.
+    async fn process_notification_icon(notification: &CapturedNotification) -> Option<Vec<u8>> {
+        use crate::notification_image::NotificationImage;
+
+        let image = if let Some(icon_data) = notification.icon_data() {
+            NotificationImage::from_image_data(icon_data)
+        } else if std::path::Path::new(&notification.app_icon).is_absolute() {
+            NotificationImage::from_path(&notification.app_icon)
+        } else {
+            return None;
+        };
+
+        match image.and_then(|img| img.to_png()) {
+            Ok(png_bytes) => Some(png_bytes),
+            Err(e) => {
+                debug!("Failed to process notification icon: {}", e);
+                None
+            }
+        }
+    }
+
     /// Process notification image from captured notification
     ///
     /// Attempts to extract and process an image from the notification, trying:
@@ -1620,6 +2045,8 @@ impl Daemon {
         config: &Arc<RwLock<Config>>,
         error_handler: &Option<Arc<ErrorHandler>>,
         tls_config: &Arc<cosmic_connect_protocol::TlsConfig>,
+        battery_monitor: &Arc<RwLock<battery_monitor::BatteryMonitor>>,
+        event_bus: &EventBus,
     ) -> Result<()> {
         match event {
             ConnectionEvent::Connected {
@@ -1628,6 +2055,21 @@ impl Daemon {
             } => {
                 info!("Device {} connected from {}", device_id, remote_addr);
 
+                // Tell the device about an unpair it missed while offline
+                if let Some(pairing_service) = pairing_service {
+                    if let Err(e) = pairing_service
+                        .read()
+                        .await
+                        .deliver_deferred(&device_id)
+                        .await
+                    {
+                        warn!(
+                            "Failed to deliver deferred pairing change to {}: {}",
+                            device_id, e
+                        );
+                    }
+                }
+
                 // Get device name for notifications
                 let _device_name = {
                     let dev_manager = device_manager.read().await;
@@ -1667,6 +2109,33 @@ impl Daemon {
                                             "Set TLS config on SharePlugin for device {}",
                                             device_id
                                         );
+
+                                        if let Some(device_config) =
+                                            device_config_registry.read().await.get(&device_id)
+                                        {
+                                            share_plugin.set_receive_policy(
+                                                device_config.get_file_receive_policy(),
+                                            );
+                                        }
+                                    }
+                                }
+
+                                Self::apply_sftp_auto_mount(
+                                    &mut plug_manager,
+                                    &device_id,
+                                    device_config_registry,
+                                )
+                                .await;
+
+                                // Photos are downloaded over TLS like shared files
+                                if let Some(plugin) =
+                                    plug_manager.get_device_plugin_mut(&device_id, "photo")
+                                {
+                                    use cosmic_connect_protocol::plugins::photo::PhotoPlugin;
+                                    if let Some(photo_plugin) =
+                                        plugin.as_any_mut().downcast_mut::<PhotoPlugin>()
+                                    {
+                                        photo_plugin.set_tls_config(tls_config.clone());
                                     }
                                 }
 
@@ -1872,22 +2341,14 @@ impl Daemon {
                             return Ok(());
                         };
 
-                        // Get device info and certificate from device manager
-                        let (device_info, device_cert) = {
-                            let dev_manager = device_manager.read().await;
-                            match dev_manager.get_device(&device_id) {
-                                Some(device) => (
-                                    device.info.clone(),
-                                    device.certificate_data.clone().unwrap_or_default(),
-                                ),
-                                None => {
-                                    warn!(
-                                        "Cannot handle pairing packet - device {} not found",
-                                        device_id
-                                    );
-                                    return Ok(());
-                                }
-                            }
+                        let Some((device_info, device_cert)) =
+                            pairing_peer(device_manager, &device_id).await
+                        else {
+                            warn!(
+                                "Cannot handle pairing packet - device {} not found",
+                                device_id
+                            );
+                            return Ok(());
                         };
 
                         // Forward to pairing service and send response if needed
@@ -1928,13 +2389,15 @@ impl Daemon {
                 let mut dev_manager = device_manager.write().await;
                 if let Some(device) = dev_manager.get_device_mut(&device_id) {
                     let device_name = device.name().to_string();
+                    let device_type = device.info.device_type;
 
                     // Route packet to plugin manager
                     let mut plug_manager = plugin_manager.write().await;
-                    if let Err(e) = plug_manager
+                    let plugin_error = plug_manager
                         .handle_packet(&device_id, &packet, device)
                         .await
-                    {
+                        .err();
+                    if let Some(e) = &plugin_error {
                         error!("Error handling packet from device {}: {}", device_id, e);
                     }
 
@@ -2090,8 +2553,22 @@ impl Daemon {
                     }
 
                     drop(plug_manager);
+
+                    // Plugins may have changed the device while handling the packet
+                    dev_manager.notify_changed(&device_id);
                     drop(dev_manager);
 
+                    // Published without holding locks, subscribers may need them
+                    if let Some(e) = plugin_error {
+                        event_bus
+                            .publish(DaemonEvent::PluginError {
+                                device_id: device_id.clone(),
+                                packet_type: packet.packet_type.clone(),
+                                message: e.to_string(),
+                            })
+                            .await;
+                    }
+
                     // Check device notification preference
                     let notification_pref = {
                         let config_registry = device_config_registry.read().await;
@@ -2102,32 +2579,38 @@ impl Daemon {
                         }
                     };
 
+                    // Check Do Not Disturb and the app filter before raising any pop-ups
+                    let (dnd_disposition, app_filter) = {
+                        let config = config.read().await;
+                        let desktop_dnd = config.do_not_disturb.follow_desktop
+                            && do_not_disturb::desktop_do_not_disturb();
+                        (
+                            do_not_disturb::disposition(
+                                &config.do_not_disturb,
+                                desktop_dnd,
+                                &packet,
+                            ),
+                            config.notification_app_filter.clone(),
+                        )
+                    };
+
+                    // The phone asks to edit run commands: open the manager
+                    if packet.is_type("cconnect.runcommand.request") {
+                        if let Err(e) = manager_activation::handle_runcommand_request(
+                            &manager_activation::DbusManagerActivator,
+                            &device_name,
+                            &packet.body,
+                        )
+                        .await
+                        {
+                            warn!("Failed to open command setup: {}", e);
+                        }
+                    }
+
                     // Send COSMIC notifications for specific packet types
                     if let Some(notifier) = &cosmic_notifier {
                         match packet.packet_type.as_str() {
-                            "cconnect.ping" => {
-                                // Skip notification for keepalive pings (silent pings)
-                                let is_keepalive = packet
-                                    .body
-                                    .get("keepalive")
-                                    .and_then(|v| v.as_bool())
-                                    .unwrap_or(false);
-
-                                if !is_keepalive {
-                                    // Show notification for regular pings only
-                                    let message =
-                                        packet.body.get("message").and_then(|v| v.as_str());
-
-                                    if let Err(e) =
-                                        notifier.notify_ping(&device_name, message).await
-                                    {
-                                        warn!("Failed to send ping notification: {}", e);
-                                    }
-                                } else {
-                                    debug!("Received keepalive ping from {} - suppressing notification", device_name);
-                                }
-                            }
-                            "cconnect.notification" => {
+                            "cconnect.notification" | "kdeconnect.notification" => {
                                 // Check if it's a cancel notification
                                 let is_cancel = packet
                                     .body
@@ -2173,23 +2656,43 @@ impl Daemon {
                                             .and_then(|v| v.as_bool())
                                             .unwrap_or(false);
 
-                                        // Apply notification filtering based on preference
-                                        let should_show = match notification_pref {
-                                            device_config::NotificationPreference::All => true,
-                                            device_config::NotificationPreference::Important => {
-                                                // Important includes messaging apps, calls, alarms
-                                                is_messaging
-                                                    || app_name.to_lowercase().contains("phone")
-                                                    || app_name.to_lowercase().contains("call")
-                                                    || app_name.to_lowercase().contains("alarm")
-                                                    || app_name.to_lowercase().contains("clock")
-                                            }
-                                            device_config::NotificationPreference::None => false,
-                                        };
+                                        // Filtered apps are neither raised nor logged
+                                        let package_name =
+                                            packet.body.get("packageName").and_then(|v| v.as_str());
+                                        let app_allowed =
+                                            app_filter.allows(Some(app_name), package_name);
+                                        if !app_allowed {
+                                            debug!(
+                                                "Notification from {} ({}) filtered by app filter",
+                                                device_name, app_name
+                                            );
+                                        }
+
+                                        // Apply Do Not Disturb and notification filtering based on preference
+                                        let should_show = app_allowed
+                                            && dnd_disposition == do_not_disturb::Disposition::Raise
+                                            && match notification_pref {
+                                                device_config::NotificationPreference::All => true,
+                                                device_config::NotificationPreference::Important => {
+                                                    // Important includes messaging apps, calls, alarms
+                                                    is_messaging
+                                                        || app_name.to_lowercase().contains("phone")
+                                                        || app_name.to_lowercase().contains("call")
+                                                        || app_name.to_lowercase().contains("alarm")
+                                                        || app_name.to_lowercase().contains("clock")
+                                                }
+                                                device_config::NotificationPreference::None => {
+                                                    false
+                                                }
+                                            };
 
                                         if should_show && is_messaging {
                                             let web_url =
                                                 packet.body.get("webUrl").and_then(|v| v.as_str());
+                                            let conversation_id = packet
+                                                .body
+                                                .get("conversationId")
+                                                .and_then(|v| v.as_str());
 
                                             if let Err(e) = notifier
                                                 .notify_messaging(
@@ -2199,6 +2702,7 @@ impl Daemon {
                                                     text,
                                                     rich_body,
                                                     web_url,
+                                                    conversation_id,
                                                 )
                                                 .await
                                             {
@@ -2335,56 +2839,70 @@ impl Daemon {
                                                     );
                                                 }
                                             }
+                                        } else if app_allowed
+                                            && dnd_disposition == do_not_disturb::Disposition::Log
+                                        {
+                                            info!(
+                                                "Do Not Disturb: suppressed notification from {} ({})",
+                                                device_name, app_name
+                                            );
+
+                                            // Record in history without raising a pop-up
+                                            if let Some(dbus) = &dbus_server {
+                                                let data = serde_json::json!({
+                                                    "event": "suppressed",
+                                                    "appName": app_name,
+                                                    "title": title,
+                                                })
+                                                .to_string();
+                                                if let Err(e) = dbus
+                                                    .emit_plugin_event(
+                                                        &device_id,
+                                                        "notification",
+                                                        &data,
+                                                    )
+                                                    .await
+                                                {
+                                                    warn!(
+                                                        "Failed to emit suppressed notification signal: {}",
+                                                        e
+                                                    );
+                                                }
+                                            }
                                         } else {
                                             debug!(
-                                                "Notification from {} filtered based on preference {:?}",
-                                                device_name, notification_pref
+                                                "Notification from {} filtered (preference {:?}, do not disturb {:?})",
+                                                device_name, notification_pref, dnd_disposition
                                             );
                                         }
                                     }
                                 }
                             }
                             "cconnect.share.request" => {
-                                // Handle different share types: file, URL, or text
-                                if let Some(filename) =
-                                    packet.body.get("filename").and_then(|v| v.as_str())
-                                {
-                                    if packet.payload_size.is_some() {
-                                        // This is a file transfer, show notification
-                                        let file_size = packet.payload_size.unwrap_or(0);
-
-                                        // Construct download path
-                                        let downloads_dir = std::path::PathBuf::from(
-                                            std::env::var("HOME")
-                                                .unwrap_or_else(|_| "/tmp".to_string()),
-                                        )
-                                        .join("Downloads");
-                                        let file_path = downloads_dir.join(filename);
+                                // Files and text are handled through the internal
+                                // share packets, only links are opened here
+                                let url = packet
+                                    .body
+                                    .get("url")
+                                    .and_then(|v| v.as_str())
+                                    .filter(|_| packet.body.get("filename").is_none());
+                                if let Some(url) = url {
+                                    // URL share - open in default browser
+                                    info!("Received URL share from {}: {}", device_name, url);
 
-                                        if let Err(e) = notifier
-                                            .notify_file_received(
-                                                &device_name,
-                                                filename,
-                                                &file_path.to_string_lossy(),
-                                            )
-                                            .await
+                                    // Keep handed-off links in the manager's history
+                                    if let Some(dbus) = &dbus_server {
+                                        let data = serde_json::json!({
+                                            "event": "url",
+                                            "url": url,
+                                        })
+                                        .to_string();
+                                        if let Err(e) =
+                                            dbus.emit_plugin_event(&device_id, "share", &data).await
                                         {
-                                            warn!(
-                                                "Failed to send file received notification: {}",
-                                                e
-                                            );
-                                        } else {
-                                            info!(
-                                                "Sent file received notification for '{}' ({} bytes) from {}",
-                                                filename, file_size, device_name
-                                            );
+                                            warn!("Failed to emit URL share signal: {}", e);
                                         }
                                     }
-                                } else if let Some(url) =
-                                    packet.body.get("url").and_then(|v| v.as_str())
-                                {
-                                    // URL share - open in default browser
-                                    info!("Received URL share from {}: {}", device_name, url);
 
                                     // Spawn xdg-open to open URL in default browser
                                     let url_clone = url.to_string();
@@ -2408,36 +2926,6 @@ impl Daemon {
                                             }
                                         }
                                     });
-                                } else if let Some(text) =
-                                    packet.body.get("text").and_then(|v| v.as_str())
-                                {
-                                    // Text share - copy to clipboard
-                                    info!(
-                                        "Received text share from {} ({} chars)",
-                                        device_name,
-                                        text.len()
-                                    );
-
-                                    use arboard::Clipboard;
-                                    match Clipboard::new() {
-                                        Ok(mut clipboard) => {
-                                            if let Err(e) = clipboard.set_text(text) {
-                                                warn!(
-                                                    "Failed to copy shared text to clipboard: {}",
-                                                    e
-                                                );
-                                            } else {
-                                                info!("Copied shared text from {} to clipboard ({} chars)",
-                                                    device_name, text.len());
-                                            }
-                                        }
-                                        Err(e) => {
-                                            warn!(
-                                                "Failed to initialize clipboard for text share: {}",
-                                                e
-                                            );
-                                        }
-                                    }
                                 }
                             }
                             "cconnect.clipboard" | "kdeconnect.clipboard.connect" => {
@@ -2466,8 +2954,8 @@ impl Daemon {
                                     }
                                 }
                             }
-                            "cconnect.battery" => {
-                                // Handle battery status updates - show notification for low battery
+                            "cconnect.battery" | "kdeconnect.battery" => {
+                                // Warn when the device runs low, clear it once it charges
                                 if let Some(charge) =
                                     packet.body.get("currentCharge").and_then(|v| v.as_i64())
                                 {
@@ -2481,37 +2969,104 @@ impl Daemon {
                                         .get("thresholdEvent")
                                         .and_then(|v| v.as_i64())
                                         .unwrap_or(0);
+                                    let level = charge.clamp(0, 100) as u8;
 
-                                    // threshold_event == 1 means low battery
-                                    if threshold_event == 1 && !is_charging {
-                                        info!(
-                                            "Low battery detected on {} ({}%)",
-                                            device_name, charge
-                                        );
+                                    debug!(
+                                        "Battery status from {}: {}% (charging: {})",
+                                        device_name, charge, is_charging
+                                    );
+
+                                    // threshold_event == 1 means the device reports low battery
+                                    let alert_config = config.read().await.battery_alert.clone();
+                                    let alert = battery_monitor.write().await.update(
+                                        &alert_config,
+                                        &device_id,
+                                        level,
+                                        is_charging,
+                                        threshold_event == 1,
+                                        std::time::Instant::now(),
+                                    );
 
+                                    match alert {
+                                        Some(battery_monitor::BatteryAlert::Low(level)) => {
+                                            info!(
+                                                "Low battery detected on {} ({}%)",
+                                                device_name, level
+                                            );
+
+                                            match notifier
+                                                .notify_battery_low(
+                                                    &device_name,
+                                                    device_type,
+                                                    level,
+                                                    alert_config.persistent,
+                                                )
+                                                .await
+                                            {
+                                                Ok(notification_id) => {
+                                                    battery_monitor
+                                                        .write()
+                                                        .await
+                                                        .set_notification_id(
+                                                            &device_id,
+                                                            notification_id,
+                                                        );
+                                                }
+                                                Err(e) => {
+                                                    warn!(
+                                                        "Failed to send low battery notification: {}",
+                                                        e
+                                                    );
+                                                }
+                                            }
+                                        }
+                                        Some(battery_monitor::BatteryAlert::Cleared {
+                                            notification_id,
+                                        }) => {
+                                            info!(
+                                                "Battery on {} recovered ({}%, charging: {})",
+                                                device_name, charge, is_charging
+                                            );
+
+                                            if let Some(id) = notification_id {
+                                                if let Err(e) = notifier.close(id).await {
+                                                    debug!(
+                                                        "Failed to close low battery notification: {}",
+                                                        e
+                                                    );
+                                                }
+                                            }
+                                        }
+                                        None => {}
+                                    }
+                                }
+                            }
+                            "cconnect.systemvolume" | "kdeconnect.systemvolume" => {
+                                // Show the phone's volume on-screen when it changes
+                                if config.read().await.plugins.show_volume_osd {
+                                    let osd_state = plugin_manager
+                                        .write()
+                                        .await
+                                        .take_device_volume_osd(&device_id);
+
+                                    if let Some(state) = osd_state {
+                                        let timeout_ms = VOLUME_OSD_TIMEOUT.as_millis() as i32;
                                         if let Err(e) = notifier
-                                            .notify_battery_low(
+                                            .notify_volume_osd(
                                                 &device_name,
-                                                charge.clamp(0, 100) as u8,
+                                                state.volume,
+                                                state.max_volume,
+                                                state.muted,
+                                                timeout_ms,
                                             )
                                             .await
                                         {
-                                            warn!("Failed to send low battery notification: {}", e);
-                                        } else {
-                                            info!(
-                                                "Sent low battery notification for {} ({}%)",
-                                                device_name, charge
-                                            );
+                                            warn!("Failed to show volume OSD: {}", e);
                                         }
-                                    } else {
-                                        debug!(
-                                            "Battery status from {}: {}% (charging: {})",
-                                            device_name, charge, is_charging
-                                        );
                                     }
                                 }
                             }
-                            "cconnect.mpris.request" => {
+                            "cconnect.mpris.request" | "kdeconnect.mpris.request" => {
                                 if let Some(mpris_mgr) = &mpris_manager {
                                     Self::handle_mpris_request(
                                         &packet.body,
@@ -2610,9 +3165,9 @@ impl Daemon {
         connection_mgr: &Arc<RwLock<ConnectionManager>>,
         plugin_manager: &Arc<RwLock<PluginManager>>,
     ) {
-        use cosmic_connect_protocol::plugins::mpris::MprisPlugin;
+        use cosmic_connect_protocol::plugins::mpris::{self as mpris, MprisPlugin};
 
-        let player = body.get("player").and_then(|v| v.as_str()).unwrap_or("");
+        let requested_player = body.get("player").and_then(|v| v.as_str()).unwrap_or("");
 
         // Helper to send MPRIS packets via the plugin
         let send_mpris_packet = |packet: cosmic_connect_protocol::Packet| async move {
@@ -2636,12 +3191,34 @@ impl Daemon {
 
             info!("Sending player list to {}: {:?}", device_name, players);
 
+            // Report what each player supports so the phone can disable controls
+            let mut states = Vec::with_capacity(players.len());
+            for name in players {
+                let mut state = mpris::PlayerState {
+                    name,
+                    ..Default::default()
+                };
+                let local_state = match mpris_manager.get_player_state(&state.name).await {
+                    Some(local_state) => Ok(local_state),
+                    None => mpris_manager.query_player_state(&state.name).await,
+                };
+                match local_state {
+                    Ok(local_state) => {
+                        let (status, metadata) = Self::convert_player_state(&local_state);
+                        state.status = status;
+                        state.metadata = metadata;
+                    }
+                    Err(e) => warn!("Failed to query capabilities of {}: {}", state.name, e),
+                }
+                states.push(state);
+            }
+
             let plug_manager = plugin_manager.read().await;
             if let Some(mpris_plugin) = plug_manager
                 .get_device_plugin(device_id, "mpris")
                 .and_then(|p| p.as_any().downcast_ref::<MprisPlugin>())
             {
-                let packet = mpris_plugin.create_player_list_packet(players);
+                let packet = mpris_plugin.create_player_list_packet_with_capabilities(&states);
                 drop(plug_manager);
                 send_mpris_packet(packet).await;
                 info!("Sent player list to {}", device_name);
@@ -2649,8 +3226,52 @@ impl Daemon {
             return;
         }
 
-        if player.is_empty() {
-            debug!("Received MPRIS request without player name");
+        // Headset buttons forwarded by the phone always go to the active local player
+        if let Some(transport) = body.get("transport").and_then(|v| v.as_str()) {
+            let Some(command) = mpris_manager::TransportCommand::parse(transport) else {
+                warn!(
+                    "Unknown transport command '{}' from {}",
+                    transport, device_name
+                );
+                return;
+            };
+            match mpris_manager::forward_transport_command(mpris_manager.as_ref(), command).await {
+                Ok(Some(player)) => info!(
+                    "Executed headset {:?} on {} from {}",
+                    command, player, device_name
+                ),
+                Ok(None) => debug!(
+                    "Headset {:?} from {} with no local player",
+                    command, device_name
+                ),
+                Err(e) => warn!("Failed headset {:?} from {}: {}", command, device_name, e),
+            }
+            return;
+        }
+
+        // Requests without a player name go to the active local player
+        let player = if requested_player.is_empty() {
+            match mpris_manager.active_player().await {
+                Some(player) => {
+                    debug!("MPRIS request without player name, using {}", player);
+                    player
+                }
+                None => {
+                    debug!("Received MPRIS request without player name and no local player");
+                    return;
+                }
+            }
+        } else {
+            requested_player.to_string()
+        };
+        let player = player.as_str();
+
+        // Open a media URI ("cast" from the phone)
+        if let Some(uri) = body.get("Url").and_then(|v| v.as_str()) {
+            match mpris_manager::open_remote_uri(mpris_manager.as_ref(), player, uri).await {
+                Ok(()) => info!("Opened {} on {} from {}", uri, player, device_name),
+                Err(e) => warn!("Rejected open request from {}: {:#}", device_name, e),
+            }
             return;
         }
 
@@ -2711,32 +3332,17 @@ impl Daemon {
             return;
         }
 
-        // Set loop status
-        if let Some(loop_str) = body.get("setLoopStatus").and_then(|v| v.as_str()) {
-            let loop_status = mpris_manager::LoopStatus::from_str(loop_str);
-            match mpris_manager.set_loop_status(player, loop_status).await {
-                Ok(()) => info!(
-                    "Set loop status to {} on {} from {}",
-                    loop_str, player, device_name
-                ),
-                Err(e) => warn!(
-                    "Failed to set loop status to {} on {}: {}",
-                    loop_str, player, e
-                ),
+        // Loop and shuffle toggles, possibly both in one request
+        match mpris_manager::apply_player_modes(mpris_manager.as_ref(), player, body).await {
+            Ok(true) => {
+                info!("Updated loop/shuffle on {} from {}", player, device_name);
+                return;
             }
-            return;
-        }
-
-        // Set shuffle
-        if let Some(shuffle) = body.get("setShuffle").and_then(|v| v.as_bool()) {
-            match mpris_manager.set_shuffle(player, shuffle).await {
-                Ok(()) => info!(
-                    "Set shuffle to {} on {} from {}",
-                    shuffle, player, device_name
-                ),
-                Err(e) => warn!("Failed to set shuffle to {} on {}: {}", shuffle, player, e),
+            Ok(false) => {}
+            Err(e) => {
+                warn!("Failed to update loop/shuffle on {}: {:#}", player, e);
+                return;
             }
-            return;
         }
 
         // Request for now playing state
@@ -2771,15 +3377,18 @@ impl Daemon {
     }
 
     /// Handle a discovery event
+    #[allow(clippy::too_many_arguments)]
     async fn handle_discovery_event(
         event: DiscoveryEvent,
         device_manager: &Arc<RwLock<DeviceManager>>,
         dbus_server: &Option<Arc<DbusServer>>,
-        _error_handler: &ErrorHandler,
+        error_handler: &ErrorHandler,
         connection_manager: &Arc<RwLock<ConnectionManager>>,
         connection_attempts: &Arc<
             RwLock<std::collections::HashMap<String, (std::time::Instant, u32)>>,
         >,
+        device_config_registry: &RwLock<device_config::DeviceConfigRegistry>,
+        network_source: &dyn network_policy::NetworkTypeSource,
     ) -> Result<()> {
         match event {
             DiscoveryEvent::DeviceDiscovered {
@@ -2803,55 +3412,53 @@ impl Daemon {
                     }
                 }
 
-                // Auto-connect if paired
+                // Devices restricted to Wi-Fi are not connected on metered networks
+                let network_allowed = network_policy::connection_allowed(
+                    device_config_registry,
+                    network_source,
+                    &device_id,
+                )
+                .await;
+
+                // Auto-connect if paired, unless the device rejected our
+                // certificate and has to be re-paired first, or the user
+                // disconnected from it
                 let should_connect = {
                     let manager = device_manager.read().await;
                     if let Some(device) = manager.get_device(&device_id) {
-                        device.is_paired() && !device.is_connected()
+                        device.is_paired() && !device.is_connected() && network_allowed
                     } else {
                         false
                     }
+                } && {
+                    let mgr = connection_manager.read().await;
+                    !mgr.requires_repair(&device_id).await
+                        && !mgr.is_manually_disconnected(&device_id).await
                 };
 
+                let mut auto_connect = false;
                 if should_connect {
                     // Check backoff
                     let mut attempts = connection_attempts.write().await;
                     let now = std::time::Instant::now();
-                    let (last_attempt, count) = attempts
-                        .entry(device_id.clone())
-                        .or_insert((now - Duration::from_secs(3600), 0));
-
-                    // Exponential backoff: 2^count seconds (cap at 60s)
-                    let backoff = Duration::from_secs(2u64.pow((*count).min(6)));
+                    let (next_attempt, count) =
+                        attempts.entry(device_id.clone()).or_insert((now, 0));
 
-                    if now.duration_since(*last_attempt) >= backoff {
+                    if now >= *next_attempt {
                         info!(
                             "Auto-connecting to trusted device {} (attempt {})",
                             device_id, count
                         );
-                        *last_attempt = now;
-                        *count += 1;
 
-                        // Try to connect
-                        let _mgr = connection_manager.read().await;
-                        // Need to extract SocketAddr from TransportAddress if it's TCP
-                        // DiscoveryService usually returns TransportAddress::Tcp for UDP discovery results
-                        if let cosmic_connect_protocol::transport::TransportAddress::Tcp(addr) =
-                            transport_address
-                        {
-                            let device_id_clone = device_id.clone();
-                            let socket_addr = *addr;
-
-                            let mgr_arc = connection_manager.clone();
-                            tokio::spawn(async move {
-                                let mgr = mgr_arc.read().await;
-                                if let Err(e) = mgr.connect(&device_id_clone, socket_addr).await {
-                                    warn!("Failed to auto-connect to {}: {}", device_id_clone, e);
-                                }
-                            });
-                        }
+                        // Exponential backoff: 2^count seconds (cap at 64s), jittered
+                        // so devices dropped together don't all retry together
+                        let backoff = Duration::from_secs(2u64.pow((*count).min(6)));
+                        let mgr = connection_manager.read().await;
+                        *next_attempt = now + mgr.reconnect_delay(backoff);
+                        *count += 1;
+                        auto_connect = true;
                     }
-                } else if !should_connect {
+                } else {
                     // Reset attempts if connected or not paired
                     let manager = device_manager.read().await;
                     if let Some(device) = manager.get_device(&device_id) {
@@ -2862,29 +3469,65 @@ impl Daemon {
                     }
                 }
 
-                // Emit DBus signal for device added (only on discovery, logic handles updated)
-                if matches!(event, DiscoveryEvent::DeviceDiscovered { .. }) {
-                    if let Some(dbus) = dbus_server {
-                        let manager = device_manager.read().await;
-                        if let Some(device) = manager.get_device(&device_id) {
-                            if let Err(e) = dbus.emit_device_added(device).await {
-                                warn!("Failed to emit DeviceAdded signal: {}", e);
+                // Following a device that reappears at a new address, e.g. after
+                // roaming to another network, and auto-connecting go through
+                // one task, so a beacon never starts two connections
+                if let (true, cosmic_connect_protocol::transport::TransportAddress::Tcp(addr)) =
+                    (network_allowed, transport_address)
+                {
+                    let device_id = device_id.clone();
+                    let addr = *addr;
+                    let mgr_arc = connection_manager.clone();
+                    let error_handler = error_handler.clone();
+                    tokio::spawn(async move {
+                        let mgr = mgr_arc.read().await;
+                        match mgr.handle_discovered_address(&device_id, addr).await {
+                            Ok(true) => return,
+                            Ok(false) => {}
+                            Err(e) => {
+                                warn!("Failed to reconnect to {} at {}: {}", device_id, addr, e);
+                                return;
                             }
                         }
-                    }
+
+                        if !auto_connect {
+                            return;
+                        }
+                        if let Err(e) = mgr.connect(&device_id, addr).await {
+                            if e.is_unknown_certificate() {
+                                // Prompt for re-pairing, the device is not retried
+                                error_handler
+                                    .handle_error(&e, "connecting to device", Some(&device_id))
+                                    .await;
+                            } else {
+                                warn!("Failed to auto-connect to {}: {}", device_id, e);
+                            }
+                        }
+                    });
                 }
             }
             DiscoveryEvent::DeviceTimeout { device_id } => {
                 info!("Device timed out: {}", device_id);
                 // We don't mark as disconnected here, ConnectionManager handles TCP timeout.
-                // But we can emit a signal if needed.
-                if let Some(dbus) = dbus_server {
-                    if let Err(e) = dbus
-                        .emit_device_state_changed(&device_id, "reachable")
-                        .await
-                    {
-                        // Just updating reachability state logic handled in list_devices mostly
-                        warn!("Failed to emit state change: {}", e);
+                // Without beacons the device is only reachable while connected.
+                let reachable = {
+                    let mut manager = device_manager.write().await;
+                    if let Err(e) = manager.expire_beacon(&device_id) {
+                        debug!("Timed out device {} is unknown: {}", device_id, e);
+                    }
+                    manager
+                        .get_device(&device_id)
+                        .is_some_and(|device| device.is_reachable())
+                };
+
+                if !reachable {
+                    if let Some(dbus) = dbus_server {
+                        if let Err(e) = dbus
+                            .emit_device_state_changed(&device_id, "unreachable")
+                            .await
+                        {
+                            warn!("Failed to emit state change: {}", e);
+                        }
                     }
                 }
             }
@@ -2907,6 +3550,11 @@ impl Daemon {
         let packet_receiver_mutex = self.packet_receiver.clone();
         let connection_manager = self.connection_manager.clone();
         let dbus_server = self.dbus_server.clone();
+        let cosmic_notifier = self.cosmic_notifier.clone();
+        let device_config_registry = self.device_config_registry.clone();
+        // Plugins send while the packet handler holds the plugin and device
+        // managers, so the forwarder must not wait for either of them
+        let outgoing_capabilities = self.plugin_manager.read().await.outgoing_capabilities();
 
         tokio::spawn(async move {
             let mut receiver_guard = packet_receiver_mutex.lock().await;
@@ -2919,11 +3567,23 @@ impl Daemon {
             while let Some((device_id, packet)) = receiver.recv().await {
                 // Handle internal signaling packets for DBus emission
                 let handled = if let Some(dbus) = &dbus_server {
-                    handle_internal_packet(dbus, &device_id, &packet).await
+                    handle_internal_packet(
+                        dbus,
+                        &cosmic_notifier,
+                        &device_config_registry,
+                        &device_id,
+                        &packet,
+                    )
+                    .await
                 } else {
                     false
                 };
 
+                // Catch plugins sending packets they never declared
+                if !handled && cfg!(debug_assertions) {
+                    outgoing_capabilities.validate(&device_id, &packet);
+                }
+
                 // Forward non-internal packets to the connection manager
                 if !handled {
                     let manager = connection_manager.read().await;
@@ -2998,9 +3658,60 @@ impl Daemon {
     fn enable_metrics(&mut self) {
         let metrics = Arc::new(RwLock::new(Metrics::new()));
         info!("Performance metrics enabled");
+        self.start_metrics_collection(metrics.clone());
         self.metrics = Some(metrics);
     }
 
+    /// Count connections, paired devices and plugin errors from the event bus
+    fn start_metrics_collection(&self, metrics: Arc<RwLock<Metrics>>) {
+        let mut events = self.event_bus.subscribe(&[
+            EventKind::Connection,
+            EventKind::Pairing,
+            EventKind::Plugin,
+        ]);
+        let device_manager = self.device_manager.clone();
+        tokio::spawn(async move {
+            let mut connected = std::collections::HashSet::new();
+            let mut paired: std::collections::HashSet<String> = device_manager
+                .read()
+                .await
+                .paired_devices()
+                .map(|device| device.id().to_string())
+                .collect();
+            metrics.write().await.update_paired_devices(paired.len());
+
+            while let Some(event) = events.recv().await {
+                match event {
+                    DaemonEvent::Connected { device_id } => {
+                        connected.insert(device_id);
+                        metrics.write().await.update_connections(connected.len());
+                    }
+                    DaemonEvent::Disconnected {
+                        device_id,
+                        reconnect: false,
+                    } => {
+                        connected.remove(&device_id);
+                        metrics.write().await.update_connections(connected.len());
+                    }
+                    DaemonEvent::PairingStatusChanged { device_id, status }
+                        if status == "paired" =>
+                    {
+                        paired.insert(device_id);
+                        metrics.write().await.update_paired_devices(paired.len());
+                    }
+                    DaemonEvent::Unpaired { device_id } => {
+                        paired.remove(&device_id);
+                        metrics.write().await.update_paired_devices(paired.len());
+                    }
+                    DaemonEvent::PluginError { .. } => {
+                        metrics.write().await.record_plugin_error();
+                    }
+                    _ => {}
+                }
+            }
+        });
+    }
+
     /// Enable packet dumping (debug mode)
     fn enable_packet_dumping(&mut self) {
         self.dump_packets = true;
@@ -3050,11 +3761,67 @@ impl Daemon {
     }
 }
 
+/// Device info and the certificate presented on the device's connection
+///
+/// The certificate is what pairing pins, and what it checks against the
+/// pinned one for an already paired device. It is empty if the connection
+/// reported none.
+async fn pairing_peer(
+    device_manager: &RwLock<DeviceManager>,
+    device_id: &str,
+) -> Option<(DeviceInfo, Vec<u8>)> {
+    let device_manager = device_manager.read().await;
+    let device = device_manager.get_device(device_id)?;
+    Some((
+        device.info.clone(),
+        device.certificate_data.clone().unwrap_or_default(),
+    ))
+}
+
+/// Copy text shared from a device to the clipboard
+fn copy_shared_text(device_name: &str, text: &str) {
+    use arboard::Clipboard;
+    match Clipboard::new() {
+        Ok(mut clipboard) => {
+            if let Err(e) = clipboard.set_text(text) {
+                warn!("Failed to copy shared text to clipboard: {}", e);
+            } else {
+                info!(
+                    "Copied shared text from {} to clipboard ({} chars)",
+                    device_name,
+                    text.len()
+                );
+            }
+        }
+        Err(e) => {
+            warn!("Failed to initialize clipboard for text share: {}", e);
+        }
+    }
+}
+
+/// Whether a device's file receive policy rejects its files
+async fn rejects_files(
+    device_config_registry: &Arc<RwLock<device_config::DeviceConfigRegistry>>,
+    device_id: &str,
+) -> bool {
+    device_config_registry
+        .read()
+        .await
+        .get(device_id)
+        .is_some_and(|config| config.get_file_receive_policy() == FileReceivePolicy::Reject)
+}
+
 /// Handle internal signaling packets for DBus emission
 ///
 /// Returns true if the packet was an internal packet and was handled,
 /// false if it should be forwarded to the connection manager.
-async fn handle_internal_packet(dbus: &dbus::DbusServer, device_id: &str, packet: &Packet) -> bool {
+async fn handle_internal_packet(
+    dbus: &dbus::DbusServer,
+    cosmic_notifier: &Option<Arc<cosmic_notifications::CosmicNotifier>>,
+    device_config_registry: &Arc<RwLock<device_config::DeviceConfigRegistry>>,
+    device_id: &str,
+    packet: &Packet,
+) -> bool {
     match packet.packet_type.as_str() {
         "cconnect.internal.screenshare.requested" => {
             if let Err(e) = dbus.emit_screen_share_requested(device_id).await {
@@ -3148,6 +3915,180 @@ async fn handle_internal_packet(dbus: &dbus::DbusServer, device_id: &str, packet
             }
             true
         }
+        "cconnect.internal.share.offer" => {
+            // Incoming file is waiting for the user to accept or reject it
+            let transfer_id = packet
+                .body
+                .get("transferId")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            let filename = packet
+                .body
+                .get("filename")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            let size = packet
+                .body
+                .get("size")
+                .and_then(|v| v.as_i64())
+                .unwrap_or(0);
+            if let Err(e) = dbus
+                .emit_file_offer_received(device_id, transfer_id, filename, size)
+                .await
+            {
+                error!("Failed to emit file_offer_received signal: {}", e);
+            }
+            true
+        }
+        "cconnect.internal.photo.received" => {
+            // Photo taken on request, listed in the applet's recent files
+            let Some(PhotoEvent::PhotoReceived { path }) = PhotoEvent::from_packet(packet) else {
+                return true;
+            };
+            if let Err(e) = dbus.emit_photo_received(device_id, &path).await {
+                error!("Failed to emit photo_received signal: {}", e);
+            }
+            if let Err(e) = dbus.record_received_transfer("", device_id, &path).await {
+                error!("Failed to record photo transfer: {}", e);
+            }
+            let file = received_files::ReceivedFile {
+                name: path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                path,
+                device_id: device_id.to_string(),
+                device_name: packet
+                    .body
+                    .get("deviceName")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                received_at: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0),
+            };
+            if let Err(e) = dbus.record_received_file(file).await {
+                error!("Failed to record received photo: {}", e);
+            }
+            true
+        }
+        "cconnect.internal.clipboard.pushed" => {
+            let Some(ClipboardEvent::ClipboardPushed { chars }) =
+                ClipboardEvent::from_packet(packet)
+            else {
+                return true;
+            };
+            if let Err(e) = dbus.emit_clipboard_pushed(device_id, chars).await {
+                error!("Failed to emit clipboard_pushed signal: {}", e);
+            }
+            true
+        }
+        "cconnect.internal.photo.cancelled" => {
+            info!("Photo request cancelled on device {}", device_id);
+            true
+        }
+        "cconnect.internal.sftp.credentials" | "cconnect.internal.sftp.mounted" => {
+            // Browse results, shown by the manager and applet
+            let Some(event) = SftpEvent::from_packet(packet) else {
+                return true;
+            };
+            let data = match event {
+                SftpEvent::Credentials {
+                    ip,
+                    port,
+                    user,
+                    path,
+                    mountpoint,
+                    mount_command,
+                } => serde_json::json!({
+                    "event": "credentials",
+                    "ip": ip,
+                    "port": port,
+                    "user": user,
+                    "path": path,
+                    "mountpoint": mountpoint.to_string_lossy(),
+                    "mountCommand": mount_command,
+                }),
+                SftpEvent::Mounted { mountpoint } => serde_json::json!({
+                    "event": "mounted",
+                    "mountpoint": mountpoint.to_string_lossy(),
+                }),
+            };
+            if let Err(e) = dbus
+                .emit_plugin_event(device_id, "networkshare", &data.to_string())
+                .await
+            {
+                error!("Failed to emit networkshare event: {}", e);
+            }
+            true
+        }
+        "cconnect.internal.share.text" => {
+            // Text shared alone or as the caption of a file
+            if let Some(text) = packet.body.get("text").and_then(|v| v.as_str()) {
+                let device_name = packet
+                    .body
+                    .get("deviceName")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or(device_id);
+                copy_shared_text(device_name, text);
+            }
+            true
+        }
+        "cconnect.internal.share.received" => {
+            // Downloaded file, listed in the applet's recent files
+            let field = |name: &str| {
+                packet
+                    .body
+                    .get(name)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            let file = received_files::ReceivedFile {
+                path: field("path").into(),
+                name: field("filename"),
+                device_id: device_id.to_string(),
+                device_name: field("deviceName"),
+                received_at: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0),
+            };
+            if let Err(e) = dbus
+                .record_received_transfer(&field("transferId"), device_id, &file.path)
+                .await
+            {
+                error!("Failed to record file transfer: {}", e);
+            }
+
+            // No pop-up for files of a device whose files are now rejected
+            if let Some(notifier) = cosmic_notifier {
+                if !rejects_files(device_config_registry, device_id).await {
+                    if let Err(e) = notifier
+                        .notify_file_received(
+                            &file.device_name,
+                            &file.name,
+                            &file.path.to_string_lossy(),
+                        )
+                        .await
+                    {
+                        warn!("Failed to send file received notification: {}", e);
+                    } else {
+                        info!(
+                            "Sent file received notification for '{}' from {}",
+                            file.name, file.device_name
+                        );
+                    }
+                }
+            }
+
+            if let Err(e) = dbus.record_received_file(file).await {
+                error!("Failed to record received file: {}", e);
+            }
+            true
+        }
         _ => false, // Not an internal packet
     }
 }
@@ -3298,6 +4239,10 @@ async fn handle_diagnostic_command(command: &DiagnosticCommand) -> Result<()> {
                 config.network.discovery_interval
             );
             println!("Device timeout: {} seconds", config.network.device_timeout);
+            println!(
+                "Stale device age: {} days",
+                config.network.stale_device_days
+            );
 
             println!("\n[Plugins]");
             println!("Ping: {}", config.plugins.enable_ping);
@@ -3313,6 +4258,17 @@ async fn handle_diagnostic_command(command: &DiagnosticCommand) -> Result<()> {
             println!("Presenter: {}", config.plugins.enable_presenter);
             println!("Contacts: {}", config.plugins.enable_contacts);
 
+            println!("\n[Do Not Disturb]");
+            println!("Enabled: {}", config.do_not_disturb.enabled);
+            println!("Follow desktop: {}", config.do_not_disturb.follow_desktop);
+            println!("Log suppressed: {}", config.do_not_disturb.log_suppressed);
+
+            println!("\n[Battery Alerts]");
+            println!("Enabled: {}", config.battery_alert.enabled);
+            println!("Threshold: {}%", config.battery_alert.threshold);
+            println!("Cooldown: {}s", config.battery_alert.cooldown_secs);
+            println!("Persistent: {}", config.battery_alert.persistent);
+
             if *show_sensitive {
                 println!("\n[Paths]");
                 println!("Config: {:?}", config.paths.config_dir);
@@ -3333,6 +4289,37 @@ async fn handle_diagnostic_command(command: &DiagnosticCommand) -> Result<()> {
             );
             Ok(())
         }
+        DiagnosticCommand::DoNotDisturb { enabled } => {
+            let state = if *enabled { "on" } else { "off" };
+
+            // Prefer the running daemon so the change applies immediately
+            let via_dbus = match zbus::Connection::session().await {
+                Ok(connection) => connection
+                    .call_method(
+                        Some(dbus::SERVICE_NAME),
+                        dbus::OBJECT_PATH,
+                        Some(dbus::INTERFACE_NAME),
+                        "SetDoNotDisturb",
+                        &(*enabled,),
+                    )
+                    .await
+                    .is_ok(),
+                Err(_) => false,
+            };
+
+            if via_dbus {
+                println!("Do Not Disturb turned {}", state);
+            } else {
+                let mut config = Config::load().context("Failed to load configuration")?;
+                config.do_not_disturb.enabled = *enabled;
+                config.save().context("Failed to save configuration")?;
+                println!(
+                    "Do Not Disturb turned {} (daemon not running, saved to configuration)",
+                    state
+                );
+            }
+            Ok(())
+        }
         DiagnosticCommand::Metrics { interval, count } => {
             println!("Performance metrics display");
             println!("Update interval: {} seconds", interval);
@@ -3437,6 +4424,18 @@ async fn main() -> Result<()> {
         .await
         .context("Failed to start MPRIS monitoring")?;
 
+    // Stop screen shares when the session locks
+    daemon
+        .start_session_lock_monitor()
+        .await
+        .context("Failed to start session lock monitor")?;
+
+    // Forget devices that have not been around for a while
+    daemon
+        .start_stale_device_pruning()
+        .await
+        .context("Failed to start stale device pruning")?;
+
     // Run daemon
     let result = daemon.run().await;
 
@@ -3445,3 +4444,91 @@ async fn main() -> Result<()> {
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmic_connect_protocol::{pairing::PairingPacket, Device};
+    use std::net::SocketAddr;
+
+    /// Certificate file as pinned by an earlier pairing
+    fn pem_certificate(der: &[u8]) -> String {
+        let encoded = general_purpose::STANDARD.encode(der);
+        let lines: Vec<&str> = encoded
+            .as_bytes()
+            .chunks(64)
+            .map(|line| std::str::from_utf8(line).unwrap())
+            .collect();
+        format!(
+            "-----BEGIN CERTIFICATE-----\n{}\n-----END CERTIFICATE-----\n",
+            lines.join("\n")
+        )
+    }
+
+    #[tokio::test]
+    async fn test_pair_packet_checked_against_connection_certificate() {
+        let dir = std::env::temp_dir().join("cconnect-test-pair-certificate");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // The phone was paired before the daemon started
+        let pinned = CertificateInfo::generate("phone").unwrap();
+        std::fs::write(dir.join("phone.pem"), pem_certificate(&pinned.certificate)).unwrap();
+        let pairing = PairingService::new(
+            "desktop",
+            PairingConfig {
+                cert_dir: dir.clone(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let mut events = pairing.subscribe().await;
+
+        let mut device_manager = DeviceManager::new(dir.join("registry.json")).unwrap();
+        let mut phone = DeviceInfo::new("Phone", DeviceType::Phone, 1716);
+        phone.device_id = "phone".to_string();
+        device_manager.add_device(Device::from_discovery(phone));
+        let device_manager = RwLock::new(device_manager);
+        let addr: SocketAddr = "192.168.1.20:1716".parse().unwrap();
+
+        // The reset phone connects with a new certificate and asks to pair
+        let presented = CertificateInfo::generate("phone").unwrap();
+        {
+            let mut dm = device_manager.write().await;
+            dm.mark_connected("phone", addr.ip().to_string(), addr.port())
+                .unwrap();
+            dm.set_peer_certificate("phone", Some(presented.certificate.clone()))
+                .unwrap();
+        }
+
+        let (device_info, device_cert) = pairing_peer(&device_manager, "phone").await.unwrap();
+        assert_eq!(device_cert, presented.certificate);
+        let response = pairing
+            .handle_pairing_packet(&PairingPacket::request(), &device_info, &device_cert, addr)
+            .await
+            .unwrap();
+        assert!(response.is_none());
+        assert!(matches!(
+            events.recv().await,
+            Some(PairingEvent::RePairRequired {
+                device_id,
+                old_fingerprint,
+                new_fingerprint,
+                ..
+            }) if device_id == "phone"
+                && old_fingerprint == pinned.fingerprint
+                && new_fingerprint == presented.fingerprint
+        ));
+
+        // The certificate belongs to the connection it was presented on
+        device_manager
+            .write()
+            .await
+            .mark_disconnected("phone")
+            .unwrap();
+        let (_, device_cert) = pairing_peer(&device_manager, "phone").await.unwrap();
+        assert!(device_cert.is_empty());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,240 @@
+//! Manager Activation
+//!
+//! Opens pages of `cosmic-connect-manager` on behalf of remote devices, e.g.
+//! the Commands page when the phone asks to set up run commands, and carries
+//! out notification actions such as "Open manager" on a ping.
+//!
+//! The manager is reached through the `org.freedesktop.Application` interface
+//! on its well-known bus name, so D-Bus activation starts it if it is not
+//! running and a running instance is focused on the requested page.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use cosmic_connect_protocol::plugins::notifier::ActionTarget;
+use std::collections::HashMap;
+use tracing::{debug, info};
+use zbus::zvariant::Value;
+use zbus::Connection;
+
+/// Well-known bus name of the manager (its application ID)
+pub const MANAGER_BUS_NAME: &str = "com.system76.CosmicConnectManager";
+
+/// Object path the manager serves `org.freedesktop.Application` at
+pub const MANAGER_OBJECT_PATH: &str = "/com/system76/CosmicConnectManager";
+
+/// Action that switches the manager to a page given by name
+pub const OPEN_PAGE_ACTION: &str = "open-page";
+
+/// Action that selects a device given by ID
+pub const OPEN_DEVICE_ACTION: &str = "open-device";
+
+/// Manager page for editing run commands
+pub const COMMANDS_PAGE: &str = "commands";
+
+/// Opens pages of the manager
+#[async_trait]
+pub trait ManagerActivator: Send + Sync {
+    /// Start or focus the manager on a page
+    async fn open_page(&self, page: &str) -> Result<()>;
+
+    /// Start or focus the manager with a device selected
+    async fn open_device(&self, device_id: &str) -> Result<()>;
+}
+
+/// Activates the manager over the session bus
+///
+/// Connects on each activation; setup requests are rare.
+#[derive(Debug, Default)]
+pub struct DbusManagerActivator;
+
+impl DbusManagerActivator {
+    /// Call `ActivateAction` on the manager with a single string parameter
+    async fn activate_action(&self, action: &str, parameter: &str) -> Result<()> {
+        let connection = Connection::session()
+            .await
+            .context("Failed to connect to session bus")?;
+        let proxy = zbus::Proxy::new(
+            &connection,
+            MANAGER_BUS_NAME,
+            MANAGER_OBJECT_PATH,
+            "org.freedesktop.Application",
+        )
+        .await
+        .context("Failed to create manager proxy")?;
+
+        let platform_data: HashMap<&str, Value<'_>> = HashMap::new();
+        let _: () = proxy
+            .call(
+                "ActivateAction",
+                &(action, vec![Value::from(parameter)], platform_data),
+            )
+            .await
+            .with_context(|| format!("Failed to activate manager action '{}'", action))?;
+
+        debug!("Activated manager action '{}' ({})", action, parameter);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ManagerActivator for DbusManagerActivator {
+    async fn open_page(&self, page: &str) -> Result<()> {
+        self.activate_action(OPEN_PAGE_ACTION, page).await
+    }
+
+    async fn open_device(&self, device_id: &str) -> Result<()> {
+        self.activate_action(OPEN_DEVICE_ACTION, device_id).await
+    }
+}
+
+/// Handle a run command request from a device
+///
+/// Opens the Commands page when the body asks for command setup
+/// (`{"setup": true}`). Returns whether the manager was activated; other
+/// run command requests are left to the plugin.
+pub async fn handle_runcommand_request(
+    activator: &dyn ManagerActivator,
+    device_name: &str,
+    body: &serde_json::Value,
+) -> Result<bool> {
+    let setup = body.get("setup").and_then(|v| v.as_bool()).unwrap_or(false);
+    if !setup {
+        return Ok(false);
+    }
+
+    info!("{} requested command setup, opening manager", device_name);
+    activator.open_page(COMMANDS_PAGE).await?;
+    Ok(true)
+}
+
+/// Carry out an invoked plugin notification action
+///
+/// `device_id` is the device the notification is about; device actions on
+/// notifications without one fail.
+pub async fn handle_notification_action(
+    activator: &dyn ManagerActivator,
+    device_id: Option<&str>,
+    target: &ActionTarget,
+) -> Result<()> {
+    match target {
+        ActionTarget::OpenDevice => {
+            let device_id =
+                device_id.context("Notification action needs a device but none is set")?;
+            activator.open_device(device_id).await
+        }
+        ActionTarget::OpenPage { page } => activator.open_page(page).await,
+        ActionTarget::MuteCall => {
+            anyhow::bail!("Muting a call is handled by the telephony plugin, not the manager")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Default)]
+    struct MockActivator {
+        opened: std::sync::Mutex<Vec<String>>,
+        devices: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl ManagerActivator for MockActivator {
+        async fn open_page(&self, page: &str) -> Result<()> {
+            self.opened.lock().unwrap().push(page.to_string());
+            Ok(())
+        }
+
+        async fn open_device(&self, device_id: &str) -> Result<()> {
+            self.devices.lock().unwrap().push(device_id.to_string());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_setup_request_opens_commands_page() {
+        let activator = MockActivator::default();
+
+        let activated = handle_runcommand_request(&activator, "Phone", &json!({ "setup": true }))
+            .await
+            .unwrap();
+        assert!(activated);
+        assert_eq!(*activator.opened.lock().unwrap(), vec![COMMANDS_PAGE]);
+    }
+
+    #[tokio::test]
+    async fn test_other_requests_do_not_activate() {
+        let activator = MockActivator::default();
+
+        for body in [
+            json!({ "setup": false }),
+            json!({ "requestCommandList": true }),
+            json!({ "key": "cmd1" }),
+        ] {
+            let activated = handle_runcommand_request(&activator, "Phone", &body)
+                .await
+                .unwrap();
+            assert!(!activated);
+        }
+        assert!(activator.opened.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ping_action_opens_manager_on_device() {
+        use crate::cosmic_notifications::PluginNotificationActions;
+        use cosmic_connect_protocol::plugins::notifier::MockNotifier;
+        use cosmic_connect_protocol::plugins::ping::{PingPlugin, OPEN_MANAGER_ACTION};
+        use cosmic_connect_protocol::plugins::Plugin;
+        use cosmic_connect_protocol::{Device, DeviceInfo, DeviceType, Packet};
+        use std::sync::Arc;
+
+        let notifier = Arc::new(MockNotifier::new());
+        let mut device = Device::from_discovery(DeviceInfo::new("Phone", DeviceType::Phone, 1716));
+        let mut plugin = PingPlugin::new();
+        plugin.set_notifier(notifier.clone());
+        plugin
+            .init(&device, tokio::sync::mpsc::channel(1).0)
+            .await
+            .unwrap();
+        plugin
+            .handle_packet(&Packet::new("kdeconnect.ping", json!({})), &mut device)
+            .await
+            .unwrap();
+
+        // The notification server reports the action key for the shown ID
+        let sent = notifier.sent();
+        assert!(sent[0].find_action(OPEN_MANAGER_ACTION).is_some());
+        let actions = PluginNotificationActions::default();
+        actions.register(1, &sent[0]);
+        let (device_id, target) = actions.take(1, OPEN_MANAGER_ACTION).unwrap();
+
+        let activator = MockActivator::default();
+        handle_notification_action(&activator, device_id.as_deref(), &target)
+            .await
+            .unwrap();
+        assert_eq!(*activator.devices.lock().unwrap(), vec![device.id()]);
+        assert!(activator.opened.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_page_action_opens_page() {
+        let activator = MockActivator::default();
+        let target = ActionTarget::OpenPage {
+            page: COMMANDS_PAGE.to_string(),
+        };
+
+        handle_notification_action(&activator, None, &target)
+            .await
+            .unwrap();
+        assert_eq!(*activator.opened.lock().unwrap(), vec![COMMANDS_PAGE]);
+
+        // Device actions need the notification to name a device
+        assert!(
+            handle_notification_action(&activator, None, &ActionTarget::OpenDevice)
+                .await
+                .is_err()
+        );
+    }
+}
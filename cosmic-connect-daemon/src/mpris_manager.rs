@@ -2,15 +2,42 @@
 //!
 //! Manages integration with local MPRIS2 media players via DBus.
 //! Discovers players, monitors their state, and provides control methods.
+//!
+//! Players started later are picked up, and players that exit are dropped,
+//! by watching their bus names with [`MprisManager::watch_players`].
+//!
+//! Remote devices may also ask a local player to open a media URI. Only
+//! `file`, `http` and `https` URIs are forwarded; anything else is rejected.
+//!
+//! Loop and shuffle changes from the phone's toggles are applied with
+//! [`apply_player_modes`].
+//!
+//! Local playback changes are published to [`MprisManager::subscribe`]
+//! subscribers so they can be pushed to connected devices. Only changes the
+//! phone's media widget shows are published: track, playback status, volume,
+//! loop, shuffle and capabilities. Position ticks alone are not, the phone
+//! extrapolates the position while playing.
+//!
+//! Requests from the phone that name no player go to
+//! [`MprisManager::active_player`]: a playing player, else a paused one, the
+//! most recently playing first.
+//!
+//! Headset buttons pressed on a headset connected to the phone are forwarded
+//! as [`TransportCommand`]s and always control the active player, see
+//! [`forward_transport_command`].
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
 use futures::StreamExt;
+use std::cmp::Reverse;
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Instant;
+use tokio::sync::{broadcast, RwLock};
 use tokio::task::JoinHandle;
 use tracing::{debug, info, warn};
-use zbus::zvariant::OwnedValue;
+use zbus::zvariant::{OwnedValue, Value};
 use zbus::Connection;
 
 /// MPRIS2 DBus interface names
@@ -18,6 +45,43 @@ pub const MPRIS_INTERFACE: &str = "org.mpris.MediaPlayer2";
 pub const MPRIS_PLAYER_INTERFACE: &str = "org.mpris.MediaPlayer2.Player";
 pub const MPRIS_BUS_PREFIX: &str = "org.mpris.MediaPlayer2.";
 
+/// URI schemes a remote device may ask a local player to open
+const OPEN_URI_SCHEMES: &[&str] = &["file", "http", "https"];
+
+/// Check that a URI requested by a remote device is safe to open
+///
+/// Only absolute `file`, `http` and `https` URIs are accepted.
+pub fn validate_open_uri(uri: &str) -> Result<()> {
+    let Some((scheme, rest)) = uri.trim().split_once(':') else {
+        bail!("URI has no scheme: {}", uri);
+    };
+
+    let scheme = scheme.to_ascii_lowercase();
+    if !OPEN_URI_SCHEMES.contains(&scheme.as_str()) {
+        bail!("URI scheme '{}' is not allowed", scheme);
+    }
+
+    // file:///path, http://host/...; an empty authority is only valid for files
+    match rest.strip_prefix("//") {
+        Some(path) if scheme == "file" && path.starts_with('/') => Ok(()),
+        Some(authority) if scheme != "file" && !authority.is_empty() => Ok(()),
+        _ => bail!("URI is not absolute: {}", uri),
+    }
+}
+
+/// Media player control used to forward remote open requests
+#[async_trait]
+pub trait UriOpener: Send + Sync {
+    /// Ask a player to open a URI
+    async fn open_uri(&self, player: &str, uri: &str) -> Result<()>;
+}
+
+/// Validate a URI requested by a remote device and open it in a player
+pub async fn open_remote_uri(opener: &dyn UriOpener, player: &str, uri: &str) -> Result<()> {
+    validate_open_uri(uri)?;
+    opener.open_uri(player, uri).await
+}
+
 /// Playback status from MPRIS2
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum PlaybackStatus {
@@ -57,6 +121,16 @@ impl LoopStatus {
         }
     }
 
+    /// Parse a protocol loop status, rejecting unknown values
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "None" => Some(Self::None),
+            "Track" => Some(Self::Track),
+            "Playlist" => Some(Self::Playlist),
+            _ => None,
+        }
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::None => "None",
@@ -66,18 +140,130 @@ impl LoopStatus {
     }
 }
 
+/// Media player control used to forward remote loop and shuffle changes
+#[async_trait]
+pub trait PlayerModeControl: Send + Sync {
+    /// Set a player's loop status
+    async fn set_loop_status(&self, player: &str, loop_status: LoopStatus) -> Result<()>;
+
+    /// Enable or disable shuffle on a player
+    async fn set_shuffle(&self, player: &str, shuffle: bool) -> Result<()>;
+}
+
+/// Apply `setLoopStatus` and `setShuffle` from an MPRIS request body
+///
+/// Both may arrive in the same request. Returns whether the body carried
+/// either field.
+pub async fn apply_player_modes(
+    control: &dyn PlayerModeControl,
+    player: &str,
+    body: &serde_json::Value,
+) -> Result<bool> {
+    let loop_status = body.get("setLoopStatus").and_then(|v| v.as_str());
+    let shuffle = body.get("setShuffle").and_then(|v| v.as_bool());
+
+    if let Some(loop_str) = loop_status {
+        let Some(loop_status) = LoopStatus::parse(loop_str) else {
+            bail!("Unknown loop status: {}", loop_str);
+        };
+        control.set_loop_status(player, loop_status).await?;
+    }
+
+    if let Some(shuffle) = shuffle {
+        control.set_shuffle(player, shuffle).await?;
+    }
+
+    Ok(loop_status.is_some() || shuffle.is_some())
+}
+
+/// Transport command forwarded from a headset connected to the phone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportCommand {
+    PlayPause,
+    Play,
+    Pause,
+    Stop,
+    Next,
+    Previous,
+}
+
+impl TransportCommand {
+    /// Parse a forwarded command
+    ///
+    /// Accepts the Android media key names the phone sees
+    /// (`KEYCODE_MEDIA_NEXT`, `KEYCODE_HEADSETHOOK`, ...) as well as the
+    /// short names `playpause`, `play`, `pause`, `stop`, `next` and
+    /// `previous`, in any case.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_ascii_lowercase();
+        let name = s.strip_prefix("keycode_").unwrap_or(&s);
+        let name = name.strip_prefix("media_").unwrap_or(name);
+        match name {
+            "play_pause" | "playpause" | "headsethook" => Some(Self::PlayPause),
+            "play" => Some(Self::Play),
+            "pause" => Some(Self::Pause),
+            "stop" => Some(Self::Stop),
+            "next" => Some(Self::Next),
+            "previous" | "prev" => Some(Self::Previous),
+            _ => None,
+        }
+    }
+
+    /// MPRIS2 player method carrying out the command
+    pub fn method(&self) -> &'static str {
+        match self {
+            Self::PlayPause => "PlayPause",
+            Self::Play => "Play",
+            Self::Pause => "Pause",
+            Self::Stop => "Stop",
+            Self::Next => "Next",
+            Self::Previous => "Previous",
+        }
+    }
+}
+
+/// Media player control used to forward headset transport commands
+#[async_trait]
+pub trait PlayerTransport: Send + Sync {
+    /// Player that receives commands naming no player
+    async fn active_player(&self) -> Option<String>;
+
+    /// Call a playback control method on a player
+    async fn call_player_method(&self, player: &str, method: &str) -> Result<()>;
+}
+
+/// Carry out a headset transport command on the active player
+///
+/// Returns the player that was controlled, or `None` when no local player
+/// is running.
+pub async fn forward_transport_command(
+    control: &dyn PlayerTransport,
+    command: TransportCommand,
+) -> Result<Option<String>> {
+    let Some(player) = control.active_player().await else {
+        return Ok(None);
+    };
+    control
+        .call_player_method(&player, command.method())
+        .await?;
+    Ok(Some(player))
+}
+
 /// Media player metadata
-#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct PlayerMetadata {
     pub artist: Option<String>,
     pub title: Option<String>,
     pub album: Option<String>,
     pub album_art_url: Option<String>,
     pub length: i64, // microseconds
+    /// Object path of the current track, needed to set the position
+    #[serde(default)]
+    pub track_id: Option<String>,
 }
 
 /// Player state from MPRIS2
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct PlayerState {
     pub name: String,
     pub identity: String,
@@ -114,13 +300,134 @@ impl Default for PlayerState {
     }
 }
 
+/// Track ID from `mpris:trackid`
+///
+/// The spec requires an object path, some players send a string.
+fn track_id(value: &Value<'_>) -> Option<String> {
+    match value {
+        Value::ObjectPath(path) => Some(path.to_string()),
+        Value::Str(path) => Some(path.to_string()),
+        _ => None,
+    }
+}
+
+/// Whether a player's new state should be pushed to connected devices
+///
+/// True for a newly seen player and for any change except the position.
+pub fn needs_push(previous: Option<&PlayerState>, current: &PlayerState) -> bool {
+    let Some(previous) = previous else {
+        return true;
+    };
+    let previous = PlayerState {
+        position: current.position,
+        ..previous.clone()
+    };
+    previous != *current
+}
+
+/// Store a player's new state and publish it if it needs pushing
+///
+/// The state must already be queried: the write lock is only held for the
+/// insert so readers of the players map are never stalled on DBus.
+async fn record_state(
+    players: &RwLock<HashMap<String, PlayerState>>,
+    changes: &broadcast::Sender<PlayerState>,
+    state: PlayerState,
+) {
+    let previous = {
+        let mut players = players.write().await;
+        players.insert(state.name.clone(), state.clone())
+    };
+    if needs_push(previous.as_ref(), &state) {
+        // No subscribers is fine, nothing is connected
+        let _ = changes.send(state);
+    }
+}
+
+/// Query a player's state and record it
+///
+/// The query is awaited before any lock is taken, so a player that is slow
+/// to answer on DBus never stalls readers of the players map.
+async fn refresh_state<F>(
+    players: &RwLock<HashMap<String, PlayerState>>,
+    last_active: &RwLock<HashMap<String, Instant>>,
+    changes: &broadcast::Sender<PlayerState>,
+    query: F,
+) -> Result<()>
+where
+    F: Future<Output = Result<PlayerState>>,
+{
+    let state = query.await?;
+    if state.playback_status.is_playing() {
+        last_active
+            .write()
+            .await
+            .insert(state.name.clone(), Instant::now());
+    }
+    record_state(players, changes, state).await;
+    Ok(())
+}
+
+/// Pick the player remote controls go to when none is named
+///
+/// Playing beats paused beats stopped. Ties go to the player that was
+/// playing most recently, then to the first name so the choice is stable.
+pub fn pick_active_player(
+    players: &HashMap<String, PlayerState>,
+    last_active: &HashMap<String, Instant>,
+) -> Option<String> {
+    players
+        .values()
+        .max_by_key(|state| {
+            let rank = match state.playback_status {
+                PlaybackStatus::Playing => 2,
+                PlaybackStatus::Paused => 1,
+                PlaybackStatus::Stopped => 0,
+            };
+            (rank, last_active.get(&state.name), Reverse(&state.name))
+        })
+        .map(|state| state.name.clone())
+}
+
+#[async_trait]
+impl UriOpener for MprisManager {
+    async fn open_uri(&self, player: &str, uri: &str) -> Result<()> {
+        MprisManager::open_uri(self, player, uri).await
+    }
+}
+
+#[async_trait]
+impl PlayerTransport for MprisManager {
+    async fn active_player(&self) -> Option<String> {
+        MprisManager::active_player(self).await
+    }
+
+    async fn call_player_method(&self, player: &str, method: &str) -> Result<()> {
+        MprisManager::call_player_method(self, player, method).await
+    }
+}
+
+#[async_trait]
+impl PlayerModeControl for MprisManager {
+    async fn set_loop_status(&self, player: &str, loop_status: LoopStatus) -> Result<()> {
+        MprisManager::set_loop_status(self, player, loop_status).await
+    }
+
+    async fn set_shuffle(&self, player: &str, shuffle: bool) -> Result<()> {
+        MprisManager::set_shuffle(self, player, shuffle).await
+    }
+}
+
 /// MPRIS DBus Manager
 ///
 /// Manages discovery and control of MPRIS2 media players on the session bus.
 pub struct MprisManager {
     connection: Connection,
     players: Arc<RwLock<HashMap<String, PlayerState>>>,
+    /// When each player was last seen playing
+    last_active: Arc<RwLock<HashMap<String, Instant>>>,
     monitor_tasks: Arc<RwLock<HashMap<String, JoinHandle<()>>>>,
+    changes: broadcast::Sender<PlayerState>,
 }
 
 impl MprisManager {
@@ -133,10 +440,22 @@ impl MprisManager {
         Ok(Self {
             connection,
             players: Arc::new(RwLock::new(HashMap::new())),
+            last_active: Arc::new(RwLock::new(HashMap::new())),
             monitor_tasks: Arc::new(RwLock::new(HashMap::new())),
+            changes: broadcast::channel(Self::CHANGES_CAPACITY).0,
         })
     }
 
+    /// Player changes buffered per subscriber before the oldest are dropped
+    const CHANGES_CAPACITY: usize = 32;
+
+    /// Subscribe to player changes that should be pushed to devices
+    ///
+    /// See [`needs_push`] for which changes are published.
+    pub fn subscribe(&self) -> broadcast::Receiver<PlayerState> {
+        self.changes.subscribe()
+    }
+
     /// Standard MPRIS object path
     const MPRIS_OBJECT_PATH: &'static str = "/org/mpris/MediaPlayer2";
 
@@ -169,14 +488,97 @@ impl MprisManager {
         Ok(players)
     }
 
+    /// Follow players appearing on and leaving the session bus
+    ///
+    /// Players claiming an `org.mpris.MediaPlayer2.*` name are monitored and
+    /// players releasing it are forgotten, so [`Self::active_player`] only
+    /// picks players that are still running.
+    pub async fn watch_players(self: &Arc<Self>) -> Result<()> {
+        let dbus_proxy = zbus::fdo::DBusProxy::new(&self.connection)
+            .await
+            .context("Failed to create DBus proxy")?;
+
+        let mut owner_changes = dbus_proxy
+            .receive_name_owner_changed()
+            .await
+            .context("Failed to subscribe to NameOwnerChanged")?;
+
+        let manager = self.clone();
+        tokio::spawn(async move {
+            while let Some(signal) = owner_changes.next().await {
+                let args = match signal.args() {
+                    Ok(args) => args,
+                    Err(e) => {
+                        warn!("Failed to parse NameOwnerChanged signal: {}", e);
+                        continue;
+                    }
+                };
+                let Some(player) = args.name().as_str().strip_prefix(MPRIS_BUS_PREFIX) else {
+                    continue;
+                };
+                let player = player.to_string();
+
+                if args.old_owner().is_some() {
+                    manager.stop_monitoring(&player).await;
+                }
+                if args.new_owner().is_some() {
+                    if let Err(e) = manager.start_monitoring(player.clone()).await {
+                        warn!("Failed to start monitoring player {}: {}", player, e);
+                    }
+                }
+            }
+
+            warn!("MPRIS player watch ended");
+        });
+
+        Ok(())
+    }
+
     /// Get list of active players
     pub async fn get_player_list(&self) -> Vec<String> {
         self.players.read().await.keys().cloned().collect()
     }
 
+    /// Player remote controls go to when the request names none
+    ///
+    /// See [`pick_active_player`] for how it is chosen.
+    pub async fn active_player(&self) -> Option<String> {
+        let players = self.players.read().await;
+        let last_active = self.last_active.read().await;
+        pick_active_player(&players, &last_active)
+    }
+
     /// Get player state
+    ///
+    /// Players don't signal `Position` as it advances, so it is read from the
+    /// player on every call; everything else comes from the state kept up to
+    /// date by `PropertiesChanged`.
     pub async fn get_player_state(&self, player: &str) -> Option<PlayerState> {
-        self.players.read().await.get(player).cloned()
+        let mut state = self.players.read().await.get(player).cloned()?;
+        match self.query_position(player).await {
+            Ok(position) => state.position = position,
+            Err(e) => debug!("Failed to read position of {}: {}", player, e),
+        }
+        Some(state)
+    }
+
+    /// Read the current playback position of a player, in microseconds
+    async fn query_position(&self, player: &str) -> Result<i64> {
+        let bus_name = Self::player_bus_name(player);
+        // A cached value would be the position when the proxy was created
+        let player_proxy = zbus::proxy::Builder::<zbus::Proxy>::new(&self.connection)
+            .destination(bus_name.as_str())?
+            .path(Self::MPRIS_OBJECT_PATH)?
+            .interface(MPRIS_PLAYER_INTERFACE)?
+            .cache_properties(zbus::proxy::CacheProperties::No)
+            .build()
+            .await
+            .context("Failed to create player proxy")?;
+
+        player_proxy
+            .get_property("Position")
+            .await
+            .context("Failed to get Position")
     }
 
     /// Query player state from DBus (static version for signal handlers)
@@ -284,6 +686,7 @@ impl MprisManager {
                 .get("mpris:length")
                 .and_then(|v| i64::try_from(v).ok())
                 .unwrap_or(0),
+            track_id: metadata_dict.get("mpris:trackid").and_then(|v| track_id(v)),
         })
     }
 
@@ -391,8 +794,25 @@ impl MprisManager {
         Ok(())
     }
 
+    /// Move to an absolute position in the current track
+    ///
+    /// Looks up the track ID that `SetPosition` requires, so a track change
+    /// in between makes the player ignore the request instead of seeking in
+    /// the wrong track.
+    pub async fn seek_to(&self, player: &str, position_microseconds: i64) -> Result<()> {
+        let Some(track_id) = self
+            .get_player_state(player)
+            .await
+            .and_then(|state| state.metadata.track_id)
+        else {
+            bail!("Player {} has no current track", player);
+        };
+
+        self.set_position(player, &track_id, position_microseconds.max(0))
+            .await
+    }
+
     /// Open URI
-    #[allow(dead_code)]
     pub async fn open_uri(&self, player: &str, uri: &str) -> Result<()> {
         let bus_name = Self::player_bus_name(player);
         let player_proxy = zbus::Proxy::new(
@@ -484,10 +904,17 @@ impl MprisManager {
     pub async fn start_monitoring(&self, player: String) -> Result<()> {
         info!("Starting MPRIS monitoring for player: {}", player);
 
-        // Query initial state
+        // Query initial state before taking the lock
         let state = self.query_player_state(&player).await?;
 
-        // Store state
+        if state.playback_status.is_playing() {
+            self.last_active
+                .write()
+                .await
+                .insert(player.clone(), Instant::now());
+        }
+
+        // Store state, holding the write lock only for the insert
         self.players.write().await.insert(player.clone(), state);
 
         // Subscribe to PropertiesChanged signals
@@ -509,7 +936,9 @@ impl MprisManager {
         // Spawn background task to monitor signals
         let player_name = player.clone();
         let players = self.players.clone();
+        let last_active = self.last_active.clone();
         let connection = self.connection.clone();
+        let changes = self.changes.clone();
 
         let task = tokio::spawn(async move {
             info!("Signal monitoring task started for player: {}", player_name);
@@ -536,9 +965,9 @@ impl MprisManager {
 
                 // Re-query player state when properties change
                 let bus_name = Self::player_bus_name(&player_name);
-                match Self::query_player_state_static(&connection, &player_name, &bus_name).await {
-                    Ok(new_state) => {
-                        players.write().await.insert(player_name.clone(), new_state);
+                let query = Self::query_player_state_static(&connection, &player_name, &bus_name);
+                match refresh_state(&players, &last_active, &changes, query).await {
+                    Ok(()) => {
                         debug!("Updated state for player: {}", player_name);
                     }
                     Err(e) => {
@@ -550,19 +979,21 @@ impl MprisManager {
             info!("Signal monitoring task ended for player: {}", player_name);
         });
 
-        // Store task handle
-        self.monitor_tasks.write().await.insert(player, task);
+        // Store task handle, replacing a previous monitor of the player
+        if let Some(previous) = self.monitor_tasks.write().await.insert(player, task) {
+            previous.abort();
+        }
 
         Ok(())
     }
 
     /// Stop monitoring a player
-    #[allow(dead_code)]
     pub async fn stop_monitoring(&self, player: &str) {
         info!("Stopping MPRIS monitoring for player: {}", player);
 
         // Remove player state
         self.players.write().await.remove(player);
+        self.last_active.write().await.remove(player);
 
         // Abort monitoring task if it exists
         if let Some(task) = self.monitor_tasks.write().await.remove(player) {
@@ -594,6 +1025,355 @@ mod tests {
         assert_eq!(LoopStatus::Track.as_str(), "Track");
     }
 
+    #[derive(Default)]
+    struct MockOpener {
+        opened: std::sync::Mutex<Vec<(String, String)>>,
+    }
+
+    #[async_trait]
+    impl UriOpener for MockOpener {
+        async fn open_uri(&self, player: &str, uri: &str) -> Result<()> {
+            self.opened
+                .lock()
+                .unwrap()
+                .push((player.to_string(), uri.to_string()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_track_id() {
+        use zbus::zvariant::ObjectPath;
+
+        let path = ObjectPath::try_from("/org/mpris/MediaPlayer2/Track/7").unwrap();
+        assert_eq!(
+            track_id(&Value::from(path)).as_deref(),
+            Some("/org/mpris/MediaPlayer2/Track/7")
+        );
+        assert_eq!(
+            track_id(&Value::from("/track/1")).as_deref(),
+            Some("/track/1")
+        );
+        assert_eq!(track_id(&Value::from(7i64)), None);
+    }
+
+    #[test]
+    fn test_validate_open_uri() {
+        assert!(validate_open_uri("https://example.com/stream.mp3").is_ok());
+        assert!(validate_open_uri("HTTP://example.com/video.mkv").is_ok());
+        assert!(validate_open_uri("file:///home/user/Music/song.flac").is_ok());
+
+        assert!(validate_open_uri("javascript:alert(1)").is_err());
+        assert!(validate_open_uri("smb://server/share/movie.mkv").is_err());
+        assert!(validate_open_uri("file://relative/path").is_err());
+        assert!(validate_open_uri("http://").is_err());
+        assert!(validate_open_uri("/home/user/song.flac").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_open_remote_uri_forwards_valid_uri() {
+        let opener = MockOpener::default();
+
+        open_remote_uri(&opener, "vlc", "https://example.com/stream.mp3")
+            .await
+            .unwrap();
+        assert!(open_remote_uri(&opener, "vlc", "ftp://example.com/a.mp3")
+            .await
+            .is_err());
+
+        assert_eq!(
+            *opener.opened.lock().unwrap(),
+            vec![(
+                "vlc".to_string(),
+                "https://example.com/stream.mp3".to_string()
+            )]
+        );
+    }
+
+    #[derive(Default)]
+    struct MockModeControl {
+        loop_status: std::sync::Mutex<Vec<(String, LoopStatus)>>,
+        shuffle: std::sync::Mutex<Vec<(String, bool)>>,
+    }
+
+    #[async_trait]
+    impl PlayerModeControl for MockModeControl {
+        async fn set_loop_status(&self, player: &str, loop_status: LoopStatus) -> Result<()> {
+            self.loop_status
+                .lock()
+                .unwrap()
+                .push((player.to_string(), loop_status));
+            Ok(())
+        }
+
+        async fn set_shuffle(&self, player: &str, shuffle: bool) -> Result<()> {
+            self.shuffle
+                .lock()
+                .unwrap()
+                .push((player.to_string(), shuffle));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_apply_player_modes() {
+        let control = MockModeControl::default();
+
+        let body = serde_json::json!({ "player": "vlc", "setLoopStatus": "Track" });
+        assert!(apply_player_modes(&control, "vlc", &body).await.unwrap());
+
+        // Both toggles may be sent together
+        let body = serde_json::json!({ "setLoopStatus": "Playlist", "setShuffle": true });
+        assert!(apply_player_modes(&control, "vlc", &body).await.unwrap());
+
+        let body = serde_json::json!({ "setLoopStatus": "Forever" });
+        assert!(apply_player_modes(&control, "vlc", &body).await.is_err());
+
+        let body = serde_json::json!({ "action": "Play" });
+        assert!(!apply_player_modes(&control, "vlc", &body).await.unwrap());
+
+        assert_eq!(
+            *control.loop_status.lock().unwrap(),
+            vec![
+                ("vlc".to_string(), LoopStatus::Track),
+                ("vlc".to_string(), LoopStatus::Playlist)
+            ]
+        );
+        assert_eq!(
+            *control.shuffle.lock().unwrap(),
+            vec![("vlc".to_string(), true)]
+        );
+    }
+
+    #[derive(Default)]
+    struct MockTransport {
+        active: Option<String>,
+        calls: std::sync::Mutex<Vec<(String, String)>>,
+    }
+
+    #[async_trait]
+    impl PlayerTransport for MockTransport {
+        async fn active_player(&self) -> Option<String> {
+            self.active.clone()
+        }
+
+        async fn call_player_method(&self, player: &str, method: &str) -> Result<()> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push((player.to_string(), method.to_string()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_transport_command_parse() {
+        assert_eq!(
+            TransportCommand::parse("KEYCODE_MEDIA_PLAY_PAUSE"),
+            Some(TransportCommand::PlayPause)
+        );
+        assert_eq!(
+            TransportCommand::parse("KEYCODE_HEADSETHOOK"),
+            Some(TransportCommand::PlayPause)
+        );
+        assert_eq!(
+            TransportCommand::parse("KEYCODE_MEDIA_PREVIOUS"),
+            Some(TransportCommand::Previous)
+        );
+        assert_eq!(
+            TransportCommand::parse("next"),
+            Some(TransportCommand::Next)
+        );
+        assert_eq!(
+            TransportCommand::parse("Pause"),
+            Some(TransportCommand::Pause)
+        );
+        assert_eq!(TransportCommand::parse("KEYCODE_VOLUME_UP"), None);
+        assert_eq!(TransportCommand::parse("rewind"), None);
+    }
+
+    #[tokio::test]
+    async fn test_transport_command_controls_active_player() {
+        let control = MockTransport {
+            active: Some("spotify".to_string()),
+            ..Default::default()
+        };
+
+        for key in ["KEYCODE_MEDIA_PLAY_PAUSE", "KEYCODE_MEDIA_NEXT", "prev"] {
+            let command = TransportCommand::parse(key).unwrap();
+            let player = forward_transport_command(&control, command).await.unwrap();
+            assert_eq!(player.as_deref(), Some("spotify"));
+        }
+
+        assert_eq!(
+            *control.calls.lock().unwrap(),
+            vec![
+                ("spotify".to_string(), "PlayPause".to_string()),
+                ("spotify".to_string(), "Next".to_string()),
+                ("spotify".to_string(), "Previous".to_string()),
+            ]
+        );
+
+        // Nothing to control without a local player
+        let idle = MockTransport::default();
+        assert_eq!(
+            forward_transport_command(&idle, TransportCommand::Play)
+                .await
+                .unwrap(),
+            None
+        );
+        assert!(idle.calls.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_track_change_pushed_but_position_tick_is_not() {
+        let players = RwLock::new(HashMap::new());
+        let (changes, mut receiver) = broadcast::channel(8);
+
+        let playing = PlayerState {
+            name: "vlc".to_string(),
+            playback_status: PlaybackStatus::Playing,
+            metadata: PlayerMetadata {
+                title: Some("First".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        record_state(&players, &changes, playing.clone()).await;
+        assert_eq!(receiver.try_recv().unwrap(), playing);
+
+        // Position tick alone
+        let tick = PlayerState {
+            position: 5_000_000,
+            ..playing.clone()
+        };
+        record_state(&players, &changes, tick.clone()).await;
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(players.read().await.get("vlc"), Some(&tick));
+
+        // Track change
+        let next_track = PlayerState {
+            position: 0,
+            metadata: PlayerMetadata {
+                title: Some("Second".to_string()),
+                ..Default::default()
+            },
+            ..tick
+        };
+        record_state(&players, &changes, next_track.clone()).await;
+        assert_eq!(receiver.try_recv().unwrap(), next_track);
+
+        let paused = PlayerState {
+            playback_status: PlaybackStatus::Paused,
+            ..next_track.clone()
+        };
+        assert!(needs_push(Some(&next_track), &paused));
+    }
+
+    #[tokio::test]
+    async fn test_readers_not_blocked_by_pending_query() {
+        use tokio::sync::oneshot;
+
+        let players = Arc::new(RwLock::new(HashMap::new()));
+        let last_active = Arc::new(RwLock::new(HashMap::new()));
+        let (changes, mut receiver) = broadcast::channel(8);
+        players.write().await.insert(
+            "vlc".to_string(),
+            PlayerState {
+                name: "vlc".to_string(),
+                ..Default::default()
+            },
+        );
+
+        // A DBus query that stays pending until the test releases it
+        let (started_tx, started_rx) = oneshot::channel();
+        let (release_tx, release_rx) = oneshot::channel::<PlayerState>();
+        let query = async move {
+            started_tx.send(()).unwrap();
+            release_rx.await.context("Query abandoned")
+        };
+        let refresh = {
+            let players = players.clone();
+            let last_active = last_active.clone();
+            tokio::spawn(
+                async move { refresh_state(&players, &last_active, &changes, query).await },
+            )
+        };
+
+        started_rx.await.unwrap();
+        // Readers get the previous state while the query is in flight
+        let stale = players.try_read().expect("players locked during query");
+        assert_eq!(stale["vlc"].playback_status, PlaybackStatus::Stopped);
+        drop(stale);
+        assert!(last_active.try_read().is_ok());
+
+        let playing = PlayerState {
+            name: "vlc".to_string(),
+            playback_status: PlaybackStatus::Playing,
+            ..Default::default()
+        };
+        release_tx.send(playing.clone()).unwrap();
+        refresh.await.unwrap().unwrap();
+
+        assert_eq!(players.read().await.get("vlc"), Some(&playing));
+        assert!(last_active.read().await.contains_key("vlc"));
+        assert_eq!(receiver.try_recv().unwrap(), playing);
+    }
+
+    #[test]
+    fn test_active_player_prefers_playing_then_recent() {
+        let player = |name: &str, playback_status| PlayerState {
+            name: name.to_string(),
+            playback_status,
+            ..Default::default()
+        };
+        let mut players: HashMap<String, PlayerState> = [
+            player("vlc", PlaybackStatus::Paused),
+            player("spotify", PlaybackStatus::Playing),
+            player("mpv", PlaybackStatus::Stopped),
+            player("firefox", PlaybackStatus::Paused),
+        ]
+        .into_iter()
+        .map(|state| (state.name.clone(), state))
+        .collect();
+
+        let earlier = Instant::now();
+        let later = earlier + std::time::Duration::from_secs(1);
+        let last_active: HashMap<String, Instant> = [
+            ("vlc".to_string(), earlier),
+            ("spotify".to_string(), earlier),
+            ("firefox".to_string(), later),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            pick_active_player(&players, &last_active).as_deref(),
+            Some("spotify")
+        );
+
+        // With nothing playing, the most recently playing paused player wins
+        players.get_mut("spotify").unwrap().playback_status = PlaybackStatus::Stopped;
+        assert_eq!(
+            pick_active_player(&players, &last_active).as_deref(),
+            Some("firefox")
+        );
+
+        // Paused beats stopped even when the stopped player played last
+        players.remove("firefox");
+        assert_eq!(
+            pick_active_player(&players, &last_active).as_deref(),
+            Some("vlc")
+        );
+
+        // Without any history the choice is still stable
+        assert_eq!(
+            pick_active_player(&players, &HashMap::new()).as_deref(),
+            Some("vlc")
+        );
+        assert_eq!(pick_active_player(&HashMap::new(), &last_active), None);
+    }
+
     // Integration tests require DBus session bus
     // Skipping for now as they would fail in CI
 }
@@ -0,0 +1,240 @@
+//! Network Connection Policy
+//!
+//! Per-device restriction on which networks the daemon connects to a device
+//! and transfers files over, so large transfers do not eat into the data plan
+//! of a metered hotspot.
+//!
+//! The active network is read from NetworkManager: the type of the primary
+//! connection and whether it is metered. NetworkManager flags phone hotspots
+//! as metered, so a metered Wi-Fi network is treated like a cellular one.
+
+use crate::device_config::DeviceConfigRegistry;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tracing::debug;
+
+/// Type of the primary network connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkType {
+    /// Wired Ethernet
+    Ethernet,
+    /// Wi-Fi, including phone hotspots
+    Wifi,
+    /// Mobile broadband
+    Cellular,
+    /// Any other connection type, e.g. VPN or Bluetooth tethering
+    Other,
+    /// No primary connection, or the type could not be determined
+    Unknown,
+}
+
+impl NetworkType {
+    /// Map a NetworkManager connection type, e.g. `802-11-wireless`
+    pub fn from_connection_type(connection_type: &str) -> Self {
+        match connection_type {
+            "802-3-ethernet" => Self::Ethernet,
+            "802-11-wireless" => Self::Wifi,
+            "gsm" | "cdma" => Self::Cellular,
+            "" => Self::Unknown,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Active network as seen by the daemon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetworkState {
+    /// Type of the primary connection
+    pub network_type: NetworkType,
+    /// Whether the connection is metered (or guessed to be)
+    pub metered: bool,
+}
+
+impl NetworkState {
+    /// State used when the network cannot be determined
+    pub const UNKNOWN: Self = Self {
+        network_type: NetworkType::Unknown,
+        metered: false,
+    };
+}
+
+/// Where a device may be connected to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionPolicy {
+    /// Connect on any network
+    #[default]
+    Any,
+    /// Only connect on unmetered Wi-Fi or wired networks
+    WifiOnly,
+}
+
+impl ConnectionPolicy {
+    /// Whether the policy allows connecting on a network
+    ///
+    /// An unknown network is allowed unless it is known to be metered, so the
+    /// daemon keeps working without NetworkManager.
+    pub fn allows(self, network: &NetworkState) -> bool {
+        match self {
+            Self::Any => true,
+            Self::WifiOnly => network.network_type != NetworkType::Cellular && !network.metered,
+        }
+    }
+}
+
+/// Source of the active network state
+#[async_trait]
+pub trait NetworkTypeSource: Send + Sync {
+    /// Current network state
+    async fn current(&self) -> NetworkState;
+}
+
+/// Reads the active network from NetworkManager over the system bus
+///
+/// Connects on each query; queries happen on connects and transfers only.
+#[derive(Debug, Default)]
+pub struct NetworkManagerSource;
+
+impl NetworkManagerSource {
+    async fn query(&self) -> zbus::Result<NetworkState> {
+        let connection = zbus::Connection::system().await?;
+        let proxy = zbus::Proxy::new(
+            &connection,
+            "org.freedesktop.NetworkManager",
+            "/org/freedesktop/NetworkManager",
+            "org.freedesktop.NetworkManager",
+        )
+        .await?;
+
+        let connection_type: String = proxy.get_property("PrimaryConnectionType").await?;
+        // NMMetered: 1 = yes, 3 = guessed yes
+        let metered: u32 = proxy.get_property("Metered").await?;
+
+        Ok(NetworkState {
+            network_type: NetworkType::from_connection_type(&connection_type),
+            metered: matches!(metered, 1 | 3),
+        })
+    }
+}
+
+#[async_trait]
+impl NetworkTypeSource for NetworkManagerSource {
+    async fn current(&self) -> NetworkState {
+        match self.query().await {
+            Ok(state) => state,
+            Err(e) => {
+                debug!("Failed to read network state from NetworkManager: {}", e);
+                NetworkState::UNKNOWN
+            }
+        }
+    }
+}
+
+/// Whether the device's connection policy allows connecting right now
+///
+/// Devices without a configuration use the default policy and are always
+/// allowed, without querying the network.
+pub async fn connection_allowed(
+    registry: &RwLock<DeviceConfigRegistry>,
+    source: &dyn NetworkTypeSource,
+    device_id: &str,
+) -> bool {
+    let policy = registry
+        .read()
+        .await
+        .get(device_id)
+        .map(|config| config.get_connection_policy())
+        .unwrap_or_default();
+    if policy == ConnectionPolicy::Any {
+        return true;
+    }
+
+    let network = source.current().await;
+    let allowed = policy.allows(&network);
+    if !allowed {
+        debug!(
+            "Connection policy {:?} of {} does not allow {:?}",
+            policy, device_id, network
+        );
+    }
+    allowed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedNetwork(NetworkState);
+
+    #[async_trait]
+    impl NetworkTypeSource for FixedNetwork {
+        async fn current(&self) -> NetworkState {
+            self.0
+        }
+    }
+
+    fn test_registry(name: &str) -> RwLock<DeviceConfigRegistry> {
+        let dir = std::env::temp_dir().join(format!(
+            "cosmic-connect-network-policy-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let mut registry = DeviceConfigRegistry::new(&dir);
+        registry
+            .get_or_create("phone")
+            .set_connection_policy(ConnectionPolicy::WifiOnly);
+        RwLock::new(registry)
+    }
+
+    #[test]
+    fn test_policy_allows() {
+        let wifi = NetworkState {
+            network_type: NetworkType::Wifi,
+            metered: false,
+        };
+        let hotspot = NetworkState {
+            network_type: NetworkType::Wifi,
+            metered: true,
+        };
+        let cellular = NetworkState {
+            network_type: NetworkType::Cellular,
+            metered: false,
+        };
+
+        assert!(ConnectionPolicy::Any.allows(&hotspot));
+        assert!(ConnectionPolicy::WifiOnly.allows(&wifi));
+        assert!(ConnectionPolicy::WifiOnly.allows(&NetworkState::UNKNOWN));
+        assert!(!ConnectionPolicy::WifiOnly.allows(&hotspot));
+        assert!(!ConnectionPolicy::WifiOnly.allows(&cellular));
+
+        assert_eq!(
+            NetworkType::from_connection_type("802-11-wireless"),
+            NetworkType::Wifi
+        );
+        assert_eq!(
+            NetworkType::from_connection_type("gsm"),
+            NetworkType::Cellular
+        );
+        assert_eq!(NetworkType::from_connection_type("vpn"), NetworkType::Other);
+    }
+
+    #[tokio::test]
+    async fn test_wifi_only_suppresses_connect_on_metered_network() {
+        let registry = test_registry("metered");
+        let metered = FixedNetwork(NetworkState {
+            network_type: NetworkType::Wifi,
+            metered: true,
+        });
+        let unmetered = FixedNetwork(NetworkState {
+            network_type: NetworkType::Wifi,
+            metered: false,
+        });
+
+        assert!(!connection_allowed(&registry, &metered, "phone").await);
+        assert!(connection_allowed(&registry, &unmetered, "phone").await);
+
+        // Devices without a policy connect anywhere
+        assert!(connection_allowed(&registry, &metered, "tablet").await);
+    }
+}
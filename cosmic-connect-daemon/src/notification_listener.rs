@@ -9,6 +9,9 @@
 //! to intercept `org.freedesktop.Notifications.Notify` method calls. All captured
 //! notifications are filtered according to configuration and sent via an mpsc channel.
 //!
+//! The daemon only forwards captured notifications to devices that opted in
+//! through their per-device configuration.
+//!
 //! ## DBus Notification Specification
 //!
 //! The freedesktop.org notification specification defines the following parameters:
@@ -58,7 +61,10 @@
 //! }
 //! ```
 
+use crate::config::NotificationAppFilter;
 use anyhow::{Context, Result};
+use cosmic_connect_protocol::plugins::notification::{NotificationPlugin, NotificationUrgency};
+use cosmic_connect_protocol::Packet;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -280,6 +286,24 @@ impl CapturedNotification {
             action_icons: self.action_icons(),
         }
     }
+
+    /// Build the notification packet forwarded to a device
+    ///
+    /// `image` and `app_icon` are PNG-encoded and sent base64-encoded in the
+    /// packet body.
+    pub fn to_packet(&self, image: Option<&[u8]>, app_icon: Option<&[u8]>) -> Packet {
+        NotificationPlugin::create_desktop_notification_packet(
+            &self.app_name,
+            &self.summary,
+            &self.body,
+            self.timestamp as i64,
+            image,
+            &self.actions,
+            Some(NotificationUrgency::from_byte(self.urgency())),
+            self.category(),
+            app_icon,
+        )
+    }
 }
 
 /// Notification listener configuration
@@ -298,6 +322,10 @@ pub struct NotificationListenerConfig {
     #[serde(default)]
     pub included_apps: Vec<String>,
 
+    /// Per-app allowlist or denylist, matched on app_name and desktop-entry
+    #[serde(default)]
+    pub app_filter: NotificationAppFilter,
+
     /// Include transient notifications
     #[serde(default = "default_true")]
     pub include_transient: bool,
@@ -327,6 +355,7 @@ impl Default for NotificationListenerConfig {
                 "cosmic-notifications".to_string(),
             ],
             included_apps: Vec::new(),
+            app_filter: NotificationAppFilter::default(),
             include_transient: true,
             include_low_urgency: true,
             max_body_length: 0, // No limit
@@ -372,6 +401,35 @@ impl NotificationListenerConfig {
         true
     }
 
+    /// Check if a notification passes every filter and should be forwarded
+    fn should_forward(&self, notification: &CapturedNotification) -> bool {
+        if !self.should_capture_app(&notification.app_name) {
+            trace!(
+                "Skipping notification from excluded app: {}",
+                notification.app_name
+            );
+            return false;
+        }
+
+        if !self.app_filter.allows(
+            Some(notification.app_name.as_str()),
+            notification.desktop_entry(),
+        ) {
+            trace!(
+                "Skipping notification filtered by app filter: {}",
+                notification.app_name
+            );
+            return false;
+        }
+
+        if !self.should_capture_notification(notification) {
+            trace!("Skipping notification due to filter rules");
+            return false;
+        }
+
+        true
+    }
+
     /// Truncate body if needed
     fn truncate_body(&self, body: String) -> String {
         if self.max_body_length > 0 && body.len() > self.max_body_length {
@@ -505,16 +563,7 @@ impl NotificationListener {
         let notification = self.parse_notification(msg)?;
 
         // Apply filters
-        if !self.config.should_capture_app(&notification.app_name) {
-            trace!(
-                "Skipping notification from excluded app: {}",
-                notification.app_name
-            );
-            return Ok(());
-        }
-
-        if !self.config.should_capture_notification(&notification) {
-            trace!("Skipping notification due to filter rules");
+        if !self.config.should_forward(&notification) {
             return Ok(());
         }
 
@@ -963,6 +1012,78 @@ mod tests {
         assert_eq!(rich_data.image_data.unwrap().width, 128);
     }
 
+    #[test]
+    fn test_captured_notification_to_packet() {
+        let notification = create_test_notification();
+        let packet = notification.to_packet(None, Some(&[0x89, b'P', b'N', b'G']));
+
+        assert!(packet.is_type("cconnect.notification"));
+        assert_eq!(packet.body["appName"], "TestApp");
+        assert_eq!(packet.body["title"], "Test Summary");
+        assert_eq!(packet.body["text"], "Test body");
+        assert!(packet.body.get("appIcon").is_some());
+    }
+
+    #[test]
+    fn test_denied_app_is_not_forwarded() {
+        let config = NotificationListenerConfig {
+            excluded_apps: vec!["Spammy".to_string()],
+            ..Default::default()
+        };
+
+        let mut notification = create_test_notification();
+        assert!(config.should_capture_app(&notification.app_name));
+
+        notification.app_name = "Spammy".to_string();
+        assert!(!config.should_capture_app(&notification.app_name));
+    }
+
+    #[test]
+    fn test_app_filter_and_device_opt_in_gate_forwarding() {
+        use crate::config::NotificationFilterMode;
+        use crate::device_config::DeviceConfigRegistry;
+
+        // Denied by app name, or by desktop entry whatever the app name
+        let config = NotificationListenerConfig {
+            app_filter: NotificationAppFilter {
+                mode: NotificationFilterMode::Denylist,
+                apps: vec!["testapp".to_string(), "org.example.Chat".to_string()],
+            },
+            ..Default::default()
+        };
+        let mut notification = create_test_notification();
+        assert!(!config.should_forward(&notification));
+
+        notification.app_name = "Chat".to_string();
+        assert!(config.should_forward(&notification));
+        notification.hints.insert(
+            "desktop-entry".to_string(),
+            HintValue::String("org.example.Chat".to_string()),
+        );
+        assert!(!config.should_forward(&notification));
+
+        // An allowlist only lets listed apps through
+        let config = NotificationListenerConfig {
+            app_filter: NotificationAppFilter {
+                mode: NotificationFilterMode::Allowlist,
+                apps: vec!["TestApp".to_string()],
+            },
+            ..Default::default()
+        };
+        assert!(config.should_forward(&create_test_notification()));
+        assert!(!config.should_forward(&notification));
+
+        // Notifications passing the filter only go to opted-in devices
+        let mut registry = DeviceConfigRegistry::new(&std::env::temp_dir());
+        registry.get_or_create("tablet");
+        registry
+            .get_or_create("phone")
+            .set_forward_notifications(true);
+        assert!(registry.forwards_notifications("phone"));
+        assert!(!registry.forwards_notifications("tablet"));
+        assert!(!registry.forwards_notifications("unknown"));
+    }
+
     // Helper function to create test notification
     fn create_test_notification() -> CapturedNotification {
         CapturedNotification {
@@ -0,0 +1,99 @@
+//! Recently Received Files
+//!
+//! Keeps the last files downloaded from devices so the applet can offer to
+//! open them. Entries whose file was moved or deleted are pruned when the
+//! list is read, so only files that can still be opened are listed.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+/// Number of received files remembered
+pub const MAX_RECENT_RECEIVED_FILES: usize = 20;
+
+/// A file downloaded from a device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReceivedFile {
+    /// Where the file was saved
+    pub path: PathBuf,
+    /// File name as sent by the device
+    pub name: String,
+    /// Device the file came from
+    pub device_id: String,
+    /// Name of the device the file came from
+    pub device_name: String,
+    /// When the download finished, in seconds since the Unix epoch
+    pub received_at: u64,
+}
+
+/// Most recently received files, newest first
+#[derive(Debug, Default)]
+pub struct ReceivedFiles {
+    files: VecDeque<ReceivedFile>,
+}
+
+impl ReceivedFiles {
+    /// Create an empty list
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remember a received file
+    ///
+    /// Receiving the same path again moves it to the front.
+    pub fn record(&mut self, file: ReceivedFile) {
+        self.files.retain(|existing| existing.path != file.path);
+        self.files.push_front(file);
+        self.files.truncate(MAX_RECENT_RECEIVED_FILES);
+    }
+
+    /// Up to `limit` received files that still exist, newest first
+    pub fn recent(&mut self, limit: usize) -> Vec<ReceivedFile> {
+        self.files.retain(|file| file.path.exists());
+        self.files.iter().take(limit).cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn received(path: PathBuf, received_at: u64) -> ReceivedFile {
+        ReceivedFile {
+            name: path.file_name().unwrap().to_string_lossy().to_string(),
+            path,
+            device_id: "phone".to_string(),
+            device_name: "Pixel".to_string(),
+            received_at,
+        }
+    }
+
+    #[test]
+    fn test_completed_transfer_listed_and_deleted_file_pruned() {
+        let dir = std::env::temp_dir().join(format!(
+            "cosmic-connect-received-files-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let photo = dir.join("photo.jpg");
+        let notes = dir.join("notes.txt");
+        std::fs::write(&photo, b"jpeg").unwrap();
+        std::fs::write(&notes, b"text").unwrap();
+
+        let mut files = ReceivedFiles::new();
+        files.record(received(photo.clone(), 1));
+        files.record(received(notes.clone(), 2));
+
+        let recent = files.recent(10);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].path, notes);
+        assert_eq!(recent[1].path, photo);
+        assert_eq!(files.recent(1).len(), 1);
+
+        std::fs::remove_file(&notes).unwrap();
+        assert_eq!(files.recent(10), vec![received(photo.clone(), 1)]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(files.recent(10).is_empty());
+    }
+}
@@ -0,0 +1,493 @@
+//! File Transfer Queue
+//!
+//! Tracks file transfers from the moment they are queued until they finish.
+//! Finished transfers stay listed, up to [`MAX_FINISHED_TRANSFERS`], so the
+//! Transfers page can show what completed or failed and send it again.
+//!
+//! Each transfer has a cancellation flag, checked by the payload server's
+//! progress callback. Progress and outcomes are published on the daemon's
+//! [`EventBus`].
+
+use crate::event_bus::{DaemonEvent, EventBus};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+/// Finished transfers kept in the queue
+pub const MAX_FINISHED_TRANSFERS: usize = 50;
+
+/// Minimum time between two speed measurements
+const SPEED_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Direction of a transfer, as in the `TransferProgress` signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferDirection {
+    /// File sent to a device
+    Sending,
+    /// File received from a device
+    Receiving,
+}
+
+impl TransferDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sending => "sending",
+            Self::Receiving => "receiving",
+        }
+    }
+}
+
+/// Where a transfer is in its lifetime
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferState {
+    /// Waiting for the device to fetch the file
+    Pending,
+    /// Bytes are moving
+    Active,
+    /// All bytes transferred
+    Completed,
+    /// Stopped by an error
+    Failed,
+    /// Stopped by the user
+    Cancelled,
+}
+
+impl TransferState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Active => "active",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    /// Whether the transfer has ended, successfully or not
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// Transfer in the queue for DBus serialization
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
+pub struct TransferItem {
+    /// Transfer ID
+    pub transfer_id: String,
+    /// "sending" or "receiving"
+    pub direction: String,
+    /// Device ID
+    pub device_id: String,
+    /// File name
+    pub filename: String,
+    /// Bytes transferred so far
+    pub bytes_transferred: u64,
+    /// File size in bytes
+    pub total_bytes: u64,
+    /// "pending", "active", "completed", "failed" or "cancelled"
+    pub state: String,
+    /// Transfer speed in bytes per second, 0 unless active
+    pub speed: u64,
+}
+
+/// Transfer to add to the queue
+#[derive(Debug, Clone)]
+pub struct NewTransfer {
+    /// Transfer ID
+    pub transfer_id: String,
+    /// Direction of the transfer
+    pub direction: TransferDirection,
+    /// Device ID
+    pub device_id: String,
+    /// Local file sent or written
+    pub path: PathBuf,
+    /// File size in bytes
+    pub total_bytes: u64,
+}
+
+/// Transfer with its bookkeeping
+struct TrackedTransfer {
+    transfer: NewTransfer,
+    bytes_transferred: u64,
+    state: TransferState,
+    speed: u64,
+    /// Time and byte count of the last speed measurement
+    last_sample: Option<(Instant, u64)>,
+    cancel_flag: Arc<AtomicBool>,
+}
+
+impl TrackedTransfer {
+    fn item(&self) -> TransferItem {
+        let transfer = &self.transfer;
+        TransferItem {
+            transfer_id: transfer.transfer_id.clone(),
+            direction: transfer.direction.as_str().to_string(),
+            device_id: transfer.device_id.clone(),
+            filename: transfer
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            bytes_transferred: self.bytes_transferred,
+            total_bytes: transfer.total_bytes,
+            state: self.state.as_str().to_string(),
+            speed: self.speed,
+        }
+    }
+
+    fn record_progress(&mut self, bytes_transferred: u64, total_bytes: u64) {
+        let now = Instant::now();
+        match self.last_sample {
+            Some((at, bytes)) => {
+                let elapsed = now.duration_since(at);
+                if elapsed >= SPEED_SAMPLE_INTERVAL {
+                    let delta = bytes_transferred.saturating_sub(bytes);
+                    self.speed = (delta as f64 / elapsed.as_secs_f64()) as u64;
+                    self.last_sample = Some((now, bytes_transferred));
+                }
+            }
+            None => self.last_sample = Some((now, bytes_transferred)),
+        }
+
+        self.bytes_transferred = bytes_transferred;
+        self.transfer.total_bytes = total_bytes;
+        self.state = TransferState::Active;
+    }
+}
+
+/// Tracks file transfers with cancellation support
+pub struct TransferManager {
+    /// Transfers, oldest first
+    transfers: RwLock<Vec<TrackedTransfer>>,
+    /// Bus transfer events are published on
+    event_bus: Option<EventBus>,
+}
+
+impl TransferManager {
+    /// Create a new transfer manager
+    pub fn new() -> Self {
+        Self {
+            transfers: RwLock::new(Vec::new()),
+            event_bus: None,
+        }
+    }
+
+    /// Create a transfer manager publishing progress and outcomes on `event_bus`
+    pub fn with_event_bus(event_bus: EventBus) -> Self {
+        Self {
+            event_bus: Some(event_bus),
+            ..Self::new()
+        }
+    }
+
+    /// Publish a transfer event, if there is a bus
+    async fn publish(&self, event: DaemonEvent) {
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.publish(event).await;
+        }
+    }
+
+    /// Queue a new transfer and get its cancellation flag
+    pub async fn register_transfer(&self, transfer: NewTransfer) -> Arc<AtomicBool> {
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        debug!("Transfer {} queued", transfer.transfer_id);
+        self.transfers.write().await.push(TrackedTransfer {
+            transfer,
+            bytes_transferred: 0,
+            state: TransferState::Pending,
+            speed: 0,
+            last_sample: None,
+            cancel_flag: cancel_flag.clone(),
+        });
+        cancel_flag
+    }
+
+    /// Record the progress of a transfer, marking it active
+    ///
+    /// Progress reported after the transfer finished is ignored.
+    pub async fn update_progress(
+        &self,
+        transfer_id: &str,
+        bytes_transferred: u64,
+        total_bytes: u64,
+    ) {
+        let event = {
+            let mut transfers = self.transfers.write().await;
+            let Some(tracked) = transfers
+                .iter_mut()
+                .find(|t| t.transfer.transfer_id == transfer_id && !t.state.is_finished())
+            else {
+                return;
+            };
+            tracked.record_progress(bytes_transferred, total_bytes);
+            let item = tracked.item();
+            DaemonEvent::TransferProgress {
+                device_id: item.device_id,
+                transfer_id: item.transfer_id,
+                filename: item.filename,
+                direction: tracked.transfer.direction,
+                transferred: bytes_transferred,
+                total: total_bytes,
+            }
+        };
+
+        self.publish(event).await;
+    }
+
+    /// Record the end of a transfer
+    ///
+    /// A cancelled transfer stays cancelled whatever the outcome.
+    pub async fn finish_transfer(&self, transfer_id: &str, success: bool) {
+        let event = {
+            let mut transfers = self.transfers.write().await;
+            let Some(tracked) = transfers
+                .iter_mut()
+                .find(|t| t.transfer.transfer_id == transfer_id)
+            else {
+                return;
+            };
+
+            if tracked.state != TransferState::Cancelled {
+                tracked.state = if success {
+                    tracked.bytes_transferred = tracked.transfer.total_bytes;
+                    TransferState::Completed
+                } else {
+                    TransferState::Failed
+                };
+            }
+            tracked.speed = 0;
+            let event = DaemonEvent::TransferFinished {
+                device_id: tracked.transfer.device_id.clone(),
+                transfer_id: transfer_id.to_string(),
+                success: tracked.state == TransferState::Completed,
+            };
+
+            // Drop the oldest finished transfers beyond the limit
+            let finished = transfers.iter().filter(|t| t.state.is_finished()).count();
+            let mut excess = finished.saturating_sub(MAX_FINISHED_TRANSFERS);
+            transfers.retain(|t| {
+                if excess > 0 && t.state.is_finished() {
+                    excess -= 1;
+                    false
+                } else {
+                    true
+                }
+            });
+            event
+        };
+
+        self.publish(event).await;
+    }
+
+    /// Cancel a pending or active transfer
+    ///
+    /// Returns false if the transfer is unknown or already finished.
+    pub async fn cancel_transfer(&self, transfer_id: &str) -> bool {
+        let mut transfers = self.transfers.write().await;
+        match transfers
+            .iter_mut()
+            .find(|t| t.transfer.transfer_id == transfer_id && !t.state.is_finished())
+        {
+            Some(tracked) => {
+                tracked.cancel_flag.store(true, Ordering::SeqCst);
+                tracked.state = TransferState::Cancelled;
+                tracked.speed = 0;
+                info!("Transfer {} marked for cancellation", transfer_id);
+                true
+            }
+            None => {
+                warn!("Transfer {} not found", transfer_id);
+                false
+            }
+        }
+    }
+
+    /// Device and file of a failed or cancelled outgoing transfer
+    pub async fn retry_source(&self, transfer_id: &str) -> Option<(String, PathBuf)> {
+        self.transfers
+            .read()
+            .await
+            .iter()
+            .find(|t| {
+                t.transfer.transfer_id == transfer_id
+                    && t.transfer.direction == TransferDirection::Sending
+                    && matches!(t.state, TransferState::Failed | TransferState::Cancelled)
+            })
+            .map(|t| (t.transfer.device_id.clone(), t.transfer.path.clone()))
+    }
+
+    /// Remove a transfer from the queue
+    pub async fn remove_transfer(&self, transfer_id: &str) {
+        self.transfers
+            .write()
+            .await
+            .retain(|t| t.transfer.transfer_id != transfer_id);
+        debug!("Transfer {} removed from tracking", transfer_id);
+    }
+
+    /// Every queued, running and finished transfer, oldest first
+    pub async fn list(&self) -> Vec<TransferItem> {
+        self.transfers
+            .read()
+            .await
+            .iter()
+            .map(TrackedTransfer::item)
+            .collect()
+    }
+}
+
+impl Default for TransferManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_transfer(transfer_id: &str) -> NewTransfer {
+        NewTransfer {
+            transfer_id: transfer_id.to_string(),
+            direction: TransferDirection::Sending,
+            device_id: "phone".to_string(),
+            path: PathBuf::from("/home/user/report.pdf"),
+            total_bytes: 1000,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_queue_reflects_enqueued_transfer() {
+        let manager = TransferManager::new();
+        manager.register_transfer(new_transfer("t1")).await;
+
+        let queue = manager.list().await;
+        assert_eq!(queue.len(), 1);
+        let item = &queue[0];
+        assert_eq!(item.transfer_id, "t1");
+        assert_eq!(item.direction, "sending");
+        assert_eq!(item.device_id, "phone");
+        assert_eq!(item.filename, "report.pdf");
+        assert_eq!(item.bytes_transferred, 0);
+        assert_eq!(item.total_bytes, 1000);
+        assert_eq!(item.state, "pending");
+        assert_eq!(item.speed, 0);
+
+        manager.update_progress("t1", 400, 1000).await;
+        let item = &manager.list().await[0];
+        assert_eq!(item.state, "active");
+        assert_eq!(item.bytes_transferred, 400);
+
+        manager.finish_transfer("t1", true).await;
+        let item = &manager.list().await[0];
+        assert_eq!(item.state, "completed");
+        assert_eq!(item.bytes_transferred, 1000);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_transitions_to_cancelled() {
+        let manager = TransferManager::new();
+        let cancel_flag = manager.register_transfer(new_transfer("t1")).await;
+        manager.update_progress("t1", 100, 1000).await;
+
+        assert!(manager.cancel_transfer("t1").await);
+        assert!(cancel_flag.load(Ordering::SeqCst));
+        assert_eq!(manager.list().await[0].state, "cancelled");
+
+        // The failing send doesn't turn it into a failure
+        manager.update_progress("t1", 200, 1000).await;
+        manager.finish_transfer("t1", false).await;
+        let item = &manager.list().await[0];
+        assert_eq!(item.state, "cancelled");
+        assert_eq!(item.bytes_transferred, 100);
+
+        // Finished transfers can't be cancelled, but can be sent again
+        assert!(!manager.cancel_transfer("t1").await);
+        assert!(!manager.cancel_transfer("unknown").await);
+        assert_eq!(
+            manager.retry_source("t1").await,
+            Some(("phone".to_string(), PathBuf::from("/home/user/report.pdf")))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_completed_transfers_are_not_retried() {
+        let manager = TransferManager::new();
+        manager.register_transfer(new_transfer("t1")).await;
+        assert_eq!(manager.retry_source("t1").await, None);
+
+        manager.finish_transfer("t1", true).await;
+        assert_eq!(manager.retry_source("t1").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_received_transfer_listed_but_not_retried() {
+        let manager = TransferManager::new();
+        manager
+            .register_transfer(NewTransfer {
+                direction: TransferDirection::Receiving,
+                ..new_transfer("r1")
+            })
+            .await;
+        manager.finish_transfer("r1", false).await;
+
+        let queue = manager.list().await;
+        assert_eq!(queue[0].direction, "receiving");
+        assert_eq!(queue[0].state, "failed");
+        assert_eq!(manager.retry_source("r1").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_oldest_finished_transfers_are_dropped() {
+        let manager = TransferManager::new();
+        manager.register_transfer(new_transfer("running")).await;
+        for i in 0..=MAX_FINISHED_TRANSFERS {
+            let transfer_id = format!("t{}", i);
+            manager.register_transfer(new_transfer(&transfer_id)).await;
+            manager.finish_transfer(&transfer_id, true).await;
+        }
+
+        let queue = manager.list().await;
+        assert_eq!(queue.len(), MAX_FINISHED_TRANSFERS + 1);
+        assert_eq!(queue[0].transfer_id, "running");
+        assert_eq!(queue[1].transfer_id, "t1");
+    }
+
+    #[tokio::test]
+    async fn test_progress_and_outcome_are_published() {
+        use crate::event_bus::{EventKind, EVENT_BUS_CAPACITY};
+
+        let bus = EventBus::new(EVENT_BUS_CAPACITY);
+        let mut events = bus.subscribe(&[EventKind::Transfer]);
+        let manager = TransferManager::with_event_bus(bus);
+        manager.register_transfer(new_transfer("t1")).await;
+
+        manager.update_progress("t1", 400, 1000).await;
+        manager.cancel_transfer("t1").await;
+        manager.finish_transfer("t1", true).await;
+
+        assert_eq!(
+            events.recv().await,
+            Some(DaemonEvent::TransferProgress {
+                device_id: "phone".to_string(),
+                transfer_id: "t1".to_string(),
+                filename: "report.pdf".to_string(),
+                direction: TransferDirection::Sending,
+                transferred: 400,
+                total: 1000,
+            })
+        );
+        // A cancelled transfer is reported as unsuccessful
+        assert_eq!(
+            events.recv().await,
+            Some(DaemonEvent::TransferFinished {
+                device_id: "phone".to_string(),
+                transfer_id: "t1".to_string(),
+                success: false,
+            })
+        );
+    }
+}
@@ -22,8 +22,12 @@ fn create_mock_device() -> Device {
         last_connected: Some(0),
         host: Some("127.0.0.1".to_string()),
         port: Some(1716),
+        last_endpoint: None,
         certificate_fingerprint: None,
         certificate_data: None,
+        rtt: Default::default(),
+        connected_at: None,
+        beacon_expires_at: None,
     }
 }
 
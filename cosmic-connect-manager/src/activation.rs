@@ -0,0 +1,127 @@
+//! Application Activation
+//!
+//! Serves `org.freedesktop.Application` on the session bus under the app ID so
+//! the daemon can D-Bus activate the manager, or bring a running instance to a
+//! page, e.g. the Commands page when the phone asks to set up run commands.
+//!
+//! Pages are opened with the `open-page` action, which takes the page name as
+//! its only parameter. The `open-device` action selects a device by ID, e.g.
+//! from the "Open manager" button of a ping notification.
+
+use futures::Stream;
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+use zbus::zvariant::{OwnedValue, Value};
+use zbus::{connection, interface, Connection};
+
+/// Object path the application interface is served at
+pub const OBJECT_PATH: &str = "/com/system76/CosmicConnectManager";
+
+/// Action that switches to a page given by name
+pub const OPEN_PAGE_ACTION: &str = "open-page";
+
+/// Action that selects a device given by ID
+pub const OPEN_DEVICE_ACTION: &str = "open-device";
+
+/// Request received through D-Bus activation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActivationRequest {
+    /// Plain activation, e.g. launched from the desktop entry
+    Activate,
+    /// Switch to a page by name
+    OpenPage(String),
+    /// Select a device by ID
+    OpenDevice(String),
+}
+
+/// `org.freedesktop.Application` implementation forwarding to the app
+struct ApplicationService {
+    sender: mpsc::Sender<ActivationRequest>,
+}
+
+impl ApplicationService {
+    async fn forward(&self, request: ActivationRequest) {
+        if let Err(e) = self.sender.send(request).await {
+            warn!("Failed to forward activation request: {}", e);
+        }
+    }
+}
+
+#[interface(name = "org.freedesktop.Application")]
+impl ApplicationService {
+    async fn activate(&self, _platform_data: HashMap<String, OwnedValue>) {
+        debug!("D-Bus: activate");
+        self.forward(ActivationRequest::Activate).await;
+    }
+
+    async fn activate_action(
+        &self,
+        action_name: String,
+        parameter: Vec<OwnedValue>,
+        _platform_data: HashMap<String, OwnedValue>,
+    ) {
+        debug!("D-Bus: activate_action - {}", action_name);
+
+        let argument = parameter.first().and_then(|value| match &**value {
+            Value::Str(argument) => Some(argument.to_string()),
+            _ => None,
+        });
+
+        match (action_name.as_str(), argument) {
+            (OPEN_PAGE_ACTION, Some(page)) => {
+                self.forward(ActivationRequest::OpenPage(page)).await;
+            }
+            (OPEN_DEVICE_ACTION, Some(device_id)) => {
+                self.forward(ActivationRequest::OpenDevice(device_id)).await;
+            }
+            _ => warn!("Ignoring unknown activation action '{}'", action_name),
+        }
+    }
+
+    async fn open(&self, _uris: Vec<String>, _platform_data: HashMap<String, OwnedValue>) {
+        debug!("D-Bus: open");
+        self.forward(ActivationRequest::Activate).await;
+    }
+}
+
+/// Own the app ID on the session bus and serve the application interface
+async fn start_service(
+    app_id: &str,
+    sender: mpsc::Sender<ActivationRequest>,
+) -> zbus::Result<Connection> {
+    let connection = connection::Builder::session()?
+        .name(app_id)?
+        .serve_at(OBJECT_PATH, ApplicationService { sender })?
+        .build()
+        .await?;
+
+    info!("D-Bus activation service started: {}", app_id);
+
+    Ok(connection)
+}
+
+/// Stream of activation requests
+///
+/// Ends without items if the service cannot be started, e.g. because another
+/// instance already owns the app ID.
+pub fn requests(app_id: &'static str) -> impl Stream<Item = ActivationRequest> {
+    futures::stream::unfold(None, move |state| async move {
+        let (connection, mut receiver) = match state {
+            Some(state) => state,
+            None => {
+                let (sender, receiver) = mpsc::channel(8);
+                match start_service(app_id, sender).await {
+                    Ok(connection) => (connection, receiver),
+                    Err(e) => {
+                        warn!("Failed to start D-Bus activation service: {}", e);
+                        return None;
+                    }
+                }
+            }
+        };
+
+        let request = receiver.recv().await?;
+        Some((request, Some((connection, receiver))))
+    })
+}
@@ -38,6 +38,8 @@ pub struct DeviceInfo {
     pub has_pairing_request: bool,
     /// Last seen timestamp (UNIX timestamp)
     pub last_seen: i64,
+    /// Seconds the device has been continuously connected, 0 if not connected
+    pub connected_seconds: u64,
     /// Supported incoming plugin capabilities
     pub incoming_capabilities: Vec<String>,
     /// Supported outgoing plugin capabilities
@@ -53,6 +55,69 @@ pub struct BatteryStatus {
     pub is_charging: bool,
 }
 
+/// Battery part of the device status carried by the `DeviceChanged` signal
+///
+/// Fields the device has not reported yet are absent.
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceSnapshot {
+    /// Battery level percentage (0-100)
+    pub battery_level: Option<i32>,
+    /// Is device charging
+    pub is_charging: Option<bool>,
+}
+
+impl DeviceSnapshot {
+    /// Battery status, if the device reported one
+    pub fn battery(&self) -> Option<BatteryStatus> {
+        Some(BatteryStatus {
+            level: self.battery_level?,
+            is_charging: self.is_charging.unwrap_or(false),
+        })
+    }
+}
+
+/// File transfer in the daemon's queue from DBus
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
+pub struct TransferItem {
+    /// Transfer ID
+    pub transfer_id: String,
+    /// "sending" or "receiving"
+    pub direction: String,
+    /// Device ID
+    pub device_id: String,
+    /// File name
+    pub filename: String,
+    /// Bytes transferred so far
+    pub bytes_transferred: u64,
+    /// File size in bytes
+    pub total_bytes: u64,
+    /// "pending", "active", "completed", "failed" or "cancelled"
+    pub state: String,
+    /// Transfer speed in bytes per second, 0 unless active
+    pub speed: u64,
+}
+
+impl TransferItem {
+    /// Whether the transfer is queued or running
+    pub fn is_running(&self) -> bool {
+        matches!(self.state.as_str(), "pending" | "active")
+    }
+
+    /// Whether `RetryTransfer` can send the file again
+    pub fn can_retry(&self) -> bool {
+        self.direction == "sending" && matches!(self.state.as_str(), "failed" | "cancelled")
+    }
+
+    /// Progress in percent
+    pub fn progress(&self) -> u8 {
+        if self.total_bytes == 0 {
+            return 0;
+        }
+        (self.bytes_transferred.min(self.total_bytes) * 100 / self.total_bytes) as u8
+    }
+}
+
 /// Screen share statistics from DBus
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
 pub struct ScreenShareStats {
@@ -81,6 +146,17 @@ pub struct ContactInfo {
     pub vcard: String,
 }
 
+/// Pinned certificate of a paired device from DBus
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
+pub struct PairedCertificate {
+    /// Device ID
+    pub device_id: String,
+    /// SHA256 fingerprint of the certificate
+    pub fingerprint: String,
+    /// When the device was paired (UNIX timestamp, 0 if unknown)
+    pub paired_at: u64,
+}
+
 /// Notification preference for a device
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -121,6 +197,12 @@ pub struct DeviceConfig {
     /// RemoteDesktop plugin-specific settings
     #[serde(default)]
     pub remotedesktop_settings: Option<RemoteDesktopSettings>,
+    /// Accent color for the device card, as `#rrggbb`
+    #[serde(default)]
+    pub accent_color: Option<String>,
+    /// Icon name shown instead of the device type icon
+    #[serde(default)]
+    pub custom_icon: Option<String>,
 }
 
 /// Per-device plugin configuration
@@ -203,6 +285,8 @@ pub struct PlayerMetadata {
     pub album: Option<String>,
     pub album_art_url: Option<String>,
     pub length: i64, // microseconds
+    #[serde(default)]
+    pub track_id: Option<String>,
 }
 
 /// Run Command definition
@@ -304,6 +388,11 @@ pub enum DaemonEvent {
     DeviceRemoved { device_id: String },
     /// Device state changed
     DeviceStateChanged { device_id: String, state: String },
+    /// Device battery or signal changed
+    DeviceChanged {
+        device_id: String,
+        snapshot: DeviceSnapshot,
+    },
     /// Pairing request received
     PairingRequest { device_id: String },
     /// Pairing status changed
@@ -314,11 +403,8 @@ pub enum DaemonEvent {
     },
     /// Plugin event
     PluginEvent {
-        #[allow(dead_code)]
         device_id: String,
-        #[allow(dead_code)]
         plugin: String,
-        #[allow(dead_code)]
         data: String,
     },
     /// Device plugin state changed
@@ -398,12 +484,24 @@ trait CConnect {
     /// Unpair a device
     async fn unpair_device(&self, device_id: &str) -> zbus::fdo::Result<()>;
 
+    /// Disconnect from a device without unpairing it
+    async fn disconnect_device(&self, device_id: &str) -> zbus::fdo::Result<()>;
+
+    /// List the certificates pinned for paired devices
+    async fn list_paired_certificates(&self) -> zbus::fdo::Result<Vec<PairedCertificate>>;
+
+    /// Revoke the pinned certificate of a device and unpair it
+    async fn revoke_certificate(&self, device_id: &str) -> zbus::fdo::Result<()>;
+
     /// Trigger device discovery
     async fn refresh_discovery(&self) -> zbus::fdo::Result<()>;
 
     /// Connect to a device at a specific address
     async fn connect_to_address(&self, address: &str) -> zbus::fdo::Result<()>;
 
+    /// Add a device by IP address, returning its ID
+    async fn add_device_by_address(&self, ip: &str, port: u16) -> zbus::fdo::Result<String>;
+
     /// Get device connection state
     async fn get_device_state(&self, device_id: &str) -> zbus::fdo::Result<String>;
 
@@ -419,6 +517,15 @@ trait CConnect {
     /// Cancel an active file transfer
     async fn cancel_transfer(&self, transfer_id: &str) -> zbus::fdo::Result<()>;
 
+    /// List pending, active and recently finished file transfers
+    async fn list_transfers(&self) -> zbus::fdo::Result<Vec<TransferItem>>;
+
+    /// Send the file of a failed or cancelled transfer again
+    async fn retry_transfer(&self, transfer_id: &str) -> zbus::fdo::Result<String>;
+
+    /// Push the desktop clipboard to a device
+    async fn push_clipboard(&self, device_id: &str) -> zbus::fdo::Result<()>;
+
     /// Share text or URL with a device
     async fn share_text(&self, device_id: &str, text: &str) -> zbus::fdo::Result<()>;
 
@@ -480,6 +587,13 @@ trait CConnect {
     /// Seek MPRIS player position
     async fn mpris_seek(&self, player: &str, offset_microseconds: i64) -> zbus::fdo::Result<()>;
 
+    /// Move MPRIS player to an absolute position
+    async fn mpris_set_position(
+        &self,
+        player: &str,
+        position_microseconds: i64,
+    ) -> zbus::fdo::Result<()>;
+
     /// Raise MPRIS player window (bring to front)
     async fn mpris_raise(&self, player: &str) -> zbus::fdo::Result<()>;
 
@@ -510,6 +624,14 @@ trait CConnect {
     /// Set a custom nickname for a device
     async fn set_device_nickname(&self, device_id: &str, nickname: &str) -> zbus::fdo::Result<()>;
 
+    /// Set the accent color and icon of a device
+    async fn set_device_appearance(
+        &self,
+        device_id: &str,
+        accent_color: &str,
+        custom_icon: &str,
+    ) -> zbus::fdo::Result<()>;
+
     /// Set notification preference for a device
     async fn set_device_notification_preference(
         &self,
@@ -555,6 +677,10 @@ trait CConnect {
     #[zbus(signal)]
     fn device_state_changed(device_id: &str, state: &str) -> zbus::fdo::Result<()>;
 
+    /// Signal: Device battery or signal changed (coalesced)
+    #[zbus(signal)]
+    fn device_changed(device_id: &str, snapshot: &str) -> zbus::fdo::Result<()>;
+
     /// Signal: Pairing request received
     #[zbus(signal)]
     fn pairing_request(device_id: &str) -> zbus::fdo::Result<()>;
@@ -827,6 +953,25 @@ impl DbusClient {
             }
         });
 
+        let event_tx = self.event_tx.clone();
+        let mut device_changed_stream = self.proxy.receive_device_changed().await?;
+        tokio::spawn(async move {
+            while let Some(signal) = device_changed_stream.next().await {
+                if let Ok(args) = signal.args() {
+                    let device_id = args.device_id().to_string();
+                    match serde_json::from_str(args.snapshot()) {
+                        Ok(snapshot) => {
+                            let _ = event_tx.send(DaemonEvent::DeviceChanged {
+                                device_id,
+                                snapshot,
+                            });
+                        }
+                        Err(e) => debug!("Invalid DeviceChanged snapshot: {}", e),
+                    }
+                }
+            }
+        });
+
         let event_tx = self.event_tx.clone();
         let mut pairing_request_stream = self.proxy.receive_pairing_request().await?;
         tokio::spawn(async move {
@@ -1029,6 +1174,32 @@ impl DbusClient {
             .context("Failed to unpair device")
     }
 
+    /// Disconnect from a device without unpairing it
+    pub async fn disconnect_device(&self, device_id: &str) -> Result<()> {
+        info!("Disconnecting from device {}", device_id);
+        self.proxy
+            .disconnect_device(device_id)
+            .await
+            .context("Failed to disconnect device")
+    }
+
+    /// List the certificates pinned for paired devices
+    pub async fn list_paired_certificates(&self) -> Result<Vec<PairedCertificate>> {
+        self.proxy
+            .list_paired_certificates()
+            .await
+            .context("Failed to list paired certificates")
+    }
+
+    /// Revoke the pinned certificate of a device and unpair it
+    pub async fn revoke_certificate(&self, device_id: &str) -> Result<()> {
+        info!("Revoking certificate of device {}", device_id);
+        self.proxy
+            .revoke_certificate(device_id)
+            .await
+            .context("Failed to revoke certificate")
+    }
+
     /// Trigger device discovery
     #[allow(dead_code)]
     pub async fn refresh_discovery(&self) -> Result<()> {
@@ -1040,6 +1211,7 @@ impl DbusClient {
     }
 
     /// Connect to a device at a specific address
+    #[allow(dead_code)]
     pub async fn connect_to_address(&self, address: &str) -> Result<()> {
         debug!("Connecting to address: {}", address);
         self.proxy
@@ -1048,6 +1220,17 @@ impl DbusClient {
             .context("Failed to connect to address")
     }
 
+    /// Add a device by IP address, for networks where discovery fails
+    ///
+    /// Returns the ID of the added device. A `port` of 0 uses the default.
+    pub async fn add_device_by_address(&self, ip: &str, port: u16) -> Result<String> {
+        info!("Adding device at {}:{}", ip, port);
+        self.proxy
+            .add_device_by_address(ip, port)
+            .await
+            .context("Failed to add device")
+    }
+
     /// Get device connection state
     #[allow(dead_code)]
     pub async fn get_device_state(&self, device_id: &str) -> Result<String> {
@@ -1094,6 +1277,34 @@ impl DbusClient {
             .context("Failed to cancel transfer")
     }
 
+    /// List pending, active and recently finished file transfers
+    pub async fn list_transfers(&self) -> Result<Vec<TransferItem>> {
+        self.proxy
+            .list_transfers()
+            .await
+            .context("Failed to list transfers")
+    }
+
+    /// Send the file of a failed or cancelled transfer again
+    ///
+    /// Returns the ID of the new transfer.
+    pub async fn retry_transfer(&self, transfer_id: &str) -> Result<String> {
+        info!("Retrying transfer {}", transfer_id);
+        self.proxy
+            .retry_transfer(transfer_id)
+            .await
+            .context("Failed to retry transfer")
+    }
+
+    /// Push the desktop clipboard to a device
+    pub async fn push_clipboard(&self, device_id: &str) -> Result<()> {
+        info!("Pushing clipboard to device {}", device_id);
+        self.proxy
+            .push_clipboard(device_id)
+            .await
+            .context("Failed to push clipboard")
+    }
+
     /// Share text with a device
     pub async fn share_text(&self, device_id: &str, text: &str) -> Result<()> {
         info!("Sharing text with device {}: {}", device_id, text);
@@ -1282,6 +1493,22 @@ impl DbusClient {
             .context("Failed to seek MPRIS player")
     }
 
+    /// Move MPRIS player to an absolute position in the current track
+    ///
+    /// # Arguments
+    /// * `player` - Player name
+    /// * `position_microseconds` - Position from the start of the track
+    pub async fn mpris_set_position(&self, player: &str, position_microseconds: i64) -> Result<()> {
+        info!(
+            "Setting MPRIS player {} position to {}μs",
+            player, position_microseconds
+        );
+        self.proxy
+            .mpris_set_position(player, position_microseconds)
+            .await
+            .context("Failed to set MPRIS position")
+    }
+
     /// Raise MPRIS player window (bring to front)
     ///
     /// # Arguments
@@ -1405,6 +1632,23 @@ impl DbusClient {
             .context("Failed to set device nickname")
     }
 
+    /// Set the accent color and icon of a device (empty strings clear them)
+    pub async fn set_device_appearance(
+        &self,
+        device_id: &str,
+        accent_color: &str,
+        custom_icon: &str,
+    ) -> Result<()> {
+        info!(
+            "Setting appearance for {}: color='{}' icon='{}'",
+            device_id, accent_color, custom_icon
+        );
+        self.proxy
+            .set_device_appearance(device_id, accent_color, custom_icon)
+            .await
+            .context("Failed to set device appearance")
+    }
+
     /// Set notification preference for a device
     pub async fn set_device_notification_preference(
         &self,
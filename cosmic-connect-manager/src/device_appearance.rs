@@ -0,0 +1,97 @@
+//! Per-Device Appearance
+//!
+//! Picks the icon and accent color a device card is drawn with. A device's
+//! configured `custom_icon` replaces the icon for its type, and a valid
+//! `accent_color` (`#rrggbb`) tints the device name.
+
+use crate::dbus_client::DeviceConfig;
+use cosmic::iced::Color;
+
+/// How a device card is drawn
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceAppearance<'a> {
+    /// Icon name for the device
+    pub icon_name: &'a str,
+    /// Accent color for the device name, if configured
+    pub accent: Option<Color>,
+}
+
+impl<'a> DeviceAppearance<'a> {
+    /// Appearance of a device of `device_type` with an optional config
+    pub fn resolve(device_type: &str, config: Option<&'a DeviceConfig>) -> Self {
+        let icon_name = config
+            .and_then(|c| c.custom_icon.as_deref())
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| device_icon_name(device_type));
+        let accent = config
+            .and_then(|c| c.accent_color.as_deref())
+            .and_then(parse_accent_color);
+
+        Self { icon_name, accent }
+    }
+}
+
+/// Icon name for a device type
+pub fn device_icon_name(device_type: &str) -> &'static str {
+    match device_type {
+        "phone" | "smartphone" => "phone-symbolic",
+        "tablet" => "tablet-symbolic",
+        "desktop" | "laptop" => "computer-symbolic",
+        "tv" => "video-display-symbolic",
+        _ => "network-wireless-symbolic",
+    }
+}
+
+/// Parse a `#rrggbb` accent color
+pub fn parse_accent_color(value: &str) -> Option<Color> {
+    let hex = value.trim().strip_prefix('#')?;
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::from_rgb8(channel(0)?, channel(2)?, channel(4)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dbus_client::{DevicePluginConfig, NotificationPreference};
+
+    fn config(accent_color: Option<&str>, custom_icon: Option<&str>) -> DeviceConfig {
+        DeviceConfig {
+            device_id: "a".to_string(),
+            nickname: None,
+            plugins: DevicePluginConfig {
+                enable_ping: None,
+                enable_battery: None,
+                enable_notification: None,
+                enable_share: None,
+                enable_clipboard: None,
+                enable_mpris: None,
+                enable_remotedesktop: None,
+                enable_findmyphone: None,
+            },
+            notification_preference: NotificationPreference::default(),
+            remotedesktop_settings: None,
+            accent_color: accent_color.map(str::to_string),
+            custom_icon: custom_icon.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_configured_accent_and_icon_change_card() {
+        let plain = DeviceAppearance::resolve("phone", None);
+        assert_eq!(plain.icon_name, "phone-symbolic");
+        assert_eq!(plain.accent, None);
+
+        let styled = config(Some("#ff8000"), Some("starred-symbolic"));
+        let appearance = DeviceAppearance::resolve("phone", Some(&styled));
+        assert_eq!(appearance.icon_name, "starred-symbolic");
+        assert_eq!(appearance.accent, Some(Color::from_rgb8(0xff, 0x80, 0x00)));
+
+        // Invalid colors are ignored rather than drawn
+        let invalid = config(Some("orange"), None);
+        let appearance = DeviceAppearance::resolve("phone", Some(&invalid));
+        assert_eq!(appearance, plain);
+    }
+}
@@ -0,0 +1,172 @@
+//! Device List Filtering
+//!
+//! Narrows the device list down by name, pairing state and plugin
+//! capability, and can hide offline devices. Capabilities are matched by
+//! plugin name, so `share` matches both `cconnect.share.request` and
+//! `kdeconnect.share.request`.
+
+use crate::dbus_client::{DeviceConfig, DeviceInfo};
+use std::collections::HashMap;
+
+/// Capabilities offered as filters, as (plugin name, label)
+pub const CAPABILITY_FILTERS: &[(&str, &str)] = &[
+    ("share", "Share"),
+    ("battery", "Battery"),
+    ("mpris", "Media"),
+    ("sms", "SMS"),
+    ("runcommand", "Commands"),
+    ("mousepad", "Remote input"),
+];
+
+/// Pairing state a device must have to be listed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PairingFilter {
+    #[default]
+    All,
+    Paired,
+    Unpaired,
+}
+
+impl PairingFilter {
+    pub const ALL: [PairingFilter; 3] = [Self::All, Self::Paired, Self::Unpaired];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::All => "All",
+            Self::Paired => "Paired",
+            Self::Unpaired => "Unpaired",
+        }
+    }
+
+    fn matches(&self, device: &DeviceInfo) -> bool {
+        match self {
+            Self::All => true,
+            Self::Paired => device.is_paired,
+            Self::Unpaired => !device.is_paired,
+        }
+    }
+}
+
+/// Filter applied to the device list
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceFilter {
+    /// Case-insensitive text the device name or nickname must contain
+    pub query: String,
+    /// Pairing state to list
+    pub pairing: PairingFilter,
+    /// Plugin name the device must support, e.g. `share`
+    pub capability: Option<String>,
+    /// Hide devices that are neither connected nor reachable
+    pub hide_offline: bool,
+}
+
+impl DeviceFilter {
+    /// Whether the filter hides anything at all
+    pub fn is_active(&self) -> bool {
+        *self != Self::default()
+    }
+
+    /// Whether a device is listed under this filter
+    pub fn matches(&self, device: &DeviceInfo, config: Option<&DeviceConfig>) -> bool {
+        if self.hide_offline && !device.is_connected && !device.is_reachable {
+            return false;
+        }
+
+        if !self.pairing.matches(device) {
+            return false;
+        }
+
+        if let Some(capability) = &self.capability {
+            let supported = device
+                .incoming_capabilities
+                .iter()
+                .chain(&device.outgoing_capabilities)
+                .any(|cap| capability_plugin(cap) == capability);
+            if !supported {
+                return false;
+            }
+        }
+
+        let query = self.query.trim().to_lowercase();
+        if query.is_empty() {
+            return true;
+        }
+
+        let nickname = config.and_then(|c| c.nickname.as_deref());
+        std::iter::once(device.name.as_str())
+            .chain(nickname)
+            .any(|name| name.to_lowercase().contains(&query))
+    }
+
+    /// IDs of the devices listed under this filter
+    pub fn apply<'a>(
+        &self,
+        devices: &'a HashMap<String, DeviceInfo>,
+        configs: &HashMap<String, DeviceConfig>,
+    ) -> Vec<&'a str> {
+        devices
+            .iter()
+            .filter(|(id, device)| self.matches(device, configs.get(*id)))
+            .map(|(id, _)| id.as_str())
+            .collect()
+    }
+}
+
+/// Plugin name of a capability, e.g. `share` for `kdeconnect.share.request`
+fn capability_plugin(capability: &str) -> &str {
+    let name = capability
+        .strip_prefix("cconnect.")
+        .or_else(|| capability.strip_prefix("kdeconnect."))
+        .unwrap_or(capability);
+    name.split('.').next().unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(id: &str, name: &str) -> DeviceInfo {
+        DeviceInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            device_type: "phone".to_string(),
+            is_paired: true,
+            is_reachable: true,
+            is_connected: true,
+            has_pairing_request: false,
+            last_seen: 0,
+            connected_seconds: 0,
+            incoming_capabilities: vec!["kdeconnect.share.request".to_string()],
+            outgoing_capabilities: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_name_filter_lists_matching_devices() {
+        let devices: HashMap<String, DeviceInfo> = [
+            device("a", "Pixel 8"),
+            device("b", "Galaxy Tab"),
+            device("c", "pixel tablet"),
+        ]
+        .into_iter()
+        .map(|d| (d.id.clone(), d))
+        .collect();
+        let configs = HashMap::new();
+
+        assert_eq!(DeviceFilter::default().apply(&devices, &configs).len(), 3);
+
+        let filter = DeviceFilter {
+            query: "PIXEL".to_string(),
+            ..Default::default()
+        };
+        let mut listed = filter.apply(&devices, &configs);
+        listed.sort();
+        assert_eq!(listed, vec!["a", "c"]);
+
+        let filter = DeviceFilter {
+            capability: Some("battery".to_string()),
+            ..filter
+        };
+        assert!(filter.apply(&devices, &configs).is_empty());
+    }
+}
@@ -0,0 +1,140 @@
+//! Event History
+//!
+//! Entries listed on the History page. Most entries only describe what
+//! happened, but URLs handed off from a phone keep the URL so the page can
+//! reopen it later. Plugin events reported by the daemon are turned into
+//! entries by [`HistoryStore::record_plugin_event`].
+
+use serde::Deserialize;
+
+/// What a history entry refers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HistoryKind {
+    /// Device activity with nothing to act on
+    Activity,
+    /// URL handed off from a device, reopened when the entry is clicked
+    UrlHandoff(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryEvent {
+    pub icon_name: String,
+    pub event_type: String,
+    pub description: String,
+    pub timestamp: chrono::DateTime<chrono::Local>,
+    pub kind: HistoryKind,
+}
+
+impl HistoryEvent {
+    /// Entry for a URL handed off from a device
+    pub fn url_handoff(device_name: &str, url: &str) -> Self {
+        Self {
+            icon_name: "web-browser-symbolic".to_string(),
+            event_type: format!("Link from {}", device_name),
+            description: url.to_string(),
+            timestamp: chrono::Local::now(),
+            kind: HistoryKind::UrlHandoff(url.to_string()),
+        }
+    }
+
+    /// URL reopened when the entry is clicked
+    pub fn url(&self) -> Option<&str> {
+        match &self.kind {
+            HistoryKind::UrlHandoff(url) => Some(url),
+            HistoryKind::Activity => None,
+        }
+    }
+}
+
+/// Body of a `share` plugin event
+#[derive(Debug, Deserialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+enum ShareEvent {
+    Url { url: String },
+}
+
+/// History entries, oldest first
+#[derive(Debug, Default)]
+pub struct HistoryStore {
+    events: Vec<HistoryEvent>,
+}
+
+impl HistoryStore {
+    pub fn push(&mut self, event: HistoryEvent) {
+        self.events.push(event);
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &HistoryEvent> {
+        self.events.iter()
+    }
+
+    /// Record a plugin event reported by the daemon
+    ///
+    /// Returns `false` for events that are not kept in the history.
+    pub fn record_plugin_event(&mut self, device_name: &str, plugin: &str, data: &str) -> bool {
+        if plugin != "share" {
+            return false;
+        }
+
+        match serde_json::from_str::<ShareEvent>(data) {
+            Ok(ShareEvent::Url { url }) => {
+                self.push(HistoryEvent::url_handoff(device_name, &url));
+                true
+            }
+            Err(e) => {
+                tracing::debug!("Ignoring share event from {}: {}", device_name, e);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_share_adds_handoff_entry() {
+        let mut history = HistoryStore::default();
+
+        assert!(history.record_plugin_event(
+            "Pixel",
+            "share",
+            r#"{"event":"url","url":"https://example.com/article"}"#,
+        ));
+
+        assert_eq!(history.len(), 1);
+        let event = history.iter().next().unwrap();
+        assert_eq!(
+            event.kind,
+            HistoryKind::UrlHandoff("https://example.com/article".to_string())
+        );
+        assert_eq!(event.url(), Some("https://example.com/article"));
+        assert_eq!(event.event_type, "Link from Pixel");
+    }
+
+    #[test]
+    fn test_other_plugin_events_are_ignored() {
+        let mut history = HistoryStore::default();
+
+        assert!(!history.record_plugin_event(
+            "Pixel",
+            "notification",
+            r#"{"event":"suppressed","appName":"Chat","title":"Hi"}"#,
+        ));
+        assert!(!history.record_plugin_event("Pixel", "share", r#"{"event":"text"}"#));
+        assert!(history.is_empty());
+    }
+}
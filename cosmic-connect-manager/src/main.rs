@@ -1,4 +1,10 @@
+mod activation;
 mod dbus_client;
+mod device_appearance;
+mod device_filter;
+mod history;
+mod media;
+mod transfers;
 
 use clap::Parser;
 use cosmic::{
@@ -17,6 +23,7 @@ pub enum Page {
     MediaPlayers,
     Transfers,
     History,
+    Commands,
     Settings,
 }
 
@@ -27,6 +34,7 @@ impl Page {
             Page::MediaPlayers => "Media",
             Page::Transfers => "Transfers",
             Page::History => "History",
+            Page::Commands => "Commands",
             Page::Settings => "Settings",
         }
     }
@@ -37,13 +45,30 @@ impl Page {
             Page::MediaPlayers => "multimedia-player-symbolic",
             Page::Transfers => "folder-download-symbolic",
             Page::History => "document-open-recent-symbolic",
+            Page::Commands => "utilities-terminal-symbolic",
             Page::Settings => "preferences-system-symbolic",
         }
     }
+
+    /// Page for a `--tab` argument or activation page name
+    fn from_name(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "share" | "files" => Page::Transfers,
+            "settings" => Page::Settings,
+            "history" => Page::History,
+            "commands" => Page::Commands,
+            _ => Page::Devices,
+        }
+    }
 }
 
+use cosmic_connect_protocol::plugins::battery::BatteryState;
 use dbus_client::{DaemonEvent, DbusClient, DeviceConfig, DeviceInfo, RunCommand};
+use device_appearance::DeviceAppearance;
+use device_filter::{DeviceFilter, PairingFilter, CAPABILITY_FILTERS};
+use history::{HistoryEvent, HistoryKind, HistoryStore};
 use std::collections::HashMap;
+use transfers::TransferQueue;
 
 const APP_ID: &str = "com.system76.CosmicConnectManager";
 
@@ -90,7 +115,7 @@ pub enum DeviceCategory {
 impl DeviceCategory {
     fn from_device_type(device_type: &str) -> Self {
         match device_type {
-            "phone" | "tablet" => DeviceCategory::Mobile,
+            "phone" | "smartphone" | "tablet" => DeviceCategory::Mobile,
             "desktop" | "laptop" => DeviceCategory::Desktop,
             _ => DeviceCategory::Unknown,
         }
@@ -107,6 +132,7 @@ pub enum DeviceAction {
     Screenshot,
     SystemInfo,
     Settings,
+    Disconnect,
 
     // Mobile-only actions (phone/tablet)
     Find,
@@ -138,6 +164,7 @@ impl DeviceAction {
             "screenshot" => Some(DeviceAction::Screenshot),
             "system-info" => Some(DeviceAction::SystemInfo),
             "settings" => Some(DeviceAction::Settings),
+            "disconnect" => Some(DeviceAction::Disconnect),
             "find" => Some(DeviceAction::Find),
             "sms" => Some(DeviceAction::Sms),
             "mute-call" => Some(DeviceAction::MuteCall),
@@ -165,7 +192,8 @@ impl DeviceAction {
             | DeviceAction::RemoteInput
             | DeviceAction::Screenshot
             | DeviceAction::SystemInfo
-            | DeviceAction::Settings => true,
+            | DeviceAction::Settings
+            | DeviceAction::Disconnect => true,
 
             // Mobile-only actions
             DeviceAction::Find
@@ -202,6 +230,7 @@ impl DeviceAction {
             DeviceAction::Screenshot => "applets-screenshooter-symbolic",
             DeviceAction::SystemInfo => "computer-symbolic",
             DeviceAction::Settings => "preferences-system-symbolic",
+            DeviceAction::Disconnect => "network-offline-symbolic",
             DeviceAction::Find => "find-location-symbolic",
             DeviceAction::Sms => "mail-message-new-symbolic",
             DeviceAction::MuteCall => "audio-volume-muted-symbolic",
@@ -223,11 +252,12 @@ impl DeviceAction {
         match self {
             DeviceAction::Ping => "Send ping",
             DeviceAction::SendFile => "Send file",
-            DeviceAction::Clipboard => "Sync clipboard",
+            DeviceAction::Clipboard => "Send clipboard",
             DeviceAction::RemoteInput => "Remote keyboard/mouse",
             DeviceAction::Screenshot => "Take screenshot",
             DeviceAction::SystemInfo => "System information",
             DeviceAction::Settings => "Device settings",
+            DeviceAction::Disconnect => "Disconnect",
             DeviceAction::Find => "Find device",
             DeviceAction::Sms => "Send SMS",
             DeviceAction::MuteCall => "Mute call",
@@ -274,6 +304,7 @@ fn get_available_actions(device: &DeviceInfo) -> Vec<DeviceAction> {
         DeviceAction::RemoteInput,
         DeviceAction::MediaControl,
         DeviceAction::SystemInfo,
+        DeviceAction::Disconnect,
     ];
 
     all_actions
@@ -282,12 +313,12 @@ fn get_available_actions(device: &DeviceInfo) -> Vec<DeviceAction> {
         .collect()
 }
 
-fn device_icon_name(device_type: &str) -> &'static str {
-    match device_type {
-        "phone" => "phone-symbolic",
-        "tablet" => "tablet-symbolic",
-        "desktop" | "laptop" => "computer-symbolic",
-        _ => "network-wireless-symbolic",
+/// Icon for a transferred file
+fn transfer_icon(filename: &str) -> &'static str {
+    if filename.ends_with(".jpg") || filename.ends_with(".png") {
+        "image-x-generic-symbolic"
+    } else {
+        "text-x-generic-symbolic"
     }
 }
 
@@ -301,6 +332,21 @@ fn connection_status(device: &DeviceInfo) -> &'static str {
     }
 }
 
+/// Split a user-entered "ip" or "ip:port" into the address and port
+///
+/// IPv6 addresses with a port are written as "[::1]:1716". Port 0 leaves the
+/// choice to the daemon.
+fn parse_device_address(input: &str) -> Option<(String, u16)> {
+    let input = input.trim();
+    if let Ok(addr) = input.parse::<std::net::SocketAddr>() {
+        return Some((addr.ip().to_string(), addr.port()));
+    }
+    input
+        .parse::<std::net::IpAddr>()
+        .ok()
+        .map(|ip| (ip.to_string(), 0))
+}
+
 /// Convert a file:// URI to a filesystem path
 ///
 /// Desktop entries use %U which passes file:// URIs. This function
@@ -378,34 +424,21 @@ pub struct TransferInfo {
     pub direction: String,
 }
 
-#[derive(Debug, Clone)]
-pub struct CompletedTransfer {
-    pub filename: String,
-    pub size: u64,
-    pub timestamp: chrono::DateTime<chrono::Local>,
-    pub success: bool,
-}
-
-#[derive(Debug, Clone)]
-pub struct HistoryEvent {
-    pub icon_name: String,
-    pub event_type: String,
-    pub description: String,
-    pub timestamp: chrono::DateTime<chrono::Local>,
-}
-
 #[derive(Debug, Clone)]
 pub enum Message {
     NavigateTo(Page),
     SelectDevice(String),
+    SetDeviceFilter(DeviceFilter),
     DevicesUpdated(HashMap<String, DeviceInfo>),
     DeviceConfigLoaded(String, DeviceConfig),
     ExecuteAction(String, DeviceAction),
     DbusReady(DbusClient),
-    MediaPlayPause(String),
-    MediaNext(String),
-    MediaPrevious(String),
+    MediaControl(String, media::MediaControl),
+    MediaSeekDrag(String, f64),
     CancelTransfer(String),
+    RetryTransfer(String),
+    RefreshTransfers,
+    TransfersLoaded(Vec<dbus_client::TransferItem>),
     ClearHistory,
     ToggleAutoStart(bool),
     ToggleNotifications(bool),
@@ -420,6 +453,7 @@ pub enum Message {
     TransferProgressUpdate(TransferInfo),
     TransferCompleted(String, String, String, bool, String),
     AddHistoryEvent(HistoryEvent),
+    OpenHistoryUrl(String),
     RefreshDevices,
     RefreshMprisPlayers,
     BatteryStatusLoaded(String, dbus_client::BatteryStatus),
@@ -444,7 +478,17 @@ pub enum Message {
     DeviceSettingsLoaded(DeviceConfig),
     SaveDeviceSettings,
     DeviceNicknameChanged(String),
+    DeviceAccentColorChanged(String),
+    DeviceCustomIconChanged(String),
     DevicePluginToggled(String, bool),
+    // Network settings messages
+    ManualAddressChanged(String),
+    AddDeviceByAddress,
+    DeviceAddedByAddress,
+    // Security settings messages
+    PairedCertificatesLoaded(Vec<dbus_client::PairedCertificate>),
+    ForgetDevice(String),
+    DeviceForgotten(String),
     // Remote input dialog messages
     OpenRemoteInputDialog(String),
     CloseRemoteInputDialog,
@@ -462,6 +506,8 @@ pub enum Message {
     // CLI args processing (Issue #143 - Desktop icons)
     ProcessPendingCliArgs,
     SendFilesToDevice(String, Vec<String>), // device_id, file_paths
+    // D-Bus activation from the daemon
+    Activated(activation::ActivationRequest),
     None,
 }
 
@@ -473,6 +519,7 @@ pub struct CosmicConnectManager {
     device_configs: HashMap<String, DeviceConfig>,
     battery_status: HashMap<String, dbus_client::BatteryStatus>,
     selected_device: Option<String>,
+    device_filter: DeviceFilter,
     _initial_device: Option<String>,
     _initial_action: Option<DeviceAction>,
     // CLI args for desktop icon integration (Issue #143)
@@ -485,9 +532,9 @@ pub struct CosmicConnectManager {
     show_notifications: bool,
     plugin_states: HashMap<String, bool>,
     mpris_players: Vec<(String, Option<dbus_client::PlayerState>)>,
-    active_transfers: HashMap<String, TransferInfo>,
-    completed_transfers: Vec<CompletedTransfer>,
-    history_events: Vec<HistoryEvent>,
+    seek_drag: Option<media::SeekDrag>,
+    transfer_queue: TransferQueue,
+    history_events: HistoryStore,
     _event_rx: Option<tokio::sync::mpsc::UnboundedReceiver<DaemonEvent>>,
     show_runcommand_dialog: bool,
     runcommand_device_id: Option<String>,
@@ -507,7 +554,13 @@ pub struct CosmicConnectManager {
     settings_device_id: Option<String>,
     device_settings_config: Option<DeviceConfig>,
     device_settings_nickname: String,
+    device_settings_accent_color: String,
+    device_settings_custom_icon: String,
     device_settings_plugins: HashMap<String, bool>,
+    // Network settings state
+    manual_device_address: String,
+    // Security settings state
+    paired_certificates: Vec<dbus_client::PairedCertificate>,
     // Remote input dialog state
     show_remote_input_dialog: bool,
     remote_input_device_id: Option<String>,
@@ -525,6 +578,7 @@ impl CosmicConnectManager {
             Page::MediaPlayers,
             Page::Transfers,
             Page::History,
+            Page::Commands,
             Page::Settings,
         ];
 
@@ -578,11 +632,17 @@ impl CosmicConnectManager {
             Page::MediaPlayers => self.media_players_view(),
             Page::Transfers => self.transfers_view(),
             Page::History => self.history_view(),
+            Page::Commands => self.commands_view(),
             Page::Settings => self.settings_view(),
         }
     }
 
-    #[allow(dead_code)]
+    fn commands_view(&self) -> Element<'_, Message> {
+        // Command editing is not implemented yet; the page exists so the
+        // phone's setup request has somewhere to land
+        self.placeholder_view(Page::Commands.title(), Page::Commands.icon_name())
+    }
+
     fn placeholder_view(
         &self,
         title: &'static str,
@@ -603,12 +663,95 @@ impl CosmicConnectManager {
         .into()
     }
 
+    fn device_filter_bar(&self) -> Element<'_, Message> {
+        use cosmic::widget::text_input;
+
+        let filter = &self.device_filter;
+
+        let search = text_input("Search devices", &filter.query)
+            .on_input({
+                let filter = filter.clone();
+                move |query| {
+                    Message::SetDeviceFilter(DeviceFilter {
+                        query,
+                        ..filter.clone()
+                    })
+                }
+            })
+            .padding(theme::active().cosmic().space_xs())
+            .width(Length::Fill);
+
+        let mut pairing_row = row::with_capacity(PairingFilter::ALL.len())
+            .spacing(theme::active().cosmic().space_xxs());
+        for pairing in PairingFilter::ALL {
+            let class = if filter.pairing == pairing {
+                theme::Button::Suggested
+            } else {
+                theme::Button::Standard
+            };
+            let message = Message::SetDeviceFilter(DeviceFilter {
+                pairing,
+                ..filter.clone()
+            });
+            pairing_row =
+                pairing_row.push(button::text(pairing.label()).class(class).on_press(message));
+        }
+
+        let mut capability_row = row::with_capacity(CAPABILITY_FILTERS.len())
+            .spacing(theme::active().cosmic().space_xxs());
+        for (capability, label) in CAPABILITY_FILTERS {
+            let selected = filter.capability.as_deref() == Some(*capability);
+            let (class, capability) = if selected {
+                (theme::Button::Suggested, None)
+            } else {
+                (theme::Button::Standard, Some(capability.to_string()))
+            };
+            let message = Message::SetDeviceFilter(DeviceFilter {
+                capability,
+                ..filter.clone()
+            });
+            capability_row =
+                capability_row.push(button::text(*label).class(class).on_press(message));
+        }
+
+        let hide_offline = toggler(filter.hide_offline).on_toggle({
+            let filter = filter.clone();
+            move |hide_offline| {
+                Message::SetDeviceFilter(DeviceFilter {
+                    hide_offline,
+                    ..filter.clone()
+                })
+            }
+        });
+
+        column::with_capacity(2)
+            .spacing(theme::active().cosmic().space_xs())
+            .push(
+                row::with_capacity(4)
+                    .spacing(theme::active().cosmic().space_s())
+                    .align_y(Alignment::Center)
+                    .push(search)
+                    .push(pairing_row)
+                    .push(text("Hide offline").size(14))
+                    .push(hide_offline),
+            )
+            .push(capability_row)
+            .into()
+    }
+
     fn device_list_view(&self) -> Element<'_, Message> {
         let mut connected_devices = Vec::new();
         let mut available_devices = Vec::new();
         let mut offline_devices = Vec::new();
 
+        let listed = self
+            .device_filter
+            .apply(&self.devices, &self.device_configs);
+
         for (device_id, device) in &self.devices {
+            if !listed.contains(&device_id.as_str()) {
+                continue;
+            }
             let config = self.device_configs.get(device_id);
             let is_selected = self.selected_device.as_ref() == Some(device_id);
             let card = self.device_card(device_id, device, config, is_selected);
@@ -622,10 +765,14 @@ impl CosmicConnectManager {
             }
         }
 
-        let mut sections = column::with_capacity(6)
+        let mut sections = column::with_capacity(7)
             .spacing(theme::active().cosmic().space_m())
             .padding(theme::active().cosmic().space_m());
 
+        if !self.devices.is_empty() {
+            sections = sections.push(self.device_filter_bar());
+        }
+
         if !connected_devices.is_empty() {
             sections = sections.push(text("Connected").size(14));
             for device in connected_devices {
@@ -660,6 +807,22 @@ impl CosmicConnectManager {
                 .center_x(Length::Fill)
                 .center_y(Length::Fill),
             );
+        } else if listed.is_empty() {
+            sections = sections.push(
+                container(
+                    column::with_capacity(3)
+                        .spacing(theme::active().cosmic().space_s())
+                        .align_x(Alignment::Center)
+                        .push(icon::from_name("edit-find-symbolic").size(64))
+                        .push(text("No matching devices").size(18))
+                        .push(
+                            button::text("Clear filter")
+                                .on_press(Message::SetDeviceFilter(DeviceFilter::default())),
+                        ),
+                )
+                .center_x(Length::Fill)
+                .center_y(Length::Fill),
+            );
         }
 
         container(sections)
@@ -706,70 +869,146 @@ impl CosmicConnectManager {
         player_id: &str,
         state: Option<&dbus_client::PlayerState>,
     ) -> Element<'_, Message> {
-        let player_icon = icon::from_name("multimedia-player-symbolic").size(48);
+        use media::MediaControl;
 
-        let (player_name, track_info_text, play_pause_icon) = if let Some(state) = state {
-            let track = if let Some(title) = &state.metadata.title {
-                if let Some(artist) = &state.metadata.artist {
-                    format!("{} - {}", artist, title)
-                } else {
-                    title.clone()
-                }
-            } else {
-                "No track playing".to_string()
-            };
+        let Some(state) = state else {
+            let header_row = row::with_capacity(2)
+                .spacing(theme::active().cosmic().space_s())
+                .align_y(Alignment::Center)
+                .push(icon::from_name("multimedia-player-symbolic").size(48))
+                .push(
+                    column::with_capacity(2)
+                        .spacing(theme::active().cosmic().space_xxs())
+                        .push(text(player_id.to_string()).size(16))
+                        .push(text("Loading...").size(12)),
+                );
+            return container(header_row)
+                .padding(theme::active().cosmic().space_s())
+                .width(Length::Fill)
+                .into();
+        };
 
-            let icon_name = match state.playback_status {
-                dbus_client::PlaybackStatus::Playing => "media-playback-pause-symbolic",
-                _ => "media-playback-start-symbolic",
-            };
+        let control = |control: MediaControl| Message::MediaControl(player_id.to_string(), control);
 
-            (state.identity.clone(), track, icon_name)
-        } else {
-            (
-                player_id.to_string(),
-                "Loading...".to_string(),
-                "media-playback-start-symbolic",
-            )
+        // Album art, when the player has it on disk
+        let art: Element<'_, Message> = match state
+            .metadata
+            .album_art_url
+            .as_deref()
+            .and_then(media::album_art_path)
+        {
+            Some(path) => {
+                cosmic::widget::image(cosmic::iced::widget::image::Handle::from_path(path))
+                    .width(Length::Fixed(96.0))
+                    .height(Length::Fixed(96.0))
+                    .content_fit(cosmic::iced::ContentFit::Cover)
+                    .into()
+            }
+            None => container(icon::from_name("audio-x-generic-symbolic").size(64))
+                .center_x(Length::Fixed(96.0))
+                .center_y(Length::Fixed(96.0))
+                .into(),
         };
 
-        let name_text = text(player_name).size(16);
-        let track_info = text(track_info_text).size(12);
+        let title = state
+            .metadata
+            .title
+            .as_deref()
+            .unwrap_or("No track playing");
+        let subtitle = match (&state.metadata.artist, &state.metadata.album) {
+            (Some(artist), Some(album)) => format!("{} \u{2014} {}", artist, album),
+            (Some(artist), None) => artist.clone(),
+            (None, Some(album)) => album.clone(),
+            (None, None) => String::new(),
+        };
 
-        let info_column = column::with_capacity(2)
+        let info_column = column::with_capacity(3)
             .spacing(theme::active().cosmic().space_xxs())
-            .push(name_text)
-            .push(track_info);
+            .push(text(state.identity.clone()).size(12))
+            .push(text(title.to_string()).size(16))
+            .push(text(subtitle).size(12));
 
         let header_row = row::with_capacity(2)
             .spacing(theme::active().cosmic().space_s())
             .align_y(Alignment::Center)
-            .push(player_icon)
+            .push(art)
             .push(info_column);
 
-        let prev_button = button::icon(icon::from_name("media-skip-backward-symbolic").size(16))
-            .on_press(Message::MediaPrevious(player_id.to_string()))
-            .padding(theme::active().cosmic().space_xxs());
-
-        let play_pause_button = button::icon(icon::from_name(play_pause_icon).size(16))
-            .on_press(Message::MediaPlayPause(player_id.to_string()))
-            .padding(theme::active().cosmic().space_xxs());
-
-        let next_button = button::icon(icon::from_name("media-skip-forward-symbolic").size(16))
-            .on_press(Message::MediaNext(player_id.to_string()))
-            .padding(theme::active().cosmic().space_xxs());
+        let play_pause_icon = match state.playback_status {
+            dbus_client::PlaybackStatus::Playing => "media-playback-pause-symbolic",
+            _ => "media-playback-start-symbolic",
+        };
+        let transport_button = |icon_name: &'static str, enabled: bool, action: MediaControl| {
+            button::icon(icon::from_name(icon_name).size(16))
+                .on_press_maybe(enabled.then(|| control(action)))
+                .padding(theme::active().cosmic().space_xxs())
+        };
 
-        let controls_row = row::with_capacity(3)
+        let controls_row = row::with_capacity(4)
             .spacing(theme::active().cosmic().space_xs())
-            .push(prev_button)
-            .push(play_pause_button)
-            .push(next_button);
+            .align_y(Alignment::Center)
+            .push(transport_button(
+                "media-skip-backward-symbolic",
+                state.can_go_previous,
+                MediaControl::Previous,
+            ))
+            .push(transport_button(
+                play_pause_icon,
+                state.can_play || state.can_pause,
+                MediaControl::PlayPause,
+            ))
+            .push(transport_button(
+                "media-playback-stop-symbolic",
+                state.playback_status != dbus_client::PlaybackStatus::Stopped,
+                MediaControl::Stop,
+            ))
+            .push(transport_button(
+                "media-skip-forward-symbolic",
+                state.can_go_next,
+                MediaControl::Next,
+            ));
 
-        let card_content = column::with_capacity(2)
+        let mut card_content = column::with_capacity(4)
             .spacing(theme::active().cosmic().space_s())
             .push(header_row)
             .push(controls_row);
 
+        // Seek bar, showing the dragged position until it is released
+        if let Some((position, length)) = media::seek_range(state) {
+            let position = match &self.seek_drag {
+                Some(drag) if drag.player == player_id => drag.seconds,
+                _ => position,
+            };
+            let player = player_id.to_string();
+            let seek_bar = cosmic::widget::slider(0.0..=length, position, move |seconds| {
+                Message::MediaSeekDrag(player.clone(), seconds)
+            })
+            .step(1.0)
+            .on_release(control(MediaControl::SeekTo(position)));
+
+            card_content = card_content.push(
+                row::with_capacity(3)
+                    .spacing(theme::active().cosmic().space_xs())
+                    .align_y(Alignment::Center)
+                    .push(text(media::format_time(position)).size(12))
+                    .push(seek_bar)
+                    .push(text(media::format_time(length)).size(12)),
+            );
+        }
+
+        let player = player_id.to_string();
+        let volume_slider = cosmic::widget::slider(0.0..=1.0, state.volume, move |volume| {
+            Message::MediaControl(player.clone(), MediaControl::Volume(volume))
+        })
+        .step(0.01);
+        card_content = card_content.push(
+            row::with_capacity(2)
+                .spacing(theme::active().cosmic().space_xs())
+                .align_y(Alignment::Center)
+                .push(icon::from_name("audio-volume-high-symbolic").size(16))
+                .push(volume_slider),
+        );
+
         container(card_content)
             .padding(theme::active().cosmic().space_s())
             .width(Length::Fill)
@@ -794,16 +1033,25 @@ impl CosmicConnectManager {
             .push(info_column);
 
         let prev_button = button::icon(icon::from_name("media-skip-backward-symbolic").size(16))
-            .on_press(Message::MediaPrevious(player_id.to_string()))
+            .on_press(Message::MediaControl(
+                player_id.to_string(),
+                media::MediaControl::Previous,
+            ))
             .padding(theme::active().cosmic().space_xxs());
 
         let play_pause_button =
             button::icon(icon::from_name("media-playback-start-symbolic").size(16))
-                .on_press(Message::MediaPlayPause(player_id.to_string()))
+                .on_press(Message::MediaControl(
+                    player_id.to_string(),
+                    media::MediaControl::PlayPause,
+                ))
                 .padding(theme::active().cosmic().space_xxs());
 
         let next_button = button::icon(icon::from_name("media-skip-forward-symbolic").size(16))
-            .on_press(Message::MediaNext(player_id.to_string()))
+            .on_press(Message::MediaControl(
+                player_id.to_string(),
+                media::MediaControl::Next,
+            ))
             .padding(theme::active().cosmic().space_xxs());
 
         let controls_row = row::with_capacity(3)
@@ -828,37 +1076,24 @@ impl CosmicConnectManager {
             .spacing(theme::active().cosmic().space_m())
             .padding(theme::active().cosmic().space_m());
 
-        let active_count = self.active_transfers.len();
-        content = content.push(text(format!("Active Transfers ({})", active_count)).size(16));
+        let running: Vec<_> = self.transfer_queue.running().collect();
+        content = content.push(text(format!("Active Transfers ({})", running.len())).size(16));
 
-        if !self.active_transfers.is_empty() {
-            for (transfer_id, info) in &self.active_transfers {
-                let progress = if info.total > 0 {
-                    ((info.current as f64 / info.total as f64) * 100.0) as u8
-                } else {
-                    0
-                };
-
-                let speed = if info.current > 0 {
-                    format!("{:.1} MB/s", info.current as f64 / 1_000_000.0)
+        if !running.is_empty() {
+            for item in running {
+                let speed = if item.state == "pending" {
+                    "Waiting for device".to_string()
+                } else if item.speed > 0 {
+                    format!("{:.1} MB/s", item.speed as f64 / 1_000_000.0)
                 } else {
                     "Calculating...".to_string()
                 };
 
-                let icon_name =
-                    if info.filename.ends_with(".pdf") || info.filename.ends_with(".txt") {
-                        "text-x-generic-symbolic"
-                    } else if info.filename.ends_with(".jpg") || info.filename.ends_with(".png") {
-                        "image-x-generic-symbolic"
-                    } else {
-                        "text-x-generic-symbolic"
-                    };
-
                 content = content.push(self.transfer_card(
-                    transfer_id,
-                    &info.filename,
-                    icon_name,
-                    progress,
+                    &item.transfer_id,
+                    &item.filename,
+                    transfer_icon(&item.filename),
+                    item.progress(),
                     &speed,
                     true,
                 ));
@@ -867,43 +1102,37 @@ impl CosmicConnectManager {
             content = content.push(text("No active transfers").size(14));
         }
 
-        if !self.completed_transfers.is_empty() {
+        let finished: Vec<_> = self.transfer_queue.finished().collect();
+        if !finished.is_empty() {
             content = content.push(vertical_space().height(theme::active().cosmic().space_m()));
-            content = content
-                .push(text(format!("Completed ({})", self.completed_transfers.len())).size(16));
+            content = content.push(text(format!("Finished ({})", finished.len())).size(16));
 
-            let mut completed_col = column::with_capacity(self.completed_transfers.len())
-                .spacing(theme::active().cosmic().space_xs());
-
-            for transfer in &self.completed_transfers {
-                let icon_name = if transfer.filename.ends_with(".pdf")
-                    || transfer.filename.ends_with(".txt")
-                {
-                    "text-x-generic-symbolic"
-                } else if transfer.filename.ends_with(".jpg") || transfer.filename.ends_with(".png")
-                {
-                    "image-x-generic-symbolic"
-                } else {
-                    "text-x-generic-symbolic"
-                };
+            let mut finished_col =
+                column::with_capacity(finished.len()).spacing(theme::active().cosmic().space_xs());
 
-                let size_str = if transfer.size > 0 {
-                    format!("{:.1} MB", transfer.size as f64 / 1_000_000.0)
+            for item in finished {
+                let size_str = if item.total_bytes > 0 {
+                    format!("{:.1} MB", item.total_bytes as f64 / 1_000_000.0)
                 } else {
                     "Unknown".to_string()
                 };
 
-                let time_str = transfer.timestamp.format("%H:%M").to_string();
+                let status = match item.state.as_str() {
+                    "completed" => "Completed",
+                    "cancelled" => "Cancelled",
+                    _ => "Failed",
+                };
 
-                completed_col = completed_col.push(self.completed_transfer_item(
-                    &transfer.filename,
-                    icon_name,
+                finished_col = finished_col.push(self.completed_transfer_item(
+                    &item.filename,
+                    transfer_icon(&item.filename),
                     &size_str,
-                    &time_str,
+                    status,
+                    item.can_retry().then_some(item.transfer_id.as_str()),
                 ));
             }
 
-            content = content.push(completed_col);
+            content = content.push(finished_col);
         }
 
         container(content)
@@ -966,14 +1195,15 @@ impl CosmicConnectManager {
         filename: &str,
         icon_name: &str,
         size: &str,
-        time: &str,
+        status: &str,
+        retry_id: Option<&str>,
     ) -> Element<'_, Message> {
         let file_icon = icon::from_name(icon_name).size(20);
         let filename_text = text(filename.to_string()).size(14);
         let size_text = text(size.to_string()).size(12);
-        let time_text = text(time.to_string()).size(12);
+        let status_text = text(status.to_string()).size(12);
 
-        let item_row = row::with_capacity(5)
+        let mut item_row = row::with_capacity(7)
             .spacing(theme::active().cosmic().space_s())
             .align_y(Alignment::Center)
             .push(file_icon)
@@ -981,7 +1211,15 @@ impl CosmicConnectManager {
             .push(text("-").size(12))
             .push(size_text)
             .push(text("-").size(12))
-            .push(time_text);
+            .push(status_text);
+
+        if let Some(transfer_id) = retry_id {
+            item_row = item_row.push(
+                button::text("Retry")
+                    .on_press(Message::RetryTransfer(transfer_id.to_string()))
+                    .padding(theme::active().cosmic().space_xxs()),
+            );
+        }
 
         container(item_row)
             .padding(theme::active().cosmic().space_xs())
@@ -1024,7 +1262,7 @@ impl CosmicConnectManager {
             let mut events_list = column::with_capacity(self.history_events.len())
                 .spacing(theme::active().cosmic().space_xs());
 
-            for event in &self.history_events {
+            for event in self.history_events.iter() {
                 let timestamp_str = {
                     let now = chrono::Local::now();
                     let diff = now.signed_duration_since(event.timestamp);
@@ -1045,6 +1283,7 @@ impl CosmicConnectManager {
                     &event.event_type,
                     &event.description,
                     &timestamp_str,
+                    event.url(),
                 ));
             }
 
@@ -1063,6 +1302,7 @@ impl CosmicConnectManager {
         event_type: &str,
         description: &str,
         timestamp: &str,
+        url: Option<&str>,
     ) -> Element<'_, Message> {
         let event_icon = icon::from_name(icon_name).size(20);
 
@@ -1084,10 +1324,19 @@ impl CosmicConnectManager {
             .push(event_icon)
             .push(event_info);
 
-        container(item_content)
+        let item = container(item_content)
             .padding(theme::active().cosmic().space_s())
-            .width(Length::Fill)
-            .into()
+            .width(Length::Fill);
+
+        // Handed-off links reopen when clicked
+        match url {
+            Some(url) => button::custom(item)
+                .class(theme::Button::Text)
+                .on_press(Message::OpenHistoryUrl(url.to_string()))
+                .width(Length::Fill)
+                .into(),
+            None => item.into(),
+        }
     }
 
     fn settings_view(&self) -> Element<'_, Message> {
@@ -1171,12 +1420,138 @@ impl CosmicConnectManager {
 
         content = content.push(plugin_section);
 
+        content = content.push(vertical_space().height(theme::active().cosmic().space_m()));
+        content = content.push(text("Network").size(18));
+        content = content.push(self.manual_device_section());
+
+        content = content.push(vertical_space().height(theme::active().cosmic().space_m()));
+        content = content.push(text("Security").size(18));
+        content = content.push(self.security_section());
+
         container(content)
             .width(Length::Fill)
             .height(Length::Fill)
             .into()
     }
 
+    /// Field to add a device by address when discovery doesn't find it
+    fn manual_device_section(&self) -> Element<'_, Message> {
+        let address = self.manual_device_address.trim();
+        let add_button = button::text("Add")
+            .on_press_maybe((!address.is_empty()).then_some(Message::AddDeviceByAddress));
+
+        let section = column::with_capacity(2)
+            .spacing(theme::active().cosmic().space_xs())
+            .push(text("Add device by IP").size(14))
+            .push(
+                row::with_capacity(2)
+                    .spacing(theme::active().cosmic().space_s())
+                    .align_y(Alignment::Center)
+                    .push(
+                        text_input(
+                            "IP address, e.g. 192.168.1.20 or 192.168.1.20:1716",
+                            &self.manual_device_address,
+                        )
+                        .on_input(Message::ManualAddressChanged)
+                        .on_submit(|_| Message::AddDeviceByAddress)
+                        .padding(theme::active().cosmic().space_s()),
+                    )
+                    .push(add_button),
+            );
+
+        container(section)
+            .padding(theme::active().cosmic().space_s())
+            .width(Length::Fill)
+            .into()
+    }
+
+    /// Paired devices with their pinned certificates and a button to forget each
+    fn security_section(&self) -> Element<'_, Message> {
+        if self.paired_certificates.is_empty() {
+            return container(text("No paired devices").size(14))
+                .padding(theme::active().cosmic().space_s())
+                .width(Length::Fill)
+                .into();
+        }
+
+        let mut section = column::with_capacity(self.paired_certificates.len())
+            .spacing(theme::active().cosmic().space_xs());
+
+        for certificate in &self.paired_certificates {
+            let name = self
+                .devices
+                .get(&certificate.device_id)
+                .map(|device| device.name.as_str())
+                .unwrap_or(certificate.device_id.as_str());
+            let paired_at = chrono::DateTime::from_timestamp(certificate.paired_at as i64, 0)
+                .filter(|_| certificate.paired_at > 0)
+                .map(|date| {
+                    format!(
+                        "Paired {}",
+                        date.with_timezone(&chrono::Local).format("%b %d %Y, %H:%M")
+                    )
+                })
+                .unwrap_or_else(|| "Pairing date unknown".to_string());
+
+            let info = column::with_capacity(3)
+                .spacing(theme::active().cosmic().space_xxs())
+                .push(text(name).size(14))
+                .push(text(&certificate.fingerprint).size(12))
+                .push(text(paired_at).size(12));
+
+            let device_row = row::with_capacity(3)
+                .spacing(theme::active().cosmic().space_s())
+                .align_y(Alignment::Center)
+                .push(info)
+                .push(horizontal_space())
+                .push(
+                    button::destructive("Forget")
+                        .on_press(Message::ForgetDevice(certificate.device_id.clone())),
+                );
+
+            section = section.push(
+                container(device_row)
+                    .padding(theme::active().cosmic().space_s())
+                    .width(Length::Fill),
+            );
+        }
+
+        section.into()
+    }
+
+    /// Fetch the pinned certificates of paired devices from the daemon
+    fn load_transfers(&self) -> Task<Message> {
+        let Some(client) = self.dbus_client.clone() else {
+            return Task::none();
+        };
+
+        cosmic::task::future(async move {
+            match client.list_transfers().await {
+                Ok(items) => Message::TransfersLoaded(items),
+                Err(e) => {
+                    tracing::error!("Failed to load transfers: {}", e);
+                    Message::None
+                }
+            }
+        })
+    }
+
+    fn load_paired_certificates(&self) -> Task<Message> {
+        let Some(client) = self.dbus_client.clone() else {
+            return Task::none();
+        };
+
+        cosmic::task::future(async move {
+            match client.list_paired_certificates().await {
+                Ok(certificates) => Message::PairedCertificatesLoaded(certificates),
+                Err(e) => {
+                    tracing::error!("Failed to load paired certificates: {}", e);
+                    Message::None
+                }
+            }
+        })
+    }
+
     fn device_card<'a>(
         &self,
         device_id: &'a str,
@@ -1184,11 +1559,15 @@ impl CosmicConnectManager {
         config: Option<&'a DeviceConfig>,
         is_selected: bool,
     ) -> Element<'a, Message> {
-        let device_icon = icon::from_name(device_icon_name(&device.device_type)).size(48);
+        let appearance = DeviceAppearance::resolve(&device.device_type, config);
+        let device_icon = icon::from_name(appearance.icon_name).size(48);
         let display_name = config
             .and_then(|c| c.nickname.as_deref())
             .unwrap_or(&device.name);
-        let name_text = text(display_name).size(16);
+        let mut name_text = text(display_name).size(16);
+        if let Some(accent) = appearance.accent {
+            name_text = name_text.class(theme::Text::Color(accent));
+        }
         let status_text = connection_status(device);
         let status_badge = text(status_text).size(12);
 
@@ -1198,23 +1577,13 @@ impl CosmicConnectManager {
             .push(status_badge);
 
         if let Some(battery) = self.battery_status.get(device_id) {
-            let battery_icon = if battery.is_charging {
-                "battery-charging-symbolic"
-            } else if battery.level > 80 {
-                "battery-full-symbolic"
-            } else if battery.level > 50 {
-                "battery-good-symbolic"
-            } else if battery.level > 20 {
-                "battery-low-symbolic"
-            } else {
-                "battery-caution-symbolic"
-            };
+            let battery = BatteryState::new(battery.level, battery.is_charging);
 
             let battery_row = row::with_capacity(2)
                 .spacing(theme::active().cosmic().space_xxs())
                 .align_y(Alignment::Center)
-                .push(icon::from_name(battery_icon).size(16))
-                .push(text(format!("{}%", battery.level)).size(12));
+                .push(icon::from_name(battery.icon_name()).size(16))
+                .push(text(battery.label()).size(12));
 
             info_column = info_column.push(battery_row);
         }
@@ -1488,6 +1857,29 @@ impl CosmicConnectManager {
                 ),
         );
 
+        // Appearance
+        content = content.push(
+            column::with_capacity(3)
+                .spacing(theme::active().cosmic().space_xxs())
+                .push(text("Appearance").size(14))
+                .push(
+                    text_input(
+                        "Accent color, e.g. #3584e4",
+                        &self.device_settings_accent_color,
+                    )
+                    .on_input(Message::DeviceAccentColorChanged)
+                    .padding(theme::active().cosmic().space_s()),
+                )
+                .push(
+                    text_input(
+                        "Icon name, e.g. phone-symbolic",
+                        &self.device_settings_custom_icon,
+                    )
+                    .on_input(Message::DeviceCustomIconChanged)
+                    .padding(theme::active().cosmic().space_s()),
+                ),
+        );
+
         // Plugin toggles
         content = content.push(text("Plugins").size(16));
 
@@ -1844,9 +2236,9 @@ impl Application for CosmicConnectManager {
                 show_notifications: true,
                 plugin_states,
                 mpris_players: Vec::new(),
-                active_transfers: HashMap::new(),
-                completed_transfers: Vec::new(),
-                history_events: Vec::new(),
+                seek_drag: None,
+                transfer_queue: TransferQueue::default(),
+                history_events: HistoryStore::default(),
                 _event_rx: None,
                 show_runcommand_dialog: false,
                 runcommand_device_id: None,
@@ -1866,7 +2258,13 @@ impl Application for CosmicConnectManager {
                 settings_device_id: None,
                 device_settings_config: None,
                 device_settings_nickname: String::new(),
+                device_settings_accent_color: String::new(),
+                device_settings_custom_icon: String::new(),
                 device_settings_plugins: HashMap::new(),
+                // Network settings
+                manual_device_address: String::new(),
+                // Security settings
+                paired_certificates: Vec::new(),
                 // Remote input dialog
                 show_remote_input_dialog: false,
                 remote_input_device_id: None,
@@ -1874,13 +2272,34 @@ impl Application for CosmicConnectManager {
                 show_power_dialog: false,
                 power_device_id: None,
                 status_message: None,
+                device_filter: DeviceFilter::default(),
             },
             connect_task,
         )
     }
 
     fn subscription(&self) -> cosmic::iced::Subscription<Self::Message> {
-        cosmic::iced::Subscription::none()
+        struct ActivationSubscription;
+        struct MediaRefreshSubscription;
+
+        let activation = cosmic::iced::Subscription::run_with_id(
+            std::any::TypeId::of::<ActivationSubscription>(),
+            cosmic::iced::futures::StreamExt::map(activation::requests(APP_ID), Message::Activated),
+        );
+
+        // Keep positions and tracks current while the Media page is shown
+        if self.active_page != Page::MediaPlayers || !self.dbus_ready {
+            return activation;
+        }
+        let media_refresh = cosmic::iced::Subscription::run_with_id(
+            std::any::TypeId::of::<MediaRefreshSubscription>(),
+            futures::stream::unfold((), |()| async {
+                tokio::time::sleep(media::REFRESH_INTERVAL).await;
+                Some((Message::RefreshMprisPlayers, ()))
+            }),
+        );
+
+        cosmic::iced::Subscription::batch([activation, media_refresh])
     }
 
     fn header_start(&self) -> Vec<Element<'_, Self::Message>> {
@@ -1950,7 +2369,11 @@ impl Application for CosmicConnectManager {
         match message {
             Message::NavigateTo(page) => {
                 self.active_page = page;
-                Task::none()
+                match page {
+                    Page::Settings => self.load_paired_certificates(),
+                    Page::Transfers => self.load_transfers(),
+                    _ => Task::none(),
+                }
             }
             Message::SelectDevice(device_id) => {
                 self.selected_device = Some(device_id);
@@ -2077,7 +2500,18 @@ impl Application for CosmicConnectManager {
                     })
                     .collect();
 
-                self.mpris_players = players.into_iter().map(|p| (p, None)).collect();
+                // Known players keep their state until the new one arrives
+                let previous = std::mem::take(&mut self.mpris_players);
+                self.mpris_players = players
+                    .into_iter()
+                    .map(|p| {
+                        let state = previous
+                            .iter()
+                            .find(|(known, _)| known == &p)
+                            .and_then(|(_, state)| state.clone());
+                        (p, state)
+                    })
+                    .collect();
                 Task::batch(tasks)
             }
             Message::MprisPlayerStateLoaded(player, state) => {
@@ -2129,11 +2563,15 @@ impl Application for CosmicConnectManager {
                                 }
                             })
                         }
-                        DeviceAction::Clipboard => {
-                            // Toggle clipboard sync for this device
-                            tracing::info!("Clipboard sync toggled for {}", device_id);
-                            Task::none()
-                        }
+                        DeviceAction::Clipboard => cosmic::task::future(async move {
+                            match client.push_clipboard(&device_id).await {
+                                Ok(()) => Message::ActionSuccess("Clipboard sent".to_string()),
+                                Err(e) => {
+                                    tracing::error!("Failed to push clipboard: {}", e);
+                                    Message::ActionError(format!("Clipboard send failed: {}", e))
+                                }
+                            }
+                        }),
                         DeviceAction::RemoteInput => {
                             // Open remote input dialog
                             self.show_remote_input_dialog = true;
@@ -2217,6 +2655,7 @@ impl Application for CosmicConnectManager {
                                             description: "Streaming 720p @ 30fps to V4L2 loopback"
                                                 .to_string(),
                                             timestamp: chrono::Local::now(),
+                                            kind: HistoryKind::Activity,
                                         })
                                     }
                                     Err(e) => {
@@ -2226,6 +2665,7 @@ impl Application for CosmicConnectManager {
                                             event_type: "Camera failed".to_string(),
                                             description: format!("Failed to start camera: {}", e),
                                             timestamp: chrono::Local::now(),
+                                            kind: HistoryKind::Activity,
                                         })
                                     }
                                 }
@@ -2329,78 +2769,103 @@ impl Application for CosmicConnectManager {
                             self.active_page = Page::MediaPlayers;
                             Task::none()
                         }
+                        // Connection
+                        DeviceAction::Disconnect => cosmic::task::future(async move {
+                            match client.disconnect_device(&device_id).await {
+                                Ok(()) => Message::ActionSuccess("Disconnected".to_string()),
+                                Err(e) => {
+                                    tracing::error!("Failed to disconnect: {}", e);
+                                    Message::ActionError(format!("Disconnect failed: {}", e))
+                                }
+                            }
+                        }),
                     }
                 } else {
                     Task::none()
                 }
             }
-            Message::MediaPlayPause(player) => {
-                if let Some(client) = &self.dbus_client {
-                    let client = client.clone();
-                    cosmic::task::future(async move {
-                        if let Err(e) = client.mpris_control(&player, "PlayPause").await {
-                            tracing::error!("Failed to control media player: {}", e);
+            Message::MediaControl(player, control) => {
+                match control {
+                    media::MediaControl::SeekTo(_) => self.seek_drag = None,
+                    // Follow the slider until the next refresh
+                    media::MediaControl::Volume(volume) => {
+                        if let Some((_, Some(state))) =
+                            self.mpris_players.iter_mut().find(|(p, _)| p == &player)
+                        {
+                            state.volume = volume;
                         }
-                        Message::None
-                    })
-                } else {
-                    Task::none()
+                    }
+                    _ => {}
                 }
-            }
-            Message::MediaNext(player) => {
+
                 if let Some(client) = &self.dbus_client {
                     let client = client.clone();
                     cosmic::task::future(async move {
-                        if let Err(e) = client.mpris_control(&player, "Next").await {
+                        if let Err(e) = control.call().send(&client, &player).await {
                             tracing::error!("Failed to control media player: {}", e);
                         }
-                        Message::None
+                        Message::RefreshMprisPlayers
                     })
                 } else {
                     Task::none()
                 }
             }
-            Message::MediaPrevious(player) => {
+            Message::MediaSeekDrag(player, seconds) => {
+                self.seek_drag = Some(media::SeekDrag { player, seconds });
+                Task::none()
+            }
+            Message::CancelTransfer(transfer_id) => {
                 if let Some(client) = &self.dbus_client {
                     let client = client.clone();
                     cosmic::task::future(async move {
-                        if let Err(e) = client.mpris_control(&player, "Previous").await {
-                            tracing::error!("Failed to control media player: {}", e);
+                        if let Err(e) = client.cancel_transfer(&transfer_id).await {
+                            tracing::error!("Failed to cancel transfer: {}", e);
                         }
-                        Message::None
+                        Message::RefreshTransfers
                     })
                 } else {
                     Task::none()
                 }
             }
-            Message::CancelTransfer(transfer_id) => {
+            Message::RetryTransfer(transfer_id) => {
                 if let Some(client) = &self.dbus_client {
                     let client = client.clone();
                     cosmic::task::future(async move {
-                        if let Err(e) = client.cancel_transfer(&transfer_id).await {
-                            tracing::error!("Failed to cancel transfer: {}", e);
+                        match client.retry_transfer(&transfer_id).await {
+                            Ok(_) => Message::RefreshTransfers,
+                            Err(e) => {
+                                tracing::error!("Failed to retry transfer: {}", e);
+                                Message::ActionError(format!("Retry failed: {}", e))
+                            }
                         }
-                        Message::None
                     })
                 } else {
                     Task::none()
                 }
             }
-            Message::TransferProgressUpdate(info) => {
-                self.active_transfers.insert(info.transfer_id.clone(), info);
+            Message::RefreshTransfers => self.load_transfers(),
+            Message::TransfersLoaded(items) => {
+                self.transfer_queue.set(items);
                 Task::none()
             }
-            Message::TransferCompleted(transfer_id, _device_id, filename, success, _error) => {
-                self.active_transfers.remove(&transfer_id);
-
-                let completed = CompletedTransfer {
-                    filename: filename.clone(),
-                    size: 0,
-                    timestamp: chrono::Local::now(),
-                    success,
+            Message::TransferProgressUpdate(info) => {
+                let progress = dbus_client::TransferItem {
+                    transfer_id: info.transfer_id,
+                    direction: info.direction,
+                    device_id: info.device_id,
+                    filename: info.filename,
+                    bytes_transferred: info.current,
+                    total_bytes: info.total,
+                    state: "active".to_string(),
+                    speed: 0,
                 };
-                self.completed_transfers.push(completed);
-
+                if self.transfer_queue.apply_progress(progress) {
+                    self.load_transfers()
+                } else {
+                    Task::none()
+                }
+            }
+            Message::TransferCompleted(_transfer_id, _device_id, filename, success, _error) => {
                 let event = HistoryEvent {
                     icon_name: if success {
                         "document-save-symbolic".to_string()
@@ -2414,10 +2879,11 @@ impl Application for CosmicConnectManager {
                     },
                     description: filename,
                     timestamp: chrono::Local::now(),
+                    kind: HistoryKind::Activity,
                 };
                 self.history_events.push(event);
 
-                Task::none()
+                self.load_transfers()
             }
             Message::DeviceAdded(device_id, device_info) => {
                 self.devices.insert(device_id.clone(), device_info.clone());
@@ -2427,6 +2893,7 @@ impl Application for CosmicConnectManager {
                     event_type: "Device discovered".to_string(),
                     description: device_info.name,
                     timestamp: chrono::Local::now(),
+                    kind: HistoryKind::Activity,
                 };
                 self.history_events.push(event);
 
@@ -2439,6 +2906,7 @@ impl Application for CosmicConnectManager {
                         event_type: "Device disconnected".to_string(),
                         description: device.name,
                         timestamp: chrono::Local::now(),
+                        kind: HistoryKind::Activity,
                     };
                     self.history_events.push(event);
                 }
@@ -2449,6 +2917,8 @@ impl Application for CosmicConnectManager {
                     match state.as_str() {
                         "connected" => device.is_connected = true,
                         "disconnected" => device.is_connected = false,
+                        "reachable" => device.is_reachable = true,
+                        "unreachable" => device.is_reachable = false,
                         _ => {}
                     }
                 }
@@ -2462,6 +2932,18 @@ impl Application for CosmicConnectManager {
                 self.history_events.clear();
                 Task::none()
             }
+            Message::OpenHistoryUrl(url) => {
+                match std::process::Command::new("xdg-open").arg(&url).spawn() {
+                    Ok(_) => tracing::info!("Reopened handed-off URL: {}", url),
+                    Err(e) => {
+                        tracing::error!("Failed to open {}: {}", url, e);
+                        return cosmic::task::future(async move {
+                            Message::ActionError(format!("Failed to open link: {}", e))
+                        });
+                    }
+                }
+                Task::none()
+            }
             Message::ToggleAutoStart(enabled) => {
                 self.auto_start_enabled = enabled;
                 Task::none()
@@ -2491,6 +2973,15 @@ impl Application for CosmicConnectManager {
                 DaemonEvent::DeviceStateChanged { device_id, state } => cosmic::task::future(
                     async move { Message::DeviceStateChanged(device_id, state) },
                 ),
+                DaemonEvent::DeviceChanged {
+                    device_id,
+                    snapshot,
+                } => {
+                    if let Some(battery) = snapshot.battery() {
+                        self.battery_status.insert(device_id, battery);
+                    }
+                    Task::none()
+                }
                 DaemonEvent::TransferProgress {
                     transfer_id,
                     device_id,
@@ -2518,6 +3009,19 @@ impl Application for CosmicConnectManager {
                 } => cosmic::task::future(async move {
                     Message::TransferCompleted(transfer_id, device_id, filename, success, error)
                 }),
+                DaemonEvent::PluginEvent {
+                    device_id,
+                    plugin,
+                    data,
+                } => {
+                    let device_name = self
+                        .devices
+                        .get(&device_id)
+                        .map_or(device_id.as_str(), |device| device.name.as_str());
+                    self.history_events
+                        .record_plugin_event(device_name, &plugin, &data);
+                    Task::none()
+                }
                 _ => Task::none(),
             },
             Message::DbusReady(client) => {
@@ -2683,11 +3187,15 @@ impl Application for CosmicConnectManager {
                 self.settings_device_id = None;
                 self.device_settings_config = None;
                 self.device_settings_nickname.clear();
+                self.device_settings_accent_color.clear();
+                self.device_settings_custom_icon.clear();
                 self.device_settings_plugins.clear();
                 Task::none()
             }
             Message::DeviceSettingsLoaded(config) => {
                 self.device_settings_nickname = config.nickname.clone().unwrap_or_default();
+                self.device_settings_accent_color = config.accent_color.clone().unwrap_or_default();
+                self.device_settings_custom_icon = config.custom_icon.clone().unwrap_or_default();
                 self.device_settings_plugins.clear();
                 self.device_settings_plugins.insert(
                     "ping".to_string(),
@@ -2731,6 +3239,8 @@ impl Application for CosmicConnectManager {
                     let client = client.clone();
                     let device_id = device_id.clone();
                     let nickname = self.device_settings_nickname.clone();
+                    let accent_color = self.device_settings_accent_color.trim().to_string();
+                    let custom_icon = self.device_settings_custom_icon.trim().to_string();
                     let plugins = self.device_settings_plugins.clone();
                     self.show_device_settings = false;
                     cosmic::task::future(async move {
@@ -2740,6 +3250,12 @@ impl Application for CosmicConnectManager {
                                 tracing::error!("Failed to set nickname: {}", e);
                             }
                         }
+                        if let Err(e) = client
+                            .set_device_appearance(&device_id, &accent_color, &custom_icon)
+                            .await
+                        {
+                            tracing::error!("Failed to set appearance: {}", e);
+                        }
                         for (plugin, enabled) in plugins {
                             if let Err(e) = client
                                 .set_device_plugin_enabled(&device_id, &plugin, enabled)
@@ -2759,6 +3275,14 @@ impl Application for CosmicConnectManager {
                 self.device_settings_nickname = nickname;
                 Task::none()
             }
+            Message::DeviceAccentColorChanged(color) => {
+                self.device_settings_accent_color = color;
+                Task::none()
+            }
+            Message::DeviceCustomIconChanged(icon_name) => {
+                self.device_settings_custom_icon = icon_name;
+                Task::none()
+            }
             Message::DevicePluginToggled(plugin, enabled) => {
                 self.device_settings_plugins.insert(plugin, enabled);
                 Task::none()
@@ -2837,6 +3361,65 @@ impl Application for CosmicConnectManager {
                     Task::none()
                 }
             }
+            Message::ManualAddressChanged(address) => {
+                self.manual_device_address = address;
+                Task::none()
+            }
+            Message::AddDeviceByAddress => {
+                let address = self.manual_device_address.trim().to_string();
+                if address.is_empty() {
+                    return Task::none();
+                }
+                let Some((ip, port)) = parse_device_address(&address) else {
+                    return self.update(Message::ActionError(format!(
+                        "Invalid IP address: {}",
+                        address
+                    )));
+                };
+                if let Some(client) = &self.dbus_client {
+                    self.manual_device_address.clear();
+                    let client = client.clone();
+                    cosmic::task::future(async move {
+                        match client.add_device_by_address(&ip, port).await {
+                            Ok(_) => Message::DeviceAddedByAddress,
+                            Err(e) => Message::ActionError(format!(
+                                "Failed to add device at {}: {:#}",
+                                address, e
+                            )),
+                        }
+                    })
+                } else {
+                    Task::none()
+                }
+            }
+            Message::DeviceAddedByAddress => Task::batch(vec![
+                self.update(Message::ActionSuccess("Device added".to_string())),
+                cosmic::task::future(async { Message::RefreshDevices }),
+            ]),
+            Message::PairedCertificatesLoaded(certificates) => {
+                self.paired_certificates = certificates;
+                Task::none()
+            }
+            Message::ForgetDevice(device_id) => {
+                if let Some(client) = &self.dbus_client {
+                    let client = client.clone();
+                    cosmic::task::future(async move {
+                        match client.revoke_certificate(&device_id).await {
+                            Ok(()) => Message::DeviceForgotten(device_id),
+                            Err(e) => {
+                                Message::ActionError(format!("Failed to forget device: {}", e))
+                            }
+                        }
+                    })
+                } else {
+                    Task::none()
+                }
+            }
+            Message::DeviceForgotten(device_id) => {
+                self.paired_certificates
+                    .retain(|certificate| certificate.device_id != device_id);
+                self.update(Message::ActionSuccess("Device forgotten".to_string()))
+            }
             Message::ActionSuccess(msg) => {
                 self.status_message = Some((msg, false));
                 // Auto-clear after 3 seconds
@@ -2857,6 +3440,24 @@ impl Application for CosmicConnectManager {
                 self.status_message = None;
                 Task::none()
             }
+            Message::SetDeviceFilter(filter) => {
+                self.device_filter = filter;
+                Task::none()
+            }
+            Message::Activated(request) => {
+                tracing::info!("Activated via D-Bus: {:?}", request);
+                match request {
+                    activation::ActivationRequest::Activate => {}
+                    activation::ActivationRequest::OpenPage(name) => {
+                        self.active_page = Page::from_name(&name);
+                    }
+                    activation::ActivationRequest::OpenDevice(device_id) => {
+                        self.active_page = Page::Devices;
+                        self.selected_device = Some(device_id);
+                    }
+                }
+                Task::none()
+            }
             // Issue #143: Desktop icons CLI args processing
             Message::ProcessPendingCliArgs => {
                 let mut tasks = Vec::new();
@@ -2864,13 +3465,7 @@ impl Application for CosmicConnectManager {
                 // Handle --tab: navigate to specific tab (works independently)
                 if let Some(tab) = self.pending_tab.take() {
                     tracing::info!("Processing CLI arg: tab={}", tab);
-                    let page = match tab.to_lowercase().as_str() {
-                        "share" | "files" => Page::Transfers,
-                        "settings" => Page::Settings,
-                        "history" => Page::History,
-                        _ => Page::Devices,
-                    };
-                    self.active_page = page;
+                    self.active_page = Page::from_name(&tab);
                 }
 
                 // Handle --select-device: select the device
@@ -0,0 +1,172 @@
+//! Media Player
+//!
+//! Controls of the local players shown on the Media page. Every button and
+//! slider produces a [`MediaControl`], which maps to exactly one daemon call
+//! carried out by its `MprisManager`: transport buttons to `MprisControl`
+//! (`call_player_method`), the seek bar to `MprisSetPosition`
+//! (`set_position`) and the volume slider to `MprisSetVolume`.
+//!
+//! Player state is fetched again every [`REFRESH_INTERVAL`] while the page is
+//! shown. A seek bar being dragged keeps the dragged position in a
+//! [`SeekDrag`] so refreshes don't move it; the position is only sent when
+//! the bar is released.
+
+use crate::dbus_client::{DbusClient, PlayerState};
+use cosmic_connect_protocol::plugins::mpris;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Time between two player state fetches while the Media page is shown
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A control on a player card
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MediaControl {
+    Previous,
+    PlayPause,
+    Stop,
+    Next,
+    /// Seek bar released at a position in seconds
+    SeekTo(f64),
+    /// Volume slider moved, from 0.0 to 1.0
+    Volume(f64),
+}
+
+/// Daemon call carrying out a control
+#[derive(Debug, Clone, PartialEq)]
+pub enum MediaCall {
+    /// `MprisControl` with an MPRIS player method
+    PlayerMethod(&'static str),
+    /// `MprisSetPosition` in microseconds
+    SetPosition(i64),
+    /// `MprisSetVolume`
+    SetVolume(f64),
+}
+
+impl MediaControl {
+    /// Daemon call for this control
+    pub fn call(self) -> MediaCall {
+        match self {
+            Self::Previous => MediaCall::PlayerMethod("Previous"),
+            Self::PlayPause => MediaCall::PlayerMethod("PlayPause"),
+            Self::Stop => MediaCall::PlayerMethod("Stop"),
+            Self::Next => MediaCall::PlayerMethod("Next"),
+            Self::SeekTo(seconds) => MediaCall::SetPosition((seconds.max(0.0) * 1e6) as i64),
+            Self::Volume(volume) => MediaCall::SetVolume(volume.clamp(0.0, 1.0)),
+        }
+    }
+}
+
+impl MediaCall {
+    /// Send the call for a player to the daemon
+    pub async fn send(self, client: &DbusClient, player: &str) -> anyhow::Result<()> {
+        match self {
+            Self::PlayerMethod(method) => client.mpris_control(player, method).await,
+            Self::SetPosition(position) => client.mpris_set_position(player, position).await,
+            Self::SetVolume(volume) => client.mpris_set_volume(player, volume).await,
+        }
+    }
+}
+
+/// Seek bar being dragged
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeekDrag {
+    /// Player whose seek bar is dragged
+    pub player: String,
+    /// Dragged position in seconds
+    pub seconds: f64,
+}
+
+/// Seek bar position and track length in seconds
+///
+/// `None` when the player can't seek or the track length is unknown.
+pub fn seek_range(state: &PlayerState) -> Option<(f64, f64)> {
+    if !state.can_seek || state.metadata.length <= 0 {
+        return None;
+    }
+
+    let length = state.metadata.length as f64 / 1e6;
+    let position = (state.position as f64 / 1e6).clamp(0.0, length);
+    Some((position, length))
+}
+
+/// Format seconds as `m:ss`
+pub fn format_time(seconds: f64) -> String {
+    let seconds = seconds.max(0.0) as u64;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Local file of an album art URL
+///
+/// Decoded like the art the phone is sent, see [`mpris::album_art_path`].
+pub fn album_art_path(url: &str) -> Option<PathBuf> {
+    mpris::album_art_path(url).filter(|path| !path.as_os_str().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dbus_client::{LoopStatus, PlaybackStatus, PlayerMetadata};
+
+    #[test]
+    fn test_controls_dispatch_manager_calls() {
+        assert_eq!(
+            MediaControl::Previous.call(),
+            MediaCall::PlayerMethod("Previous")
+        );
+        assert_eq!(
+            MediaControl::PlayPause.call(),
+            MediaCall::PlayerMethod("PlayPause")
+        );
+        assert_eq!(MediaControl::Stop.call(), MediaCall::PlayerMethod("Stop"));
+        assert_eq!(MediaControl::Next.call(), MediaCall::PlayerMethod("Next"));
+
+        assert_eq!(
+            MediaControl::SeekTo(83.5).call(),
+            MediaCall::SetPosition(83_500_000)
+        );
+        assert_eq!(MediaControl::Volume(1.4).call(), MediaCall::SetVolume(1.0));
+    }
+
+    #[test]
+    fn test_seek_range() {
+        let mut state = PlayerState {
+            name: "vlc".to_string(),
+            identity: "VLC media player".to_string(),
+            playback_status: PlaybackStatus::Playing,
+            position: 90_000_000,
+            volume: 0.5,
+            loop_status: LoopStatus::None,
+            shuffle: false,
+            can_play: true,
+            can_pause: true,
+            can_go_next: true,
+            can_go_previous: true,
+            can_seek: true,
+            metadata: PlayerMetadata {
+                length: 240_000_000,
+                ..Default::default()
+            },
+        };
+        assert_eq!(seek_range(&state), Some((90.0, 240.0)));
+        assert_eq!(format_time(90.0), "1:30");
+
+        // Streams have no length
+        state.metadata.length = 0;
+        assert_eq!(seek_range(&state), None);
+    }
+
+    #[test]
+    fn test_album_art_path() {
+        assert_eq!(
+            album_art_path("file:///tmp/cover.jpg"),
+            Some(PathBuf::from("/tmp/cover.jpg"))
+        );
+        assert_eq!(
+            album_art_path("file:///home/user/My%20Album/cover%231.jpg"),
+            Some(PathBuf::from("/home/user/My Album/cover#1.jpg"))
+        );
+        assert_eq!(album_art_path("file://"), None);
+        assert_eq!(album_art_path("https://example.com/cover.jpg"), None);
+    }
+}
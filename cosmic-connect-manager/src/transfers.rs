@@ -0,0 +1,104 @@
+//! Transfer Queue
+//!
+//! Copy of the daemon's transfer queue shown on the Transfers page. The
+//! whole queue is fetched with `ListTransfers`; `TransferProgress` signals
+//! update the byte counts in between, and ask for a new fetch at most once
+//! per [`REFRESH_INTERVAL`] to pick up speeds and new transfers.
+
+use crate::dbus_client::TransferItem;
+use std::time::{Duration, Instant};
+
+/// Minimum time between two queue fetches triggered by progress
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Transfers known to the daemon, oldest first
+#[derive(Debug, Default)]
+pub struct TransferQueue {
+    items: Vec<TransferItem>,
+    refreshed_at: Option<Instant>,
+}
+
+impl TransferQueue {
+    /// Replace the queue with the one fetched from the daemon
+    pub fn set(&mut self, items: Vec<TransferItem>) {
+        self.items = items;
+    }
+
+    /// Apply a progress signal
+    ///
+    /// Transfers not fetched yet are added. Returns `true` when the queue
+    /// should be fetched again.
+    pub fn apply_progress(&mut self, progress: TransferItem) -> bool {
+        match self
+            .items
+            .iter_mut()
+            .find(|item| item.transfer_id == progress.transfer_id)
+        {
+            Some(item) => {
+                item.bytes_transferred = progress.bytes_transferred;
+                item.total_bytes = progress.total_bytes;
+                item.state = progress.state;
+            }
+            None => self.items.push(progress),
+        }
+
+        let due = match self.refreshed_at {
+            Some(at) => at.elapsed() >= REFRESH_INTERVAL,
+            None => true,
+        };
+        if due {
+            self.refreshed_at = Some(Instant::now());
+        }
+        due
+    }
+
+    /// Pending and active transfers, oldest first
+    pub fn running(&self) -> impl Iterator<Item = &TransferItem> {
+        self.items.iter().filter(|item| item.is_running())
+    }
+
+    /// Completed, failed and cancelled transfers, newest first
+    pub fn finished(&self) -> impl Iterator<Item = &TransferItem> {
+        self.items.iter().rev().filter(|item| !item.is_running())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(transfer_id: &str, state: &str, bytes_transferred: u64) -> TransferItem {
+        TransferItem {
+            transfer_id: transfer_id.to_string(),
+            direction: "sending".to_string(),
+            device_id: "phone".to_string(),
+            filename: "report.pdf".to_string(),
+            bytes_transferred,
+            total_bytes: 1000,
+            state: state.to_string(),
+            speed: 0,
+        }
+    }
+
+    #[test]
+    fn test_progress_updates_listed_transfer() {
+        let mut queue = TransferQueue::default();
+        queue.set(vec![item("t0", "failed", 0), item("t1", "pending", 0)]);
+
+        // The first progress asks for speeds, the next ones wait
+        assert!(queue.apply_progress(item("t1", "active", 250)));
+        assert!(!queue.apply_progress(item("t1", "active", 500)));
+        // Not fetched yet
+        assert!(!queue.apply_progress(item("t2", "active", 100)));
+
+        let running: Vec<_> = queue.running().collect();
+        assert_eq!(running.len(), 2);
+        assert_eq!(running[0].state, "active");
+        assert_eq!(running[0].progress(), 50);
+        assert_eq!(running[1].transfer_id, "t2");
+
+        let finished: Vec<_> = queue.finished().collect();
+        assert_eq!(finished.len(), 1);
+        assert!(finished[0].can_retry());
+    }
+}
@@ -0,0 +1,427 @@
+//! Fake Phone
+//!
+//! Runs an in-process "phone" built on the protocol crate so the daemon can be
+//! exercised end-to-end over loopback without real hardware.
+//!
+//! The fake phone listens for TLS connections through [`ConnectionManager`]
+//! (which wraps `TlsServer`), announces its identity over UDP, accepts every
+//! pairing request, and answers ping, battery and clipboard packets from the
+//! devices it is paired with. Files shared with it are downloaded over TLS and
+//! kept in memory.
+//!
+//! Run with:
+//! ```bash
+//! cargo run --example fake_phone -- [ANNOUNCE_ADDR]
+//! ```
+//!
+//! `ANNOUNCE_ADDR` defaults to `127.0.0.1:1816`, the daemon's discovery port.
+//!
+//! `tests/fake_phone_tests.rs` includes this file to drive the same harness.
+
+use cosmic_connect_protocol::plugins::battery::{BatteryPlugin, BatteryStatus};
+use cosmic_connect_protocol::plugins::ping::PingPlugin;
+use cosmic_connect_protocol::{
+    current_timestamp, CertificateInfo, ConnectionConfig, ConnectionEvent, ConnectionManager,
+    DeviceInfo, DeviceManager, DeviceType, Packet, PairingPacket, ProtocolError, Result,
+    TlsPayloadClient,
+};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::net::UdpSocket;
+use tokio::sync::{mpsc, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Battery level reported by the fake phone
+pub const FAKE_BATTERY_LEVEL: i32 = 80;
+
+/// Clipboard content the fake phone starts with
+pub const FAKE_CLIPBOARD: &str = "Hello from the fake phone";
+
+/// Message the fake phone answers pings with
+pub const PONG_MESSAGE: &str = "pong";
+
+/// Default address identity announcements are sent to
+const DEFAULT_ANNOUNCE_ADDR: &str = "127.0.0.1:1816";
+
+/// Interval between identity announcements
+const ANNOUNCE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Packet types the fake phone understands
+fn capabilities() -> Vec<String> {
+    [
+        "cconnect.ping",
+        "cconnect.battery",
+        "cconnect.clipboard",
+        "cconnect.share.request",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+/// State shared with the responder task
+#[derive(Debug)]
+struct PhoneState {
+    /// Devices we are paired with
+    paired: HashSet<String>,
+    /// Current clipboard content
+    clipboard: String,
+    /// Contents of files shared with the phone, by filename
+    received_files: HashMap<String, Vec<u8>>,
+}
+
+/// In-process phone speaking the CConnect protocol
+pub struct FakePhone {
+    /// Identity advertised by the phone
+    device_info: DeviceInfo,
+    /// Certificate the phone presents over TLS
+    certificate: CertificateInfo,
+    /// Connection manager accepting TLS connections
+    connection_manager: Arc<ConnectionManager>,
+    /// Pairing and clipboard state
+    state: Arc<RwLock<PhoneState>>,
+    /// Task answering incoming packets
+    responder: JoinHandle<()>,
+    /// Device registry and download storage, removed when the phone is dropped
+    _data_dir: TempDir,
+}
+
+impl FakePhone {
+    /// Start a fake phone listening on a free loopback port
+    pub async fn start(name: &str) -> Result<Self> {
+        let device_info = DeviceInfo::new(name, DeviceType::Phone, free_port()?)
+            .with_incoming_capabilities(capabilities())
+            .with_outgoing_capabilities(capabilities());
+
+        Self::start_with_info(device_info).await
+    }
+
+    /// Stop the phone and start it again on a new port with the same identity
+    ///
+    /// Simulates a phone that moved to another network. Pairing state is lost,
+    /// like when the app restarts.
+    pub async fn relocate(self) -> Result<Self> {
+        let mut device_info = self.device_info.clone();
+        self.stop().await;
+
+        device_info.tcp_port = free_port()?;
+        Self::start_with_info(device_info).await
+    }
+
+    async fn start_with_info(device_info: DeviceInfo) -> Result<Self> {
+        let port = device_info.tcp_port;
+        let data_dir = TempDir::new()?;
+        let device_manager = Arc::new(RwLock::new(DeviceManager::new(
+            data_dir.path().join("registry.json"),
+        )?));
+        let certificate = CertificateInfo::generate(&device_info.device_id)?;
+        let config = ConnectionConfig {
+            listen_addr: SocketAddr::from((Ipv4Addr::LOCALHOST, port)),
+            ..Default::default()
+        };
+
+        let connection_manager = Arc::new(ConnectionManager::new(
+            certificate.clone(),
+            device_info.clone(),
+            device_manager,
+            config,
+        )?);
+        let events = connection_manager.subscribe().await;
+        connection_manager.start().await?;
+
+        let state = Arc::new(RwLock::new(PhoneState {
+            paired: HashSet::new(),
+            clipboard: FAKE_CLIPBOARD.to_string(),
+            received_files: HashMap::new(),
+        }));
+        let downloads = data_dir.path().join("downloads");
+        std::fs::create_dir_all(&downloads)?;
+        let responder = tokio::spawn(respond(
+            events,
+            connection_manager.clone(),
+            state.clone(),
+            downloads,
+        ));
+
+        info!(
+            "Fake phone {} ({}) listening on port {}",
+            device_info.device_name, device_info.device_id, port
+        );
+
+        Ok(Self {
+            device_info,
+            certificate,
+            connection_manager,
+            state,
+            responder,
+            _data_dir: data_dir,
+        })
+    }
+
+    /// Identity advertised by the phone
+    pub fn device_info(&self) -> &DeviceInfo {
+        &self.device_info
+    }
+
+    /// Certificate the phone presents over TLS
+    pub fn certificate(&self) -> &CertificateInfo {
+        &self.certificate
+    }
+
+    /// Address the phone accepts connections on
+    pub fn addr(&self) -> SocketAddr {
+        SocketAddr::from((Ipv4Addr::LOCALHOST, self.device_info.tcp_port))
+    }
+
+    /// Check whether the phone is paired with a device
+    pub async fn is_paired(&self, device_id: &str) -> bool {
+        self.state.read().await.paired.contains(device_id)
+    }
+
+    /// Current clipboard content of the phone
+    pub async fn clipboard(&self) -> String {
+        self.state.read().await.clipboard.clone()
+    }
+
+    /// Contents of a file shared with the phone, once fully downloaded
+    pub async fn received_file(&self, filename: &str) -> Option<Vec<u8>> {
+        self.state
+            .read()
+            .await
+            .received_files
+            .get(filename)
+            .cloned()
+    }
+
+    /// Send a packet to a connected device
+    pub async fn send_packet(&self, device_id: &str, packet: &Packet) -> Result<()> {
+        self.connection_manager.send_packet(device_id, packet).await
+    }
+
+    /// Announce the phone's identity over UDP, like discovery broadcasts
+    pub async fn announce(&self, target: SocketAddr) -> Result<()> {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+        let identity = self.device_info.to_identity_packet().to_bytes()?;
+        socket.send_to(&identity, target).await?;
+        debug!("Fake phone announced identity to {}", target);
+        Ok(())
+    }
+
+    /// Stop answering and close all connections
+    pub async fn stop(self) {
+        self.responder.abort();
+        self.connection_manager.stop().await;
+    }
+}
+
+/// Find a free loopback port so the identity can advertise it up front
+fn free_port() -> Result<u16> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    Ok(listener.local_addr()?.port())
+}
+
+/// Answer packets received by the phone
+async fn respond(
+    mut events: mpsc::UnboundedReceiver<ConnectionEvent>,
+    connection_manager: Arc<ConnectionManager>,
+    state: Arc<RwLock<PhoneState>>,
+    downloads: PathBuf,
+) {
+    while let Some(event) = events.recv().await {
+        let ConnectionEvent::PacketReceived {
+            device_id,
+            packet,
+            remote_addr,
+        } = event
+        else {
+            continue;
+        };
+
+        // Downloads finish before later packets are answered, so a reply to
+        // the next packet means the file has arrived
+        if packet.is_type("cconnect.share.request") && packet.payload_transfer_info.is_some() {
+            if let Err(e) = download_file(
+                &connection_manager,
+                &state,
+                &device_id,
+                &packet,
+                remote_addr,
+                &downloads,
+            )
+            .await
+            {
+                warn!("Fake phone failed to download from {}: {}", device_id, e);
+            }
+            continue;
+        }
+
+        for reply in handle_packet(&state, &device_id, &packet).await {
+            if let Err(e) = connection_manager.send_packet(&device_id, &reply).await {
+                warn!("Fake phone failed to answer {}: {}", device_id, e);
+            }
+        }
+    }
+}
+
+/// Work out the replies to a packet
+async fn handle_packet(
+    state: &RwLock<PhoneState>,
+    device_id: &str,
+    packet: &Packet,
+) -> Vec<Packet> {
+    let mut state = state.write().await;
+
+    if packet.is_type("cconnect.pair") {
+        if !packet.get_body_field::<bool>("pair").unwrap_or(false) {
+            info!("Fake phone unpaired from {}", device_id);
+            state.paired.remove(device_id);
+            return Vec::new();
+        }
+
+        // A second pair packet is the peer confirming our accept
+        if !state.paired.insert(device_id.to_string()) {
+            return Vec::new();
+        }
+
+        info!("Fake phone paired with {}", device_id);
+        return vec![
+            PairingPacket::accept(),
+            battery_packet(),
+            clipboard_connect_packet(&state.clipboard),
+        ];
+    }
+
+    if !state.paired.contains(device_id) {
+        debug!(
+            "Fake phone ignoring '{}' from unpaired device {}",
+            packet.packet_type, device_id
+        );
+        return Vec::new();
+    }
+
+    if packet.is_type("cconnect.ping") {
+        if packet.get_body_field::<bool>("keepalive").unwrap_or(false) {
+            return Vec::new();
+        }
+        vec![PingPlugin::new().create_ping(Some(PONG_MESSAGE.to_string()))]
+    } else if packet.is_type("cconnect.battery.request") {
+        vec![battery_packet()]
+    } else if packet.is_type("cconnect.clipboard") || packet.is_type("cconnect.clipboard.connect") {
+        if let Some(content) = packet.get_body_field::<String>("content") {
+            state.clipboard = content;
+        }
+        Vec::new()
+    } else {
+        Vec::new()
+    }
+}
+
+/// Download a shared file over TLS and keep its contents
+async fn download_file(
+    connection_manager: &ConnectionManager,
+    state: &RwLock<PhoneState>,
+    device_id: &str,
+    packet: &Packet,
+    remote_addr: SocketAddr,
+    downloads: &Path,
+) -> Result<()> {
+    if !state.read().await.paired.contains(device_id) {
+        debug!(
+            "Fake phone ignoring file from unpaired device {}",
+            device_id
+        );
+        return Ok(());
+    }
+
+    let filename = packet
+        .get_body_field::<String>("filename")
+        .unwrap_or_else(|| "shared-file".to_string());
+    let port = packet
+        .payload_transfer_info
+        .as_ref()
+        .and_then(|info| info.get("port"))
+        .and_then(|port| port.as_u64())
+        .and_then(|port| u16::try_from(port).ok())
+        .ok_or_else(|| {
+            ProtocolError::InvalidPacket("Share request without a payload port".to_string())
+        })?;
+    let size = packet.payload_size.unwrap_or(0).max(0) as u64;
+
+    let tls_config = connection_manager.tls_config();
+    let client = TlsPayloadClient::new(&remote_addr.ip().to_string(), port, &tls_config).await?;
+    let path = downloads.join(&filename);
+    client.receive_file(&path, size).await?;
+
+    let contents = tokio::fs::read(&path).await?;
+    info!(
+        "Fake phone received '{}' ({} bytes) from {}",
+        filename,
+        contents.len(),
+        device_id
+    );
+    state
+        .write()
+        .await
+        .received_files
+        .insert(filename, contents);
+    Ok(())
+}
+
+/// Battery status the fake phone reports
+fn battery_packet() -> Packet {
+    BatteryPlugin::new().create_battery_packet(&BatteryStatus::new(FAKE_BATTERY_LEVEL, false, 0))
+}
+
+/// Clipboard sync sent right after pairing
+fn clipboard_connect_packet(content: &str) -> Packet {
+    Packet::new(
+        "cconnect.clipboard.connect",
+        json!({
+            "content": content,
+            "timestamp": current_timestamp(),
+        }),
+    )
+}
+
+#[tokio::main]
+async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .init();
+
+    let announce_addr: SocketAddr = std::env::args()
+        .nth(1)
+        .as_deref()
+        .unwrap_or(DEFAULT_ANNOUNCE_ADDR)
+        .parse()?;
+
+    let phone = FakePhone::start("Fake Phone").await?;
+    println!(
+        "Fake phone {} listening on {}, announcing to {}",
+        phone.device_info().device_id,
+        phone.addr(),
+        announce_addr
+    );
+    println!("Press Ctrl+C to stop");
+
+    let mut interval = tokio::time::interval(ANNOUNCE_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                if let Err(e) = phone.announce(announce_addr).await {
+                    warn!("Failed to announce identity: {}", e);
+                }
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    phone.stop().await;
+    Ok(())
+}
@@ -5,6 +5,12 @@
 use crate::Packet;
 use std::net::SocketAddr;
 
+/// `Disconnected` reason when the user closed the connection
+///
+/// The device stays paired; automatic reconnection skips it until it
+/// connects again.
+pub const MANUAL_DISCONNECT_REASON: &str = "Manual";
+
 /// Connection event types
 #[derive(Debug, Clone)]
 pub enum ConnectionEvent {
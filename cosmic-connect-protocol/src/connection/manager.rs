@@ -16,15 +16,52 @@
 //! 3. A disconnected event is emitted for the old connection
 //! 4. A connected event is emitted for the new connection
 //! 5. No rejection is sent to the client, preventing cascade failures
-
-use super::events::ConnectionEvent;
+//!
+//! ## Address Changes
+//!
+//! Devices roaming between networks announce themselves from a new address
+//! while their old socket is dead or dying. [`ConnectionManager::handle_discovered_address`]
+//! correlates such beacons with the last known endpoint of the device and
+//! reconnects to the new address, leaving healthy connections alone. The last
+//! known endpoint is kept on the device in the registry, so it survives a
+//! restart.
+//!
+//! ## Manual Disconnect
+//!
+//! [`ConnectionManager::disconnect`] closes a connection the user wants gone
+//! without unpairing. The connection task closes the TLS session before it
+//! exits, and `Disconnected` is emitted with reason
+//! [`MANUAL_DISCONNECT_REASON`](super::events::MANUAL_DISCONNECT_REASON).
+//! The device is not followed to new addresses, and
+//! [`ConnectionManager::is_manually_disconnected`] tells auto-connect to
+//! leave it alone, until a connection to it is established again.
+//!
+//! ## Reconnect Jitter
+//!
+//! Devices that lose their connection at the same moment, e.g. when the
+//! desktop resumes from suspend, would otherwise retry in lockstep. Backoff
+//! delays are randomized with the [`BackoffJitter`] configured in
+//! [`ConnectionConfig`], full jitter by default, via
+//! [`ConnectionManager::reconnect_delay`].
+//!
+//! ## Rejected Certificates
+//!
+//! An outgoing connection whose TLS handshake fails over a certificate fails
+//! with [`ProtocolError::TlsHandshake`], and the device is marked as needing
+//! to be re-paired. Further [`ConnectionManager::connect`] calls to it fail
+//! immediately instead of retrying a handshake that cannot succeed, until
+//! pairing connects to it again via [`ConnectionManager::connect_with_cert`].
+
+use super::events::{ConnectionEvent, MANUAL_DISCONNECT_REASON};
+use super::writer::PacketWriter;
 use crate::{
-    CertificateInfo, Device, DeviceInfo, DeviceManager, Packet, ProtocolError, Result, TlsConfig,
-    TlsConnection, TlsDeviceInfo, TlsServer,
+    CertificateInfo, Device, DeviceInfo, DeviceManager, DiscoveryService, Packet, ProtocolError,
+    Result, TlsConfig, TlsConnection, TlsDeviceInfo, TlsServer, TransportAddress,
 };
-use std::collections::HashMap;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, RwLock};
 use tokio::task::JoinHandle;
@@ -36,6 +73,9 @@ const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(10);
 /// Connection timeout (consider disconnected after 60 seconds of no activity)
 const CONNECTION_TIMEOUT: Duration = Duration::from_secs(60);
 
+/// Time a manually closed connection gets to close its TLS session
+const DISCONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Minimum delay between connection attempts from the same device
 /// Issue #52: This is now used for logging warnings, not rejection
 /// Socket replacement prevents connection storms while maintaining stability
@@ -62,6 +102,58 @@ struct ActiveConnection {
     device_id: String,
     /// Remote address
     remote_addr: SocketAddr,
+    /// When a packet was last received on this connection
+    last_activity: Arc<Mutex<Instant>>,
+}
+
+impl ActiveConnection {
+    /// Whether the connection is still usable for a device announcing itself at `addr`
+    ///
+    /// A connection whose task has stopped is dead. A connection from another
+    /// IP than the announcement is only considered healthy while packets keep
+    /// arriving on it; a device that moved networks leaves its old socket
+    /// silent until TCP gives up on it.
+    fn is_healthy(&self, addr: SocketAddr, timeout: Duration) -> bool {
+        if self.command_tx.is_closed() {
+            return false;
+        }
+
+        self.remote_addr.ip() == addr.ip() || self.last_activity.lock().unwrap().elapsed() < timeout
+    }
+}
+
+/// How reconnect backoff delays are randomized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackoffJitter {
+    /// Wait exactly the backoff delay
+    None,
+    /// Wait a random time between zero and the backoff delay
+    #[default]
+    Full,
+    /// Wait half the backoff delay plus a random time up to the other half
+    Equal,
+}
+
+impl BackoffJitter {
+    /// Randomize a backoff delay
+    pub fn apply(self, backoff: Duration) -> Duration {
+        match self {
+            Self::None => backoff,
+            Self::Full => backoff.mul_f64(random_fraction()),
+            Self::Equal => backoff / 2 + (backoff / 2).mul_f64(random_fraction()),
+        }
+    }
+}
+
+/// Uniformly distributed random number in `[0, 1)`
+fn random_fraction() -> f64 {
+    let mut bytes = [0u8; 8];
+    if SystemRandom::new().fill(&mut bytes).is_err() {
+        // Without randomness, fall back to the plain backoff
+        return 1.0;
+    }
+    // 53 random bits fill the mantissa of an f64
+    (u64::from_le_bytes(bytes) >> 11) as f64 / (1u64 << 53) as f64
 }
 
 /// Connection manager configuration
@@ -73,6 +165,13 @@ pub struct ConnectionConfig {
     pub keep_alive_interval: Duration,
     /// Connection timeout
     pub connection_timeout: Duration,
+    /// Randomization of reconnect backoff delays
+    pub reconnect_jitter: BackoffJitter,
+    /// Allow plaintext debug connections, see [`super::plaintext`]
+    ///
+    /// **Insecure**, for protocol bring-up only. Off by default.
+    #[cfg(feature = "insecure_plaintext")]
+    pub insecure_plaintext: bool,
 }
 
 impl Default for ConnectionConfig {
@@ -81,6 +180,9 @@ impl Default for ConnectionConfig {
             listen_addr: "0.0.0.0:1716".parse().unwrap(),
             keep_alive_interval: KEEP_ALIVE_INTERVAL,
             connection_timeout: CONNECTION_TIMEOUT,
+            reconnect_jitter: BackoffJitter::default(),
+            #[cfg(feature = "insecure_plaintext")]
+            insecure_plaintext: false,
         }
     }
 }
@@ -116,6 +218,12 @@ pub struct ConnectionManager {
 
     /// Last connection time per device (for rate limiting to prevent connection storms)
     last_connection_time: Arc<RwLock<HashMap<String, Instant>>>,
+
+    /// Devices whose TLS handshake failed over a certificate, until re-paired
+    repair_required: Arc<RwLock<HashSet<String>>>,
+
+    /// Devices the user disconnected from, until connected again
+    manually_disconnected: Arc<RwLock<HashSet<String>>>,
 }
 
 /// Helper to convert discovery::DeviceInfo to TlsDeviceInfo
@@ -155,6 +263,8 @@ impl ConnectionManager {
             config,
             server_task: Arc::new(RwLock::new(None)),
             last_connection_time: Arc::new(RwLock::new(HashMap::new())),
+            repair_required: Arc::new(RwLock::new(HashSet::new())),
+            manually_disconnected: Arc::new(RwLock::new(HashSet::new())),
         })
     }
 
@@ -163,6 +273,11 @@ impl ConnectionManager {
         self.device_info = Arc::new(device_info);
     }
 
+    /// Local device information sent in identity packets
+    pub fn device_info(&self) -> &crate::DeviceInfo {
+        &self.device_info
+    }
+
     /// Get the TLS configuration for payload transfers
     pub fn tls_config(&self) -> Arc<TlsConfig> {
         Arc::clone(&self.tls_config)
@@ -211,6 +326,7 @@ impl ConnectionManager {
         let device_manager = self.device_manager.clone();
         let device_info = self.device_info.clone();
         let last_connection_time = self.last_connection_time.clone();
+        let manually_disconnected = self.manually_disconnected.clone();
 
         let server_task = tokio::spawn(async move {
             let mut consecutive_errors = 0u32;
@@ -245,6 +361,7 @@ impl ConnectionManager {
                             device_manager.clone(),
                             Some(remote_identity), // Pass the already-received identity
                             last_connection_time.clone(),
+                            manually_disconnected.clone(),
                         );
                     }
                     Err(e) => {
@@ -308,13 +425,23 @@ impl ConnectionManager {
         }
         drop(connections);
 
+        if self.requires_repair(device_id).await {
+            return Err(ProtocolError::InvalidState(format!(
+                "Device {} must be re-paired before connecting",
+                device_id
+            )));
+        }
+
         // Connect with TLS (rustls with TOFU)
         // Note: cosmic-connect-core TLS uses TOFU - no pre-verification needed
         // Create identity packet to send before TLS handshake (KDE Connect protocol v8)
         let identity_packet = self.device_info.to_identity_packet();
         let identity_bytes = identity_packet.to_bytes()?;
         let mut connection =
-            TlsConnection::connect(addr, &self.tls_config, &identity_bytes).await?;
+            match TlsConnection::connect(addr, &self.tls_config, &identity_bytes).await {
+                Ok(connection) => connection,
+                Err(e) => return Err(self.handshake_failed(device_id, e).await),
+            };
 
         connection.set_device_id(device_id.to_string());
 
@@ -329,6 +456,7 @@ impl ConnectionManager {
             self.device_manager.clone(),
             None, // Will perform identity exchange in handler
             self.last_connection_time.clone(),
+            self.manually_disconnected.clone(),
         );
 
         info!("Connected to device {} at {}", device_id, addr);
@@ -336,6 +464,69 @@ impl ConnectionManager {
         Ok(())
     }
 
+    /// Add the device at `addr`, for devices discovery doesn't find
+    ///
+    /// Probes the address for the device's identity (see
+    /// [`DiscoveryService::probe_identity`]), records the device as reachable
+    /// there and connects to it, so it can be paired like a discovered device.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no device answers at `addr`, the address belongs to
+    /// this device, or connecting fails.
+    pub async fn add_device_by_address(&self, addr: SocketAddr) -> Result<DeviceInfo> {
+        info!("Adding device at {}", addr);
+
+        let identity = self.device_info.to_identity_packet();
+        let info = DiscoveryService::probe_identity(&identity, &self.tls_config, addr).await?;
+        if info.device_id == self.device_info.device_id {
+            return Err(ProtocolError::InvalidState(format!(
+                "{} is this device",
+                addr
+            )));
+        }
+
+        {
+            let mut device_manager = self.device_manager.write().await;
+            device_manager.update_from_discovery(info.clone(), TransportAddress::Tcp(addr));
+            if let Err(e) = device_manager.save_registry() {
+                warn!("Failed to save device registry: {}", e);
+            }
+        }
+
+        self.connect(&info.device_id, addr).await?;
+        Ok(info)
+    }
+
+    /// Connect to a remote device without TLS, logging every packet
+    ///
+    /// **Insecure**, for protocol debugging only. The connection is handed to
+    /// the caller instead of being managed alongside the TLS connections.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if [`ConnectionConfig::insecure_plaintext`] is not
+    /// set, or if connecting or the identity exchange fails.
+    #[cfg(feature = "insecure_plaintext")]
+    pub async fn connect_plaintext(
+        &self,
+        addr: SocketAddr,
+    ) -> Result<(
+        PacketWriter<super::plaintext::PlaintextConnection<crate::TcpConnection>>,
+        Packet,
+    )> {
+        if !self.config.insecure_plaintext {
+            return Err(ProtocolError::Configuration(
+                "Plaintext connections are disabled".to_string(),
+            ));
+        }
+
+        warn!("INSECURE: connecting to {} without TLS", addr);
+        let connection = crate::TcpConnection::connect(addr).await?;
+        let identity = self.device_info.to_identity_packet();
+        super::plaintext::exchange_identity(connection, &identity).await
+    }
+
     /// Connect to a remote device using a provided certificate (for pairing)
     /// This is used during pairing when the device certificate isn't in DeviceManager yet
     pub async fn connect_with_cert(
@@ -354,14 +545,18 @@ impl ConnectionManager {
         }
         drop(connections);
 
+        // Pairing again is how a device with a rejected certificate recovers
+        self.repair_required.write().await.remove(device_id);
+
         // Connect with TLS (rustls with TOFU)
         // Note: peer_cert is ignored - cosmic-connect-core uses TOFU model
         // Certificate verification happens at application layer via SHA256 fingerprint
         // Create identity packet to send before TLS handshake (KDE Connect protocol v8)
         let identity_packet = self.device_info.to_identity_packet();
         let identity_bytes = identity_packet.to_bytes()?;
-        let mut connection =
-            TlsConnection::connect(addr, &self.tls_config, &identity_bytes).await?;
+        let mut connection = TlsConnection::connect(addr, &self.tls_config, &identity_bytes)
+            .await
+            .map_err(ProtocolError::from_handshake_error)?;
 
         connection.set_device_id(device_id.to_string());
 
@@ -376,6 +571,7 @@ impl ConnectionManager {
             self.device_manager.clone(),
             None, // Will perform identity exchange in handler
             self.last_connection_time.clone(),
+            self.manually_disconnected.clone(),
         );
 
         info!(
@@ -386,6 +582,47 @@ impl ConnectionManager {
         Ok(())
     }
 
+    /// Delay before the next reconnect attempt, given the backoff delay
+    ///
+    /// Spreads out the attempts of devices backing off in lockstep according
+    /// to the configured [`BackoffJitter`].
+    pub fn reconnect_delay(&self, backoff: Duration) -> Duration {
+        self.config.reconnect_jitter.apply(backoff)
+    }
+
+    /// Whether the device has to be re-paired before it can be connected to
+    pub async fn requires_repair(&self, device_id: &str) -> bool {
+        self.repair_required.read().await.contains(device_id)
+    }
+
+    /// Whether the user disconnected from the device and it has not been
+    /// connected since, so it should not be connected to automatically
+    pub async fn is_manually_disconnected(&self, device_id: &str) -> bool {
+        self.manually_disconnected.read().await.contains(device_id)
+    }
+
+    /// Convert the error of a failed connection attempt to a device
+    ///
+    /// A handshake rejected over a certificate marks the device as needing
+    /// to be re-paired.
+    async fn handshake_failed<E>(&self, device_id: &str, error: E) -> ProtocolError
+    where
+        E: std::error::Error + Into<ProtocolError> + 'static,
+    {
+        let error = ProtocolError::from_handshake_error(error);
+        if error.is_unknown_certificate() {
+            warn!(
+                "TLS handshake with {} rejected over its certificate, re-pairing required: {}",
+                device_id, error
+            );
+            self.repair_required
+                .write()
+                .await
+                .insert(device_id.to_string());
+        }
+        error
+    }
+
     /// Send a packet to a device
     pub async fn send_packet(&self, device_id: &str, packet: &Packet) -> Result<()> {
         debug!(
@@ -412,24 +649,145 @@ impl ConnectionManager {
         Ok(())
     }
 
-    /// Disconnect from a device
+    /// Send a packet built outside of the plugins to a device
+    ///
+    /// The packet is written verbatim once it passed
+    /// [`validate_custom_packet`](super::writer::validate_custom_packet).
+    pub async fn send_custom_packet(&self, device_id: &str, packet: &Packet) -> Result<()> {
+        super::writer::validate_custom_packet(packet)?;
+        info!(
+            "Sending custom packet '{}' to device {}",
+            packet.packet_type, device_id
+        );
+        self.send_packet(device_id, packet).await
+    }
+
+    /// Disconnect from a device without unpairing it
+    ///
+    /// Waits for the connection task to close the TLS session, then emits
+    /// `Disconnected` with reason [`MANUAL_DISCONNECT_REASON`]. Plugins are
+    /// cleaned up by the `Disconnected` handler as for any other disconnect,
+    /// which cancels the device's transfers. The device counts as
+    /// [manually disconnected](Self::is_manually_disconnected) until it is
+    /// connected again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device is not connected.
     pub async fn disconnect(&self, device_id: &str) -> Result<()> {
         info!("Disconnecting from device {}", device_id);
 
-        let mut connections = self.connections.write().await;
-        if let Some(active_conn) = connections.remove(device_id) {
-            // Send close command
-            let _ = active_conn.command_tx.send(ConnectionCommand::Close);
+        self.close_connection(device_id).await?;
+        self.manually_disconnected
+            .write()
+            .await
+            .insert(device_id.to_string());
+
+        info!("Disconnected from device {}", device_id);
+        Ok(())
+    }
+
+    /// Close the connection to a device and emit `Disconnected` with reason
+    /// [`MANUAL_DISCONNECT_REASON`]
+    async fn close_connection(&self, device_id: &str) -> Result<()> {
+        let Some(active_conn) = self.connections.write().await.remove(device_id) else {
+            return Err(ProtocolError::InvalidState(format!(
+                "Device {} is not connected",
+                device_id
+            )));
+        };
 
-            // Abort task
+        // The task drops its command receiver once the session is closed
+        let _ = active_conn.command_tx.send(ConnectionCommand::Close);
+        if tokio::time::timeout(DISCONNECT_TIMEOUT, active_conn.command_tx.closed())
+            .await
+            .is_err()
+        {
+            warn!(
+                "Connection to {} did not close within {:?}",
+                device_id, DISCONNECT_TIMEOUT
+            );
             active_conn.task.abort();
+        }
 
-            info!("Disconnected from device {}", device_id);
+        let mut device_manager = self.device_manager.write().await;
+        if let Err(e) = device_manager.mark_disconnected(device_id) {
+            debug!("Failed to mark device {} as disconnected: {}", device_id, e);
         }
+        drop(device_manager);
+        let _ = self.event_tx.send(ConnectionEvent::Disconnected {
+            device_id: device_id.to_string(),
+            reason: Some(MANUAL_DISCONNECT_REASON.to_string()),
+            reconnect: false,
+        });
 
         Ok(())
     }
 
+    /// Handle a discovery announcement of a device at `addr`
+    ///
+    /// If the device was connected at another address and that connection is
+    /// gone, or has gone quiet, reconnects to `addr` and records it as the
+    /// device's last known address. Healthy connections are kept, and
+    /// manually disconnected devices are not reconnected.
+    ///
+    /// Returns whether a reconnect was started.
+    pub async fn handle_discovered_address(
+        &self,
+        device_id: &str,
+        addr: SocketAddr,
+    ) -> Result<bool> {
+        if self.is_manually_disconnected(device_id).await {
+            return Ok(false);
+        }
+
+        let last_known = self
+            .device_manager
+            .read()
+            .await
+            .get_device(device_id)
+            .and_then(|device| device.last_endpoint);
+        match last_known {
+            Some(last_known) if last_known != addr => {}
+            // Unknown devices and unchanged addresses are left to regular auto-connect
+            _ => return Ok(false),
+        }
+
+        let mut connections = self.connections.write().await;
+        if let Some(active) = connections.get(device_id) {
+            if active.is_healthy(addr, self.config.connection_timeout) {
+                debug!(
+                    "Device {} announced at {} but connection from {} is healthy",
+                    device_id, addr, active.remote_addr
+                );
+                return Ok(false);
+            }
+
+            info!(
+                "Connection to {} at {} is stale, replacing it",
+                device_id, active.remote_addr
+            );
+            if let Some(stale) = connections.remove(device_id) {
+                let _ = stale.command_tx.send(ConnectionCommand::CloseForReconnect);
+            }
+        }
+        drop(connections);
+
+        info!(
+            "Device {} moved from {:?} to {}, reconnecting",
+            device_id, last_known, addr
+        );
+        if let Err(e) = self.connect(device_id, addr).await {
+            // A replaced connection no longer reports the disconnect itself
+            let mut device_manager = self.device_manager.write().await;
+            let _ = device_manager.mark_disconnected(device_id);
+            return Err(e);
+        }
+        record_endpoint(&mut *self.device_manager.write().await, device_id, addr);
+
+        Ok(true)
+    }
+
     /// Check if there's an active connection to a device
     pub async fn has_connection(&self, device_id: &str) -> bool {
         let connections = self.connections.read().await;
@@ -453,9 +811,13 @@ impl ConnectionManager {
             connections.keys().cloned().collect()
         };
 
-        for device_id in device_ids {
-            let _ = self.disconnect(&device_id).await;
-        }
+        // Each connection gets its own time to close its session
+        futures::future::join_all(
+            device_ids
+                .iter()
+                .map(|device_id| self.close_connection(device_id)),
+        )
+        .await;
 
         // Emit stopped event
         let _ = self.event_tx.send(ConnectionEvent::ManagerStopped);
@@ -470,7 +832,7 @@ impl ConnectionManager {
     /// the identity exchange here.
     #[allow(clippy::too_many_arguments)]
     fn spawn_connection_handler(
-        mut connection: TlsConnection,
+        connection: TlsConnection,
         remote_addr: SocketAddr,
         device_info: Arc<crate::DeviceInfo>,
         event_tx: mpsc::UnboundedSender<ConnectionEvent>,
@@ -478,28 +840,38 @@ impl ConnectionManager {
         device_manager: Arc<RwLock<DeviceManager>>,
         remote_identity: Option<crate::Packet>,
         last_connection_time: Arc<RwLock<HashMap<String, Instant>>>,
+        manually_disconnected: Arc<RwLock<HashSet<String>>>,
     ) {
         let (command_tx, mut command_rx) = mpsc::unbounded_channel();
+        let last_activity = Arc::new(Mutex::new(Instant::now()));
 
         let _task = tokio::spawn(async move {
             let device_id: Option<String>;
 
+            // Pairing checks this against the certificate pinned for the device
+            let peer_certificate = connection.peer_certificate().map(|cert| cert.to_vec());
+
+            // All writes go through the packet writer, which keeps plugin
+            // packets behind the identity exchange
+            let mut writer;
+
             // If remote_identity is already provided, skip the identity exchange
             let packet = if let Some(identity_packet) = remote_identity {
                 debug!("Using pre-exchanged identity packet from {}", remote_addr);
+                writer = PacketWriter::negotiated(connection);
                 identity_packet
             } else {
                 // CConnect protocol v8: Send our identity over encrypted connection first
+                writer = PacketWriter::new(connection);
                 let our_identity = device_info.to_identity_packet();
-                let core_identity = our_identity.to_core_packet();
-                if let Err(e) = connection.send_packet(&core_identity).await {
+                if let Err(e) = writer.send_identity(&our_identity).await {
                     error!("Failed to send identity over TLS to {}: {}", remote_addr, e);
                     return;
                 }
                 debug!("Sent encrypted identity packet to {}", remote_addr);
 
                 // Now receive the client's encrypted identity packet
-                match connection.receive_packet().await {
+                match writer.get_mut().receive_packet().await {
                     Ok(core_pkt) => Packet::from_core_packet(core_pkt),
                     Err(e) => {
                         error!(
@@ -514,7 +886,7 @@ impl ConnectionManager {
             // Extract device ID from the identity packet
             if let Some(id) = packet.body.get("deviceId").and_then(|v| v.as_str()) {
                 device_id = Some(id.to_string());
-                connection.set_device_id(id.to_string());
+                writer.get_mut().set_device_id(id.to_string());
 
                 info!("Connection identified as device {}", id);
 
@@ -545,8 +917,28 @@ impl ConnectionManager {
                 {
                     warn!("Failed to mark device {} as connected: {}", id, e);
                 }
+                if peer_certificate.is_none() {
+                    warn!("No peer certificate on the connection from {}", id);
+                }
+                if let Err(e) = dm.set_peer_certificate(id, peer_certificate) {
+                    warn!("Failed to record certificate of device {}: {}", id, e);
+                }
+
+                // Remember where the device accepts connections; incoming
+                // connections come from an ephemeral port, so use the
+                // advertised TCP port
+                let tcp_port = packet
+                    .body
+                    .get("tcpPort")
+                    .and_then(|v| v.as_u64())
+                    .and_then(|port| u16::try_from(port).ok())
+                    .unwrap_or(remote_addr.port());
+                record_endpoint(&mut dm, id, SocketAddr::new(remote_addr.ip(), tcp_port));
                 drop(dm);
 
+                // Connected again, auto-connect may follow the device again
+                manually_disconnected.write().await.remove(id);
+
                 // Rate limiting: Check if device is connecting too frequently
                 // Issue #52: With socket replacement, we no longer reject rapid reconnections
                 // Instead, we log a warning to help diagnose client-side issues
@@ -605,10 +997,15 @@ impl ConnectionManager {
                         task: tokio::task::spawn(async {}), // Placeholder task
                         device_id: id.to_string(),
                         remote_addr,
+                        last_activity: last_activity.clone(),
                     },
                 );
                 drop(conns);
 
+                // Both identities are known now; packets plugins sent during
+                // the exchange wait in the command channel, in order
+                writer.complete_negotiation();
+
                 // Emit connected event
                 let _ = event_tx.send(ConnectionEvent::Connected {
                     device_id: id.to_string(),
@@ -637,6 +1034,8 @@ impl ConnectionManager {
 
             // Track if this is a socket replacement (reconnect) to preserve plugins
             let mut is_reconnect = false;
+            // Closed by disconnect(), which reports the disconnect itself
+            let mut is_manual = false;
 
             // Main connection loop
             loop {
@@ -647,19 +1046,20 @@ impl ConnectionManager {
                             ConnectionCommand::SendPacket(packet) => {
                                 // Convert applet Packet to core Packet for TLS
                                 debug!("Connection task sending packet '{}' to {}", packet.packet_type, device_id);
-                                let core_packet = packet.to_core_packet();
-                                match connection.send_packet(&core_packet).await {
+                                let packet_type = packet.packet_type.clone();
+                                match writer.send(packet).await {
                                     Ok(_) => {
-                                        debug!("Packet '{}' successfully written to socket for {}", packet.packet_type, device_id);
+                                        debug!("Packet '{}' successfully written to socket for {}", packet_type, device_id);
                                     }
                                     Err(e) => {
-                                        error!("Failed to send packet '{}' to {}: {}", packet.packet_type, device_id, e);
+                                        error!("Failed to send packet '{}' to {}: {}", packet_type, device_id, e);
                                         break;
                                     }
                                 }
                             }
                             ConnectionCommand::Close => {
                                 info!("Closing connection to {}", device_id);
+                                is_manual = true;
                                 break;
                             }
                             ConnectionCommand::CloseForReconnect => {
@@ -671,12 +1071,13 @@ impl ConnectionManager {
                     }
 
                     // Receive packets
-                    result = connection.receive_packet() => {
+                    result = writer.get_mut().receive_packet() => {
                         match result {
                             Ok(core_packet) => {
                                 // Convert core Packet to applet Packet
                                 let packet = crate::Packet::from_core_packet(core_packet);
                                 debug!("Received packet '{}' from {}", packet.packet_type, device_id);
+                                *last_activity.lock().unwrap() = Instant::now();
                                 let _ = event_tx.send(ConnectionEvent::PacketReceived {
                                     device_id: device_id.clone(),
                                     packet,
@@ -704,8 +1105,7 @@ impl ConnectionManager {
                         let ping_packet = crate::Packet::new("cconnect.ping", serde_json::json!({
                             "keepalive": true
                         }));
-                        let core_ping = ping_packet.to_core_packet();
-                        if let Err(e) = connection.send_packet(&core_ping).await {
+                        if let Err(e) = writer.send(ping_packet).await {
                             error!("Failed to send keepalive ping to {}: {}", device_id, e);
                             break;
                         }
@@ -742,7 +1142,9 @@ impl ConnectionManager {
 
             // Update device manager only if this was the active connection
             // and NOT a socket replacement (reconnect)
-            if should_mark_disconnected && !is_reconnect {
+            if is_manual {
+                debug!("Connection to {} closed manually", device_id);
+            } else if should_mark_disconnected && !is_reconnect {
                 let mut dm = device_manager.write().await;
                 let _ = dm.mark_disconnected(&device_id);
                 drop(dm);
@@ -764,7 +1166,7 @@ impl ConnectionManager {
             }
 
             // Close connection
-            let _ = connection.close().await;
+            let _ = writer.into_inner().close().await;
 
             info!("Connection handler for {} stopped", device_id);
         });
@@ -775,3 +1177,172 @@ impl ConnectionManager {
         // it's not necessary since we can abort via the command channel.
     }
 }
+
+/// Record where a device accepts connections, saving the registry if it moved
+fn record_endpoint(device_manager: &mut DeviceManager, device_id: &str, endpoint: SocketAddr) {
+    let known = device_manager
+        .get_device(device_id)
+        .and_then(|device| device.last_endpoint);
+    if known == Some(endpoint) {
+        return;
+    }
+
+    if let Err(e) = device_manager.set_last_endpoint(device_id, endpoint) {
+        debug!("Failed to record endpoint of device {}: {}", device_id, e);
+        return;
+    }
+    if let Err(e) = device_manager.save_registry() {
+        warn!("Failed to save device registry: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection::MockConnection;
+    use crate::{ConnectionState, DeviceType, PairingStatus};
+    use tokio::net::TcpListener;
+
+    fn create_manager(dir: &std::path::Path) -> ConnectionManager {
+        let device_manager = DeviceManager::new(dir.join("registry.json")).unwrap();
+        ConnectionManager::new(
+            CertificateInfo::generate("desktop").unwrap(),
+            DeviceInfo::new("Desktop", DeviceType::Desktop, 0),
+            Arc::new(RwLock::new(device_manager)),
+            ConnectionConfig::default(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rejected_certificate_is_not_retried() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let manager = create_manager(dir.path());
+
+        let rejected = std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            rustls::Error::InvalidCertificate(rustls::CertificateError::UnknownIssuer),
+        );
+        let error = manager.handshake_failed("phone", rejected).await;
+        assert!(matches!(error, ProtocolError::TlsHandshake(_)));
+        assert!(manager.requires_repair("phone").await);
+
+        // Reconnecting fails without another handshake
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let error = manager
+            .connect("phone", listener.local_addr().unwrap())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ProtocolError::InvalidState(_)));
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), listener.accept())
+                .await
+                .is_err()
+        );
+
+        // Other failures don't block reconnecting
+        let refused = std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "refused");
+        manager.handshake_failed("tablet", refused).await;
+        assert!(!manager.requires_repair("tablet").await);
+    }
+
+    #[tokio::test]
+    async fn test_first_retries_are_spread_out() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let manager = create_manager(dir.path());
+        let backoff = Duration::from_secs(2);
+
+        // Every device backs off by the same amount after a network blip
+        let delays: Vec<Duration> = (0..50).map(|_| manager.reconnect_delay(backoff)).collect();
+        assert!(delays.iter().all(|delay| *delay <= backoff));
+        let distinct: HashSet<Duration> = delays.iter().copied().collect();
+        assert!(distinct.len() > 40, "{} distinct delays", distinct.len());
+
+        let equal: Vec<Duration> = (0..50)
+            .map(|_| BackoffJitter::Equal.apply(backoff))
+            .collect();
+        assert!(equal.iter().all(|d| *d >= backoff / 2 && *d <= backoff));
+
+        // Without jitter every device retries at the same time
+        assert!((0..50).all(|_| BackoffJitter::None.apply(backoff) == backoff));
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_keeps_device_paired() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let manager = create_manager(dir.path());
+        let mut events = manager.subscribe().await;
+
+        let info = DeviceInfo::new("Phone", DeviceType::Phone, 1716);
+        let device_id = info.device_id.clone();
+        {
+            let mut devices = manager.device_manager.write().await;
+            devices.add_device(Device::new(
+                info,
+                ConnectionState::Connected,
+                PairingStatus::Paired,
+            ));
+        }
+
+        // Stand-in for the connection task, closing the session on Close
+        let connection = MockConnection::new();
+        let (command_tx, mut command_rx) = mpsc::unbounded_channel();
+        let task = tokio::spawn({
+            let connection = connection.clone();
+            async move {
+                while let Some(command) = command_rx.recv().await {
+                    if matches!(command, ConnectionCommand::Close) {
+                        connection.close();
+                        break;
+                    }
+                }
+            }
+        });
+        manager.connections.write().await.insert(
+            device_id.clone(),
+            ActiveConnection {
+                command_tx,
+                task,
+                device_id: device_id.clone(),
+                remote_addr: "127.0.0.1:1716".parse().unwrap(),
+                last_activity: Arc::new(Mutex::new(Instant::now())),
+            },
+        );
+
+        manager.disconnect(&device_id).await.unwrap();
+
+        assert!(connection.is_closed());
+        assert!(!manager.has_connection(&device_id).await);
+        let event = tokio::time::timeout(Duration::from_secs(1), events.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            event,
+            ConnectionEvent::Disconnected { reason: Some(reason), reconnect: false, .. }
+                if reason == MANUAL_DISCONNECT_REASON
+        ));
+
+        let devices = manager.device_manager.read().await;
+        let device = devices.get_device(&device_id).unwrap();
+        assert!(device.is_paired());
+        assert!(!device.is_connected());
+        drop(devices);
+
+        // Not followed to a new address until connected again
+        assert!(manager.is_manually_disconnected(&device_id).await);
+        manager
+            .device_manager
+            .write()
+            .await
+            .set_last_endpoint(&device_id, "127.0.0.1:1716".parse().unwrap())
+            .unwrap();
+        assert!(!manager
+            .handle_discovered_address(&device_id, "127.0.0.2:1716".parse().unwrap())
+            .await
+            .unwrap());
+
+        // Nothing left to disconnect
+        assert!(manager.disconnect(&device_id).await.is_err());
+    }
+}
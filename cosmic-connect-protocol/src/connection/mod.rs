@@ -5,6 +5,14 @@
 
 pub mod events;
 pub mod manager;
+#[cfg(feature = "insecure_plaintext")]
+pub mod plaintext;
+pub mod writer;
 
-pub use events::ConnectionEvent;
-pub use manager::{ConnectionConfig, ConnectionManager};
+pub use events::{ConnectionEvent, MANUAL_DISCONNECT_REASON};
+pub use manager::{BackoffJitter, ConnectionConfig, ConnectionManager};
+#[cfg(feature = "insecure_plaintext")]
+pub use plaintext::{exchange_identity, PacketSource, PlaintextConnection};
+#[cfg(test)]
+pub use writer::MockConnection;
+pub use writer::{validate_custom_packet, PacketSink, PacketWriter};
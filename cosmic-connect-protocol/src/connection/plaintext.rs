@@ -0,0 +1,176 @@
+//! Plaintext Debug Connections
+//!
+//! **Insecure.** Connections without TLS that log every packet in full, for
+//! bringing up the protocol against a new device. Packets, including the
+//! identity and anything plugins send, travel unencrypted and unauthenticated.
+//!
+//! Only compiled with the `insecure_plaintext` feature and only used when
+//! [`ConnectionConfig::insecure_plaintext`](super::ConnectionConfig) is set,
+//! which it never is by default.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! let connection = TcpConnection::connect(addr).await?;
+//! let (mut writer, remote_identity) = exchange_identity(connection, &identity).await?;
+//! writer.send(Packet::new("cconnect.ping", json!({}))).await?;
+//! let reply = writer.get_mut().read_packet().await?;
+//! ```
+
+#[cfg(test)]
+use super::writer::MockConnection;
+use super::writer::{PacketSink, PacketWriter};
+use crate::{Packet, ProtocolError, Result, TcpConnection};
+use async_trait::async_trait;
+use tracing::{info, warn};
+
+/// Connection packets are read from
+#[async_trait]
+pub trait PacketSource: Send {
+    /// Read the next packet from the remote
+    async fn read_packet(&mut self) -> Result<Packet>;
+}
+
+#[async_trait]
+impl PacketSink for TcpConnection {
+    async fn write_packet(&mut self, packet: &Packet) -> Result<()> {
+        self.send_packet(packet).await
+    }
+}
+
+#[async_trait]
+impl PacketSource for TcpConnection {
+    async fn read_packet(&mut self) -> Result<Packet> {
+        self.receive_packet().await
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl PacketSource for MockConnection {
+    async fn read_packet(&mut self) -> Result<Packet> {
+        self.next_incoming()
+            .ok_or_else(|| ProtocolError::Transport("No incoming packet queued".to_string()))
+    }
+}
+
+/// Connection that logs every packet read from or written to it
+#[derive(Debug)]
+pub struct PlaintextConnection<C> {
+    inner: C,
+}
+
+impl<C> PlaintextConnection<C> {
+    /// Wrap a connection without TLS
+    pub fn new(inner: C) -> Self {
+        warn!("INSECURE: using a plaintext connection, packets are neither encrypted nor authenticated");
+        warn!("INSECURE: plaintext connections are for protocol debugging only");
+        Self { inner }
+    }
+
+    /// Underlying connection
+    pub fn get_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+
+    /// Unwrap the underlying connection
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+/// Full packet as sent on the wire, for the debug log
+fn describe(packet: &Packet) -> String {
+    match packet.to_bytes() {
+        Ok(bytes) => String::from_utf8_lossy(&bytes).trim_end().to_string(),
+        Err(e) => format!("<{} not serializable: {}>", packet.packet_type, e),
+    }
+}
+
+#[async_trait]
+impl<C: PacketSink> PacketSink for PlaintextConnection<C> {
+    async fn write_packet(&mut self, packet: &Packet) -> Result<()> {
+        info!("plaintext >> {}", describe(packet));
+        self.inner.write_packet(packet).await
+    }
+}
+
+#[async_trait]
+impl<C: PacketSource> PacketSource for PlaintextConnection<C> {
+    async fn read_packet(&mut self) -> Result<Packet> {
+        let packet = self.inner.read_packet().await?;
+        info!("plaintext << {}", describe(&packet));
+        Ok(packet)
+    }
+}
+
+/// Exchange identities over a plaintext connection
+///
+/// Writes our identity, reads the remote's and returns a negotiated writer
+/// along with the remote identity.
+///
+/// # Errors
+///
+/// Returns an error if the connection fails or the remote's first packet is
+/// not an identity.
+pub async fn exchange_identity<C: PacketSink + PacketSource>(
+    connection: C,
+    identity: &Packet,
+) -> Result<(PacketWriter<PlaintextConnection<C>>, Packet)> {
+    let mut writer = PacketWriter::new(PlaintextConnection::new(connection));
+    writer.send_identity(identity).await?;
+
+    let remote_identity = writer.get_mut().read_packet().await?;
+    if !remote_identity.is_type("cconnect.identity") {
+        return Err(ProtocolError::InvalidPacket(format!(
+            "Expected identity, got {}",
+            remote_identity.packet_type
+        )));
+    }
+
+    writer.complete_negotiation();
+    Ok((writer, remote_identity))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DeviceInfo, DeviceType};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_exchange_identity_and_ping() {
+        let connection = MockConnection::new();
+        let phone = DeviceInfo::new("Phone", DeviceType::Phone, 1716).to_identity_packet();
+        connection.push_incoming(phone.clone());
+        connection.push_incoming(Packet::new("cconnect.ping", json!({})));
+
+        let identity = DeviceInfo::new("Desktop", DeviceType::Desktop, 1716).to_identity_packet();
+        let (mut writer, remote_identity) = exchange_identity(connection.clone(), &identity)
+            .await
+            .unwrap();
+        assert_eq!(remote_identity, phone);
+        assert!(writer.is_negotiated());
+
+        writer
+            .send(Packet::new("cconnect.ping", json!({ "message": "hi" })))
+            .await
+            .unwrap();
+        let ping = writer.get_mut().read_packet().await.unwrap();
+        assert!(ping.is_type("cconnect.ping"));
+
+        let written = connection.written();
+        assert_eq!(written.len(), 2);
+        assert_eq!(written[0], identity);
+        assert!(written[1].is_type("cconnect.ping"));
+    }
+
+    #[tokio::test]
+    async fn test_non_identity_first_packet_rejected() {
+        let connection = MockConnection::new();
+        connection.push_incoming(Packet::new("cconnect.ping", json!({})));
+
+        let identity = DeviceInfo::new("Desktop", DeviceType::Desktop, 1716).to_identity_packet();
+        assert!(exchange_identity(connection, &identity).await.is_err());
+    }
+}
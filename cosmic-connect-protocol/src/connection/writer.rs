@@ -0,0 +1,301 @@
+//! Packet Writer
+//!
+//! Orders the packets written to a connection. Our identity always goes out
+//! first, and no other packet is written until the identity exchange
+//! completes.
+//!
+//! Plugins push their connect-time state (battery, clipboard, audio sinks) as
+//! soon as a device connects. The connection task only takes packets from
+//! its command channel once the exchange completed, so those packets wait
+//! there, in the order they were sent, and the remote has always read our
+//! identity, and we have read its capabilities, before any of them arrives.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! let mut writer = PacketWriter::new(connection);
+//! writer.send_identity(&identity).await?;
+//! let remote_identity = writer.get_mut().receive_packet().await?;
+//! writer.complete_negotiation();
+//!
+//! writer.send(battery_packet).await?;
+//! ```
+//!
+//! ## Custom Packets
+//!
+//! Packets built outside of the plugins, e.g. by extension developers trying
+//! out a new packet type, are checked with [`validate_custom_packet`] before
+//! being written. They are written verbatim, but may not take over the
+//! identity exchange or pairing.
+
+use crate::{Packet, ProtocolError, Result, TlsConnection};
+use async_trait::async_trait;
+#[cfg(test)]
+use std::collections::VecDeque;
+#[cfg(test)]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(test)]
+use std::sync::{Arc, Mutex};
+
+/// Packet types only the connection and pairing layers may send
+const RESERVED_PACKET_TYPES: &[&str] = &["cconnect.identity", "cconnect.pair"];
+
+/// Check that a custom packet can be written to a device
+///
+/// # Errors
+///
+/// Returns an error if the packet has no type, uses a type reserved for the
+/// identity exchange or pairing, or doesn't serialize.
+pub fn validate_custom_packet(packet: &Packet) -> Result<()> {
+    if packet.packet_type.trim().is_empty() {
+        return Err(ProtocolError::InvalidPacket(
+            "Custom packet has no type".to_string(),
+        ));
+    }
+    if let Some(reserved) = RESERVED_PACKET_TYPES
+        .iter()
+        .find(|reserved| packet.is_type(reserved))
+    {
+        return Err(ProtocolError::InvalidPacket(format!(
+            "Custom packets may not use the reserved type {}",
+            reserved
+        )));
+    }
+    packet.to_bytes()?;
+    Ok(())
+}
+
+/// Connection packets are written to
+#[async_trait]
+pub trait PacketSink: Send {
+    /// Write a packet to the remote
+    ///
+    /// Returns once the packet has been flushed to the transport, so packets
+    /// written one after another arrive in the same order.
+    async fn write_packet(&mut self, packet: &Packet) -> Result<()>;
+}
+
+#[async_trait]
+impl PacketSink for TlsConnection {
+    async fn write_packet(&mut self, packet: &Packet) -> Result<()> {
+        self.send_packet(&packet.to_core_packet()).await?;
+        Ok(())
+    }
+}
+
+/// Writes packets to a connection, refusing all but our identity until the
+/// identity exchange completes
+#[derive(Debug)]
+pub struct PacketWriter<S> {
+    sink: S,
+    /// Whether the identity exchange completed
+    negotiated: bool,
+}
+
+impl<S: PacketSink> PacketWriter<S> {
+    /// Create a writer for a connection whose identity exchange is pending
+    pub fn new(sink: S) -> Self {
+        Self {
+            sink,
+            negotiated: false,
+        }
+    }
+
+    /// Create a writer for a connection whose identities were already
+    /// exchanged, e.g. by the TLS server while accepting it
+    pub fn negotiated(sink: S) -> Self {
+        Self {
+            negotiated: true,
+            ..Self::new(sink)
+        }
+    }
+
+    /// Whether the identity exchange completed
+    pub fn is_negotiated(&self) -> bool {
+        self.negotiated
+    }
+
+    /// Underlying connection, e.g. to receive packets
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    /// Unwrap the underlying connection
+    pub fn into_inner(self) -> S {
+        self.sink
+    }
+
+    /// Write our identity, ahead of every other packet
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the identity exchange already completed.
+    pub async fn send_identity(&mut self, identity: &Packet) -> Result<()> {
+        if self.negotiated {
+            return Err(ProtocolError::InvalidPacket(
+                "Identity sent after the identity exchange completed".to_string(),
+            ));
+        }
+        self.sink.write_packet(identity).await
+    }
+
+    /// Mark the identity exchange as complete, allowing other packets
+    pub fn complete_negotiation(&mut self) {
+        self.negotiated = true;
+    }
+
+    /// Write a packet
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the identity exchange has not completed yet.
+    pub async fn send(&mut self, packet: Packet) -> Result<()> {
+        if !self.negotiated {
+            return Err(ProtocolError::InvalidPacket(format!(
+                "Packet {} sent before the identity exchange completed",
+                packet.packet_type
+            )));
+        }
+        self.sink.write_packet(&packet).await
+    }
+}
+
+/// Connection that records written packets instead of sending them, for tests
+///
+/// Clones share the recorded packets, so a test can keep one while the
+/// writer owns another. Packets pushed with [`MockConnection::push_incoming`]
+/// are handed out as if received from the remote.
+#[cfg(test)]
+#[derive(Debug, Clone, Default)]
+pub struct MockConnection {
+    written: Arc<Mutex<Vec<Packet>>>,
+    incoming: Arc<Mutex<VecDeque<Packet>>>,
+    closed: Arc<AtomicBool>,
+}
+
+#[cfg(test)]
+impl MockConnection {
+    /// Create a connection with nothing written
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every packet written so far, in order
+    pub fn written(&self) -> Vec<Packet> {
+        self.written.lock().unwrap().clone()
+    }
+
+    /// Queue a packet as received from the remote
+    pub fn push_incoming(&self, packet: Packet) {
+        self.incoming.lock().unwrap().push_back(packet);
+    }
+
+    /// Next packet received from the remote, if any is queued
+    pub fn next_incoming(&self) -> Option<Packet> {
+        self.incoming.lock().unwrap().pop_front()
+    }
+
+    /// Close the connection, as closing the TLS session would
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether [`MockConnection::close`] was called on any clone
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl PacketSink for MockConnection {
+    async fn write_packet(&mut self, packet: &Packet) -> Result<()> {
+        self.written.lock().unwrap().push(packet.clone());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DeviceInfo, DeviceType};
+    use serde_json::json;
+
+    fn types(packets: &[Packet]) -> Vec<&str> {
+        packets.iter().map(|p| p.packet_type.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_identity_first_and_plugin_packets_after_negotiation() {
+        let connection = MockConnection::new();
+        let mut writer = PacketWriter::new(connection.clone());
+
+        // Nothing but the identity before the exchange completes
+        let battery = Packet::new("cconnect.battery", json!({ "currentCharge": 80 }));
+        assert!(writer.send(battery.clone()).await.is_err());
+        assert!(connection.written().is_empty());
+
+        let identity = DeviceInfo::new("Desktop", DeviceType::Desktop, 1716).to_identity_packet();
+        writer.send_identity(&identity).await.unwrap();
+        assert_eq!(
+            types(&connection.written()),
+            vec![identity.packet_type.as_str()]
+        );
+
+        writer.complete_negotiation();
+        writer.send(battery).await.unwrap();
+        writer
+            .send(Packet::new("cconnect.ping", json!({})))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            types(&connection.written()),
+            vec![
+                identity.packet_type.as_str(),
+                "cconnect.battery",
+                "cconnect.ping",
+            ]
+        );
+
+        // The identity cannot be sent again once negotiated
+        assert!(writer.send_identity(&identity).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_custom_packet_written_verbatim() {
+        let connection = MockConnection::new();
+        let mut writer = PacketWriter::negotiated(connection.clone());
+
+        let packet = Packet::new(
+            "cconnect.experimental.hello",
+            json!({ "nested": { "values": [1, 2, 3] }, "flag": true }),
+        )
+        .with_payload_size(42);
+        validate_custom_packet(&packet).unwrap();
+        writer.send(packet.clone()).await.unwrap();
+
+        assert_eq!(connection.written(), vec![packet]);
+    }
+
+    #[test]
+    fn test_reserved_custom_packets_rejected() {
+        for packet_type in ["", "cconnect.identity", "kdeconnect.pair"] {
+            let packet = Packet::new(packet_type, json!({}));
+            assert!(validate_custom_packet(&packet).is_err(), "{}", packet_type);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pre_negotiated_writes_immediately() {
+        let connection = MockConnection::new();
+        let mut writer = PacketWriter::negotiated(connection.clone());
+        assert!(writer.is_negotiated());
+
+        writer
+            .send(Packet::new("cconnect.ping", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(types(&connection.written()), vec!["cconnect.ping"]);
+    }
+}
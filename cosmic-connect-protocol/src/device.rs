@@ -15,19 +15,51 @@
 //! The `DeviceManager` maintains a registry of all known devices and their states.
 //! It provides methods for adding, removing, and querying devices.
 //!
+//! UIs can subscribe with [`DeviceManager::watch`] to receive a
+//! [`DeviceEvent`] for every added, changed or removed device, instead of
+//! rebuilding their device list on each update.
+//!
 //! ## Persistence
 //!
 //! Device information is persisted to disk to remember paired devices
 //! across application restarts.
+//!
+//! Devices that were seen once and never paired would pile up in the
+//! registry; [`DeviceManager::remove_stale_devices`] forgets the ones not
+//! seen for a while. Paired devices are never removed this way.
+//!
+//! ## Connection Quality
+//!
+//! Round-trip times measured by the ping plugin are kept in a small rolling
+//! window per device. Their average is bucketed into a `ConnectionQuality`
+//! for display. RTT samples are runtime-only and are not persisted.
+//!
+//! ## Connection Uptime
+//!
+//! Each device records when its current connection was established, so the
+//! UI can show how long it has been connected. The timestamp is reset on
+//! every reconnect and, like RTT samples, is not persisted.
 
 use crate::{DeviceInfo, PairingStatus, ProtocolError, Result, TransportAddress};
+use futures::Stream;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::fs;
+use std::net::SocketAddr;
 use std::path::PathBuf;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
 use tracing::{debug, info, warn};
 
+/// Number of device events buffered per watcher before it lags
+const DEVICE_EVENT_CAPACITY: usize = 64;
+
+/// How long a discovery beacon keeps an unconnected device reachable
+pub const DEFAULT_BEACON_TTL: Duration = Duration::from_secs(30);
+
+/// How long an unpaired device is remembered after it was last seen
+pub const DEFAULT_STALE_DEVICE_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
 /// Device connection state
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -57,6 +89,84 @@ impl ConnectionState {
     }
 }
 
+/// Number of RTT samples kept for the rolling average
+pub const RTT_WINDOW: usize = 8;
+
+/// Average RTT (ms) below which the connection is rated excellent
+const EXCELLENT_RTT_MS: u32 = 50;
+
+/// Average RTT (ms) below which the connection is rated good
+const GOOD_RTT_MS: u32 = 200;
+
+/// Connection quality bucket derived from round-trip times
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionQuality {
+    /// Low latency
+    Excellent,
+    /// Usable latency
+    Good,
+    /// High latency
+    Poor,
+}
+
+impl ConnectionQuality {
+    /// Bucket an RTT in milliseconds
+    pub fn from_rtt_ms(rtt_ms: u32) -> Self {
+        if rtt_ms < EXCELLENT_RTT_MS {
+            ConnectionQuality::Excellent
+        } else if rtt_ms < GOOD_RTT_MS {
+            ConnectionQuality::Good
+        } else {
+            ConnectionQuality::Poor
+        }
+    }
+
+    /// Get the quality as a lowercase string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConnectionQuality::Excellent => "excellent",
+            ConnectionQuality::Good => "good",
+            ConnectionQuality::Poor => "poor",
+        }
+    }
+}
+
+/// Rolling window of round-trip time samples
+#[derive(Debug, Clone, Default)]
+pub struct RttStats {
+    samples: VecDeque<u32>,
+}
+
+impl RttStats {
+    /// Record an RTT sample in milliseconds, dropping the oldest if full
+    pub fn record(&mut self, rtt_ms: u32) {
+        if self.samples.len() == RTT_WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(rtt_ms);
+    }
+
+    /// Average RTT over the window, if any samples were recorded
+    pub fn average_ms(&self) -> Option<u32> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let total: u64 = self.samples.iter().map(|&ms| u64::from(ms)).sum();
+        Some((total / self.samples.len() as u64) as u32)
+    }
+
+    /// Connection quality derived from the average RTT
+    pub fn quality(&self) -> Option<ConnectionQuality> {
+        self.average_ms().map(ConnectionQuality::from_rtt_ms)
+    }
+
+    /// Forget all samples
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+}
+
 /// Complete device state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Device {
@@ -85,12 +195,35 @@ pub struct Device {
     /// TCP port when connected
     pub port: Option<u16>,
 
+    /// Address the device last accepted connections on
+    ///
+    /// Kept across disconnects and restarts, to notice when the device
+    /// reappears at another address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_endpoint: Option<SocketAddr>,
+
     /// Certificate fingerprint (SHA256)
     pub certificate_fingerprint: Option<String>,
 
-    /// Certificate data (DER-encoded, for TLS validation)
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Certificate presented on the current TLS connection (DER-encoded)
+    ///
+    /// Checked against the certificate pinned when pairing. Not persisted,
+    /// so a certificate from an earlier connection is never checked in place
+    /// of the one actually presented.
+    #[serde(skip)]
     pub certificate_data: Option<Vec<u8>>,
+
+    /// Round-trip time samples for the current connection
+    #[serde(skip)]
+    pub rtt: RttStats,
+
+    /// When the current connection was established
+    #[serde(skip)]
+    pub connected_at: Option<Instant>,
+
+    /// Until when the last discovery beacon keeps the device reachable
+    #[serde(skip)]
+    pub beacon_expires_at: Option<Instant>,
 }
 
 impl Device {
@@ -105,8 +238,12 @@ impl Device {
             last_connected: None,
             host: None,
             port: None,
+            last_endpoint: None,
             certificate_fingerprint: None,
             certificate_data: None,
+            rtt: RttStats::default(),
+            connected_at: None,
+            beacon_expires_at: None,
         }
     }
 
@@ -125,8 +262,12 @@ impl Device {
             last_connected: None,
             host: None,
             port: None,
+            last_endpoint: None,
             certificate_fingerprint: None,
             certificate_data: None,
+            rtt: RttStats::default(),
+            connected_at: None,
+            beacon_expires_at: None,
         }
     }
 
@@ -151,8 +292,14 @@ impl Device {
     }
 
     /// Check if device is reachable
+    ///
+    /// A device is reachable while connected or connecting, and for a while
+    /// after each discovery beacon, see [`record_beacon`](Self::record_beacon).
     pub fn is_reachable(&self) -> bool {
         self.connection_state.is_reachable()
+            || self
+                .beacon_expires_at
+                .is_some_and(|expires_at| Instant::now() < expires_at)
     }
 
     /// Update last seen timestamp
@@ -160,12 +307,19 @@ impl Device {
         self.last_seen = current_timestamp();
     }
 
+    /// Record a discovery beacon keeping the device reachable for `ttl`
+    pub fn record_beacon(&mut self, ttl: Duration) {
+        self.beacon_expires_at = Some(Instant::now() + ttl);
+        self.update_last_seen();
+    }
+
     /// Mark device as connected
     pub fn mark_connected(&mut self, host: String, port: u16) {
         self.connection_state = ConnectionState::Connected;
         self.host = Some(host);
         self.port = Some(port);
         self.last_connected = Some(current_timestamp());
+        self.connected_at = Some(Instant::now());
         self.update_last_seen();
         info!(
             "Device {} ({}) connected at {}:{}",
@@ -178,11 +332,19 @@ impl Device {
 
     /// Mark device as disconnected
     pub fn mark_disconnected(&mut self) {
+        self.reset_connection();
+        self.update_last_seen();
+        info!("Device {} ({}) disconnected", self.id(), self.name());
+    }
+
+    /// Clear the connection state, keeping when the device was last seen
+    fn reset_connection(&mut self) {
         self.connection_state = ConnectionState::Disconnected;
         self.host = None;
         self.port = None;
-        self.update_last_seen();
-        info!("Device {} ({}) disconnected", self.id(), self.name());
+        self.certificate_data = None;
+        self.rtt.clear();
+        self.connected_at = None;
     }
 
     /// Mark device as connecting
@@ -203,6 +365,7 @@ impl Device {
     /// Mark device connection as failed
     pub fn mark_failed(&mut self) {
         self.connection_state = ConnectionState::Failed;
+        self.connected_at = None;
         self.update_last_seen();
         warn!("Device {} ({}) connection failed", self.id(), self.name());
     }
@@ -241,6 +404,28 @@ impl Device {
             .contains(&capability.to_string())
     }
 
+    /// Record a measured round-trip time in milliseconds
+    pub fn record_rtt(&mut self, rtt_ms: u32) {
+        self.rtt.record(rtt_ms);
+    }
+
+    /// Average round-trip time of the current connection in milliseconds
+    pub fn rtt_ms(&self) -> Option<u32> {
+        self.rtt.average_ms()
+    }
+
+    /// Connection quality derived from recent round-trip times
+    pub fn connection_quality(&self) -> Option<ConnectionQuality> {
+        self.rtt.quality()
+    }
+
+    /// How long the device has been continuously connected
+    ///
+    /// Returns `None` while the device is not connected.
+    pub fn connected_duration(&self) -> Option<Duration> {
+        self.connected_at.map(|at| at.elapsed())
+    }
+
     /// Get time since last seen in seconds
     pub fn seconds_since_last_seen(&self) -> u64 {
         current_timestamp().saturating_sub(self.last_seen)
@@ -252,6 +437,27 @@ impl Device {
     }
 }
 
+/// Change to the set of known devices
+#[derive(Debug, Clone)]
+pub enum DeviceEvent {
+    /// A device became known
+    Added(Device),
+    /// A known device's state changed
+    Changed(Device),
+    /// A device was removed, identified by its ID
+    Removed(String),
+}
+
+impl DeviceEvent {
+    /// ID of the device the event is about
+    pub fn device_id(&self) -> &str {
+        match self {
+            Self::Added(device) | Self::Changed(device) => device.id(),
+            Self::Removed(device_id) => device_id,
+        }
+    }
+}
+
 /// Device manager for tracking multiple devices
 pub struct DeviceManager {
     /// Map of device ID to device
@@ -259,6 +465,12 @@ pub struct DeviceManager {
 
     /// Path to store device registry
     registry_path: PathBuf,
+
+    /// Device events for watchers
+    events: broadcast::Sender<DeviceEvent>,
+
+    /// How long a discovery beacon keeps a device reachable
+    beacon_ttl: Duration,
 }
 
 impl DeviceManager {
@@ -283,6 +495,8 @@ impl DeviceManager {
         let mut manager = Self {
             devices: HashMap::new(),
             registry_path,
+            events: broadcast::channel(DEVICE_EVENT_CAPACITY).0,
+            beacon_ttl: DEFAULT_BEACON_TTL,
         };
 
         // Load existing registry
@@ -291,11 +505,70 @@ impl DeviceManager {
         Ok(manager)
     }
 
+    /// Subscribe to device changes
+    ///
+    /// The stream yields an event for every device added, changed or removed
+    /// through the manager after the call. Changes made through
+    /// [`get_device_mut`](Self::get_device_mut) are only reported once
+    /// [`notify_changed`](Self::notify_changed) is called. A watcher that
+    /// falls more than a few dozen events behind skips the missed ones and
+    /// should re-read the device list.
+    pub fn watch(&self) -> impl Stream<Item = DeviceEvent> {
+        futures::stream::unfold(self.events.subscribe(), |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => return Some((event, receiver)),
+                    Err(broadcast::error::RecvError::Lagged(missed)) => {
+                        warn!("Device watcher lagged, skipped {} events", missed);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
+    /// Set how long a discovery beacon keeps a device reachable
+    pub fn set_beacon_ttl(&mut self, ttl: Duration) {
+        self.beacon_ttl = ttl;
+    }
+
+    /// Report a device changed through [`get_device_mut`](Self::get_device_mut)
+    pub fn notify_changed(&self, device_id: &str) {
+        if let Some(device) = self.devices.get(device_id) {
+            self.emit(DeviceEvent::Changed(device.clone()));
+        }
+    }
+
+    /// Send an event to all watchers
+    fn emit(&self, event: DeviceEvent) {
+        // Sending only fails without watchers
+        let _ = self.events.send(event);
+    }
+
+    /// Apply a change to a device and report it to watchers
+    fn change_device(&mut self, device_id: &str, change: impl FnOnce(&mut Device)) -> Result<()> {
+        let device = self
+            .devices
+            .get_mut(device_id)
+            .ok_or_else(|| ProtocolError::DeviceNotFound(device_id.to_string()))?;
+
+        change(device);
+        let event = DeviceEvent::Changed(device.clone());
+        self.emit(event);
+        Ok(())
+    }
+
     /// Add or update a device
     pub fn add_device(&mut self, device: Device) {
         let device_id = device.id().to_string();
         info!("Adding/updating device: {} ({})", device.name(), device_id);
+        let event = if self.devices.contains_key(&device_id) {
+            DeviceEvent::Changed(device.clone())
+        } else {
+            DeviceEvent::Added(device.clone())
+        };
         self.devices.insert(device_id, device);
+        self.emit(event);
     }
 
     /// Get a device by ID
@@ -304,6 +577,9 @@ impl DeviceManager {
     }
 
     /// Get a mutable reference to a device by ID
+    ///
+    /// Call [`notify_changed`](Self::notify_changed) once done changing the
+    /// device, so watchers see the change.
     pub fn get_device_mut(&mut self, device_id: &str) -> Option<&mut Device> {
         self.devices.get_mut(device_id)
     }
@@ -311,7 +587,9 @@ impl DeviceManager {
     /// Remove a device by ID
     pub fn remove_device(&mut self, device_id: &str) -> Option<Device> {
         info!("Removing device: {}", device_id);
-        self.devices.remove(device_id)
+        let device = self.devices.remove(device_id)?;
+        self.emit(DeviceEvent::Removed(device_id.to_string()));
+        Some(device)
     }
 
     /// Check if a device exists
@@ -374,70 +652,64 @@ impl DeviceManager {
             device.info = info;
             device.host = host;
             device.port = port;
-            device.update_last_seen();
+            device.record_beacon(self.beacon_ttl);
             debug!("Updated device from discovery: {}", device_id);
+            let event = DeviceEvent::Changed(device.clone());
+            self.emit(event);
         } else {
             // Add new device
             let mut device = Device::from_discovery(info);
             device.host = host;
             device.port = port;
+            device.record_beacon(self.beacon_ttl);
             self.add_device(device);
         }
     }
 
     /// Mark device as reachable (update last seen)
     pub fn mark_reachable(&mut self, device_id: &str) -> Result<()> {
-        let device = self
-            .devices
-            .get_mut(device_id)
-            .ok_or_else(|| ProtocolError::DeviceNotFound(device_id.to_string()))?;
+        self.change_device(device_id, |device| device.update_last_seen())
+    }
 
-        device.update_last_seen();
-        Ok(())
+    /// Forget the last discovery beacon of a device that stopped sending them
+    ///
+    /// Connected devices stay reachable.
+    pub fn expire_beacon(&mut self, device_id: &str) -> Result<()> {
+        self.change_device(device_id, |device| device.beacon_expires_at = None)
     }
 
     /// Mark device as connected
     pub fn mark_connected(&mut self, device_id: &str, host: String, port: u16) -> Result<()> {
-        let device = self
-            .devices
-            .get_mut(device_id)
-            .ok_or_else(|| ProtocolError::DeviceNotFound(device_id.to_string()))?;
+        self.change_device(device_id, |device| device.mark_connected(host, port))
+    }
 
-        device.mark_connected(host, port);
-        Ok(())
+    /// Record the certificate the device presented on its TLS connection
+    pub fn set_peer_certificate(
+        &mut self,
+        device_id: &str,
+        certificate: Option<Vec<u8>>,
+    ) -> Result<()> {
+        self.change_device(device_id, |device| device.certificate_data = certificate)
+    }
+
+    /// Record the address the device accepts connections on
+    pub fn set_last_endpoint(&mut self, device_id: &str, endpoint: SocketAddr) -> Result<()> {
+        self.change_device(device_id, |device| device.last_endpoint = Some(endpoint))
     }
 
     /// Mark device as disconnected
     pub fn mark_disconnected(&mut self, device_id: &str) -> Result<()> {
-        let device = self
-            .devices
-            .get_mut(device_id)
-            .ok_or_else(|| ProtocolError::DeviceNotFound(device_id.to_string()))?;
-
-        device.mark_disconnected();
-        Ok(())
+        self.change_device(device_id, |device| device.mark_disconnected())
     }
 
     /// Update device pairing status
     pub fn update_pairing_status(&mut self, device_id: &str, status: PairingStatus) -> Result<()> {
-        let device = self
-            .devices
-            .get_mut(device_id)
-            .ok_or_else(|| ProtocolError::DeviceNotFound(device_id.to_string()))?;
-
-        device.update_pairing_status(status);
-        Ok(())
+        self.change_device(device_id, |device| device.update_pairing_status(status))
     }
 
     /// Mark device as paired with certificate
     pub fn mark_paired(&mut self, device_id: &str, fingerprint: String) -> Result<()> {
-        let device = self
-            .devices
-            .get_mut(device_id)
-            .ok_or_else(|| ProtocolError::DeviceNotFound(device_id.to_string()))?;
-
-        device.mark_paired(fingerprint);
-        Ok(())
+        self.change_device(device_id, |device| device.mark_paired(fingerprint))
     }
 
     /// Save device registry to disk
@@ -468,9 +740,10 @@ impl DeviceManager {
         })?;
         self.devices = serde_json::from_str(&json)?;
 
-        // Reset all connection states to disconnected since no connections are active on startup
+        // Reset all connection states to disconnected since no connections are active on startup.
+        // last_seen is kept, or devices gone for good would never become stale
         for device in self.devices.values_mut() {
-            device.mark_disconnected();
+            device.reset_connection();
         }
 
         info!("Loaded {} devices from registry", self.devices.len());
@@ -478,18 +751,47 @@ impl DeviceManager {
     }
 
     /// Clean up stale devices (not seen in N seconds)
+    ///
+    /// Paired and connected devices are kept. Returns the number of removed
+    /// devices.
     pub fn cleanup_stale_devices(&mut self, max_age_seconds: u64) -> usize {
-        let before_count = self.devices.len();
+        self.retain_fresh_devices(max_age_seconds).len()
+    }
 
+    /// Forget unpaired devices not seen within `max_age`
+    ///
+    /// Paired and connected devices are never removed. The registry is saved
+    /// when devices were removed. Returns the IDs of the removed devices.
+    pub fn remove_stale_devices(&mut self, max_age: Duration) -> Result<Vec<String>> {
+        let removed = self.retain_fresh_devices(max_age.as_secs());
+
+        if !removed.is_empty() {
+            info!("Removed {} stale devices", removed.len());
+            self.save_registry()?;
+        }
+
+        Ok(removed)
+    }
+
+    /// Remove unpaired, unconnected devices not seen in N seconds
+    fn retain_fresh_devices(&mut self, max_age_seconds: u64) -> Vec<String> {
+        let mut removed = Vec::new();
         self.devices.retain(|id, device| {
-            let keep = device.is_paired() || device.seen_recently(max_age_seconds);
+            let keep = device.is_paired()
+                || device.is_connected()
+                || device.seen_recently(max_age_seconds);
             if !keep {
                 debug!("Removing stale device: {} ({})", device.name(), id);
+                removed.push(id.clone());
             }
             keep
         });
 
-        before_count - self.devices.len()
+        for device_id in &removed {
+            self.emit(DeviceEvent::Removed(device_id.clone()));
+        }
+
+        removed
     }
 }
 
@@ -520,6 +822,56 @@ mod tests {
         assert!(!ConnectionState::Disconnected.is_reachable());
     }
 
+    #[test]
+    fn test_connection_quality_from_rtt_samples() {
+        let mut device = Device::from_discovery(create_test_device_info());
+        assert_eq!(device.rtt_ms(), None);
+        assert_eq!(device.connection_quality(), None);
+
+        for rtt in [10, 20, 30] {
+            device.record_rtt(rtt);
+        }
+        assert_eq!(device.rtt_ms(), Some(20));
+        assert_eq!(
+            device.connection_quality(),
+            Some(ConnectionQuality::Excellent)
+        );
+
+        // A slow sample pulls the average into the next bucket
+        device.record_rtt(300);
+        assert_eq!(device.rtt_ms(), Some(90));
+        assert_eq!(device.connection_quality(), Some(ConnectionQuality::Good));
+
+        // Old samples roll out of the window
+        for _ in 0..RTT_WINDOW {
+            device.record_rtt(400);
+        }
+        assert_eq!(device.rtt_ms(), Some(400));
+        assert_eq!(device.connection_quality(), Some(ConnectionQuality::Poor));
+
+        device.mark_disconnected();
+        assert_eq!(device.connection_quality(), None);
+    }
+
+    #[test]
+    fn test_connected_duration_tracks_connection() {
+        let mut device = Device::from_discovery(create_test_device_info());
+        assert!(device.connected_at.is_none());
+        assert_eq!(device.connected_duration(), None);
+
+        device.mark_connected("192.168.1.100".to_string(), 1716);
+        let first = device.connected_at.expect("connecting sets the timestamp");
+        assert!(device.connected_duration().is_some());
+
+        device.mark_disconnected();
+        assert!(device.connected_at.is_none());
+        assert_eq!(device.connected_duration(), None);
+
+        // A reconnect starts counting again
+        device.mark_connected("192.168.1.100".to_string(), 1716);
+        assert!(device.connected_at.unwrap() >= first);
+    }
+
     #[test]
     fn test_device_creation() {
         let info = create_test_device_info();
@@ -557,6 +909,33 @@ mod tests {
         assert!(device.host.is_none());
     }
 
+    #[test]
+    fn test_reachability_expires_with_beacon_ttl() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = DeviceManager::new(temp_dir.path().join("registry.json")).unwrap();
+        manager.set_beacon_ttl(Duration::from_millis(50));
+
+        let info = create_test_device_info();
+        let device_id = info.device_id.clone();
+        let address = TransportAddress::Tcp("192.168.1.100:1716".parse().unwrap());
+        manager.update_from_discovery(info, address);
+
+        let device = manager.get_device(&device_id).unwrap();
+        assert!(device.is_reachable());
+        assert!(!device.is_connected());
+
+        // No further beacons and no connection
+        std::thread::sleep(Duration::from_millis(80));
+        assert!(!manager.get_device(&device_id).unwrap().is_reachable());
+
+        // An active connection keeps the device reachable regardless
+        manager
+            .mark_connected(&device_id, "192.168.1.100".to_string(), 1716)
+            .unwrap();
+        manager.expire_beacon(&device_id).unwrap();
+        assert!(manager.get_device(&device_id).unwrap().is_reachable());
+    }
+
     #[test]
     fn test_device_pairing() {
         let info = create_test_device_info();
@@ -612,6 +991,46 @@ mod tests {
         assert_eq!(manager.device_count(), 0);
     }
 
+    #[tokio::test]
+    async fn test_watch_reports_added_then_changed() {
+        use futures::StreamExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let registry_path = temp_dir.path().join("registry.json");
+        let mut manager = DeviceManager::new(&registry_path).unwrap();
+        let mut events = Box::pin(manager.watch());
+
+        let info = create_test_device_info();
+        let device_id = info.device_id.clone();
+        manager.add_device(Device::from_discovery(info));
+        manager
+            .mark_connected(&device_id, "192.168.1.100".to_string(), 1716)
+            .unwrap();
+        manager.get_device_mut(&device_id).unwrap().record_rtt(42);
+        manager.notify_changed(&device_id);
+        manager.remove_device(&device_id);
+
+        match events.next().await {
+            Some(DeviceEvent::Added(device)) => assert_eq!(device.id(), device_id),
+            other => panic!("expected Added, got {:?}", other),
+        }
+        match events.next().await {
+            Some(DeviceEvent::Changed(device)) => {
+                assert_eq!(device.id(), device_id);
+                assert!(device.is_connected());
+            }
+            other => panic!("expected Changed, got {:?}", other),
+        }
+        match events.next().await {
+            Some(DeviceEvent::Changed(device)) => assert_eq!(device.rtt_ms(), Some(42)),
+            other => panic!("expected Changed, got {:?}", other),
+        }
+        match events.next().await {
+            Some(DeviceEvent::Removed(id)) => assert_eq!(id, device_id),
+            other => panic!("expected Removed, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_device_manager_persistence() {
         let temp_dir = TempDir::new().unwrap();
@@ -690,4 +1109,72 @@ mod tests {
 
         assert_eq!(manager.device_count(), 2);
     }
+
+    #[test]
+    fn test_remove_stale_devices() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry_path = temp_dir.path().join("registry.json");
+        let mut manager = DeviceManager::new(&registry_path).unwrap();
+        let now = current_timestamp();
+        let eight_days_ago = now - 8 * 24 * 60 * 60;
+
+        let device = |id: &str, last_seen: u64, status: PairingStatus| {
+            let mut info = DeviceInfo::new(id, DeviceType::Phone, 1716);
+            info.device_id = id.to_string();
+            let mut device = Device::from_discovery(info);
+            device.update_pairing_status(status);
+            device.last_seen = last_seen;
+            device
+        };
+        manager.add_device(device("old", eight_days_ago, PairingStatus::Unpaired));
+        manager.add_device(device("paired", eight_days_ago, PairingStatus::Paired));
+        manager.add_device(device("recent", now, PairingStatus::Unpaired));
+
+        let removed = manager
+            .remove_stale_devices(DEFAULT_STALE_DEVICE_AGE)
+            .unwrap();
+
+        assert_eq!(removed, vec!["old".to_string()]);
+        assert!(manager.has_device("paired"));
+        assert!(manager.has_device("recent"));
+
+        // The pruned registry was persisted
+        let reloaded = DeviceManager::new(&registry_path).unwrap();
+        assert!(!reloaded.has_device("old"));
+        assert!(reloaded.has_device("paired"));
+        assert!(reloaded.has_device("recent"));
+
+        // Nothing left to prune
+        assert!(manager
+            .remove_stale_devices(DEFAULT_STALE_DEVICE_AGE)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_stale_devices_pruned_after_restart() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry_path = temp_dir.path().join("registry.json");
+        let eight_days_ago = current_timestamp() - 8 * 24 * 60 * 60;
+
+        let mut manager = DeviceManager::new(&registry_path).unwrap();
+        let mut info = DeviceInfo::new("Old Phone", DeviceType::Phone, 1716);
+        info.device_id = "old".to_string();
+        let mut device = Device::from_discovery(info);
+        device.mark_connected("192.168.1.100".to_string(), 1716);
+        device.last_seen = eight_days_ago;
+        manager.add_device(device);
+        manager.save_registry().unwrap();
+
+        // Loading resets the connection but not when the device was seen
+        let mut reloaded = DeviceManager::new(&registry_path).unwrap();
+        let device = reloaded.get_device("old").unwrap();
+        assert!(!device.is_connected());
+        assert_eq!(device.last_seen, eight_days_ago);
+
+        let removed = reloaded
+            .remove_stale_devices(DEFAULT_STALE_DEVICE_AGE)
+            .unwrap();
+        assert_eq!(removed, vec!["old".to_string()]);
+    }
 }
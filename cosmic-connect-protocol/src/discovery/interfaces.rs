@@ -0,0 +1,162 @@
+//! Network Interface Selection
+//!
+//! On machines with VPNs or several network cards, broadcasting identities on
+//! every interface can leave the one the phone is on unreachable, or announce
+//! the desktop on networks it shouldn't be found on. Discovery can be limited
+//! to named interfaces with [`DiscoveryConfig::interfaces`](super::DiscoveryConfig).
+//!
+//! With interfaces selected, identities are sent as directed broadcasts to
+//! each selected interface's subnet instead of the limited broadcast address,
+//! and identities received from other networks are ignored. The listener
+//! looks the interfaces up again every few seconds, so interfaces that come
+//! and go are followed without a lookup per datagram.
+
+use super::service::BROADCAST_ADDR;
+use crate::{ProtocolError, Result};
+use std::net::{IpAddr, Ipv4Addr, SocketAddrV4};
+
+/// IPv4 address assigned to a network interface
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterfaceAddr {
+    /// Interface name, e.g. `wlan0`
+    pub name: String,
+    /// Address of the interface
+    pub addr: Ipv4Addr,
+    /// Netmask of the interface's subnet
+    pub netmask: Ipv4Addr,
+}
+
+impl InterfaceAddr {
+    /// Create an interface address
+    pub fn new(name: impl Into<String>, addr: Ipv4Addr, netmask: Ipv4Addr) -> Self {
+        Self {
+            name: name.into(),
+            addr,
+            netmask,
+        }
+    }
+
+    /// Directed broadcast address of the interface's subnet
+    pub fn broadcast(&self) -> Ipv4Addr {
+        Ipv4Addr::from(u32::from(self.addr) | !u32::from(self.netmask))
+    }
+
+    /// Whether an address is on the interface's subnet
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let IpAddr::V4(ip) = ip else {
+            return false;
+        };
+        let mask = u32::from(self.netmask);
+        u32::from(ip) & mask == u32::from(self.addr) & mask
+    }
+}
+
+/// Lists the IPv4 addresses of the local network interfaces
+pub trait InterfaceEnumerator: Send + Sync {
+    /// Addresses of all interfaces that are up
+    fn interfaces(&self) -> Result<Vec<InterfaceAddr>>;
+}
+
+/// Enumerates interfaces with `getifaddrs`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemInterfaces;
+
+impl InterfaceEnumerator for SystemInterfaces {
+    fn interfaces(&self) -> Result<Vec<InterfaceAddr>> {
+        let addrs = nix::ifaddrs::getifaddrs().map_err(|e| {
+            ProtocolError::NetworkError(format!("Failed to list interfaces: {}", e))
+        })?;
+
+        Ok(addrs
+            .filter(|ifaddr| ifaddr.flags.contains(nix::net::if_::InterfaceFlags::IFF_UP))
+            .filter_map(|ifaddr| {
+                let addr = ifaddr.address?.as_sockaddr_in().copied()?;
+                let netmask = ifaddr.netmask?.as_sockaddr_in().copied()?;
+                Some(InterfaceAddr::new(
+                    ifaddr.interface_name,
+                    *SocketAddrV4::from(addr).ip(),
+                    *SocketAddrV4::from(netmask).ip(),
+                ))
+            })
+            .collect())
+    }
+}
+
+/// Addresses of the interfaces named in `names`
+pub fn select_interfaces(names: &[String], interfaces: Vec<InterfaceAddr>) -> Vec<InterfaceAddr> {
+    interfaces
+        .into_iter()
+        .filter(|interface| names.contains(&interface.name))
+        .collect()
+}
+
+/// Addresses to broadcast identities to
+///
+/// Without selected interfaces this is the limited broadcast address plus the
+/// additional addresses. With them, it is the directed broadcast address of
+/// each selected interface plus the additional addresses on their subnets.
+pub fn broadcast_targets(
+    selected: Option<&[InterfaceAddr]>,
+    additional: &[Ipv4Addr],
+) -> Vec<Ipv4Addr> {
+    let Some(selected) = selected else {
+        let mut targets = vec![BROADCAST_ADDR];
+        targets.extend_from_slice(additional);
+        return targets;
+    };
+
+    let mut targets: Vec<Ipv4Addr> = Vec::new();
+    let on_selected = additional
+        .iter()
+        .filter(|addr| selected.iter().any(|i| i.contains(IpAddr::V4(**addr))));
+    for addr in selected
+        .iter()
+        .map(InterfaceAddr::broadcast)
+        .chain(on_selected.copied())
+    {
+        if !targets.contains(&addr) {
+            targets.push(addr);
+        }
+    }
+    targets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interfaces() -> Vec<InterfaceAddr> {
+        let mask = Ipv4Addr::new(255, 255, 255, 0);
+        vec![
+            InterfaceAddr::new("wlan0", Ipv4Addr::new(192, 168, 1, 20), mask),
+            InterfaceAddr::new("tun0", Ipv4Addr::new(10, 8, 0, 2), mask),
+            InterfaceAddr::new("waydroid0", Ipv4Addr::new(192, 168, 240, 1), mask),
+        ]
+    }
+
+    #[test]
+    fn test_additional_addrs_kept_only_on_selected_subnets() {
+        let waydroid = Ipv4Addr::new(192, 168, 240, 255);
+
+        let selected = select_interfaces(&["wlan0".to_string()], interfaces());
+        assert_eq!(
+            broadcast_targets(Some(&selected), &[waydroid]),
+            vec![Ipv4Addr::new(192, 168, 1, 255)]
+        );
+        assert!(selected[0].contains("192.168.1.42".parse().unwrap()));
+        assert!(!selected[0].contains("10.8.0.1".parse().unwrap()));
+
+        let names = ["wlan0".to_string(), "waydroid0".to_string()];
+        let selected = select_interfaces(&names, interfaces());
+        assert_eq!(
+            broadcast_targets(Some(&selected), &[waydroid]),
+            vec![Ipv4Addr::new(192, 168, 1, 255), waydroid]
+        );
+
+        // Unset means every interface through the limited broadcast address
+        assert_eq!(
+            broadcast_targets(None, &[waydroid]),
+            vec![BROADCAST_ADDR, waydroid]
+        );
+    }
+}
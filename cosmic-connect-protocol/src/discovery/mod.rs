@@ -5,8 +5,11 @@
 //! ## Discovery Protocol
 //!
 //! 1. **Broadcast**: Send identity packet via UDP broadcast on port 1816
-//! 2. **Listen**: Listen for identity packets from other devices
+//! 2. **Listen**: Listen for identity packets from other devices, dropping
+//!    oversized datagrams, non-identity packets and implausible identities
 //! 3. **Track**: Track device presence and timeouts
+//! 4. **Probe**: Devices broadcasts don't reach can be looked up by address
+//!    with [`DiscoveryService::probe`], which exchanges identities over TLS
 //!
 //! ## Usage
 //!
@@ -52,6 +55,7 @@
 
 pub mod bluetooth;
 pub mod events;
+pub mod interfaces;
 pub mod service;
 pub mod unified;
 
@@ -61,7 +65,6 @@ use serde_json::json;
 use std::net::{IpAddr, SocketAddr, UdpSocket};
 use std::time::Duration;
 use tracing::{debug, error, info, warn};
-use uuid::Uuid;
 
 /// Default timeout for discovery operations
 pub const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(5);
@@ -72,10 +75,11 @@ pub use bluetooth::{
     DEFAULT_BT_SCAN_INTERVAL,
 };
 pub use events::DiscoveryEvent;
+pub use interfaces::{InterfaceAddr, InterfaceEnumerator, SystemInterfaces};
 pub use service::{
     default_additional_broadcast_addrs, DiscoveryConfig, DiscoveryService, BROADCAST_ADDR,
-    DEFAULT_BROADCAST_INTERVAL, DEFAULT_DEVICE_TIMEOUT, DISCOVERY_PORT, PORT_RANGE_END,
-    PORT_RANGE_START,
+    DEFAULT_BROADCAST_INTERVAL, DEFAULT_DEVICE_TIMEOUT, DISCOVERY_PORT, MAX_IDENTITY_DATAGRAM_SIZE,
+    PORT_RANGE_END, PORT_RANGE_START, PROBE_TIMEOUT,
 };
 pub use unified::{UnifiedDiscoveryConfig, UnifiedDiscoveryService};
 
@@ -85,9 +89,13 @@ pub use unified::{UnifiedDiscoveryConfig, UnifiedDiscoveryService};
 pub enum DeviceType {
     Desktop,
     Laptop,
+    #[serde(alias = "smartphone")]
     Phone,
     Tablet,
     Tv,
+    /// Device type not known to this version
+    #[serde(other)]
+    Unknown,
 }
 
 impl DeviceType {
@@ -99,6 +107,22 @@ impl DeviceType {
             DeviceType::Phone => "phone",
             DeviceType::Tablet => "tablet",
             DeviceType::Tv => "tv",
+            DeviceType::Unknown => "unknown",
+        }
+    }
+
+    /// Parse the `deviceType` field of an identity packet
+    ///
+    /// Older Android clients report phones as `smartphone`. Types added by
+    /// newer clients map to [`DeviceType::Unknown`] instead of failing.
+    pub fn from_identity_str(device_type: &str) -> Self {
+        match device_type {
+            "desktop" => DeviceType::Desktop,
+            "laptop" => DeviceType::Laptop,
+            "phone" | "smartphone" => DeviceType::Phone,
+            "tablet" => DeviceType::Tablet,
+            "tv" => DeviceType::Tv,
+            _ => DeviceType::Unknown,
         }
     }
 }
@@ -157,7 +181,7 @@ impl DeviceInfo {
         }
 
         Self {
-            device_id: Self::generate_device_id(),
+            device_id: crate::identity::generate_device_id(),
             device_name,
             device_type,
             protocol_version: PROTOCOL_VERSION,
@@ -167,13 +191,6 @@ impl DeviceInfo {
         }
     }
 
-    /// Generate a UUIDv4 device ID with underscores
-    ///
-    /// CConnect uses UUIDs with underscores instead of hyphens
-    fn generate_device_id() -> String {
-        Uuid::new_v4().to_string().replace('-', "_")
-    }
-
     /// Create a DeviceInfo with explicit device ID
     pub fn with_id(
         device_id: impl Into<String>,
@@ -255,19 +272,7 @@ impl DeviceInfo {
             .get_body_field::<String>("deviceType")
             .ok_or_else(|| ProtocolError::InvalidPacket("Missing deviceType".to_string()))?;
 
-        let device_type = match device_type_str.as_str() {
-            "desktop" => DeviceType::Desktop,
-            "laptop" => DeviceType::Laptop,
-            "phone" => DeviceType::Phone,
-            "tablet" => DeviceType::Tablet,
-            "tv" => DeviceType::Tv,
-            _ => {
-                return Err(ProtocolError::InvalidPacket(format!(
-                    "Unknown device type: {}",
-                    device_type_str
-                )))
-            }
-        };
+        let device_type = DeviceType::from_identity_str(&device_type_str);
 
         let protocol_version = packet
             .get_body_field::<u32>("protocolVersion")
@@ -459,6 +464,37 @@ mod tests {
         assert_eq!(DeviceType::Phone.as_str(), "phone");
         assert_eq!(DeviceType::Tablet.as_str(), "tablet");
         assert_eq!(DeviceType::Tv.as_str(), "tv");
+        assert_eq!(DeviceType::Unknown.as_str(), "unknown");
+    }
+
+    #[test]
+    fn test_identity_device_types() {
+        let cases = [
+            ("desktop", DeviceType::Desktop),
+            ("laptop", DeviceType::Laptop),
+            ("phone", DeviceType::Phone),
+            ("smartphone", DeviceType::Phone),
+            ("tablet", DeviceType::Tablet),
+            ("tv", DeviceType::Tv),
+            ("car", DeviceType::Unknown),
+        ];
+
+        for (identity_str, expected) in cases {
+            let packet = Packet::new(
+                "cconnect.identity",
+                json!({
+                    "deviceId": "test_device",
+                    "deviceName": "Test",
+                    "deviceType": identity_str,
+                    "tcpPort": 1716,
+                }),
+            );
+            let info = DeviceInfo::from_identity_packet(&packet).unwrap();
+            assert_eq!(info.device_type, expected, "{}", identity_str);
+
+            let deserialized: DeviceType = serde_json::from_value(json!(identity_str)).unwrap();
+            assert_eq!(deserialized, expected, "{}", identity_str);
+        }
     }
 
     #[test]
@@ -1,9 +1,13 @@
 use super::events::DiscoveryEvent;
-use crate::{DeviceInfo, Packet, ProtocolError, Result};
+use super::interfaces::{
+    broadcast_targets, select_interfaces, InterfaceAddr, InterfaceEnumerator, SystemInterfaces,
+};
+use crate::plugins::PluginManager;
+use crate::{DeviceInfo, Packet, ProtocolError, Result, TlsConfig, TlsConnection};
 use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
 use std::sync::Arc;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::{mpsc, RwLock};
 use tokio::time::interval;
 use tracing::{debug, error, info, warn};
@@ -15,6 +19,18 @@ pub const BROADCAST_ADDR: Ipv4Addr = Ipv4Addr::new(255, 255, 255, 255);
 pub const DEFAULT_BROADCAST_INTERVAL: Duration = Duration::from_secs(5);
 pub const DEFAULT_DEVICE_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// Largest identity datagram accepted, larger ones are dropped unparsed
+pub const MAX_IDENTITY_DATAGRAM_SIZE: usize = 8192;
+
+/// Longest device ID accepted from an identity packet
+const MAX_DEVICE_ID_LENGTH: usize = 128;
+
+/// How long probing an address may take, from connecting to the identity exchange
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long the listener uses the discovery interfaces before looking them up again
+const INTERFACE_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
 /// Additional broadcast addresses for cross-network discovery
 /// Includes Waydroid subnet (192.168.240.255) by default
 pub fn default_additional_broadcast_addrs() -> Vec<Ipv4Addr> {
@@ -30,6 +46,14 @@ pub struct DiscoveryConfig {
     pub enable_timeout_check: bool,
     /// Additional broadcast addresses for cross-network discovery (e.g., Waydroid, VMs)
     pub additional_broadcast_addrs: Vec<Ipv4Addr>,
+    /// Drop identity packets from loopback addresses
+    ///
+    /// Off by default so local test devices on 127.0.0.1 are still found.
+    pub ignore_loopback: bool,
+    /// Interfaces to discover devices on, by name, or all if unset
+    ///
+    /// See [`super::interfaces`].
+    pub interfaces: Option<Vec<String>>,
 }
 
 impl Default for DiscoveryConfig {
@@ -39,6 +63,8 @@ impl Default for DiscoveryConfig {
             device_timeout: DEFAULT_DEVICE_TIMEOUT,
             enable_timeout_check: true,
             additional_broadcast_addrs: default_additional_broadcast_addrs(),
+            ignore_loopback: false,
+            interfaces: None,
         }
     }
 }
@@ -51,6 +77,8 @@ pub struct DiscoveryService {
     config: DiscoveryConfig,
     shutdown_tx: Option<tokio::sync::oneshot::Sender<()>>,
     last_seen: Arc<RwLock<HashMap<String, u64>>>,
+    interface_enumerator: Arc<dyn InterfaceEnumerator>,
+    plugin_manager: Option<Arc<RwLock<PluginManager>>>,
 }
 
 impl DiscoveryService {
@@ -65,9 +93,27 @@ impl DiscoveryService {
             config,
             shutdown_tx: None,
             last_seen: Arc::new(RwLock::new(HashMap::new())),
+            interface_enumerator: Arc::new(SystemInterfaces),
+            plugin_manager: None,
         })
     }
 
+    /// Use another source of interface addresses, e.g. in tests
+    pub fn with_interface_enumerator(mut self, enumerator: Arc<dyn InterfaceEnumerator>) -> Self {
+        self.interface_enumerator = enumerator;
+        self
+    }
+
+    /// Advertise the capabilities of the plugins registered with a manager
+    ///
+    /// The capability lists are rebuilt for every broadcast, so plugins
+    /// registered or unregistered while discovery runs are reflected in the
+    /// next identity packet.
+    pub fn with_plugin_manager(mut self, plugin_manager: Arc<RwLock<PluginManager>>) -> Self {
+        self.plugin_manager = Some(plugin_manager);
+        self
+    }
+
     pub fn with_defaults(device_info: DeviceInfo) -> Result<Self> {
         Self::new(device_info, DiscoveryConfig::default())
     }
@@ -130,36 +176,78 @@ impl DiscoveryService {
         old_rx
     }
 
+    /// Addresses of the configured interfaces, or `None` to use all of them
+    ///
+    /// Interfaces come and go, e.g. when a VPN connects, so they are looked up
+    /// again on every call.
+    fn selected_interfaces(
+        enumerator: &dyn InterfaceEnumerator,
+        names: Option<&[String]>,
+    ) -> Option<Vec<InterfaceAddr>> {
+        let names = names?;
+        match enumerator.interfaces() {
+            Ok(interfaces) => Some(select_interfaces(names, interfaces)),
+            Err(e) => {
+                warn!("{}, discovery is paused", e);
+                Some(Vec::new())
+            }
+        }
+    }
+
+    /// Addresses identity packets are broadcast to
+    ///
+    /// Each target is sent to on the discovery port and on the KDE Connect
+    /// port for compatibility.
+    fn broadcast_addrs(
+        enumerator: &dyn InterfaceEnumerator,
+        config: &DiscoveryConfig,
+    ) -> Vec<SocketAddr> {
+        let selected = Self::selected_interfaces(enumerator, config.interfaces.as_deref());
+        let targets = broadcast_targets(selected.as_deref(), &config.additional_broadcast_addrs);
+
+        let kdeconnect_port = 1716u16;
+        [DISCOVERY_PORT, kdeconnect_port]
+            .into_iter()
+            .flat_map(|port| {
+                targets
+                    .iter()
+                    .map(move |addr| SocketAddr::new(IpAddr::V4(*addr), port))
+            })
+            .collect()
+    }
+
+    /// Identity packet to broadcast
+    ///
+    /// With a plugin manager, the capability lists come from the plugins
+    /// registered right now rather than from `device_info`.
+    async fn identity_packet(
+        device_info: &DeviceInfo,
+        plugin_manager: Option<&RwLock<PluginManager>>,
+    ) -> Packet {
+        let Some(plugin_manager) = plugin_manager else {
+            return device_info.to_identity_packet();
+        };
+
+        let mut identity = device_info.clone();
+        plugin_manager
+            .read()
+            .await
+            .advertise_capabilities(&mut identity)
+            .await;
+        identity.to_identity_packet()
+    }
+
     fn spawn_broadcaster(&self, mut shutdown_rx: tokio::sync::oneshot::Receiver<()>) {
         let socket = self.socket.clone();
         let device_info = self.device_info.clone();
         let interval_duration = self.config.broadcast_interval;
-        let additional_addrs = self.config.additional_broadcast_addrs.clone();
+        let config = self.config.clone();
+        let enumerator = self.interface_enumerator.clone();
+        let plugin_manager = self.plugin_manager.clone();
         tokio::spawn(async move {
             let mut interval = interval(interval_duration);
-            let packet = device_info.to_identity_packet();
-            let bytes = match packet.to_bytes() {
-                Ok(b) => b,
-                Err(e) => {
-                    error!("Failed to serialize identity packet: {}", e);
-                    return;
-                }
-            };
-
-            // Build list of all broadcast addresses
-            let mut broadcast_addrs =
-                vec![SocketAddr::new(IpAddr::V4(BROADCAST_ADDR), DISCOVERY_PORT)];
-            for addr in &additional_addrs {
-                broadcast_addrs.push(SocketAddr::new(IpAddr::V4(*addr), DISCOVERY_PORT));
-            }
-
-            // Also broadcast to KDE Connect port for compatibility
-            let kdeconnect_port = 1716u16;
-            broadcast_addrs.push(SocketAddr::new(IpAddr::V4(BROADCAST_ADDR), kdeconnect_port));
-            for addr in &additional_addrs {
-                broadcast_addrs.push(SocketAddr::new(IpAddr::V4(*addr), kdeconnect_port));
-            }
 
+            let mut broadcast_addrs = Self::broadcast_addrs(enumerator.as_ref(), &config);
             info!(
                 "Discovery broadcaster configured with {} addresses",
                 broadcast_addrs.len()
@@ -168,6 +256,18 @@ impl DiscoveryService {
             loop {
                 tokio::select! {
                     _ = interval.tick() => {
+                        if config.interfaces.is_some() {
+                            broadcast_addrs = Self::broadcast_addrs(enumerator.as_ref(), &config);
+                        }
+                        let packet =
+                            Self::identity_packet(&device_info, plugin_manager.as_deref()).await;
+                        let bytes = match packet.to_bytes() {
+                            Ok(b) => b,
+                            Err(e) => {
+                                error!("Failed to serialize identity packet: {}", e);
+                                continue;
+                            }
+                        };
                         let mut success_count = 0;
                         for broadcast_addr in &broadcast_addrs {
                             if let Err(e) = socket.send_to(&bytes, broadcast_addr) {
@@ -200,19 +300,34 @@ impl DiscoveryService {
         let socket = self.socket.clone();
         let event_tx = self.event_tx.clone();
         let own_device_id = self.device_info.device_id.clone();
-        let own_device_info = self.device_info.clone();
+        let ignore_loopback = self.config.ignore_loopback;
         let last_seen = self.last_seen.clone();
+        let interfaces = self.config.interfaces.clone();
+        let enumerator = self.interface_enumerator.clone();
         tokio::spawn(async move {
-            let mut buf = [0u8; 8192];
+            // One byte extra so oversized datagrams can be told from ones that fit
+            let mut buf = [0u8; MAX_IDENTITY_DATAGRAM_SIZE + 1];
+            // Looking the interfaces up per datagram is a syscall each, so
+            // they are cached and refreshed periodically instead
+            let mut networks =
+                Self::selected_interfaces(enumerator.as_ref(), interfaces.as_deref());
+            let mut networks_read_at = Instant::now();
             loop {
                 match socket.recv_from(&mut buf) {
                     Ok((size, src_addr)) => {
+                        if networks_read_at.elapsed() >= INTERFACE_REFRESH_INTERVAL {
+                            networks = Self::selected_interfaces(
+                                enumerator.as_ref(),
+                                interfaces.as_deref(),
+                            );
+                            networks_read_at = Instant::now();
+                        }
                         if let Err(e) = Self::handle_packet(
                             &buf[..size],
                             src_addr,
                             &own_device_id,
-                            &own_device_info,
-                            &socket,
+                            ignore_loopback,
+                            networks.as_deref(),
                             &event_tx,
                             &last_seen,
                         )
@@ -237,19 +352,59 @@ impl DiscoveryService {
         data: &[u8],
         src_addr: SocketAddr,
         own_device_id: &str,
-        _own_device_info: &DeviceInfo,
-        _socket: &UdpSocket,
+        ignore_loopback: bool,
+        networks: Option<&[InterfaceAddr]>,
         event_tx: &mpsc::UnboundedSender<DiscoveryEvent>,
         last_seen: &Arc<RwLock<HashMap<String, u64>>>,
     ) -> Result<()> {
+        if ignore_loopback && src_addr.ip().is_loopback() {
+            return Ok(());
+        }
+        if let Some(networks) = networks {
+            if !networks.iter().any(|n| n.contains(src_addr.ip())) {
+                debug!(
+                    "Ignoring datagram from {} outside the discovery interfaces",
+                    src_addr
+                );
+                return Ok(());
+            }
+        }
+        if data.len() > MAX_IDENTITY_DATAGRAM_SIZE {
+            return Err(ProtocolError::PacketSizeExceeded(
+                data.len(),
+                MAX_IDENTITY_DATAGRAM_SIZE,
+            ));
+        }
+        // Skip the JSON parser for datagrams that can't be a packet
+        if data.iter().find(|b| !b.is_ascii_whitespace()) != Some(&b'{') {
+            return Err(ProtocolError::InvalidPacket(
+                "Datagram is not a JSON object".to_string(),
+            ));
+        }
         let packet = Packet::from_bytes(data)?;
         if !packet.is_type("cconnect.identity") {
             return Ok(());
         }
         let device_info = DeviceInfo::from_identity_packet(&packet)?;
+        Self::validate_identity(&device_info)?;
+        // Our own broadcast, looped back or received on another interface
         if device_info.device_id == own_device_id {
+            debug!("Ignoring own identity broadcast from {}", src_addr);
             return Ok(());
         }
+        let mut tcp_addr = src_addr;
+        tcp_addr.set_port(device_info.tcp_port);
+        Self::record_device(device_info, tcp_addr, event_tx, last_seen).await;
+        Ok(())
+    }
+
+    /// Mark a device as seen at `tcp_addr` and report it
+    async fn record_device(
+        device_info: DeviceInfo,
+        tcp_addr: SocketAddr,
+        event_tx: &mpsc::UnboundedSender<DiscoveryEvent>,
+        last_seen: &Arc<RwLock<HashMap<String, u64>>>,
+    ) {
         let current_time = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
@@ -258,8 +413,6 @@ impl DiscoveryService {
         let is_new = !last_seen_map.contains_key(&device_info.device_id);
         last_seen_map.insert(device_info.device_id.clone(), current_time);
         drop(last_seen_map);
-        let mut tcp_addr = src_addr;
-        tcp_addr.set_port(device_info.tcp_port);
         let event = if is_new {
             info!(
                 "Discovered new device: {} ({}) at {}",
@@ -272,6 +425,97 @@ impl DiscoveryService {
             DiscoveryEvent::tcp_updated(device_info, tcp_addr)
         };
         let _ = event_tx.send(event);
+    }
+
+    /// Look for a device at an address, e.g. one entered by the user
+    ///
+    /// Connects over TLS and exchanges identities like a regular connection,
+    /// then closes the connection. A device that answers is reported like one
+    /// found by broadcast, so devices UDP discovery can't see (multicast
+    /// filtered, another subnet) can still be added by address.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if nothing answers within [`PROBE_TIMEOUT`], the
+    /// identity exchange fails, or the address belongs to this device.
+    pub async fn probe(
+        &self,
+        tls_config: &TlsConfig,
+        address: IpAddr,
+        port: u16,
+    ) -> Result<DeviceInfo> {
+        let addr = SocketAddr::new(address, port);
+        let identity =
+            Self::identity_packet(&self.device_info, self.plugin_manager.as_deref()).await;
+
+        let device_info = Self::probe_identity(&identity, tls_config, addr).await?;
+        if device_info.device_id == self.device_info.device_id {
+            return Err(ProtocolError::InvalidState(format!(
+                "{} is this device",
+                addr
+            )));
+        }
+
+        Self::record_device(device_info.clone(), addr, &self.event_tx, &self.last_seen).await;
+        Ok(device_info)
+    }
+
+    /// Exchange identities with the device at `addr` without reporting it
+    ///
+    /// For callers that handle the device themselves, see [`Self::probe`].
+    pub async fn probe_identity(
+        identity: &Packet,
+        tls_config: &TlsConfig,
+        addr: SocketAddr,
+    ) -> Result<DeviceInfo> {
+        debug!("Probing {} for a device", addr);
+
+        let exchange = async {
+            let mut connection = TlsConnection::connect(addr, tls_config, &identity.to_bytes()?)
+                .await
+                .map_err(ProtocolError::from_handshake_error)?;
+            connection.send_packet(&identity.to_core_packet()).await?;
+            let remote = connection.receive_packet().await?;
+            Ok::<_, ProtocolError>(Packet::from_core_packet(remote))
+        };
+        let packet = tokio::time::timeout(PROBE_TIMEOUT, exchange)
+            .await
+            .map_err(|_| ProtocolError::Timeout(format!("No device answered at {}", addr)))??;
+
+        if !packet.is_type("cconnect.identity") {
+            return Err(ProtocolError::InvalidPacket(format!(
+                "Expected an identity from {}, got {}",
+                addr, packet.packet_type
+            )));
+        }
+        let device_info = DeviceInfo::from_identity_packet(&packet)?;
+        Self::validate_identity(&device_info)?;
+
+        info!(
+            "Probed device: {} ({}) at {}",
+            device_info.device_name,
+            device_info.device_type.as_str(),
+            addr
+        );
+        Ok(device_info)
+    }
+
+    /// Reject identities no real device would announce
+    fn validate_identity(device_info: &DeviceInfo) -> Result<()> {
+        if device_info.device_id.is_empty() || device_info.device_id.len() > MAX_DEVICE_ID_LENGTH {
+            return Err(ProtocolError::InvalidPacket(format!(
+                "Invalid deviceId of {} bytes",
+                device_info.device_id.len()
+            )));
+        }
+        if device_info.device_name.trim().is_empty() {
+            return Err(ProtocolError::InvalidPacket("Empty deviceName".to_string()));
+        }
+        if device_info.tcp_port == 0 {
+            return Err(ProtocolError::InvalidPacket(
+                "Invalid tcpPort 0".to_string(),
+            ));
+        }
         Ok(())
     }
 
@@ -306,3 +550,290 @@ impl DiscoveryService {
         Ok(self.socket.local_addr()?.port())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DeviceType;
+
+    async fn handle(
+        own: &DeviceInfo,
+        sender: &DeviceInfo,
+        src_addr: SocketAddr,
+        ignore_loopback: bool,
+    ) -> Option<DiscoveryEvent> {
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let last_seen = Arc::new(RwLock::new(HashMap::new()));
+        let data = sender.to_identity_packet().to_bytes().unwrap();
+
+        DiscoveryService::handle_packet(
+            &data,
+            src_addr,
+            &own.device_id,
+            ignore_loopback,
+            None,
+            &event_tx,
+            &last_seen,
+        )
+        .await
+        .unwrap();
+        event_rx.try_recv().ok()
+    }
+
+    #[tokio::test]
+    async fn test_own_identity_is_dropped() {
+        let own = DeviceInfo::new("Desktop", DeviceType::Desktop, 1816);
+        let lan: SocketAddr = "192.168.1.20:1816".parse().unwrap();
+
+        assert!(handle(&own, &own, lan, false).await.is_none());
+
+        let phone = DeviceInfo::new("Phone", DeviceType::Phone, 1816);
+        assert!(matches!(
+            handle(&own, &phone, lan, false).await,
+            Some(DiscoveryEvent::DeviceDiscovered { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_malformed_datagrams_produce_no_device() {
+        let own = DeviceInfo::new("Desktop", DeviceType::Desktop, 1816);
+        let phone = DeviceInfo::new("Phone", DeviceType::Phone, 1816);
+        let lan: SocketAddr = "192.168.1.20:1816".parse().unwrap();
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let last_seen = Arc::new(RwLock::new(HashMap::new()));
+
+        let identity = phone.to_identity_packet().to_bytes().unwrap();
+        let mut no_port = phone.to_identity_packet();
+        no_port.body["tcpPort"] = serde_json::json!(0);
+        let mut oversized = identity.clone();
+        oversized.resize(MAX_IDENTITY_DATAGRAM_SIZE + 1, b' ');
+
+        let datagrams: Vec<Vec<u8>> = vec![
+            identity[..identity.len() / 2].to_vec(),
+            vec![0xff, 0x00, 0x13, 0x37],
+            b"hello".to_vec(),
+            b"{\"id\": 1}".to_vec(),
+            Packet::new("cconnect.ping", serde_json::json!({}))
+                .to_bytes()
+                .unwrap(),
+            no_port.to_bytes().unwrap(),
+            oversized,
+            Vec::new(),
+        ];
+        for data in &datagrams {
+            let _ = DiscoveryService::handle_packet(
+                data,
+                lan,
+                &own.device_id,
+                false,
+                None,
+                &event_tx,
+                &last_seen,
+            )
+            .await;
+        }
+        assert!(event_rx.try_recv().is_err());
+        assert!(last_seen.read().await.is_empty());
+
+        // A valid identity after the garbage is still handled
+        DiscoveryService::handle_packet(
+            &identity,
+            lan,
+            &own.device_id,
+            false,
+            None,
+            &event_tx,
+            &last_seen,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(
+            event_rx.try_recv(),
+            Ok(DiscoveryEvent::DeviceDiscovered { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_loopback_filter() {
+        let own = DeviceInfo::new("Desktop", DeviceType::Desktop, 1816);
+        let phone = DeviceInfo::new("Phone", DeviceType::Phone, 1816);
+        let loopback: SocketAddr = "127.0.0.1:1816".parse().unwrap();
+
+        assert!(handle(&own, &phone, loopback, true).await.is_none());
+        assert!(handle(&own, &phone, loopback, false).await.is_some());
+    }
+
+    struct MockInterfaces;
+
+    impl InterfaceEnumerator for MockInterfaces {
+        fn interfaces(&self) -> Result<Vec<InterfaceAddr>> {
+            let mask = Ipv4Addr::new(255, 255, 255, 0);
+            Ok(vec![
+                InterfaceAddr::new("wlan0", Ipv4Addr::new(192, 168, 1, 20), mask),
+                InterfaceAddr::new("tun0", Ipv4Addr::new(10, 8, 0, 2), mask),
+                InterfaceAddr::new("docker0", Ipv4Addr::new(172, 17, 0, 1), mask),
+            ])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_only_configured_interfaces_used() {
+        let config = DiscoveryConfig {
+            interfaces: Some(vec!["wlan0".to_string()]),
+            ..Default::default()
+        };
+        let addrs = DiscoveryService::broadcast_addrs(&MockInterfaces, &config);
+        assert_eq!(
+            addrs,
+            vec![
+                "192.168.1.255:1816".parse().unwrap(),
+                "192.168.1.255:1716".parse::<SocketAddr>().unwrap(),
+            ]
+        );
+
+        // Identities from other networks are ignored
+        let own = DeviceInfo::new("Desktop", DeviceType::Desktop, 1816);
+        let phone = DeviceInfo::new("Phone", DeviceType::Phone, 1816);
+        let networks =
+            DiscoveryService::selected_interfaces(&MockInterfaces, config.interfaces.as_deref())
+                .unwrap();
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let last_seen = Arc::new(RwLock::new(HashMap::new()));
+        let data = phone.to_identity_packet().to_bytes().unwrap();
+        for src in ["10.8.0.1:1816", "192.168.1.42:1816"] {
+            DiscoveryService::handle_packet(
+                &data,
+                src.parse().unwrap(),
+                &own.device_id,
+                false,
+                Some(&networks),
+                &event_tx,
+                &last_seen,
+            )
+            .await
+            .unwrap();
+        }
+        assert!(matches!(
+            event_rx.try_recv(),
+            Ok(DiscoveryEvent::DeviceDiscovered { .. })
+        ));
+        assert!(event_rx.try_recv().is_err());
+
+        // Unset uses every interface
+        let addrs = DiscoveryService::broadcast_addrs(&MockInterfaces, &DiscoveryConfig::default());
+        assert!(addrs.contains(&SocketAddr::new(IpAddr::V4(BROADCAST_ADDR), DISCOVERY_PORT)));
+    }
+
+    #[tokio::test]
+    async fn test_identity_follows_registered_plugins() {
+        use crate::plugins::battery::BatteryPluginFactory;
+        use crate::plugins::ping::PingPluginFactory;
+
+        let own = DeviceInfo::new("Desktop", DeviceType::Desktop, 1816);
+        let manager = RwLock::new(PluginManager::new());
+        manager
+            .write()
+            .await
+            .register_factory(Arc::new(PingPluginFactory))
+            .unwrap();
+
+        let advertised = |packet: Packet| DeviceInfo::from_identity_packet(&packet).unwrap();
+
+        let identity = advertised(DiscoveryService::identity_packet(&own, Some(&manager)).await);
+        assert!(identity
+            .incoming_capabilities
+            .contains(&"cconnect.ping".to_string()));
+        assert!(!identity
+            .incoming_capabilities
+            .contains(&"cconnect.battery".to_string()));
+
+        manager
+            .write()
+            .await
+            .register_factory(Arc::new(BatteryPluginFactory))
+            .unwrap();
+        let identity = advertised(DiscoveryService::identity_packet(&own, Some(&manager)).await);
+        assert!(identity
+            .incoming_capabilities
+            .contains(&"cconnect.battery".to_string()));
+        assert!(identity
+            .outgoing_capabilities
+            .contains(&"cconnect.battery".to_string()));
+
+        manager.write().await.unregister_factory("ping");
+        let identity = advertised(DiscoveryService::identity_packet(&own, Some(&manager)).await);
+        assert!(!identity
+            .incoming_capabilities
+            .contains(&"cconnect.ping".to_string()));
+        assert!(!identity
+            .outgoing_capabilities
+            .contains(&"cconnect.ping".to_string()));
+
+        // Without a manager the identity is broadcast as configured
+        let identity = advertised(DiscoveryService::identity_packet(&own, None).await);
+        assert!(identity.incoming_capabilities.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_probe_finds_device_behind_tls_server() {
+        use crate::{CertificateInfo, TlsDeviceInfo, TlsServer};
+
+        let phone = DeviceInfo::new("Phone", DeviceType::Phone, 1816);
+        let phone_cert = CertificateInfo::generate(&phone.device_id).unwrap();
+        let server = TlsServer::new(
+            SocketAddr::from((Ipv4Addr::LOCALHOST, 0)),
+            &phone_cert,
+            TlsDeviceInfo {
+                device_id: phone.device_id.clone(),
+                device_name: phone.device_name.clone(),
+                device_type: phone.device_type.as_str().to_string(),
+                protocol_version: phone.protocol_version as i32,
+                incoming_capabilities: Vec::new(),
+                outgoing_capabilities: Vec::new(),
+                tcp_port: phone.tcp_port,
+            },
+        )
+        .await
+        .unwrap();
+        let port = server.local_addr().port();
+        let accept =
+            tokio::spawn(async move { server.accept().await.map(|(_, identity)| identity) });
+
+        let own = DeviceInfo::new("Desktop", DeviceType::Desktop, 1816);
+        let own_cert = CertificateInfo::generate(&own.device_id).unwrap();
+        let service = DiscoveryService::with_defaults(own.clone()).unwrap();
+        let mut events = service.subscribe().await;
+
+        let found = service
+            .probe(
+                &TlsConfig::new(&own_cert).unwrap(),
+                IpAddr::V4(Ipv4Addr::LOCALHOST),
+                port,
+            )
+            .await
+            .unwrap();
+        assert_eq!(found.device_id, phone.device_id);
+
+        match events.try_recv() {
+            Ok(DiscoveryEvent::DeviceDiscovered {
+                info,
+                transport_address,
+                ..
+            }) => {
+                assert_eq!(info.device_id, phone.device_id);
+                assert_eq!(
+                    transport_address,
+                    crate::TransportAddress::Tcp(SocketAddr::from((Ipv4Addr::LOCALHOST, port)))
+                );
+            }
+            other => panic!("Expected DeviceDiscovered, got {:?}", other),
+        }
+
+        // The phone saw our identity
+        let identity = accept.await.unwrap().unwrap();
+        assert_eq!(
+            identity.get_body_field::<String>("deviceId").as_deref(),
+            Some(own.device_id.as_str())
+        );
+    }
+}
@@ -114,6 +114,11 @@
 //! Secure connection and certificate validation failures.
 //! Automatically converted from `rustls::Error`.
 //!
+//! ### TLS Handshake Errors
+//! Handshakes of device connections that failed, carrying the `rustls::Error`.
+//! A handshake rejected over the device's certificate means the pairing is no
+//! longer valid and the device has to be re-paired; retrying cannot succeed.
+//!
 //! ### Certificate Errors
 //! Certificate generation and management failures.
 //! Automatically converted from `rcgen::Error`.
@@ -259,6 +264,15 @@ pub enum ProtocolError {
     #[error("Core protocol error: {0}")]
     CoreProtocol(#[from] cosmic_connect_core::ProtocolError),
 
+    /// TLS handshake failure
+    ///
+    /// This error occurs when the TLS handshake with a device fails, e.g.
+    /// because either side no longer trusts the other's certificate or the
+    /// devices share no protocol version. Created with
+    /// [`ProtocolError::from_handshake_error`].
+    #[error("TLS handshake failed: {0}")]
+    TlsHandshake(rustls::Error),
+
     /// Transport layer error
     ///
     /// This error occurs during transport operations (TCP, Bluetooth, etc.).
@@ -455,6 +469,68 @@ impl ProtocolError {
         }
     }
 
+    /// Convert the error of a failed connection attempt, picking out TLS handshake failures
+    ///
+    /// The TLS layer reports handshake failures wrapped in I/O or core protocol
+    /// errors. If a `rustls::Error` is found in the error's source chain, a
+    /// [`ProtocolError::TlsHandshake`] is returned, otherwise the error is
+    /// converted as usual.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cosmic_connect_core::ProtocolError;
+    /// use std::io::{Error, ErrorKind};
+    ///
+    /// let rejected = rustls::Error::InvalidCertificate(rustls::CertificateError::UnknownIssuer);
+    /// let io_error = Error::new(ErrorKind::InvalidData, rejected);
+    /// let error = ProtocolError::from_handshake_error(io_error);
+    ///
+    /// assert!(matches!(error, ProtocolError::TlsHandshake(_)));
+    /// assert!(error.is_unknown_certificate());
+    /// ```
+    pub fn from_handshake_error<E>(error: E) -> Self
+    where
+        E: std::error::Error + Into<ProtocolError> + 'static,
+    {
+        let mut current: Option<&(dyn std::error::Error + 'static)> = Some(&error);
+        while let Some(source) = current {
+            if let Some(tls_error) = source.downcast_ref::<rustls::Error>() {
+                return ProtocolError::TlsHandshake(tls_error.clone());
+            }
+            // I/O errors don't expose their inner error as the source
+            current = match source.downcast_ref::<std::io::Error>() {
+                Some(io_error) => io_error
+                    .get_ref()
+                    .map(|inner| inner as &(dyn std::error::Error + 'static)),
+                None => source.source(),
+            };
+        }
+        error.into()
+    }
+
+    /// Check if this is a TLS handshake rejected over a certificate
+    ///
+    /// This happens when we no longer trust the device's certificate, or the
+    /// device no longer trusts ours, e.g. after it was reset or re-paired
+    /// elsewhere. Reconnecting cannot succeed until the device is re-paired.
+    pub fn is_unknown_certificate(&self) -> bool {
+        use rustls::AlertDescription;
+
+        matches!(
+            self,
+            ProtocolError::TlsHandshake(
+                rustls::Error::InvalidCertificate(_)
+                    | rustls::Error::NoCertificatesPresented
+                    | rustls::Error::AlertReceived(
+                        AlertDescription::BadCertificate
+                            | AlertDescription::UnknownCA
+                            | AlertDescription::CertificateUnknown
+                    )
+            )
+        )
+    }
+
     /// Check if this error is recoverable (transient error that can be retried)
     ///
     /// Returns `true` if the error might succeed on retry, `false` if it's permanent.
@@ -507,7 +583,7 @@ impl ProtocolError {
                 | ProtocolError::Configuration(_)
                 | ProtocolError::ProtocolVersionMismatch(_)
                 | ProtocolError::Database(_)
-        )
+        ) || self.is_unknown_certificate()
     }
 
     /// Get a user-friendly error message suitable for display in UI
@@ -597,6 +673,15 @@ impl ProtocolError {
             ProtocolError::Tls(e) => {
                 format!("Secure connection error: {}.", e)
             }
+            ProtocolError::TlsHandshake(e) if self.is_unknown_certificate() => {
+                format!(
+                    "Device certificate rejected: {}. Re-pair the device to connect again.",
+                    e
+                )
+            }
+            ProtocolError::TlsHandshake(e) => {
+                format!("Secure connection handshake failed: {}.", e)
+            }
             ProtocolError::Certificate(e) => {
                 format!("Certificate error: {}. You may need to re-pair.", e)
             }
@@ -695,4 +780,64 @@ mod tests {
 
         assert!(matches!(protocol_error, ProtocolError::Json(_)));
     }
+
+    #[test]
+    fn test_handshake_cert_error_is_not_retried() {
+        use std::io::{Error, ErrorKind};
+
+        let rejected = rustls::Error::InvalidCertificate(rustls::CertificateError::UnknownIssuer);
+        let error =
+            ProtocolError::from_handshake_error(Error::new(ErrorKind::InvalidData, rejected));
+        assert!(matches!(error, ProtocolError::TlsHandshake(_)));
+        assert!(error.is_unknown_certificate());
+        assert!(!error.is_recoverable());
+        assert!(error.requires_user_action());
+
+        let mismatch = rustls::Error::PeerIncompatible(
+            rustls::PeerIncompatible::ServerDoesNotSupportTls12Or13,
+        );
+        let error =
+            ProtocolError::from_handshake_error(Error::new(ErrorKind::InvalidData, mismatch));
+        assert!(matches!(error, ProtocolError::TlsHandshake(_)));
+        assert!(!error.is_unknown_certificate());
+
+        // Plain network errors are unchanged
+        let refused = Error::new(ErrorKind::ConnectionRefused, "refused");
+        assert!(matches!(
+            ProtocolError::from_handshake_error(refused),
+            ProtocolError::Io(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_failed_tls_handshake_is_unknown_certificate() {
+        use crate::{CertificateInfo, TlsConfig};
+        use std::sync::Arc;
+        use tokio_rustls::{TlsAcceptor, TlsConnector};
+
+        // A device presenting a certificate we don't trust
+        let certificate = CertificateInfo::generate("phone").unwrap();
+        let acceptor = TlsAcceptor::from(TlsConfig::new(&certificate).unwrap().server_config());
+        let (client, server) = tokio::io::duplex(16 * 1024);
+        let device = tokio::spawn(async move { acceptor.accept(server).await });
+
+        let untrusting = rustls::ClientConfig::builder()
+            .with_root_certificates(rustls::RootCertStore::empty())
+            .with_no_client_auth();
+        let server_name = rustls::pki_types::ServerName::try_from("kdeconnect").unwrap();
+        let rejected = TlsConnector::from(Arc::new(untrusting))
+            .connect(server_name, client)
+            .await
+            .map(|_| ())
+            .unwrap_err();
+        let error = ProtocolError::from_handshake_error(rejected);
+        assert!(matches!(error, ProtocolError::TlsHandshake(_)));
+        assert!(error.is_unknown_certificate());
+
+        // The device learns its certificate was rejected from our alert
+        let alerted = device.await.unwrap().map(|_| ()).unwrap_err();
+        let error = ProtocolError::from_handshake_error(alerted);
+        assert!(matches!(error, ProtocolError::TlsHandshake(_)));
+        assert!(error.is_unknown_certificate());
+    }
 }
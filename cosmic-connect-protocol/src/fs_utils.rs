@@ -2,6 +2,9 @@
 //!
 //! Provides safe file system operations with proper error handling,
 //! disk space checks, and directory creation.
+//!
+//! The `*_private` helpers are synchronous and keep secrets such as TLS
+//! private keys and pinned certificates readable by the owner only.
 
 use crate::{ProtocolError, Result};
 use std::path::{Path, PathBuf};
@@ -313,6 +316,89 @@ pub async fn get_unique_download_path(base_dir: impl AsRef<Path>, filename: &str
     base_dir.join(new_filename)
 }
 
+/// Permissions for directories holding secrets (owner only)
+pub const PRIVATE_DIR_MODE: u32 = 0o700;
+
+/// Permissions for files holding secrets (owner read/write only)
+pub const PRIVATE_FILE_MODE: u32 = 0o600;
+
+/// Create a directory (and parents) readable by the owner only
+///
+/// An existing directory has its permissions tightened to [`PRIVATE_DIR_MODE`].
+pub fn create_private_dir(path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+    std::fs::create_dir_all(path)
+        .map_err(|e| ProtocolError::from_io_error(e, &format!("creating {}", path.display())))?;
+    restrict_to_owner(path)?;
+    Ok(())
+}
+
+/// Write a file that only the owner can read
+///
+/// New files are created with [`PRIVATE_FILE_MODE`] so the contents are never
+/// briefly world-readable; existing files are tightened before being rewritten.
+pub fn write_private_file(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Result<()> {
+    use std::io::Write;
+
+    let path = path.as_ref();
+    if path.exists() {
+        restrict_to_owner(path)?;
+    }
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(PRIVATE_FILE_MODE);
+    }
+
+    let mut file = options
+        .open(path)
+        .map_err(|e| ProtocolError::from_io_error(e, &format!("writing {}", path.display())))?;
+    file.write_all(contents.as_ref())
+        .map_err(|e| ProtocolError::from_io_error(e, &format!("writing {}", path.display())))?;
+    Ok(())
+}
+
+/// Restrict an existing file or directory to its owner
+///
+/// Directories get [`PRIVATE_DIR_MODE`] and files [`PRIVATE_FILE_MODE`].
+/// Returns `true` if the permissions were looser and had to be changed.
+/// This is a no-op on platforms without Unix permissions.
+pub fn restrict_to_owner(path: impl AsRef<Path>) -> Result<bool> {
+    let path = path.as_ref();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let metadata = std::fs::metadata(path)
+            .map_err(|e| ProtocolError::from_io_error(e, &format!("reading {}", path.display())))?;
+        let mode = if metadata.is_dir() {
+            PRIVATE_DIR_MODE
+        } else {
+            PRIVATE_FILE_MODE
+        };
+
+        if metadata.permissions().mode() & 0o777 == mode {
+            return Ok(false);
+        }
+
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).map_err(|e| {
+            ProtocolError::from_io_error(e, &format!("restricting {}", path.display()))
+        })?;
+        debug!("Restricted permissions of {} to {:o}", path.display(), mode);
+        Ok(true)
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        Ok(false)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -444,4 +530,28 @@ mod tests {
         let err = result.unwrap_err();
         assert!(err.to_string().contains("Insufficient disk space"));
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_private_helpers_restrict_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join("secrets");
+        create_private_dir(&dir).unwrap();
+
+        let file = dir.join("key.pem");
+        write_private_file(&file, b"secret").unwrap();
+
+        let mode = |p: &Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&dir), PRIVATE_DIR_MODE);
+        assert_eq!(mode(&file), PRIVATE_FILE_MODE);
+        assert_eq!(std::fs::read(&file).unwrap(), b"secret");
+
+        // Loosened permissions are tightened again and reported
+        std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(restrict_to_owner(&file).unwrap());
+        assert_eq!(mode(&file), PRIVATE_FILE_MODE);
+        assert!(!restrict_to_owner(&file).unwrap());
+    }
 }
@@ -0,0 +1,235 @@
+//! Device Identity
+//!
+//! Generates and persists the stable device ID announced in identity packets.
+//!
+//! ## Device ID Format
+//!
+//! KDE Connect only accepts IDs made of ASCII letters, digits and underscores,
+//! 32 to 38 characters long. New IDs are UUIDv4s in their 32-character simple
+//! form, which is the format current KDE Connect releases generate themselves.
+//!
+//! ## Storage
+//!
+//! The ID is stored in a `device_id` file next to the device certificate so
+//! that the two stay together when the identity is backed up or reset.
+//!
+//! ## Migration
+//!
+//! Older releases stored hyphenated UUIDs or free-form IDs. When such an ID is
+//! loaded, characters KDE Connect rejects are replaced with underscores. IDs
+//! that still do not fit the format afterwards are replaced by a new one.
+
+use crate::{ProtocolError, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// File name of the persisted device ID
+pub const DEVICE_ID_FILE: &str = "device_id";
+
+/// Minimum device ID length accepted by KDE Connect
+pub const MIN_DEVICE_ID_LEN: usize = 32;
+
+/// Maximum device ID length accepted by KDE Connect
+pub const MAX_DEVICE_ID_LEN: usize = 38;
+
+/// Check whether a device ID is in a format KDE Connect accepts
+pub fn is_valid_device_id(id: &str) -> bool {
+    (MIN_DEVICE_ID_LEN..=MAX_DEVICE_ID_LEN).contains(&id.len())
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Generate a new random device ID
+pub fn generate_device_id() -> String {
+    Uuid::new_v4().simple().to_string()
+}
+
+/// Convert a device ID from an older format into a valid one
+///
+/// Returns `None` if the ID cannot be salvaged.
+fn migrate_device_id(id: &str) -> Option<String> {
+    let migrated: String = id
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    is_valid_device_id(&migrated).then_some(migrated)
+}
+
+/// Local device identity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identity {
+    /// Stable device ID
+    device_id: String,
+}
+
+impl Identity {
+    /// Create a new identity with a freshly generated device ID
+    pub fn generate() -> Self {
+        Self {
+            device_id: generate_device_id(),
+        }
+    }
+
+    /// Create an identity from an existing device ID
+    ///
+    /// IDs in an older format are migrated; IDs that cannot be migrated are
+    /// rejected.
+    pub fn from_device_id(id: &str) -> Result<Self> {
+        let device_id = migrate_device_id(id)
+            .ok_or_else(|| ProtocolError::Configuration(format!("Invalid device ID: {:?}", id)))?;
+
+        if device_id != id {
+            warn!("Migrated device ID {:?} to {}", id, device_id);
+        }
+
+        Ok(Self { device_id })
+    }
+
+    /// Load the identity stored in `dir`, creating it if needed
+    ///
+    /// If nothing is stored yet, `legacy_id` (an ID kept by an older release)
+    /// is migrated and stored, so the device keeps its ID across upgrades.
+    /// Otherwise a new ID is generated and stored.
+    pub fn load_or_generate(dir: &Path, legacy_id: Option<&str>) -> Result<Self> {
+        let path = Self::path(dir);
+
+        let stored = match fs::read_to_string(&path) {
+            Ok(contents) => Some(contents.trim().to_string()).filter(|id| !id.is_empty()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        if let Some(id) = stored.as_deref() {
+            if is_valid_device_id(id) {
+                info!("Loaded device ID from {}", path.display());
+                return Ok(Self {
+                    device_id: id.to_string(),
+                });
+            }
+        }
+
+        let identity = match stored.as_deref().or(legacy_id) {
+            Some(id) => Self::from_device_id(id).unwrap_or_else(|e| {
+                warn!("{}, generating a new one", e);
+                Self::generate()
+            }),
+            None => Self::generate(),
+        };
+
+        identity.save(dir)?;
+        Ok(identity)
+    }
+
+    /// Persist the device ID into `dir`
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir)?;
+        let path = Self::path(dir);
+        fs::write(&path, &self.device_id)?;
+        info!("Saved device ID to {}", path.display());
+        Ok(())
+    }
+
+    /// Path of the device ID file inside `dir`
+    pub fn path(dir: &Path) -> PathBuf {
+        dir.join(DEVICE_ID_FILE)
+    }
+
+    /// Get the device ID
+    pub fn device_id(&self) -> &str {
+        &self.device_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_generated_id_is_valid() {
+        for _ in 0..16 {
+            let identity = Identity::generate();
+            assert!(is_valid_device_id(identity.device_id()));
+            assert_eq!(identity.device_id().len(), 32);
+        }
+        assert_ne!(Identity::generate(), Identity::generate());
+    }
+
+    #[test]
+    fn test_is_valid_device_id() {
+        assert!(is_valid_device_id("0123456789abcdef0123456789abcdef"));
+        assert!(is_valid_device_id("01234567_89ab_cdef_0123_456789abcdef"));
+        assert!(!is_valid_device_id("01234567-89ab-cdef-0123-456789abcdef"));
+        assert!(!is_valid_device_id("cconnect-device"));
+        assert!(!is_valid_device_id(""));
+        assert!(!is_valid_device_id(
+            "0123456789abcdef0123456789abcdef0123456"
+        ));
+    }
+
+    #[test]
+    fn test_load_is_stable_across_runs() {
+        let dir = TempDir::new().unwrap();
+
+        let first = Identity::load_or_generate(dir.path(), None).unwrap();
+        let second = Identity::load_or_generate(dir.path(), None).unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(
+            fs::read_to_string(Identity::path(dir.path())).unwrap(),
+            first.device_id()
+        );
+    }
+
+    #[test]
+    fn test_stored_id_wins_over_legacy() {
+        let dir = TempDir::new().unwrap();
+        let stored = Identity::generate();
+        stored.save(dir.path()).unwrap();
+
+        let loaded =
+            Identity::load_or_generate(dir.path(), Some("0123456789abcdef0123456789abcdef"))
+                .unwrap();
+        assert_eq!(loaded, stored);
+    }
+
+    #[test]
+    fn test_legacy_hyphenated_id_is_migrated() {
+        let dir = TempDir::new().unwrap();
+        let legacy = "01234567-89ab-cdef-0123-456789abcdef";
+
+        let identity = Identity::load_or_generate(dir.path(), Some(legacy)).unwrap();
+        assert_eq!(identity.device_id(), "01234567_89ab_cdef_0123_456789abcdef");
+
+        // The migrated ID is persisted and reused
+        let reloaded = Identity::load_or_generate(dir.path(), None).unwrap();
+        assert_eq!(reloaded, identity);
+    }
+
+    #[test]
+    fn test_invalid_stored_id_is_replaced() {
+        let dir = TempDir::new().unwrap();
+        fs::write(Identity::path(dir.path()), "cconnect-device\n").unwrap();
+
+        let identity = Identity::load_or_generate(dir.path(), None).unwrap();
+        assert!(is_valid_device_id(identity.device_id()));
+        assert_eq!(
+            Identity::load_or_generate(dir.path(), None).unwrap(),
+            identity
+        );
+    }
+
+    #[test]
+    fn test_from_device_id() {
+        assert!(Identity::from_device_id("too short").is_err());
+        assert_eq!(
+            Identity::from_device_id("0123456789abcdef0123456789abcdef")
+                .unwrap()
+                .device_id(),
+            "0123456789abcdef0123456789abcdef"
+        );
+    }
+}
@@ -9,6 +9,7 @@ pub mod connection;
 pub mod device;
 pub mod discovery;
 pub mod fs_utils;
+pub mod identity;
 pub mod packet;
 pub mod pairing;
 pub mod payload;
@@ -30,20 +31,24 @@ pub use cosmic_connect_core::{Packet as CorePacket, ProtocolError as CoreProtoco
 
 // Re-export local types
 pub use bluetooth_connection_manager::BluetoothConnectionManager;
-pub use connection::{ConnectionConfig, ConnectionEvent, ConnectionManager};
-pub use device::{ConnectionState, Device, DeviceManager};
+pub use connection::{
+    BackoffJitter, ConnectionConfig, ConnectionEvent, ConnectionManager, MANUAL_DISCONNECT_REASON,
+};
+pub use device::{ConnectionQuality, ConnectionState, Device, DeviceEvent, DeviceManager};
 pub use discovery::{
     DeviceInfo, DeviceType, Discovery, DiscoveryConfig, DiscoveryEvent, DiscoveryService,
     DISCOVERY_PORT,
 };
 pub use error::{ProtocolError, Result};
+pub use identity::Identity;
 pub use packet::{current_timestamp, Packet};
 pub use pairing::{
-    PairingConfig, PairingEvent, PairingHandler, PairingPacket, PairingService, PairingStatus,
-    PAIRING_TIMEOUT,
+    PairedCertificate, PairingConfig, PairingEvent, PairingHandler, PairingPacket, PairingService,
+    PairingStatus, PAIRING_TIMEOUT,
 };
 pub use payload::{
-    FileTransferInfo, PayloadClient, PayloadServer, TlsPayloadClient, TlsPayloadServer,
+    AdaptiveRateConfig, AdaptiveRateController, FileTransferInfo, PayloadCache, PayloadClient,
+    PayloadServer, RateLimiter, TlsPayloadClient, TlsPayloadServer,
 };
 pub use plugins::{Plugin, PluginManager};
 pub use recovery::{ReconnectionStrategy, RecoveryManager, TransferState};
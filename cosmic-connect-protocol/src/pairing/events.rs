@@ -43,6 +43,21 @@ pub enum PairingEvent {
         reason: Option<String>,
     },
 
+    /// A paired device presented a different certificate
+    ///
+    /// The device was most likely reset or reinstalled. It is handled as a
+    /// pairing request until the user re-verifies or rejects it.
+    RePairRequired {
+        /// ID of the device
+        device_id: String,
+        /// Name of the device
+        device_name: String,
+        /// Fingerprint of the certificate pinned when pairing
+        old_fingerprint: String,
+        /// Fingerprint of the certificate now presented, for user verification
+        new_fingerprint: String,
+    },
+
     /// Pairing status changed
     StatusChanged {
         /// ID of the device
@@ -88,6 +103,11 @@ impl PairingEvent {
         matches!(self, PairingEvent::PairingRejected { .. })
     }
 
+    /// Check if this is a re-pair required event
+    pub fn is_repair_required(&self) -> bool {
+        matches!(self, PairingEvent::RePairRequired { .. })
+    }
+
     /// Get device ID if this event is device-related
     pub fn device_id(&self) -> Option<&str> {
         match self {
@@ -95,6 +115,7 @@ impl PairingEvent {
             PairingEvent::RequestReceived { device_id, .. } => Some(device_id),
             PairingEvent::PairingAccepted { device_id, .. } => Some(device_id),
             PairingEvent::PairingRejected { device_id, .. } => Some(device_id),
+            PairingEvent::RePairRequired { device_id, .. } => Some(device_id),
             PairingEvent::StatusChanged { device_id, .. } => Some(device_id),
             PairingEvent::DeviceUnpaired { device_id } => Some(device_id),
             PairingEvent::PairingTimeout { device_id } => Some(device_id),
@@ -124,6 +145,16 @@ mod tests {
         };
         assert!(accepted.is_pairing_accepted());
         assert!(!accepted.is_request_received());
+
+        let repair = PairingEvent::RePairRequired {
+            device_id: "test".to_string(),
+            device_name: "Test Device".to_string(),
+            old_fingerprint: "AA:BB:CC".to_string(),
+            new_fingerprint: "DD:EE:FF".to_string(),
+        };
+        assert!(repair.is_repair_required());
+        assert!(!repair.is_request_received());
+        assert_eq!(repair.device_id(), Some("test"));
     }
 
     #[test]
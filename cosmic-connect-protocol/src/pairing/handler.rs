@@ -31,13 +31,14 @@
 //! - [Valent Protocol Reference](https://valent.andyholmes.ca/documentation/protocol.html)
 //! - [CConnect TLS Implementation](https://invent.kde.org/network/cconnect-kde)
 
+use crate::fs_utils::{create_private_dir, restrict_to_owner, write_private_file};
 use crate::{Packet, ProtocolError, Result};
 use cosmic_connect_core::crypto::CertificateInfo;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::fs;
-use std::path::PathBuf;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
 use tracing::{debug, info, warn};
 
 /// Default pairing timeout (30 seconds per protocol specification)
@@ -49,14 +50,27 @@ pub const PAIRING_TIMEOUT: Duration = Duration::from_secs(30);
 pub enum PairingStatus {
     /// Not paired
     Unpaired,
-    /// Pairing request sent, awaiting response
-    Requested,
-    /// Pairing request received, awaiting user confirmation
+    /// Pairing request sent by us, awaiting the peer's response
+    #[serde(alias = "requested")]
+    RequestedByUs,
+    /// Pairing request received from the peer, awaiting user confirmation
     RequestedByPeer,
     /// Successfully paired
     Paired,
 }
 
+impl PairingStatus {
+    /// Name used for the status in DBus signals
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PairingStatus::Unpaired => "unpaired",
+            PairingStatus::RequestedByUs => "requested_by_us",
+            PairingStatus::RequestedByPeer => "requested_by_peer",
+            PairingStatus::Paired => "paired",
+        }
+    }
+}
+
 /// Pairing request/response packet
 #[derive(Debug, Clone)]
 pub struct PairingPacket {
@@ -123,6 +137,17 @@ impl PairingPacket {
     }
 }
 
+/// Certificate pinned for a paired device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PairedCertificate {
+    /// Device ID
+    pub device_id: String,
+    /// SHA256 fingerprint of the certificate
+    pub fingerprint: String,
+    /// When the certificate was pinned, in seconds since the Unix epoch
+    pub paired_at: Option<u64>,
+}
+
 /// Pairing handler for managing device pairing
 pub struct PairingHandler {
     /// This device's certificate
@@ -145,12 +170,15 @@ impl PairingHandler {
     ///
     /// * `device_id` - This device's unique identifier
     /// * `cert_dir` - Directory to store certificates
+    ///
+    /// The directory, this device's private key and all pinned certificates
+    /// are kept readable by the owner only.
     pub fn new(device_id: impl Into<String>, cert_dir: impl Into<PathBuf>) -> Result<Self> {
         let device_id = device_id.into();
         let cert_dir = cert_dir.into();
 
-        // Ensure certificate directory exists
-        fs::create_dir_all(&cert_dir)?;
+        // Ensure certificate directory exists and is private
+        create_private_dir(&cert_dir)?;
 
         // Load or generate certificate
         let cert_path = cert_dir.join("device_cert.pem");
@@ -158,11 +186,21 @@ impl PairingHandler {
 
         let certificate = if cert_path.exists() && key_path.exists() {
             info!("Loading existing certificate for device {}", device_id);
+            for path in [&key_path, &cert_path] {
+                if restrict_to_owner(path)? {
+                    warn!(
+                        "{} was readable by other users; restricted to owner",
+                        path.display()
+                    );
+                }
+            }
             CertificateInfo::load_from_files(&cert_path, &key_path)?
         } else {
             info!("Generating new certificate for device {}", device_id);
             let cert = CertificateInfo::generate(&device_id)?;
             cert.save_to_files(&cert_path, &key_path)?;
+            restrict_to_owner(&key_path)?;
+            restrict_to_owner(&cert_path)?;
             cert
         };
 
@@ -191,7 +229,7 @@ impl PairingHandler {
 
     /// Send pairing request
     pub fn request_pairing(&mut self) -> Packet {
-        self.status = PairingStatus::Requested;
+        self.status = PairingStatus::RequestedByUs;
         info!("Sending pairing request");
         PairingPacket::request()
     }
@@ -227,7 +265,7 @@ impl PairingHandler {
                     // Don't auto-accept, wait for user confirmation
                     Ok((false, None))
                 }
-                PairingStatus::Requested => {
+                PairingStatus::RequestedByUs => {
                     // Received pairing accept - send confirmation response
                     self.store_device_certificate(device_id, device_cert)?;
                     self.status = PairingStatus::Paired;
@@ -265,7 +303,23 @@ impl PairingHandler {
     }
 
     /// Accept pairing request (user confirmed)
-    pub fn accept_pairing(&mut self, device_id: &str, device_cert: &[u8]) -> Result<Packet> {
+    ///
+    /// Returns the acceptance packet to send. Accepting a device that is
+    /// already accepted with the same certificate (a double click, or a
+    /// retried accept) is a no-op and returns `None`, so only one acceptance
+    /// is ever sent.
+    pub fn accept_pairing(
+        &mut self,
+        device_id: &str,
+        device_cert: &[u8],
+    ) -> Result<Option<Packet>> {
+        if self.status == PairingStatus::Paired
+            && self.paired_devices.get(device_id).map(Vec::as_slice) == Some(device_cert)
+        {
+            debug!("Pairing with device {} already accepted", device_id);
+            return Ok(None);
+        }
+
         if self.status != PairingStatus::RequestedByPeer {
             return Err(ProtocolError::InvalidPacket(
                 "No pairing request pending".to_string(),
@@ -276,7 +330,7 @@ impl PairingHandler {
         self.status = PairingStatus::Paired;
         info!("Accepted pairing with device {}", device_id);
 
-        Ok(PairingPacket::accept())
+        Ok(Some(PairingPacket::accept()))
     }
 
     /// Reject pairing request (user declined)
@@ -299,11 +353,89 @@ impl PairingHandler {
         self.paired_devices.contains_key(device_id) || self.status == PairingStatus::Paired
     }
 
+    /// Check if a device's certificate has been accepted
+    pub fn has_certificate(&self, device_id: &str) -> bool {
+        self.paired_devices.contains_key(device_id)
+    }
+
+    /// Get the certificate accepted for a device
+    pub fn pinned_certificate(&self, device_id: &str) -> Option<&[u8]> {
+        self.paired_devices.get(device_id).map(Vec::as_slice)
+    }
+
+    /// Handle a paired device's new certificate as a pairing request
+    ///
+    /// The pinned certificate is kept until the request is accepted.
+    pub fn request_repair(&mut self, device_id: &str) {
+        self.status = PairingStatus::RequestedByPeer;
+        info!("Device {} must be re-verified", device_id);
+    }
+
+    /// Check if a certificate is pinned for a device, in memory or on disk
+    pub fn has_pinned_certificate(&self, device_id: &str) -> bool {
+        self.has_certificate(device_id) || self.certificate_path(device_id).exists()
+    }
+
+    /// List the certificates pinned in the certificate directory
+    ///
+    /// Sorted by device ID. The pairing date is the time the certificate file
+    /// was written.
+    pub fn paired_certificates(&self) -> Result<Vec<PairedCertificate>> {
+        let mut certificates = Vec::new();
+
+        for (device_id, path) in self.pinned_certificate_files()? {
+            let Some(cert_der) = read_certificate_file(&path, &device_id) else {
+                continue;
+            };
+            let paired_at = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map(|since_epoch| since_epoch.as_secs());
+
+            certificates.push(PairedCertificate {
+                fingerprint: CertificateInfo::calculate_fingerprint(&cert_der),
+                device_id,
+                paired_at,
+            });
+        }
+
+        certificates.sort_by(|a, b| a.device_id.cmp(&b.device_id));
+        Ok(certificates)
+    }
+
+    /// Path of the pinned certificate of a device
+    fn certificate_path(&self, device_id: &str) -> PathBuf {
+        self.cert_dir.join(format!("{}.pem", device_id))
+    }
+
+    /// Pinned certificate files in the certificate directory, by device ID
+    fn pinned_certificate_files(&self) -> Result<Vec<(String, PathBuf)>> {
+        let mut files = Vec::new();
+
+        for entry in fs::read_dir(&self.cert_dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("pem") {
+                continue;
+            }
+
+            if let Some(device_id) = path.file_stem().and_then(|s| s.to_str()) {
+                // Skip our own certificate
+                if device_id == "device_cert" || device_id == "device_key" {
+                    continue;
+                }
+                files.push((device_id.to_string(), path.clone()));
+            }
+        }
+
+        Ok(files)
+    }
+
     /// Store device certificate
     fn store_device_certificate(&mut self, device_id: &str, cert_der: &[u8]) -> Result<()> {
-        let cert_path = self.cert_dir.join(format!("{}.pem", device_id));
+        let cert_path = self.certificate_path(device_id);
         let cert_pem = pem::encode(&pem::Pem::new("CERTIFICATE", cert_der.to_vec()));
-        fs::write(&cert_path, cert_pem)?;
+        write_private_file(&cert_path, cert_pem)?;
 
         self.paired_devices
             .insert(device_id.to_string(), cert_der.to_vec());
@@ -317,7 +449,7 @@ impl PairingHandler {
 
     /// Remove device certificate
     fn remove_device_certificate(&mut self, device_id: &str) -> Result<()> {
-        let cert_path = self.cert_dir.join(format!("{}.pem", device_id));
+        let cert_path = self.certificate_path(device_id);
         if cert_path.exists() {
             fs::remove_file(&cert_path)?;
         }
@@ -330,48 +462,17 @@ impl PairingHandler {
 
     /// Load all paired device certificates
     pub fn load_paired_devices(&mut self) -> Result<()> {
-        for entry in fs::read_dir(&self.cert_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.extension().and_then(|s| s.to_str()) == Some("pem") {
-                let filename = path.file_stem().and_then(|s| s.to_str());
-                if let Some(device_id) = filename {
-                    // Skip our own certificate
-                    if device_id == "device_cert" || device_id == "device_key" {
-                        continue;
-                    }
-
-                    // Load certificate (PEM format) and extract DER
-                    // Paired device certificates are stored as cert only, no private key needed
-                    let cert_data = match fs::read(&path) {
-                        Ok(data) => data,
-                        Err(e) => {
-                            warn!("Failed to read certificate file for {}: {}", device_id, e);
-                            continue;
-                        }
-                    };
-
-                    let cert_pem = match pem::parse(&cert_data) {
-                        Ok(pem) => pem,
-                        Err(e) => {
-                            warn!("Failed to parse certificate PEM for {}: {}", device_id, e);
-                            continue;
-                        }
-                    };
-
-                    if cert_pem.tag() == "CERTIFICATE" {
-                        self.paired_devices
-                            .insert(device_id.to_string(), cert_pem.contents().to_vec());
-                        debug!("Loaded paired device certificate: {}", device_id);
-                    } else {
-                        warn!(
-                            "Invalid certificate tag for {}: expected CERTIFICATE, got {}",
-                            device_id,
-                            cert_pem.tag()
-                        );
-                    }
-                }
+        for (device_id, path) in self.pinned_certificate_files()? {
+            if restrict_to_owner(&path)? {
+                warn!(
+                    "Pinned certificate for {} was readable by other users; restricted to owner",
+                    device_id
+                );
+            }
+            // Paired device certificates are stored as cert only, no private key needed
+            if let Some(cert_der) = read_certificate_file(&path, &device_id) {
+                self.paired_devices.insert(device_id.clone(), cert_der);
+                debug!("Loaded paired device certificate: {}", device_id);
             }
         }
 
@@ -383,6 +484,38 @@ impl PairingHandler {
     }
 }
 
+/// Read a pinned certificate (PEM format) and extract the DER
+///
+/// Logs and returns `None` for unreadable or malformed files.
+fn read_certificate_file(path: &Path, device_id: &str) -> Option<Vec<u8>> {
+    let cert_data = match fs::read(path) {
+        Ok(data) => data,
+        Err(e) => {
+            warn!("Failed to read certificate file for {}: {}", device_id, e);
+            return None;
+        }
+    };
+
+    let cert_pem = match pem::parse(&cert_data) {
+        Ok(pem) => pem,
+        Err(e) => {
+            warn!("Failed to parse certificate PEM for {}: {}", device_id, e);
+            return None;
+        }
+    };
+
+    if cert_pem.tag() == "CERTIFICATE" {
+        Some(cert_pem.contents().to_vec())
+    } else {
+        warn!(
+            "Invalid certificate tag for {}: expected CERTIFICATE, got {}",
+            device_id,
+            cert_pem.tag()
+        );
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -453,6 +586,33 @@ mod tests {
         assert!(!handler.fingerprint().is_empty());
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_pairing_secrets_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let cert_dir = temp_dir.path().join("certs");
+        let mut handler = PairingHandler::new("test_device", &cert_dir).unwrap();
+        handler
+            .store_device_certificate("phone", b"phone certificate")
+            .unwrap();
+
+        let mode = |p: &Path| fs::metadata(p).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&cert_dir), 0o700);
+        assert_eq!(mode(&cert_dir.join("device_key.pem")), 0o600);
+        assert_eq!(mode(&cert_dir.join("device_cert.pem")), 0o600);
+        assert_eq!(mode(&handler.certificate_path("phone")), 0o600);
+
+        // Keys left world-readable by older versions are tightened on load
+        let key_path = cert_dir.join("device_key.pem");
+        fs::set_permissions(&key_path, fs::Permissions::from_mode(0o644)).unwrap();
+        fs::set_permissions(&cert_dir, fs::Permissions::from_mode(0o755)).unwrap();
+        PairingHandler::new("test_device", &cert_dir).unwrap();
+        assert_eq!(mode(&key_path), 0o600);
+        assert_eq!(mode(&cert_dir), 0o700);
+    }
+
     #[test]
     fn test_pairing_request_flow() {
         let temp_dir = TempDir::new().unwrap();
@@ -460,10 +620,92 @@ mod tests {
 
         // Send pairing request
         let request = handler.request_pairing();
-        assert_eq!(handler.status(), PairingStatus::Requested);
+        assert_eq!(handler.status(), PairingStatus::RequestedByUs);
         assert!(request.is_type("cconnect.pair"));
     }
 
+    #[test]
+    fn test_pairing_direction_transitions() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut handler = PairingHandler::new("test_device", temp_dir.path()).unwrap();
+        let phone_cert = b"phone certificate".to_vec();
+
+        // We ask, the phone accepts
+        handler.request_pairing();
+        assert_eq!(handler.status(), PairingStatus::RequestedByUs);
+        let (respond, _) = handler
+            .handle_pairing_packet(&PairingPacket::request(), "phone", &phone_cert)
+            .unwrap();
+        assert!(respond);
+        assert_eq!(handler.status(), PairingStatus::Paired);
+        handler.unpair("phone").unwrap();
+
+        // We ask, the phone declines
+        handler.request_pairing();
+        handler
+            .handle_pairing_packet(&PairingPacket::reject(), "phone", &phone_cert)
+            .unwrap();
+        assert_eq!(handler.status(), PairingStatus::Unpaired);
+
+        // The phone asks, the user declines
+        handler
+            .handle_pairing_packet(&PairingPacket::request(), "phone", &phone_cert)
+            .unwrap();
+        assert_eq!(handler.status(), PairingStatus::RequestedByPeer);
+        handler.reject_pairing();
+        assert_eq!(handler.status(), PairingStatus::Unpaired);
+
+        // The phone asks, the user accepts
+        handler
+            .handle_pairing_packet(&PairingPacket::request(), "phone", &phone_cert)
+            .unwrap();
+        assert_eq!(handler.status(), PairingStatus::RequestedByPeer);
+        assert!(handler
+            .accept_pairing("phone", &phone_cert)
+            .unwrap()
+            .is_some());
+        assert_eq!(handler.status(), PairingStatus::Paired);
+    }
+
+    #[test]
+    fn test_pairing_status_names() {
+        assert_eq!(PairingStatus::RequestedByUs.as_str(), "requested_by_us");
+        assert_eq!(PairingStatus::RequestedByPeer.as_str(), "requested_by_peer");
+
+        // Statuses saved before the split still load
+        let status: PairingStatus = serde_json::from_str("\"requested\"").unwrap();
+        assert_eq!(status, PairingStatus::RequestedByUs);
+    }
+
+    #[test]
+    fn test_accept_pairing_is_idempotent() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut handler = PairingHandler::new("test_device", temp_dir.path()).unwrap();
+        let phone_cert = b"phone certificate".to_vec();
+
+        // Nothing to accept yet
+        assert!(handler.accept_pairing("phone", &phone_cert).is_err());
+
+        handler
+            .handle_pairing_packet(&PairingPacket::request(), "phone", &phone_cert)
+            .unwrap();
+        assert_eq!(handler.status(), PairingStatus::RequestedByPeer);
+
+        // Double click: only the first accept produces a packet
+        let first = handler.accept_pairing("phone", &phone_cert).unwrap();
+        let second = handler.accept_pairing("phone", &phone_cert).unwrap();
+        assert!(first.is_some_and(|p| PairingPacket::from_packet(&p).unwrap().pair));
+        assert!(second.is_none());
+
+        assert_eq!(handler.status(), PairingStatus::Paired);
+        assert!(handler.has_certificate("phone"));
+        assert!(temp_dir.path().join("phone.pem").exists());
+
+        // A different certificate is not a retry of the same accept
+        assert!(handler.accept_pairing("phone", b"other").is_err());
+        assert!(handler.has_certificate("phone"));
+    }
+
     #[test]
     fn test_certificate_fingerprint() {
         let cert1 = CertificateInfo::generate("device1").unwrap();
@@ -30,7 +30,9 @@ pub mod service;
 
 // Re-export main types
 pub use events::PairingEvent;
-pub use handler::{PairingHandler, PairingPacket, PairingStatus, PAIRING_TIMEOUT};
+pub use handler::{
+    PairedCertificate, PairingHandler, PairingPacket, PairingStatus, PAIRING_TIMEOUT,
+};
 pub use service::{PairingConfig, PairingService};
 
 // CertificateInfo now comes from cosmic-connect-core (re-exported in lib.rs)
@@ -1,14 +1,26 @@
 //! Pairing Service
 //!
 //! Manages pairing for multiple devices simultaneously.
+//!
+//! ## Deferred Notifications
+//!
+//! Unpairing takes effect locally even when the device is offline. The unpair
+//! packet that could not be sent is kept per device and delivered by
+//! [`PairingService::deliver_deferred`] when the device connects again, so
+//! the phone does not keep believing it is paired. Only the latest
+//! undelivered change is kept, and completing a new pairing drops it.
+//!
+//! Undelivered changes are stored in `deferred_packets.json` in the
+//! certificate directory, so they survive a restart of the daemon.
 
 use super::events::PairingEvent;
-use super::handler::{PairingHandler, PairingStatus};
-use crate::{DeviceInfo, Packet, Result};
+use super::handler::{PairedCertificate, PairingHandler, PairingStatus};
+use crate::fs_utils::write_private_file;
+use crate::{DeviceInfo, Packet, ProtocolError, Result};
 use cosmic_connect_core::crypto::CertificateInfo;
 use std::collections::HashMap;
 use std::net::SocketAddr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, RwLock};
@@ -17,6 +29,9 @@ use tracing::{debug, error, info, warn};
 /// Pairing timeout duration (30 seconds)
 const PAIRING_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// File in the certificate directory holding undelivered pairing changes
+const DEFERRED_PACKETS_FILE: &str = "deferred_packets.json";
+
 /// Pairing request state
 #[derive(Debug)]
 struct PairingRequest {
@@ -59,6 +74,9 @@ pub struct PairingService {
     /// Active pairing requests (device_id -> request state)
     active_requests: Arc<RwLock<HashMap<String, PairingRequest>>>,
 
+    /// Pairing changes not delivered yet (device_id -> packet)
+    deferred_packets: Arc<RwLock<HashMap<String, Packet>>>,
+
     /// Event channel sender
     event_tx: mpsc::UnboundedSender<PairingEvent>,
 
@@ -77,16 +95,21 @@ impl PairingService {
     pub fn new(device_id: impl Into<String>, config: PairingConfig) -> Result<Self> {
         let device_id = device_id.into();
 
-        // Create pairing handler
-        let handler = PairingHandler::new(device_id.clone(), &config.cert_dir)?;
+        // Create pairing handler, with the certificates pinned by earlier
+        // pairings to check paired devices against
+        let mut handler = PairingHandler::new(device_id.clone(), &config.cert_dir)?;
+        handler.load_paired_devices()?;
         let certificate = handler.certificate().clone();
 
+        let deferred_packets = load_deferred_packets(&config.cert_dir);
+
         let (event_tx, event_rx) = mpsc::unbounded_channel();
 
         Ok(Self {
             certificate: Arc::new(certificate),
             handler: Arc::new(RwLock::new(handler)),
             active_requests: Arc::new(RwLock::new(HashMap::new())),
+            deferred_packets: Arc::new(RwLock::new(deferred_packets)),
             event_tx,
             event_rx: Arc::new(RwLock::new(event_rx)),
             config,
@@ -211,9 +234,13 @@ impl PairingService {
 
                 // Emit event
                 let _ = self.event_tx.send(PairingEvent::RequestSent {
-                    device_id,
+                    device_id: device_id.clone(),
                     our_fingerprint: self.fingerprint().to_string(),
                 });
+                let _ = self.event_tx.send(PairingEvent::StatusChanged {
+                    device_id,
+                    status: PairingStatus::RequestedByUs,
+                });
 
                 // Start timeout checker
                 self.spawn_timeout_checker();
@@ -246,6 +273,13 @@ impl PairingService {
             device_id, remote_addr
         );
 
+        if !self
+            .verify_certificate(device_info, device_cert, remote_addr)
+            .await
+        {
+            return Ok(None);
+        }
+
         let mut handler = self.handler.write().await;
         let (should_respond, response_packet) =
             handler.handle_pairing_packet(packet, device_id, device_cert)?;
@@ -281,6 +315,10 @@ impl PairingService {
                     device_name: device_info.device_name.clone(),
                     their_fingerprint: fingerprint,
                 });
+                let _ = self.event_tx.send(PairingEvent::StatusChanged {
+                    device_id: device_id.clone(),
+                    status: PairingStatus::RequestedByPeer,
+                });
 
                 // Start timeout checker
                 self.spawn_timeout_checker();
@@ -295,11 +333,16 @@ impl PairingService {
 
                 let fingerprint = CertificateInfo::calculate_fingerprint(device_cert);
 
+                // Pairing again supersedes an undelivered unpair
+                self.drop_deferred(device_id).await;
+
                 let _ = self.event_tx.send(PairingEvent::PairingAccepted {
                     device_id: device_id.clone(),
                     device_name: device_info.device_name.clone(),
                     certificate_fingerprint: fingerprint,
                 });
+
+                self.connect_paired_device(device_info, remote_addr).await;
             }
             PairingStatus::Unpaired => {
                 debug!("Pairing rejected or unpaired for device {}", device_id);
@@ -325,6 +368,81 @@ impl PairingService {
         })
     }
 
+    /// Check a device's certificate against the one pinned when pairing
+    ///
+    /// A paired device presenting another certificate was most likely reset
+    /// or reinstalled. Rather than trusting the new certificate, or refusing
+    /// the device until it is unpaired by hand, the device is handled as a
+    /// pairing request: [`PairingEvent::RePairRequired`] is emitted and the
+    /// user re-verifies it with [`accept_pairing`](Self::accept_pairing) or
+    /// turns it down with [`reject_pairing`](Self::reject_pairing).
+    ///
+    /// `device_cert` is the certificate the device presented on its TLS
+    /// connection. Returns `false` if it does not match. An empty
+    /// certificate (the connection reported none) is not checked.
+    pub async fn verify_certificate(
+        &self,
+        device_info: &DeviceInfo,
+        device_cert: &[u8],
+        remote_addr: SocketAddr,
+    ) -> bool {
+        if device_cert.is_empty() {
+            return true;
+        }
+        let device_id = &device_info.device_id;
+
+        let old_fingerprint = {
+            let mut handler = self.handler.write().await;
+            match handler.pinned_certificate(device_id) {
+                Some(pinned) if pinned != device_cert => {
+                    let old_fingerprint = CertificateInfo::calculate_fingerprint(pinned);
+                    handler.request_repair(device_id);
+                    old_fingerprint
+                }
+                _ => return true,
+            }
+        };
+
+        // Ask once per presented certificate
+        let mut requests = self.active_requests.write().await;
+        if requests
+            .get(device_id)
+            .is_some_and(|request| request.device_cert == device_cert)
+        {
+            return false;
+        }
+        requests.insert(
+            device_id.clone(),
+            PairingRequest {
+                started_at: Instant::now(),
+                device_info: device_info.clone(),
+                remote_addr,
+                device_cert: device_cert.to_vec(),
+            },
+        );
+        drop(requests);
+
+        let new_fingerprint = CertificateInfo::calculate_fingerprint(device_cert);
+        warn!(
+            "Device {} ({}) presented a new certificate ({}, pinned {}), re-pairing required",
+            device_info.device_name, device_id, new_fingerprint, old_fingerprint
+        );
+
+        let _ = self.event_tx.send(PairingEvent::RePairRequired {
+            device_id: device_id.clone(),
+            device_name: device_info.device_name.clone(),
+            old_fingerprint,
+            new_fingerprint,
+        });
+        let _ = self.event_tx.send(PairingEvent::StatusChanged {
+            device_id: device_id.clone(),
+            status: PairingStatus::RequestedByPeer,
+        });
+
+        self.spawn_timeout_checker();
+        false
+    }
+
     /// Accept a pairing request (user confirmed)
     pub async fn accept_pairing(&self, device_id: &str) -> Result<()> {
         info!("Accepting pairing with device {}", device_id);
@@ -344,13 +462,19 @@ impl PairingService {
         };
 
         debug!("Step 2: Extracting device info, cert, and address");
-        let (device_info, device_cert, remote_addr) = request_data.ok_or_else(|| {
+        let Some((device_info, device_cert, remote_addr)) = request_data else {
+            // A repeated accept after the first one completed
+            if self.handler.read().await.has_certificate(device_id) {
+                debug!("Pairing with {} already accepted", device_id);
+                return Ok(());
+            }
+
             error!("No active pairing request found for device {}", device_id);
-            crate::ProtocolError::Configuration(format!(
+            return Err(crate::ProtocolError::Configuration(format!(
                 "No active pairing request for device {}",
                 device_id
-            ))
-        })?;
+            )));
+        };
         debug!(
             "Device info: name={}, addr={}",
             device_info.device_name, remote_addr
@@ -359,10 +483,17 @@ impl PairingService {
         debug!("Step 3: Creating pairing acceptance response packet");
         let response = {
             let mut handler = self.handler.write().await;
-            let resp = handler.accept_pairing(device_id, &device_cert)?;
-            debug!("Response packet created: type={}", resp.packet_type);
-            resp
+            handler.accept_pairing(device_id, &device_cert)?
         };
+        // A concurrent accept for this device is already sending the response
+        let Some(response) = response else {
+            debug!(
+                "Pairing with {} already being accepted, not sending another response",
+                device_id
+            );
+            return Ok(());
+        };
+        debug!("Response packet created: type={}", response.packet_type);
 
         debug!("Step 4: Checking for active TLS connection");
         // Ensure there's an active TLS connection before sending the acceptance packet
@@ -430,6 +561,7 @@ impl PairingService {
         debug!("Step 9: Removing device from active pairing requests");
         // Remove from active requests now that pairing is accepted
         self.active_requests.write().await.remove(device_id);
+        self.drop_deferred(device_id).await;
         debug!("Device removed from active requests");
 
         debug!("Step 10: Sending PairingAccepted event");
@@ -443,6 +575,56 @@ impl PairingService {
         Ok(())
     }
 
+    /// Make sure a newly paired device is connected
+    ///
+    /// Unpaired devices may drop the connection right after the identity
+    /// exchange, so the peer's acceptance can arrive with no connection left
+    /// to carry plugin traffic. An existing connection is reused.
+    ///
+    /// `remote_addr` is where the pairing packet came from; its port is the
+    /// peer's source port, so the device is dialed on the TCP port from its
+    /// identity. Devices without an IP address, e.g. over Bluetooth, are
+    /// left to their transport.
+    ///
+    /// Returns whether a new connection was started.
+    async fn connect_paired_device(
+        &self,
+        device_info: &DeviceInfo,
+        remote_addr: SocketAddr,
+    ) -> bool {
+        let device_id = device_info.device_id.as_str();
+        let Some(conn_mgr) = &self.connection_manager else {
+            return false;
+        };
+
+        let conn_mgr = conn_mgr.read().await;
+        if conn_mgr.has_connection(device_id).await {
+            debug!("Reusing existing connection to paired device {}", device_id);
+            return false;
+        }
+
+        if remote_addr.ip().is_unspecified() || device_info.tcp_port == 0 {
+            debug!(
+                "No TCP address for paired device {}, not connecting",
+                device_id
+            );
+            return false;
+        }
+        let addr = SocketAddr::new(remote_addr.ip(), device_info.tcp_port);
+
+        info!(
+            "Connecting to newly paired device {} at {}",
+            device_id, addr
+        );
+        match conn_mgr.connect(device_id, addr).await {
+            Ok(()) => true,
+            Err(e) => {
+                warn!("Failed to connect to paired device {}: {}", device_id, e);
+                false
+            }
+        }
+    }
+
     /// Get remote address for an active pairing request
     #[allow(dead_code)]
     async fn get_request_addr(&self, device_id: &str) -> Option<SocketAddr> {
@@ -487,11 +669,18 @@ impl PairingService {
 
         // Send unpair packet to the device via TLS connection
         if let Err(e) = self.send_pairing_packet(&packet, device_id).await {
-            warn!("Failed to send unpair packet to {}: {}", device_id, e);
             // Continue with local unpair even if packet sending fails
-            // The device may be unreachable, but we still want to unpair locally
+            // The device may be unreachable, so tell it when it connects again
+            warn!(
+                "Failed to send unpair packet to {}, deferring until it connects: {}",
+                device_id, e
+            );
+            let mut deferred = self.deferred_packets.write().await;
+            deferred.insert(device_id.to_string(), packet);
+            self.save_deferred(&deferred);
         } else {
             debug!("Unpair packet sent successfully to {}", device_id);
+            self.drop_deferred(device_id).await;
         }
 
         let _ = self.event_tx.send(PairingEvent::DeviceUnpaired {
@@ -501,6 +690,90 @@ impl PairingService {
         Ok(())
     }
 
+    /// List the certificates pinned for paired devices
+    pub async fn paired_certificates(&self) -> Result<Vec<PairedCertificate>> {
+        self.handler.read().await.paired_certificates()
+    }
+
+    /// Revoke trust in a device
+    ///
+    /// Removes the pinned certificate and unpairs, notifying the device if it
+    /// is connected. Fails if no certificate is pinned for the device, or if
+    /// the device ID could name a file outside the certificate directory.
+    pub async fn revoke_certificate(&self, device_id: &str) -> Result<()> {
+        if device_id.is_empty() || device_id.contains(['/', '\\']) || device_id.contains("..") {
+            return Err(ProtocolError::PermissionDenied(format!(
+                "Invalid device ID {:?}",
+                device_id
+            )));
+        }
+
+        if !self.handler.read().await.has_pinned_certificate(device_id) {
+            return Err(ProtocolError::DeviceNotFound(format!(
+                "No certificate pinned for device {}",
+                device_id
+            )));
+        }
+
+        info!("Revoking certificate of device {}", device_id);
+        self.unpair(device_id).await
+    }
+
+    /// Check whether a pairing change is waiting for the device to connect
+    pub async fn has_deferred(&self, device_id: &str) -> bool {
+        self.deferred_packets.read().await.contains_key(device_id)
+    }
+
+    /// Deliver the pairing change deferred while the device was offline
+    ///
+    /// Called when the device connects. The change stays queued if sending
+    /// fails. Returns whether a change was delivered.
+    pub async fn deliver_deferred(&self, device_id: &str) -> Result<bool> {
+        let Some(packet) = self.drop_deferred(device_id).await else {
+            return Ok(false);
+        };
+
+        info!(
+            "Delivering deferred '{}' to device {}",
+            packet.packet_type, device_id
+        );
+        if let Err(e) = self.send_pairing_packet(&packet, device_id).await {
+            // Keep a change queued in the meantime, it is newer
+            let mut deferred = self.deferred_packets.write().await;
+            deferred.entry(device_id.to_string()).or_insert(packet);
+            self.save_deferred(&deferred);
+            return Err(e);
+        }
+
+        Ok(true)
+    }
+
+    /// Drop the pairing change deferred for a device, returning it
+    async fn drop_deferred(&self, device_id: &str) -> Option<Packet> {
+        let mut deferred = self.deferred_packets.write().await;
+        let packet = deferred.remove(device_id)?;
+        self.save_deferred(&deferred);
+        Some(packet)
+    }
+
+    /// Store the deferred pairing changes
+    ///
+    /// Called with the lock held so concurrent changes are written in order.
+    /// Failures are logged; the changes are still delivered while the
+    /// service runs.
+    fn save_deferred(&self, deferred: &HashMap<String, Packet>) {
+        let path = self.config.cert_dir.join(DEFERRED_PACKETS_FILE);
+        let result = serde_json::to_vec_pretty(deferred)
+            .map_err(ProtocolError::from)
+            .and_then(|json| write_private_file(&path, json));
+        if let Err(e) = result {
+            warn!(
+                "Failed to store deferred pairing changes at {:?}: {}",
+                path, e
+            );
+        }
+    }
+
     /// Check if a device is paired
     pub async fn is_paired(&self, device_id: &str) -> bool {
         let handler = self.handler.read().await;
@@ -568,9 +841,42 @@ impl PairingService {
     }
 }
 
+/// Load the pairing changes deferred before the last restart
+///
+/// Logs and ignores an unreadable or malformed file.
+fn load_deferred_packets(cert_dir: &Path) -> HashMap<String, Packet> {
+    let path = cert_dir.join(DEFERRED_PACKETS_FILE);
+    let data = match std::fs::read(&path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return HashMap::new(),
+        Err(e) => {
+            warn!(
+                "Failed to read deferred pairing changes at {:?}: {}",
+                path, e
+            );
+            return HashMap::new();
+        }
+    };
+
+    match serde_json::from_slice::<HashMap<String, Packet>>(&data) {
+        Ok(deferred) => {
+            debug!("Loaded {} deferred pairing changes", deferred.len());
+            deferred
+        }
+        Err(e) => {
+            warn!(
+                "Ignoring malformed deferred pairing changes at {:?}: {}",
+                path, e
+            );
+            HashMap::new()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pairing::handler::PairingPacket;
     use tempfile::TempDir;
 
     #[tokio::test]
@@ -599,4 +905,197 @@ mod tests {
         // Events channel should be ready
         assert!(!service.event_tx.is_closed());
     }
+
+    #[tokio::test]
+    async fn test_inbound_request_reports_requested_by_peer() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = PairingConfig {
+            cert_dir: temp_dir.path().to_path_buf(),
+            timeout: Duration::from_secs(30),
+        };
+
+        let service = PairingService::new("test_device", config).unwrap();
+        let mut events = service.subscribe().await;
+        let mut phone = DeviceInfo::new("Phone", crate::DeviceType::Phone, 1716);
+        phone.device_id = "phone".to_string();
+        let addr: SocketAddr = "127.0.0.1:1716".parse().unwrap();
+
+        service
+            .handle_pairing_packet(&PairingPacket::request(), &phone, b"cert", addr)
+            .await
+            .unwrap();
+        assert!(matches!(
+            events.recv().await,
+            Some(PairingEvent::RequestReceived { device_id, .. }) if device_id == "phone"
+        ));
+        assert!(matches!(
+            events.recv().await,
+            Some(PairingEvent::StatusChanged {
+                device_id,
+                status: PairingStatus::RequestedByPeer,
+            }) if device_id == "phone"
+        ));
+
+        // The phone withdraws the request
+        service
+            .handle_pairing_packet(&PairingPacket::reject(), &phone, b"cert", addr)
+            .await
+            .unwrap();
+        assert!(matches!(
+            events.recv().await,
+            Some(PairingEvent::PairingRejected { device_id, .. }) if device_id == "phone"
+        ));
+        assert!(!service.is_paired("phone").await);
+    }
+
+    #[tokio::test]
+    async fn test_revoke_certificate() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = PairingConfig {
+            cert_dir: temp_dir.path().to_path_buf(),
+            timeout: Duration::from_secs(30),
+        };
+
+        // A certificate pinned by an earlier pairing
+        let phone_cert = CertificateInfo::generate("phone").unwrap();
+        std::fs::write(
+            temp_dir.path().join("phone.pem"),
+            pem::encode(&pem::Pem::new(
+                "CERTIFICATE",
+                phone_cert.certificate.clone(),
+            )),
+        )
+        .unwrap();
+
+        let service = PairingService::new("test_device", config).unwrap();
+        service.handler.write().await.load_paired_devices().unwrap();
+        let mut events = service.subscribe().await;
+
+        let certificates = service.paired_certificates().await.unwrap();
+        assert_eq!(certificates.len(), 1);
+        assert_eq!(certificates[0].device_id, "phone");
+        assert_eq!(certificates[0].fingerprint, phone_cert.fingerprint);
+        assert!(certificates[0].paired_at.is_some());
+        assert!(service.is_paired("phone").await);
+
+        service.revoke_certificate("phone").await.unwrap();
+
+        assert!(service.paired_certificates().await.unwrap().is_empty());
+        assert!(!temp_dir.path().join("phone.pem").exists());
+        assert!(!service.is_paired("phone").await);
+        assert!(matches!(
+            events.recv().await,
+            Some(PairingEvent::DeviceUnpaired { device_id }) if device_id == "phone"
+        ));
+
+        // Nothing left to revoke
+        assert!(service.revoke_certificate("phone").await.is_err());
+
+        // The phone was not connected; telling it survives a restart
+        assert!(service.has_deferred("phone").await);
+        drop(service);
+        let config = PairingConfig {
+            cert_dir: temp_dir.path().to_path_buf(),
+            timeout: Duration::from_secs(30),
+        };
+        let service = PairingService::new("test_device", config).unwrap();
+        assert!(service.has_deferred("phone").await);
+    }
+
+    #[tokio::test]
+    async fn test_revoke_certificate_rejects_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested = temp_dir.path().join("nested");
+        std::fs::create_dir(&nested).unwrap();
+        let config = PairingConfig {
+            cert_dir: nested,
+            timeout: Duration::from_secs(30),
+        };
+        let service = PairingService::new("test_device", config).unwrap();
+
+        // A certificate next to the certificate directory is never touched
+        std::fs::write(temp_dir.path().join("outside.pem"), "").unwrap();
+        for device_id in ["../outside", "..\\outside", "a/b", ""] {
+            assert!(matches!(
+                service.revoke_certificate(device_id).await,
+                Err(ProtocolError::PermissionDenied(_))
+            ));
+        }
+        assert!(temp_dir.path().join("outside.pem").exists());
+    }
+
+    #[tokio::test]
+    async fn test_changed_certificate_requires_repair() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = PairingConfig {
+            cert_dir: temp_dir.path().to_path_buf(),
+            timeout: Duration::from_secs(30),
+        };
+
+        // A certificate pinned by an earlier pairing
+        let old_cert = CertificateInfo::generate("phone").unwrap();
+        std::fs::write(
+            temp_dir.path().join("phone.pem"),
+            pem::encode(&pem::Pem::new("CERTIFICATE", old_cert.certificate.clone())),
+        )
+        .unwrap();
+
+        let service = PairingService::new("test_device", config).unwrap();
+        service.handler.write().await.load_paired_devices().unwrap();
+        let mut events = service.subscribe().await;
+        let mut phone = DeviceInfo::new("Phone", crate::DeviceType::Phone, 1716);
+        phone.device_id = "phone".to_string();
+        let addr: SocketAddr = "127.0.0.1:1716".parse().unwrap();
+
+        // The pinned certificate is still accepted
+        assert!(
+            service
+                .verify_certificate(&phone, &old_cert.certificate, addr)
+                .await
+        );
+
+        // The phone was reset and pairs again with a new certificate
+        let new_cert = CertificateInfo::generate("phone").unwrap();
+        let response = service
+            .handle_pairing_packet(
+                &PairingPacket::request(),
+                &phone,
+                &new_cert.certificate,
+                addr,
+            )
+            .await
+            .unwrap();
+        assert!(response.is_none());
+        assert!(matches!(
+            events.recv().await,
+            Some(PairingEvent::RePairRequired {
+                device_id,
+                old_fingerprint,
+                new_fingerprint,
+                ..
+            }) if device_id == "phone"
+                && old_fingerprint == old_cert.fingerprint
+                && new_fingerprint == new_cert.fingerprint
+        ));
+        assert!(matches!(
+            events.recv().await,
+            Some(PairingEvent::StatusChanged {
+                device_id,
+                status: PairingStatus::RequestedByPeer,
+            }) if device_id == "phone"
+        ));
+
+        // Not trusted until re-verified, and only asked once
+        assert!(
+            !service
+                .verify_certificate(&phone, &new_cert.certificate, addr)
+                .await
+        );
+        assert!(events.try_recv().is_err());
+        assert_eq!(
+            service.handler.read().await.pinned_certificate("phone"),
+            Some(old_cert.certificate.as_slice())
+        );
+        assert!(service.active_requests.read().await.contains_key("phone"));
+    }
 }
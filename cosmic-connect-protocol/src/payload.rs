@@ -2,6 +2,9 @@
 //!
 //! Handles TCP-based file transfers for the Share plugin.
 //! Implements the CConnect payload transfer protocol with TLS encryption.
+//! Received payloads that are fetched repeatedly (album art, icons,
+//! attachments) can be kept in a size-bounded [`PayloadCache`]. Sending can be
+//! capped with a [`RateLimiter`], optionally adapted to link quality.
 //!
 //! ## Protocol
 //!
@@ -60,9 +63,18 @@
 //! client.receive_file("/path/to/save/file.pdf", size).await?;
 //! ```
 
+pub mod cache;
+pub mod rate_limit;
+
+pub use cache::PayloadCache;
+pub use rate_limit::{AdaptiveRateConfig, AdaptiveRateController, RateLimiter};
+
 use crate::fs_utils::{cleanup_partial_file, create_file_safe, write_file_safe};
 use crate::{ProtocolError, Result, TlsConfig};
+use serde_json::Value;
+use std::collections::HashMap;
 use std::net::{SocketAddr, ToSocketAddrs};
+use std::ops::RangeInclusive;
 use std::path::Path;
 use tokio::fs::File;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
@@ -177,6 +189,7 @@ pub struct PayloadServer {
     listener: TcpListener,
     port: u16,
     progress_callback: Option<ProgressCallback>,
+    rate_limit: Option<RateLimiter>,
 }
 
 impl PayloadServer {
@@ -188,16 +201,35 @@ impl PayloadServer {
     ///
     /// Returns error if no ports are available in the range.
     pub async fn new() -> Result<Self> {
-        // Try to bind to a port in the CConnect range
-        for port in PORT_RANGE_START..=PORT_RANGE_END {
-            let addr = format!("0.0.0.0:{}", port);
-            if let Ok(listener) = TcpListener::bind(&addr).await {
-                info!("Payload server listening on port {}", port);
-                return Ok(Self {
-                    listener,
-                    port,
-                    progress_callback: None,
-                });
+        Self::bind_in_range(PORT_RANGE_START..=PORT_RANGE_END).await
+    }
+
+    /// Create a new payload server on an explicit port
+    ///
+    /// Binds to 0.0.0.0. Port 0 lets the system pick a free port; either
+    /// way [`port`](Self::port) reports the port actually bound.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the port is in use.
+    pub async fn bind(port: u16) -> Result<Self> {
+        let listener = TcpListener::bind(("0.0.0.0", port))
+            .await
+            .map_err(|e| ProtocolError::from_io_error(e, "Failed to bind payload server"))?;
+        Self::from_listener(listener)
+    }
+
+    /// Create a new payload server on the first free port of a range
+    ///
+    /// Binds to 0.0.0.0, trying the ports in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no ports are available in the range.
+    pub async fn bind_in_range(ports: RangeInclusive<u16>) -> Result<Self> {
+        for port in ports.clone() {
+            if let Ok(listener) = TcpListener::bind(("0.0.0.0", port)).await {
+                return Self::from_listener(listener);
             }
         }
 
@@ -205,11 +237,24 @@ impl PayloadServer {
             std::io::ErrorKind::AddrInUse,
             format!(
                 "Failed to bind payload server - all ports in range {}-{} are in use",
-                PORT_RANGE_START, PORT_RANGE_END
+                ports.start(),
+                ports.end()
             ),
         )))
     }
 
+    /// Wrap a bound listener, reading back the port it is bound to
+    fn from_listener(listener: TcpListener) -> Result<Self> {
+        let port = listener.local_addr().map_err(ProtocolError::Io)?.port();
+        info!("Payload server listening on port {}", port);
+        Ok(Self {
+            listener,
+            port,
+            progress_callback: None,
+            rate_limit: None,
+        })
+    }
+
     /// Create a new payload server using blocking I/O
     ///
     /// This variant uses std::net::TcpListener internally and converts to tokio,
@@ -237,6 +282,7 @@ impl PayloadServer {
                     listener,
                     port,
                     progress_callback: None,
+                    rate_limit: None,
                 });
             }
         }
@@ -269,11 +315,22 @@ impl PayloadServer {
         self
     }
 
+    /// Cap the sending rate with a shared limiter
+    pub fn with_rate_limit(mut self, limiter: RateLimiter) -> Self {
+        self.rate_limit = Some(limiter);
+        self
+    }
+
     /// Get the port this server is listening on
     pub fn port(&self) -> u16 {
         self.port
     }
 
+    /// Payload transfer info to advertise in the packet carrying the payload
+    pub fn transfer_info(&self) -> HashMap<String, Value> {
+        HashMap::from([("port".to_string(), Value::from(self.port))])
+    }
+
     /// Get the socket address this server is bound to
     pub fn local_addr(&self) -> Result<SocketAddr> {
         self.listener.local_addr().map_err(ProtocolError::Io)
@@ -331,6 +388,7 @@ impl PayloadServer {
         // Stream file data
         let mut buffer = vec![0u8; BUFFER_SIZE];
         let mut total_bytes = 0u64;
+        let mut pacer = self.rate_limit.as_ref().map(RateLimiter::pacer);
 
         loop {
             // Read from file
@@ -366,6 +424,10 @@ impl PayloadServer {
                 bytes_read, total_bytes, file_size
             );
 
+            if let Some(pacer) = pacer.as_mut() {
+                pacer.pace(bytes_read).await;
+            }
+
             // Call progress callback if set
             if let Some(ref callback) = self.progress_callback {
                 if !callback(total_bytes, file_size) {
@@ -854,6 +916,7 @@ pub struct TlsPayloadServer {
     port: u16,
     tls_config: std::sync::Arc<TlsConfig>,
     progress_callback: Option<ProgressCallback>,
+    rate_limit: Option<RateLimiter>,
 }
 
 impl TlsPayloadServer {
@@ -879,6 +942,7 @@ impl TlsPayloadServer {
                     port,
                     tls_config,
                     progress_callback: None,
+                    rate_limit: None,
                 });
             }
         }
@@ -906,6 +970,15 @@ impl TlsPayloadServer {
         self
     }
 
+    /// Cap the sending rate with a shared limiter
+    ///
+    /// The cap can change while the file is being sent, see
+    /// [`AdaptiveRateController`].
+    pub fn with_rate_limit(mut self, limiter: RateLimiter) -> Self {
+        self.rate_limit = Some(limiter);
+        self
+    }
+
     /// Accept connection and send file over TLS
     ///
     /// Waits for a single connection, performs TLS handshake as CLIENT (inverted role),
@@ -987,6 +1060,7 @@ impl TlsPayloadServer {
         // Stream file data over TLS
         let mut buffer = vec![0u8; BUFFER_SIZE];
         let mut total_bytes: u64 = 0;
+        let mut pacer = self.rate_limit.as_ref().map(RateLimiter::pacer);
 
         loop {
             let bytes_read = timeout(TRANSFER_TIMEOUT, file.read(&mut buffer))
@@ -1024,6 +1098,10 @@ impl TlsPayloadServer {
                 bytes_read, total_bytes, file_size
             );
 
+            if let Some(pacer) = pacer.as_mut() {
+                pacer.pace(bytes_read).await;
+            }
+
             // Call progress callback if set
             if let Some(ref callback) = self.progress_callback {
                 if !callback(total_bytes, file_size) {
@@ -1116,6 +1194,58 @@ mod tests {
         assert_eq!(&received_data[..], test_data);
     }
 
+    #[tokio::test]
+    async fn test_transfer_on_explicit_port() {
+        let mut source_file = NamedTempFile::new().unwrap();
+        let test_data = b"Sent over an explicitly chosen port";
+        source_file.write_all(test_data).unwrap();
+        source_file.flush().unwrap();
+        let source_path = source_file.path().to_owned();
+        let dest_file = NamedTempFile::new().unwrap();
+        let dest_path = dest_file.path().to_owned();
+
+        // Find a free port, then bind the server to it
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let server = PayloadServer::bind(port).await.unwrap();
+        assert_eq!(server.port(), port);
+        assert_eq!(server.transfer_info()["port"], port);
+
+        // The advertised port is the one to connect to
+        let advertised = server.transfer_info()["port"].as_u64().unwrap() as u16;
+        let server_task = tokio::spawn(async move { server.send_file(source_path).await });
+        let client = PayloadClient::new("127.0.0.1", advertised).await.unwrap();
+        client
+            .receive_file(&dest_path, test_data.len() as u64)
+            .await
+            .unwrap();
+        server_task.await.unwrap().unwrap();
+
+        assert_eq!(tokio::fs::read(&dest_path).await.unwrap(), test_data);
+    }
+
+    #[tokio::test]
+    async fn test_bind_reports_system_chosen_port() {
+        let server = PayloadServer::bind(0).await.unwrap();
+
+        assert_ne!(server.port(), 0);
+        assert_eq!(server.port(), server.local_addr().unwrap().port());
+        assert_eq!(server.transfer_info()["port"], server.port());
+    }
+
+    #[tokio::test]
+    async fn test_bind_fails_when_ports_in_use() {
+        let taken = PayloadServer::bind(0).await.unwrap();
+        let port = taken.port();
+
+        // The only port of the range is in use
+        assert!(PayloadServer::bind_in_range(port..=port).await.is_err());
+        assert!(PayloadServer::bind(port).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_file_transfer_info_conversion() {
         let transfer_info = FileTransferInfo {
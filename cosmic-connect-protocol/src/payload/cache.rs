@@ -0,0 +1,379 @@
+//! Payload Cache
+//!
+//! Size-bounded on-disk cache for payloads that are fetched repeatedly, such
+//! as album art, notification icons and message attachments. Entries are
+//! keyed by an arbitrary string (a URL or content hash) and stored under the
+//! SHA256 of the key, so the cache can be reopened after a restart.
+//!
+//! When the total size exceeds the cap, the least recently used entries are
+//! evicted. Files are written to a temporary name and renamed into place, so
+//! a path returned by [`PayloadCache::get`] never points to a partial file.
+//! It may however be evicted later; callers should read it promptly or
+//! handle it disappearing.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! let cache = PayloadCache::open(cache_dir.join("album-art"), 32 * 1024 * 1024).await?;
+//!
+//! let path = match cache.get(&art_url).await {
+//!     Some(path) => path,
+//!     None => cache.put(&art_url, &downloaded).await?,
+//! };
+//! ```
+
+use crate::{ProtocolError, Result};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::UNIX_EPOCH;
+use tokio::fs;
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+/// Suffix of files that are still being written
+const TEMP_SUFFIX: &str = ".tmp";
+
+/// Cached entry
+#[derive(Debug, Clone, Copy)]
+struct CacheEntry {
+    /// Size of the file in bytes
+    size: u64,
+    /// Logical time of the last access, higher is more recent
+    last_used: u64,
+}
+
+/// Index of the cached files, by file name
+#[derive(Debug, Default)]
+struct CacheIndex {
+    entries: HashMap<String, CacheEntry>,
+    total_bytes: u64,
+    clock: u64,
+}
+
+impl CacheIndex {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn insert(&mut self, name: String, size: u64) {
+        let last_used = self.tick();
+        if let Some(old) = self.entries.insert(name, CacheEntry { size, last_used }) {
+            self.total_bytes -= old.size;
+        }
+        self.total_bytes += size;
+    }
+
+    fn remove(&mut self, name: &str) -> Option<CacheEntry> {
+        let entry = self.entries.remove(name)?;
+        self.total_bytes -= entry.size;
+        Some(entry)
+    }
+
+    /// Least recently used entry other than `keep`
+    fn oldest_except(&self, keep: &str) -> Option<String> {
+        self.entries
+            .iter()
+            .filter(|(name, _)| name.as_str() != keep)
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(name, _)| name.clone())
+    }
+}
+
+/// Size-bounded LRU cache of payloads on disk
+#[derive(Debug)]
+pub struct PayloadCache {
+    /// Directory holding the cached files
+    dir: PathBuf,
+    /// Maximum total size of the cached files in bytes
+    max_bytes: u64,
+    /// Index of cached files, also serializing changes to the directory
+    index: Mutex<CacheIndex>,
+    /// Counter for unique temporary file names
+    temp_counter: AtomicU64,
+}
+
+impl PayloadCache {
+    /// Open a cache in `dir`, holding at most `max_bytes`
+    ///
+    /// Files already in the directory are indexed, oldest modification first,
+    /// and leftovers of interrupted writes are removed.
+    pub async fn open(dir: impl Into<PathBuf>, max_bytes: u64) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).await?;
+
+        let mut files = Vec::new();
+        let mut entries = fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.ends_with(TEMP_SUFFIX) {
+                let _ = fs::remove_file(entry.path()).await;
+                continue;
+            }
+
+            let metadata = entry.metadata().await?;
+            if !metadata.is_file() {
+                continue;
+            }
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .unwrap_or_default();
+            files.push((modified, name, metadata.len()));
+        }
+
+        files.sort();
+        let mut index = CacheIndex::default();
+        for (_, name, size) in files {
+            index.insert(name, size);
+        }
+        debug!(
+            "Opened payload cache {:?} with {} entries ({} bytes)",
+            dir,
+            index.entries.len(),
+            index.total_bytes
+        );
+
+        let cache = Self {
+            dir,
+            max_bytes,
+            index: Mutex::new(index),
+            temp_counter: AtomicU64::new(0),
+        };
+        cache.evict(&mut *cache.index.lock().await, "").await;
+
+        Ok(cache)
+    }
+
+    /// Directory holding the cached files
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Maximum total size of the cached files in bytes
+    pub fn max_bytes(&self) -> u64 {
+        self.max_bytes
+    }
+
+    /// Total size of the cached files in bytes
+    pub async fn total_bytes(&self) -> u64 {
+        self.index.lock().await.total_bytes
+    }
+
+    /// Number of cached entries
+    pub async fn len(&self) -> usize {
+        self.index.lock().await.entries.len()
+    }
+
+    /// Whether the cache is empty
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+
+    /// Path of a cached entry, marking it as recently used
+    pub async fn get(&self, key: &str) -> Option<PathBuf> {
+        let name = file_name(key);
+        let mut index = self.index.lock().await;
+
+        let last_used = index.tick();
+        let entry = index.entries.get_mut(&name)?;
+        entry.last_used = last_used;
+
+        Some(self.dir.join(name))
+    }
+
+    /// Store `bytes` under `key` and return the path of the cached file
+    ///
+    /// Replaces an existing entry for the key and evicts least recently used
+    /// entries until the cache fits its cap again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payload alone exceeds the cap, or if writing
+    /// the file fails.
+    pub async fn put(&self, key: &str, bytes: &[u8]) -> Result<PathBuf> {
+        let size = bytes.len() as u64;
+        if size > self.max_bytes {
+            return Err(ProtocolError::ResourceExhausted(format!(
+                "Payload of {} bytes exceeds cache size of {} bytes",
+                size, self.max_bytes
+            )));
+        }
+
+        let name = file_name(key);
+        let path = self.dir.join(&name);
+
+        // Write outside the lock; concurrent puts use distinct temporary files
+        let counter = self.temp_counter.fetch_add(1, Ordering::Relaxed);
+        let temp_path = self
+            .dir
+            .join(format!("{}.{}{}", name, counter, TEMP_SUFFIX));
+        if let Err(e) = fs::write(&temp_path, bytes).await {
+            let _ = fs::remove_file(&temp_path).await;
+            return Err(e.into());
+        }
+
+        let mut index = self.index.lock().await;
+        if let Err(e) = fs::rename(&temp_path, &path).await {
+            let _ = fs::remove_file(&temp_path).await;
+            return Err(e.into());
+        }
+        index.insert(name.clone(), size);
+        self.evict(&mut index, &name).await;
+
+        debug!("Cached {} bytes for {}", size, key);
+        Ok(path)
+    }
+
+    /// Remove an entry
+    ///
+    /// Returns whether the key was cached.
+    pub async fn remove(&self, key: &str) -> Result<bool> {
+        let name = file_name(key);
+        let mut index = self.index.lock().await;
+
+        if index.remove(&name).is_none() {
+            return Ok(false);
+        }
+        remove_file_if_exists(&self.dir.join(&name)).await?;
+        Ok(true)
+    }
+
+    /// Evict least recently used entries, except `keep`, until under the cap
+    async fn evict(&self, index: &mut CacheIndex, keep: &str) {
+        while index.total_bytes > self.max_bytes {
+            let Some(name) = index.oldest_except(keep) else {
+                break;
+            };
+
+            index.remove(&name);
+            if let Err(e) = remove_file_if_exists(&self.dir.join(&name)).await {
+                warn!("Failed to evict cached payload {}: {}", name, e);
+            } else {
+                debug!("Evicted cached payload {}", name);
+            }
+        }
+    }
+}
+
+/// File name for a cache key
+fn file_name(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+async fn remove_file_if_exists(path: &Path) -> std::io::Result<()> {
+    match fs::remove_file(path).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_put_and_get() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = PayloadCache::open(temp_dir.path(), 1024).await.unwrap();
+
+        assert!(cache.get("https://example.com/art.png").await.is_none());
+
+        let path = cache
+            .put("https://example.com/art.png", b"image")
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"image");
+        assert_eq!(cache.get("https://example.com/art.png").await, Some(path));
+
+        // Replacing an entry does not count it twice
+        cache
+            .put("https://example.com/art.png", b"new image")
+            .await
+            .unwrap();
+        assert_eq!(cache.len().await, 1);
+        assert_eq!(cache.total_bytes().await, 9);
+
+        assert!(cache.remove("https://example.com/art.png").await.unwrap());
+        assert!(cache.is_empty().await);
+    }
+
+    #[tokio::test]
+    async fn test_evicts_least_recently_used_at_cap() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = PayloadCache::open(temp_dir.path(), 300).await.unwrap();
+
+        let first = cache.put("first", &[1; 100]).await.unwrap();
+        let second = cache.put("second", &[2; 100]).await.unwrap();
+        cache.put("third", &[3; 100]).await.unwrap();
+        assert_eq!(cache.total_bytes().await, 300);
+
+        // Touch the first entry so the second is the oldest
+        cache.get("first").await.unwrap();
+        cache.put("fourth", &[4; 100]).await.unwrap();
+
+        assert_eq!(cache.total_bytes().await, 300);
+        assert!(cache.get("second").await.is_none());
+        assert!(!second.exists());
+        assert!(cache.get("first").await.is_some());
+        assert!(first.exists());
+
+        // Too large to ever fit
+        assert!(cache.put("huge", &[0; 301]).await.is_err());
+        assert_eq!(cache.len().await, 3);
+    }
+
+    #[tokio::test]
+    async fn test_reopen_keeps_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        {
+            let cache = PayloadCache::open(temp_dir.path(), 1024).await.unwrap();
+            cache.put("icon", b"png").await.unwrap();
+        }
+
+        let cache = PayloadCache::open(temp_dir.path(), 1024).await.unwrap();
+        let path = cache.get("icon").await.unwrap();
+        assert_eq!(std::fs::read(path).unwrap(), b"png");
+        assert_eq!(cache.total_bytes().await, 3);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_puts_keep_index_consistent() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = Arc::new(PayloadCache::open(temp_dir.path(), 1000).await.unwrap());
+
+        let tasks: Vec<_> = (0..64)
+            .map(|i| {
+                let cache = cache.clone();
+                tokio::spawn(async move {
+                    // Half the keys collide so entries are also replaced concurrently
+                    let key = format!("key-{}", i % 32);
+                    cache.put(&key, &vec![i as u8; 50 + i]).await.unwrap();
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        // The index matches the directory exactly and respects the cap
+        let total = cache.total_bytes().await;
+        assert!(total <= 1000);
+
+        let mut on_disk = 0;
+        let mut files = 0;
+        for entry in std::fs::read_dir(temp_dir.path()).unwrap() {
+            let entry = entry.unwrap();
+            assert!(!entry.file_name().to_string_lossy().ends_with(TEMP_SUFFIX));
+            on_disk += entry.metadata().unwrap().len();
+            files += 1;
+        }
+        assert_eq!(on_disk, total);
+        assert_eq!(files, cache.len().await);
+    }
+}
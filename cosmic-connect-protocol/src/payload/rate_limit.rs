@@ -0,0 +1,259 @@
+//! Payload Rate Limiting
+//!
+//! Large transfers can saturate a slow link and make interactive features
+//! such as remote input and media control lag. A [`RateLimiter`] caps the
+//! rate payload servers send at, and can pause sending altogether.
+//!
+//! [`AdaptiveRateController`] sets the cap from the measured
+//! [`ConnectionQuality`]: no cap while the link is excellent, a lower cap as
+//! round-trip times grow, and the full rate again once they recover. It is
+//! opt-in; payload servers without a limiter send as fast as they can.
+//!
+//! A paused transfer stalls the receiver, which gives up after its transfer
+//! timeout, so pausing is only suitable for short dips in link quality.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! let controller = AdaptiveRateController::new(AdaptiveRateConfig::default());
+//! let server = TlsPayloadServer::new(tls_config)
+//!     .await?
+//!     .with_rate_limit(controller.limiter());
+//!
+//! // Whenever a new RTT sample arrives
+//! controller.observe(device.connection_quality());
+//! ```
+
+use crate::ConnectionQuality;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::watch;
+use tokio::time::{sleep, Duration, Instant};
+use tracing::debug;
+
+/// Shared cap on the rate payloads are sent at
+///
+/// Clones share the cap, so one limiter can be handed to several transfers
+/// and adjusted while they run. Each transfer is paced separately.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    cap: Arc<watch::Sender<Option<u64>>>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateLimiter {
+    /// Create a limiter without a cap
+    pub fn new() -> Self {
+        let (cap, _) = watch::channel(None);
+        Self { cap: Arc::new(cap) }
+    }
+
+    /// Current cap in bytes per second
+    ///
+    /// `None` means unlimited and `Some(0)` means paused.
+    pub fn cap(&self) -> Option<u64> {
+        *self.cap.borrow()
+    }
+
+    /// Change the cap, taking effect for running transfers immediately
+    pub fn set_cap(&self, cap: Option<u64>) {
+        self.cap.send_if_modified(|current| {
+            let changed = *current != cap;
+            *current = cap;
+            changed
+        });
+    }
+
+    /// Start pacing a transfer
+    pub fn pacer(&self) -> Pacer {
+        Pacer {
+            cap: self.cap.subscribe(),
+            window_start: Instant::now(),
+            window_bytes: 0,
+        }
+    }
+}
+
+/// Paces a single transfer against a [`RateLimiter`]
+#[derive(Debug)]
+pub struct Pacer {
+    cap: watch::Receiver<Option<u64>>,
+    window_start: Instant,
+    window_bytes: u64,
+}
+
+impl Pacer {
+    /// Account for sent bytes, waiting as long as the cap requires
+    ///
+    /// While the limiter is paused this waits until the cap is raised.
+    pub async fn pace(&mut self, bytes: usize) {
+        // Measure from the last cap change so a new cap applies right away
+        if self.cap.has_changed().unwrap_or(false) {
+            self.cap.borrow_and_update();
+            self.restart_window();
+        }
+        self.window_bytes += bytes as u64;
+
+        loop {
+            let cap = *self.cap.borrow();
+            match cap {
+                None => return,
+                Some(0) => {
+                    debug!("Payload transfer paused");
+                    if self.cap.changed().await.is_err() {
+                        return;
+                    }
+                    self.restart_window();
+                }
+                Some(rate) => {
+                    let due = Duration::from_secs_f64(self.window_bytes as f64 / rate as f64);
+                    let elapsed = self.window_start.elapsed();
+                    if due <= elapsed {
+                        return;
+                    }
+                    tokio::select! {
+                        _ = sleep(due - elapsed) => return,
+                        changed = self.cap.changed() => {
+                            if changed.is_err() {
+                                return;
+                            }
+                            self.restart_window();
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn restart_window(&mut self) {
+        self.window_start = Instant::now();
+        self.window_bytes = 0;
+    }
+}
+
+/// Rate caps for each connection quality
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdaptiveRateConfig {
+    /// Cap in bytes per second while the link is rated good
+    pub good_rate: u64,
+    /// Cap in bytes per second while the link is rated poor, 0 pauses
+    pub poor_rate: u64,
+}
+
+impl Default for AdaptiveRateConfig {
+    fn default() -> Self {
+        Self {
+            good_rate: 4 * 1024 * 1024,
+            poor_rate: 256 * 1024,
+        }
+    }
+}
+
+impl AdaptiveRateConfig {
+    /// Cap for a connection quality, `None` meaning unlimited
+    ///
+    /// Without RTT measurements the link is assumed to be fine.
+    pub fn cap_for(&self, quality: Option<ConnectionQuality>) -> Option<u64> {
+        match quality {
+            None | Some(ConnectionQuality::Excellent) => None,
+            Some(ConnectionQuality::Good) => Some(self.good_rate),
+            Some(ConnectionQuality::Poor) => Some(self.poor_rate),
+        }
+    }
+}
+
+/// Adjusts a [`RateLimiter`] to the measured link quality
+#[derive(Debug, Clone, Default)]
+pub struct AdaptiveRateController {
+    config: AdaptiveRateConfig,
+    limiter: RateLimiter,
+}
+
+impl AdaptiveRateController {
+    /// Create a controller with an unlimited limiter
+    pub fn new(config: AdaptiveRateConfig) -> Self {
+        Self {
+            config,
+            limiter: RateLimiter::new(),
+        }
+    }
+
+    /// Limiter to attach to payload servers
+    pub fn limiter(&self) -> RateLimiter {
+        self.limiter.clone()
+    }
+
+    /// Update the cap from the latest connection quality
+    pub fn observe(&self, quality: Option<ConnectionQuality>) {
+        let cap = self.config.cap_for(quality);
+        if cap != self.limiter.cap() {
+            debug!(
+                "Link quality {:?}, payload rate cap now {:?} bytes/s",
+                quality.map(|q| q.as_str()),
+                cap
+            );
+        }
+        self.limiter.set_cap(cap);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cap_follows_link_quality() {
+        let config = AdaptiveRateConfig {
+            good_rate: 1024 * 1024,
+            poor_rate: 64 * 1024,
+        };
+        let controller = AdaptiveRateController::new(config);
+        let limiter = controller.limiter();
+
+        // Round-trip times degrade, then recover
+        let caps: Vec<Option<u64>> = [20, 120, 400, 120, 20]
+            .into_iter()
+            .map(|rtt_ms| {
+                controller.observe(Some(ConnectionQuality::from_rtt_ms(rtt_ms)));
+                limiter.cap()
+            })
+            .collect();
+        assert_eq!(
+            caps,
+            vec![
+                None,
+                Some(config.good_rate),
+                Some(config.poor_rate),
+                Some(config.good_rate),
+                None
+            ]
+        );
+
+        // Sending under a cap takes as long as the cap requires
+        controller.observe(Some(ConnectionQuality::Poor));
+        let mut pacer = limiter.pacer();
+        let start = Instant::now();
+        pacer.pace(config.poor_rate as usize / 4).await;
+        assert!(start.elapsed() >= Duration::from_millis(250));
+
+        // A paused transfer resumes once the link recovers
+        let paused = AdaptiveRateController::new(AdaptiveRateConfig {
+            poor_rate: 0,
+            ..config
+        });
+        paused.observe(Some(ConnectionQuality::Poor));
+        let mut pacer = paused.limiter().pacer();
+        let transfer = tokio::spawn(async move { pacer.pace(1024).await });
+        sleep(Duration::from_millis(100)).await;
+        assert!(!transfer.is_finished());
+
+        paused.observe(Some(ConnectionQuality::Excellent));
+        transfer.await.unwrap();
+    }
+}
@@ -2,7 +2,13 @@
 //!
 //! Provides PipeWire/WirePlumber integration for volume control using wpctl CLI.
 //! Falls back gracefully if wpctl is not available.
+//!
+//! Volume and mute changes return the stderr of a failed wpctl command as a
+//! [`ProtocolError::Plugin`], so callers can tell the remote device the
+//! change did not apply. Plugins use the [`VolumeControl`] trait so tests can
+//! swap in a mock backend.
 
+use crate::{ProtocolError, Result};
 use std::process::Command;
 use tracing::{debug, warn};
 
@@ -23,9 +29,52 @@ pub struct AudioSink {
     pub max_volume: i32,
 }
 
+/// Audio sink control used by plugins
+pub trait VolumeControl: Send + Sync {
+    /// List all audio sinks
+    fn list_sinks(&self) -> Vec<AudioSink>;
+
+    /// Set volume for a sink (0-150, allows boost)
+    fn set_volume(&self, id: u32, volume: i32) -> Result<()>;
+
+    /// Set mute status for a sink
+    fn set_mute(&self, id: u32, muted: bool) -> Result<()>;
+
+    /// Get the default sink ID
+    fn default_sink_id(&self) -> Option<u32> {
+        self.list_sinks()
+            .into_iter()
+            .find(|s| s.is_default)
+            .map(|s| s.id)
+    }
+
+    /// Find sink by name (partial match)
+    fn find_sink_by_name(&self, name: &str) -> Option<AudioSink> {
+        let name = name.to_lowercase();
+        self.list_sinks()
+            .into_iter()
+            .find(|s| s.name.to_lowercase().contains(&name))
+    }
+}
+
 /// Audio backend using wpctl (WirePlumber CLI)
+#[derive(Debug, Default)]
 pub struct AudioBackend;
 
+impl VolumeControl for AudioBackend {
+    fn list_sinks(&self) -> Vec<AudioSink> {
+        Self::list_sinks()
+    }
+
+    fn set_volume(&self, id: u32, volume: i32) -> Result<()> {
+        Self::set_volume(id, volume)
+    }
+
+    fn set_mute(&self, id: u32, muted: bool) -> Result<()> {
+        Self::set_mute(id, muted)
+    }
+}
+
 impl AudioBackend {
     /// Check if wpctl is available
     pub fn is_available() -> bool {
@@ -158,31 +207,50 @@ impl AudioBackend {
         Some((volume, muted))
     }
 
+    /// Run a wpctl command that changes state
+    ///
+    /// # Errors
+    ///
+    /// Returns the command's stderr if it fails, or why it could not be run.
+    fn run_wpctl(args: &[&str]) -> Result<()> {
+        let output = Command::new("wpctl")
+            .args(args)
+            .output()
+            .map_err(|e| ProtocolError::Plugin(format!("Failed to run wpctl: {}", e)))?;
+
+        if output.status.success() {
+            return Ok(());
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = match stderr.trim() {
+            "" => output.status.to_string(),
+            stderr => stderr.to_string(),
+        };
+        Err(ProtocolError::Plugin(format!(
+            "wpctl {} failed: {}",
+            args.join(" "),
+            reason
+        )))
+    }
+
     /// Set volume for a sink (0-150, allows boost)
-    pub fn set_volume(id: u32, volume: i32) -> bool {
+    pub fn set_volume(id: u32, volume: i32) -> Result<()> {
         let volume = volume.clamp(0, 150);
         let vol_str = format!("{}%", volume);
 
         debug!("Setting volume for sink {} to {}", id, vol_str);
 
-        Command::new("wpctl")
-            .args(["set-volume", &id.to_string(), &vol_str])
-            .status()
-            .map(|s| s.success())
-            .unwrap_or(false)
+        Self::run_wpctl(&["set-volume", &id.to_string(), &vol_str])
     }
 
     /// Set mute status for a sink
-    pub fn set_mute(id: u32, muted: bool) -> bool {
+    pub fn set_mute(id: u32, muted: bool) -> Result<()> {
         let mute_arg = if muted { "1" } else { "0" };
 
         debug!("Setting mute for sink {} to {}", id, muted);
 
-        Command::new("wpctl")
-            .args(["set-mute", &id.to_string(), mute_arg])
-            .status()
-            .map(|s| s.success())
-            .unwrap_or(false)
+        Self::run_wpctl(&["set-mute", &id.to_string(), mute_arg])
     }
 
     /// Get the default sink ID
@@ -0,0 +1,386 @@
+//! Adaptive Playback Buffer
+//!
+//! Decides how much incoming audio to hold back before playback. The target
+//! delay grows when packets arrive with jitter, go missing, or playback runs
+//! dry, and shrinks again once the link has been stable for a while, always
+//! staying within the configured bounds.
+//!
+//! Jitter is estimated from packet arrival times alone (the data packets carry
+//! no sender timestamps): a running mean of the inter-arrival interval gives
+//! the frame duration, and a running mean of the deviation from it gives the
+//! jitter, smoothed with the 1/16 gain used by RTP (RFC 3550).
+//!
+//! The buffer does not own any audio. The playback task reports arrivals with
+//! [`AdaptiveBuffer::on_packet`] and asks [`AdaptiveBuffer::schedule`] on every
+//! tick how many queued packets to play or drop.
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Frame duration assumed until two packets have arrived (480 samples at 48kHz)
+const DEFAULT_FRAME_MS: f64 = 10.0;
+
+/// Smoothing gain for the frame duration and jitter estimates
+const ESTIMATE_GAIN: f64 = 1.0 / 16.0;
+
+/// How many jitter deviations the target must cover
+const JITTER_MULTIPLIER: f64 = 3.0;
+
+/// Growth after an underrun or lost packets
+const GROW_STEP_MS: u32 = 20;
+
+/// Reduction after a stable period
+const SHRINK_STEP_MS: u32 = 10;
+
+/// How long the link must be stable before the target shrinks
+const SHRINK_AFTER: Duration = Duration::from_secs(5);
+
+/// Bounds and starting point of the playback delay
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdaptiveBufferConfig {
+    /// Smallest target delay in milliseconds
+    pub min_ms: u32,
+    /// Largest target delay in milliseconds
+    pub max_ms: u32,
+    /// Target delay when the stream starts
+    pub initial_ms: u32,
+}
+
+/// Current buffering state, for diagnostics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BufferStats {
+    /// Delay the buffer is aiming for in milliseconds
+    pub target_ms: u32,
+
+    /// Smallest allowed target in milliseconds
+    pub min_ms: u32,
+
+    /// Largest allowed target in milliseconds
+    pub max_ms: u32,
+
+    /// Audio currently queued in milliseconds
+    pub buffered_ms: u32,
+
+    /// Estimated frame duration in milliseconds
+    pub frame_ms: f64,
+
+    /// Estimated arrival jitter in milliseconds
+    pub jitter_ms: f64,
+
+    /// Number of times playback ran dry
+    pub underruns: u64,
+
+    /// Packets detected missing from the sequence
+    pub lost_packets: u64,
+
+    /// Whether playback is running (false while prebuffering)
+    pub playing: bool,
+}
+
+/// What to do with queued packets on a playback tick
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Schedule {
+    /// Packets to decode and play, oldest first
+    pub play: usize,
+    /// Packets to discard after the played ones to cut latency
+    pub drop: usize,
+}
+
+/// Playback delay that adapts to network conditions
+#[derive(Debug, Clone)]
+pub struct AdaptiveBuffer {
+    config: AdaptiveBufferConfig,
+    target_ms: u32,
+    /// Running mean of the inter-arrival interval, once one has been seen
+    frame_estimate: Option<f64>,
+    jitter_ms: f64,
+    last_arrival: Option<Instant>,
+    next_sequence: Option<u64>,
+    /// When the target last grew or shrank
+    last_change: Option<Instant>,
+    playing: bool,
+    /// Playback time owed to the backend in milliseconds
+    release_budget_ms: f64,
+    underruns: u64,
+    lost_packets: u64,
+}
+
+impl AdaptiveBuffer {
+    /// Create a buffer; the initial target is clamped to the bounds
+    pub fn new(config: AdaptiveBufferConfig) -> Self {
+        Self {
+            config,
+            target_ms: config.initial_ms.clamp(config.min_ms, config.max_ms),
+            frame_estimate: None,
+            jitter_ms: 0.0,
+            last_arrival: None,
+            next_sequence: None,
+            last_change: None,
+            playing: false,
+            release_budget_ms: 0.0,
+            underruns: 0,
+            lost_packets: 0,
+        }
+    }
+
+    /// Change the bounds, keeping the current estimates
+    pub fn set_config(&mut self, config: AdaptiveBufferConfig) {
+        self.config = config;
+        self.target_ms = self.target_ms.clamp(config.min_ms, config.max_ms);
+    }
+
+    /// Delay the buffer is aiming for in milliseconds
+    pub fn target_ms(&self) -> u32 {
+        self.target_ms
+    }
+
+    /// Record a packet arriving at `now`
+    ///
+    /// `sequence` is the sender's packet counter, if it sent one; gaps in it
+    /// count as lost packets.
+    pub fn on_packet(&mut self, now: Instant, sequence: Option<u64>) {
+        if let Some(last) = self.last_arrival {
+            let interval = now.saturating_duration_since(last).as_secs_f64() * 1000.0;
+            self.frame_estimate = Some(match self.frame_estimate {
+                // The first interval seeds the frame estimate
+                None => interval.max(1.0),
+                Some(frame_ms) => {
+                    let deviation = (interval - frame_ms).abs();
+                    self.jitter_ms += (deviation - self.jitter_ms) * ESTIMATE_GAIN;
+                    frame_ms + (interval - frame_ms) * ESTIMATE_GAIN
+                }
+            });
+        }
+        self.last_arrival = Some(now);
+
+        if let Some(sequence) = sequence {
+            if let Some(expected) = self.next_sequence {
+                if sequence > expected {
+                    let lost = sequence - expected;
+                    self.lost_packets += lost;
+                    debug!("Audio stream lost {} packet(s)", lost);
+                    self.grow(GROW_STEP_MS, now);
+                }
+            }
+            self.next_sequence = Some(sequence + 1);
+        }
+
+        let required = self.required_ms();
+        if required > self.target_ms {
+            self.set_target(required, now);
+        } else if self.target_ms > required {
+            let stable_since = *self.last_change.get_or_insert(now);
+            if now.saturating_duration_since(stable_since) >= SHRINK_AFTER {
+                let shrunk = self.target_ms.saturating_sub(SHRINK_STEP_MS).max(required);
+                self.set_target(shrunk, now);
+            }
+        }
+    }
+
+    /// Decide what to do with `queued` packets after `elapsed` playback time
+    ///
+    /// Playback starts once the queue covers the target delay. If the queue
+    /// runs dry while playing, that is an underrun: the target grows and the
+    /// buffer refills before playing again. Audio queued well beyond the
+    /// target (after the target shrank) is dropped one packet per tick.
+    pub fn schedule(&mut self, queued: usize, elapsed: Duration, now: Instant) -> Schedule {
+        if !self.playing {
+            if queued == 0 || self.queued_ms(queued) < self.target_ms as f64 {
+                return Schedule::default();
+            }
+            self.playing = true;
+            self.release_budget_ms = 0.0;
+        }
+
+        let frame_ms = self.frame_ms();
+        self.release_budget_ms += elapsed.as_secs_f64() * 1000.0;
+        let mut play = 0;
+        while play < queued && self.release_budget_ms >= frame_ms {
+            play += 1;
+            self.release_budget_ms -= frame_ms;
+        }
+
+        if play == queued && self.release_budget_ms >= frame_ms {
+            self.underruns += 1;
+            debug!("Audio playback underrun, rebuffering");
+            self.playing = false;
+            self.release_budget_ms = 0.0;
+            self.grow(GROW_STEP_MS, now);
+            return Schedule { play, drop: 0 };
+        }
+
+        let remaining_ms = self.queued_ms(queued - play);
+        let drop = usize::from(remaining_ms > self.target_ms as f64 + frame_ms);
+        Schedule { play, drop }
+    }
+
+    /// Current buffering state with `queued` packets waiting
+    pub fn stats(&self, queued: usize) -> BufferStats {
+        BufferStats {
+            target_ms: self.target_ms,
+            min_ms: self.config.min_ms,
+            max_ms: self.config.max_ms,
+            buffered_ms: self.queued_ms(queued).round() as u32,
+            frame_ms: self.frame_ms(),
+            jitter_ms: self.jitter_ms,
+            underruns: self.underruns,
+            lost_packets: self.lost_packets,
+            playing: self.playing,
+        }
+    }
+
+    fn frame_ms(&self) -> f64 {
+        self.frame_estimate.unwrap_or(DEFAULT_FRAME_MS)
+    }
+
+    /// Delay needed to ride out the current jitter, within bounds
+    fn required_ms(&self) -> u32 {
+        let required = (self.frame_ms() + self.jitter_ms * JITTER_MULTIPLIER).ceil() as u32;
+        required.clamp(self.config.min_ms, self.config.max_ms)
+    }
+
+    fn queued_ms(&self, queued: usize) -> f64 {
+        queued as f64 * self.frame_ms()
+    }
+
+    fn grow(&mut self, step_ms: u32, now: Instant) {
+        self.set_target(self.target_ms.saturating_add(step_ms), now);
+    }
+
+    fn set_target(&mut self, target_ms: u32, now: Instant) {
+        let target_ms = target_ms.clamp(self.config.min_ms, self.config.max_ms);
+        if target_ms != self.target_ms {
+            debug!(
+                "Audio buffer target {}ms -> {}ms (jitter {:.1}ms)",
+                self.target_ms, target_ms, self.jitter_ms
+            );
+            self.target_ms = target_ms;
+        }
+        self.last_change = Some(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: AdaptiveBufferConfig = AdaptiveBufferConfig {
+        min_ms: 50,
+        max_ms: 300,
+        initial_ms: 100,
+    };
+
+    /// Feed packets sent 20ms apart, each delayed by a repeating pattern
+    ///
+    /// Packets arrive in order, like over TCP, so a delayed packet holds up
+    /// the ones behind it. Returns the last arrival time.
+    fn feed(
+        buffer: &mut AdaptiveBuffer,
+        start: Instant,
+        first: u64,
+        count: u64,
+        delays_ms: &[u64],
+    ) -> Instant {
+        let mut now = start;
+        for i in first..first + count {
+            let delay = delays_ms[(i % delays_ms.len() as u64) as usize];
+            now = now.max(start + Duration::from_millis((i - first) * 20 + delay));
+            buffer.on_packet(now, Some(i));
+        }
+        now
+    }
+
+    #[test]
+    fn test_target_adapts_within_bounds() {
+        let start = Instant::now();
+        let mut buffer = AdaptiveBuffer::new(CONFIG);
+        assert_eq!(buffer.target_ms(), 100);
+
+        // A steady link lets the target shrink to the minimum
+        let now = feed(&mut buffer, start, 0, 1500, &[0]);
+        assert_eq!(buffer.target_ms(), CONFIG.min_ms);
+        assert!((buffer.stats(0).frame_ms - 20.0).abs() < 0.5);
+
+        // Heavy jitter pushes it up, but never past the maximum
+        let now = feed(&mut buffer, now, 1500, 500, &[0, 90, 10, 120, 0, 60]);
+        let jittery = buffer.target_ms();
+        assert!(jittery > CONFIG.min_ms);
+        assert!(jittery <= CONFIG.max_ms);
+        assert!(buffer.stats(0).jitter_ms > 10.0);
+
+        // Once the link calms down, the target comes back down
+        feed(
+            &mut buffer,
+            now + Duration::from_millis(20),
+            2000,
+            3000,
+            &[0],
+        );
+        assert!(buffer.target_ms() < jittery);
+        assert!(buffer.target_ms() >= CONFIG.min_ms);
+    }
+
+    #[test]
+    fn test_extreme_jitter_is_capped() {
+        let start = Instant::now();
+        let mut buffer = AdaptiveBuffer::new(CONFIG);
+
+        feed(&mut buffer, start, 0, 200, &[0, 900, 0, 1500]);
+        assert_eq!(buffer.target_ms(), CONFIG.max_ms);
+    }
+
+    #[test]
+    fn test_packet_loss_grows_target() {
+        let start = Instant::now();
+        let mut buffer = AdaptiveBuffer::new(CONFIG);
+
+        buffer.on_packet(start, Some(0));
+        buffer.on_packet(start + Duration::from_millis(20), Some(1));
+        // Packets 2 and 3 never arrive
+        buffer.on_packet(start + Duration::from_millis(80), Some(4));
+
+        assert_eq!(buffer.stats(0).lost_packets, 2);
+        assert_eq!(buffer.target_ms(), 100 + GROW_STEP_MS);
+    }
+
+    #[test]
+    fn test_schedule_prebuffers_and_recovers_from_underrun() {
+        let start = Instant::now();
+        let mut buffer = AdaptiveBuffer::new(CONFIG);
+        feed(&mut buffer, start, 0, 3, &[0]);
+        let tick = Duration::from_millis(20);
+
+        // 100ms target with 20ms frames: wait for five packets
+        assert_eq!(buffer.schedule(4, tick, start), Schedule::default());
+        assert!(!buffer.stats(4).playing);
+        assert_eq!(buffer.schedule(5, tick, start).play, 1);
+        assert!(buffer.stats(4).playing);
+
+        // The queue runs dry
+        let schedule = buffer.schedule(0, tick, start);
+        assert_eq!(schedule.play, 0);
+        let stats = buffer.stats(0);
+        assert_eq!(stats.underruns, 1);
+        assert!(!stats.playing);
+        assert_eq!(stats.target_ms, 100 + GROW_STEP_MS);
+    }
+
+    #[test]
+    fn test_schedule_drops_excess_latency() {
+        let start = Instant::now();
+        let mut buffer = AdaptiveBuffer::new(CONFIG);
+        feed(&mut buffer, start, 0, 3, &[0]);
+        let tick = Duration::from_millis(20);
+
+        assert_eq!(
+            buffer.schedule(5, tick, start),
+            Schedule { play: 1, drop: 0 }
+        );
+        // Far more queued than the 100ms target
+        assert_eq!(
+            buffer.schedule(20, tick, start),
+            Schedule { play: 1, drop: 1 }
+        );
+    }
+}
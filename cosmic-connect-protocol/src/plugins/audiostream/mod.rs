@@ -35,7 +35,7 @@
 //! - **Quality Control**: Configurable bitrate and sample rate
 //! - **Low Latency Mode**: Minimize audio delay
 //! - **Multi-channel**: Stereo and mono support
-//! - **Buffer Management**: Smooth playback with network jitter
+//! - **Buffer Management**: Playback delay adapts to network jitter and loss
 //! - **Virtual Devices**: Create virtual audio sinks/sources
 //!
 //! ## Audio Backend
@@ -65,6 +65,8 @@ use tracing::{debug, error, info, warn};
 #[cfg(feature = "audiostream")]
 mod audio_backend;
 
+mod jitter;
+
 #[cfg(feature = "audiostream")]
 mod codec;
 
@@ -74,6 +76,8 @@ use audio_backend::{AudioBackend, AudioSample, BackendConfig};
 #[cfg(feature = "audiostream")]
 use codec::{AacCodec, OpusCodec, PcmCodec};
 
+pub use jitter::{AdaptiveBuffer, AdaptiveBufferConfig, BufferStats, Schedule};
+
 const PLUGIN_NAME: &str = "audiostream";
 const INCOMING_CAPABILITY: &str = "cconnect.audiostream";
 const OUTGOING_CAPABILITY: &str = "cconnect.audiostream";
@@ -152,8 +156,18 @@ pub struct StreamConfig {
     pub low_latency: bool,
 
     /// Buffer size in milliseconds
+    ///
+    /// Starting playback delay; it then adapts between the bounds below.
     #[serde(default = "default_buffer_size")]
     pub buffer_size_ms: u32,
+
+    /// Smallest playback delay in milliseconds
+    #[serde(default = "default_min_buffer")]
+    pub min_buffer_ms: u32,
+
+    /// Largest playback delay in milliseconds
+    #[serde(default = "default_max_buffer")]
+    pub max_buffer_ms: u32,
 }
 
 fn default_sample_rate() -> u32 {
@@ -176,6 +190,14 @@ fn default_buffer_size() -> u32 {
     }
 }
 
+fn default_min_buffer() -> u32 {
+    MIN_BUFFER_SIZE_MS
+}
+
+fn default_max_buffer() -> u32 {
+    MAX_BUFFER_SIZE_MS
+}
+
 impl Default for StreamConfig {
     fn default() -> Self {
         Self {
@@ -186,6 +208,8 @@ impl Default for StreamConfig {
             direction: StreamDirection::Output,
             low_latency: false,
             buffer_size_ms: default_buffer_size(),
+            min_buffer_ms: default_min_buffer(),
+            max_buffer_ms: default_max_buffer(),
         }
     }
 }
@@ -220,6 +244,22 @@ impl StreamConfig {
             )));
         }
 
+        // Validate adaptive buffer bounds
+        if self.min_buffer_ms < MIN_BUFFER_SIZE_MS
+            || self.max_buffer_ms > MAX_BUFFER_SIZE_MS
+            || self.min_buffer_ms > self.buffer_size_ms
+            || self.buffer_size_ms > self.max_buffer_ms
+        {
+            return Err(ProtocolError::InvalidPacket(format!(
+                "Invalid buffer bounds: {}ms to {}ms around {}ms. Must satisfy {}ms <= min <= buffer size <= max <= {}ms",
+                self.min_buffer_ms,
+                self.max_buffer_ms,
+                self.buffer_size_ms,
+                MIN_BUFFER_SIZE_MS,
+                MAX_BUFFER_SIZE_MS
+            )));
+        }
+
         // Validate bitrate for compressed codecs
         if matches!(self.codec, AudioCodec::Opus | AudioCodec::Aac)
             && (self.bitrate < 32000 || self.bitrate > 512000) {
@@ -231,6 +271,15 @@ impl StreamConfig {
 
         Ok(())
     }
+
+    /// Adaptive playback buffer settings
+    pub fn buffer_config(&self) -> AdaptiveBufferConfig {
+        AdaptiveBufferConfig {
+            min_ms: self.min_buffer_ms,
+            max_ms: self.max_buffer_ms,
+            initial_ms: self.buffer_size_ms,
+        }
+    }
 }
 
 /// Active audio stream state
@@ -252,6 +301,9 @@ struct AudioStream {
     /// Audio buffer (for playback)
     buffer: std::collections::VecDeque<Vec<u8>>,
 
+    /// Adaptive playback delay for `buffer`
+    jitter: AdaptiveBuffer,
+
     /// Volume level (0.0 to 1.0)
     volume: f32,
 
@@ -280,6 +332,7 @@ struct AudioStream {
 impl AudioStream {
     fn new(config: StreamConfig) -> Self {
         Self {
+            jitter: AdaptiveBuffer::new(config.buffer_config()),
             config,
             started_at: std::time::Instant::now(),
             bytes_streamed: 0,
@@ -581,6 +634,7 @@ impl AudioStreamPlugin {
             }
             StreamDirection::Input => {
                 if let Some(stream) = self.incoming_stream.write().await.as_mut() {
+                    stream.jitter.set_config(config.buffer_config());
                     stream.config = config;
                     info!("Updated incoming stream configuration");
                     // Decoder reconfiguration requires stopping and restarting the stream
@@ -652,6 +706,7 @@ impl AudioStreamPlugin {
                     };
 
                     // Update stats
+                    let sequence = stream.packet_count;
                     stream.update_stats(encoded.len() as u64);
 
                     drop(stream_lock);
@@ -664,6 +719,7 @@ impl AudioStreamPlugin {
                                 "data".to_string(),
                                 serde_json::Value::String(BASE64.encode(&encoded)),
                             );
+                            body.insert("sequence".to_string(), sequence.into());
 
                             let packet = Packet::new(
                                 "cconnect.audiostream.data",
@@ -692,13 +748,22 @@ impl AudioStreamPlugin {
         let incoming_stream = self.incoming_stream.clone();
 
         tokio::spawn(async move {
+            let mut last_tick = std::time::Instant::now();
             loop {
                 tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
 
                 let mut stream_lock = incoming_stream.write().await;
                 if let Some(stream) = stream_lock.as_mut() {
-                    // Process buffered packets
-                    while let Some(encoded_data) = stream.buffer.pop_front() {
+                    // Release buffered packets at playback pace
+                    let now = std::time::Instant::now();
+                    let elapsed = now - last_tick;
+                    last_tick = now;
+                    let schedule = stream.jitter.schedule(stream.buffer.len(), elapsed, now);
+
+                    for _ in 0..schedule.play {
+                        let Some(encoded_data) = stream.buffer.pop_front() else {
+                            break;
+                        };
                         // Decode based on codec
                         let samples = if let Some(opus) = &mut stream.opus_codec {
                             match opus.decode(&encoded_data) {
@@ -741,6 +806,10 @@ impl AudioStreamPlugin {
                             }
                         }
                     }
+
+                    // Trim latency left over after the target shrank
+                    let excess = schedule.drop.min(stream.buffer.len());
+                    stream.buffer.drain(..excess);
                 } else {
                     break;
                 }
@@ -752,13 +821,18 @@ impl AudioStreamPlugin {
     }
 
     /// Process audio data packet
-    async fn process_audio_data(&self, _data: &[u8]) -> Result<()> {
+    ///
+    /// `_sequence` is the sender's packet counter, used to detect loss.
+    async fn process_audio_data(&self, _data: &[u8], _sequence: Option<u64>) -> Result<()> {
         #[cfg(feature = "audiostream")]
         {
             let data = _data;
             let mut stream_lock = self.incoming_stream.write().await;
             if let Some(stream) = stream_lock.as_mut() {
                 stream.update_stats(data.len() as u64);
+                stream
+                    .jitter
+                    .on_packet(std::time::Instant::now(), _sequence);
 
                 // Add to buffer for processing by incoming task
                 stream.buffer.push_back(data.to_vec());
@@ -795,6 +869,15 @@ impl AudioStreamPlugin {
         }
     }
 
+    /// Get playback buffering statistics of the incoming stream
+    pub async fn buffer_stats(&self) -> Option<BufferStats> {
+        self.incoming_stream
+            .read()
+            .await
+            .as_ref()
+            .map(|s| s.jitter.stats(s.buffer.len()))
+    }
+
     /// Check if a codec is supported
     pub fn is_codec_supported(&self, codec: AudioCodec) -> bool {
         self.supported_codecs.contains(&codec)
@@ -1021,7 +1104,8 @@ impl Plugin for AudioStreamPlugin {
                 match BASE64.decode(payload_b64) {
                     Ok(audio_data) => {
                         debug!("Received audio data packet: {} bytes", audio_data.len());
-                        self.process_audio_data(&audio_data).await?;
+                        let sequence = packet.body.get("sequence").and_then(|v| v.as_u64());
+                        self.process_audio_data(&audio_data, sequence).await?;
                     }
                     Err(e) => {
                         warn!("Failed to decode base64 audio data: {}", e);
@@ -1137,6 +1221,33 @@ mod tests {
         assert!(invalid_buffer.validate().is_err());
     }
 
+    #[tokio::test]
+    async fn test_buffer_bounds_validation() {
+        let config = StreamConfig {
+            buffer_size_ms: 100,
+            min_buffer_ms: 60,
+            max_buffer_ms: 300,
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.buffer_config(),
+            AdaptiveBufferConfig {
+                min_ms: 60,
+                max_ms: 300,
+                initial_ms: 100,
+            }
+        );
+
+        let mut above_max = config.clone();
+        above_max.buffer_size_ms = 400;
+        assert!(above_max.validate().is_err());
+
+        let mut below_limit = config;
+        below_limit.min_buffer_ms = 10;
+        assert!(below_limit.validate().is_err());
+    }
+
     #[tokio::test]
     async fn test_start_stop_outgoing_stream() {
         let mut plugin = AudioStreamPlugin::new();
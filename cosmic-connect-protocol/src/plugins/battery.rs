@@ -13,6 +13,9 @@
 //! - Incoming: `cconnect.battery`, `cconnect.battery.request`
 //! - Outgoing: `cconnect.battery`, `cconnect.battery.request`
 //!
+//! A desktop without a battery (as reported by UPower) does not advertise the
+//! outgoing `cconnect.battery` capability.
+//!
 //! ## Packet Formats
 //!
 //! ### Battery Status (`cconnect.battery`)
@@ -53,6 +56,12 @@
 //! - **Idempotent**: Multiple status updates are safe
 //! - **No Battery**: Use -1 for currentCharge if device has no battery
 //!
+//! ## Display
+//!
+//! [`BatteryState`] maps a battery to the icon name and label shown by the
+//! applet and manager, so both render it the same way. A discharging battery
+//! below [`LOW_BATTERY_THRESHOLD`] gets the warning icon.
+//!
 //! ## Use Cases
 //!
 //! - Monitor remote device battery levels
@@ -92,15 +101,19 @@
 //!
 //! - [Valent Protocol - Battery](https://valent.andyholmes.ca/documentation/protocol.html)
 
-use crate::{Device, Packet, Result};
+use crate::{Device, DeviceInfo, Packet, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::sync::{Arc, RwLock};
 use tracing::{debug, info, warn};
 
+use super::upower_backend::UPowerBackend;
 use super::{Plugin, PluginFactory};
 
+/// Charge (percent) below which a discharging battery is shown as low
+pub const LOW_BATTERY_THRESHOLD: u8 = 15;
+
 /// Battery status information
 ///
 /// Represents the power state of a device.
@@ -209,6 +222,90 @@ impl BatteryStatus {
     }
 }
 
+/// Battery state as shown in the UI
+///
+/// ## Example
+///
+/// ```rust
+/// use cosmic_connect_core::plugins::battery::BatteryState;
+///
+/// let state = BatteryState::new(85, true);
+/// assert_eq!(state.icon_name(), "battery-full-charging-symbolic");
+/// assert_eq!(state.label(), "85%, charging");
+///
+/// let state = BatteryState::new(10, false);
+/// assert!(state.low);
+/// assert_eq!(state.icon_name(), "battery-caution-symbolic");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatteryState {
+    /// Battery percentage (0-100, or -1 for no battery)
+    pub charge: i32,
+    /// Whether the device is charging
+    pub is_charging: bool,
+    /// Whether the battery is low and discharging
+    pub low: bool,
+}
+
+impl BatteryState {
+    /// Battery state, low when discharging below [`LOW_BATTERY_THRESHOLD`]
+    pub fn new(charge: i32, is_charging: bool) -> Self {
+        Self {
+            charge,
+            is_charging,
+            low: (0..i32::from(LOW_BATTERY_THRESHOLD)).contains(&charge) && !is_charging,
+        }
+    }
+
+    /// Symbolic icon name for the battery
+    pub fn icon_name(&self) -> &'static str {
+        if self.charge < 0 {
+            return "battery-missing-symbolic";
+        }
+        if self.is_charging {
+            return match self.charge {
+                100.. => "battery-full-charged-symbolic",
+                80..=99 => "battery-full-charging-symbolic",
+                50..=79 => "battery-good-charging-symbolic",
+                20..=49 => "battery-low-charging-symbolic",
+                _ => "battery-caution-charging-symbolic",
+            };
+        }
+        if self.low {
+            return "battery-caution-symbolic";
+        }
+        match self.charge {
+            80.. => "battery-full-symbolic",
+            50..=79 => "battery-good-symbolic",
+            _ => "battery-low-symbolic",
+        }
+    }
+
+    /// Human readable charge, e.g. "85%, charging"
+    pub fn label(&self) -> String {
+        if self.charge < 0 {
+            "No battery".to_string()
+        } else if self.is_charging && self.charge >= 100 {
+            format!("{}%, charged", self.charge)
+        } else if self.is_charging {
+            format!("{}%, charging", self.charge)
+        } else if self.low {
+            format!("{}%, low", self.charge)
+        } else {
+            format!("{}%", self.charge)
+        }
+    }
+}
+
+/// Low when below our threshold, or when the device reports being below its own
+impl From<&BatteryStatus> for BatteryState {
+    fn from(status: &BatteryStatus) -> Self {
+        let mut state = Self::new(status.current_charge, status.is_charging);
+        state.low |= status.is_low_battery() && status.has_battery() && !status.is_charging;
+        state
+    }
+}
+
 /// Battery plugin for power status monitoring
 ///
 /// Handles battery status updates from remote devices and can send local battery status.
@@ -378,6 +475,16 @@ impl BatteryPlugin {
         }
     }
 
+    /// Stop advertising battery reports from this device
+    ///
+    /// Peers keep receiving our requests for their battery, but no longer
+    /// expect a status from a desktop that has no battery to report.
+    fn drop_battery_reports(identity: &mut DeviceInfo) {
+        identity
+            .outgoing_capabilities
+            .retain(|c| c != "cconnect.battery");
+    }
+
     /// Handle incoming battery request packet
     fn handle_battery_request(&self, _packet: &Packet, device: &Device) {
         info!(
@@ -427,6 +534,18 @@ impl Plugin for BatteryPlugin {
         ]
     }
 
+    async fn augment_identity(&self, identity: &mut DeviceInfo) {
+        // Without a system bus we cannot tell, so keep advertising
+        match UPowerBackend::new().get_power_status().await {
+            Ok(status) if !status.battery_present => {
+                debug!("No local battery, not advertising battery reports");
+                Self::drop_battery_reports(identity);
+            }
+            Ok(_) => {}
+            Err(e) => debug!("Could not check for a local battery: {}", e),
+        }
+    }
+
     async fn init(
         &mut self,
         device: &Device,
@@ -548,6 +667,23 @@ mod tests {
         assert!(outgoing.contains(&"cconnect.battery.request".to_string()));
     }
 
+    #[test]
+    fn test_drop_battery_reports() {
+        let plugin = BatteryPlugin::new();
+        let mut identity = DeviceInfo::new("Test Desktop", DeviceType::Desktop, 1716)
+            .with_incoming_capabilities(plugin.incoming_capabilities())
+            .with_outgoing_capabilities(plugin.outgoing_capabilities());
+
+        BatteryPlugin::drop_battery_reports(&mut identity);
+
+        // Still asks peers for their battery and accepts their reports
+        assert_eq!(
+            identity.outgoing_capabilities,
+            vec!["cconnect.battery.request"]
+        );
+        assert_eq!(identity.incoming_capabilities.len(), 4);
+    }
+
     #[tokio::test]
     async fn test_plugin_lifecycle() {
         let mut plugin = BatteryPlugin::new();
@@ -712,4 +848,47 @@ mod tests {
         assert!(!status.is_charging);
         assert!(status.is_low_battery());
     }
+
+    #[test]
+    fn test_battery_state_icon_and_label() {
+        let charging = BatteryState::new(85, true);
+        assert!(!charging.low);
+        assert_eq!(charging.icon_name(), "battery-full-charging-symbolic");
+        assert_eq!(charging.label(), "85%, charging");
+
+        // Charging never shows the warning, even when nearly empty
+        let charging_low = BatteryState::new(5, true);
+        assert!(!charging_low.low);
+        assert_eq!(
+            charging_low.icon_name(),
+            "battery-caution-charging-symbolic"
+        );
+
+        let low = BatteryState::new(10, false);
+        assert!(low.low);
+        assert_eq!(low.icon_name(), "battery-caution-symbolic");
+        assert_eq!(low.label(), "10%, low");
+
+        let at_threshold = BatteryState::new(LOW_BATTERY_THRESHOLD.into(), false);
+        assert!(!at_threshold.low);
+        assert_eq!(at_threshold.icon_name(), "battery-low-symbolic");
+
+        let full = BatteryState::new(100, false);
+        assert_eq!(full.icon_name(), "battery-full-symbolic");
+        assert_eq!(full.label(), "100%");
+
+        let charged = BatteryState::new(100, true);
+        assert_eq!(charged.icon_name(), "battery-full-charged-symbolic");
+        assert_eq!(charged.label(), "100%, charged");
+
+        let none = BatteryState::from(&BatteryStatus::no_battery());
+        assert!(!none.low);
+        assert_eq!(none.icon_name(), "battery-missing-symbolic");
+        assert_eq!(none.label(), "No battery");
+
+        // The device's own threshold event also counts as low
+        let reported = BatteryState::from(&BatteryStatus::new(30, false, 1));
+        assert!(reported.low);
+        assert_eq!(reported.icon_name(), "battery-caution-symbolic");
+    }
 }
@@ -51,6 +51,15 @@
 //! 4. Incoming updates with timestamp > local timestamp are **accepted**
 //! 5. Connect packets with timestamp `0` are ignored (no content)
 //!
+//! ### Clock Skew
+//!
+//! Timestamps come from the clock of the device that copied the content. A
+//! device whose clock runs far ahead would win every merge. A connect
+//! timestamp more than [`MAX_CLOCK_SKEW_MS`] ahead of our clock is not
+//! trusted: the content is applied as the most recently received one and
+//! stamped with our clock instead. A timestamp far in the past is compared
+//! as usual and loses to newer content; old content stays old.
+//!
 //! ## System Clipboard Access
 //!
 //! The plugin uses system commands for clipboard access:
@@ -76,6 +85,13 @@
 //! 2. Send `cconnect.clipboard.connect` with current content and timestamp
 //! 3. Peer follows standard receiving workflow
 //!
+//! ### Manual Push
+//!
+//! [`ClipboardPlugin::push_clipboard`] sends the current system clipboard on
+//! demand, e.g. from a keyboard shortcut, whether or not automatic sync is
+//! on. After sending, it reports [`ClipboardEvent::ClipboardPushed`] to the
+//! daemon as `cconnect.internal.clipboard.pushed`.
+//!
 //! ## Example
 //!
 //! ```rust,ignore
@@ -105,7 +121,7 @@
 //! - [Valent Protocol Documentation](https://valent.andyholmes.ca/documentation/protocol.html)
 //! - [CConnect Clipboard Plugin](https://invent.kde.org/network/cconnect-kde/tree/master/plugins/clipboard)
 
-use crate::{Device, Packet, Result};
+use crate::{Device, Packet, ProtocolError, Result};
 use async_trait::async_trait;
 use chrono::Utc;
 use serde_json::json;
@@ -114,9 +130,56 @@ use tokio::sync::mpsc::Sender;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
-use super::clipboard_backend::ClipboardBackend;
+use super::clipboard_backend::{ClipboardAccess, ClipboardBackend};
 use super::{Plugin, PluginFactory};
 
+/// How far ahead of our clock a remote timestamp may be
+pub const MAX_CLOCK_SKEW_MS: i64 = 60 * 60 * 1000;
+
+/// Whether a remote timestamp can be compared with ours
+///
+/// Only timestamps from the future are distrusted. `now` is our clock, in
+/// UNIX epoch milliseconds.
+fn is_plausible_timestamp(timestamp: i64, now: i64) -> bool {
+    timestamp <= now.saturating_add(MAX_CLOCK_SKEW_MS)
+}
+
+/// Internal packet type reporting a manual clipboard push
+pub const PACKET_TYPE_CLIPBOARD_PUSHED: &str = "cconnect.internal.clipboard.pushed";
+
+/// Clipboard event reported to the daemon
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClipboardEvent {
+    /// The local clipboard was pushed to the device on request
+    ClipboardPushed {
+        /// Length of the pushed text in characters
+        chars: usize,
+    },
+}
+
+impl ClipboardEvent {
+    /// Build the internal packet carrying this event
+    pub fn to_packet(&self) -> Packet {
+        match self {
+            Self::ClipboardPushed { chars } => {
+                Packet::new(PACKET_TYPE_CLIPBOARD_PUSHED, json!({ "chars": chars }))
+            }
+        }
+    }
+
+    /// Parse an internal clipboard packet
+    ///
+    /// Returns `None` for any other packet.
+    pub fn from_packet(packet: &Packet) -> Option<Self> {
+        match packet.packet_type.as_str() {
+            PACKET_TYPE_CLIPBOARD_PUSHED => Some(Self::ClipboardPushed {
+                chars: packet.body.get("chars")?.as_u64()? as usize,
+            }),
+            _ => None,
+        }
+    }
+}
+
 /// Clipboard state with content and timestamp
 ///
 /// Tracks the current clipboard content and when it was last modified.
@@ -270,7 +333,7 @@ pub struct ClipboardPlugin {
     state: Arc<RwLock<ClipboardState>>,
 
     /// System clipboard backend
-    backend: ClipboardBackend,
+    backend: Box<dyn ClipboardAccess>,
 
     /// Packet sender for proactive updates
     packet_sender: Option<Sender<(String, Packet)>>,
@@ -289,11 +352,16 @@ impl ClipboardPlugin {
     /// let plugin = ClipboardPlugin::new();
     /// ```
     pub fn new() -> Self {
+        Self::with_backend(Box::new(ClipboardBackend::new()))
+    }
+
+    /// Create a clipboard plugin using a custom clipboard
+    pub fn with_backend(backend: Box<dyn ClipboardAccess>) -> Self {
         Self {
             device_id: None,
             enabled: false,
             state: Arc::new(RwLock::new(ClipboardState::empty())),
-            backend: ClipboardBackend::new(),
+            backend,
             packet_sender: None,
         }
     }
@@ -524,6 +592,30 @@ impl ClipboardPlugin {
 
         let current_state = self.state.read().await.clone();
 
+        // A skewed clock can't take part in the comparison, the content
+        // received last wins instead
+        let now = Utc::now().timestamp_millis();
+        if !is_plausible_timestamp(timestamp, now) {
+            warn!(
+                "Clock skew detected for {} ({}): timestamp {} vs local clock {}, \
+                 applying clipboard as most recently received",
+                device.name(),
+                device.id(),
+                timestamp,
+                now
+            );
+
+            self.set_content(content.to_string()).await;
+            if !self.backend.write(content).await {
+                warn!(
+                    "Failed to write clipboard content from {} ({}) to system clipboard",
+                    device.name(),
+                    device.id()
+                );
+            }
+            return;
+        }
+
         // Only apply if incoming timestamp is newer
         if timestamp > current_state.timestamp {
             info!(
@@ -609,6 +701,47 @@ impl ClipboardPlugin {
         info!("Sent local clipboard to device");
         true
     }
+
+    /// Push the current system clipboard to the device on demand
+    ///
+    /// Unlike [`send_local_clipboard`](Self::send_local_clipboard), the
+    /// content is sent even if it was sent before, and independently of
+    /// automatic sync. Reports [`ClipboardEvent::ClipboardPushed`] afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the plugin is not initialized, the clipboard is
+    /// empty or unreadable, or the packet cannot be queued.
+    pub async fn push_clipboard(&self) -> Result<()> {
+        let (Some(device_id), Some(packet_sender)) = (&self.device_id, &self.packet_sender) else {
+            return Err(ProtocolError::InvalidState(
+                "Clipboard plugin not initialized".to_string(),
+            ));
+        };
+
+        let content = match self.backend.read().await {
+            Some(content) if !content.is_empty() => content,
+            _ => {
+                return Err(ProtocolError::Plugin(
+                    "Clipboard is empty or unreadable".to_string(),
+                ))
+            }
+        };
+        let event = ClipboardEvent::ClipboardPushed {
+            chars: content.chars().count(),
+        };
+
+        let packet = self.create_clipboard_packet(content).await;
+        for packet in [packet, event.to_packet()] {
+            packet_sender
+                .send((device_id.clone(), packet))
+                .await
+                .map_err(|e| ProtocolError::Transport(format!("Failed to send packet: {}", e)))?;
+        }
+
+        info!("Pushed local clipboard to device {}", device_id);
+        Ok(())
+    }
 }
 
 impl Default for ClipboardPlugin {
@@ -1099,4 +1232,133 @@ mod tests {
         assert_eq!(state.content, "Current");
         assert_eq!(state.timestamp, 2000);
     }
+
+    #[tokio::test]
+    async fn test_far_future_timestamp_falls_back_to_last_received() {
+        let mut plugin = ClipboardPlugin::with_backend(Box::new(MockClipboard::default()));
+        let device = create_test_device();
+        plugin
+            .init(&device, tokio::sync::mpsc::channel(100).0)
+            .await
+            .unwrap();
+        plugin.start().await.unwrap();
+        plugin.set_content("Desktop content".to_string()).await;
+
+        // Phone clock ten years ahead
+        let now = Utc::now().timestamp_millis();
+        let skewed = now + 10 * 365 * 24 * 60 * 60 * 1000;
+        let mut device = create_test_device();
+        let packet = Packet::new(
+            "cconnect.clipboard.connect",
+            json!({ "content": "Phone content", "timestamp": skewed }),
+        );
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        // Applied as received now, not with the skewed timestamp
+        let state = plugin.get_state().await;
+        assert_eq!(state.content, "Phone content");
+        assert!(state.timestamp >= now && state.timestamp < skewed);
+        assert_eq!(
+            plugin.backend.read().await.as_deref(),
+            Some("Phone content")
+        );
+
+        // Later content still wins over it
+        plugin
+            .handle_packet(
+                &Packet::new(
+                    "cconnect.clipboard.connect",
+                    json!({ "content": "Later content", "timestamp": state.timestamp + 1 }),
+                ),
+                &mut device,
+            )
+            .await
+            .unwrap();
+        assert_eq!(plugin.get_content().await, "Later content");
+    }
+
+    #[test]
+    fn test_plausible_timestamps() {
+        let now = 1_700_000_000_000;
+        assert!(is_plausible_timestamp(now, now));
+        assert!(is_plausible_timestamp(now + MAX_CLOCK_SKEW_MS, now));
+        assert!(!is_plausible_timestamp(now + MAX_CLOCK_SKEW_MS + 1, now));
+        assert!(is_plausible_timestamp(1, now));
+    }
+
+    #[tokio::test]
+    async fn test_far_past_timestamp_loses_merge() {
+        let mut plugin = ClipboardPlugin::with_backend(Box::new(MockClipboard::default()));
+        let device = create_test_device();
+        plugin
+            .init(&device, tokio::sync::mpsc::channel(100).0)
+            .await
+            .unwrap();
+        plugin.start().await.unwrap();
+        plugin.set_content("Desktop content".to_string()).await;
+        let local = plugin.get_state().await;
+
+        // Content copied on the phone a year ago, on reconnect
+        let stale = local.timestamp - 365 * 24 * 60 * 60 * 1000;
+        let mut device = create_test_device();
+        let packet = Packet::new(
+            "cconnect.clipboard.connect",
+            json!({ "content": "Old phone content", "timestamp": stale }),
+        );
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        assert_eq!(plugin.get_state().await, local);
+    }
+
+    #[derive(Default)]
+    struct MockClipboard(std::sync::Mutex<String>);
+
+    #[async_trait]
+    impl ClipboardAccess for MockClipboard {
+        async fn read(&self) -> Option<String> {
+            Some(self.0.lock().unwrap().clone())
+        }
+
+        async fn write(&self, content: &str) -> bool {
+            *self.0.lock().unwrap() = content.to_string();
+            true
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_push_sends_current_clipboard() {
+        let clipboard = MockClipboard::default();
+        clipboard.write("Copied on the desktop").await;
+        let mut plugin = ClipboardPlugin::with_backend(Box::new(clipboard));
+        let device = create_test_device();
+
+        // Not initialized yet
+        assert!(plugin.push_clipboard().await.is_err());
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        plugin.init(&device, tx).await.unwrap();
+
+        // Pushing twice sends the same content twice
+        for _ in 0..2 {
+            plugin.push_clipboard().await.unwrap();
+
+            let (device_id, packet) = rx.recv().await.unwrap();
+            assert_eq!(device_id, device.id());
+            assert!(packet.is_type("cconnect.clipboard"));
+            assert_eq!(
+                packet.get_body_field::<String>("content").as_deref(),
+                Some("Copied on the desktop")
+            );
+
+            let (_, event) = rx.recv().await.unwrap();
+            assert_eq!(
+                ClipboardEvent::from_packet(&event),
+                Some(ClipboardEvent::ClipboardPushed { chars: 21 })
+            );
+        }
+    }
 }
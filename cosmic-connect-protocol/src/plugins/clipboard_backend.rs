@@ -2,6 +2,8 @@
 //!
 //! Provides system clipboard access via Wayland (wl-copy/wl-paste) or X11 (xclip).
 //! Automatically detects the session type and uses the appropriate backend.
+//! The clipboard plugin reaches it through the [`ClipboardAccess`] trait, so
+//! tests can substitute their own clipboard.
 //!
 //! ## Session Detection
 //!
@@ -31,6 +33,7 @@
 //! backend.write("Hello, World!").await;
 //! ```
 
+use async_trait::async_trait;
 use std::env;
 use std::process::Stdio;
 use tokio::io::AsyncWriteExt;
@@ -65,6 +68,19 @@ impl SessionType {
     }
 }
 
+/// Clipboard access used by the clipboard plugin
+#[async_trait]
+pub trait ClipboardAccess: Send + Sync {
+    /// Read text from the clipboard, `None` if empty or unreadable
+    async fn read(&self) -> Option<String>;
+
+    /// Write text to the clipboard, returning `true` on success
+    async fn write(&self, content: &str) -> bool;
+
+    /// Check if the clipboard can be accessed
+    async fn is_available(&self) -> bool;
+}
+
 /// System clipboard backend
 ///
 /// Provides read/write access to the system clipboard using
@@ -274,6 +290,21 @@ impl Default for ClipboardBackend {
     }
 }
 
+#[async_trait]
+impl ClipboardAccess for ClipboardBackend {
+    async fn read(&self) -> Option<String> {
+        ClipboardBackend::read(self).await
+    }
+
+    async fn write(&self, content: &str) -> bool {
+        ClipboardBackend::write(self, content).await
+    }
+
+    async fn is_available(&self) -> bool {
+        ClipboardBackend::is_available(self).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -21,12 +21,17 @@
 //!
 //! ## Sound Playback
 //!
-//! Tries multiple methods in order:
-//! 1. `paplay` (PulseAudio/PipeWire) with system sounds
+//! Playback is delegated to a [`Ringer`]. The default [`SystemRinger`] tries
+//! multiple methods in order:
+//! 1. `paplay` (PulseAudio/PipeWire) with the configured sound
 //! 2. `canberra-gtk-play` (freedesktop sound theme)
 //! 3. `pw-play` (PipeWire native)
 //! 4. Desktop notification as fallback
 //!
+//! The sound file and an optional volume override are configured through
+//! [`RingConfig`]. When a volume override is set, the default sink volume is
+//! temporarily raised (and unmuted) while ringing and restored afterwards.
+//!
 //! ## References
 //!
 //! - [KDE Connect FindMyPhone](https://github.com/KDE/kdeconnect-android)
@@ -36,11 +41,13 @@ use crate::{Device, Packet, Result};
 use async_trait::async_trait;
 use serde_json::json;
 use std::any::Any;
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tracing::{debug, error, info, warn};
 
+use super::audio_backend::AudioBackend;
 use super::{Plugin, PluginFactory};
 
 /// Packet type for find my phone requests
@@ -58,6 +65,260 @@ const SYSTEM_SOUNDS: &[&str] = &[
     "/usr/share/sounds/Yaru/stereo/phone-incoming-call.oga",
 ];
 
+/// Default ring sound (freedesktop sound theme, shipped by most distributions)
+pub const DEFAULT_RING_SOUND: &str = "/usr/share/sounds/freedesktop/stereo/phone-incoming-call.oga";
+
+/// Audio file extensions the supported players can decode
+const PLAYABLE_EXTENSIONS: &[&str] = &["oga", "ogg", "wav", "flac", "mp3", "opus"];
+
+/// Ring sound configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RingConfig {
+    /// Sound file played while ringing
+    pub sound: PathBuf,
+
+    /// Volume (0-150) to force on the default sink while ringing
+    ///
+    /// `None` keeps the current system volume.
+    pub volume_override: Option<u8>,
+}
+
+impl Default for RingConfig {
+    fn default() -> Self {
+        Self {
+            sound: PathBuf::from(DEFAULT_RING_SOUND),
+            volume_override: None,
+        }
+    }
+}
+
+/// Check that a sound file exists and has a format the players can decode
+///
+/// Returns a human-readable reason when the file is not usable.
+pub fn validate_ring_sound(path: &Path) -> std::result::Result<(), String> {
+    if !path.is_file() {
+        return Err(format!("{} does not exist", path.display()));
+    }
+
+    let playable = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| PLAYABLE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false);
+
+    if !playable {
+        return Err(format!(
+            "{} is not a supported audio file ({})",
+            path.display(),
+            PLAYABLE_EXTENSIONS.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+/// Sound playback used when the desktop is asked to ring
+pub trait Ringer: Send + Sync {
+    /// Start ringing with the given configuration
+    ///
+    /// Returns true if an audible ring was started.
+    fn start(&mut self, config: &RingConfig) -> bool;
+
+    /// Stop ringing and restore any changed system state
+    fn stop(&mut self);
+}
+
+/// Ringer that plays sounds through the system audio players
+#[derive(Default)]
+pub struct SystemRinger {
+    /// Current sound process (if playing)
+    sound_process: Option<Child>,
+
+    /// Default sink state (id, volume, muted) saved before a volume override
+    saved_volume: Option<(u32, i32, bool)>,
+}
+
+impl SystemRinger {
+    /// Create a new system ringer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolve the sound to play, falling back to known system sounds
+    fn resolve_sound(config: &RingConfig) -> Option<PathBuf> {
+        if validate_ring_sound(&config.sound).is_ok() {
+            return Some(config.sound.clone());
+        }
+
+        warn!(
+            "Configured ring sound {} is unavailable, searching system sounds",
+            config.sound.display()
+        );
+        Self::find_sound_file().map(PathBuf::from)
+    }
+
+    /// Find an available system sound file
+    fn find_sound_file() -> Option<&'static str> {
+        SYSTEM_SOUNDS
+            .iter()
+            .copied()
+            .find(|path| Path::new(path).exists())
+    }
+
+    /// Raise the default sink to the override volume, remembering the old state
+    fn boost_volume(&mut self, volume: u8) {
+        let Some(sink) = AudioBackend::list_sinks()
+            .into_iter()
+            .find(|sink| sink.is_default)
+        else {
+            debug!("No default sink found, skipping ring volume override");
+            return;
+        };
+
+        // Saved first so a partly applied override is undone as well
+        self.saved_volume = Some((sink.id, sink.volume, sink.muted));
+
+        let result = AudioBackend::set_volume(sink.id, i32::from(volume)).and_then(|()| {
+            if sink.muted {
+                AudioBackend::set_mute(sink.id, false)
+            } else {
+                Ok(())
+            }
+        });
+        match result {
+            Ok(()) => debug!(
+                "Ring volume override: sink {} {}% -> {}%",
+                sink.id, sink.volume, volume
+            ),
+            Err(e) => {
+                warn!(
+                    "Failed to apply ring volume override on sink {}: {}",
+                    sink.id, e
+                );
+                self.restore_volume();
+            }
+        }
+    }
+
+    /// Restore the sink state saved by `boost_volume`
+    fn restore_volume(&mut self) {
+        if let Some((id, volume, muted)) = self.saved_volume.take() {
+            if let Err(e) = AudioBackend::set_volume(id, volume) {
+                warn!("Failed to restore volume on sink {}: {}", id, e);
+            }
+            if muted {
+                if let Err(e) = AudioBackend::set_mute(id, true) {
+                    warn!("Failed to restore mute on sink {}: {}", id, e);
+                }
+            }
+        }
+    }
+
+    /// Play sound using paplay (PulseAudio/PipeWire)
+    fn play_with_paplay(sound_path: &Path) -> Option<Child> {
+        Command::new("paplay")
+            .arg("--loop")
+            .arg(sound_path)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()
+    }
+
+    /// Play sound using canberra-gtk-play
+    fn play_with_canberra(sound_path: &Path) -> Option<Child> {
+        Command::new("canberra-gtk-play")
+            .arg("-f")
+            .arg(sound_path)
+            .arg("-l")
+            .arg("10") // Loop 10 times
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()
+    }
+
+    /// Play sound using pw-play (PipeWire)
+    fn play_with_pwplay(sound_path: &Path) -> Option<Child> {
+        Command::new("pw-play")
+            .arg(sound_path)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()
+    }
+
+    /// Play sound event using canberra (uses system theme)
+    fn play_sound_event() -> Option<Child> {
+        Command::new("canberra-gtk-play")
+            .arg("-i")
+            .arg("phone-incoming-call")
+            .arg("-l")
+            .arg("10")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()
+    }
+
+    /// Send desktop notification as fallback
+    fn send_notification() {
+        if let Err(e) = Command::new("notify-send")
+            .arg("--urgency=critical")
+            .arg("--icon=phone")
+            .arg("Find My Device")
+            .arg("Your device is being located!")
+            .spawn()
+        {
+            error!("Failed to send notification: {}", e);
+        }
+    }
+}
+
+impl Ringer for SystemRinger {
+    fn start(&mut self, config: &RingConfig) -> bool {
+        if let Some(volume) = config.volume_override {
+            self.boost_volume(volume);
+        }
+
+        // Try sound players in order of preference
+        let player_attempts: Vec<(&str, Option<Child>)> =
+            if let Some(sound_path) = Self::resolve_sound(config) {
+                vec![
+                    ("paplay", Self::play_with_paplay(&sound_path)),
+                    ("canberra-gtk-play", Self::play_with_canberra(&sound_path)),
+                    ("pw-play", Self::play_with_pwplay(&sound_path)),
+                    ("sound event", Self::play_sound_event()),
+                ]
+            } else {
+                vec![("sound event", Self::play_sound_event())]
+            };
+
+        for (player_name, child_option) in player_attempts {
+            if let Some(child) = child_option {
+                self.sound_process = Some(child);
+                info!("Ring started using {}", player_name);
+                return true;
+            }
+        }
+
+        // Last resort: send notification
+        Self::send_notification();
+        warn!("No sound player available, using notification fallback");
+        false
+    }
+
+    fn stop(&mut self) {
+        if let Some(mut child) = self.sound_process.take() {
+            if let Err(e) = child.kill() {
+                debug!("Failed to kill sound process: {}", e);
+            }
+            let _ = child.wait();
+        }
+        self.restore_volume();
+    }
+}
+
 /// Find My Phone plugin for locating devices
 pub struct FindMyPhonePlugin {
     /// Device ID this plugin is attached to
@@ -69,21 +330,40 @@ pub struct FindMyPhonePlugin {
     /// Whether currently ringing
     is_ringing: Arc<AtomicBool>,
 
-    /// Current sound process (if playing)
-    sound_process: Option<Child>,
+    /// Ring sound configuration
+    config: RingConfig,
+
+    /// Sound playback backend
+    ringer: Box<dyn Ringer>,
 }
 
 impl FindMyPhonePlugin {
     /// Create a new Find My Phone plugin
     pub fn new() -> Self {
+        Self::with_config(RingConfig::default())
+    }
+
+    /// Create with a custom ring configuration
+    pub fn with_config(config: RingConfig) -> Self {
+        Self::with_ringer(config, Box::new(SystemRinger::new()))
+    }
+
+    /// Create with a custom ring configuration and playback backend
+    pub fn with_ringer(config: RingConfig, ringer: Box<dyn Ringer>) -> Self {
         Self {
             device_id: None,
             enabled: false,
             is_ringing: Arc::new(AtomicBool::new(false)),
-            sound_process: None,
+            config,
+            ringer,
         }
     }
 
+    /// Get the ring configuration
+    pub fn ring_config(&self) -> &RingConfig {
+        &self.config
+    }
+
     /// Check if the device is currently ringing
     ///
     /// Returns true if a ring sound is currently playing.
@@ -157,114 +437,17 @@ impl FindMyPhonePlugin {
 
     /// Start playing the ring sound
     fn start_ringing(&mut self) {
-        // Try sound players in order of preference
-        let player_attempts: Vec<(&str, Option<Child>)> =
-            if let Some(sound_path) = Self::find_sound_file() {
-                vec![
-                    ("paplay", Self::play_with_paplay(sound_path)),
-                    ("canberra-gtk-play", Self::play_with_canberra(sound_path)),
-                    ("pw-play", Self::play_with_pwplay(sound_path)),
-                    ("sound event", Self::play_sound_event()),
-                ]
-            } else {
-                vec![("sound event", Self::play_sound_event())]
-            };
-
-        for (player_name, child_option) in player_attempts {
-            if let Some(child) = child_option {
-                self.sound_process = Some(child);
-                self.is_ringing.store(true, Ordering::SeqCst);
-                info!("Ring started using {}", player_name);
-                return;
-            }
-        }
-
-        // Last resort: send notification
-        Self::send_notification();
+        self.ringer.start(&self.config);
         self.is_ringing.store(true, Ordering::SeqCst);
-        warn!("No sound player available, using notification fallback");
     }
 
     /// Stop the ring sound
     fn stop_ringing(&mut self) {
-        if let Some(mut child) = self.sound_process.take() {
-            if let Err(e) = child.kill() {
-                debug!("Failed to kill sound process: {}", e);
-            }
-            let _ = child.wait();
-        }
+        self.ringer.stop();
         self.is_ringing.store(false, Ordering::SeqCst);
         info!("Ring stopped");
     }
 
-    /// Find an available system sound file
-    fn find_sound_file() -> Option<&'static str> {
-        SYSTEM_SOUNDS
-            .iter()
-            .copied()
-            .find(|path| std::path::Path::new(path).exists())
-    }
-
-    /// Play sound using paplay (PulseAudio/PipeWire)
-    fn play_with_paplay(sound_path: &str) -> Option<Child> {
-        Command::new("paplay")
-            .arg("--loop")
-            .arg(sound_path)
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .spawn()
-            .ok()
-    }
-
-    /// Play sound using canberra-gtk-play
-    fn play_with_canberra(sound_path: &str) -> Option<Child> {
-        Command::new("canberra-gtk-play")
-            .arg("-f")
-            .arg(sound_path)
-            .arg("-l")
-            .arg("10") // Loop 10 times
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .spawn()
-            .ok()
-    }
-
-    /// Play sound using pw-play (PipeWire)
-    fn play_with_pwplay(sound_path: &str) -> Option<Child> {
-        Command::new("pw-play")
-            .arg(sound_path)
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .spawn()
-            .ok()
-    }
-
-    /// Play sound event using canberra (uses system theme)
-    fn play_sound_event() -> Option<Child> {
-        Command::new("canberra-gtk-play")
-            .arg("-i")
-            .arg("phone-incoming-call")
-            .arg("-l")
-            .arg("10")
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .spawn()
-            .ok()
-    }
-
-    /// Send desktop notification as fallback
-    fn send_notification() {
-        if let Err(e) = Command::new("notify-send")
-            .arg("--urgency=critical")
-            .arg("--icon=phone")
-            .arg("Find My Device")
-            .arg("Your device is being located!")
-            .spawn()
-        {
-            error!("Failed to send notification: {}", e);
-        }
-    }
-
     /// Check if a ring request packet
     fn is_ring_request(packet: &Packet) -> bool {
         packet.is_type(PACKET_TYPE_FINDMYPHONE_REQUEST)
@@ -351,8 +534,18 @@ impl Plugin for FindMyPhonePlugin {
 }
 
 /// Factory for creating Find My Phone plugin instances
-#[derive(Debug, Clone, Copy)]
-pub struct FindMyPhonePluginFactory;
+#[derive(Debug, Clone, Default)]
+pub struct FindMyPhonePluginFactory {
+    /// Ring configuration passed to each plugin instance
+    config: RingConfig,
+}
+
+impl FindMyPhonePluginFactory {
+    /// Create a factory whose plugins ring with the given configuration
+    pub fn with_config(config: RingConfig) -> Self {
+        Self { config }
+    }
+}
 
 impl PluginFactory for FindMyPhonePluginFactory {
     fn name(&self) -> &str {
@@ -371,7 +564,7 @@ impl PluginFactory for FindMyPhonePluginFactory {
     }
 
     fn create(&self) -> Box<dyn Plugin> {
-        Box::new(FindMyPhonePlugin::new())
+        Box::new(FindMyPhonePlugin::with_config(self.config.clone()))
     }
 }
 
@@ -379,12 +572,31 @@ impl PluginFactory for FindMyPhonePluginFactory {
 mod tests {
     use super::*;
     use crate::{DeviceInfo, DeviceType};
+    use std::sync::Mutex;
 
     fn create_test_device() -> Device {
         let info = DeviceInfo::new("Test Device", DeviceType::Desktop, 1716);
         Device::from_discovery(info)
     }
 
+    /// Ringer that records the configurations it was started with
+    #[derive(Default, Clone)]
+    struct RecordingRinger {
+        started: Arc<Mutex<Vec<RingConfig>>>,
+        stops: Arc<Mutex<usize>>,
+    }
+
+    impl Ringer for RecordingRinger {
+        fn start(&mut self, config: &RingConfig) -> bool {
+            self.started.lock().unwrap().push(config.clone());
+            true
+        }
+
+        fn stop(&mut self) {
+            *self.stops.lock().unwrap() += 1;
+        }
+    }
+
     #[tokio::test]
     async fn test_plugin_creation() {
         let plugin = FindMyPhonePlugin::new();
@@ -436,7 +648,7 @@ mod tests {
 
     #[test]
     fn test_factory() {
-        let factory = FindMyPhonePluginFactory;
+        let factory = FindMyPhonePluginFactory::default();
         assert_eq!(factory.name(), "findmyphone");
 
         let outgoing = factory.outgoing_capabilities();
@@ -479,7 +691,7 @@ mod tests {
     fn test_find_sound_file() {
         // This test just verifies the function doesn't panic
         // Actual result depends on system sound files
-        let _result = FindMyPhonePlugin::find_sound_file();
+        let _result = SystemRinger::find_sound_file();
     }
 
     #[tokio::test]
@@ -540,4 +752,61 @@ mod tests {
         assert!(state1.load(Ordering::SeqCst));
         assert!(state2.load(Ordering::SeqCst));
     }
+
+    #[tokio::test]
+    async fn test_configured_sound_passed_to_ringer() {
+        let ringer = RecordingRinger::default();
+        let config = RingConfig {
+            sound: PathBuf::from("/tmp/custom-ring.ogg"),
+            volume_override: Some(120),
+        };
+        let mut plugin = FindMyPhonePlugin::with_ringer(config.clone(), Box::new(ringer.clone()));
+        let mut device = create_test_device();
+
+        plugin
+            .init(&device, tokio::sync::mpsc::channel(100).0)
+            .await
+            .unwrap();
+        plugin.start().await.unwrap();
+
+        let packet = Packet::new(PACKET_TYPE_KDECONNECT_FINDMYPHONE, json!({}));
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        assert!(plugin.is_ringing());
+        assert_eq!(*ringer.started.lock().unwrap(), vec![config]);
+
+        // Second request toggles the ring off
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+        assert!(!plugin.is_ringing());
+        assert_eq!(*ringer.stops.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_factory_passes_config() {
+        let config = RingConfig {
+            sound: PathBuf::from("/tmp/factory-ring.wav"),
+            volume_override: None,
+        };
+        let factory = FindMyPhonePluginFactory::with_config(config.clone());
+        let plugin = factory.create();
+        let plugin = plugin.as_any().downcast_ref::<FindMyPhonePlugin>().unwrap();
+
+        assert_eq!(plugin.ring_config(), &config);
+    }
+
+    #[test]
+    fn test_validate_ring_sound() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let missing = dir.path().join("missing.ogg");
+        assert!(validate_ring_sound(&missing).is_err());
+
+        let text = dir.path().join("notes.txt");
+        std::fs::write(&text, b"not audio").unwrap();
+        assert!(validate_ring_sound(&text).is_err());
+
+        let sound = dir.path().join("ring.OGG");
+        std::fs::write(&sound, b"OggS").unwrap();
+        assert!(validate_ring_sound(&sound).is_ok());
+    }
 }
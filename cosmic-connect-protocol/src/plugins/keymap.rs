@@ -0,0 +1,442 @@
+//! Keyboard Layout Awareness for Remote Input
+//!
+//! Maps characters and special keys to key strokes on the user's active XKB
+//! layout, so that remote text is typed correctly regardless of the layout
+//! (e.g. `@` is AltGr+Q on a German layout, not Shift+2).
+//!
+//! ## Keymap Source
+//!
+//! The active layout names (rules, model, layout, variant, options) are read
+//! from, in order:
+//!
+//! 1. The COSMIC compositor's `xkb_config`
+//! 2. The `XKB_DEFAULT_*` environment variables
+//! 3. `setxkbmap -query` on X11 sessions
+//!
+//! The names are compiled into a keymap with libxkbcommon. When several
+//! layouts are configured, the first one is used.
+//!
+//! ## Levels
+//!
+//! Shift levels are mapped to modifiers the way the common XKB key types
+//! define them: level 2 is Shift, level 3 is AltGr (ISO_Level3_Shift) and
+//! level 4 is Shift+AltGr.
+//!
+//! ## Runtime Changes
+//!
+//! `KeymapCache` re-reads the layout names at most every
+//! `KEYMAP_REFRESH_INTERVAL` and recompiles the keymap when they change, so
+//! layout switches are picked up without restarting the daemon.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+use xkbcommon::xkb;
+
+/// How often the active layout is re-checked
+pub const KEYMAP_REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Offset between XKB and Linux evdev key codes
+const EVDEV_OFFSET: u32 = 8;
+
+/// Number of shift levels mapped to modifiers
+const MAX_LEVELS: u32 = 4;
+
+/// A single key event sent to the virtual keyboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyEvent {
+    /// Press and hold a key
+    Press(u16),
+    /// Press and release a key
+    Click(u16),
+    /// Release a held key
+    Release(u16),
+}
+
+/// A key together with the modifiers needed to reach a shift level
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyStroke {
+    /// Linux key code
+    pub keycode: u16,
+    /// Modifier key codes held while the key is clicked
+    pub modifiers: Vec<u16>,
+}
+
+impl KeyStroke {
+    /// Key events for this stroke: press modifiers, click, release in reverse
+    pub fn events(&self) -> Vec<KeyEvent> {
+        let mut events: Vec<KeyEvent> = self
+            .modifiers
+            .iter()
+            .map(|&modifier| KeyEvent::Press(modifier))
+            .collect();
+        events.push(KeyEvent::Click(self.keycode));
+        events.extend(
+            self.modifiers
+                .iter()
+                .rev()
+                .map(|&modifier| KeyEvent::Release(modifier)),
+        );
+        events
+    }
+}
+
+/// XKB layout names describing a keymap
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct XkbNames {
+    pub rules: String,
+    pub model: String,
+    pub layout: String,
+    pub variant: String,
+    pub options: Option<String>,
+}
+
+impl XkbNames {
+    /// Detect the layout names of the current session
+    pub fn detect() -> Option<Self> {
+        Self::from_cosmic()
+            .or_else(Self::from_env)
+            .or_else(Self::from_setxkbmap)
+            .map(Self::first_layout)
+    }
+
+    /// Path of the COSMIC compositor's keyboard configuration
+    fn cosmic_config_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| {
+            dir.join("cosmic")
+                .join("com.system76.CosmicComp")
+                .join("v1")
+                .join("xkb_config")
+        })
+    }
+
+    fn from_cosmic() -> Option<Self> {
+        let text = std::fs::read_to_string(Self::cosmic_config_path()?).ok()?;
+        Self::parse_cosmic_config(&text)
+    }
+
+    fn from_env() -> Option<Self> {
+        let var = |name: &str| std::env::var(name).unwrap_or_default();
+        let names = Self {
+            rules: var("XKB_DEFAULT_RULES"),
+            model: var("XKB_DEFAULT_MODEL"),
+            layout: var("XKB_DEFAULT_LAYOUT"),
+            variant: var("XKB_DEFAULT_VARIANT"),
+            options: std::env::var("XKB_DEFAULT_OPTIONS").ok(),
+        };
+        (!names.layout.is_empty()).then_some(names)
+    }
+
+    fn from_setxkbmap() -> Option<Self> {
+        std::env::var_os("DISPLAY")?;
+        let output = Command::new("setxkbmap").arg("-query").output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Self::parse_setxkbmap(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Parse the RON `xkb_config` written by cosmic-comp
+    fn parse_cosmic_config(text: &str) -> Option<Self> {
+        let names = Self {
+            rules: ron_string_field(text, "rules").unwrap_or_default(),
+            model: ron_string_field(text, "model").unwrap_or_default(),
+            layout: ron_string_field(text, "layout")?,
+            variant: ron_string_field(text, "variant").unwrap_or_default(),
+            options: ron_string_field(text, "options"),
+        };
+        (!names.layout.is_empty()).then_some(names)
+    }
+
+    /// Parse `setxkbmap -query` output
+    fn parse_setxkbmap(text: &str) -> Option<Self> {
+        let fields: HashMap<&str, &str> = text
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .collect();
+        let field = |key: &str| fields.get(key).map(|v| v.to_string()).unwrap_or_default();
+
+        let names = Self {
+            rules: field("rules"),
+            model: field("model"),
+            layout: field("layout"),
+            variant: field("variant"),
+            options: fields.get("options").map(|v| v.to_string()),
+        };
+        (!names.layout.is_empty()).then_some(names)
+    }
+
+    /// Keep only the first of several comma-separated layouts
+    fn first_layout(mut self) -> Self {
+        let first = |list: &str| list.split(',').next().unwrap_or_default().to_string();
+        self.layout = first(&self.layout);
+        self.variant = first(&self.variant);
+        self
+    }
+}
+
+/// Read a `key: "value"` or `key: Some("value")` string field from RON text
+fn ron_string_field(text: &str, key: &str) -> Option<String> {
+    let pattern = format!("{}:", key);
+    let start = text.find(&pattern)? + pattern.len();
+    let rest = text[start..].trim_start();
+    let rest = rest.strip_prefix("Some(").unwrap_or(rest).trim_start();
+    let rest = rest.strip_prefix('"')?;
+    rest.find('"').map(|end| rest[..end].to_string())
+}
+
+/// Character and keysym lookup table for one layout
+#[derive(Debug, Clone, Default)]
+pub struct Keymap {
+    /// Characters to key code and shift level (0-based)
+    chars: HashMap<char, (u16, u32)>,
+    /// Keysym names on the base level to key code
+    keysyms: HashMap<String, u16>,
+}
+
+impl Keymap {
+    /// Create an empty keymap
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compile the keymap for the given layout names
+    pub fn compile(names: &XkbNames) -> Option<Self> {
+        let context = xkb::Context::new(xkb::CONTEXT_NO_FLAGS);
+        let xkb_keymap = xkb::Keymap::new_from_names(
+            &context,
+            &names.rules,
+            &names.model,
+            &names.layout,
+            &names.variant,
+            names.options.clone(),
+            xkb::KEYMAP_COMPILE_NO_FLAGS,
+        )?;
+
+        let mut keymap = Self::new();
+        xkb_keymap.key_for_each(|xkb_keymap, key| {
+            let Some(keycode) = key
+                .raw()
+                .checked_sub(EVDEV_OFFSET)
+                .and_then(|code| u16::try_from(code).ok())
+            else {
+                return;
+            };
+
+            let levels = xkb_keymap.num_levels_for_key(key, 0).min(MAX_LEVELS);
+            for level in 0..levels {
+                for &keysym in xkb_keymap.key_get_syms_by_level(key, 0, level) {
+                    if let Some(ch) = char::from_u32(xkb::keysym_to_utf32(keysym)) {
+                        if ch != '\0' {
+                            keymap.insert_char(ch, keycode, level);
+                        }
+                    }
+                    if level == 0 {
+                        keymap.insert_keysym(&xkb::keysym_get_name(keysym), keycode);
+                    }
+                }
+            }
+        });
+
+        Some(keymap)
+    }
+
+    /// Map a character to a key code and shift level
+    ///
+    /// The lowest level wins when a character is reachable in several ways.
+    pub fn insert_char(&mut self, ch: char, keycode: u16, level: u32) {
+        let entry = self.chars.entry(ch).or_insert((keycode, level));
+        if level < entry.1 {
+            *entry = (keycode, level);
+        }
+    }
+
+    /// Map a base-level keysym name to a key code
+    pub fn insert_keysym(&mut self, name: &str, keycode: u16) {
+        self.keysyms.entry(name.to_string()).or_insert(keycode);
+    }
+
+    /// Key code producing a keysym on the base level
+    pub fn keycode_for_keysym(&self, name: &str) -> Option<u16> {
+        self.keysyms.get(name).copied()
+    }
+
+    /// Key stroke typing a character on this layout
+    pub fn stroke_for_char(&self, ch: char) -> Option<KeyStroke> {
+        use mouse_keyboard_input::{KEY_LEFTSHIFT, KEY_RIGHTALT};
+
+        let &(keycode, level) = self.chars.get(&ch)?;
+        let shift = self.keycode_for_keysym("Shift_L").unwrap_or(KEY_LEFTSHIFT);
+        let level3 = self
+            .keycode_for_keysym("ISO_Level3_Shift")
+            .unwrap_or(KEY_RIGHTALT);
+
+        let modifiers = match level {
+            0 => vec![],
+            1 => vec![shift],
+            2 => vec![level3],
+            _ => vec![level3, shift],
+        };
+
+        Some(KeyStroke { keycode, modifiers })
+    }
+}
+
+/// Active keymap, refreshed when the session's layout changes
+#[derive(Debug, Default)]
+pub struct KeymapCache {
+    names: Option<XkbNames>,
+    keymap: Option<Arc<Keymap>>,
+    checked_at: Option<Instant>,
+    /// Keymap was injected and is never refreshed
+    pinned: bool,
+}
+
+impl KeymapCache {
+    /// Create an empty cache; the keymap is loaded on first use
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a cache holding a fixed keymap that is never refreshed
+    pub fn with_keymap(keymap: Keymap) -> Self {
+        Self {
+            keymap: Some(Arc::new(keymap)),
+            pinned: true,
+            ..Self::default()
+        }
+    }
+
+    /// Get the keymap for the active layout, reloading it if it changed
+    pub fn current(&mut self) -> Option<Arc<Keymap>> {
+        if self.pinned {
+            return self.keymap.clone();
+        }
+
+        let now = Instant::now();
+        let due = match self.checked_at {
+            Some(checked_at) => {
+                now.saturating_duration_since(checked_at) >= KEYMAP_REFRESH_INTERVAL
+            }
+            None => true,
+        };
+
+        if due {
+            self.checked_at = Some(now);
+            let names = XkbNames::detect();
+            if names != self.names {
+                self.keymap = names.as_ref().and_then(|names| {
+                    let keymap = Keymap::compile(names);
+                    match &keymap {
+                        Some(_) => info!(
+                            "Using keyboard layout '{}' (variant '{}') for remote input",
+                            names.layout, names.variant
+                        ),
+                        None => debug!("Failed to compile keymap for {:?}", names),
+                    }
+                    keymap.map(Arc::new)
+                });
+                self.names = names;
+            }
+        }
+
+        self.keymap.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mouse_keyboard_input::{KEY_2, KEY_LEFTSHIFT, KEY_Q, KEY_RIGHTALT, KEY_Y, KEY_Z};
+
+    /// Part of a German layout: Y and Z swapped, @ on AltGr+Q
+    fn german_keymap() -> Keymap {
+        let mut keymap = Keymap::new();
+        keymap.insert_char('q', KEY_Q, 0);
+        keymap.insert_char('Q', KEY_Q, 1);
+        keymap.insert_char('@', KEY_Q, 2);
+        keymap.insert_char('z', KEY_Y, 0);
+        keymap.insert_char('y', KEY_Z, 0);
+        keymap.insert_char('"', KEY_2, 1);
+        keymap.insert_keysym("Shift_L", KEY_LEFTSHIFT);
+        keymap.insert_keysym("ISO_Level3_Shift", KEY_RIGHTALT);
+        keymap
+    }
+
+    #[test]
+    fn test_level3_character_uses_altgr() {
+        let keymap = german_keymap();
+        let stroke = keymap.stroke_for_char('@').unwrap();
+
+        assert_eq!(
+            stroke.events(),
+            vec![
+                KeyEvent::Press(KEY_RIGHTALT),
+                KeyEvent::Click(KEY_Q),
+                KeyEvent::Release(KEY_RIGHTALT),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_layout_specific_keys_and_shift() {
+        let keymap = german_keymap();
+
+        assert_eq!(keymap.stroke_for_char('z').unwrap().keycode, KEY_Y);
+        assert_eq!(
+            keymap.stroke_for_char('"').unwrap().events(),
+            vec![
+                KeyEvent::Press(KEY_LEFTSHIFT),
+                KeyEvent::Click(KEY_2),
+                KeyEvent::Release(KEY_LEFTSHIFT),
+            ]
+        );
+        assert!(keymap.stroke_for_char('€').is_none());
+    }
+
+    #[test]
+    fn test_lowest_level_wins() {
+        let mut keymap = Keymap::new();
+        keymap.insert_char('1', KEY_2, 1);
+        keymap.insert_char('1', KEY_Q, 0);
+        assert_eq!(keymap.stroke_for_char('1').unwrap().modifiers, vec![]);
+    }
+
+    #[test]
+    fn test_parse_cosmic_config() {
+        let text = r#"(
+    rules: "",
+    model: "pc104",
+    layout: "de,us",
+    variant: "nodeadkeys,",
+    options: Some("compose:ralt"),
+    repeat_delay: 600,
+    repeat_rate: 25,
+)"#;
+        let names = XkbNames::parse_cosmic_config(text).unwrap().first_layout();
+        assert_eq!(names.layout, "de");
+        assert_eq!(names.variant, "nodeadkeys");
+        assert_eq!(names.model, "pc104");
+        assert_eq!(names.options.as_deref(), Some("compose:ralt"));
+    }
+
+    #[test]
+    fn test_parse_setxkbmap() {
+        let text = "rules:      evdev\nmodel:      pc105\nlayout:     fr\n";
+        let names = XkbNames::parse_setxkbmap(text).unwrap();
+        assert_eq!(names.rules, "evdev");
+        assert_eq!(names.layout, "fr");
+        assert_eq!(names.options, None);
+    }
+
+    #[test]
+    fn test_cache_with_injected_keymap() {
+        let mut cache = KeymapCache::with_keymap(german_keymap());
+        let keymap = cache.current().unwrap();
+        assert_eq!(keymap.stroke_for_char('y').unwrap().keycode, KEY_Z);
+    }
+}
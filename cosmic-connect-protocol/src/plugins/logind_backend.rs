@@ -19,11 +19,18 @@
 //! - `LockedHint`: Boolean indicating if session is locked
 //! - `Active`: Boolean indicating if session is active
 //! - `State`: Session state (online, active, closing)
+//!
+//! ## Session Signals
+//!
+//! - `Lock` / `Unlock`: Sent when the session is asked to lock or unlock
+//! - `PropertiesChanged`: Carries `LockedHint` changes
 
+use futures::StreamExt;
+use std::collections::HashMap;
 use std::env;
 use tracing::{debug, info};
 use zbus::zvariant::OwnedValue;
-use zbus::Connection;
+use zbus::{Connection, MatchRule, Message, MessageStream};
 
 /// DBus service name for logind
 const LOGIND_SERVICE: &str = "org.freedesktop.login1";
@@ -60,6 +67,36 @@ impl Default for LockState {
     }
 }
 
+/// Change of the session's lock state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionLockEvent {
+    /// The session is being locked
+    Locked,
+    /// The session was unlocked
+    Unlocked,
+}
+
+impl SessionLockEvent {
+    /// Lock state change carried by a logind session signal, if any
+    fn from_message(msg: &Message) -> Option<Self> {
+        let header = msg.header();
+        match header.member()?.as_str() {
+            "Lock" => Some(Self::Locked),
+            "Unlock" => Some(Self::Unlocked),
+            "PropertiesChanged" => {
+                let (interface, changed, _): (String, HashMap<String, OwnedValue>, Vec<String>) =
+                    msg.body().deserialize().ok()?;
+                if interface != LOGIND_SESSION_INTERFACE {
+                    return None;
+                }
+                let locked = changed.get("LockedHint")?.downcast_ref::<bool>().ok()?;
+                Some(if locked { Self::Locked } else { Self::Unlocked })
+            }
+            _ => None,
+        }
+    }
+}
+
 /// Logind DBus backend for screen lock control
 pub struct LogindBackend {
     /// DBus connection
@@ -387,6 +424,36 @@ impl LogindBackend {
         })
     }
 
+    /// Subscribe to lock state changes of the current session
+    ///
+    /// Yields [`SessionLockEvent::Locked`] as soon as the session is asked to
+    /// lock, and again once `LockedHint` is set; consumers should treat
+    /// repeated events as no-ops.
+    pub async fn lock_events(
+        &mut self,
+    ) -> Result<impl futures::Stream<Item = SessionLockEvent> + Unpin, String> {
+        self.ensure_connected().await?;
+        let conn = self.connection.as_ref().ok_or("Not connected")?;
+        let path = self.session_path.as_ref().ok_or("No session found")?;
+
+        let rule = MatchRule::builder()
+            .msg_type(zbus::message::Type::Signal)
+            .sender(LOGIND_SERVICE)
+            .and_then(|builder| builder.path(path.as_str()))
+            .map_err(|e| format!("Invalid match rule: {}", e))?
+            .build();
+        let stream = MessageStream::for_match_rule(rule, conn, Some(16))
+            .await
+            .map_err(|e| format!("Failed to subscribe to session signals: {}", e))?;
+
+        Ok(Box::pin(stream.filter_map(|msg| {
+            futures::future::ready(
+                msg.ok()
+                    .and_then(|msg| SessionLockEvent::from_message(&msg)),
+            )
+        })))
+    }
+
     /// Check if logind service is available
     pub async fn is_available(&mut self) -> bool {
         if self.connection.is_none() {
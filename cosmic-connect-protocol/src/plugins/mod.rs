@@ -113,25 +113,32 @@ pub mod connectivity_report;
 pub mod contacts;
 pub mod filesync;
 pub mod findmyphone;
+pub mod keymap;
 pub mod lock;
 pub mod logind_backend;
 pub mod r#macro;
+pub mod metrics;
 pub mod mkshare;
 pub mod mousekeyboardshare;
 pub mod mpris;
 pub mod mpris_backend;
 pub mod networkshare;
 pub mod notification;
+pub mod notifier;
 pub mod phoneauth;
+pub mod photo;
 pub mod ping;
 pub mod power;
 pub mod presenter;
 pub mod remotedesktop;
 pub mod remoteinput;
+pub mod reply;
 pub mod runcommand;
 pub mod screenshare;
 pub mod screenshot;
+pub mod settings;
 pub mod share;
+pub mod system_state;
 pub mod systemd_inhibitor;
 pub mod systemmonitor;
 pub mod systemvolume;
@@ -139,14 +146,24 @@ pub mod telephony;
 pub mod upower_backend;
 pub mod wol;
 
-use crate::{Device, Packet, ProtocolError, Result};
+use crate::{Device, DeviceInfo, Packet, ProtocolError, Result};
 use async_trait::async_trait;
+use metrics::{PluginMetrics, PluginMetricsSnapshot};
+use notifier::Notifier;
+use settings::{PluginSettings, PluginSettingsStore};
 use std::any::Any;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc::Sender;
 use tracing::{debug, error, info, warn};
 
+/// How long a plugin may take to handle a packet before it is abandoned
+pub const DEFAULT_HANDLER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Packets in a row a plugin may time out on before it is reported degraded
+const MAX_CONSECUTIVE_TIMEOUTS: u32 = 3;
+
 /// Factory trait for creating plugin instances
 ///
 /// Plugins must implement this trait to support per-device instances.
@@ -181,6 +198,87 @@ pub trait PluginFactory: Send + Sync {
     fn create(&self) -> Box<dyn Plugin>;
 }
 
+/// Operational status of a plugin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PluginStatus {
+    /// Plugin is fully functional
+    Active,
+    /// Plugin is loaded but cannot provide its feature (with the reason)
+    Degraded(String),
+}
+
+/// Way an outgoing packet fails to match the declared capabilities
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutgoingCapabilityMismatch {
+    /// No plugin of the device declares the packet type as outgoing
+    Undeclared,
+    /// The device did not declare the packet type as incoming
+    NotAccepted,
+}
+
+/// Capabilities an outgoing packet is checked against
+#[derive(Debug, Clone, Default)]
+struct DeviceCapabilities {
+    /// Outgoing capabilities of the device's plugins
+    declared: Vec<String>,
+    /// Incoming capabilities the device advertised
+    accepted: Vec<String>,
+}
+
+impl DeviceCapabilities {
+    fn check(&self, device_id: &str, packet: &Packet) -> Option<OutgoingCapabilityMismatch> {
+        if packet.packet_type.starts_with("cconnect.internal.") {
+            return None;
+        }
+
+        let mismatch = if !self.declared.iter().any(|c| packet.is_type(c)) {
+            OutgoingCapabilityMismatch::Undeclared
+        } else if !self.accepted.is_empty() && !self.accepted.iter().any(|c| packet.is_type(c)) {
+            OutgoingCapabilityMismatch::NotAccepted
+        } else {
+            return None;
+        };
+
+        warn!(
+            "Outgoing packet {} to device {} does not match capabilities: {:?}",
+            packet.packet_type, device_id, mismatch
+        );
+        Some(mismatch)
+    }
+}
+
+/// Snapshot of the capabilities of each device with plugins
+///
+/// Kept up to date by the [`PluginManager`] it came from, see
+/// [`PluginManager::outgoing_capabilities`]. Checking a packet only takes a
+/// short internal lock, so packet forwarders can validate packets without
+/// waiting for the plugin or device manager, whose holders may themselves
+/// be waiting for the forwarder.
+#[derive(Debug, Clone, Default)]
+pub struct OutgoingCapabilityIndex {
+    devices: Arc<std::sync::RwLock<HashMap<String, DeviceCapabilities>>>,
+}
+
+impl OutgoingCapabilityIndex {
+    fn insert(&self, device_id: &str, capabilities: DeviceCapabilities) {
+        let mut devices = self.devices.write().unwrap_or_else(|e| e.into_inner());
+        devices.insert(device_id.to_string(), capabilities);
+    }
+
+    fn remove(&self, device_id: &str) {
+        let mut devices = self.devices.write().unwrap_or_else(|e| e.into_inner());
+        devices.remove(device_id);
+    }
+
+    /// Check an outgoing packet, like [`PluginManager::validate_outgoing_packet`]
+    ///
+    /// Packets to devices without plugins are not checked.
+    pub fn validate(&self, device_id: &str, packet: &Packet) -> Option<OutgoingCapabilityMismatch> {
+        let devices = self.devices.read().unwrap_or_else(|e| e.into_inner());
+        devices.get(device_id)?.check(device_id, packet)
+    }
+}
+
 /// Plugin trait for extending CConnect functionality
 ///
 /// Plugins must implement this trait to handle specific packet types and provide
@@ -290,6 +388,36 @@ pub trait Plugin: Send + Sync + Any {
     /// Malformed packets should be logged but not cause errors.
     async fn handle_packet(&mut self, packet: &Packet, device: &mut Device) -> Result<()>;
 
+    /// Provide the notifier used to raise desktop notifications
+    ///
+    /// Called by the `PluginManager` before `init`. Plugins that raise
+    /// notifications keep it. Default implementation ignores it.
+    fn set_notifier(&mut self, _notifier: Arc<dyn Notifier>) {}
+
+    /// Provide the plugin's settings for this device
+    ///
+    /// Called by the `PluginManager` before `init` when a settings store is
+    /// set. Plugins read their settings in `init` and persist changes with
+    /// [`PluginSettings::save_config`]. Default implementation ignores it.
+    fn set_settings(&mut self, _settings: PluginSettings) {}
+
+    /// Provide the plugin's counters for this device
+    ///
+    /// Called by the `PluginManager` before `init`. The manager counts handled
+    /// packets and errors; plugins that transfer payloads or raise
+    /// notifications keep the counters and record those. Default
+    /// implementation ignores them.
+    fn set_metrics(&mut self, _metrics: Arc<PluginMetrics>) {}
+
+    /// Adjust the identity advertised to other devices
+    ///
+    /// Called on a fresh instance when the identity is built, after it has
+    /// been filled with the capabilities of all registered factories. Plugins
+    /// can add capabilities or flags that depend on the local system, or drop
+    /// their own (e.g. battery on a desktop without one). Default
+    /// implementation leaves the identity unchanged.
+    async fn augment_identity(&self, _identity: &mut DeviceInfo) {}
+
     /// Check if plugin is ready to handle packets
     ///
     /// Optional method for plugins that need startup time (e.g., loading state).
@@ -298,6 +426,23 @@ pub trait Plugin: Send + Sync + Any {
         true
     }
 
+    /// Get the plugin's operational status
+    ///
+    /// Optional method for plugins that depend on the local environment
+    /// (e.g., an input backend). Default implementation returns `Active`.
+    fn status(&self) -> PluginStatus {
+        PluginStatus::Active
+    }
+
+    /// Get the plugin's priority
+    ///
+    /// Plugins with a higher priority are initialized first when a device
+    /// connects, so the packets they send on connect go out first. Plugins of
+    /// equal priority are ordered by name. Default is 0.
+    fn priority(&self) -> i32 {
+        0
+    }
+
     /// Get plugin version for compatibility checking
     ///
     /// Optional method for plugins that track version compatibility.
@@ -353,6 +498,25 @@ pub struct PluginManager {
 
     /// Mapping from incoming capability to plugin name
     capability_map: HashMap<String, String>,
+
+    /// Notifier shared by all plugin instances
+    notifier: Option<Arc<dyn Notifier>>,
+
+    /// Store plugin settings are loaded from and saved to
+    settings_store: Option<Arc<dyn PluginSettingsStore>>,
+
+    /// Per-device plugin counters, kept across reconnects
+    /// Outer key: device_id, Inner key: plugin_name
+    metrics: HashMap<String, HashMap<String, Arc<PluginMetrics>>>,
+
+    /// How long a plugin may take to handle a packet
+    handler_timeout: Duration,
+
+    /// Packets each plugin timed out on in a row, by (device_id, plugin_name)
+    consecutive_timeouts: HashMap<(String, String), u32>,
+
+    /// Capabilities of the devices with plugins, shared with packet forwarders
+    outgoing_capabilities: OutgoingCapabilityIndex,
 }
 
 impl PluginManager {
@@ -362,9 +526,42 @@ impl PluginManager {
             factories: HashMap::new(),
             device_plugins: HashMap::new(),
             capability_map: HashMap::new(),
+            notifier: None,
+            settings_store: None,
+            metrics: HashMap::new(),
+            handler_timeout: DEFAULT_HANDLER_TIMEOUT,
+            consecutive_timeouts: HashMap::new(),
+            outgoing_capabilities: OutgoingCapabilityIndex::default(),
         }
     }
 
+    /// Set how long a plugin may take to handle a packet
+    ///
+    /// A handler still running after this is abandoned so it cannot stall the
+    /// device's other packets. Defaults to [`DEFAULT_HANDLER_TIMEOUT`].
+    pub fn set_handler_timeout(&mut self, timeout: Duration) {
+        self.handler_timeout = timeout;
+    }
+
+    /// Set the notifier handed to plugin instances
+    ///
+    /// Applies to plugins created afterwards by `init_device_plugins`.
+    pub fn set_notifier(&mut self, notifier: Arc<dyn Notifier>) {
+        self.notifier = Some(notifier);
+    }
+
+    /// Get the notifier shared by plugin instances
+    pub fn notifier(&self) -> Option<Arc<dyn Notifier>> {
+        self.notifier.clone()
+    }
+
+    /// Set the store plugin settings are kept in
+    ///
+    /// Applies to plugins created afterwards by `init_device_plugins`.
+    pub fn set_settings_store(&mut self, store: Arc<dyn PluginSettingsStore>) {
+        self.settings_store = Some(store);
+    }
+
     /// Register a plugin factory
     ///
     /// Adds the plugin factory to the registry and builds capability mappings.
@@ -422,6 +619,10 @@ impl PluginManager {
     /// Creates plugin instances from registered factories and initializes them
     /// for the given device. Each device gets its own set of plugin instances.
     ///
+    /// Plugins are initialized by [`Plugin::priority`], highest first, then in
+    /// plugin name order, so the packets they send on connect reach the device
+    /// in a deterministic order.
+    ///
     /// # Errors
     ///
     /// Returns error if plugin creation or initialization fails
@@ -439,11 +640,49 @@ impl PluginManager {
 
         let mut device_plugins = HashMap::new();
 
-        for (name, factory) in &self.factories {
-            debug!("Creating plugin {} for device {}", name, device_id);
+        // Create plugin instances, highest priority first
+        let mut plugins: Vec<(String, Box<dyn Plugin>)> = self
+            .factories
+            .iter()
+            .map(|(name, factory)| {
+                debug!("Creating plugin {} for device {}", name, device_id);
+                (name.clone(), factory.create())
+            })
+            .collect();
+        plugins.sort_by(|(a_name, a), (b_name, b)| {
+            b.priority()
+                .cmp(&a.priority())
+                .then_with(|| a_name.cmp(b_name))
+        });
+
+        // Before initializing, as plugins may send packets from init
+        self.outgoing_capabilities.insert(
+            device_id,
+            DeviceCapabilities {
+                declared: plugins
+                    .iter()
+                    .flat_map(|(_, plugin)| plugin.outgoing_capabilities())
+                    .collect(),
+                accepted: device.info.incoming_capabilities.clone(),
+            },
+        );
+
+        for (name, mut plugin) in plugins {
+            if let Some(notifier) = &self.notifier {
+                plugin.set_notifier(notifier.clone());
+            }
+
+            if let Some(store) = &self.settings_store {
+                plugin.set_settings(PluginSettings::load(store.clone(), device_id, &name).await);
+            }
 
-            // Create plugin instance
-            let mut plugin = factory.create();
+            let metrics = self
+                .metrics
+                .entry(device_id.to_string())
+                .or_default()
+                .entry(name.clone())
+                .or_default();
+            plugin.set_metrics(metrics.clone());
 
             // Initialize plugin
             if let Err(e) = plugin.init(device, packet_sender.clone()).await {
@@ -465,7 +704,7 @@ impl PluginManager {
                 continue;
             }
 
-            device_plugins.insert(name.clone(), plugin);
+            device_plugins.insert(name, plugin);
         }
 
         info!(
@@ -489,6 +728,7 @@ impl PluginManager {
     ///
     /// Returns error if plugin cleanup fails, but attempts to cleanup all plugins
     pub async fn cleanup_device_plugins(&mut self, device_id: &str) -> Result<()> {
+        self.outgoing_capabilities.remove(device_id);
         if let Some(mut plugins) = self.device_plugins.remove(device_id) {
             info!(
                 "Cleaning up {} plugins for device {}",
@@ -574,7 +814,9 @@ impl PluginManager {
 
     /// Get all incoming capabilities from registered factories
     pub fn get_all_incoming_capabilities(&self) -> Vec<String> {
-        self.capability_map.keys().cloned().collect()
+        let mut capabilities: Vec<String> = self.capability_map.keys().cloned().collect();
+        capabilities.sort();
+        capabilities
     }
 
     /// Get all outgoing capabilities from registered factories
@@ -589,6 +831,33 @@ impl PluginManager {
         capabilities
     }
 
+    /// Let every registered plugin adjust the identity
+    ///
+    /// Runs [`Plugin::augment_identity`] on a fresh instance from each
+    /// factory, in plugin name order. Call after filling the identity with
+    /// [`get_all_incoming_capabilities`](Self::get_all_incoming_capabilities)
+    /// and [`get_all_outgoing_capabilities`](Self::get_all_outgoing_capabilities).
+    pub async fn augment_identity(&self, identity: &mut DeviceInfo) {
+        let mut names: Vec<&String> = self.factories.keys().collect();
+        names.sort();
+
+        for name in names {
+            let plugin = self.factories[name].create();
+            plugin.augment_identity(identity).await;
+        }
+    }
+
+    /// Advertise the registered plugins in an identity
+    ///
+    /// Replaces the identity's capability lists with those of the registered
+    /// factories, then lets the plugins adjust it with
+    /// [`augment_identity`](Self::augment_identity).
+    pub async fn advertise_capabilities(&self, identity: &mut DeviceInfo) {
+        identity.incoming_capabilities = self.get_all_incoming_capabilities();
+        identity.outgoing_capabilities = self.get_all_outgoing_capabilities();
+        self.augment_identity(identity).await;
+    }
+
     /// Initialize all plugins with device context (deprecated)
     ///
     /// Use `init_device_plugins(device_id, device)` instead for per-device plugin instances.
@@ -649,10 +918,16 @@ impl PluginManager {
     /// Looks up the plugin that handles the packet's type for the given device
     /// and delegates packet processing to that plugin instance.
     ///
+    /// Packets no plugin handles, such as types added by newer KDE Connect
+    /// releases, are logged at debug level and ignored.
+    ///
+    /// A plugin that takes longer than the handler timeout is abandoned with a
+    /// logged error, and reported degraded by [`Self::plugin_status`] after
+    /// timing out on several packets in a row.
+    ///
     /// # Errors
     ///
     /// Returns error if:
-    /// - No plugin handles the packet type
     /// - Device has no initialized plugins
     /// - Plugin packet handling fails critically
     pub async fn handle_packet(
@@ -677,16 +952,12 @@ impl PluginManager {
                 packet_type = aliased;
                 name.clone()
             } else {
-                return Err(ProtocolError::Plugin(format!(
-                    "No plugin handles packet type: {} (nor aliased {})",
-                    packet.packet_type, aliased
-                )));
+                debug!("No handler for {} (nor aliased {})", packet_type, aliased);
+                return Ok(());
             }
         } else {
-            return Err(ProtocolError::Plugin(format!(
-                "No plugin handles packet type: {}",
-                packet_type
-            )));
+            debug!("No handler for {}", packet_type);
+            return Ok(());
         };
 
         // Get device plugins
@@ -707,10 +978,44 @@ impl PluginManager {
             packet.packet_type, packet_type, plugin_name, device_id
         );
 
-        // Handle packet with error isolation
-        match plugin.handle_packet(packet, device).await {
+        let metrics = self
+            .metrics
+            .get(device_id)
+            .and_then(|plugins| plugins.get(&plugin_name));
+        if let Some(metrics) = metrics {
+            metrics.record_packet_handled();
+        }
+
+        // Handle packet with error isolation, abandoning hung handlers
+        let timeout_key = (device_id.to_string(), plugin_name.clone());
+        let handled =
+            tokio::time::timeout(self.handler_timeout, plugin.handle_packet(packet, device)).await;
+        let result = match handled {
+            Ok(result) => {
+                self.consecutive_timeouts.remove(&timeout_key);
+                result
+            }
+            Err(_) => {
+                let timeouts = self.consecutive_timeouts.entry(timeout_key).or_insert(0);
+                *timeouts += 1;
+                error!(
+                    "Plugin {} did not handle packet {} for device {} within {:?} ({} in a row), abandoned",
+                    plugin_name, packet_type, device_id, self.handler_timeout, timeouts
+                );
+                Err(ProtocolError::Timeout(format!(
+                    "Plugin {} timed out handling {}",
+                    plugin_name, packet_type
+                )))
+            }
+        };
+
+        match result {
             Ok(()) => Ok(()),
             Err(e) => {
+                if let Some(metrics) = metrics {
+                    metrics.record_error();
+                }
+
                 // Check if error is recoverable
                 if e.is_recoverable() {
                     // Log recoverable errors but don't propagate
@@ -744,6 +1049,89 @@ impl PluginManager {
         }
     }
 
+    /// Operational status of a device's plugin
+    ///
+    /// A plugin that timed out on several packets in a row is degraded until
+    /// it handles a packet in time again; otherwise the plugin reports its own
+    /// status. `None` if the device has no such plugin.
+    pub fn plugin_status(&self, device_id: &str, plugin_name: &str) -> Option<PluginStatus> {
+        let plugin = self.get_device_plugin(device_id, plugin_name)?;
+        let timeouts = self
+            .consecutive_timeouts
+            .get(&(device_id.to_string(), plugin_name.to_string()))
+            .copied()
+            .unwrap_or(0);
+        if timeouts >= MAX_CONSECUTIVE_TIMEOUTS {
+            return Some(PluginStatus::Degraded(format!(
+                "Timed out handling {} packets in a row",
+                timeouts
+            )));
+        }
+        Some(plugin.status())
+    }
+
+    /// Operational status of each of a device's plugins, by plugin name
+    ///
+    /// See [`Self::plugin_status`]. Empty if the device has no plugins.
+    pub fn device_plugin_statuses(&self, device_id: &str) -> HashMap<String, PluginStatus> {
+        self.device_plugins
+            .get(device_id)
+            .into_iter()
+            .flat_map(|plugins| plugins.keys())
+            .filter_map(|name| Some((name.clone(), self.plugin_status(device_id, name)?)))
+            .collect()
+    }
+
+    /// Get the plugin counters of a device, by plugin name
+    ///
+    /// Includes plugins of previous connections of the device; empty if the
+    /// device never connected.
+    pub fn device_plugin_metrics(&self, device_id: &str) -> HashMap<String, PluginMetricsSnapshot> {
+        self.metrics
+            .get(device_id)
+            .map(|plugins| {
+                plugins
+                    .iter()
+                    .map(|(name, metrics)| (name.clone(), metrics.snapshot()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Check an outgoing packet against the declared capabilities
+    ///
+    /// The packet type must be among the outgoing capabilities of the device's
+    /// plugins and, when the device advertised any, among its incoming
+    /// capabilities. A mismatch is logged as a warning so plugins sending
+    /// packets they never declared are caught early. Internal
+    /// `cconnect.internal.*` packets are not checked.
+    pub fn validate_outgoing_packet(
+        &self,
+        device: &Device,
+        packet: &Packet,
+    ) -> Option<OutgoingCapabilityMismatch> {
+        let capabilities = DeviceCapabilities {
+            declared: self
+                .device_plugins
+                .get(device.id())
+                .into_iter()
+                .flat_map(|plugins| plugins.values())
+                .flat_map(|plugin| plugin.outgoing_capabilities())
+                .collect(),
+            accepted: device.info.incoming_capabilities.clone(),
+        };
+        capabilities.check(device.id(), packet)
+    }
+
+    /// Capabilities outgoing packets are checked against, for use without
+    /// holding the manager
+    ///
+    /// The index follows the manager as device plugins are initialized and
+    /// cleaned up.
+    pub fn outgoing_capabilities(&self) -> OutgoingCapabilityIndex {
+        self.outgoing_capabilities.clone()
+    }
+
     /// Check if a packet type is supported
     pub fn supports_packet_type(&self, packet_type: &str) -> bool {
         self.capability_map.contains_key(packet_type)
@@ -822,6 +1210,48 @@ impl PluginManager {
         screenshare_plugin.get_stats()
     }
 
+    /// Apply a session lock change to every device's screen share
+    ///
+    /// Locking stops in-flight screen shares; see
+    /// [`screenshare::ScreenSharePlugin::handle_lock_event`].
+    pub async fn handle_session_lock_event(&mut self, event: logind_backend::SessionLockEvent) {
+        for (device_id, plugins) in self.device_plugins.iter_mut() {
+            let Some(screenshare_plugin) = plugins.get_mut("screenshare").and_then(|p| {
+                p.as_any_mut()
+                    .downcast_mut::<screenshare::ScreenSharePlugin>()
+            }) else {
+                continue;
+            };
+
+            if let Err(e) = screenshare_plugin.handle_lock_event(event).await {
+                warn!(
+                    "Failed to apply session lock to screen share of {}: {}",
+                    device_id, e
+                );
+            }
+        }
+    }
+
+    /// Take the last remote volume change of a device
+    ///
+    /// Returns the phone's volume once per change to show on-screen, `None`
+    /// if nothing changed since the last call.
+    pub fn take_device_volume_osd(
+        &mut self,
+        device_id: &str,
+    ) -> Option<systemvolume::VolumeOsdState> {
+        // Get the systemvolume plugin
+        let systemvolume_plugin = self.get_device_plugin_mut(device_id, "systemvolume")?;
+
+        // Downcast to SystemVolumePlugin
+        let systemvolume_plugin = systemvolume_plugin
+            .as_any_mut()
+            .downcast_mut::<systemvolume::SystemVolumePlugin>()?;
+
+        // Take the OSD state
+        systemvolume_plugin.take_volume_osd()
+    }
+
     /// Get number of registered plugins (deprecated)
     ///
     /// Use `factory_count()` to get number of registered factories.
@@ -840,13 +1270,14 @@ impl Default for PluginManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{DeviceInfo, DeviceType};
+    use crate::DeviceType;
 
     // Mock plugin for testing
     struct MockPlugin {
         name: String,
         incoming: Vec<String>,
         outgoing: Vec<String>,
+        priority: i32,
         initialized: bool,
         started: bool,
         packets_handled: usize,
@@ -858,6 +1289,7 @@ mod tests {
                 name: name.to_string(),
                 incoming: incoming.iter().map(|s| s.to_string()).collect(),
                 outgoing: outgoing.iter().map(|s| s.to_string()).collect(),
+                priority: 0,
                 initialized: false,
                 started: false,
                 packets_handled: 0,
@@ -887,11 +1319,20 @@ mod tests {
             self.outgoing.clone()
         }
 
+        fn priority(&self) -> i32 {
+            self.priority
+        }
+
         async fn init(
             &mut self,
-            _device: &Device,
-            _packet_sender: Sender<(String, Packet)>,
+            device: &Device,
+            packet_sender: Sender<(String, Packet)>,
         ) -> Result<()> {
+            // Push connect-time state like real plugins do
+            for packet_type in &self.outgoing {
+                let packet = Packet::new(packet_type.clone(), serde_json::json!({}));
+                let _ = packet_sender.send((device.id().to_string(), packet)).await;
+            }
             self.initialized = true;
             Ok(())
         }
@@ -906,7 +1347,10 @@ mod tests {
             Ok(())
         }
 
-        async fn handle_packet(&mut self, _packet: &Packet, _device: &mut Device) -> Result<()> {
+        async fn handle_packet(&mut self, packet: &Packet, _device: &mut Device) -> Result<()> {
+            if packet.is_type("cconnect.test.hang") {
+                std::future::pending::<()>().await;
+            }
             self.packets_handled += 1;
             Ok(())
         }
@@ -930,6 +1374,7 @@ mod tests {
         name: String,
         incoming: Vec<String>,
         outgoing: Vec<String>,
+        priority: i32,
     }
 
     impl MockPluginFactory {
@@ -938,8 +1383,14 @@ mod tests {
                 name: name.to_string(),
                 incoming: incoming.iter().map(|s| s.to_string()).collect(),
                 outgoing: outgoing.iter().map(|s| s.to_string()).collect(),
+                priority: 0,
             }
         }
+
+        fn with_priority(mut self, priority: i32) -> Self {
+            self.priority = priority;
+            self
+        }
     }
 
     impl PluginFactory for MockPluginFactory {
@@ -958,7 +1409,9 @@ mod tests {
         fn create(&self) -> Box<dyn Plugin> {
             let incoming: Vec<&str> = self.incoming.iter().map(|s| s.as_str()).collect();
             let outgoing: Vec<&str> = self.outgoing.iter().map(|s| s.as_str()).collect();
-            Box::new(MockPlugin::new(&self.name, incoming, outgoing))
+            let mut plugin = MockPlugin::new(&self.name, incoming, outgoing);
+            plugin.priority = self.priority;
+            Box::new(plugin)
         }
     }
 
@@ -1040,6 +1493,77 @@ mod tests {
         assert_eq!(manager.device_plugin_count(device_id), 1);
     }
 
+    #[tokio::test]
+    async fn test_connect_packets_sent_in_plugin_order() {
+        let mut manager = PluginManager::new();
+        for name in ["sftp", "battery", "mpris", "clipboard"] {
+            let packet_type = format!("cconnect.{}", name);
+            manager
+                .register_factory(Arc::new(MockPluginFactory::new(
+                    name,
+                    vec![],
+                    vec![packet_type.as_str()],
+                )))
+                .unwrap();
+        }
+
+        let device = create_test_device();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+        manager
+            .init_device_plugins(device.id(), &device, tx)
+            .await
+            .unwrap();
+
+        let mut sent = Vec::new();
+        while let Ok((_, packet)) = rx.try_recv() {
+            sent.push(packet.packet_type);
+        }
+        assert_eq!(
+            sent,
+            vec![
+                "cconnect.battery",
+                "cconnect.clipboard",
+                "cconnect.mpris",
+                "cconnect.sftp"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_higher_priority_plugin_sends_first() {
+        let mut manager = PluginManager::new();
+        manager
+            .register_factory(Arc::new(MockPluginFactory::new(
+                "aaa",
+                vec![],
+                vec!["cconnect.aaa"],
+            )))
+            .unwrap();
+        manager
+            .register_factory(Arc::new(
+                MockPluginFactory::new("zzz", vec![], vec!["cconnect.zzz"]).with_priority(10),
+            ))
+            .unwrap();
+        manager
+            .register_factory(Arc::new(
+                MockPluginFactory::new("mmm", vec![], vec!["cconnect.mmm"]).with_priority(-5),
+            ))
+            .unwrap();
+
+        let device = create_test_device();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+        manager
+            .init_device_plugins(device.id(), &device, tx)
+            .await
+            .unwrap();
+
+        let mut sent = Vec::new();
+        while let Ok((_, packet)) = rx.try_recv() {
+            sent.push(packet.packet_type);
+        }
+        assert_eq!(sent, vec!["cconnect.zzz", "cconnect.aaa", "cconnect.mmm"]);
+    }
+
     #[tokio::test]
     async fn test_per_device_plugin_cleanup() {
         let mut manager = PluginManager::new();
@@ -1092,6 +1616,120 @@ mod tests {
             .is_ok());
     }
 
+    #[tokio::test]
+    async fn test_handled_packets_are_counted() {
+        let mut manager = PluginManager::new();
+        manager
+            .register_factory(Arc::new(MockPluginFactory::new(
+                "test_plugin",
+                vec!["cconnect.test"],
+                vec![],
+            )))
+            .unwrap();
+
+        let mut device = create_test_device();
+        let device_id = device.id().to_string();
+        assert!(manager.device_plugin_metrics(&device_id).is_empty());
+
+        let (tx, _rx) = tokio::sync::mpsc::channel(100);
+        manager
+            .init_device_plugins(&device_id, &device, tx.clone())
+            .await
+            .unwrap();
+
+        let packet = Packet::new("cconnect.test", serde_json::json!({}));
+        for _ in 0..3 {
+            manager
+                .handle_packet(&device_id, &packet, &mut device)
+                .await
+                .unwrap();
+        }
+
+        // Unhandled packet types are not attributed to any plugin
+        let unknown = Packet::new("cconnect.unknown", serde_json::json!({}));
+        manager
+            .handle_packet(&device_id, &unknown, &mut device)
+            .await
+            .unwrap();
+
+        let metrics = manager.device_plugin_metrics(&device_id);
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics["test_plugin"].packets_handled, 3);
+        assert_eq!(metrics["test_plugin"].errors, 0);
+
+        // Counters survive the device reconnecting
+        manager.cleanup_device_plugins(&device_id).await.unwrap();
+        manager
+            .init_device_plugins(&device_id, &device, tx)
+            .await
+            .unwrap();
+        manager
+            .handle_packet(&device_id, &packet, &mut device)
+            .await
+            .unwrap();
+        assert_eq!(
+            manager.device_plugin_metrics(&device_id)["test_plugin"].packets_handled,
+            4
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hung_handler_is_abandoned() {
+        let mut manager = PluginManager::new();
+        manager.set_handler_timeout(Duration::from_millis(20));
+        manager
+            .register_factory(Arc::new(MockPluginFactory::new(
+                "test_plugin",
+                vec!["cconnect.test", "cconnect.test.hang"],
+                vec![],
+            )))
+            .unwrap();
+
+        let mut device = create_test_device();
+        let device_id = device.id().to_string();
+        let (tx, _rx) = tokio::sync::mpsc::channel(100);
+        manager
+            .init_device_plugins(&device_id, &device, tx)
+            .await
+            .unwrap();
+
+        let hang = Packet::new("cconnect.test.hang", serde_json::json!({}));
+        let packet = Packet::new("cconnect.test", serde_json::json!({}));
+        for _ in 0..MAX_CONSECUTIVE_TIMEOUTS {
+            // Abandoned, and the next packet is still handled
+            manager
+                .handle_packet(&device_id, &hang, &mut device)
+                .await
+                .unwrap();
+        }
+        assert!(matches!(
+            manager.plugin_status(&device_id, "test_plugin"),
+            Some(PluginStatus::Degraded(_))
+        ));
+        assert!(matches!(
+            manager
+                .device_plugin_statuses(&device_id)
+                .get("test_plugin"),
+            Some(PluginStatus::Degraded(_))
+        ));
+
+        manager
+            .handle_packet(&device_id, &packet, &mut device)
+            .await
+            .unwrap();
+        let plugin = manager
+            .get_device_plugin(&device_id, "test_plugin")
+            .and_then(|p| p.as_any().downcast_ref::<MockPlugin>())
+            .unwrap();
+        assert_eq!(plugin.packets_handled, 1);
+        assert_eq!(
+            manager.plugin_status(&device_id, "test_plugin"),
+            Some(PluginStatus::Active)
+        );
+        let metrics = manager.device_plugin_metrics(&device_id);
+        assert_eq!(metrics["test_plugin"].errors, 3);
+    }
+
     #[tokio::test]
     async fn test_multiple_devices_independent_state() {
         let mut manager = PluginManager::new();
@@ -1163,14 +1801,294 @@ mod tests {
             .await
             .unwrap();
 
+        // Types from newer clients are ignored, not treated as errors
+        let packet = Packet::new("kdeconnect.future.thing", serde_json::json!({}));
+        assert!(manager
+            .handle_packet(&device_id, &packet, &mut device)
+            .await
+            .is_ok());
         let packet = Packet::new("cconnect.unsupported", serde_json::json!({}));
-        let result = manager
+        assert!(manager
             .handle_packet(&device_id, &packet, &mut device)
-            .await;
-        assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .to_string()
-            .contains("No plugin handles"));
+            .await
+            .is_ok());
+
+        // ...and never advertised
+        assert!(!manager
+            .get_all_incoming_capabilities()
+            .contains(&"kdeconnect.future.thing".to_string()));
+
+        // Known packets are still routed afterwards
+        let packet = Packet::new("cconnect.test", serde_json::json!({}));
+        manager
+            .handle_packet(&device_id, &packet, &mut device)
+            .await
+            .unwrap();
+        let plugin = manager
+            .get_device_plugin(&device_id, "test_plugin")
+            .and_then(|p| p.as_any().downcast_ref::<MockPlugin>())
+            .unwrap();
+        assert_eq!(plugin.packets_handled, 1);
+    }
+
+    #[tokio::test]
+    async fn test_plugins_share_notifier() {
+        use notifier::MockNotifier;
+
+        let notifier = Arc::new(MockNotifier::new());
+        let mut manager = PluginManager::new();
+        manager.set_notifier(notifier.clone());
+        manager
+            .register_factory(Arc::new(ping::PingPluginFactory))
+            .unwrap();
+        manager
+            .register_factory(Arc::new(telephony::TelephonyPluginFactory))
+            .unwrap();
+
+        let mut device = create_test_device();
+        let device_id = device.id().to_string();
+        let (tx, _rx) = tokio::sync::mpsc::channel(100);
+        manager
+            .init_device_plugins(&device_id, &device, tx)
+            .await
+            .unwrap();
+
+        let ping = Packet::new("cconnect.ping", serde_json::json!({}));
+        manager
+            .handle_packet(&device_id, &ping, &mut device)
+            .await
+            .unwrap();
+        let ringing = Packet::new(
+            "cconnect.telephony",
+            serde_json::json!({ "event": "ringing", "phoneNumber": "+1234567890" }),
+        );
+        manager
+            .handle_packet(&device_id, &ringing, &mut device)
+            .await
+            .unwrap();
+
+        let sent = notifier.sent();
+        assert_eq!(sent.len(), 2);
+        assert!(sent[0].summary.starts_with("Ping from"));
+        assert_eq!(sent[1].summary, "Incoming call");
+
+        // Answering closes the incoming call notification through its handle
+        let talking = Packet::new(
+            "cconnect.telephony",
+            serde_json::json!({ "event": "talking", "phoneNumber": "+1234567890" }),
+        );
+        manager
+            .handle_packet(&device_id, &talking, &mut device)
+            .await
+            .unwrap();
+        let open = notifier.open();
+        assert_eq!(open.len(), 1);
+        assert!(open[0].summary.starts_with("Ping from"));
+    }
+
+    /// Plugin advertising a capability only when its hardware is present
+    struct HardwarePlugin {
+        capability: String,
+        present: bool,
+    }
+
+    #[async_trait]
+    impl Plugin for HardwarePlugin {
+        fn name(&self) -> &str {
+            "hardware"
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+
+        fn incoming_capabilities(&self) -> Vec<String> {
+            vec![self.capability.clone()]
+        }
+
+        fn outgoing_capabilities(&self) -> Vec<String> {
+            vec![self.capability.clone()]
+        }
+
+        async fn init(
+            &mut self,
+            _device: &Device,
+            _packet_sender: Sender<(String, Packet)>,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        async fn start(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn stop(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn handle_packet(&mut self, _packet: &Packet, _device: &mut Device) -> Result<()> {
+            Ok(())
+        }
+
+        async fn augment_identity(&self, identity: &mut DeviceInfo) {
+            for capabilities in [
+                &mut identity.incoming_capabilities,
+                &mut identity.outgoing_capabilities,
+            ] {
+                capabilities.retain(|c| c != &self.capability);
+                if self.present {
+                    capabilities.push(self.capability.clone());
+                }
+            }
+        }
+    }
+
+    struct HardwarePluginFactory {
+        capability: &'static str,
+        /// Whether the factory declares the capability up front
+        declared: bool,
+        present: bool,
+    }
+
+    impl PluginFactory for HardwarePluginFactory {
+        fn name(&self) -> &str {
+            "hardware"
+        }
+
+        fn incoming_capabilities(&self) -> Vec<String> {
+            if self.declared {
+                vec![self.capability.to_string()]
+            } else {
+                Vec::new()
+            }
+        }
+
+        fn outgoing_capabilities(&self) -> Vec<String> {
+            self.incoming_capabilities()
+        }
+
+        fn create(&self) -> Box<dyn Plugin> {
+            Box::new(HardwarePlugin {
+                capability: self.capability.to_string(),
+                present: self.present,
+            })
+        }
+    }
+
+    async fn build_identity(manager: &PluginManager) -> DeviceInfo {
+        let mut identity = DeviceInfo::new("Test Desktop", DeviceType::Desktop, 1716)
+            .with_incoming_capabilities(manager.get_all_incoming_capabilities())
+            .with_outgoing_capabilities(manager.get_all_outgoing_capabilities());
+        manager.augment_identity(&mut identity).await;
+        identity
+    }
+
+    #[tokio::test]
+    async fn test_augment_identity_adds_capability() {
+        let mut manager = PluginManager::new();
+        manager
+            .register_factory(Arc::new(MockPluginFactory::new(
+                "test_plugin",
+                vec!["cconnect.test"],
+                vec![],
+            )))
+            .unwrap();
+        manager
+            .register_factory(Arc::new(HardwarePluginFactory {
+                capability: "cconnect.hardware",
+                declared: false,
+                present: true,
+            }))
+            .unwrap();
+
+        let identity = build_identity(&manager).await;
+
+        // Plugins without the hook leave the identity alone
+        assert!(identity
+            .incoming_capabilities
+            .contains(&"cconnect.test".to_string()));
+        assert!(identity
+            .incoming_capabilities
+            .contains(&"cconnect.hardware".to_string()));
+        assert_eq!(identity.outgoing_capabilities, vec!["cconnect.hardware"]);
+
+        let packet = identity.to_identity_packet();
+        let advertised: Vec<String> = packet.get_body_field("incomingCapabilities").unwrap();
+        assert!(advertised.contains(&"cconnect.hardware".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_augment_identity_opts_out() {
+        let mut manager = PluginManager::new();
+        manager
+            .register_factory(Arc::new(HardwarePluginFactory {
+                capability: "cconnect.battery",
+                declared: true,
+                present: false,
+            }))
+            .unwrap();
+        assert_eq!(
+            manager.get_all_incoming_capabilities(),
+            vec!["cconnect.battery"]
+        );
+
+        let identity = build_identity(&manager).await;
+
+        assert!(identity.incoming_capabilities.is_empty());
+        assert!(identity.outgoing_capabilities.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_undeclared_outgoing_packet_is_flagged() {
+        let mut manager = PluginManager::new();
+        manager
+            .register_factory(Arc::new(MockPluginFactory::new(
+                "keyboard",
+                vec![],
+                vec!["cconnect.mousepad.keyboardstate"],
+            )))
+            .unwrap();
+
+        let mut device = create_test_device();
+        device.info.incoming_capabilities = vec!["kdeconnect.mousepad.keyboardstate".to_string()];
+        let (tx, _rx) = tokio::sync::mpsc::channel(100);
+        manager
+            .init_device_plugins(device.id(), &device, tx)
+            .await
+            .unwrap();
+
+        let declared = Packet::new("cconnect.mousepad.keyboardstate", serde_json::json!({}));
+        assert_eq!(manager.validate_outgoing_packet(&device, &declared), None);
+
+        let undeclared = Packet::new("cconnect.mousepad.request", serde_json::json!({}));
+        assert_eq!(
+            manager.validate_outgoing_packet(&device, &undeclared),
+            Some(OutgoingCapabilityMismatch::Undeclared)
+        );
+
+        device.info.incoming_capabilities = vec!["cconnect.ping".to_string()];
+        assert_eq!(
+            manager.validate_outgoing_packet(&device, &declared),
+            Some(OutgoingCapabilityMismatch::NotAccepted)
+        );
+
+        let internal = Packet::new("cconnect.internal.test", serde_json::json!({}));
+        assert_eq!(manager.validate_outgoing_packet(&device, &internal), None);
+
+        // The index checks against the capabilities from initialization,
+        // without the manager
+        let index = manager.outgoing_capabilities();
+        assert_eq!(
+            index.validate(device.id(), &undeclared),
+            Some(OutgoingCapabilityMismatch::Undeclared)
+        );
+        assert_eq!(index.validate(device.id(), &declared), None);
+
+        manager.cleanup_device_plugins(device.id()).await.unwrap();
+        assert_eq!(index.validate(device.id(), &undeclared), None);
     }
 }
@@ -22,14 +22,36 @@
 //!     "type": "cconnect.mpris",
 //!     "body": {
 //!         "playerList": ["vlc", "spotify"],
-//!         "supportAlbumArtPayload": true
+//!         "supportAlbumArtPayload": true,
+//!         "playerCapabilities": {
+//!             "vlc": {
+//!                 "canPlay": true,
+//!                 "canPause": true,
+//!                 "canGoNext": true,
+//!                 "canGoPrevious": true,
+//!                 "canSeek": true
+//!             },
+//!             "spotify": {
+//!                 "canPlay": true,
+//!                 "canPause": true,
+//!                 "canGoNext": true,
+//!                 "canGoPrevious": true,
+//!                 "canSeek": false
+//!             }
+//!         }
 //!     }
 //! }
 //! ```
 //!
+//! `playerCapabilities` lets the remote enable only the controls each player
+//! supports before it has asked for the player's status.
+//!
 //! ## Player Status
 //!
-//! Report current playback state and position:
+//! Report current playback state and position. `pos` and `length` are in
+//! milliseconds, `volume` is 0-100. Players on this desktop report their
+//! state the same way, so the phone can show and control them from its lock
+//! screen:
 //!
 //! ```json
 //! {
@@ -98,6 +120,25 @@
 //!
 //! Actions: "Play", "Pause", "PlayPause", "Stop", "Next", "Previous"
 //!
+//! ### Headset Buttons
+//!
+//! Buttons pressed on a headset connected to the phone are forwarded as
+//! transport commands. They name no player and control the active player of
+//! the receiving device:
+//!
+//! ```json
+//! {
+//!     "id": 1234567890,
+//!     "type": "cconnect.mpris.request",
+//!     "body": {
+//!         "transport": "KEYCODE_MEDIA_NEXT"
+//!     }
+//! }
+//! ```
+//!
+//! Transport commands: the Android media key names for play/pause (including
+//! `KEYCODE_HEADSETHOOK`), play, pause, stop, next and previous.
+//!
 //! ### Seek
 //!
 //! ```json
@@ -159,6 +200,19 @@
 //!
 //! ## Album Art Transfer
 //!
+//! The remote asks for the art named by `albumArtUrl` in the player status:
+//!
+//! ```json
+//! {
+//!     "id": 1234567890,
+//!     "type": "cconnect.mpris.request",
+//!     "body": {
+//!         "player": "spotify",
+//!         "albumArtUrl": "file:///path/to/art.jpg"
+//!     }
+//! }
+//! ```
+//!
 //! Album art is transferred via TCP payload:
 //!
 //! ```json
@@ -167,7 +221,8 @@
 //!     "type": "cconnect.mpris",
 //!     "body": {
 //!         "transferringAlbumArt": true,
-//!         "player": "spotify"
+//!         "player": "spotify",
+//!         "albumArtUrl": "file:///path/to/art.jpg"
 //!     },
 //!     "payloadSize": 204800,
 //!     "payloadTransferInfo": {
@@ -275,17 +330,19 @@
 //! - [Valent Protocol Documentation](https://valent.andyholmes.ca/documentation/protocol.html)
 //! - [MPRIS2 Specification](https://specifications.freedesktop.org/mpris-spec/latest/)
 
+use crate::payload::PayloadServer;
 use crate::{Device, Packet, ProtocolError, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
-use super::mpris_backend::MprisBackend;
+use super::mpris_backend::{self, MprisBackend};
 use super::{Plugin, PluginFactory};
 
 /// Loop status for media playback
@@ -313,11 +370,14 @@ impl LoopStatus {
     }
 
     /// Parse loop status from string
-    pub fn parse_str(s: &str) -> Self {
+    ///
+    /// Returns `None` for values not defined by the protocol.
+    pub fn parse_str(s: &str) -> Option<Self> {
         match s {
-            "Track" => Self::Track,
-            "Playlist" => Self::Playlist,
-            _ => Self::None,
+            "None" => Some(Self::None),
+            "Track" => Some(Self::Track),
+            "Playlist" => Some(Self::Playlist),
+            _ => None,
         }
     }
 }
@@ -371,7 +431,8 @@ impl PlaybackAction {
 /// Media player capabilities
 ///
 /// Indicates which operations the player supports.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct PlayerCapabilities {
     /// Can start playback
     pub can_play: bool,
@@ -385,6 +446,19 @@ pub struct PlayerCapabilities {
     pub can_seek: bool,
 }
 
+impl PlayerCapabilities {
+    /// Capabilities of a local MPRIS2 player
+    pub fn from_backend_state(state: &mpris_backend::PlayerState) -> Self {
+        Self {
+            can_play: state.can_play,
+            can_pause: state.can_pause,
+            can_go_next: state.can_go_next,
+            can_go_previous: state.can_go_previous,
+            can_seek: state.can_seek,
+        }
+    }
+}
+
 impl Default for PlayerCapabilities {
     fn default() -> Self {
         Self {
@@ -495,6 +569,165 @@ pub struct PlayerState {
     pub metadata: PlayerMetadata,
 }
 
+/// Player list body of a `cconnect.mpris` packet
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MprisPlayerList {
+    /// Available player names
+    pub player_list: Vec<String>,
+    /// Whether album art can be sent as a payload
+    pub support_album_art_payload: bool,
+    /// Operations each player supports, by player name
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub player_capabilities: HashMap<String, PlayerCapabilities>,
+}
+
+/// Now-playing body of a `cconnect.mpris` packet
+///
+/// Positions and lengths are in milliseconds, volume is 0-100.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MprisNowPlaying {
+    /// Player name/identifier
+    pub player: String,
+    /// Currently playing
+    pub is_playing: bool,
+    /// Current position in milliseconds
+    pub pos: i64,
+    /// Track length in milliseconds
+    pub length: i64,
+    /// Volume (0-100)
+    pub volume: i32,
+    /// Loop/repeat status
+    pub loop_status: LoopStatus,
+    /// Shuffle enabled
+    pub shuffle: bool,
+    /// Can start playback
+    pub can_play: bool,
+    /// Can pause playback
+    pub can_pause: bool,
+    /// Can skip to next track
+    pub can_go_next: bool,
+    /// Can skip to previous track
+    pub can_go_previous: bool,
+    /// Can seek within track
+    pub can_seek: bool,
+    /// "Artist - Title", for clients that predate the separate fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub now_playing: Option<String>,
+    /// Track title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Track artist
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artist: Option<String>,
+    /// Album name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub album: Option<String>,
+    /// Album art URL/path
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub album_art_url: Option<String>,
+}
+
+impl MprisNowPlaying {
+    /// Build from protocol status and metadata
+    pub fn new(player: impl Into<String>, status: PlayerStatus, metadata: PlayerMetadata) -> Self {
+        let now_playing = match (&metadata.artist, &metadata.title) {
+            (Some(artist), Some(title)) => Some(format!("{} - {}", artist, title)),
+            (None, Some(title)) => Some(title.clone()),
+            _ => None,
+        };
+
+        Self {
+            player: player.into(),
+            is_playing: status.is_playing,
+            pos: status.position,
+            length: status.length,
+            volume: status.volume,
+            loop_status: status.loop_status,
+            shuffle: status.shuffle,
+            can_play: status.capabilities.can_play,
+            can_pause: status.capabilities.can_pause,
+            can_go_next: status.capabilities.can_go_next,
+            can_go_previous: status.capabilities.can_go_previous,
+            can_seek: status.capabilities.can_seek,
+            now_playing,
+            title: metadata.title,
+            artist: metadata.artist,
+            album: metadata.album,
+            album_art_url: metadata.album_art_url,
+        }
+    }
+
+    /// Build from a tracked player state
+    pub fn from_state(state: &PlayerState) -> Self {
+        Self::new(
+            state.name.clone(),
+            state.status.clone(),
+            state.metadata.clone(),
+        )
+    }
+
+    /// Build from a local MPRIS2 player state
+    ///
+    /// Converts MPRIS2 microseconds to milliseconds and volume from 0.0-1.0
+    /// to 0-100.
+    pub fn from_backend_state(
+        player: impl Into<String>,
+        state: &mpris_backend::PlayerState,
+    ) -> Self {
+        let status = PlayerStatus {
+            is_playing: state.playback_status.is_playing(),
+            position: state.position / 1000,
+            length: state.metadata.length / 1000,
+            volume: (state.volume * 100.0).round() as i32,
+            loop_status: LoopStatus::parse_str(&state.loop_status).unwrap_or_default(),
+            shuffle: state.shuffle,
+            capabilities: PlayerCapabilities::from_backend_state(state),
+        };
+        let metadata = PlayerMetadata {
+            artist: state.metadata.artist.clone(),
+            title: state.metadata.title.clone(),
+            album: state.metadata.album.clone(),
+            album_art_url: state.metadata.album_art_url.clone(),
+        };
+
+        Self::new(player, status, metadata)
+    }
+}
+
+/// Local file behind a `file://` album art URL
+///
+/// Only local art can be sent as a payload; other URLs give `None`.
+/// Percent-escapes in the path are decoded.
+pub fn album_art_path(url: &str) -> Option<PathBuf> {
+    let path = url.strip_prefix("file://")?;
+
+    // Percent-decode the path
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut rest = path.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let decoded = match (byte, tail) {
+            (b'%', [high, low, ..]) => std::str::from_utf8(&[*high, *low])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+        match decoded {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+
+    String::from_utf8(bytes).ok().map(PathBuf::from)
+}
+
 /// MPRIS plugin for media player control
 ///
 /// Handles `cconnect.mpris` packets for controlling and monitoring media
@@ -589,13 +822,43 @@ impl MprisPlugin {
     /// assert_eq!(packet.packet_type, "cconnect.mpris");
     /// ```
     pub fn create_player_list_packet(&self, players: Vec<String>) -> Packet {
-        Packet::new(
-            "cconnect.mpris",
-            json!({
-                "playerList": players,
-                "supportAlbumArtPayload": self.support_album_art
-            }),
-        )
+        let body = MprisPlayerList {
+            player_list: players,
+            support_album_art_payload: self.support_album_art,
+            player_capabilities: HashMap::new(),
+        };
+        Packet::new("cconnect.mpris", json!(body))
+    }
+
+    /// Create a player list packet with each player's capabilities
+    ///
+    /// Like [`create_player_list_packet`](Self::create_player_list_packet),
+    /// but also reports which operations each player supports so the remote
+    /// can disable controls up front.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cosmic_connect_core::plugins::mpris::*;
+    ///
+    /// let plugin = MprisPlugin::new();
+    /// let state = PlayerState {
+    ///     name: "vlc".to_string(),
+    ///     ..Default::default()
+    /// };
+    /// let packet = plugin.create_player_list_packet_with_capabilities(&[state]);
+    /// assert!(packet.body["playerCapabilities"]["vlc"]["canSeek"].is_boolean());
+    /// ```
+    pub fn create_player_list_packet_with_capabilities(&self, players: &[PlayerState]) -> Packet {
+        let body = MprisPlayerList {
+            player_list: players.iter().map(|p| p.name.clone()).collect(),
+            support_album_art_payload: self.support_album_art,
+            player_capabilities: players
+                .iter()
+                .map(|p| (p.name.clone(), p.status.capabilities.clone()))
+                .collect(),
+        };
+        Packet::new("cconnect.mpris", json!(body))
     }
 
     /// Create a player status packet
@@ -641,36 +904,8 @@ impl MprisPlugin {
         status: PlayerStatus,
         metadata: PlayerMetadata,
     ) -> Packet {
-        let mut body = json!({
-            "player": player,
-            "isPlaying": status.is_playing,
-            "pos": status.position,
-            "length": status.length,
-            "volume": status.volume,
-            "loopStatus": status.loop_status.as_str(),
-            "shuffle": status.shuffle,
-            "canPlay": status.capabilities.can_play,
-            "canPause": status.capabilities.can_pause,
-            "canGoNext": status.capabilities.can_go_next,
-            "canGoPrevious": status.capabilities.can_go_previous,
-            "canSeek": status.capabilities.can_seek,
-        });
-
-        // Add optional metadata fields
-        if let Some(artist) = metadata.artist {
-            body["artist"] = json!(artist);
-        }
-        if let Some(title) = metadata.title {
-            body["title"] = json!(title);
-        }
-        if let Some(album) = metadata.album {
-            body["album"] = json!(album);
-        }
-        if let Some(album_art_url) = metadata.album_art_url {
-            body["albumArtUrl"] = json!(album_art_url);
-        }
-
-        Packet::new("cconnect.mpris", body)
+        let body = MprisNowPlaying::new(player, status, metadata);
+        Packet::new("cconnect.mpris", json!(body))
     }
 
     /// Create a request player list packet
@@ -1147,8 +1382,8 @@ impl MprisPlugin {
                 .body
                 .get("loopStatus")
                 .and_then(|v| v.as_str())
-                .map(LoopStatus::parse_str)
-                .unwrap_or(LoopStatus::None),
+                .and_then(LoopStatus::parse_str)
+                .unwrap_or_default(),
             shuffle: packet
                 .body
                 .get("shuffle")
@@ -1257,6 +1492,17 @@ impl MprisPlugin {
             return self.send_now_playing(player).await;
         }
 
+        // Handle album art request
+        if let Some(url) = packet.body.get("albumArtUrl").and_then(|v| v.as_str()) {
+            info!(
+                "Received album art request from {} ({}) for player: {}",
+                device.name(),
+                device.id(),
+                player
+            );
+            return self.send_album_art(player, url).await;
+        }
+
         // Handle playback control action
         if let Some(action) = packet.body.get("action").and_then(|v| v.as_str()) {
             info!(
@@ -1312,19 +1558,26 @@ impl MprisPlugin {
             return Ok(());
         }
 
-        // Handle set loop status
+        // Handle set loop status and shuffle, which may arrive together
         if let Some(loop_status) = packet.body.get("setLoopStatus").and_then(|v| v.as_str()) {
             info!(
                 "Received set loop status request for {} (status: {})",
                 player, loop_status
             );
-            if let Err(e) = self.backend.set_loop_status(player, loop_status).await {
-                warn!("Failed to set loop status on {}: {}", player, e);
+            match LoopStatus::parse_str(loop_status) {
+                Some(loop_status) => {
+                    if let Err(e) = self
+                        .backend
+                        .set_loop_status(player, loop_status.as_str())
+                        .await
+                    {
+                        warn!("Failed to set loop status on {}: {}", player, e);
+                    }
+                }
+                None => warn!("Ignoring unknown loop status '{}'", loop_status),
             }
-            return Ok(());
         }
 
-        // Handle set shuffle
         if let Some(shuffle) = packet.body.get("setShuffle").and_then(|v| v.as_bool()) {
             info!(
                 "Received set shuffle request for {} (shuffle: {})",
@@ -1333,7 +1586,6 @@ impl MprisPlugin {
             if let Err(e) = self.backend.set_shuffle(player, shuffle).await {
                 warn!("Failed to set shuffle on {}: {}", player, e);
             }
-            return Ok(());
         }
 
         Ok(())
@@ -1364,8 +1616,27 @@ impl MprisPlugin {
             vec![]
         });
 
-        info!("Sending player list: {:?}", players);
-        let packet = self.create_player_list_packet(players);
+        let mut states = Vec::with_capacity(players.len());
+        for name in players {
+            let mut state = PlayerState {
+                name,
+                ..Default::default()
+            };
+            match self.backend.query_player_state(&state.name).await {
+                Ok(backend_state) => {
+                    state.status.capabilities =
+                        PlayerCapabilities::from_backend_state(&backend_state);
+                }
+                Err(e) => warn!("Failed to query capabilities of {}: {}", state.name, e),
+            }
+            states.push(state);
+        }
+
+        info!(
+            "Sending player list: {:?}",
+            states.iter().map(|s| &s.name).collect::<Vec<_>>()
+        );
+        let packet = self.create_player_list_packet_with_capabilities(&states);
         self.send_packet(packet).await
     }
 
@@ -1379,40 +1650,80 @@ impl MprisPlugin {
             }
         };
 
-        // Convert backend state to protocol types (microseconds to milliseconds)
-        let status = PlayerStatus {
-            is_playing: state.playback_status.is_playing(),
-            position: state.position / 1000,
-            length: state.metadata.length / 1000,
-            volume: (state.volume * 100.0) as i32,
-            loop_status: LoopStatus::parse_str(&state.loop_status),
-            shuffle: state.shuffle,
-            capabilities: PlayerCapabilities {
-                can_play: state.can_play,
-                can_pause: state.can_pause,
-                can_go_next: state.can_go_next,
-                can_go_previous: state.can_go_previous,
-                can_seek: state.can_seek,
-            },
-        };
-
-        let metadata = PlayerMetadata {
-            artist: state.metadata.artist,
-            title: state.metadata.title,
-            album: state.metadata.album,
-            album_art_url: state.metadata.album_art_url,
-        };
+        let now_playing = MprisNowPlaying::from_backend_state(player, &state);
 
         info!(
-            "Sending now playing for {}: {} - {}",
+            "Sending now playing for {}: {}",
             player,
-            metadata.artist.as_deref().unwrap_or("Unknown"),
-            metadata.title.as_deref().unwrap_or("Unknown")
+            now_playing.now_playing.as_deref().unwrap_or("Unknown")
         );
 
-        let packet = self.create_status_packet(player.to_string(), status, metadata);
+        let packet = Packet::new("cconnect.mpris", json!(now_playing));
         self.send_packet(packet).await
     }
+
+    /// Create an album art packet
+    ///
+    /// The art itself follows as a payload served on `port`.
+    fn create_album_art_packet(player: &str, url: &str, size: u64, port: u16) -> Packet {
+        let transfer_info = HashMap::from([("port".to_string(), json!(port))]);
+
+        Packet::new(
+            "cconnect.mpris",
+            json!({
+                "player": player,
+                "albumArtUrl": url,
+                "transferringAlbumArt": true,
+            }),
+        )
+        .with_payload_size(size as i64)
+        .with_payload_transfer_info(transfer_info)
+    }
+
+    /// Send the album art of a local player's current track
+    ///
+    /// Only the art the player currently reports is sent, so the remote
+    /// cannot read arbitrary files.
+    async fn send_album_art(&mut self, player: &str, url: &str) -> Result<()> {
+        let state = match self.backend.query_player_state(player).await {
+            Ok(s) => s,
+            Err(e) => {
+                warn!("Failed to query player state for {}: {}", player, e);
+                return Ok(());
+            }
+        };
+
+        if state.metadata.album_art_url.as_deref() != Some(url) {
+            warn!(
+                "Album art {} requested for {} is not its current art",
+                url, player
+            );
+            return Ok(());
+        }
+
+        let Some(path) = album_art_path(url) else {
+            debug!("Album art {} of {} is not a local file", url, player);
+            return Ok(());
+        };
+
+        let size = std::fs::metadata(&path)
+            .map_err(|e| ProtocolError::from_io_error(e, "Failed to read album art"))?
+            .len();
+
+        let server = PayloadServer::new().await.map_err(|e| {
+            ProtocolError::Plugin(format!("Failed to create payload server: {}", e))
+        })?;
+        let packet = Self::create_album_art_packet(player, url, size, server.port());
+        self.send_packet(packet).await?;
+
+        tokio::spawn(async move {
+            if let Err(e) = server.send_file(&path).await {
+                warn!("Album art transfer failed: {}", e);
+            }
+        });
+
+        Ok(())
+    }
 }
 
 impl Default for MprisPlugin {
@@ -1560,10 +1871,13 @@ mod tests {
         assert_eq!(LoopStatus::Track.as_str(), "Track");
         assert_eq!(LoopStatus::Playlist.as_str(), "Playlist");
 
-        assert_eq!(LoopStatus::parse_str("None"), LoopStatus::None);
-        assert_eq!(LoopStatus::parse_str("Track"), LoopStatus::Track);
-        assert_eq!(LoopStatus::parse_str("Playlist"), LoopStatus::Playlist);
-        assert_eq!(LoopStatus::parse_str("invalid"), LoopStatus::None);
+        assert_eq!(LoopStatus::parse_str("None"), Some(LoopStatus::None));
+        assert_eq!(LoopStatus::parse_str("Track"), Some(LoopStatus::Track));
+        assert_eq!(
+            LoopStatus::parse_str("Playlist"),
+            Some(LoopStatus::Playlist)
+        );
+        assert_eq!(LoopStatus::parse_str("invalid"), None);
     }
 
     #[test]
@@ -1632,6 +1946,43 @@ mod tests {
         assert_eq!(player_list.len(), 2);
     }
 
+    #[test]
+    fn test_player_list_includes_capabilities() {
+        let plugin = MprisPlugin::new();
+        let seekable = PlayerState {
+            name: "vlc".to_string(),
+            ..Default::default()
+        };
+        let radio = PlayerState {
+            name: "radio".to_string(),
+            status: PlayerStatus {
+                capabilities: PlayerCapabilities {
+                    can_go_next: false,
+                    can_go_previous: false,
+                    can_seek: false,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let packet = plugin.create_player_list_packet_with_capabilities(&[seekable, radio]);
+        let body: MprisPlayerList = serde_json::from_value(packet.body.clone()).unwrap();
+
+        assert_eq!(body.player_list, vec!["vlc", "radio"]);
+        assert_eq!(
+            body.player_capabilities["vlc"],
+            PlayerCapabilities::default()
+        );
+        let radio_caps = &packet.body["playerCapabilities"]["radio"];
+        assert_eq!(radio_caps["canPlay"], json!(true));
+        assert_eq!(radio_caps["canPause"], json!(true));
+        assert_eq!(radio_caps["canGoNext"], json!(false));
+        assert_eq!(radio_caps["canGoPrevious"], json!(false));
+        assert_eq!(radio_caps["canSeek"], json!(false));
+    }
+
     #[test]
     fn test_create_status_packet() {
         let plugin = MprisPlugin::new();
@@ -1640,6 +1991,8 @@ mod tests {
             position: 30000,
             length: 180000,
             volume: 75,
+            loop_status: LoopStatus::Track,
+            shuffle: true,
             ..Default::default()
         };
         let metadata = PlayerMetadata {
@@ -1666,6 +2019,14 @@ mod tests {
             Some(180000)
         );
         assert_eq!(packet.body.get("volume").and_then(|v| v.as_i64()), Some(75));
+        assert_eq!(
+            packet.body.get("loopStatus").and_then(|v| v.as_str()),
+            Some("Track")
+        );
+        assert_eq!(
+            packet.body.get("shuffle").and_then(|v| v.as_bool()),
+            Some(true)
+        );
         assert_eq!(
             packet.body.get("artist").and_then(|v| v.as_str()),
             Some("Artist")
@@ -1676,6 +2037,137 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_now_playing_from_backend_state() {
+        let state = mpris_backend::PlayerState {
+            name: "spotify".to_string(),
+            playback_status: mpris_backend::PlaybackStatus::Playing,
+            position: 30_000_000,
+            volume: 0.75,
+            loop_status: "Playlist".to_string(),
+            shuffle: true,
+            can_play: true,
+            can_pause: true,
+            metadata: mpris_backend::PlayerMetadata {
+                artist: Some("Artist".to_string()),
+                title: Some("Title".to_string()),
+                album: Some("Album".to_string()),
+                length: 180_000_000,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let body = json!(MprisNowPlaying::from_backend_state("spotify", &state));
+
+        assert_eq!(
+            body,
+            json!({
+                "player": "spotify",
+                "isPlaying": true,
+                "pos": 30000,
+                "length": 180000,
+                "volume": 75,
+                "loopStatus": "Playlist",
+                "shuffle": true,
+                "canPlay": true,
+                "canPause": true,
+                "canGoNext": false,
+                "canGoPrevious": false,
+                "canSeek": false,
+                "nowPlaying": "Artist - Title",
+                "title": "Title",
+                "artist": "Artist",
+                "album": "Album",
+            })
+        );
+    }
+
+    #[test]
+    fn test_full_player_state_now_playing_packet() {
+        let state = PlayerState {
+            name: "spotify".to_string(),
+            status: PlayerStatus {
+                is_playing: true,
+                position: 45_000,
+                length: 180_000,
+                volume: 80,
+                loop_status: LoopStatus::Playlist,
+                shuffle: true,
+                capabilities: PlayerCapabilities::default(),
+            },
+            metadata: PlayerMetadata {
+                artist: Some("Artist".to_string()),
+                title: Some("Title".to_string()),
+                album: Some("Album".to_string()),
+                album_art_url: Some("file:///tmp/art.png".to_string()),
+            },
+        };
+
+        let packet = Packet::new("cconnect.mpris", json!(MprisNowPlaying::from_state(&state)));
+
+        assert_eq!(packet.packet_type, "cconnect.mpris");
+        assert_eq!(
+            packet.body,
+            json!({
+                "player": "spotify",
+                "isPlaying": true,
+                "pos": 45000,
+                "length": 180000,
+                "volume": 80,
+                "loopStatus": "Playlist",
+                "shuffle": true,
+                "canPlay": true,
+                "canPause": true,
+                "canGoNext": true,
+                "canGoPrevious": true,
+                "canSeek": true,
+                "nowPlaying": "Artist - Title",
+                "title": "Title",
+                "artist": "Artist",
+                "album": "Album",
+                "albumArtUrl": "file:///tmp/art.png",
+            })
+        );
+    }
+
+    #[test]
+    fn test_album_art_path() {
+        assert_eq!(
+            album_art_path("file:///home/user/Music/My%20Album/cover.jpg"),
+            Some(PathBuf::from("/home/user/Music/My Album/cover.jpg"))
+        );
+        assert_eq!(
+            album_art_path("file:///tmp/100%"),
+            Some(PathBuf::from("/tmp/100%"))
+        );
+        assert_eq!(album_art_path("https://example.com/cover.jpg"), None);
+    }
+
+    #[test]
+    fn test_album_art_packet() {
+        let packet = MprisPlugin::create_album_art_packet("vlc", "file:///tmp/art.png", 2048, 1739);
+
+        assert_eq!(packet.body["player"], "vlc");
+        assert_eq!(packet.body["albumArtUrl"], "file:///tmp/art.png");
+        assert_eq!(packet.body["transferringAlbumArt"], true);
+        assert_eq!(packet.payload_size, Some(2048));
+        assert_eq!(packet.payload_transfer_info.unwrap()["port"], 1739);
+    }
+
+    #[test]
+    fn test_player_list_body() {
+        let plugin = MprisPlugin::new();
+        let packet = plugin.create_player_list_packet(vec!["vlc".to_string()]);
+
+        assert_eq!(
+            packet.body,
+            json!({ "playerList": ["vlc"], "supportAlbumArtPayload": true })
+        );
+        let list: MprisPlayerList = serde_json::from_value(packet.body).unwrap();
+        assert_eq!(list.player_list, vec!["vlc".to_string()]);
+    }
+
     #[test]
     fn test_create_request_packets() {
         let plugin = MprisPlugin::new();
@@ -26,7 +26,7 @@
 
 use std::collections::HashMap;
 use tracing::{debug, info, warn};
-use zbus::zvariant::{ObjectPath, OwnedValue};
+use zbus::zvariant::{ObjectPath, OwnedValue, Value};
 use zbus::Connection;
 
 /// MPRIS2 DBus interface names
@@ -232,16 +232,18 @@ impl MprisBackend {
                 .and_then(|v| <&str>::try_from(v).ok())
                 .map(String::from)
         };
+        // Helper to extract text fields, which may be string lists
+        let get_text = |key: &str| metadata_dict.get(key).and_then(|v| metadata_text(v));
 
         PlayerMetadata {
-            artist: get_string("xesam:artist"),
-            title: get_string("xesam:title"),
-            album: get_string("xesam:album"),
+            artist: get_text("xesam:artist"),
+            title: get_text("xesam:title"),
+            album: get_text("xesam:album"),
             album_art_url: get_string("mpris:artUrl"),
             track_id: get_string("mpris:trackid"),
             length: metadata_dict
                 .get("mpris:length")
-                .and_then(|v| i64::try_from(v).ok())
+                .and_then(|v| metadata_length(v))
                 .unwrap_or(0),
         }
     }
@@ -419,6 +421,31 @@ impl Default for MprisBackend {
     }
 }
 
+/// Read a text metadata field
+///
+/// `xesam:artist` and similar fields are string lists, joined with ", ".
+fn metadata_text(value: &Value<'_>) -> Option<String> {
+    let text = match value {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|item| <&str>::try_from(item).ok())
+            .collect::<Vec<_>>()
+            .join(", "),
+        value => <&str>::try_from(value).ok()?.to_string(),
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+/// Read `mpris:length`, which players send as either a signed or an
+/// unsigned integer of microseconds
+fn metadata_length(value: &Value<'_>) -> Option<i64> {
+    i64::try_from(value).ok().or_else(|| {
+        u64::try_from(value)
+            .ok()
+            .and_then(|l| i64::try_from(l).ok())
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -447,6 +474,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_metadata_text() {
+        assert_eq!(
+            metadata_text(&Value::from("Title")),
+            Some("Title".to_string())
+        );
+        assert_eq!(
+            metadata_text(&Value::from(vec!["Artist", "Featured"])),
+            Some("Artist, Featured".to_string())
+        );
+        assert_eq!(metadata_text(&Value::from(Vec::<&str>::new())), None);
+        assert_eq!(metadata_text(&Value::from(42i64)), None);
+    }
+
+    #[test]
+    fn test_metadata_length() {
+        assert_eq!(
+            metadata_length(&Value::from(180_000_000i64)),
+            Some(180_000_000)
+        );
+        assert_eq!(
+            metadata_length(&Value::from(180_000_000u64)),
+            Some(180_000_000)
+        );
+        assert_eq!(metadata_length(&Value::from(true)), None);
+    }
+
     #[test]
     fn test_backend_new() {
         let backend = MprisBackend::new();
@@ -7,9 +7,13 @@
 //! **Packet Types**:
 //! - `kdeconnect.sftp` - SFTP connection details (incoming)
 //! - `cconnect.sftp` - COSMIC Connect SFTP details (incoming)
+//! - `cconnect.sftp.open` - Open a local path in the file manager (incoming)
+//! - `kdeconnect.sftp.request` - Ask the device to start its SFTP server (outgoing)
 //!
 //! **Capabilities**:
 //! - Incoming: `kdeconnect.sftp`, `cconnect.sftp` - Receive SFTP connection info
+//! - Incoming: `cconnect.sftp.open` - Open a desktop folder or file from the phone
+//! - Outgoing: `kdeconnect.sftp.request` - Browse the device's files
 //!
 //! ## Packet Format
 //!
@@ -29,10 +33,50 @@
 //!
 //! ## Behavior
 //!
-//! When this packet is received, the desktop client should mount the remote filesystem
-//! using sshfs.
+//! [`NetworkSharePlugin::browse`] sends `{"startBrowsing": true}` and the
+//! device answers with the packet above. What happens next depends on the
+//! device's auto-mount setting:
 //!
-//! `sshfs -p <port> <user>@<ip>:/ <mountpoint> -o password_stdin`
+//! - **Auto-mount** (default): the filesystem is mounted with sshfs under the
+//!   runtime directory and opened in the file manager, then reported as
+//!   `cconnect.internal.sftp.mounted`.
+//! - **Manual**: nothing is mounted; the connection details and the mount
+//!   command are reported as `cconnect.internal.sftp.credentials`, see
+//!   [`SftpEvent`]. The password is not part of the event, it can be looked
+//!   up with [`NetworkSharePlugin::get_share`].
+//!
+//! Connection details the device sends without a browse request are only
+//! stored.
+//!
+//! `sshfs -p <port> -o password_stdin -- <user>@<ip>:/ <mountpoint>`
+//!
+//! The details end up on the sshfs command line, so they are validated
+//! first (see [`SftpInfo::validate`]): `ip` must be an IP address and `user`
+//! a plain user name, so neither can be parsed as an ssh option.
+//!
+//! Mounts are removed with [`NetworkSharePlugin::unmount`]. When the device
+//! disconnects, remaining mounts are lazily unmounted so they don't go stale.
+//!
+//! ## Opening Desktop Paths
+//!
+//! While browsing the desktop's files, the phone can ask to open one of them
+//! on the desktop:
+//!
+//! ```json
+//! {
+//!     "id": 1234567890,
+//!     "type": "cconnect.sftp.open",
+//!     "body": {
+//!         "path": "/home/user/Documents"
+//!     }
+//! }
+//! ```
+//!
+//! The path must exist and, after resolving `..` and symlinks, lie inside one
+//! of the allowed roots (the home directory by default). Folders are opened
+//! in the file manager with `xdg-open`. Files are never opened themselves,
+//! since the phone could share a file and then have it run; the folder
+//! containing them is opened instead.
 //!
 //! ## Public API
 //!
@@ -57,13 +101,19 @@
 //!
 //! - [KDE Connect SFTP Plugin](https://invent.kde.org/network/kdeconnect-kde/-/tree/master/plugins/sftp)
 
-use crate::{Device, Packet, Result};
+use crate::{Device, Packet, ProtocolError, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::any::Any;
 use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{mpsc, RwLock};
 use tracing::{debug, info, warn};
 
 use super::{Plugin, PluginFactory};
@@ -72,6 +122,241 @@ use super::{Plugin, PluginFactory};
 pub const PACKET_TYPE_SFTP: &str = "kdeconnect.sftp";
 pub const PACKET_TYPE_CCONNECT_SFTP: &str = "cconnect.sftp";
 
+/// Packet type asking to open a local path in the file manager
+pub const PACKET_TYPE_SFTP_OPEN: &str = "cconnect.sftp.open";
+
+/// Packet type asking the device to start its SFTP server (outgoing)
+pub const PACKET_TYPE_SFTP_REQUEST: &str = "kdeconnect.sftp.request";
+
+/// Internal packet type reporting credentials for mounting manually
+pub const PACKET_TYPE_SFTP_CREDENTIALS: &str = "cconnect.internal.sftp.credentials";
+
+/// Internal packet type reporting a mounted device filesystem
+pub const PACKET_TYPE_SFTP_MOUNTED: &str = "cconnect.internal.sftp.mounted";
+
+/// Opens local folders for the user
+pub trait PathOpener: Send + Sync {
+    /// Open a folder in the file manager
+    fn open(&self, path: &Path) -> Result<()>;
+}
+
+/// Opens folders with `xdg-open`
+#[derive(Debug, Default)]
+pub struct XdgOpener;
+
+impl PathOpener for XdgOpener {
+    fn open(&self, path: &Path) -> Result<()> {
+        // xdg-open runs files with their default handler
+        if !path.is_dir() {
+            return Err(crate::ProtocolError::InvalidPacket(format!(
+                "Not a folder: {}",
+                path.display()
+            )));
+        }
+
+        // Not awaited: the file manager may keep running, tokio reaps the child
+        tokio::process::Command::new("xdg-open")
+            .arg(path)
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| crate::ProtocolError::Plugin(format!("Failed to run xdg-open: {}", e)))
+    }
+}
+
+/// Check that a path requested by a remote device may be opened
+///
+/// The path must be absolute and exist, and once `..` and symlinks are
+/// resolved it must lie inside one of `roots`. Returns the resolved path.
+pub fn validate_open_path(path: &str, roots: &[PathBuf]) -> Result<PathBuf> {
+    let requested = Path::new(path);
+    if !requested.is_absolute() {
+        return Err(crate::ProtocolError::InvalidPacket(format!(
+            "Path is not absolute: {}",
+            path
+        )));
+    }
+
+    let resolved = requested
+        .canonicalize()
+        .map_err(|e| crate::ProtocolError::InvalidPacket(format!("Cannot open {}: {}", path, e)))?;
+
+    let allowed = roots
+        .iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .any(|root| resolved.starts_with(root));
+    if !allowed {
+        return Err(crate::ProtocolError::InvalidPacket(format!(
+            "Path is outside the allowed folders: {}",
+            path
+        )));
+    }
+
+    Ok(resolved)
+}
+
+/// Mounts and unmounts device filesystems
+#[async_trait]
+pub trait Mounter: Send + Sync {
+    /// Mount the share described by `info` at `mountpoint`
+    async fn mount(&self, info: &SftpInfo, mountpoint: &Path) -> Result<()>;
+
+    /// Unmount `mountpoint`, detaching it lazily if `lazy` is set
+    async fn unmount(&self, mountpoint: &Path, lazy: bool) -> Result<()>;
+}
+
+/// Mounts with `sshfs` and unmounts with `fusermount`
+#[derive(Debug, Default)]
+pub struct SshfsMounter;
+
+#[async_trait]
+impl Mounter for SshfsMounter {
+    async fn mount(&self, info: &SftpInfo, mountpoint: &Path) -> Result<()> {
+        info.validate()?;
+        tokio::fs::create_dir_all(mountpoint).await?;
+
+        let mut child = tokio::process::Command::new("sshfs")
+            .arg("-p")
+            .arg(info.effective_port().to_string())
+            .args(["-o", "password_stdin", "--"])
+            .arg(info.remote_spec())
+            .arg(mountpoint)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| ProtocolError::Plugin(format!("Failed to run sshfs: {}", e)))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(format!("{}\n", info.password).as_bytes())
+                .await?;
+        }
+
+        // sshfs detaches once the filesystem is mounted
+        let output = child.wait_with_output().await?;
+        if !output.status.success() {
+            return Err(ProtocolError::Plugin(format!(
+                "sshfs failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(())
+    }
+
+    async fn unmount(&self, mountpoint: &Path, lazy: bool) -> Result<()> {
+        let mut command = tokio::process::Command::new("fusermount");
+        command.arg("-u");
+        if lazy {
+            command.arg("-z");
+        }
+
+        let output = command
+            .arg(mountpoint)
+            .output()
+            .await
+            .map_err(|e| ProtocolError::Plugin(format!("Failed to run fusermount: {}", e)))?;
+        if !output.status.success() {
+            return Err(ProtocolError::Plugin(format!(
+                "fusermount failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Directory device filesystems are mounted under
+pub fn default_mount_root() -> PathBuf {
+    dirs::runtime_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("cosmic-connect")
+}
+
+/// Browse result, reported to the daemon
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SftpEvent {
+    /// Auto-mount is off: everything needed to mount the share manually
+    ///
+    /// The password is left out since the event is broadcast.
+    Credentials {
+        ip: String,
+        port: u16,
+        user: String,
+        path: String,
+        /// Suggested mount point
+        mountpoint: PathBuf,
+        /// sshfs command mounting the share at `mountpoint`
+        mount_command: String,
+    },
+    /// The share was mounted at `mountpoint`
+    Mounted { mountpoint: PathBuf },
+}
+
+impl SftpEvent {
+    /// Credentials for mounting `info` manually at `mountpoint`
+    pub fn credentials(info: &SftpInfo, mountpoint: &Path) -> Self {
+        Self::Credentials {
+            ip: info.ip.clone(),
+            port: info.effective_port(),
+            user: info.user.clone(),
+            path: info.effective_path().to_string(),
+            mountpoint: mountpoint.to_path_buf(),
+            mount_command: info.sshfs_command(&mountpoint.to_string_lossy()),
+        }
+    }
+
+    /// Build the internal packet carrying this event
+    pub fn to_packet(&self) -> Packet {
+        match self {
+            Self::Credentials {
+                ip,
+                port,
+                user,
+                path,
+                mountpoint,
+                mount_command,
+            } => Packet::new(
+                PACKET_TYPE_SFTP_CREDENTIALS,
+                json!({
+                    "ip": ip,
+                    "port": port,
+                    "user": user,
+                    "path": path,
+                    "mountpoint": mountpoint.to_string_lossy(),
+                    "mountCommand": mount_command,
+                }),
+            ),
+            Self::Mounted { mountpoint } => Packet::new(
+                PACKET_TYPE_SFTP_MOUNTED,
+                json!({ "mountpoint": mountpoint.to_string_lossy() }),
+            ),
+        }
+    }
+
+    /// Parse an internal SFTP packet
+    ///
+    /// Returns `None` for any other packet.
+    pub fn from_packet(packet: &Packet) -> Option<Self> {
+        let field =
+            |name: &str| -> Option<String> { Some(packet.body.get(name)?.as_str()?.to_string()) };
+
+        match packet.packet_type.as_str() {
+            PACKET_TYPE_SFTP_CREDENTIALS => Some(Self::Credentials {
+                ip: field("ip")?,
+                port: u16::try_from(packet.body.get("port")?.as_u64()?).ok()?,
+                user: field("user")?,
+                path: field("path")?,
+                mountpoint: field("mountpoint")?.into(),
+                mount_command: field("mountCommand")?,
+            }),
+            PACKET_TYPE_SFTP_MOUNTED => Some(Self::Mounted {
+                mountpoint: field("mountpoint")?.into(),
+            }),
+            _ => None,
+        }
+    }
+}
+
 /// SFTP connection details
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SftpInfo {
@@ -101,14 +386,55 @@ impl SftpInfo {
         self.path.as_deref().unwrap_or("/")
     }
 
+    /// Check that the details are safe to pass to sshfs
+    ///
+    /// They come from the device and end up on the sshfs command line:
+    /// `ip` must be an IP address, `user` a plain user name not starting
+    /// with `-`, and the path absolute.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |field: &str, value: &str| {
+            Err(ProtocolError::InvalidPacket(format!(
+                "Invalid SFTP {}: {:?}",
+                field, value
+            )))
+        };
+
+        if self.ip.parse::<IpAddr>().is_err() {
+            return invalid("ip", &self.ip);
+        }
+
+        let user_valid = !self.user.is_empty()
+            && !self.user.starts_with('-')
+            && self
+                .user
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+        if !user_valid {
+            return invalid("user", &self.user);
+        }
+
+        let path = self.effective_path();
+        if !path.starts_with('/') || path.chars().any(char::is_control) {
+            return invalid("path", path);
+        }
+
+        Ok(())
+    }
+
+    /// Remote `user@host:path` argument of sshfs
+    pub fn remote_spec(&self) -> String {
+        match self.ip.parse::<IpAddr>() {
+            Ok(IpAddr::V6(ip)) => format!("{}@[{}]:{}", self.user, ip, self.effective_path()),
+            _ => format!("{}@{}:{}", self.user, self.ip, self.effective_path()),
+        }
+    }
+
     /// Generate the sshfs mount command
     pub fn sshfs_command(&self, mountpoint: &str) -> String {
         format!(
-            "sshfs -p {} {}@{}:{} {} -o password_stdin",
+            "sshfs -p {} -o password_stdin -- {} {}",
             self.effective_port(),
-            self.user,
-            self.ip,
-            self.effective_path(),
+            self.remote_spec(),
             mountpoint
         )
     }
@@ -139,16 +465,145 @@ impl SftpInfo {
 pub struct NetworkSharePlugin {
     /// SFTP connection info keyed by device ID
     shares: Arc<RwLock<HashMap<String, SftpInfo>>>,
+
+    /// Folders the device may open paths inside
+    open_roots: Vec<PathBuf>,
+
+    /// Opens requested paths
+    opener: Box<dyn PathOpener>,
+
+    /// Mount shares on browse instead of only reporting the credentials
+    auto_mount: bool,
+
+    /// Mounts shares
+    mounter: Box<dyn Mounter>,
+
+    /// Directory shares are mounted under, one folder per device
+    mount_root: PathBuf,
+
+    /// Mount points keyed by device ID
+    mounts: Arc<RwLock<HashMap<String, PathBuf>>>,
+
+    /// Whether a browse request awaits the device's connection details
+    browse_pending: AtomicBool,
+
+    /// Device this instance belongs to
+    device_id: Option<String>,
+
+    /// Channel for browse requests and events
+    packet_sender: Option<mpsc::Sender<(String, Packet)>>,
 }
 
 impl NetworkSharePlugin {
     /// Create a new Network Share plugin
+    ///
+    /// The device may open paths inside the home directory.
     pub fn new() -> Self {
+        Self::with_opener(dirs::home_dir().into_iter().collect(), Box::new(XdgOpener))
+    }
+
+    /// Create a plugin opening paths inside `open_roots` with `opener`
+    pub fn with_opener(open_roots: Vec<PathBuf>, opener: Box<dyn PathOpener>) -> Self {
         Self {
             shares: Arc::new(RwLock::new(HashMap::new())),
+            open_roots,
+            opener,
+            auto_mount: true,
+            mounter: Box::new(SshfsMounter),
+            mount_root: default_mount_root(),
+            mounts: Arc::new(RwLock::new(HashMap::new())),
+            browse_pending: AtomicBool::new(false),
+            device_id: None,
+            packet_sender: None,
         }
     }
 
+    /// Mount shares with `mounter`, under `mount_root`
+    pub fn with_mounter(mut self, mounter: Box<dyn Mounter>, mount_root: PathBuf) -> Self {
+        self.mounter = mounter;
+        self.mount_root = mount_root;
+        self
+    }
+
+    /// Whether shares are mounted on browse
+    pub fn auto_mount(&self) -> bool {
+        self.auto_mount
+    }
+
+    /// Mount shares on browse, or only report the credentials
+    pub fn set_auto_mount(&mut self, auto_mount: bool) {
+        self.auto_mount = auto_mount;
+    }
+
+    /// Where the share of a device is mounted
+    pub fn mountpoint(&self, device_id: &str) -> PathBuf {
+        self.mount_root.join(device_id)
+    }
+
+    /// Report an event to the daemon
+    async fn report(&self, event: SftpEvent) -> Result<()> {
+        self.send(event.to_packet()).await
+    }
+
+    /// Send a packet through the plugin's channel
+    async fn send(&self, packet: Packet) -> Result<()> {
+        if let (Some(sender), Some(device_id)) = (&self.packet_sender, &self.device_id) {
+            sender
+                .send((device_id.clone(), packet))
+                .await
+                .map_err(|e| ProtocolError::Transport(format!("Failed to send packet: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Mount a share and open it in the file manager
+    async fn mount_share(&self, device_id: &str, info: &SftpInfo) -> Result<()> {
+        let mountpoint = self.mountpoint(device_id);
+
+        if !self.mounts.read().await.contains_key(device_id) {
+            self.mounter.mount(info, &mountpoint).await?;
+            self.mounts
+                .write()
+                .await
+                .insert(device_id.to_string(), mountpoint.clone());
+            info!("Mounted {} at {}", device_id, mountpoint.display());
+        }
+
+        self.report(SftpEvent::Mounted {
+            mountpoint: mountpoint.clone(),
+        })
+        .await?;
+        if let Err(e) = self.opener.open(&mountpoint) {
+            warn!("Failed to open {}: {}", mountpoint.display(), e);
+        }
+        Ok(())
+    }
+
+    /// Handle a request to open a local path
+    fn handle_open_packet(&self, device: &Device, packet: &Packet) -> Result<()> {
+        let path = packet
+            .body
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                crate::ProtocolError::InvalidPacket("Open request without path".to_string())
+            })?;
+
+        let resolved = validate_open_path(path, &self.open_roots).inspect_err(|e| {
+            warn!("Rejected open request from {}: {}", device.name(), e);
+        })?;
+
+        // Only folders are opened, a file would run with its default handler
+        let folder = if resolved.is_dir() {
+            resolved.as_path()
+        } else {
+            resolved.parent().unwrap_or(&resolved)
+        };
+
+        info!("Opening {} for {}", folder.display(), device.name());
+        self.opener.open(folder)
+    }
+
     /// Handle SFTP packet from a device
     async fn handle_sftp_packet(&self, device: &Device, packet: &Packet) -> Result<()> {
         let mut info: SftpInfo = serde_json::from_value(packet.body.clone()).map_err(|e| {
@@ -156,6 +611,9 @@ impl NetworkSharePlugin {
         })?;
 
         info.received_at = Some(std::time::Instant::now());
+        info.validate().inspect_err(|e| {
+            warn!("Rejected SFTP details from {}: {}", device.name(), e);
+        })?;
 
         info!(
             "Received SFTP connection info from {}: {}",
@@ -166,15 +624,56 @@ impl NetworkSharePlugin {
         self.shares
             .write()
             .await
-            .insert(device.id().to_string(), info);
+            .insert(device.id().to_string(), info.clone());
 
-        debug!("SFTP share stored and ready for mounting");
+        if !self.browse_pending.swap(false, Ordering::Relaxed) {
+            debug!("SFTP share stored and ready for mounting");
+            return Ok(());
+        }
 
-        Ok(())
+        if self.auto_mount {
+            self.mount_share(device.id(), &info).await
+        } else {
+            debug!("Auto-mount disabled, reporting SFTP credentials");
+            let mountpoint = self.mountpoint(device.id());
+            self.report(SftpEvent::credentials(&info, &mountpoint))
+                .await
+        }
     }
 
     // ========== Public API ==========
 
+    /// Ask the device to share its filesystem
+    ///
+    /// The device answers with its SFTP details, which are then mounted or
+    /// reported depending on [`auto_mount`](Self::auto_mount).
+    pub async fn browse(&self) -> Result<()> {
+        self.browse_pending.store(true, Ordering::Relaxed);
+        self.send(Packet::new(
+            PACKET_TYPE_SFTP_REQUEST,
+            json!({ "startBrowsing": true }),
+        ))
+        .await
+    }
+
+    /// Unmount the share of a device
+    ///
+    /// Returns `false` if the device's share was not mounted.
+    pub async fn unmount(&self, device_id: &str) -> Result<bool> {
+        let Some(mountpoint) = self.mounts.write().await.remove(device_id) else {
+            return Ok(false);
+        };
+
+        self.mounter.unmount(&mountpoint, false).await?;
+        info!("Unmounted {} from {}", device_id, mountpoint.display());
+        Ok(true)
+    }
+
+    /// Mount point of a device's share, if mounted
+    pub async fn get_mount(&self, device_id: &str) -> Option<PathBuf> {
+        self.mounts.read().await.get(device_id).cloned()
+    }
+
     /// Get all active SFTP shares
     ///
     /// Returns a map of device ID to SFTP connection info.
@@ -243,18 +742,21 @@ impl Plugin for NetworkSharePlugin {
         vec![
             PACKET_TYPE_SFTP.to_string(),
             PACKET_TYPE_CCONNECT_SFTP.to_string(),
+            PACKET_TYPE_SFTP_OPEN.to_string(),
         ]
     }
 
     fn outgoing_capabilities(&self) -> Vec<String> {
-        vec![]
+        vec![PACKET_TYPE_SFTP_REQUEST.to_string()]
     }
 
     async fn init(
         &mut self,
         device: &Device,
-        _packet_sender: tokio::sync::mpsc::Sender<(String, Packet)>,
+        packet_sender: tokio::sync::mpsc::Sender<(String, Packet)>,
     ) -> Result<()> {
+        self.device_id = Some(device.id().to_string());
+        self.packet_sender = Some(packet_sender);
         info!(
             "NetworkShare plugin initialized for device {}",
             device.name()
@@ -268,6 +770,20 @@ impl Plugin for NetworkSharePlugin {
     }
 
     async fn stop(&mut self) -> Result<()> {
+        // The device is gone, so a mount left behind would hang on access
+        let mounts: Vec<_> = self.mounts.write().await.drain().collect();
+        for (device_id, mountpoint) in mounts {
+            match self.mounter.unmount(&mountpoint, true).await {
+                Ok(()) => info!("Lazily unmounted stale mount of {}", device_id),
+                Err(e) => warn!(
+                    "Failed to unmount {} for {}: {}",
+                    mountpoint.display(),
+                    device_id,
+                    e
+                ),
+            }
+        }
+
         self.clear_shares().await;
         info!("NetworkShare plugin stopped");
         Ok(())
@@ -276,6 +792,8 @@ impl Plugin for NetworkSharePlugin {
     async fn handle_packet(&mut self, packet: &Packet, device: &mut Device) -> Result<()> {
         if packet.is_type(PACKET_TYPE_SFTP) || packet.is_type(PACKET_TYPE_CCONNECT_SFTP) {
             self.handle_sftp_packet(device, packet).await
+        } else if packet.is_type(PACKET_TYPE_SFTP_OPEN) {
+            self.handle_open_packet(device, packet)
         } else {
             warn!(
                 "NetworkShare plugin received unknown packet type: {}",
@@ -299,11 +817,12 @@ impl PluginFactory for NetworkSharePluginFactory {
         vec![
             PACKET_TYPE_SFTP.to_string(),
             PACKET_TYPE_CCONNECT_SFTP.to_string(),
+            PACKET_TYPE_SFTP_OPEN.to_string(),
         ]
     }
 
     fn outgoing_capabilities(&self) -> Vec<String> {
-        vec![]
+        vec![PACKET_TYPE_SFTP_REQUEST.to_string()]
     }
 
     fn create(&self) -> Box<dyn Plugin> {
@@ -352,7 +871,10 @@ mod tests {
         assert!(factory
             .incoming_capabilities()
             .contains(&PACKET_TYPE_CCONNECT_SFTP.to_string()));
-        assert!(factory.outgoing_capabilities().is_empty());
+        assert_eq!(
+            factory.outgoing_capabilities(),
+            vec![PACKET_TYPE_SFTP_REQUEST.to_string()]
+        );
     }
 
     #[test]
@@ -449,6 +971,64 @@ mod tests {
         assert!(cmd.contains("-o password_stdin"));
     }
 
+    #[test]
+    fn test_sftp_info_validate_rejects_ssh_options() {
+        let info = |ip: &str, user: &str, path: Option<&str>| SftpInfo {
+            ip: ip.to_string(),
+            port: Some(1739),
+            user: user.to_string(),
+            password: "secret".to_string(),
+            path: path.map(str::to_string),
+            received_at: None,
+        };
+
+        assert!(info("192.168.1.10", "kdeconnect", Some("/sdcard"))
+            .validate()
+            .is_ok());
+        assert!(info("fe80::1", "u0_a123", None).validate().is_ok());
+
+        for bad in [
+            info("192.168.1.10", "-oProxyCommand=touch /tmp/pwned", None),
+            info("192.168.1.10", "user name", None),
+            info("192.168.1.10", "", None),
+            info("-oProxyCommand=touch /tmp/pwned", "kdeconnect", None),
+            info("phone.local", "kdeconnect", None),
+            info("192.168.1.10", "kdeconnect", Some("sdcard")),
+            info(
+                "192.168.1.10",
+                "kdeconnect",
+                Some("/sdcard\n-oProxyCommand=x"),
+            ),
+        ] {
+            assert!(bad.validate().is_err(), "accepted {:?}", bad);
+        }
+
+        let ipv6 = info("fe80::1", "kdeconnect", Some("/sdcard"));
+        assert_eq!(ipv6.remote_spec(), "kdeconnect@[fe80::1]:/sdcard");
+        assert_eq!(
+            ipv6.sshfs_command("/mnt/phone"),
+            "sshfs -p 1739 -o password_stdin -- kdeconnect@[fe80::1]:/sdcard /mnt/phone"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handle_sftp_packet_rejects_option_injection() {
+        let mut plugin = NetworkSharePlugin::new();
+        let mut device = create_test_device();
+
+        let packet = Packet::new(
+            PACKET_TYPE_SFTP,
+            json!({
+                "ip": "192.168.1.50",
+                "user": "-oProxyCommand=sh -c id",
+                "password": "pass"
+            }),
+        );
+
+        assert!(plugin.handle_packet(&packet, &mut device).await.is_err());
+        assert!(!plugin.has_shares().await);
+    }
+
     #[test]
     fn test_sftp_info_is_fresh_none() {
         let info = SftpInfo {
@@ -750,10 +1330,245 @@ mod tests {
         assert!(caps.contains(&PACKET_TYPE_CCONNECT_SFTP.to_string()));
     }
 
+    // ========== Open Path Tests ==========
+
+    #[derive(Clone, Default)]
+    struct MockOpener {
+        opened: Arc<std::sync::Mutex<Vec<PathBuf>>>,
+    }
+
+    impl PathOpener for MockOpener {
+        fn open(&self, path: &Path) -> Result<()> {
+            self.opened.lock().unwrap().push(path.to_path_buf());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_open_path_inside_root_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("home");
+        let documents = root.join("Documents");
+        std::fs::create_dir_all(&documents).unwrap();
+        let script = documents.join("run-me.desktop");
+        std::fs::write(&script, "[Desktop Entry]").unwrap();
+        std::fs::create_dir_all(dir.path().join("secret")).unwrap();
+
+        let opener = MockOpener::default();
+        let mut plugin =
+            NetworkSharePlugin::with_opener(vec![root.clone()], Box::new(opener.clone()));
+        let mut device = create_test_device();
+
+        let open = |path: PathBuf| {
+            Packet::new(
+                PACKET_TYPE_SFTP_OPEN,
+                json!({ "path": path.to_string_lossy() }),
+            )
+        };
+
+        plugin
+            .handle_packet(&open(documents.clone()), &mut device)
+            .await
+            .unwrap();
+
+        // A file only reveals its folder
+        plugin
+            .handle_packet(&open(script), &mut device)
+            .await
+            .unwrap();
+
+        // Traversal out of the root, a missing path and a relative path
+        for packet in [
+            open(documents.join("../../secret")),
+            open(root.join("missing")),
+            Packet::new(PACKET_TYPE_SFTP_OPEN, json!({ "path": "Documents" })),
+        ] {
+            assert!(plugin.handle_packet(&packet, &mut device).await.is_err());
+        }
+
+        let documents = documents.canonicalize().unwrap();
+        assert_eq!(
+            *opener.opened.lock().unwrap(),
+            vec![documents.clone(), documents]
+        );
+    }
+
     #[test]
-    fn test_outgoing_capabilities_empty() {
+    fn test_outgoing_capabilities() {
         let plugin = NetworkSharePlugin::new();
         let caps = plugin.outgoing_capabilities();
-        assert!(caps.is_empty());
+        assert_eq!(caps, vec![PACKET_TYPE_SFTP_REQUEST.to_string()]);
+    }
+
+    // ========== Browse Tests ==========
+
+    /// Calls made to a [`MockMounter`]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum MountCall {
+        Mount(PathBuf),
+        Unmount { mountpoint: PathBuf, lazy: bool },
+    }
+
+    #[derive(Clone, Default)]
+    struct MockMounter {
+        calls: Arc<std::sync::Mutex<Vec<MountCall>>>,
+    }
+
+    #[async_trait]
+    impl Mounter for MockMounter {
+        async fn mount(&self, _info: &SftpInfo, mountpoint: &Path) -> Result<()> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(MountCall::Mount(mountpoint.to_path_buf()));
+            Ok(())
+        }
+
+        async fn unmount(&self, mountpoint: &Path, lazy: bool) -> Result<()> {
+            self.calls.lock().unwrap().push(MountCall::Unmount {
+                mountpoint: mountpoint.to_path_buf(),
+                lazy,
+            });
+            Ok(())
+        }
+    }
+
+    fn sftp_packet() -> Packet {
+        Packet::new(
+            PACKET_TYPE_SFTP,
+            json!({
+                "ip": "192.168.1.50",
+                "port": 1739,
+                "user": "kdeconnect",
+                "password": "secret",
+                "path": "/storage/emulated/0"
+            }),
+        )
+    }
+
+    async fn browse_plugin(
+        auto_mount: bool,
+    ) -> (
+        NetworkSharePlugin,
+        MockMounter,
+        MockOpener,
+        mpsc::Receiver<(String, Packet)>,
+    ) {
+        let mounter = MockMounter::default();
+        let opener = MockOpener::default();
+        let mut plugin = NetworkSharePlugin::with_opener(vec![], Box::new(opener.clone()))
+            .with_mounter(Box::new(mounter.clone()), PathBuf::from("/run/test"));
+        plugin.set_auto_mount(auto_mount);
+
+        let (tx, rx) = mpsc::channel(10);
+        plugin
+            .init(&create_test_device_with_id("phone", "Phone"), tx)
+            .await
+            .unwrap();
+        (plugin, mounter, opener, rx)
+    }
+
+    #[tokio::test]
+    async fn test_browse_without_auto_mount_reports_credentials() {
+        let (mut plugin, mounter, opener, mut rx) = browse_plugin(false).await;
+        let mut device = create_test_device_with_id("phone", "Phone");
+
+        plugin.browse().await.unwrap();
+        let (_, request) = rx.try_recv().unwrap();
+        assert!(request.is_type(PACKET_TYPE_SFTP_REQUEST));
+        assert_eq!(request.body["startBrowsing"], json!(true));
+
+        plugin
+            .handle_packet(&sftp_packet(), &mut device)
+            .await
+            .unwrap();
+
+        let (device_id, event) = rx.try_recv().unwrap();
+        assert_eq!(device_id, "phone");
+        assert!(event.body.get("password").is_none());
+        let Some(SftpEvent::Credentials {
+            user,
+            mountpoint,
+            mount_command,
+            ..
+        }) = SftpEvent::from_packet(&event)
+        else {
+            panic!("Expected credentials, got {:?}", event);
+        };
+        assert_eq!(user, "kdeconnect");
+        assert_eq!(mountpoint, PathBuf::from("/run/test/phone"));
+        assert!(mount_command.starts_with("sshfs -p 1739"));
+
+        assert!(mounter.calls.lock().unwrap().is_empty());
+        assert!(opener.opened.lock().unwrap().is_empty());
+        assert_eq!(plugin.get_mount("phone").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_browse_with_auto_mount_mounts_and_opens() {
+        let (mut plugin, mounter, opener, mut rx) = browse_plugin(true).await;
+        let mut device = create_test_device_with_id("phone", "Phone");
+        let mountpoint = PathBuf::from("/run/test/phone");
+
+        // Details sent without a browse request are only stored
+        plugin
+            .handle_packet(&sftp_packet(), &mut device)
+            .await
+            .unwrap();
+        assert!(mounter.calls.lock().unwrap().is_empty());
+
+        plugin.browse().await.unwrap();
+        plugin
+            .handle_packet(&sftp_packet(), &mut device)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *mounter.calls.lock().unwrap(),
+            vec![MountCall::Mount(mountpoint.clone())]
+        );
+        assert_eq!(*opener.opened.lock().unwrap(), vec![mountpoint.clone()]);
+        assert_eq!(plugin.get_mount("phone").await, Some(mountpoint.clone()));
+
+        let _request = rx.try_recv().unwrap();
+        let (_, event) = rx.try_recv().unwrap();
+        assert_eq!(
+            SftpEvent::from_packet(&event),
+            Some(SftpEvent::Mounted {
+                mountpoint: mountpoint.clone()
+            })
+        );
+
+        assert!(plugin.unmount("phone").await.unwrap());
+        assert!(!plugin.unmount("phone").await.unwrap());
+        assert_eq!(
+            mounter.calls.lock().unwrap().last(),
+            Some(&MountCall::Unmount {
+                mountpoint,
+                lazy: false
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stop_lazily_unmounts_stale_mounts() {
+        let (mut plugin, mounter, _opener, _rx) = browse_plugin(true).await;
+        let mut device = create_test_device_with_id("phone", "Phone");
+
+        plugin.browse().await.unwrap();
+        plugin
+            .handle_packet(&sftp_packet(), &mut device)
+            .await
+            .unwrap();
+        plugin.stop().await.unwrap();
+
+        assert_eq!(
+            mounter.calls.lock().unwrap().last(),
+            Some(&MountCall::Unmount {
+                mountpoint: PathBuf::from("/run/test/phone"),
+                lazy: true
+            })
+        );
+        assert_eq!(plugin.get_mount("phone").await, None);
     }
 }
@@ -190,12 +190,48 @@ use crate::{Device, Packet, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 use tracing::{debug, info, warn};
+use unicode_segmentation::UnicodeSegmentation;
 
+use super::reply::prepare_reply;
 use super::{Plugin, PluginFactory};
 
+/// Default length, in characters, mirrored notification bodies are shown at
+pub const DEFAULT_MAX_BODY_CHARS: usize = 500;
+
+/// Shorten a notification body for display
+///
+/// Bodies longer than `max_chars` user-perceived characters (grapheme
+/// clusters) are cut to fit, ellipsis included, without splitting a
+/// character. A `max_chars` of 0 means no limit. The full text stays in the
+/// stored [`Notification`].
+///
+/// ## Example
+///
+/// ```rust
+/// use cosmic_connect_core::plugins::notification::truncate_body;
+///
+/// assert_eq!(truncate_body("Hello world", 6), "Hello…");
+/// assert_eq!(truncate_body("Hello", 6), "Hello");
+/// ```
+pub fn truncate_body(text: &str, max_chars: usize) -> Cow<'_, str> {
+    if max_chars == 0 || grapheme_prefix(text, max_chars).is_none() {
+        return Cow::Borrowed(text);
+    }
+    let kept = grapheme_prefix(text, max_chars - 1).unwrap_or_default();
+    Cow::Owned(format!("{}…", kept.trim_end()))
+}
+
+/// First `count` grapheme clusters of `text`, or `None` if it is not longer
+fn grapheme_prefix(text: &str, count: usize) -> Option<&str> {
+    text.grapheme_indices(true)
+        .nth(count)
+        .map(|(end, _)| &text[..end])
+}
+
 /// Notification urgency level
 ///
 /// Follows the freedesktop.org notification spec urgency levels.
@@ -954,6 +990,36 @@ impl NotificationPlugin {
         Packet::new("cconnect.notification.request", body)
     }
 
+    /// Create a reply to a notification on the remote device
+    ///
+    /// `request_reply_id` is the `requestReplyId` of the notification being
+    /// answered. The message is sanitized with [`prepare_reply`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message is empty or too long.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cosmic_connect_core::plugins::notification::NotificationPlugin;
+    ///
+    /// let plugin = NotificationPlugin::new();
+    /// let packet = plugin.create_reply_packet("reply-42", "On my way").unwrap();
+    ///
+    /// assert_eq!(packet.packet_type, "cconnect.notification.reply");
+    /// assert_eq!(packet.body["requestReplyId"], "reply-42");
+    /// assert_eq!(packet.body["message"], "On my way");
+    /// ```
+    pub fn create_reply_packet(&self, request_reply_id: &str, message: &str) -> Result<Packet> {
+        let message = prepare_reply(message)?;
+        let body = json!({
+            "requestReplyId": request_reply_id,
+            "message": message
+        });
+        Ok(Packet::new("cconnect.notification.reply", body))
+    }
+
     /// Create an action invocation packet (Android → Desktop)
     ///
     /// This packet is sent when a user taps an action button in a notification
@@ -1074,12 +1140,11 @@ impl NotificationPlugin {
         // Generate unique ID from app name and timestamp
         let id = format!("desktop-{}-{}", app_name, timestamp);
 
-        // Truncate body if too long
+        // Truncate body if too long, without splitting a character
         const MAX_BODY_LENGTH: usize = 2000;
-        let truncated_body = if body.len() > MAX_BODY_LENGTH {
-            format!("{}...", &body[..MAX_BODY_LENGTH])
-        } else {
-            body.to_string()
+        let truncated_body = match grapheme_prefix(body, MAX_BODY_LENGTH) {
+            Some(prefix) => format!("{}...", prefix),
+            None => body.to_string(),
         };
 
         // Create ticker (combined title and text)
@@ -1925,6 +1990,24 @@ mod tests {
         assert!(text.ends_with("..."));
     }
 
+    #[test]
+    fn test_truncate_body_at_char_boundary() {
+        assert_eq!(truncate_body("short", 10), "short");
+        assert_eq!(truncate_body("exactly10!", 10), "exactly10!");
+        assert_eq!(truncate_body("a very long body", 0), "a very long body");
+
+        let truncated = truncate_body("Hello wonderful world", 10);
+        assert_eq!(truncated, "Hello won…");
+        assert_eq!(truncated.chars().count(), 10);
+
+        // Multi-byte characters and grapheme clusters stay whole
+        let body = "Grüße 👋🏽👋🏽👋🏽 from the phone";
+        let truncated = truncate_body(body, 8);
+        assert_eq!(truncated, "Grüße 👋🏽…");
+        assert!(body.starts_with(truncated.trim_end_matches('…')));
+        assert_eq!(truncated.graphemes(true).count(), 8);
+    }
+
     #[test]
     fn test_create_desktop_notification_packet_with_image() {
         use base64::{engine::general_purpose, Engine as _};
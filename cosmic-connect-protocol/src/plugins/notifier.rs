@@ -0,0 +1,682 @@
+//! Desktop Notification Backend
+//!
+//! Plugins raise desktop notifications through a shared [`Notifier`] that the
+//! [`PluginManager`](super::PluginManager) hands to every plugin instance,
+//! instead of talking to the notification server themselves. Policy such as
+//! Do Not Disturb can then be applied in one place, and tests can observe
+//! notifications with [`MockNotifier`].
+//!
+//! [`FreedesktopNotifier`] sends notifications over the
+//! `org.freedesktop.Notifications` D-Bus interface.
+//!
+//! ## Fallback
+//!
+//! Minimal sessions may run without a notification server. Wrapping the
+//! desktop notifier in a [`FallbackNotifier`] hands notifications it fails to
+//! show to a second notifier, usually a [`LogNotifier`], so events such as
+//! pings and incoming calls still end up somewhere and plugins never see the
+//! error. Use [`notification_service_available`] to check for a server up
+//! front.
+//!
+//! ## Actions
+//!
+//! Notifications can carry [`NotificationAction`] buttons. Each action names
+//! an [`ActionTarget`] instead of a callback, so the owner of the notifier
+//! (the daemon) can carry it out when the notification server reports the
+//! action as invoked, e.g. by opening the manager on the notifying device.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use cosmic_connect_protocol::plugins::notifier::*;
+//!
+//! let notifier = FreedesktopNotifier::new("CConnect").await?;
+//! let handle = notifier
+//!     .notify(NotificationSpec::new("Incoming call").body("Alice"))
+//!     .await?;
+//! notifier.close(handle).await?;
+//! ```
+
+use crate::{ProtocolError, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info, warn};
+use zbus::zvariant::Value;
+
+/// Notification urgency level
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationUrgency {
+    /// Low priority notification
+    Low,
+    /// Normal priority notification
+    #[default]
+    Normal,
+    /// Critical notification that requires attention
+    Critical,
+}
+
+impl NotificationUrgency {
+    /// Value of the freedesktop `urgency` hint
+    pub fn as_u8(self) -> u8 {
+        match self {
+            Self::Low => 0,
+            Self::Normal => 1,
+            Self::Critical => 2,
+        }
+    }
+}
+
+/// What invoking a notification action does
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ActionTarget {
+    /// Start or focus the manager on the device that raised the notification
+    OpenDevice,
+    /// Start or focus the manager on a page by name, e.g. `commands`
+    OpenPage {
+        /// Page name
+        page: String,
+    },
+    /// Silence the ringing phone that raised the notification
+    MuteCall,
+}
+
+/// Button shown on a notification
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationAction {
+    /// Key reported back by the notification server when invoked
+    pub id: String,
+    /// Button label
+    pub label: String,
+    /// What invoking the action does
+    pub target: ActionTarget,
+}
+
+impl NotificationAction {
+    /// Create an action
+    pub fn new(id: impl Into<String>, label: impl Into<String>, target: ActionTarget) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+            target,
+        }
+    }
+}
+
+/// Description of a notification to raise
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationSpec {
+    /// Single line summary
+    pub summary: String,
+    /// Body text
+    pub body: String,
+    /// Icon name
+    pub icon: String,
+    /// Urgency level
+    pub urgency: NotificationUrgency,
+    /// Freedesktop category, e.g. `call.incoming`
+    pub category: Option<String>,
+    /// Expiry in milliseconds; 0 never expires, -1 uses the server default
+    pub timeout_ms: i32,
+    /// Device the notification is about, used by device actions
+    pub device_id: Option<String>,
+    /// Action buttons
+    pub actions: Vec<NotificationAction>,
+    /// Freedesktop sound theme name to play, e.g. `message-new-instant`
+    pub sound: Option<String>,
+}
+
+impl NotificationSpec {
+    /// Create a notification with the given summary
+    pub fn new(summary: impl Into<String>) -> Self {
+        Self {
+            summary: summary.into(),
+            body: String::new(),
+            icon: "phone-symbolic".to_string(),
+            urgency: NotificationUrgency::Normal,
+            category: None,
+            timeout_ms: 5000,
+            device_id: None,
+            actions: Vec::new(),
+            sound: None,
+        }
+    }
+
+    /// Set the body text
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Set the icon name
+    pub fn icon(mut self, icon: impl Into<String>) -> Self {
+        self.icon = icon.into();
+        self
+    }
+
+    /// Set the urgency level
+    pub fn urgency(mut self, urgency: NotificationUrgency) -> Self {
+        self.urgency = urgency;
+        self
+    }
+
+    /// Set the freedesktop category
+    pub fn category(mut self, category: impl Into<String>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// Set the expiry in milliseconds
+    pub fn timeout(mut self, timeout_ms: i32) -> Self {
+        self.timeout_ms = timeout_ms;
+        self
+    }
+
+    /// Set the device the notification is about
+    pub fn device(mut self, device_id: impl Into<String>) -> Self {
+        self.device_id = Some(device_id.into());
+        self
+    }
+
+    /// Add an action button
+    pub fn action(mut self, action: NotificationAction) -> Self {
+        self.actions.push(action);
+        self
+    }
+
+    /// Play a sound from the freedesktop sound theme
+    pub fn sound(mut self, sound_name: impl Into<String>) -> Self {
+        self.sound = Some(sound_name.into());
+        self
+    }
+
+    /// Action with the given key
+    pub fn find_action(&self, id: &str) -> Option<&NotificationAction> {
+        self.actions.iter().find(|action| action.id == id)
+    }
+}
+
+/// Handle to a raised notification, used to close it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NotificationHandle(u32);
+
+impl NotificationHandle {
+    /// Handle for a notification that was not shown (e.g. suppressed)
+    ///
+    /// Closing it does nothing. Notification servers never use ID 0.
+    pub const NONE: Self = Self(0);
+
+    /// Create a handle from a notification server ID
+    pub fn new(id: u32) -> Self {
+        Self(id)
+    }
+
+    /// Notification server ID
+    pub fn id(&self) -> u32 {
+        self.0
+    }
+
+    /// Whether the notification was actually shown
+    pub fn is_shown(&self) -> bool {
+        self.0 != 0
+    }
+}
+
+/// Backend that raises and closes desktop notifications
+#[async_trait]
+pub trait Notifier: Send + Sync + std::fmt::Debug {
+    /// Raise a notification
+    async fn notify(&self, spec: NotificationSpec) -> Result<NotificationHandle>;
+
+    /// Close a notification raised by [`notify`](Self::notify)
+    ///
+    /// Closing a notification that is already gone is not an error.
+    async fn close(&self, handle: NotificationHandle) -> Result<()>;
+}
+
+/// Notifier using the `org.freedesktop.Notifications` D-Bus interface
+///
+/// Shows action buttons but does not listen for them being invoked.
+#[derive(Debug, Clone)]
+pub struct FreedesktopNotifier {
+    connection: zbus::Connection,
+    app_name: String,
+}
+
+impl FreedesktopNotifier {
+    /// Connect to the session bus
+    pub async fn new(app_name: impl Into<String>) -> Result<Self> {
+        let connection = zbus::Connection::session().await.map_err(|e| {
+            ProtocolError::Plugin(format!("Failed to connect to session bus: {}", e))
+        })?;
+
+        Ok(Self::with_connection(connection, app_name))
+    }
+
+    /// Use an existing D-Bus connection
+    pub fn with_connection(connection: zbus::Connection, app_name: impl Into<String>) -> Self {
+        Self {
+            connection,
+            app_name: app_name.into(),
+        }
+    }
+
+    async fn proxy(&self) -> Result<zbus::Proxy<'_>> {
+        zbus::Proxy::new(
+            &self.connection,
+            "org.freedesktop.Notifications",
+            "/org/freedesktop/Notifications",
+            "org.freedesktop.Notifications",
+        )
+        .await
+        .map_err(|e| ProtocolError::Plugin(format!("Failed to create notifications proxy: {}", e)))
+    }
+}
+
+#[async_trait]
+impl Notifier for FreedesktopNotifier {
+    async fn notify(&self, spec: NotificationSpec) -> Result<NotificationHandle> {
+        let mut hints: HashMap<&str, Value<'_>> = HashMap::new();
+        hints.insert("urgency", Value::U8(spec.urgency.as_u8()));
+        if let Some(category) = &spec.category {
+            hints.insert("category", Value::from(category.as_str()));
+        }
+        if let Some(sound) = &spec.sound {
+            hints.insert("sound-name", Value::from(sound.as_str()));
+        }
+        // Flattened as alternating keys and labels
+        let actions: Vec<&str> = spec
+            .actions
+            .iter()
+            .flat_map(|action| [action.id.as_str(), action.label.as_str()])
+            .collect();
+
+        let id: u32 = self
+            .proxy()
+            .await?
+            .call(
+                "Notify",
+                &(
+                    &self.app_name,
+                    0u32,
+                    &spec.icon,
+                    &spec.summary,
+                    &spec.body,
+                    actions,
+                    hints,
+                    spec.timeout_ms,
+                ),
+            )
+            .await
+            .map_err(|e| ProtocolError::Plugin(format!("Failed to send notification: {}", e)))?;
+
+        debug!("Sent notification '{}' with ID {}", spec.summary, id);
+        Ok(NotificationHandle::new(id))
+    }
+
+    async fn close(&self, handle: NotificationHandle) -> Result<()> {
+        if !handle.is_shown() {
+            return Ok(());
+        }
+
+        let _: () = self
+            .proxy()
+            .await?
+            .call("CloseNotification", &(handle.id(),))
+            .await
+            .map_err(|e| ProtocolError::Plugin(format!("Failed to close notification: {}", e)))?;
+
+        debug!("Closed notification {}", handle.id());
+        Ok(())
+    }
+}
+
+/// Well-known bus name of the desktop notification server
+pub const NOTIFICATIONS_BUS_NAME: &str = "org.freedesktop.Notifications";
+
+/// Whether a notification server currently owns [`NOTIFICATIONS_BUS_NAME`]
+///
+/// Bus errors are treated as the server being unavailable.
+pub async fn notification_service_available(connection: &zbus::Connection) -> bool {
+    let proxy = match zbus::fdo::DBusProxy::new(connection).await {
+        Ok(proxy) => proxy,
+        Err(e) => {
+            debug!("Failed to create D-Bus proxy: {}", e);
+            return false;
+        }
+    };
+
+    match zbus::names::BusName::try_from(NOTIFICATIONS_BUS_NAME) {
+        Ok(name) => proxy.name_has_owner(name).await.unwrap_or(false),
+        Err(_) => false,
+    }
+}
+
+/// Notifier that writes notifications to the log
+///
+/// Used when no notification server is running, so notifications are still
+/// recorded somewhere. Handles count down from `u32::MAX` to stay clear of
+/// the IDs notification servers hand out, which count up from 1.
+#[derive(Debug)]
+pub struct LogNotifier {
+    next_id: AtomicU32,
+}
+
+impl LogNotifier {
+    /// Create a log notifier
+    pub fn new() -> Self {
+        Self {
+            next_id: AtomicU32::new(u32::MAX),
+        }
+    }
+}
+
+impl Default for LogNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Notifier for LogNotifier {
+    async fn notify(&self, spec: NotificationSpec) -> Result<NotificationHandle> {
+        let id = self.next_id.fetch_sub(1, Ordering::Relaxed);
+        match (&spec.device_id, spec.body.is_empty()) {
+            (Some(device_id), false) => info!(
+                "Notification from {}: {} - {}",
+                device_id, spec.summary, spec.body
+            ),
+            (Some(device_id), true) => info!("Notification from {}: {}", device_id, spec.summary),
+            (None, false) => info!("Notification: {} - {}", spec.summary, spec.body),
+            (None, true) => info!("Notification: {}", spec.summary),
+        }
+        Ok(NotificationHandle::new(id))
+    }
+
+    async fn close(&self, _handle: NotificationHandle) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Notifier that falls back to a second notifier when the first one fails
+///
+/// Each notification is first offered to the primary notifier, so desktop
+/// notifications resume as soon as a notification server appears. If the
+/// primary fails the notification goes to the fallback instead, and closing
+/// its handle is routed back there.
+#[derive(Debug)]
+pub struct FallbackNotifier {
+    primary: Arc<dyn Notifier>,
+    fallback: Arc<dyn Notifier>,
+    /// Handles of notifications shown by the fallback
+    fallback_handles: Mutex<HashSet<NotificationHandle>>,
+    /// Whether the last notification went to the fallback, to warn only once
+    falling_back: AtomicBool,
+}
+
+impl FallbackNotifier {
+    /// Create a notifier preferring `primary` over `fallback`
+    pub fn new(primary: Arc<dyn Notifier>, fallback: Arc<dyn Notifier>) -> Self {
+        Self {
+            primary,
+            fallback,
+            fallback_handles: Mutex::new(HashSet::new()),
+            falling_back: AtomicBool::new(false),
+        }
+    }
+
+    /// Whether a notification was shown by the fallback notifier
+    pub fn is_fallback(&self, handle: NotificationHandle) -> bool {
+        self.fallback_handles.lock().unwrap().contains(&handle)
+    }
+}
+
+#[async_trait]
+impl Notifier for FallbackNotifier {
+    async fn notify(&self, spec: NotificationSpec) -> Result<NotificationHandle> {
+        match self.primary.notify(spec.clone()).await {
+            Ok(handle) => {
+                if self.falling_back.swap(false, Ordering::Relaxed) {
+                    info!("Desktop notifications available again");
+                }
+                Ok(handle)
+            }
+            Err(e) => {
+                if !self.falling_back.swap(true, Ordering::Relaxed) {
+                    warn!("Desktop notifications unavailable, using fallback: {}", e);
+                }
+                let handle = self.fallback.notify(spec).await?;
+                if handle.is_shown() {
+                    self.fallback_handles.lock().unwrap().insert(handle);
+                }
+                Ok(handle)
+            }
+        }
+    }
+
+    async fn close(&self, handle: NotificationHandle) -> Result<()> {
+        if self.fallback_handles.lock().unwrap().remove(&handle) {
+            self.fallback.close(handle).await
+        } else {
+            self.primary.close(handle).await
+        }
+    }
+}
+
+/// State recorded by [`MockNotifier`]
+#[derive(Debug, Default)]
+struct MockState {
+    /// Last ID handed out
+    last_id: u32,
+    /// Every notification raised, in order
+    sent: Vec<NotificationSpec>,
+    /// Notifications that have not been closed
+    open: HashMap<u32, NotificationSpec>,
+}
+
+/// Notifier that records notifications instead of showing them
+///
+/// Intended for tests of plugins and of code built on top of them.
+#[derive(Debug, Default)]
+pub struct MockNotifier {
+    state: Mutex<MockState>,
+}
+
+impl MockNotifier {
+    /// Create a notifier with nothing recorded
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every notification raised so far, in order
+    pub fn sent(&self) -> Vec<NotificationSpec> {
+        self.state.lock().unwrap().sent.clone()
+    }
+
+    /// Notifications that are still open, in the order they were raised
+    pub fn open(&self) -> Vec<NotificationSpec> {
+        let state = self.state.lock().unwrap();
+        let mut open: Vec<_> = state.open.iter().collect();
+        open.sort_by_key(|(id, _)| **id);
+        open.into_iter().map(|(_, spec)| spec.clone()).collect()
+    }
+
+    /// Whether a notification is still open
+    pub fn is_open(&self, handle: NotificationHandle) -> bool {
+        self.state.lock().unwrap().open.contains_key(&handle.id())
+    }
+}
+
+#[async_trait]
+impl Notifier for MockNotifier {
+    async fn notify(&self, spec: NotificationSpec) -> Result<NotificationHandle> {
+        let mut state = self.state.lock().unwrap();
+        state.last_id += 1;
+        let id = state.last_id;
+        state.sent.push(spec.clone());
+        state.open.insert(id, spec);
+        Ok(NotificationHandle::new(id))
+    }
+
+    async fn close(&self, handle: NotificationHandle) -> Result<()> {
+        self.state.lock().unwrap().open.remove(&handle.id());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_builder() {
+        let spec = NotificationSpec::new("Incoming call")
+            .body("Alice")
+            .icon("call-start-symbolic")
+            .urgency(NotificationUrgency::Critical)
+            .category("call.incoming")
+            .timeout(0);
+
+        assert_eq!(spec.summary, "Incoming call");
+        assert_eq!(spec.body, "Alice");
+        assert_eq!(spec.icon, "call-start-symbolic");
+        assert_eq!(spec.urgency.as_u8(), 2);
+        assert_eq!(spec.category.as_deref(), Some("call.incoming"));
+        assert_eq!(spec.timeout_ms, 0);
+        assert!(spec.actions.is_empty());
+    }
+
+    #[test]
+    fn test_spec_actions() {
+        let spec = NotificationSpec::new("Ping from Phone")
+            .device("phone")
+            .action(NotificationAction::new(
+                "open-manager",
+                "Open manager",
+                ActionTarget::OpenDevice,
+            ));
+
+        assert_eq!(spec.device_id.as_deref(), Some("phone"));
+        assert_eq!(
+            spec.find_action("open-manager").map(|a| &a.target),
+            Some(&ActionTarget::OpenDevice)
+        );
+        assert!(spec.find_action("reply").is_none());
+
+        let target: ActionTarget =
+            serde_json::from_value(serde_json::json!({ "type": "open_page", "page": "commands" }))
+                .unwrap();
+        assert_eq!(
+            target,
+            ActionTarget::OpenPage {
+                page: "commands".to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mock_notify_and_close() {
+        let notifier = MockNotifier::new();
+
+        let first = notifier
+            .notify(NotificationSpec::new("First"))
+            .await
+            .unwrap();
+        let second = notifier
+            .notify(NotificationSpec::new("Second"))
+            .await
+            .unwrap();
+        assert_ne!(first, second);
+        assert!(first.is_shown());
+
+        notifier.close(first).await.unwrap();
+        assert!(!notifier.is_open(first));
+        assert!(notifier.is_open(second));
+        assert_eq!(notifier.sent().len(), 2);
+        assert_eq!(notifier.open(), vec![NotificationSpec::new("Second")]);
+
+        // Closing twice, or closing a notification never shown, is harmless
+        notifier.close(first).await.unwrap();
+        notifier.close(NotificationHandle::NONE).await.unwrap();
+    }
+
+    /// Notifier behaving like a session without a notification server
+    #[derive(Debug)]
+    struct UnavailableNotifier;
+
+    #[async_trait]
+    impl Notifier for UnavailableNotifier {
+        async fn notify(&self, _spec: NotificationSpec) -> Result<NotificationHandle> {
+            Err(ProtocolError::Plugin(
+                "The name org.freedesktop.Notifications was not provided".to_string(),
+            ))
+        }
+
+        async fn close(&self, _handle: NotificationHandle) -> Result<()> {
+            Err(ProtocolError::Plugin("unavailable".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fallback_when_notification_service_unavailable() {
+        let fallback = Arc::new(MockNotifier::new());
+        let notifier = FallbackNotifier::new(Arc::new(UnavailableNotifier), fallback.clone());
+
+        let handle = notifier
+            .notify(NotificationSpec::new("Ping from Phone"))
+            .await
+            .unwrap();
+
+        assert!(handle.is_shown());
+        assert!(notifier.is_fallback(handle));
+        assert_eq!(
+            fallback.sent(),
+            vec![NotificationSpec::new("Ping from Phone")]
+        );
+
+        // Closing goes to the fallback, not the unavailable server
+        notifier.close(handle).await.unwrap();
+        assert!(!fallback.is_open(handle));
+        assert!(!notifier.is_fallback(handle));
+    }
+
+    #[tokio::test]
+    async fn test_fallback_prefers_primary() {
+        let primary = Arc::new(MockNotifier::new());
+        let fallback = Arc::new(MockNotifier::new());
+        let notifier = FallbackNotifier::new(primary.clone(), fallback.clone());
+
+        let handle = notifier
+            .notify(NotificationSpec::new("Incoming call"))
+            .await
+            .unwrap();
+
+        assert!(!notifier.is_fallback(handle));
+        assert_eq!(primary.sent().len(), 1);
+        assert!(fallback.sent().is_empty());
+
+        notifier.close(handle).await.unwrap();
+        assert!(!primary.is_open(handle));
+    }
+
+    #[tokio::test]
+    async fn test_log_notifier_handles() {
+        let notifier = LogNotifier::new();
+        let first = notifier
+            .notify(NotificationSpec::new("Battery low").device("phone"))
+            .await
+            .unwrap();
+        let second = notifier
+            .notify(NotificationSpec::new("Ping").body("Hello"))
+            .await
+            .unwrap();
+
+        assert!(first.is_shown());
+        assert_ne!(first, second);
+        notifier.close(first).await.unwrap();
+    }
+}
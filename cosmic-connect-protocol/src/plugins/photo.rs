@@ -0,0 +1,555 @@
+//! Photo Plugin
+//!
+//! Asks the remote device to take a photo with its camera and receives the
+//! picture as a payload.
+//!
+//! ## Protocol
+//!
+//! **Packet Types**:
+//! - `cconnect.photo.request` - Ask the phone to take a photo (outgoing)
+//! - `cconnect.photo` - Photo taken, with payload (incoming)
+//!
+//! **Capabilities**:
+//! - Incoming: `cconnect.photo`
+//! - Outgoing: `cconnect.photo.request`
+//!
+//! ## Packet Format
+//!
+//! **Request (outgoing)**:
+//! ```json
+//! {
+//!     "type": "cconnect.photo.request",
+//!     "body": {}
+//! }
+//! ```
+//!
+//! **Photo (incoming)**:
+//! ```json
+//! {
+//!     "type": "cconnect.photo",
+//!     "body": {
+//!         "filename": "IMG_20240115_143045.jpg"
+//!     },
+//!     "payloadSize": 2457600,
+//!     "payloadTransferInfo": {
+//!         "port": 1739
+//!     }
+//! }
+//! ```
+//!
+//! ## Cancellation
+//!
+//! If the user closes the camera without taking a picture, the phone sends a
+//! `cconnect.photo` packet without payload. The plugin then reports
+//! [`PhotoEvent::Cancelled`] instead of downloading anything.
+//!
+//! ## Events
+//!
+//! Results are reported to the daemon as internal packets, see
+//! [`PhotoEvent`]: `cconnect.internal.photo.received` with the path of the
+//! downloaded picture, or `cconnect.internal.photo.cancelled`.
+
+use crate::{Device, Packet, ProtocolError, Result, TlsConfig, TlsPayloadClient};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::any::Any;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+use super::{Plugin, PluginFactory};
+
+/// Packet type for photo requests (outgoing)
+pub const PACKET_TYPE_PHOTO_REQUEST: &str = "cconnect.photo.request";
+
+/// Packet type for taken photos (incoming)
+pub const PACKET_TYPE_PHOTO: &str = "cconnect.photo";
+
+/// Internal packet type reporting a downloaded photo
+pub const PACKET_TYPE_PHOTO_RECEIVED: &str = "cconnect.internal.photo.received";
+
+/// Internal packet type reporting a cancelled photo request
+pub const PACKET_TYPE_PHOTO_CANCELLED: &str = "cconnect.internal.photo.cancelled";
+
+/// Outcome of a photo request, reported to the daemon
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PhotoEvent {
+    /// The photo was downloaded to `path`
+    PhotoReceived { path: PathBuf },
+    /// The user closed the camera without taking a photo
+    Cancelled,
+}
+
+impl PhotoEvent {
+    /// Build the internal packet carrying this event
+    pub fn to_packet(&self) -> Packet {
+        match self {
+            Self::PhotoReceived { path } => Packet::new(
+                PACKET_TYPE_PHOTO_RECEIVED,
+                json!({ "path": path.to_string_lossy() }),
+            ),
+            Self::Cancelled => Packet::new(PACKET_TYPE_PHOTO_CANCELLED, json!({})),
+        }
+    }
+
+    /// Parse an internal photo packet
+    ///
+    /// Returns `None` for any other packet.
+    pub fn from_packet(packet: &Packet) -> Option<Self> {
+        match packet.packet_type.as_str() {
+            PACKET_TYPE_PHOTO_RECEIVED => {
+                let path = packet.body.get("path")?.as_str()?;
+                Some(Self::PhotoReceived { path: path.into() })
+            }
+            PACKET_TYPE_PHOTO_CANCELLED => Some(Self::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+/// Photo packet body (incoming)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PhotoInfo {
+    /// Name of the picture on the phone
+    #[serde(default)]
+    pub filename: Option<String>,
+}
+
+/// Photo ready to be downloaded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhotoDownload {
+    /// Device that took the photo
+    pub device_id: String,
+
+    /// Name of the device that took the photo
+    pub device_name: String,
+
+    /// Remote host serving the payload
+    pub host: String,
+
+    /// Remote port serving the payload
+    pub port: u16,
+
+    /// Payload size in bytes
+    pub size: u64,
+
+    /// Where the photo is saved
+    pub path: PathBuf,
+}
+
+/// Starts payload downloads of taken photos
+pub trait PhotoDownloader: Send + Sync {
+    /// Begin downloading a photo in the background
+    ///
+    /// A [`PhotoEvent::PhotoReceived`] packet, with the device name added as
+    /// `deviceName`, is sent through `reporter` once the photo is downloaded.
+    fn download(
+        &self,
+        download: PhotoDownload,
+        tls_config: Option<Arc<TlsConfig>>,
+        reporter: Option<mpsc::Sender<(String, Packet)>>,
+    );
+}
+
+/// Downloads photo payloads over TLS
+///
+/// Downloads are refused when no TLS config is set, rather than receiving
+/// the photo over an unencrypted connection.
+#[derive(Debug, Default)]
+pub struct PayloadPhotoDownloader;
+
+impl PayloadPhotoDownloader {
+    /// Receive the payload into `download.path`
+    async fn receive(download: &PhotoDownload, tls_config: Option<Arc<TlsConfig>>) -> Result<()> {
+        let config = tls_config.ok_or_else(|| {
+            ProtocolError::Configuration(
+                "TLS config not set. Call set_tls_config() on PhotoPlugin before receiving photos."
+                    .to_string(),
+            )
+        })?;
+
+        if let Some(dir) = download.path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+
+        TlsPayloadClient::new(&download.host, download.port, &config)
+            .await?
+            .receive_file(&download.path, download.size)
+            .await
+    }
+}
+
+impl PhotoDownloader for PayloadPhotoDownloader {
+    fn download(
+        &self,
+        download: PhotoDownload,
+        tls_config: Option<Arc<TlsConfig>>,
+        reporter: Option<mpsc::Sender<(String, Packet)>>,
+    ) {
+        tokio::spawn(async move {
+            info!(
+                "Downloading photo from {} ({}:{}) to {:?}",
+                download.device_id, download.host, download.port, download.path
+            );
+
+            if let Err(e) = Self::receive(&download, tls_config).await {
+                warn!(
+                    "Failed to download photo from {}: {}",
+                    download.device_id, e
+                );
+                return;
+            }
+
+            info!("Received photo {:?}", download.path);
+            if let Some(reporter) = reporter {
+                let event = PhotoEvent::PhotoReceived {
+                    path: download.path.clone(),
+                };
+                let mut packet = event.to_packet();
+                packet.body["deviceName"] = json!(download.device_name);
+                if let Err(e) = reporter.send((download.device_id, packet)).await {
+                    warn!("Failed to report received photo: {}", e);
+                }
+            }
+        });
+    }
+}
+
+/// Default directory for received photos
+fn default_photo_dir() -> PathBuf {
+    dirs::picture_dir()
+        .or_else(dirs::home_dir)
+        .unwrap_or_else(std::env::temp_dir)
+}
+
+/// Photo plugin
+///
+/// Sends photo requests and downloads the pictures the phone sends back.
+///
+/// ## Example
+///
+/// ```rust
+/// use cosmic_connect_core::plugins::photo::PhotoPlugin;
+/// use cosmic_connect_core::Plugin;
+///
+/// let plugin = PhotoPlugin::new();
+/// assert_eq!(plugin.name(), "photo");
+///
+/// let packet = PhotoPlugin::create_photo_request();
+/// assert_eq!(packet.packet_type, "cconnect.photo.request");
+/// ```
+pub struct PhotoPlugin {
+    device_id: Option<String>,
+    packet_sender: Option<mpsc::Sender<(String, Packet)>>,
+    /// TLS configuration for payload downloads
+    tls_config: Option<Arc<TlsConfig>>,
+    /// Directory received photos are saved to
+    photo_dir: PathBuf,
+    /// Starts photo downloads
+    downloader: Box<dyn PhotoDownloader>,
+}
+
+impl PhotoPlugin {
+    /// Create a new Photo plugin
+    pub fn new() -> Self {
+        Self::with_downloader(Box::new(PayloadPhotoDownloader))
+    }
+
+    /// Create a Photo plugin with a custom downloader
+    pub fn with_downloader(downloader: Box<dyn PhotoDownloader>) -> Self {
+        Self {
+            device_id: None,
+            packet_sender: None,
+            tls_config: None,
+            photo_dir: default_photo_dir(),
+            downloader,
+        }
+    }
+
+    /// Set the TLS configuration used for payload downloads
+    pub fn set_tls_config(&mut self, config: Arc<TlsConfig>) {
+        self.tls_config = Some(config);
+    }
+
+    /// Set the directory received photos are saved to
+    pub fn set_photo_dir(&mut self, dir: impl Into<PathBuf>) {
+        self.photo_dir = dir.into();
+    }
+
+    /// Directory received photos are saved to
+    pub fn photo_dir(&self) -> &Path {
+        &self.photo_dir
+    }
+
+    /// Create a packet asking the remote device to take a photo
+    pub fn create_photo_request() -> Packet {
+        Packet::new(PACKET_TYPE_PHOTO_REQUEST, json!({}))
+    }
+
+    /// Path a photo is saved to
+    ///
+    /// Only the file name of the remote name is used, so the phone cannot
+    /// write outside the photo directory.
+    fn photo_path(&self, filename: Option<&str>) -> PathBuf {
+        let name = filename
+            .and_then(|name| Path::new(name).file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| {
+                format!("photo_{}.jpg", chrono::Local::now().format("%Y%m%d_%H%M%S"))
+            });
+        self.photo_dir.join(name)
+    }
+
+    /// Report an event to the daemon
+    async fn report(&self, event: PhotoEvent) -> Result<()> {
+        if let (Some(sender), Some(device_id)) = (&self.packet_sender, &self.device_id) {
+            sender
+                .send((device_id.clone(), event.to_packet()))
+                .await
+                .map_err(|e| ProtocolError::Transport(format!("Failed to send packet: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Handle a photo sent by the remote device
+    async fn handle_photo(&self, packet: &Packet, device: &Device) -> Result<()> {
+        let info: PhotoInfo = serde_json::from_value(packet.body.clone()).map_err(|e| {
+            ProtocolError::InvalidPacket(format!("Failed to parse photo packet: {}", e))
+        })?;
+
+        let port = packet
+            .payload_transfer_info
+            .as_ref()
+            .and_then(|transfer_info| transfer_info.get("port"))
+            .and_then(|port| port.as_u64());
+        let (Some(port), Some(size)) = (port, packet.payload_size) else {
+            info!("Photo request cancelled on {}", device.name());
+            return self.report(PhotoEvent::Cancelled).await;
+        };
+
+        let Some(host) = &device.host else {
+            warn!("Cannot download photo: device host not available");
+            return Ok(());
+        };
+
+        let download = PhotoDownload {
+            device_id: device.id().to_string(),
+            device_name: device.name().to_string(),
+            host: host.clone(),
+            port: port as u16,
+            size: size.max(0) as u64,
+            path: self.photo_path(info.filename.as_deref()),
+        };
+        debug!("Starting photo download: {:?}", download);
+
+        self.downloader.download(
+            download,
+            self.tls_config.clone(),
+            self.packet_sender.clone(),
+        );
+        Ok(())
+    }
+}
+
+impl Default for PhotoPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Plugin for PhotoPlugin {
+    fn name(&self) -> &str {
+        "photo"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn incoming_capabilities(&self) -> Vec<String> {
+        vec![
+            PACKET_TYPE_PHOTO.to_string(),
+            "kdeconnect.photo".to_string(),
+        ]
+    }
+
+    fn outgoing_capabilities(&self) -> Vec<String> {
+        vec![
+            PACKET_TYPE_PHOTO_REQUEST.to_string(),
+            "kdeconnect.photo.request".to_string(),
+        ]
+    }
+
+    async fn init(
+        &mut self,
+        device: &Device,
+        packet_sender: mpsc::Sender<(String, Packet)>,
+    ) -> Result<()> {
+        self.device_id = Some(device.id().to_string());
+        self.packet_sender = Some(packet_sender);
+        Ok(())
+    }
+
+    async fn start(&mut self) -> Result<()> {
+        info!("Photo plugin started");
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        info!("Photo plugin stopped");
+        Ok(())
+    }
+
+    async fn handle_packet(&mut self, packet: &Packet, device: &mut Device) -> Result<()> {
+        if packet.is_type(PACKET_TYPE_PHOTO) {
+            self.handle_photo(packet, device).await
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Factory for creating PhotoPlugin instances
+pub struct PhotoPluginFactory;
+
+impl PluginFactory for PhotoPluginFactory {
+    fn name(&self) -> &str {
+        "photo"
+    }
+
+    fn incoming_capabilities(&self) -> Vec<String> {
+        vec![
+            PACKET_TYPE_PHOTO.to_string(),
+            "kdeconnect.photo".to_string(),
+        ]
+    }
+
+    fn outgoing_capabilities(&self) -> Vec<String> {
+        vec![
+            PACKET_TYPE_PHOTO_REQUEST.to_string(),
+            "kdeconnect.photo.request".to_string(),
+        ]
+    }
+
+    fn create(&self) -> Box<dyn Plugin> {
+        Box::new(PhotoPlugin::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DeviceInfo, DeviceType};
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[derive(Clone, Default)]
+    struct RecordingDownloader {
+        downloads: Arc<Mutex<Vec<PhotoDownload>>>,
+    }
+
+    impl PhotoDownloader for RecordingDownloader {
+        fn download(
+            &self,
+            download: PhotoDownload,
+            _tls_config: Option<Arc<TlsConfig>>,
+            _reporter: Option<mpsc::Sender<(String, Packet)>>,
+        ) {
+            self.downloads.lock().unwrap().push(download);
+        }
+    }
+
+    async fn setup() -> (
+        PhotoPlugin,
+        RecordingDownloader,
+        mpsc::Receiver<(String, Packet)>,
+        Device,
+    ) {
+        let info = DeviceInfo::new("Test Phone", DeviceType::Phone, 1716);
+        let mut device = Device::from_discovery(info);
+        device.mark_connected("192.168.1.20".to_string(), 1716);
+
+        let downloader = RecordingDownloader::default();
+        let mut plugin = PhotoPlugin::with_downloader(Box::new(downloader.clone()));
+        plugin.set_photo_dir("/tmp/photos");
+        let (tx, rx) = mpsc::channel(10);
+        plugin.init(&device, tx).await.unwrap();
+
+        (plugin, downloader, rx, device)
+    }
+
+    #[test]
+    fn test_photo_request_packet() {
+        let packet = PhotoPlugin::create_photo_request();
+        assert_eq!(packet.packet_type, PACKET_TYPE_PHOTO_REQUEST);
+        assert_eq!(packet.body, json!({}));
+        assert!(packet.payload_size.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_received_photo_is_downloaded() {
+        let (mut plugin, downloader, mut rx, mut device) = setup().await;
+
+        let packet = Packet::new("kdeconnect.photo", json!({ "filename": "../IMG_1.jpg" }))
+            .with_payload_size(2048)
+            .with_payload_transfer_info(HashMap::from([("port".to_string(), json!(1739))]));
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        let downloads = downloader.downloads.lock().unwrap().clone();
+        assert_eq!(
+            downloads,
+            vec![PhotoDownload {
+                device_id: device.id().to_string(),
+                device_name: "Test Phone".to_string(),
+                host: "192.168.1.20".to_string(),
+                port: 1739,
+                size: 2048,
+                path: PathBuf::from("/tmp/photos/IMG_1.jpg"),
+            }]
+        );
+        // The downloader reports the photo once it is saved
+        assert!(rx.try_recv().is_err());
+
+        let event = PhotoEvent::PhotoReceived {
+            path: downloads[0].path.clone(),
+        };
+        assert_eq!(PhotoEvent::from_packet(&event.to_packet()), Some(event));
+    }
+
+    #[tokio::test]
+    async fn test_photo_without_payload_is_cancelled() {
+        let (mut plugin, downloader, mut rx, mut device) = setup().await;
+
+        let packet = Packet::new("kdeconnect.photo", json!({}));
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        assert!(downloader.downloads.lock().unwrap().is_empty());
+        let (device_id, event) = rx.try_recv().unwrap();
+        assert_eq!(device_id, device.id());
+        assert_eq!(PhotoEvent::from_packet(&event), Some(PhotoEvent::Cancelled));
+    }
+
+    #[tokio::test]
+    async fn test_download_refused_without_tls() {
+        let dir = tempfile::tempdir().unwrap();
+        let download = PhotoDownload {
+            device_id: "phone".to_string(),
+            device_name: "Test Phone".to_string(),
+            host: "127.0.0.1".to_string(),
+            port: 1739,
+            size: 2048,
+            path: dir.path().join("photos").join("IMG_1.jpg"),
+        };
+
+        let result = PayloadPhotoDownloader::receive(&download, None).await;
+        assert!(matches!(result, Err(ProtocolError::Configuration(_))));
+        assert!(!dir.path().join("photos").exists());
+    }
+}
@@ -25,12 +25,57 @@
 //!
 //! The `message` field is optional. If omitted, the packet body is empty.
 //!
+//! ## Round-Trip Time
+//!
+//! Plain pings are shown as notifications on most peers, so latency is measured
+//! with a separate `cconnect.ping.rtt` probe, only sent to devices that
+//! advertise it. The peer echoes the probe back with `"reply": true`:
+//!
+//! ```json
+//! {
+//!     "type": "cconnect.ping.rtt",
+//!     "body": {
+//!         "timestamp": 1234567890123,
+//!         "reply": true
+//!     }
+//! }
+//! ```
+//!
+//! Probes are sent every `RTT_PROBE_INTERVAL` while the plugin is running, and
+//! the measured round-trip times feed the device's connection quality.
+//!
 //! ## Behavior
 //!
 //! - **Receiving**: When a ping is received, it's logged and can trigger notifications
 //! - **Bidirectional**: Both devices can send and receive pings
 //! - **Simple**: No response required, fire-and-forget
-//! - **Notifications**: Pings are typically displayed as notifications
+//! - **Notifications**: Pings with a notifier set are shown as desktop
+//!   notifications; keepalive pings are not
+//!
+//! ## Notification Actions
+//!
+//! Ping notifications carry the actions from the plugin settings, by default
+//! a single "Open manager" button that focuses the manager on the device:
+//!
+//! ```json
+//! {
+//!     "actions": [
+//!         { "id": "open-manager", "label": "Open manager", "target": { "type": "open_device" } }
+//!     ]
+//! }
+//! ```
+//!
+//! ## Notification Style
+//!
+//! Pings from several devices can be told apart by giving each device its
+//! own style in its plugin settings, stored with the device configuration.
+//! By default pings are normal notifications without sound:
+//!
+//! ```json
+//! { "urgency": "critical", "sound": true }
+//! ```
+//!
+//! With `sound` set, [`PING_SOUND_NAME`] is played from the sound theme.
 //!
 //! ## Use Cases
 //!
@@ -63,15 +108,70 @@
 //!
 //! - [Valent Protocol - Ping](https://valent.andyholmes.ca/documentation/protocol.html)
 
-use crate::{Device, Packet, Result};
+use crate::{current_timestamp, Device, Packet, Result};
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tracing::{debug, info};
-
+use std::time::Duration;
+use tokio::sync::mpsc::Sender;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use super::metrics::PluginMetrics;
+use super::notifier::{
+    ActionTarget, NotificationAction, NotificationSpec, NotificationUrgency, Notifier,
+};
+use super::settings::PluginSettings;
 use super::{Plugin, PluginFactory};
 
+/// Packet type for round-trip time probes
+pub const PACKET_TYPE_PING_RTT: &str = "cconnect.ping.rtt";
+
+/// Interval between round-trip time probes
+///
+/// Kept long so that measuring the connection adds no noticeable traffic.
+pub const RTT_PROBE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Replies older than this are ignored as stale
+const MAX_RTT_MS: i64 = 60_000;
+
+/// Key of the default action that opens the manager on the device
+pub const OPEN_MANAGER_ACTION: &str = "open-manager";
+
+/// Sound theme name played for pings with sound enabled
+pub const PING_SOUND_NAME: &str = "message-new-instant";
+
+/// Ping plugin settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PingSettings {
+    /// Actions attached to ping notifications
+    pub actions: Vec<NotificationAction>,
+
+    /// Urgency of ping notifications
+    #[serde(default)]
+    pub urgency: NotificationUrgency,
+
+    /// Whether ping notifications play a sound
+    #[serde(default)]
+    pub sound: bool,
+}
+
+impl Default for PingSettings {
+    fn default() -> Self {
+        Self {
+            actions: vec![NotificationAction::new(
+                OPEN_MANAGER_ACTION,
+                "Open manager",
+                ActionTarget::OpenDevice,
+            )],
+            urgency: NotificationUrgency::Normal,
+            sound: false,
+        }
+    }
+}
+
 /// Ping plugin for connectivity testing
 ///
 /// Handles `cconnect.ping` packets for simple device-to-device communication
@@ -104,6 +204,30 @@ pub struct PingPlugin {
 
     /// Count of pings sent
     pings_sent: Arc<AtomicU64>,
+
+    /// Packet sender for RTT probes and replies
+    packet_sender: Option<Sender<(String, Packet)>>,
+
+    /// Whether the device answers RTT probes
+    rtt_supported: bool,
+
+    /// Periodic RTT probe task
+    probe_task: Option<JoinHandle<()>>,
+
+    /// Notifier for received pings
+    notifier: Option<Arc<dyn Notifier>>,
+
+    /// Actions attached to ping notifications
+    notification_actions: Vec<NotificationAction>,
+
+    /// Urgency of ping notifications
+    notification_urgency: NotificationUrgency,
+
+    /// Whether ping notifications play a sound
+    notification_sound: bool,
+
+    /// Counters of this plugin for the device
+    metrics: Option<Arc<PluginMetrics>>,
 }
 
 impl PingPlugin {
@@ -123,9 +247,22 @@ impl PingPlugin {
             device_id: None,
             pings_received: Arc::new(AtomicU64::new(0)),
             pings_sent: Arc::new(AtomicU64::new(0)),
+            packet_sender: None,
+            rtt_supported: false,
+            probe_task: None,
+            notifier: None,
+            notification_actions: PingSettings::default().actions,
+            notification_urgency: NotificationUrgency::Normal,
+            notification_sound: false,
+            metrics: None,
         }
     }
 
+    /// Actions attached to ping notifications
+    pub fn notification_actions(&self) -> &[NotificationAction] {
+        &self.notification_actions
+    }
+
     /// Get the number of pings received
     ///
     /// # Example
@@ -191,6 +328,52 @@ impl PingPlugin {
         Packet::new("cconnect.ping", body)
     }
 
+    /// Create a round-trip time probe stamped with `timestamp_ms`
+    pub fn create_rtt_probe(timestamp_ms: i64) -> Packet {
+        Packet::new(PACKET_TYPE_PING_RTT, json!({ "timestamp": timestamp_ms }))
+    }
+
+    /// Handle an RTT probe or a reply to one of ours
+    async fn handle_rtt(&self, packet: &Packet, device: &mut Device) {
+        let Some(timestamp) = packet.body.get("timestamp").and_then(|v| v.as_i64()) else {
+            debug!("Ignoring RTT packet without timestamp from {}", device.id());
+            return;
+        };
+
+        let is_reply = packet
+            .body
+            .get("reply")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if is_reply {
+            let rtt = current_timestamp() - timestamp;
+            if (0..=MAX_RTT_MS).contains(&rtt) {
+                device.record_rtt(rtt as u32);
+                debug!(
+                    "RTT to {}: {}ms (average {:?}ms)",
+                    device.name(),
+                    rtt,
+                    device.rtt_ms()
+                );
+            } else {
+                debug!("Ignoring stale RTT reply from {}", device.id());
+            }
+            return;
+        }
+
+        // Echo the probe back unchanged so the sender can measure it
+        if let Some(sender) = &self.packet_sender {
+            let reply = Packet::new(
+                PACKET_TYPE_PING_RTT,
+                json!({ "timestamp": timestamp, "reply": true }),
+            );
+            if let Err(e) = sender.send((device.id().to_string(), reply)).await {
+                warn!("Failed to send RTT reply to {}: {}", device.id(), e);
+            }
+        }
+    }
+
     /// Handle an incoming ping packet
     ///
     /// Processes a received ping, extracts any message, and updates statistics.
@@ -222,6 +405,50 @@ impl PingPlugin {
             self.pings_sent()
         );
     }
+
+    /// Show a desktop notification for a received ping
+    async fn notify_ping(&self, packet: &Packet, device: &Device) {
+        let Some(notifier) = &self.notifier else {
+            return;
+        };
+
+        // Keepalive pings maintain the connection and are not meant for the user
+        if packet
+            .body
+            .get("keepalive")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+        {
+            debug!("Keepalive ping from {} - no notification", device.name());
+            return;
+        }
+
+        let summary = format!("Ping from {}", device.name());
+        let body = match packet.body.get("message").and_then(|v| v.as_str()) {
+            Some(message) => format!("\"{}\"", message),
+            None => summary.clone(),
+        };
+
+        let mut spec = NotificationSpec::new(summary)
+            .body(body)
+            .urgency(self.notification_urgency)
+            .device(device.id());
+        if self.notification_sound {
+            spec = spec.sound(PING_SOUND_NAME);
+        }
+        for action in &self.notification_actions {
+            spec = spec.action(action.clone());
+        }
+
+        match notifier.notify(spec).await {
+            Ok(_) => {
+                if let Some(metrics) = &self.metrics {
+                    metrics.record_notification();
+                }
+            }
+            Err(e) => warn!("Failed to show ping notification: {}", e),
+        }
+    }
 }
 
 impl Default for PingPlugin {
@@ -245,29 +472,59 @@ impl Plugin for PingPlugin {
     }
 
     fn incoming_capabilities(&self) -> Vec<String> {
-        vec!["cconnect.ping".to_string(), "kdeconnect.ping".to_string()]
+        vec![
+            "cconnect.ping".to_string(),
+            "kdeconnect.ping".to_string(),
+            PACKET_TYPE_PING_RTT.to_string(),
+        ]
     }
 
     fn outgoing_capabilities(&self) -> Vec<String> {
-        vec!["cconnect.ping".to_string()]
+        vec![
+            "cconnect.ping".to_string(),
+            PACKET_TYPE_PING_RTT.to_string(),
+        ]
     }
 
     async fn init(
         &mut self,
         device: &Device,
-        _packet_sender: tokio::sync::mpsc::Sender<(String, Packet)>,
+        packet_sender: tokio::sync::mpsc::Sender<(String, Packet)>,
     ) -> Result<()> {
         self.device_id = Some(device.id().to_string());
+        self.packet_sender = Some(packet_sender);
+        self.rtt_supported = device.has_incoming_capability(PACKET_TYPE_PING_RTT);
         info!("Ping plugin initialized for device {}", device.name());
         Ok(())
     }
 
     async fn start(&mut self) -> Result<()> {
+        if let (true, Some(device_id), Some(sender)) = (
+            self.rtt_supported,
+            self.device_id.clone(),
+            self.packet_sender.clone(),
+        ) {
+            self.probe_task = Some(tokio::spawn(async move {
+                let mut interval = tokio::time::interval(RTT_PROBE_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    let probe = PingPlugin::create_rtt_probe(current_timestamp());
+                    if sender.send((device_id.clone(), probe)).await.is_err() {
+                        break;
+                    }
+                }
+            }));
+        }
+
         info!("Ping plugin started");
         Ok(())
     }
 
     async fn stop(&mut self) -> Result<()> {
+        if let Some(task) = self.probe_task.take() {
+            task.abort();
+        }
+
         info!(
             "Ping plugin stopped - received: {}, sent: {}",
             self.pings_received(),
@@ -277,11 +534,30 @@ impl Plugin for PingPlugin {
     }
 
     async fn handle_packet(&mut self, packet: &Packet, device: &mut Device) -> Result<()> {
-        if packet.is_type("cconnect.ping") || packet.is_type("kdeconnect.ping") {
+        if packet.is_type(PACKET_TYPE_PING_RTT) {
+            self.handle_rtt(packet, device).await;
+        } else if packet.is_type("cconnect.ping") || packet.is_type("kdeconnect.ping") {
             self.handle_ping(packet, device);
+            self.notify_ping(packet, device).await;
         }
         Ok(())
     }
+
+    fn set_notifier(&mut self, notifier: Arc<dyn Notifier>) {
+        self.notifier = Some(notifier);
+    }
+
+    fn set_settings(&mut self, settings: PluginSettings) {
+        if let Some(ping) = settings.get::<PingSettings>() {
+            self.notification_actions = ping.actions;
+            self.notification_urgency = ping.urgency;
+            self.notification_sound = ping.sound;
+        }
+    }
+
+    fn set_metrics(&mut self, metrics: Arc<PluginMetrics>) {
+        self.metrics = Some(metrics);
+    }
 }
 
 /// Factory for creating PingPlugin instances
@@ -309,11 +585,18 @@ impl PluginFactory for PingPluginFactory {
     }
 
     fn incoming_capabilities(&self) -> Vec<String> {
-        vec!["cconnect.ping".to_string(), "kdeconnect.ping".to_string()]
+        vec![
+            "cconnect.ping".to_string(),
+            "kdeconnect.ping".to_string(),
+            PACKET_TYPE_PING_RTT.to_string(),
+        ]
     }
 
     fn outgoing_capabilities(&self) -> Vec<String> {
-        vec!["cconnect.ping".to_string()]
+        vec![
+            "cconnect.ping".to_string(),
+            PACKET_TYPE_PING_RTT.to_string(),
+        ]
     }
 
     fn create(&self) -> Box<dyn Plugin> {
@@ -324,6 +607,8 @@ impl PluginFactory for PingPluginFactory {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::plugins::notifier::MockNotifier;
+    use crate::plugins::settings::{MemorySettingsStore, PluginSettingsStore};
     use crate::{DeviceInfo, DeviceType};
 
     fn create_test_device() -> Device {
@@ -344,12 +629,13 @@ mod tests {
         let plugin = PingPlugin::new();
 
         let incoming = plugin.incoming_capabilities();
-        assert_eq!(incoming.len(), 2);
+        assert_eq!(incoming.len(), 3);
         assert!(incoming.contains(&"cconnect.ping".to_string()));
         assert!(incoming.contains(&"kdeconnect.ping".to_string()));
+        assert!(incoming.contains(&PACKET_TYPE_PING_RTT.to_string()));
 
         let outgoing = plugin.outgoing_capabilities();
-        assert_eq!(outgoing.len(), 1);
+        assert_eq!(outgoing.len(), 2);
         assert_eq!(outgoing[0], "cconnect.ping");
     }
 
@@ -465,6 +751,139 @@ mod tests {
         assert_eq!(plugin.pings_received(), 0);
     }
 
+    #[tokio::test]
+    async fn test_rtt_probe_is_echoed_and_reply_recorded() {
+        let mut plugin = PingPlugin::new();
+        let mut device = create_test_device();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        plugin.init(&device, tx).await.unwrap();
+
+        // A probe from the peer is echoed back as a reply
+        let probe = PingPlugin::create_rtt_probe(42);
+        plugin.handle_packet(&probe, &mut device).await.unwrap();
+        let (_, reply) = rx.try_recv().unwrap();
+        assert!(reply.is_type(PACKET_TYPE_PING_RTT));
+        assert_eq!(reply.body["timestamp"], 42);
+        assert_eq!(reply.body["reply"], true);
+        assert_eq!(plugin.pings_received(), 0);
+
+        // A reply to our own probe is recorded on the device
+        let reply = Packet::new(
+            PACKET_TYPE_PING_RTT,
+            json!({ "timestamp": current_timestamp() - 30, "reply": true }),
+        );
+        plugin.handle_packet(&reply, &mut device).await.unwrap();
+        assert!(device.rtt_ms().is_some());
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_ping_raises_notification() {
+        let notifier = Arc::new(MockNotifier::new());
+        let metrics = Arc::new(PluginMetrics::default());
+        let mut plugin = PingPlugin::new();
+        let mut device = create_test_device();
+        plugin.set_notifier(notifier.clone());
+        plugin.set_metrics(metrics.clone());
+        plugin
+            .init(&device, tokio::sync::mpsc::channel(100).0)
+            .await
+            .unwrap();
+
+        let packet = Packet::new("cconnect.ping", json!({ "message": "Hi" }));
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+        let keepalive = Packet::new("cconnect.ping", json!({ "keepalive": true }));
+        plugin.handle_packet(&keepalive, &mut device).await.unwrap();
+
+        let sent = notifier.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(metrics.snapshot().notifications_raised, 1);
+        assert_eq!(sent[0].summary, "Ping from Test Device");
+        assert_eq!(sent[0].body, "\"Hi\"");
+        assert_eq!(sent[0].device_id.as_deref(), Some(device.id()));
+        assert_eq!(
+            sent[0]
+                .find_action(OPEN_MANAGER_ACTION)
+                .map(|action| &action.target),
+            Some(&ActionTarget::OpenDevice)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ping_notification_uses_configured_actions() {
+        let store: Arc<dyn PluginSettingsStore> = Arc::new(MemorySettingsStore::new());
+        let mut device = create_test_device();
+        let mut settings = PluginSettings::load(store, device.id(), "ping").await;
+        settings
+            .save_config(&PingSettings {
+                actions: vec![NotificationAction::new(
+                    "commands",
+                    "Edit commands",
+                    ActionTarget::OpenPage {
+                        page: "commands".to_string(),
+                    },
+                )],
+                ..PingSettings::default()
+            })
+            .await
+            .unwrap();
+
+        let notifier = Arc::new(MockNotifier::new());
+        let mut plugin = PingPlugin::new();
+        plugin.set_notifier(notifier.clone());
+        plugin.set_settings(settings);
+        plugin
+            .init(&device, tokio::sync::mpsc::channel(100).0)
+            .await
+            .unwrap();
+
+        let packet = Packet::new("kdeconnect.ping", json!({}));
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        let sent = notifier.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].actions.len(), 1);
+        assert_eq!(sent[0].actions[0].label, "Edit commands");
+        assert!(sent[0].find_action(OPEN_MANAGER_ACTION).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_ping_notification_style_per_device() {
+        let store: Arc<dyn PluginSettingsStore> = Arc::new(MemorySettingsStore::new());
+        let notifier = Arc::new(MockNotifier::new());
+        let packet = Packet::new("cconnect.ping", json!({}));
+
+        let mut urgent = create_test_device();
+        let mut settings = PluginSettings::load(store.clone(), urgent.id(), "ping").await;
+        settings
+            .save_config(&PingSettings {
+                urgency: NotificationUrgency::Critical,
+                sound: true,
+                ..PingSettings::default()
+            })
+            .await
+            .unwrap();
+        let mut plugin = PingPlugin::new();
+        plugin.set_notifier(notifier.clone());
+        plugin.set_settings(settings);
+        plugin.handle_packet(&packet, &mut urgent).await.unwrap();
+
+        // Nothing configured for this one
+        let mut quiet = Device::from_discovery(DeviceInfo::new("Tablet", DeviceType::Tablet, 1716));
+        let settings = PluginSettings::load(store, quiet.id(), "ping").await;
+        let mut plugin = PingPlugin::new();
+        plugin.set_notifier(notifier.clone());
+        plugin.set_settings(settings);
+        plugin.handle_packet(&packet, &mut quiet).await.unwrap();
+
+        let sent = notifier.sent();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[0].urgency, NotificationUrgency::Critical);
+        assert_eq!(sent[0].sound.as_deref(), Some(PING_SOUND_NAME));
+        assert_eq!(sent[1].urgency, NotificationUrgency::Normal);
+        assert_eq!(sent[1].sound, None);
+    }
+
     #[test]
     fn test_statistics() {
         let plugin = PingPlugin::new();
@@ -25,6 +25,13 @@
 //! Presenter packets contain one of:
 //! - `dx`, `dy`: Pointer movement delta (for laser pointer)
 //! - `stop`: Boolean, true to stop presentation mode
+//! - `slide`: `"next"` or `"previous"` to change slides
+//!
+//! ## Slide Navigation
+//!
+//! Slides are changed by pressing a key in the focused app. The key depends
+//! on the app, see [`slide_keys`]; Page Down/Up is used unless the plugin
+//! settings map the focused app to other keys.
 //!
 //! ## References
 //!
@@ -32,14 +39,20 @@
 //! - [Valent Protocol Documentation](https://valent.andyholmes.ca/documentation/protocol.html)
 
 pub mod laser_pointer;
+pub mod slide_keys;
 
 use crate::{Device, Packet, ProtocolError, Result};
 use async_trait::async_trait;
 use laser_pointer::LaserPointer;
 use serde::{Deserialize, Serialize};
+use slide_keys::{
+    FocusedAppSource, KeyPresser, SlideAction, SlideKeyMap, UinputKeys, XdotoolFocus,
+};
 use std::any::Any;
+use std::sync::Arc;
 use tracing::{debug, info};
 
+use super::settings::PluginSettings;
 use super::{Plugin, PluginFactory};
 
 // Re-export for external use
@@ -62,6 +75,10 @@ pub struct PresenterEvent {
     /// Stop presentation mode
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stop: Option<bool>,
+
+    /// Change slides
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slide: Option<SlideAction>,
 }
 
 /// Presenter plugin for presentation remote control
@@ -69,18 +86,35 @@ pub struct PresenterPlugin {
     device_id: Option<String>,
     presentation_active: bool,
     laser_pointer: LaserPointer,
+    key_map: SlideKeyMap,
+    focus: Arc<dyn FocusedAppSource>,
+    keys: Arc<dyn KeyPresser>,
 }
 
 impl PresenterPlugin {
     /// Create a new Presenter plugin
     pub fn new() -> Self {
+        Self::with_backends(Arc::new(XdotoolFocus), Arc::new(UinputKeys::default()))
+    }
+
+    /// Create a plugin that looks up the focused app and presses slide keys
+    /// through the given backends
+    pub fn with_backends(focus: Arc<dyn FocusedAppSource>, keys: Arc<dyn KeyPresser>) -> Self {
         Self {
             device_id: None,
             presentation_active: false,
             laser_pointer: LaserPointer::new(),
+            key_map: SlideKeyMap::default(),
+            focus,
+            keys,
         }
     }
 
+    /// Set the slide keys used per app
+    pub fn set_key_map(&mut self, key_map: SlideKeyMap) {
+        self.key_map = key_map;
+    }
+
     /// Get laser pointer reference
     pub fn laser_pointer(&self) -> &LaserPointer {
         &self.laser_pointer
@@ -96,6 +130,18 @@ impl PresenterPlugin {
         let event: PresenterEvent = serde_json::from_value(packet.body.clone())
             .map_err(|e| ProtocolError::InvalidPacket(format!("Failed to parse event: {}", e)))?;
 
+        if let Some(action) = event.slide {
+            let app_id = self.focus.focused_app_id().await;
+            let key = self.key_map.key_for(action, app_id.as_deref());
+            debug!(
+                "Slide {:?} in {} with {:?}",
+                action,
+                app_id.as_deref().unwrap_or("unknown app"),
+                key
+            );
+            return self.keys.press(key).await;
+        }
+
         // Handle stop event
         if event.stop.unwrap_or(false) {
             info!("Presentation mode stopped");
@@ -185,6 +231,12 @@ impl Plugin for PresenterPlugin {
             Ok(())
         }
     }
+
+    fn set_settings(&mut self, settings: PluginSettings) {
+        if let Some(key_map) = settings.get::<SlideKeyMap>() {
+            self.key_map = key_map;
+        }
+    }
 }
 
 /// Factory for creating Presenter plugin instances
@@ -215,8 +267,10 @@ impl PluginFactory for PresenterPluginFactory {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::plugins::remoteinput::SpecialKey;
     use crate::{DeviceInfo, DeviceType};
     use serde_json::json;
+    use slide_keys::SlideKeys;
 
     fn create_test_device() -> Device {
         let info = DeviceInfo::new("Test Device", DeviceType::Desktop, 1716);
@@ -296,6 +350,73 @@ mod tests {
         assert!(!plugin.laser_pointer().is_active());
     }
 
+    struct MockFocus(std::sync::Mutex<Option<String>>);
+
+    #[async_trait]
+    impl FocusedAppSource for MockFocus {
+        async fn focused_app_id(&self) -> Option<String> {
+            self.0.lock().unwrap().clone()
+        }
+    }
+
+    #[derive(Default)]
+    struct MockKeys(std::sync::Mutex<Vec<SpecialKey>>);
+
+    #[async_trait]
+    impl KeyPresser for MockKeys {
+        async fn press(&self, key: SpecialKey) -> Result<()> {
+            self.0.lock().unwrap().push(key);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slide_keys_follow_focused_app() {
+        let focus = Arc::new(MockFocus(std::sync::Mutex::new(None)));
+        let keys = Arc::new(MockKeys::default());
+        let mut plugin = PresenterPlugin::with_backends(focus.clone(), keys.clone());
+        plugin.set_key_map(SlideKeyMap {
+            apps: [
+                (
+                    "libreoffice-impress".to_string(),
+                    SlideKeys {
+                        next: SpecialKey::Right,
+                        previous: SpecialKey::Left,
+                    },
+                ),
+                (
+                    "okular".to_string(),
+                    SlideKeys {
+                        next: SpecialKey::Down,
+                        previous: SpecialKey::Up,
+                    },
+                ),
+            ]
+            .into(),
+            ..Default::default()
+        });
+        let mut device = create_test_device();
+        let next = Packet::new("cconnect.presenter", json!({ "slide": "next" }));
+
+        for app_id in [Some("libreoffice-impress"), Some("okular"), None] {
+            *focus.0.lock().unwrap() = app_id.map(str::to_string);
+            plugin.handle_packet(&next, &mut device).await.unwrap();
+        }
+        let previous = Packet::new("cconnect.presenter", json!({ "slide": "previous" }));
+        plugin.handle_packet(&previous, &mut device).await.unwrap();
+
+        assert_eq!(
+            *keys.0.lock().unwrap(),
+            vec![
+                SpecialKey::Right,
+                SpecialKey::Down,
+                SpecialKey::PageDown,
+                SpecialKey::PageUp
+            ]
+        );
+        assert!(!plugin.laser_pointer().is_active());
+    }
+
     #[test]
     fn test_factory() {
         let factory = PresenterPluginFactory;
@@ -0,0 +1,190 @@
+//! Focus-Aware Slide Navigation
+//!
+//! Presentation apps disagree on which keys change slides: most accept
+//! Page Down/Up, while others only react to the arrow keys. [`SlideKeyMap`]
+//! picks the keys for "next slide" and "previous slide" by the app id of the
+//! focused window, falling back to Page Down/Up.
+//!
+//! The map is read from the presenter plugin's settings, e.g.:
+//!
+//! ```json
+//! {
+//!     "apps": {
+//!         "libreoffice-impress": { "next": "Right", "previous": "Left" }
+//!     }
+//! }
+//! ```
+//!
+//! The focused app id is looked up through a [`FocusedAppSource`] and keys are
+//! pressed through a [`KeyPresser`], both replaceable for testing.
+//! [`UinputKeys`] presses keys through the same kernel virtual input device
+//! as remote input, on Wayland and X11 alike. [`XdotoolFocus`] only sees X11
+//! and XWayland windows; with native Wayland windows focused, the default
+//! keys are pressed.
+
+use crate::plugins::remoteinput::{RemoteInputPlugin, SpecialKey};
+use crate::{ProtocolError, Result};
+use async_trait::async_trait;
+use mouse_keyboard_input::VirtualDevice;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::process::Command;
+use tracing::info;
+
+/// Slide navigation requested by the phone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SlideAction {
+    /// Go to the next slide
+    Next,
+    /// Go to the previous slide
+    Previous,
+}
+
+/// Keys an app uses to change slides
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlideKeys {
+    /// Key for the next slide
+    pub next: SpecialKey,
+    /// Key for the previous slide
+    pub previous: SpecialKey,
+}
+
+impl Default for SlideKeys {
+    fn default() -> Self {
+        Self {
+            next: SpecialKey::PageDown,
+            previous: SpecialKey::PageUp,
+        }
+    }
+}
+
+impl SlideKeys {
+    /// Key for a slide action
+    pub fn key(&self, action: SlideAction) -> SpecialKey {
+        match action {
+            SlideAction::Next => self.next,
+            SlideAction::Previous => self.previous,
+        }
+    }
+}
+
+/// Slide keys per app id, with a fallback for all other apps
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlideKeyMap {
+    /// Keys by app id, e.g. `libreoffice-impress`
+    #[serde(default)]
+    pub apps: HashMap<String, SlideKeys>,
+    /// Keys for apps without an entry
+    #[serde(default)]
+    pub default: SlideKeys,
+}
+
+impl SlideKeyMap {
+    /// Key to press for a slide action in the focused app
+    ///
+    /// App ids are compared case-insensitively, since X11 window classes and
+    /// Wayland app ids differ in case for the same app.
+    pub fn key_for(&self, action: SlideAction, app_id: Option<&str>) -> SpecialKey {
+        app_id
+            .and_then(|app_id| {
+                self.apps
+                    .iter()
+                    .find(|(id, _)| id.eq_ignore_ascii_case(app_id))
+                    .map(|(_, keys)| keys)
+            })
+            .unwrap_or(&self.default)
+            .key(action)
+    }
+}
+
+/// Looks up the app id of the focused window
+#[async_trait]
+pub trait FocusedAppSource: Send + Sync {
+    /// App id of the focused window, if known
+    async fn focused_app_id(&self) -> Option<String>;
+}
+
+/// Presses keys on the desktop
+#[async_trait]
+pub trait KeyPresser: Send + Sync {
+    /// Press and release a key
+    async fn press(&self, key: SpecialKey) -> Result<()>;
+}
+
+/// Focused window class via `xdotool`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct XdotoolFocus;
+
+#[async_trait]
+impl FocusedAppSource for XdotoolFocus {
+    async fn focused_app_id(&self) -> Option<String> {
+        let output = Command::new("xdotool")
+            .args(["getactivewindow", "getwindowclassname"])
+            .output()
+            .await
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let class = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (!class.is_empty()).then_some(class)
+    }
+}
+
+/// Key presses via a kernel virtual input device
+///
+/// The device is created on the first key press and kept for later ones.
+#[derive(Default, Clone)]
+pub struct UinputKeys {
+    device: Arc<Mutex<Option<VirtualDevice>>>,
+}
+
+#[async_trait]
+impl KeyPresser for UinputKeys {
+    async fn press(&self, key: SpecialKey) -> Result<()> {
+        let key_code = RemoteInputPlugin::special_key_to_keycode(key as i32)
+            .ok_or_else(|| ProtocolError::Plugin(format!("No key code for {:?}", key)))?;
+
+        // Creating the device and writing to it block
+        let device = self.device.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut device = device.lock().unwrap();
+            if device.is_none() {
+                let created = VirtualDevice::default().map_err(|e| {
+                    ProtocolError::Plugin(format!("Failed to create virtual input device: {}", e))
+                })?;
+                info!("Created virtual input device for slide keys");
+                *device = Some(created);
+            }
+            if let Some(device) = device.as_mut() {
+                device.click(key_code).map_err(|e| {
+                    ProtocolError::Plugin(format!("Failed to press {:?}: {}", key, e))
+                })?;
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| ProtocolError::Plugin(format!("Key press task failed: {}", e)))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_format() {
+        let map: SlideKeyMap = serde_json::from_value(serde_json::json!({
+            "apps": { "okular": { "next": "Right", "previous": "Left" } }
+        }))
+        .unwrap();
+        assert_eq!(map.default, SlideKeys::default());
+        assert_eq!(
+            map.key_for(SlideAction::Previous, Some("Okular")),
+            SpecialKey::Left
+        );
+        assert_eq!(map.key_for(SlideAction::Next, None), SpecialKey::PageDown);
+    }
+}
@@ -9,10 +9,51 @@
 //! - `cconnect.mousepad.request` - Remote input request (incoming)
 //! - `cconnect.mousepad.echo` - Echo response (outgoing)
 //! - `cconnect.mousepad.keyboardstate` - Keyboard state broadcast (outgoing)
+//! - `cconnect.clipboard.type` - Type the clipboard content (incoming)
 //!
 //! **Capabilities**:
 //! - Incoming: `cconnect.mousepad.request` - Receives pointer and keyboard events
+//! - Incoming: `cconnect.clipboard.type` - Receives remote paste requests
 //! - Outgoing: `cconnect.mousepad.keyboardstate` - Sends keyboard support status
+//! - Outgoing: `cconnect.mousepad.echo` - Confirms applied input
+//!
+//! ## Input Acknowledgement
+//!
+//! When a request sets `sendAck`, an echo packet mirroring the request's
+//! input fields with `isAck: true` is sent right after the input has been
+//! handed to the backend. The phone uses it for its touchpad latency display.
+//!
+//! ## Text Input
+//!
+//! The `key` field may hold any UTF-8 text, including emoji and composed
+//! characters. It is split into grapheme clusters so that multi-codepoint
+//! characters are typed as a single unit. Characters on the active keyboard
+//! layout are sent through the virtual keyboard together with the modifiers
+//! the layout needs (see [`super::keymap`]); everything else is typed through
+//! the compositor's virtual-keyboard protocol (`wtype`), falling back to the
+//! Ctrl+Shift+U unicode entry sequence. Without a known layout, a US layout is
+//! assumed.
+//!
+//! ## Remote Paste
+//!
+//! Some apps block pasting into their fields. A `cconnect.clipboard.type`
+//! request (empty body) types the desktop's current clipboard content into
+//! the focused field as keystrokes, the same way as a `key` field. The
+//! clipboard itself is left unchanged. The request is handled here rather
+//! than by the clipboard plugin, so it is only accepted while remote input
+//! is enabled for the device.
+//!
+//! ## Input Backends
+//!
+//! The backend is chosen when the plugin is initialized:
+//!
+//! - **uinput**: a kernel virtual input device, used whenever `/dev/uinput`
+//!   is writable. Works on Wayland and X11 sessions alike.
+//! - **XTest**: the X11 XTest extension through `xdotool`, used on X11
+//!   sessions without uinput access.
+//! - **None**: neither is usable (e.g. headless sessions). The plugin warns
+//!   once, reports itself as degraded and drops input without failing the
+//!   connection.
 //!
 //! ## References
 //!
@@ -24,10 +65,14 @@ use async_trait::async_trait;
 use mouse_keyboard_input::VirtualDevice;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
-use std::sync::{Arc, Mutex};
+use std::process::Command;
+use std::sync::{Arc, Mutex, Once};
 use tracing::{debug, error, info, warn};
+use unicode_segmentation::UnicodeSegmentation;
 
-use super::{Plugin, PluginFactory};
+use super::clipboard_backend::{ClipboardAccess, ClipboardBackend};
+use super::keymap::{KeyEvent, KeymapCache};
+use super::{Plugin, PluginFactory, PluginStatus};
 
 /// Packet type for remote input requests
 pub const PACKET_TYPE_MOUSEPAD_REQUEST: &str = "cconnect.mousepad.request";
@@ -38,6 +83,55 @@ pub const PACKET_TYPE_MOUSEPAD_ECHO: &str = "cconnect.mousepad.echo";
 /// Packet type for keyboard state
 pub const PACKET_TYPE_MOUSEPAD_KEYBOARDSTATE: &str = "cconnect.mousepad.keyboardstate";
 
+/// Packet type for typing the clipboard content into the focused field
+pub const PACKET_TYPE_CLIPBOARD_TYPE: &str = "cconnect.clipboard.type";
+
+/// Path of the kernel virtual input device
+const UINPUT_PATH: &str = "/dev/uinput";
+
+/// Warn only once per process when no input backend is usable
+static NO_BACKEND_WARNING: Once = Once::new();
+
+/// Input injection backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputBackend {
+    /// Kernel virtual input device
+    Uinput,
+    /// X11 XTest extension via `xdotool`
+    XTest,
+    /// No usable backend; input is dropped
+    None,
+}
+
+impl InputBackend {
+    /// Detect the best input backend for this session
+    pub fn detect() -> Self {
+        let uinput = std::fs::OpenOptions::new()
+            .write(true)
+            .open(UINPUT_PATH)
+            .is_ok();
+        let xtest = std::env::var_os("DISPLAY").is_some()
+            && Command::new("xdotool")
+                .arg("version")
+                .output()
+                .map(|output| output.status.success())
+                .unwrap_or(false);
+
+        Self::select(uinput, xtest)
+    }
+
+    /// Choose a backend from the available ones, preferring uinput
+    fn select(uinput: bool, xtest: bool) -> Self {
+        if uinput {
+            Self::Uinput
+        } else if xtest {
+            Self::XTest
+        } else {
+            Self::None
+        }
+    }
+}
+
 /// Special key codes for non-printable characters
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[allow(dead_code)]
@@ -69,10 +163,46 @@ pub enum SpecialKey {
     F12 = 42,
 }
 
+/// A single unit of keyboard input taken from a `key` field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyInput {
+    /// Character with a direct Linux key code
+    Key(u16),
+    /// Grapheme cluster without a key code, typed as text
+    Text(String),
+}
+
+/// Split a `key` field into input units
+///
+/// Every grapheme cluster becomes exactly one unit, so emoji with skin tone
+/// modifiers or ZWJ sequences and characters with combining marks are never
+/// split or truncated.
+pub fn key_input_units(key: &str) -> Vec<KeyInput> {
+    key.graphemes(true)
+        .map(|grapheme| {
+            let mut chars = grapheme.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => RemoteInputPlugin::char_to_keycode(ch)
+                    .map(KeyInput::Key)
+                    .unwrap_or_else(|| KeyInput::Text(grapheme.to_string())),
+                _ => KeyInput::Text(grapheme.to_string()),
+            }
+        })
+        .collect()
+}
+
+/// Key codes for the hex digits of a code point in unicode entry mode
+fn unicode_entry_keycodes(ch: char) -> Vec<u16> {
+    format!("{:x}", ch as u32)
+        .chars()
+        .filter_map(RemoteInputPlugin::char_to_keycode)
+        .collect()
+}
+
 /// Remote input request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RemoteInputRequest {
-    /// Single readable character input
+    /// Readable text input, possibly multi-codepoint (emoji, composed characters)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub key: Option<String>,
 
@@ -140,7 +270,14 @@ pub struct RemoteInputRequest {
 /// Remote Input plugin for pointer and keyboard control
 pub struct RemoteInputPlugin {
     device_id: Option<String>,
+    packet_sender: Option<tokio::sync::mpsc::Sender<(String, Packet)>>,
     virtual_device: Arc<Mutex<Option<VirtualDevice>>>,
+    /// Input backend, detected at init unless set explicitly
+    backend: Option<InputBackend>,
+    /// Active keyboard layout
+    keymap: Arc<Mutex<KeymapCache>>,
+    /// System clipboard, read for remote paste
+    clipboard: Box<dyn ClipboardAccess>,
 }
 
 impl RemoteInputPlugin {
@@ -148,15 +285,115 @@ impl RemoteInputPlugin {
     pub fn new() -> Self {
         Self {
             device_id: None,
+            packet_sender: None,
             virtual_device: Arc::new(Mutex::new(None)),
+            backend: None,
+            keymap: Arc::new(Mutex::new(KeymapCache::new())),
+            clipboard: Box::new(ClipboardBackend::new()),
+        }
+    }
+
+    /// Create a plugin using a specific input backend instead of detecting one
+    pub fn with_backend(backend: InputBackend) -> Self {
+        Self {
+            backend: Some(backend),
+            ..Self::new()
         }
     }
 
+    /// Use a custom clipboard for remote paste
+    pub fn with_clipboard(mut self, clipboard: Box<dyn ClipboardAccess>) -> Self {
+        self.clipboard = clipboard;
+        self
+    }
+
+    /// Get the input backend in use
+    ///
+    /// Returns `None` before the plugin is initialized.
+    pub fn backend(&self) -> Option<InputBackend> {
+        self.backend
+    }
+
     /// Handle a remote input request packet
+    ///
+    /// Applies the input, then sends an echo if the request asked for one.
     async fn handle_request(&self, packet: &Packet) -> Result<()> {
         let request: RemoteInputRequest = serde_json::from_value(packet.body.clone())
             .map_err(|e| ProtocolError::InvalidPacket(format!("Failed to parse request: {}", e)))?;
 
+        self.apply_request(&request)?;
+
+        if request.send_ack.unwrap_or(false) {
+            self.send_echo(&request).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Handle a remote paste request
+    ///
+    /// Types the clipboard content as keystrokes. Returns the injected
+    /// request, or `None` if the clipboard is empty or unreadable.
+    async fn handle_clipboard_type(&self) -> Result<Option<RemoteInputRequest>> {
+        let content = match self.clipboard.read().await {
+            Some(content) if !content.is_empty() => content,
+            _ => {
+                debug!("Clipboard is empty or unreadable, nothing to type");
+                return Ok(None);
+            }
+        };
+
+        info!("Typing {} clipboard characters", content.chars().count());
+        let request = RemoteInputRequest {
+            key: Some(content),
+            ..Default::default()
+        };
+        self.apply_request(&request)?;
+
+        Ok(Some(request))
+    }
+
+    /// Create the echo packet acknowledging a request
+    ///
+    /// Mirrors the request's input fields and sets `isAck`.
+    pub fn create_echo_packet(request: &RemoteInputRequest) -> Result<Packet> {
+        let echo = RemoteInputRequest {
+            send_ack: None,
+            ..request.clone()
+        };
+        let mut body = serde_json::to_value(echo)?;
+        body["isAck"] = serde_json::Value::Bool(true);
+
+        Ok(Packet::new(PACKET_TYPE_MOUSEPAD_ECHO, body))
+    }
+
+    /// Send the echo for an applied request to the remote device
+    async fn send_echo(&self, request: &RemoteInputRequest) -> Result<()> {
+        if let (Some(sender), Some(device_id)) = (&self.packet_sender, &self.device_id) {
+            let packet = Self::create_echo_packet(request)?;
+            sender
+                .send((device_id.clone(), packet))
+                .await
+                .map_err(|e| ProtocolError::Transport(format!("Failed to send echo: {}", e)))?;
+        }
+
+        Ok(())
+    }
+
+    /// Inject a request through the active input backend
+    fn apply_request(&self, request: &RemoteInputRequest) -> Result<()> {
+        match self.backend.unwrap_or(InputBackend::None) {
+            InputBackend::Uinput => {}
+            InputBackend::XTest => {
+                Self::inject_xtest(request);
+                return Ok(());
+            }
+            InputBackend::None => {
+                debug!("No input backend available, dropping remote input");
+                return Ok(());
+            }
+        }
+
         // Get or create virtual device
         let device = {
             let mut device_guard = self.virtual_device.lock().unwrap();
@@ -244,15 +481,36 @@ impl RemoteInputPlugin {
         }
 
         // Handle keyboard input
+        let keymap = self.keymap.lock().unwrap().current();
         if let Some(key) = &request.key {
             debug!("Remote input: Key '{}'", key);
             let mut device_guard = device.lock().unwrap();
             if let Some(dev) = device_guard.as_mut() {
-                // Convert string to key codes and send
-                for ch in key.chars() {
-                    if let Some(key_code) = Self::char_to_keycode(ch) {
-                        if let Err(e) = dev.click(key_code) {
-                            warn!("Failed to send key '{}': {}", ch, e);
+                for grapheme in key.graphemes(true) {
+                    let mut chars = grapheme.chars();
+                    let single = match (chars.next(), chars.next()) {
+                        (Some(ch), None) => Some(ch),
+                        _ => None,
+                    };
+
+                    // Prefer the active layout so shifted and AltGr characters come out
+                    // right; characters it cannot produce are typed as text
+                    if let (Some(ch), Some(keymap)) = (single, keymap.as_ref()) {
+                        match keymap.stroke_for_char(ch) {
+                            Some(stroke) => Self::send_key_events(dev, &stroke.events()),
+                            None => Self::type_text(dev, grapheme),
+                        }
+                        continue;
+                    }
+
+                    for unit in key_input_units(grapheme) {
+                        match unit {
+                            KeyInput::Key(key_code) => {
+                                if let Err(e) = dev.click(key_code) {
+                                    warn!("Failed to send key code {}: {}", key_code, e);
+                                }
+                            }
+                            KeyInput::Text(text) => Self::type_text(dev, &text),
                         }
                     }
                 }
@@ -262,7 +520,10 @@ impl RemoteInputPlugin {
             debug!("Remote input: Special key {}", special_key);
             let mut device_guard = device.lock().unwrap();
             if let Some(dev) = device_guard.as_mut() {
-                if let Some(key_code) = Self::special_key_to_keycode(special_key) {
+                let key_code = Self::special_key_to_keysym(special_key)
+                    .and_then(|name| keymap.as_ref()?.keycode_for_keysym(name))
+                    .or_else(|| Self::special_key_to_keycode(special_key));
+                if let Some(key_code) = key_code {
                     if let Err(e) = dev.click(key_code) {
                         warn!("Failed to send special key {}: {}", special_key, e);
                     }
@@ -273,6 +534,121 @@ impl RemoteInputPlugin {
         Ok(())
     }
 
+    /// Inject a request through X11 XTest using `xdotool`
+    fn inject_xtest(request: &RemoteInputRequest) {
+        let mut commands: Vec<Vec<String>> = Vec::new();
+
+        if request.dx.is_some() || request.dy.is_some() {
+            let dx = request.dx.unwrap_or(0.0) as i32;
+            let dy = request.dy.unwrap_or(0.0) as i32;
+            if request.scroll.unwrap_or(false) {
+                debug!("Remote input (XTest): Scroll dx={}, dy={}", dx, dy);
+                // Buttons 4/5 scroll vertically, 6/7 horizontally
+                let vertical = (if dy > 0 { "5" } else { "4" }, dy);
+                let horizontal = (if dx > 0 { "7" } else { "6" }, dx);
+                for (button, delta) in [vertical, horizontal] {
+                    if delta != 0 {
+                        commands.push(vec!["click".into(), button.into()]);
+                    }
+                }
+            } else {
+                debug!("Remote input (XTest): Move pointer dx={}, dy={}", dx, dy);
+                commands.push(vec![
+                    "mousemove_relative".into(),
+                    "--".into(),
+                    dx.to_string(),
+                    dy.to_string(),
+                ]);
+            }
+        }
+
+        let clicks = [
+            (request.singleclick, vec!["click", "1"]),
+            (request.doubleclick, vec!["click", "--repeat", "2", "1"]),
+            (request.middleclick, vec!["click", "2"]),
+            (request.rightclick, vec!["click", "3"]),
+            (request.singlehold, vec!["mousedown", "1"]),
+            (request.singlerelease, vec!["mouseup", "1"]),
+        ];
+        for (requested, args) in clicks {
+            if requested.unwrap_or(false) {
+                commands.push(args.into_iter().map(String::from).collect());
+            }
+        }
+
+        if let Some(key) = &request.key {
+            debug!("Remote input (XTest): Key '{}'", key);
+            commands.push(vec!["type".into(), "--".into(), key.clone()]);
+        }
+        if let Some(special_key) = request.special_key {
+            debug!("Remote input (XTest): Special key {}", special_key);
+            if let Some(keysym) = Self::special_key_to_keysym(special_key) {
+                commands.push(vec!["key".into(), keysym.into()]);
+            }
+        }
+
+        for args in commands {
+            match Command::new("xdotool").args(&args).status() {
+                Ok(status) if status.success() => {}
+                Ok(status) => warn!("xdotool {:?} exited with {}", args, status),
+                Err(e) => warn!("Failed to run xdotool {:?}: {}", args, e),
+            }
+        }
+    }
+
+    /// Send a sequence of key events to the virtual keyboard
+    fn send_key_events(dev: &mut VirtualDevice, events: &[KeyEvent]) {
+        for event in events {
+            let result = match *event {
+                KeyEvent::Press(key_code) => dev.press(key_code),
+                KeyEvent::Click(key_code) => dev.click(key_code),
+                KeyEvent::Release(key_code) => dev.release(key_code),
+            };
+            if let Err(e) = result {
+                warn!("Failed to send key event {:?}: {}", event, e);
+            }
+        }
+    }
+
+    /// Type text that has no key code
+    ///
+    /// Uses the compositor's virtual-keyboard protocol via `wtype`, which
+    /// handles any Unicode text. Without it, each code point is entered with
+    /// the Ctrl+Shift+U unicode entry sequence understood by GTK and IBus.
+    fn type_text(dev: &mut VirtualDevice, text: &str) {
+        match Command::new("wtype").arg("--").arg(text).status() {
+            Ok(status) if status.success() => return,
+            Ok(status) => debug!("wtype exited with {}, using unicode entry", status),
+            Err(e) => debug!("wtype unavailable ({}), using unicode entry", e),
+        }
+
+        for ch in text.chars() {
+            Self::type_unicode_entry(dev, ch);
+        }
+    }
+
+    /// Enter a single code point with Ctrl+Shift+U, hex digits and Space
+    fn type_unicode_entry(dev: &mut VirtualDevice, ch: char) {
+        use mouse_keyboard_input::{KEY_LEFTCTRL, KEY_LEFTSHIFT, KEY_SPACE, KEY_U};
+
+        let result = dev
+            .press(KEY_LEFTCTRL)
+            .and_then(|_| dev.press(KEY_LEFTSHIFT))
+            .and_then(|_| dev.click(KEY_U))
+            .and_then(|_| dev.release(KEY_LEFTSHIFT))
+            .and_then(|_| dev.release(KEY_LEFTCTRL))
+            .and_then(|_| {
+                unicode_entry_keycodes(ch)
+                    .into_iter()
+                    .try_for_each(|key_code| dev.click(key_code))
+            })
+            .and_then(|_| dev.click(KEY_SPACE));
+
+        if let Err(e) = result {
+            warn!("Failed to type '{}' (U+{:04X}): {}", ch, ch as u32, e);
+        }
+    }
+
     /// Convert character to Linux key code
     fn char_to_keycode(ch: char) -> Option<u16> {
         use mouse_keyboard_input::*;
@@ -331,8 +707,40 @@ impl RemoteInputPlugin {
         }
     }
 
+    /// Convert special key code to an X11 keysym name
+    fn special_key_to_keysym(special: i32) -> Option<&'static str> {
+        match special {
+            1 => Some("BackSpace"),
+            2 => Some("Tab"),
+            12 => Some("Return"),
+            27 => Some("Escape"),
+            21 => Some("Left"),
+            22 => Some("Up"),
+            23 => Some("Right"),
+            24 => Some("Down"),
+            25 => Some("Prior"),
+            26 => Some("Next"),
+            28 => Some("Home"),
+            29 => Some("End"),
+            30 => Some("Delete"),
+            31 => Some("F1"),
+            32 => Some("F2"),
+            33 => Some("F3"),
+            34 => Some("F4"),
+            35 => Some("F5"),
+            36 => Some("F6"),
+            37 => Some("F7"),
+            38 => Some("F8"),
+            39 => Some("F9"),
+            40 => Some("F10"),
+            41 => Some("F11"),
+            42 => Some("F12"),
+            _ => None,
+        }
+    }
+
     /// Convert special key code to Linux key code
-    fn special_key_to_keycode(special: i32) -> Option<u16> {
+    pub(crate) fn special_key_to_keycode(special: i32) -> Option<u16> {
         use mouse_keyboard_input::*;
         match special {
             1 => Some(KEY_BACKSPACE), // Backspace
@@ -389,22 +797,39 @@ impl Plugin for RemoteInputPlugin {
         vec![
             PACKET_TYPE_MOUSEPAD_REQUEST.to_string(),
             "kdeconnect.mousepad.request".to_string(),
+            PACKET_TYPE_CLIPBOARD_TYPE.to_string(),
         ]
     }
 
     fn outgoing_capabilities(&self) -> Vec<String> {
-        vec![PACKET_TYPE_MOUSEPAD_KEYBOARDSTATE.to_string()]
+        vec![
+            PACKET_TYPE_MOUSEPAD_KEYBOARDSTATE.to_string(),
+            PACKET_TYPE_MOUSEPAD_ECHO.to_string(),
+        ]
     }
 
     async fn init(
         &mut self,
         device: &Device,
-        _packet_sender: tokio::sync::mpsc::Sender<(String, Packet)>,
+        packet_sender: tokio::sync::mpsc::Sender<(String, Packet)>,
     ) -> Result<()> {
         self.device_id = Some(device.id().to_string());
+        self.packet_sender = Some(packet_sender);
+
+        let backend = *self.backend.get_or_insert_with(InputBackend::detect);
+        if backend == InputBackend::None {
+            NO_BACKEND_WARNING.call_once(|| {
+                warn!(
+                    "No input backend available (no uinput access and no X11 XTest); \
+                     remote input will be ignored"
+                );
+            });
+        }
+
         info!(
-            "Remote Input plugin initialized for device {}",
-            device.name()
+            "Remote Input plugin initialized for device {} (backend: {:?})",
+            device.name(),
+            backend
         );
         Ok(())
     }
@@ -419,12 +844,24 @@ impl Plugin for RemoteInputPlugin {
         Ok(())
     }
 
+    fn status(&self) -> PluginStatus {
+        match self.backend {
+            Some(InputBackend::None) => {
+                PluginStatus::Degraded("No input backend available".to_string())
+            }
+            _ => PluginStatus::Active,
+        }
+    }
+
     async fn handle_packet(&mut self, packet: &Packet, _device: &mut Device) -> Result<()> {
         if packet.is_type(PACKET_TYPE_MOUSEPAD_REQUEST)
             || packet.is_type("kdeconnect.mousepad.request")
         {
             debug!("Received remote input request");
             self.handle_request(packet).await
+        } else if packet.is_type(PACKET_TYPE_CLIPBOARD_TYPE) {
+            debug!("Received remote paste request");
+            self.handle_clipboard_type().await.map(|_| ())
         } else {
             Ok(())
         }
@@ -444,11 +881,15 @@ impl PluginFactory for RemoteInputPluginFactory {
         vec![
             PACKET_TYPE_MOUSEPAD_REQUEST.to_string(),
             "kdeconnect.mousepad.request".to_string(),
+            PACKET_TYPE_CLIPBOARD_TYPE.to_string(),
         ]
     }
 
     fn outgoing_capabilities(&self) -> Vec<String> {
-        vec![PACKET_TYPE_MOUSEPAD_KEYBOARDSTATE.to_string()]
+        vec![
+            PACKET_TYPE_MOUSEPAD_KEYBOARDSTATE.to_string(),
+            PACKET_TYPE_MOUSEPAD_ECHO.to_string(),
+        ]
     }
 
     fn create(&self) -> Box<dyn Plugin> {
@@ -485,6 +926,45 @@ mod tests {
         assert_eq!(plugin.device_id, Some(device.id().to_string()));
     }
 
+    #[tokio::test]
+    async fn test_send_ack_produces_one_echo() {
+        let mut plugin = RemoteInputPlugin::with_backend(InputBackend::None);
+        let mut device = create_test_device();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+        plugin.init(&device, tx).await.unwrap();
+
+        let packet = Packet::new(
+            "kdeconnect.mousepad.request",
+            serde_json::json!({
+                "key": "a",
+                "ctrl": true,
+                "sendAck": true
+            }),
+        );
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        let (device_id, echo) = rx.try_recv().expect("an echo is sent");
+        assert_eq!(device_id, device.id());
+        assert_eq!(echo.packet_type, PACKET_TYPE_MOUSEPAD_ECHO);
+        assert_eq!(
+            echo.body,
+            serde_json::json!({
+                "key": "a",
+                "ctrl": true,
+                "isAck": true
+            })
+        );
+        assert!(rx.try_recv().is_err(), "exactly one echo is sent");
+
+        // Without sendAck nothing is echoed
+        let packet = Packet::new(
+            "kdeconnect.mousepad.request",
+            serde_json::json!({ "key": "a" }),
+        );
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+        assert!(rx.try_recv().is_err());
+    }
+
     #[tokio::test]
     async fn test_handle_mouse_movement() {
         let mut plugin = RemoteInputPlugin::new();
@@ -621,9 +1101,10 @@ mod tests {
         assert_eq!(factory.name(), "remoteinput");
 
         let incoming = factory.incoming_capabilities();
-        assert_eq!(incoming.len(), 2);
+        assert_eq!(incoming.len(), 3);
         assert!(incoming.contains(&PACKET_TYPE_MOUSEPAD_REQUEST.to_string()));
         assert!(incoming.contains(&"kdeconnect.mousepad.request".to_string()));
+        assert!(incoming.contains(&PACKET_TYPE_CLIPBOARD_TYPE.to_string()));
 
         let outgoing = factory.outgoing_capabilities();
         assert!(outgoing.contains(&PACKET_TYPE_MOUSEPAD_KEYBOARDSTATE.to_string()));
@@ -644,4 +1125,154 @@ mod tests {
         assert!(plugin.start().await.is_ok());
         assert!(plugin.stop().await.is_ok());
     }
+
+    #[test]
+    fn test_backend_selection() {
+        assert_eq!(InputBackend::select(true, true), InputBackend::Uinput);
+        assert_eq!(InputBackend::select(false, true), InputBackend::XTest);
+        assert_eq!(InputBackend::select(false, false), InputBackend::None);
+    }
+
+    #[tokio::test]
+    async fn test_no_backend_degrades_and_drops_input() {
+        let mut plugin = RemoteInputPlugin::with_backend(InputBackend::None);
+        let mut device = create_test_device();
+        plugin
+            .init(&device, tokio::sync::mpsc::channel(100).0)
+            .await
+            .unwrap();
+
+        assert_eq!(plugin.backend(), Some(InputBackend::None));
+        assert!(matches!(plugin.status(), PluginStatus::Degraded(_)));
+
+        let packet = Packet::new(
+            "cconnect.mousepad.request",
+            serde_json::json!({
+                "dx": 10.0,
+                "dy": 20.0,
+                "singleclick": true,
+                "key": "a"
+            }),
+        );
+        assert!(plugin.handle_packet(&packet, &mut device).await.is_ok());
+        assert!(plugin.virtual_device.lock().unwrap().is_none());
+    }
+
+    /// Clipboard that counts writes
+    #[derive(Clone, Default)]
+    struct MockClipboard {
+        content: Arc<Mutex<String>>,
+        writes: Arc<Mutex<usize>>,
+    }
+
+    #[async_trait]
+    impl ClipboardAccess for MockClipboard {
+        async fn read(&self) -> Option<String> {
+            Some(self.content.lock().unwrap().clone())
+        }
+
+        async fn write(&self, content: &str) -> bool {
+            *self.content.lock().unwrap() = content.to_string();
+            *self.writes.lock().unwrap() += 1;
+            true
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_clipboard_type_injects_keystrokes() {
+        let clipboard = MockClipboard::default();
+        *clipboard.content.lock().unwrap() = "hunter2 🔑".to_string();
+
+        let mut plugin = RemoteInputPlugin::with_backend(InputBackend::None)
+            .with_clipboard(Box::new(clipboard.clone()));
+        let mut device = create_test_device();
+        plugin
+            .init(&device, tokio::sync::mpsc::channel(100).0)
+            .await
+            .unwrap();
+
+        // The clipboard is typed as key input
+        let request = plugin.handle_clipboard_type().await.unwrap().unwrap();
+        assert_eq!(request.key.as_deref(), Some("hunter2 🔑"));
+        assert!(request.special_key.is_none());
+
+        let packet = Packet::new(PACKET_TYPE_CLIPBOARD_TYPE, serde_json::json!({}));
+        assert!(plugin.handle_packet(&packet, &mut device).await.is_ok());
+
+        // ...and never set
+        assert_eq!(*clipboard.writes.lock().unwrap(), 0);
+        assert_eq!(*clipboard.content.lock().unwrap(), "hunter2 🔑");
+
+        // Nothing to type
+        clipboard.content.lock().unwrap().clear();
+        assert!(plugin.handle_clipboard_type().await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_key_input_units_ascii() {
+        use mouse_keyboard_input::{KEY_A, KEY_SPACE};
+
+        assert_eq!(
+            key_input_units("a a"),
+            vec![
+                KeyInput::Key(KEY_A),
+                KeyInput::Key(KEY_SPACE),
+                KeyInput::Key(KEY_A)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_key_input_units_multi_codepoint_emoji() {
+        // Family emoji: four code points joined by zero width joiners
+        let family = "\u{1F469}\u{200D}\u{1F469}\u{200D}\u{1F467}\u{200D}\u{1F466}";
+        assert_eq!(family.chars().count(), 7);
+        assert_eq!(
+            key_input_units(family),
+            vec![KeyInput::Text(family.to_string())]
+        );
+
+        // Thumbs up with skin tone modifier
+        let thumbs_up = "\u{1F44D}\u{1F3FD}";
+        assert_eq!(
+            key_input_units(thumbs_up),
+            vec![KeyInput::Text(thumbs_up.to_string())]
+        );
+    }
+
+    #[test]
+    fn test_key_input_units_composed_and_cjk() {
+        use mouse_keyboard_input::KEY_A;
+
+        // "e" followed by a combining acute accent is one character on screen
+        let composed = "e\u{0301}";
+        assert_eq!(
+            key_input_units(composed),
+            vec![KeyInput::Text(composed.to_string())]
+        );
+
+        assert_eq!(
+            key_input_units("a\u{00E9}\u{6F22}"),
+            vec![
+                KeyInput::Key(KEY_A),
+                KeyInput::Text("\u{00E9}".to_string()),
+                KeyInput::Text("\u{6F22}".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unicode_entry_keycodes() {
+        use mouse_keyboard_input::{KEY_1, KEY_4, KEY_D, KEY_F};
+
+        // U+1F44D
+        assert_eq!(
+            unicode_entry_keycodes('\u{1F44D}'),
+            vec![KEY_1, KEY_F, KEY_4, KEY_4, KEY_D]
+        );
+    }
 }
@@ -0,0 +1,111 @@
+//! Reply Text
+//!
+//! Text typed on the desktop and sent to the phone, as an SMS or as a reply to
+//! a notification, goes through [`prepare_reply`] first. Control characters
+//! pasted from terminals or rich text can confuse the Android app's input
+//! handling, so they are stripped and line endings are normalized to `\n`.
+//! Replies longer than [`MAX_REPLY_CHARS`] are rejected instead of being
+//! silently truncated on the phone.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cosmic_connect_protocol::plugins::reply::{prepare_reply, sanitize_reply};
+//!
+//! assert_eq!(sanitize_reply("On my way\r\n\u{7}"), "On my way\n");
+//! assert!(prepare_reply(&"x".repeat(10_000)).is_err());
+//! ```
+
+use crate::{ProtocolError, Result};
+
+/// Longest reply accepted, in characters
+///
+/// Long SMS are sent as multipart messages and carriers commonly refuse
+/// messages made of more than a few dozen parts.
+pub const MAX_REPLY_CHARS: usize = 4096;
+
+/// Strip control characters and normalize line endings
+///
+/// Keeps newlines and tabs; `\r\n` and lone `\r` become `\n`. Everything else
+/// is left untouched, including emoji and other non-ASCII text.
+pub fn sanitize_reply(text: &str) -> String {
+    let mut sanitized = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\r' => {
+                chars.next_if_eq(&'\n');
+                sanitized.push('\n');
+            }
+            '\n' | '\t' => sanitized.push(c),
+            c if c.is_control() => {}
+            c => sanitized.push(c),
+        }
+    }
+
+    sanitized
+}
+
+/// Sanitize a reply and check it can be sent
+///
+/// # Errors
+///
+/// Returns an error if the reply is empty after sanitizing, or longer than
+/// [`MAX_REPLY_CHARS`].
+pub fn prepare_reply(text: &str) -> Result<String> {
+    let sanitized = sanitize_reply(text);
+
+    if sanitized.trim().is_empty() {
+        return Err(ProtocolError::InvalidPacket("Reply is empty".to_string()));
+    }
+
+    let length = sanitized.chars().count();
+    if length > MAX_REPLY_CHARS {
+        return Err(ProtocolError::InvalidPacket(format!(
+            "Reply is {} characters long, the limit is {}",
+            length, MAX_REPLY_CHARS
+        )));
+    }
+
+    Ok(sanitized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_control_characters_are_stripped() {
+        assert_eq!(
+            sanitize_reply("Hi\u{0}\u{7}\u{1b}[31m there"),
+            "Hi[31m there"
+        );
+        assert_eq!(
+            sanitize_reply("one\r\ntwo\rthree\nfour"),
+            "one\ntwo\nthree\nfour"
+        );
+        assert_eq!(sanitize_reply("tab\there"), "tab\there");
+        assert_eq!(sanitize_reply("\u{85}next line"), "next line");
+
+        // Multi-byte text survives untouched
+        assert_eq!(sanitize_reply("Grüße 👋🏽 你好"), "Grüße 👋🏽 你好");
+    }
+
+    #[test]
+    fn test_over_length_reply_is_rejected() {
+        // The limit counts characters, not bytes
+        let at_limit = "é".repeat(MAX_REPLY_CHARS);
+        assert_eq!(prepare_reply(&at_limit).unwrap(), at_limit);
+
+        let error = prepare_reply(&"é".repeat(MAX_REPLY_CHARS + 1)).unwrap_err();
+        assert!(error.to_string().contains("4097 characters"));
+        assert!(error.to_string().contains("limit is 4096"));
+
+        // Control characters do not count towards the limit
+        let padded = format!("{}{}", "a".repeat(MAX_REPLY_CHARS), "\u{0}".repeat(10));
+        assert!(prepare_reply(&padded).is_ok());
+
+        assert!(prepare_reply("\u{7}\r\n").is_err());
+    }
+}
@@ -8,10 +8,12 @@
 //! **Packet Types**:
 //! - `cconnect.runcommand` - Command list response (outgoing)
 //! - `cconnect.runcommand.request` - Command execution request (incoming)
+//! - `cconnect.runcommand.result` - Result of an executed command (outgoing)
 //!
 //! **Capabilities**:
 //! - Incoming: `cconnect.runcommand.request` - Receives command execution requests
 //! - Outgoing: `cconnect.runcommand` - Sends command list to devices
+//! - Outgoing: `cconnect.runcommand.result` - Reports whether a command succeeded
 //!
 //! ## Packet Formats
 //!
@@ -52,6 +54,48 @@
 //! }
 //! ```
 //!
+//! Open the desktop's command editor (handled by the daemon, which activates
+//! the manager's Commands page):
+//! ```json
+//! {
+//!     "id": 1234567890,
+//!     "type": "cconnect.runcommand.request",
+//!     "body": {
+//!         "setup": true
+//!     }
+//! }
+//! ```
+//!
+//! ### Command Result (`cconnect.runcommand.result`)
+//!
+//! Sent after a command finishes when [`RunCommandSettings::report_result`]
+//! is enabled:
+//! ```json
+//! {
+//!     "id": 1234567890,
+//!     "type": "cconnect.runcommand.result",
+//!     "body": {
+//!         "key": "cmd1",
+//!         "success": false,
+//!         "exitCode": 1,
+//!         "timedOut": false
+//!     }
+//! }
+//! ```
+//!
+//! `exitCode` is omitted when the command was killed or could not be started.
+//!
+//! ## Execution
+//!
+//! Commands run in the background so the packet loop never waits for them.
+//! Each command gets its own process group. Commands may run for as long as
+//! they like unless [`RunCommandSettings::timeout_secs`] is set; past that
+//! the whole group is killed, including anything the command started.
+//! stdout and stderr are captured up to [`RunCommandSettings::max_output_bytes`]
+//! each and logged, or not connected at all when that is 0. Processes the
+//! command leaves running in the background after it exits are not waited
+//! for; their output is read and discarded, so writing to it never fails.
+//!
 //! ## Configuration
 //!
 //! Commands are stored in a JSON configuration file per device:
@@ -109,13 +153,283 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::process::{ExitStatus, Stdio};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::fs;
-use tokio::sync::RwLock;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Child;
+use tokio::sync::{oneshot, RwLock};
 use tracing::{debug, error, info, warn};
 
+use super::settings::PluginSettings;
 use super::{Plugin, PluginFactory};
 
+/// Packet type for command results (outgoing)
+pub const PACKET_TYPE_RUNCOMMAND_RESULT: &str = "cconnect.runcommand.result";
+
+/// Default time a command may run before it is killed, 0 for no limit
+///
+/// Commands often start long-running programs, a media player or a backup,
+/// so none is killed unless a limit is configured.
+pub const DEFAULT_COMMAND_TIMEOUT_SECS: u64 = 0;
+
+/// Default cap on captured output, per stream
+pub const DEFAULT_MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// How long to keep capturing output after the command exited
+///
+/// Background processes started by the command inherit its stdout and stderr,
+/// so the streams may stay open long after the command itself is done. Past
+/// this their output is drained without being kept.
+const OUTPUT_GRACE_PERIOD: Duration = Duration::from_millis(200);
+
+/// RunCommand plugin settings, stored per device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunCommandSettings {
+    /// Seconds a command may run before its process group is killed, 0 for no limit
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Bytes of stdout and of stderr kept from a command, 0 to not capture output
+    #[serde(default = "default_max_output_bytes")]
+    pub max_output_bytes: usize,
+
+    /// Tell the device whether each command succeeded
+    #[serde(default)]
+    pub report_result: bool,
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_COMMAND_TIMEOUT_SECS
+}
+
+fn default_max_output_bytes() -> usize {
+    DEFAULT_MAX_OUTPUT_BYTES
+}
+
+impl Default for RunCommandSettings {
+    fn default() -> Self {
+        Self {
+            timeout_secs: DEFAULT_COMMAND_TIMEOUT_SECS,
+            max_output_bytes: DEFAULT_MAX_OUTPUT_BYTES,
+            report_result: false,
+        }
+    }
+}
+
+impl RunCommandSettings {
+    /// Time limit for a command, `None` if commands may run forever
+    pub fn timeout(&self) -> Option<Duration> {
+        (self.timeout_secs > 0).then(|| Duration::from_secs(self.timeout_secs))
+    }
+}
+
+/// Outcome of a finished command
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandOutput {
+    /// Exit code, `None` if the command was killed by a signal
+    pub exit_code: Option<i32>,
+
+    /// Whether the command was killed for running too long
+    pub timed_out: bool,
+
+    /// Captured standard output
+    pub stdout: String,
+
+    /// Captured standard error
+    pub stderr: String,
+
+    /// Whether output beyond the capture limit was dropped
+    pub truncated: bool,
+}
+
+impl CommandOutput {
+    /// Whether the command ran to completion and exited with code 0
+    pub fn success(&self) -> bool {
+        !self.timed_out && self.exit_code == Some(0)
+    }
+}
+
+/// Output read from one stream of a command
+#[derive(Debug, Default)]
+struct CapturedStream {
+    bytes: Vec<u8>,
+    truncated: bool,
+}
+
+impl CapturedStream {
+    fn into_string(self) -> String {
+        String::from_utf8_lossy(&self.bytes).into_owned()
+    }
+}
+
+/// Read a stream to the end, keeping at most `max_bytes`
+///
+/// Reading continues past the limit so the command never blocks on a full pipe.
+/// The reader is cleared once the stream is closed.
+async fn capture_stream<R: AsyncRead + Unpin>(
+    reader: &mut Option<R>,
+    max_bytes: usize,
+    captured: &mut CapturedStream,
+) {
+    let Some(stream) = reader.as_mut() else {
+        return;
+    };
+
+    let mut buf = [0u8; 4096];
+    loop {
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => {
+                *reader = None;
+                break;
+            }
+            Ok(n) => {
+                let room = max_bytes.saturating_sub(captured.bytes.len());
+                if n > room {
+                    captured.truncated = true;
+                }
+                captured.bytes.extend_from_slice(&buf[..n.min(room)]);
+            }
+        }
+    }
+}
+
+/// Read a stream to the end, discarding the data
+async fn drain_stream<R: AsyncRead + Unpin>(reader: Option<R>) {
+    if let Some(mut reader) = reader {
+        let _ = tokio::io::copy(&mut reader, &mut tokio::io::sink()).await;
+    }
+}
+
+/// Kill a command together with everything it started
+fn kill_process_group(child: &mut Child) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        use nix::sys::signal::{killpg, Signal};
+        use nix::unistd::Pid;
+
+        if let Err(e) = killpg(Pid::from_raw(pid as i32), Signal::SIGKILL) {
+            warn!("Failed to kill process group {}: {}", pid, e);
+        }
+    }
+
+    // Also covers platforms without process groups
+    let _ = child.start_kill();
+}
+
+/// Wait for a command to exit, killing it once `limit` has passed
+///
+/// Returns `None` if the command was killed.
+async fn wait_with_timeout(
+    child: &mut Child,
+    limit: Option<Duration>,
+) -> std::io::Result<Option<ExitStatus>> {
+    let Some(limit) = limit else {
+        return child.wait().await.map(Some);
+    };
+
+    match tokio::time::timeout(limit, child.wait()).await {
+        Ok(status) => status.map(Some),
+        Err(_) => {
+            kill_process_group(child);
+            child.wait().await?;
+            Ok(None)
+        }
+    }
+}
+
+/// Run a shell command with a time limit, capturing its output
+///
+/// The command runs in its own process group, which is killed as a whole when
+/// `timeout` passes. At most `max_output_bytes` of stdout and of stderr are
+/// kept; with 0 the command's output is not connected. Output of processes
+/// still running after the command exited is drained in the background.
+///
+/// # Errors
+///
+/// Returns an error if the shell cannot be started.
+pub async fn run_command(
+    command: &str,
+    timeout: Option<Duration>,
+    max_output_bytes: usize,
+) -> Result<CommandOutput> {
+    // Execute command using sh -c (Linux/Unix) or cmd /C (Windows)
+    #[cfg(target_os = "windows")]
+    let (shell, flag) = ("cmd", "/C");
+
+    #[cfg(not(target_os = "windows"))]
+    let (shell, flag) = ("/bin/sh", "-c");
+
+    let output = || {
+        if max_output_bytes > 0 {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        }
+    };
+    let mut process = tokio::process::Command::new(shell);
+    process
+        .arg(flag)
+        .arg(command)
+        .stdin(Stdio::null())
+        .stdout(output())
+        .stderr(output());
+    #[cfg(unix)]
+    process.process_group(0);
+
+    let mut child = process
+        .spawn()
+        .map_err(|e| ProtocolError::Plugin(format!("Failed to execute command: {}", e)))?;
+    debug!("Command started (PID: {:?})", child.id());
+
+    let mut stdout = child.stdout.take();
+    let mut stderr = child.stderr.take();
+    let mut stdout_captured = CapturedStream::default();
+    let mut stderr_captured = CapturedStream::default();
+    let (exited_tx, exited_rx) = oneshot::channel();
+
+    let wait = async {
+        let status = wait_with_timeout(&mut child, timeout).await;
+        let _ = exited_tx.send(());
+        status
+    };
+    let read = async {
+        let streams = async {
+            tokio::join!(
+                capture_stream(&mut stdout, max_output_bytes, &mut stdout_captured),
+                capture_stream(&mut stderr, max_output_bytes, &mut stderr_captured),
+            )
+        };
+        tokio::select! {
+            _ = streams => {}
+            _ = async {
+                let _ = exited_rx.await;
+                tokio::time::sleep(OUTPUT_GRACE_PERIOD).await;
+            } => debug!("Output still open after command exited, stopped capturing"),
+        }
+    };
+    let (status, ()) = tokio::join!(wait, read);
+    let status = status?;
+
+    // Closing the pipes would kill processes still writing to them
+    if stdout.is_some() || stderr.is_some() {
+        tokio::spawn(async move {
+            tokio::join!(drain_stream(stdout), drain_stream(stderr));
+        });
+    }
+
+    let truncated = stdout_captured.truncated || stderr_captured.truncated;
+    Ok(CommandOutput {
+        exit_code: status.and_then(|status| status.code()),
+        timed_out: status.is_none(),
+        stdout: stdout_captured.into_string(),
+        stderr: stderr_captured.into_string(),
+        truncated,
+    })
+}
+
 /// A runnable command definition
 ///
 /// Represents a pre-configured shell command that can be executed
@@ -187,6 +501,9 @@ pub struct RunCommandPlugin {
 
     /// Channel to send packets
     packet_sender: Option<tokio::sync::mpsc::Sender<(String, Packet)>>,
+
+    /// Execution limits and result reporting
+    settings: RunCommandSettings,
 }
 
 impl RunCommandPlugin {
@@ -208,6 +525,7 @@ impl RunCommandPlugin {
             config_path: None,
             commands_executed: Arc::new(RwLock::new(0)),
             packet_sender: None,
+            settings: RunCommandSettings::default(),
         }
     }
 
@@ -378,9 +696,26 @@ impl RunCommandPlugin {
         )
     }
 
+    /// Create a command result packet
+    ///
+    /// `output` is `None` if the command could not be started.
+    pub fn create_result_packet(key: &str, output: Option<&CommandOutput>) -> Packet {
+        let mut body = json!({
+            "key": key,
+            "success": output.is_some_and(CommandOutput::success),
+            "timedOut": output.is_some_and(|output| output.timed_out),
+        });
+        if let Some(exit_code) = output.and_then(|output| output.exit_code) {
+            body["exitCode"] = json!(exit_code);
+        }
+
+        Packet::new(PACKET_TYPE_RUNCOMMAND_RESULT, body)
+    }
+
     /// Execute a command by ID
     ///
-    /// Looks up the command and executes it using the system shell.
+    /// Looks up the command and runs it in the background with the limits
+    /// from the plugin settings, so this returns as soon as it is started.
     ///
     /// # Parameters
     ///
@@ -388,7 +723,7 @@ impl RunCommandPlugin {
     ///
     /// # Returns
     ///
-    /// `Ok(())` if command executed successfully, `Err` otherwise
+    /// `Ok(())` if the command exists, `Err` otherwise
     async fn execute_command(&self, id: &str) -> Result<()> {
         let command = self
             .get_command(id)
@@ -398,58 +733,55 @@ impl RunCommandPlugin {
         info!("Executing command '{}': {}", id, command.name);
         debug!("Command: {}", command.command);
 
-        // Execute command using sh -c (Linux/Unix) or cmd /C (Windows)
-        #[cfg(target_os = "windows")]
-        let (shell, flag) = ("cmd", "/C");
-
-        #[cfg(not(target_os = "windows"))]
-        let (shell, flag) = ("/bin/sh", "-c");
-
-        // Spawn command detached (non-blocking)
-        match tokio::process::Command::new(shell)
-            .arg(flag)
-            .arg(&command.command)
-            .spawn()
-        {
-            Ok(mut child) => {
-                // Increment execution counter
-                let mut count = self.commands_executed.write().await;
-                *count += 1;
-                drop(count);
-
-                info!(
-                    "Command '{}' started successfully (PID: {:?})",
-                    id,
-                    child.id()
-                );
-
-                // Wait for completion in background
-                let id_clone = id.to_string();
-                tokio::spawn(async move {
-                    match child.wait().await {
-                        Ok(status) => {
-                            if status.success() {
-                                debug!("Command '{}' completed successfully", id_clone);
-                            } else {
-                                warn!("Command '{}' exited with status: {}", id_clone, status);
-                            }
-                        }
-                        Err(e) => {
-                            error!("Failed to wait for command '{}': {}", id_clone, e);
-                        }
+        // Increment execution counter
+        let mut count = self.commands_executed.write().await;
+        *count += 1;
+        drop(count);
+
+        let id = id.to_string();
+        let settings = self.settings.clone();
+        let reply_to = self
+            .packet_sender
+            .clone()
+            .zip(self.device_id.clone())
+            .filter(|_| settings.report_result);
+        tokio::spawn(async move {
+            let output = match run_command(
+                &command.command,
+                settings.timeout(),
+                settings.max_output_bytes,
+            )
+            .await
+            {
+                Ok(output) => {
+                    if output.timed_out {
+                        warn!("Command '{}' killed after {}s", id, settings.timeout_secs);
+                    } else if output.success() {
+                        debug!("Command '{}' completed successfully", id);
+                    } else {
+                        warn!("Command '{}' exited with code {:?}", id, output.exit_code);
                     }
-                });
+                    debug!(
+                        "Command '{}' output (truncated: {}):\n{}{}",
+                        id, output.truncated, output.stdout, output.stderr
+                    );
+                    Some(output)
+                }
+                Err(e) => {
+                    error!("Failed to execute command '{}': {}", id, e);
+                    None
+                }
+            };
 
-                Ok(())
-            }
-            Err(e) => {
-                error!("Failed to execute command '{}': {}", id, e);
-                Err(ProtocolError::Plugin(format!(
-                    "Failed to execute command: {}",
-                    e
-                )))
+            if let Some((sender, device_id)) = reply_to {
+                let packet = Self::create_result_packet(&id, output.as_ref());
+                if let Err(e) = sender.send((device_id, packet)).await {
+                    error!("Failed to send result of command '{}': {}", id, e);
+                }
             }
-        }
+        });
+
+        Ok(())
     }
 
     /// Handle a command request packet
@@ -476,6 +808,18 @@ impl RunCommandPlugin {
             return Ok(None);
         }
 
+        // The phone asks the desktop to open its command editor; the daemon
+        // handles this by activating the manager
+        if packet
+            .body
+            .get("setup")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+        {
+            debug!("Received command setup request");
+            return Ok(None);
+        }
+
         warn!("Received runcommand request with no valid action");
         Ok(None)
     }
@@ -549,9 +893,16 @@ impl Plugin for RunCommandPlugin {
         vec![
             "cconnect.runcommand".to_string(),
             "cconnect.runcommand.request".to_string(),
+            PACKET_TYPE_RUNCOMMAND_RESULT.to_string(),
         ]
     }
 
+    fn set_settings(&mut self, settings: PluginSettings) {
+        if let Some(runcommand) = settings.get::<RunCommandSettings>() {
+            self.settings = runcommand;
+        }
+    }
+
     async fn init(
         &mut self,
         device: &Device,
@@ -646,6 +997,7 @@ impl PluginFactory for RunCommandPluginFactory {
         vec![
             "cconnect.runcommand".to_string(),
             "cconnect.runcommand.request".to_string(),
+            PACKET_TYPE_RUNCOMMAND_RESULT.to_string(),
         ]
     }
 
@@ -682,9 +1034,10 @@ mod tests {
         assert!(incoming.contains(&"kdeconnect.runcommand".to_string()));
 
         let outgoing = plugin.outgoing_capabilities();
-        assert_eq!(outgoing.len(), 2);
+        assert_eq!(outgoing.len(), 3);
         assert!(outgoing.contains(&"cconnect.runcommand".to_string()));
         assert!(outgoing.contains(&"cconnect.runcommand.request".to_string()));
+        assert!(outgoing.contains(&PACKET_TYPE_RUNCOMMAND_RESULT.to_string()));
     }
 
     #[test]
@@ -825,6 +1178,23 @@ mod tests {
         assert_eq!(response.packet_type, "cconnect.runcommand");
     }
 
+    #[tokio::test]
+    async fn test_handle_setup_request() {
+        let mut plugin = RunCommandPlugin::new();
+        let device = create_test_device();
+        plugin
+            .init(&device, tokio::sync::mpsc::channel(100).0)
+            .await
+            .unwrap();
+
+        let packet = Packet::new("cconnect.runcommand.request", json!({ "setup": true }));
+
+        // Nothing to answer; the daemon opens the command editor
+        let response = plugin.handle_request(&packet).await.unwrap();
+        assert!(response.is_none());
+        assert_eq!(plugin.commands_executed().await, 0);
+    }
+
     #[tokio::test]
     async fn test_plugin_lifecycle() {
         let mut plugin = RunCommandPlugin::new();
@@ -845,6 +1215,77 @@ mod tests {
         plugin.stop().await.unwrap();
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_hanging_command_is_killed_after_timeout() {
+        let started = std::time::Instant::now();
+        let output = run_command(
+            "echo started; sleep 30",
+            Some(Duration::from_millis(200)),
+            1024,
+        )
+        .await
+        .unwrap();
+
+        assert!(output.timed_out);
+        assert!(!output.success());
+        assert_eq!(output.stdout, "started\n");
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        let packet = RunCommandPlugin::create_result_packet("hang", Some(&output));
+        assert_eq!(packet.body["success"], json!(false));
+        assert_eq!(packet.body["timedOut"], json!(true));
+        assert!(packet.body.get("exitCode").is_none());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_background_process_keeps_running_after_command_exits() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let marker = temp_dir.path().join("done");
+        let output = run_command(
+            &format!("(sleep 0.5; echo late; touch '{}') &", marker.display()),
+            None,
+            1024,
+        )
+        .await
+        .unwrap();
+        assert!(output.success());
+
+        // Writing after the capture stopped must not kill the process
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert!(marker.exists());
+    }
+
+    #[test]
+    fn test_commands_not_killed_by_default() {
+        assert_eq!(RunCommandSettings::default().timeout(), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_reports_success_with_capped_output() {
+        let output = run_command(
+            "echo hello; echo oops >&2",
+            Some(Duration::from_secs(10)),
+            4,
+        )
+        .await
+        .unwrap();
+
+        assert!(output.success());
+        assert_eq!(output.exit_code, Some(0));
+        assert_eq!(output.stdout, "hell");
+        assert_eq!(output.stderr, "oops");
+        assert!(output.truncated);
+
+        let packet = RunCommandPlugin::create_result_packet("hello", Some(&output));
+        assert_eq!(packet.packet_type, PACKET_TYPE_RUNCOMMAND_RESULT);
+        assert_eq!(packet.body["key"], json!("hello"));
+        assert_eq!(packet.body["success"], json!(true));
+        assert_eq!(packet.body["exitCode"], json!(0));
+    }
+
     #[test]
     fn test_factory() {
         let factory = RunCommandPluginFactory;
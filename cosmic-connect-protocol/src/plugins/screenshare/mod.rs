@@ -60,6 +60,13 @@
 //! - [x] Cursor tracking (DBus signals emitted, mirror UI receives updates)
 //! - [x] Annotation system (DBus signals emitted, mirror UI receives updates)
 //! - [x] Canvas-based cursor/annotation rendering (Stack + Canvas overlay on video)
+//!
+//! ## Session Lock
+//!
+//! Locking the desktop session stops an in-flight share so the lock screen,
+//! or whatever is shown while locked, never reaches viewers. The share is not
+//! resumed on unlock; the user resumes it explicitly with
+//! [`ScreenSharePlugin::resume_after_lock`].
 
 pub mod capture;
 pub mod decoder;
@@ -67,6 +74,7 @@ pub mod portal;
 pub mod stream_receiver;
 pub mod stream_sender;
 
+use crate::plugins::logind_backend::SessionLockEvent;
 use crate::plugins::{Plugin, PluginFactory};
 use crate::{Device, Packet, ProtocolError, Result};
 use async_trait::async_trait;
@@ -537,6 +545,12 @@ pub struct ScreenSharePlugin {
 
     /// Shared flag to signal streaming pause
     pause_streaming: Arc<Mutex<bool>>,
+
+    /// Portal session backing the capture, closed when streaming stops
+    portal_session: Option<portal::PortalSession>,
+
+    /// Configuration of the share stopped by a session lock, until resumed
+    stopped_by_lock: Option<ShareConfig>,
 }
 
 impl ScreenSharePlugin {
@@ -554,6 +568,8 @@ impl ScreenSharePlugin {
             frame_sender: None,
             stop_streaming: Arc::new(Mutex::new(false)),
             pause_streaming: Arc::new(Mutex::new(false)),
+            portal_session: None,
+            stopped_by_lock: None,
         }
     }
 
@@ -576,6 +592,7 @@ impl ScreenSharePlugin {
 
         let session = ShareSession::new(config);
         self.active_session = Some(session);
+        self.stopped_by_lock = None;
 
         // Note: Capture and streaming are started when receiver sends ready packet
         // See start_streaming_to_device() which is called from handle_packet()
@@ -613,9 +630,67 @@ impl ScreenSharePlugin {
         if let Some(device_id) = &self.device_id {
             clear_pending_session(device_id);
         }
+        self.stopped_by_lock = None;
         self.stop_sharing().await
     }
 
+    /// Handle a change of the session's lock state
+    ///
+    /// Locking stops an active share, including its capture pipeline, and
+    /// tells the viewer. Unlocking leaves it stopped.
+    pub async fn handle_lock_event(&mut self, event: SessionLockEvent) -> Result<()> {
+        match event {
+            SessionLockEvent::Locked => {
+                let Some(session) = &self.active_session else {
+                    return Ok(());
+                };
+                let config = session.config.clone();
+
+                info!("Session locked, stopping screen share");
+                self.stop_sharing_explicit().await?;
+                self.stopped_by_lock = Some(config);
+
+                if let Some(device_id) = self.device_id.clone() {
+                    if let Some(sender) = &self.packet_sender {
+                        let stop_packet =
+                            Packet::new("cconnect.screenshare.stop", serde_json::json!({}));
+                        if let Err(e) = sender.send((device_id.clone(), stop_packet)).await {
+                            error!("Failed to send stop packet: {}", e);
+                        }
+                    }
+                    self.emit_internal_packet(
+                        &device_id,
+                        "cconnect.internal.screenshare.stopped",
+                        serde_json::json!({ "reason": "locked" }),
+                    )
+                    .await;
+                }
+            }
+            SessionLockEvent::Unlocked => {
+                if self.stopped_by_lock.is_some() {
+                    info!("Session unlocked, screen share stays stopped until resumed");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check if a share was stopped by a session lock and can be resumed
+    pub fn is_stopped_by_lock(&self) -> bool {
+        self.stopped_by_lock.is_some()
+    }
+
+    /// Resume the share stopped by a session lock
+    ///
+    /// Starts a new share with the same configuration; the viewer has to
+    /// accept it again.
+    pub async fn resume_after_lock(&mut self) -> Result<()> {
+        let config = self.stopped_by_lock.take().ok_or_else(|| {
+            ProtocolError::Plugin("No screen share was stopped by a session lock".to_string())
+        })?;
+        self.share_to_device(config).await
+    }
+
     /// Pause screen sharing session
     ///
     /// The capture pipeline is paused but the session remains active.
@@ -1023,6 +1098,9 @@ impl ScreenSharePlugin {
             });
 
             self.capture_task = Some(capture_handle);
+
+            // Keep the portal session alive for as long as the capture runs
+            self.portal_session = portal_session;
         }
 
         // Spawn sender task for this viewer
@@ -1161,6 +1239,11 @@ impl ScreenSharePlugin {
             handle.abort();
         }
 
+        // Release the capture so the recording indicator goes away
+        if let Some(mut session) = self.portal_session.take() {
+            session.close().await;
+        }
+
         // Clear frame sender
         self.frame_sender = None;
 
@@ -1503,6 +1586,54 @@ mod tests {
         assert!(!plugin.is_sharing());
     }
 
+    #[tokio::test]
+    async fn test_lock_stops_sharing_without_resuming_on_unlock() {
+        let device = create_test_device();
+        let mut plugin = ScreenSharePlugin::new();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+        plugin.init(&device, tx).await.unwrap();
+        plugin.start().await.unwrap();
+
+        plugin
+            .share_to_device(ShareConfig::default())
+            .await
+            .unwrap();
+        while rx.try_recv().is_ok() {}
+        assert!(plugin.is_sharing());
+
+        plugin
+            .handle_lock_event(SessionLockEvent::Locked)
+            .await
+            .unwrap();
+        assert!(!plugin.is_sharing());
+        assert!(plugin.is_stopped_by_lock());
+        assert!(take_pending_session(device.id()).is_none());
+
+        // The viewer is told to stop
+        let (_, stop) = rx.recv().await.unwrap();
+        assert_eq!(stop.packet_type, "cconnect.screenshare.stop");
+        let (_, stopped) = rx.recv().await.unwrap();
+        assert_eq!(stopped.packet_type, "cconnect.internal.screenshare.stopped");
+        assert_eq!(stopped.body["reason"], "locked");
+
+        // Unlocking does not resume, only an explicit resume does
+        plugin
+            .handle_lock_event(SessionLockEvent::Unlocked)
+            .await
+            .unwrap();
+        assert!(!plugin.is_sharing());
+        assert!(rx.try_recv().is_err());
+
+        plugin.resume_after_lock().await.unwrap();
+        assert!(plugin.is_sharing());
+        assert!(!plugin.is_stopped_by_lock());
+        let (_, start) = rx.recv().await.unwrap();
+        assert_eq!(start.packet_type, "cconnect.screenshare.start");
+        assert!(plugin.resume_after_lock().await.is_err());
+
+        plugin.stop_sharing_explicit().await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_viewer_management() {
         let mut plugin = ScreenSharePlugin::new();
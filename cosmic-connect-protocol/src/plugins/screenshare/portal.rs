@@ -1,35 +1,154 @@
 //! XDG Desktop Portal integration for Screen Share
 //!
 //! Uses the ScreenCast portal to request permission and get PipeWire stream info.
+//!
+//! The [`PortalSession`] owns both the portal session and the PipeWire remote
+//! fd. Closing or dropping it releases the capture, so the compositor's
+//! recording indicator goes away as soon as sharing stops rather than at
+//! process exit.
 
 #[cfg(feature = "screenshare")]
 use ashpd::desktop::{
     screencast::{CursorMode, Screencast, SourceType},
-    PersistMode,
+    PersistMode, Session,
 };
 #[cfg(feature = "screenshare")]
 use std::os::fd::OwnedFd;
+use tracing::info;
 #[cfg(feature = "screenshare")]
-use tracing::{debug, error, info};
+use tracing::{debug, error, warn};
 
 use crate::Result;
 
+/// Lifecycle state of a portal session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortalSessionState {
+    /// Capture is running
+    Active,
+    /// Session and PipeWire fd have been released
+    Closed,
+}
+
 /// Screen share portal session info
-#[derive(Debug)]
 pub struct PortalSession {
-    /// PipeWire file descriptor
+    /// Portal session, closed when sharing stops
     #[cfg(feature = "screenshare")]
-    pub pipewire_fd: OwnedFd,
+    session: Option<Session<'static, Screencast<'static>>>,
+    /// PipeWire remote file descriptor
+    #[cfg(feature = "screenshare")]
+    pipewire_fd: Option<OwnedFd>,
     /// PipeWire node ID for the stream
     pub pipewire_node_id: u32,
+    /// Lifecycle state
+    state: PortalSessionState,
 }
 
-#[cfg(feature = "screenshare")]
 impl PortalSession {
-    /// Get the raw fd value for GStreamer
+    /// Create a session that is not backed by the portal
+    #[cfg(test)]
+    fn detached(pipewire_node_id: u32) -> Self {
+        Self {
+            #[cfg(feature = "screenshare")]
+            session: None,
+            #[cfg(feature = "screenshare")]
+            pipewire_fd: None,
+            pipewire_node_id,
+            state: PortalSessionState::Active,
+        }
+    }
+
+    /// Get the raw fd value for GStreamer, or -1 once closed
     pub fn fd(&self) -> i32 {
-        use std::os::fd::AsRawFd;
-        self.pipewire_fd.as_raw_fd()
+        #[cfg(feature = "screenshare")]
+        {
+            use std::os::fd::AsRawFd;
+            if let Some(fd) = &self.pipewire_fd {
+                return fd.as_raw_fd();
+            }
+        }
+        -1
+    }
+
+    /// Get the lifecycle state
+    pub fn state(&self) -> PortalSessionState {
+        self.state
+    }
+
+    /// Check whether the capture is still running
+    pub fn is_active(&self) -> bool {
+        self.state == PortalSessionState::Active
+    }
+
+    /// Release the PipeWire fd and close the portal session
+    ///
+    /// Closing an already closed session does nothing.
+    pub async fn close(&mut self) {
+        if !self.is_active() {
+            return;
+        }
+        self.state = PortalSessionState::Closed;
+
+        #[cfg(feature = "screenshare")]
+        {
+            // Dropping our end of the PipeWire remote stops the stream
+            self.pipewire_fd = None;
+
+            if let Some(session) = self.session.take() {
+                if let Err(e) = session.close().await {
+                    warn!("Failed to close screencast session: {}", e);
+                }
+            }
+        }
+
+        info!(
+            "Closed screencast portal session: node_id={}",
+            self.pipewire_node_id
+        );
+    }
+}
+
+impl Drop for PortalSession {
+    fn drop(&mut self) {
+        if !self.is_active() {
+            return;
+        }
+        self.state = PortalSessionState::Closed;
+
+        #[cfg(feature = "screenshare")]
+        {
+            self.pipewire_fd = None;
+
+            // Closing the session is a D-Bus call, so hand it to the runtime
+            if let Some(session) = self.session.take() {
+                match tokio::runtime::Handle::try_current() {
+                    Ok(handle) => {
+                        handle.spawn(async move {
+                            if let Err(e) = session.close().await {
+                                warn!("Failed to close screencast session: {}", e);
+                            }
+                        });
+                    }
+                    Err(_) => {
+                        warn!("No runtime to close screencast session, leaving it to the portal")
+                    }
+                }
+            }
+        }
+
+        info!(
+            "Dropped screencast portal session: node_id={}",
+            self.pipewire_node_id
+        );
+    }
+}
+
+impl std::fmt::Debug for PortalSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PortalSession")
+            .field("pipewire_fd", &self.fd())
+            .field("pipewire_node_id", &self.pipewire_node_id)
+            .field("state", &self.state)
+            .finish()
     }
 }
 
@@ -113,8 +232,10 @@ pub async fn request_screencast() -> Result<PortalSession> {
     info!("Screen share permission granted: node_id={}", node_id);
 
     Ok(PortalSession {
-        pipewire_fd: fd,
+        session: Some(session),
+        pipewire_fd: Some(fd),
         pipewire_node_id: node_id,
+        state: PortalSessionState::Active,
     })
 }
 
@@ -126,16 +247,55 @@ pub async fn request_screencast() -> Result<PortalSession> {
     ))
 }
 
-/// Stub PortalSession when feature is disabled
-#[cfg(not(feature = "screenshare"))]
-impl PortalSession {
-    pub fn fd(&self) -> i32 {
-        -1
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_close_lifecycle() {
+        let mut session = PortalSession::detached(42);
+        assert!(session.is_active());
+        assert_eq!(session.state(), PortalSessionState::Active);
+
+        session.close().await;
+        assert_eq!(session.state(), PortalSessionState::Closed);
+        assert_eq!(session.fd(), -1);
+
+        // Closing twice is harmless
+        session.close().await;
+        assert!(!session.is_active());
     }
-}
 
-#[cfg(all(test, feature = "screenshare"))]
-mod tests {
-    // Portal tests require a running D-Bus session and user interaction
-    // These are integration tests that should be run manually
+    #[cfg(feature = "screenshare")]
+    #[test]
+    fn test_drop_closes_pipewire_fd() {
+        use std::io::Read;
+        use std::os::unix::net::UnixStream;
+
+        let (ours, mut peer) = UnixStream::pair().unwrap();
+        let mut session = PortalSession::detached(42);
+        session.pipewire_fd = Some(OwnedFd::from(ours));
+        assert!(session.fd() >= 0);
+
+        drop(session);
+
+        // The peer sees EOF once our end is closed
+        let mut buf = [0u8; 1];
+        assert_eq!(peer.read(&mut buf).unwrap(), 0);
+    }
+
+    // Requires a running desktop portal and user interaction, run with:
+    // cargo test --features screenshare -- --ignored test_drop_closes_portal_session
+    #[cfg(feature = "screenshare")]
+    #[tokio::test]
+    #[ignore = "requires a desktop portal and user interaction"]
+    async fn test_drop_closes_portal_session() {
+        let session = request_screencast().await.unwrap();
+        assert!(session.is_active());
+        assert!(session.fd() >= 0);
+
+        // The recording indicator should disappear once the close is handled
+        drop(session);
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    }
 }
@@ -56,6 +56,21 @@
 //! }
 //! ```
 //!
+//! ### Cancelled Screenshot
+//!
+//! Sent instead of the data response when the user dismisses the portal's
+//! screenshot dialog, so the device stops waiting for an image.
+//!
+//! ```json
+//! {
+//!     "id": 1234567894,
+//!     "type": "cconnect.screenshot.data",
+//!     "body": {
+//!         "cancelled": true
+//!     }
+//! }
+//! ```
+//!
 //! ### Screenshot Data Response
 //!
 //! ```json
@@ -78,11 +93,20 @@
 //!
 //! ## Screenshot Capture
 //!
+//! Full-screen requests go through a [`ScreenshotCapture`], so the capture
+//! backend can be swapped, e.g. for tests.
+//!
 //! ### Wayland
-//! Uses pipewire/portal for secure screen capture:
-//! - Desktop portal API for screenshot permission
-//! - Supports full screen, region, and window selection
+//! With the `screenshot-portal` feature, [`PortalCapture`] uses the desktop
+//! portal's Screenshot interface:
 //! - User consent required per screenshot (security)
+//! - Dismissing the dialog sends a cancelled response
+//! - The portal's file is removed once copied for the transfer
+//!
+//! Without it, `gnome-screenshot` or `spectacle` are used.
+//!
+//! Region and window requests are captured with the utilities on the desktop
+//! only; the image is not sent back to the device.
 //!
 //! ### X11
 //! Uses traditional X11 screenshot utilities:
@@ -120,11 +144,100 @@ use std::fs::File;
 use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::Arc;
 use tokio::sync::mpsc::Sender;
 use tracing::{debug, error, info, warn};
 
 use super::{Plugin, PluginFactory};
 
+/// Outcome of capturing a screenshot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaptureOutcome {
+    /// Screenshot saved to this file
+    Captured(PathBuf),
+    /// The user dismissed the screenshot dialog
+    Cancelled,
+}
+
+/// Source of the full-screen screenshots a device requests
+#[async_trait]
+pub trait ScreenshotCapture: Send + Sync {
+    /// Capture the screen into a new file in `output_dir`
+    async fn capture(&self, output_dir: &Path) -> Result<CaptureOutcome>;
+}
+
+/// Captures with the screenshot utilities of the desktop
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ToolCapture;
+
+#[async_trait]
+impl ScreenshotCapture for ToolCapture {
+    async fn capture(&self, output_dir: &Path) -> Result<CaptureOutcome> {
+        let output_dir = output_dir.to_path_buf();
+        // The utilities block until the file is written
+        tokio::task::spawn_blocking(move || {
+            ScreenshotPlugin::capture_with_tools(&output_dir, CaptureType::FullScreen)
+        })
+        .await
+        .map_err(|e| ProtocolError::Plugin(format!("Screenshot task failed: {}", e)))?
+        .map(CaptureOutcome::Captured)
+    }
+}
+
+/// Captures through the desktop portal's Screenshot interface
+///
+/// The portal asks the user before capturing; dismissing its dialog yields
+/// [`CaptureOutcome::Cancelled`].
+#[cfg(feature = "screenshot-portal")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PortalCapture;
+
+#[cfg(feature = "screenshot-portal")]
+#[async_trait]
+impl ScreenshotCapture for PortalCapture {
+    async fn capture(&self, output_dir: &Path) -> Result<CaptureOutcome> {
+        use ashpd::desktop::screenshot::Screenshot;
+        use ashpd::desktop::ResponseError;
+
+        let response = Screenshot::request()
+            .interactive(false)
+            .modal(true)
+            .send()
+            .await
+            .and_then(|request| request.response());
+        let screenshot = match response {
+            Ok(screenshot) => screenshot,
+            Err(ashpd::Error::Response(ResponseError::Cancelled)) => {
+                return Ok(CaptureOutcome::Cancelled);
+            }
+            Err(e) => {
+                return Err(ProtocolError::Plugin(format!(
+                    "Screenshot portal failed: {}",
+                    e
+                )));
+            }
+        };
+
+        // The portal decides where the file is saved; move it into our
+        // directory so it does not pile up in the user's pictures
+        let source = screenshot.uri().to_file_path().map_err(|_| {
+            ProtocolError::Plugin(format!(
+                "Screenshot is not a local file: {}",
+                screenshot.uri()
+            ))
+        })?;
+        let output_path = output_dir.join(ScreenshotPlugin::screenshot_filename());
+        tokio::fs::copy(&source, &output_path)
+            .await
+            .map_err(|e| ProtocolError::from_io_error(e, "Failed to copy screenshot"))?;
+        if let Err(e) = tokio::fs::remove_file(&source).await {
+            warn!("Failed to remove portal screenshot {:?}: {}", source, e);
+        }
+
+        Ok(CaptureOutcome::Captured(output_path))
+    }
+}
+
 /// Screenshot plugin for remote screen capture
 ///
 /// Handles `cconnect.screenshot.*` packets for screenshot capture and transfer.
@@ -140,11 +253,19 @@ pub struct ScreenshotPlugin {
 
     /// Packet sender for sending responses back to the device
     packet_sender: Option<Sender<(String, Packet)>>,
+
+    /// Capture backend for full-screen requests
+    capture: Arc<dyn ScreenshotCapture>,
 }
 
 impl ScreenshotPlugin {
     /// Create a new Screenshot plugin
     pub fn new() -> Self {
+        Self::with_capture(Self::default_capture())
+    }
+
+    /// Create a Screenshot plugin capturing full-screen requests with `capture`
+    pub fn with_capture(capture: Arc<dyn ScreenshotCapture>) -> Self {
         let temp_dir = std::env::temp_dir().join("cosmic-connect-screenshots");
 
         Self {
@@ -152,9 +273,27 @@ impl ScreenshotPlugin {
             enabled: true,
             temp_dir,
             packet_sender: None,
+            capture,
         }
     }
 
+    /// Portal on Wayland when available, screenshot utilities otherwise
+    fn default_capture() -> Arc<dyn ScreenshotCapture> {
+        #[cfg(feature = "screenshot-portal")]
+        {
+            if Self::detect_display_server() == DisplayServer::Wayland {
+                return Arc::new(PortalCapture);
+            }
+        }
+        Arc::new(ToolCapture)
+    }
+
+    /// Name for a new screenshot file
+    fn screenshot_filename() -> String {
+        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+        format!("screenshot_{}.png", timestamp)
+    }
+
     /// Detect display server type (Wayland or X11)
     fn detect_display_server() -> DisplayServer {
         // Check if running under Wayland
@@ -223,6 +362,11 @@ impl ScreenshotPlugin {
             .with_payload_transfer_info(transfer_info)
     }
 
+    /// Create the response for a request the user cancelled
+    fn create_cancelled_response() -> Packet {
+        Packet::new("cconnect.screenshot.data", json!({ "cancelled": true }))
+    }
+
     /// Send a packet to the connected device
     async fn send_packet(&self, packet: Packet) -> Result<()> {
         let sender = self
@@ -244,8 +388,12 @@ impl ScreenshotPlugin {
     /// Capture a screenshot
     ///
     /// Returns the path to the captured screenshot file.
-    #[allow(dead_code)]
     fn capture_screenshot(&self, capture_type: CaptureType) -> Result<PathBuf> {
+        Self::capture_with_tools(&self.temp_dir, capture_type)
+    }
+
+    /// Capture a screenshot into `temp_dir` with the screenshot utilities
+    fn capture_with_tools(temp_dir: &Path, capture_type: CaptureType) -> Result<PathBuf> {
         let display_server = Self::detect_display_server();
 
         debug!(
@@ -254,17 +402,14 @@ impl ScreenshotPlugin {
         );
 
         // Ensure temp directory exists
-        std::fs::create_dir_all(&self.temp_dir)
+        std::fs::create_dir_all(temp_dir)
             .map_err(|e| ProtocolError::from_io_error(e, "Failed to create temp directory"))?;
 
-        // Generate filename with timestamp
-        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-        let filename = format!("screenshot_{}.png", timestamp);
-        let output_path = self.temp_dir.join(&filename);
+        let output_path = temp_dir.join(Self::screenshot_filename());
 
         match display_server {
-            DisplayServer::Wayland => self.capture_wayland(&output_path, capture_type),
-            DisplayServer::X11 => self.capture_x11(&output_path, capture_type),
+            DisplayServer::Wayland => Self::capture_wayland(&output_path, capture_type),
+            DisplayServer::X11 => Self::capture_x11(&output_path, capture_type),
             DisplayServer::Unknown => {
                 error!("Unable to detect display server (neither Wayland nor X11)");
                 Err(ProtocolError::InvalidPacket(
@@ -276,7 +421,7 @@ impl ScreenshotPlugin {
 
     /// Capture screenshot on Wayland
     #[cfg(target_os = "linux")]
-    fn capture_wayland(&self, output_path: &PathBuf, capture_type: CaptureType) -> Result<PathBuf> {
+    fn capture_wayland(output_path: &PathBuf, capture_type: CaptureType) -> Result<PathBuf> {
         info!("Attempting Wayland screenshot capture");
 
         // Try gnome-screenshot first (works with portal)
@@ -322,11 +467,7 @@ impl ScreenshotPlugin {
     }
 
     #[cfg(not(target_os = "linux"))]
-    fn capture_wayland(
-        &self,
-        _output_path: &PathBuf,
-        _capture_type: CaptureType,
-    ) -> Result<PathBuf> {
+    fn capture_wayland(_output_path: &PathBuf, _capture_type: CaptureType) -> Result<PathBuf> {
         Err(ProtocolError::InvalidPacket(
             "Wayland not supported on this platform".to_string(),
         ))
@@ -334,7 +475,7 @@ impl ScreenshotPlugin {
 
     /// Capture screenshot on X11
     #[cfg(target_os = "linux")]
-    fn capture_x11(&self, output_path: &PathBuf, capture_type: CaptureType) -> Result<PathBuf> {
+    fn capture_x11(output_path: &PathBuf, capture_type: CaptureType) -> Result<PathBuf> {
         info!("Attempting X11 screenshot capture");
 
         // Try scrot (lightweight and widely available)
@@ -386,7 +527,7 @@ impl ScreenshotPlugin {
     }
 
     #[cfg(not(target_os = "linux"))]
-    fn capture_x11(&self, _output_path: &PathBuf, _capture_type: CaptureType) -> Result<PathBuf> {
+    fn capture_x11(_output_path: &PathBuf, _capture_type: CaptureType) -> Result<PathBuf> {
         Err(ProtocolError::InvalidPacket(
             "X11 not supported on this platform".to_string(),
         ))
@@ -396,25 +537,29 @@ impl ScreenshotPlugin {
     async fn handle_screenshot_request(&mut self, packet: &Packet, device: &Device) -> Result<()> {
         debug!("Handling screenshot request from {}", device.name());
 
-        let body = &packet.body;
-        let capture_type = body
+        // Every request is captured full screen
+        let capture_type = packet
+            .body
             .get("captureType")
             .and_then(|v| v.as_str())
             .unwrap_or("fullscreen");
 
-        let capture = match capture_type {
-            "fullscreen" => CaptureType::FullScreen,
-            _ => CaptureType::FullScreen,
-        };
-
         info!(
-            "Capturing screenshot for {} (type: {:?})",
+            "Capturing screenshot for {} (requested: {})",
             device.name(),
-            capture
+            capture_type
         );
 
-        // Capture screenshot
-        let screenshot_path = self.capture_screenshot(capture)?;
+        std::fs::create_dir_all(&self.temp_dir)
+            .map_err(|e| ProtocolError::from_io_error(e, "Failed to create temp directory"))?;
+
+        let screenshot_path = match self.capture.capture(&self.temp_dir).await? {
+            CaptureOutcome::Captured(path) => path,
+            CaptureOutcome::Cancelled => {
+                info!("Screenshot for {} cancelled by the user", device.name());
+                return self.send_packet(Self::create_cancelled_response()).await;
+            }
+        };
 
         info!(
             "Screenshot captured successfully: {}",
@@ -692,12 +837,95 @@ mod tests {
     use super::*;
     use crate::{DeviceInfo, DeviceType};
     use serde_json::json;
+    use tokio::io::AsyncReadExt;
 
     fn create_test_device() -> Device {
         let info = DeviceInfo::new("Test Device", DeviceType::Desktop, 1716);
         Device::from_discovery(info)
     }
 
+    /// Capture standing in for the portal
+    enum MockCapture {
+        /// Saves this image
+        Image(Vec<u8>),
+        /// The user dismisses the dialog
+        Cancelled,
+    }
+
+    #[async_trait]
+    impl ScreenshotCapture for MockCapture {
+        async fn capture(&self, output_dir: &Path) -> Result<CaptureOutcome> {
+            match self {
+                MockCapture::Image(data) => {
+                    let path = output_dir.join("screenshot_test.png");
+                    std::fs::write(&path, data).unwrap();
+                    Ok(CaptureOutcome::Captured(path))
+                }
+                MockCapture::Cancelled => Ok(CaptureOutcome::Cancelled),
+            }
+        }
+    }
+
+    /// PNG signature and IHDR header of an image of the given size
+    fn png_header(width: u32, height: u32) -> Vec<u8> {
+        let mut data = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        data.extend_from_slice(&width.to_be_bytes());
+        data.extend_from_slice(&height.to_be_bytes());
+        data
+    }
+
+    async fn request_screenshot(capture: MockCapture) -> (Packet, tempfile::TempDir) {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut plugin = ScreenshotPlugin::with_capture(Arc::new(capture));
+        plugin.temp_dir = temp_dir.path().to_path_buf();
+
+        let device = create_test_device();
+        let (sender, mut packets) = tokio::sync::mpsc::channel(10);
+        plugin.init(&device, sender).await.unwrap();
+        plugin.start().await.unwrap();
+
+        let mut device = create_test_device();
+        let packet = Packet::new(
+            "cconnect.screenshot.request",
+            json!({ "captureType": "fullscreen" }),
+        );
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        let (_, response) = packets.recv().await.unwrap();
+        (response, temp_dir)
+    }
+
+    #[tokio::test]
+    async fn test_requested_screenshot_is_sent_as_payload() {
+        let image = png_header(640, 480);
+        let (response, _temp_dir) = request_screenshot(MockCapture::Image(image.clone())).await;
+
+        assert!(response.is_type("cconnect.screenshot.data"));
+        assert_eq!(response.body["width"], 640);
+        assert_eq!(response.body["height"], 480);
+        assert_eq!(response.payload_size, Some(image.len() as i64));
+
+        let port = response.payload_transfer_info.unwrap()["port"]
+            .as_u64()
+            .unwrap() as u16;
+        let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .unwrap();
+        let mut received = Vec::new();
+        stream.read_to_end(&mut received).await.unwrap();
+        assert_eq!(received, image);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_screenshot_is_reported() {
+        let (response, temp_dir) = request_screenshot(MockCapture::Cancelled).await;
+
+        assert!(response.is_type("cconnect.screenshot.data"));
+        assert_eq!(response.body["cancelled"], true);
+        assert_eq!(response.payload_size, None);
+        assert!(std::fs::read_dir(temp_dir.path()).unwrap().next().is_none());
+    }
+
     #[test]
     fn test_plugin_creation() {
         let plugin = ScreenshotPlugin::new();
@@ -0,0 +1,176 @@
+//! Per-Device Plugin Settings
+//!
+//! Plugins keep their settings (registered commands, sync flags, download
+//! directories) as a JSON value per device. The [`PluginManager`](super::PluginManager)
+//! hands every plugin instance a [`PluginSettings`] handle before `init`; the
+//! plugin reads its settings from it and persists changes with
+//! [`PluginSettings::save_config`]. Where the values are stored is up to the
+//! [`PluginSettingsStore`] set on the manager, e.g. the daemon's per-device
+//! configuration file.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! #[derive(Default, Serialize, Deserialize)]
+//! struct SyncSettings {
+//!     autosync: bool,
+//! }
+//!
+//! fn set_settings(&mut self, settings: PluginSettings) {
+//!     self.settings = Some(settings);
+//! }
+//!
+//! async fn init(&mut self, device: &Device, sender: Sender<(String, Packet)>) -> Result<()> {
+//!     let sync: SyncSettings = self.settings.as_ref().and_then(|s| s.get()).unwrap_or_default();
+//!     // ...
+//! }
+//! ```
+
+use crate::{ProtocolError, Result};
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Persistent storage of plugin settings, scoped by device
+#[async_trait]
+pub trait PluginSettingsStore: Send + Sync + std::fmt::Debug {
+    /// Load the settings of a plugin for a device
+    async fn load(&self, device_id: &str, plugin: &str) -> Option<Value>;
+
+    /// Store the settings of a plugin for a device
+    async fn save(&self, device_id: &str, plugin: &str, settings: Value) -> Result<()>;
+}
+
+/// Settings of one plugin for one device
+#[derive(Debug, Clone)]
+pub struct PluginSettings {
+    device_id: String,
+    plugin: String,
+    value: Option<Value>,
+    store: Arc<dyn PluginSettingsStore>,
+}
+
+impl PluginSettings {
+    /// Load the settings of a plugin for a device from a store
+    pub async fn load(
+        store: Arc<dyn PluginSettingsStore>,
+        device_id: impl Into<String>,
+        plugin: impl Into<String>,
+    ) -> Self {
+        let device_id = device_id.into();
+        let plugin = plugin.into();
+        let value = store.load(&device_id, &plugin).await;
+
+        Self {
+            device_id,
+            plugin,
+            value,
+            store,
+        }
+    }
+
+    /// Device the settings belong to
+    pub fn device_id(&self) -> &str {
+        &self.device_id
+    }
+
+    /// Raw settings, `None` if the plugin never saved any
+    pub fn value(&self) -> Option<&Value> {
+        self.value.as_ref()
+    }
+
+    /// Deserialize the settings
+    ///
+    /// Returns `None` if nothing was saved or the stored value does not match
+    /// `T`, so plugins can fall back to their defaults.
+    pub fn get<T: DeserializeOwned>(&self) -> Option<T> {
+        self.value
+            .as_ref()
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+
+    /// Persist new settings
+    pub async fn save_config<T: Serialize>(&mut self, settings: &T) -> Result<()> {
+        let value = serde_json::to_value(settings).map_err(|e| {
+            ProtocolError::Configuration(format!(
+                "Failed to serialize settings of plugin {}: {}",
+                self.plugin, e
+            ))
+        })?;
+
+        self.store
+            .save(&self.device_id, &self.plugin, value.clone())
+            .await?;
+        self.value = Some(value);
+        Ok(())
+    }
+}
+
+/// Store that keeps settings in memory
+///
+/// Intended for tests and for running without persistent configuration.
+#[derive(Debug, Default)]
+pub struct MemorySettingsStore {
+    settings: Mutex<HashMap<(String, String), Value>>,
+}
+
+impl MemorySettingsStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl PluginSettingsStore for MemorySettingsStore {
+    async fn load(&self, device_id: &str, plugin: &str) -> Option<Value> {
+        self.settings
+            .lock()
+            .unwrap()
+            .get(&(device_id.to_string(), plugin.to_string()))
+            .cloned()
+    }
+
+    async fn save(&self, device_id: &str, plugin: &str, settings: Value) -> Result<()> {
+        self.settings
+            .lock()
+            .unwrap()
+            .insert((device_id.to_string(), plugin.to_string()), settings);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct SyncSettings {
+        autosync: bool,
+    }
+
+    #[tokio::test]
+    async fn test_save_and_reload() {
+        let store: Arc<dyn PluginSettingsStore> = Arc::new(MemorySettingsStore::new());
+
+        let mut settings = PluginSettings::load(store.clone(), "phone", "clipboard").await;
+        assert!(settings.value().is_none());
+        assert_eq!(settings.get::<SyncSettings>(), None);
+
+        settings
+            .save_config(&SyncSettings { autosync: true })
+            .await
+            .unwrap();
+        assert_eq!(settings.get(), Some(SyncSettings { autosync: true }));
+
+        let reloaded = PluginSettings::load(store.clone(), "phone", "clipboard").await;
+        assert_eq!(reloaded.get(), Some(SyncSettings { autosync: true }));
+
+        let other_device = PluginSettings::load(store, "tablet", "clipboard").await;
+        assert!(other_device.value().is_none());
+    }
+}
@@ -49,6 +49,28 @@
 //! }
 //! ```
 //!
+//! ### Text With Files
+//!
+//! A file share may also carry `text`, e.g. the caption of a photo shared
+//! from Android. The file is downloaded as usual and the text is kept with
+//! it as a single [`ShareContent::FileWithText`] share. The caption of a
+//! rejected file is dropped along with it.
+//!
+//! ```json
+//! {
+//!     "id": 1234567890,
+//!     "type": "cconnect.share.request",
+//!     "body": {
+//!         "filename": "image.png",
+//!         "text": "Sunset at the beach"
+//!     },
+//!     "payloadSize": 1048576,
+//!     "payloadTransferInfo": {
+//!         "port": 1739
+//!     }
+//! }
+//! ```
+//!
 //! ### URL Sharing
 //!
 //! Shares URLs. The receiving device typically opens with the default handler.
@@ -109,6 +131,28 @@
 //! The plugin handles packet creation and metadata. Actual payload transfer
 //! is handled by the transport layer.
 //!
+//! ## Receive Policy
+//!
+//! Incoming files are handled according to a [`FileReceivePolicy`]:
+//! - `AutoAccept` downloads immediately (default)
+//! - `Prompt` holds the file as a pending [`FileOffer`] and reports it with an
+//!   internal `cconnect.internal.share.offer` packet; the download starts once
+//!   [`SharePlugin::accept_transfer`] is called
+//! - `Reject` drops the file without downloading it
+//!
+//! Once a file is downloaded it is reported to the daemon with an internal
+//! `cconnect.internal.share.received` packet carrying its path. Shared text,
+//! alone or as a caption, is handed to the daemon for the clipboard with an
+//! internal `cconnect.internal.share.text` packet.
+//!
+//! ## Sender Updates
+//!
+//! While a share is running the sender may send `cconnect.share.request.update`
+//! again with new totals, which replace the announced ones, or with
+//! `"cancel": true` when the user cancels the send. Cancelling stops the
+//! download named by an optional `transferId`, or all downloads and pending
+//! offers from the device, and removes their partial files.
+//!
 //! ## Example
 //!
 //! ```rust,ignore
@@ -148,17 +192,42 @@
 //!
 //! - [Valent Protocol Documentation](https://valent.andyholmes.ca/documentation/protocol.html)
 
-use crate::{Device, Packet, Result};
+use crate::{Device, Packet, ProtocolError, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::HashMap;
+use std::future::Future;
+use std::path::Path;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, watch, RwLock};
 use tracing::{debug, info, warn};
 
+use super::metrics::PluginMetrics;
 use super::{Plugin, PluginFactory};
 
+/// Internal packet type reporting a file offer that awaits a decision
+pub const PACKET_TYPE_SHARE_OFFER: &str = "cconnect.internal.share.offer";
+
+/// Internal packet type reporting a downloaded file
+pub const PACKET_TYPE_SHARE_RECEIVED: &str = "cconnect.internal.share.received";
+
+/// Internal packet type carrying shared text for the clipboard
+pub const PACKET_TYPE_SHARE_TEXT: &str = "cconnect.internal.share.text";
+
+/// How incoming files are handled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileReceivePolicy {
+    /// Download incoming files immediately
+    #[default]
+    AutoAccept,
+    /// Hold incoming files until the user accepts or rejects them
+    Prompt,
+    /// Drop incoming files without downloading them
+    Reject,
+}
+
 /// Information about a file being shared
 ///
 /// Contains metadata for file transfers including timestamps and display preferences.
@@ -272,6 +341,14 @@ pub enum ShareContent {
 
     /// URL to open
     Url(String),
+
+    /// File transfer sent along with text, such as a photo and its caption
+    FileWithText {
+        /// File metadata
+        file: FileShareInfo,
+        /// Accompanying text
+        text: String,
+    },
 }
 
 /// Record of an incoming or outgoing share
@@ -295,6 +372,242 @@ pub struct ShareRecord {
     pub incoming: bool,
 }
 
+/// Incoming file ready to be downloaded
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileOffer {
+    /// Transfer ID (the share request packet ID)
+    pub transfer_id: String,
+
+    /// Device offering the file
+    pub device_id: String,
+
+    /// Name of the device offering the file
+    pub device_name: String,
+
+    /// Remote host serving the payload
+    pub host: String,
+
+    /// Remote port serving the payload
+    pub port: u16,
+
+    /// File metadata
+    pub file: FileShareInfo,
+}
+
+/// Starts payload downloads for accepted files
+pub trait FileDownloader: Send + Sync {
+    /// Begin downloading an offered file in the background
+    ///
+    /// A [`PACKET_TYPE_SHARE_RECEIVED`] packet is sent through `reporter`
+    /// once the file is downloaded. The download stops once `cancel` turns
+    /// true, see [`download_until_cancelled`].
+    fn download(
+        &self,
+        offer: FileOffer,
+        tls_config: Option<Arc<crate::TlsConfig>>,
+        reporter: Option<mpsc::Sender<(String, Packet)>>,
+        cancel: watch::Receiver<bool>,
+    );
+}
+
+/// Run a download into `path` until it ends or `cancel` turns true
+///
+/// A cancelled download's partial file is removed and
+/// [`ProtocolError::Cancelled`] returned.
+pub async fn download_until_cancelled(
+    download: impl Future<Output = Result<()>>,
+    path: &Path,
+    mut cancel: watch::Receiver<bool>,
+) -> Result<()> {
+    tokio::select! {
+        result = download => result,
+        // A dropped sender means nobody can cancel any more
+        Ok(_) = cancel.wait_for(|cancelled| *cancelled) => {
+            match tokio::fs::remove_file(path).await {
+                Ok(()) => debug!("Removed partial file {:?}", path),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => warn!("Failed to remove partial file {:?}: {}", path, e),
+            }
+            Err(ProtocolError::Cancelled(format!(
+                "Download of {:?} cancelled by sender",
+                path
+            )))
+        }
+    }
+}
+
+/// Build the internal packet reporting a downloaded file
+pub fn received_file_packet(offer: &FileOffer, path: &std::path::Path) -> Packet {
+    Packet::new(
+        PACKET_TYPE_SHARE_RECEIVED,
+        json!({
+            "transferId": offer.transfer_id,
+            "filename": offer.file.filename,
+            "path": path.to_string_lossy(),
+            "deviceName": offer.device_name,
+        }),
+    )
+}
+
+/// Downloads payloads over TLS into the user's Downloads directory
+#[derive(Debug, Default)]
+pub struct PayloadDownloader;
+
+impl FileDownloader for PayloadDownloader {
+    fn download(
+        &self,
+        offer: FileOffer,
+        tls_config: Option<Arc<crate::TlsConfig>>,
+        reporter: Option<mpsc::Sender<(String, Packet)>>,
+        cancel: watch::Receiver<bool>,
+    ) {
+        let host_clone = offer.host.clone();
+        let port = offer.port;
+        let device_name = offer.device_name.clone();
+        let filename_clone = offer.file.filename.clone();
+        let size = offer.file.size;
+
+        // Spawn background task to download file
+        tokio::spawn(async move {
+            // Create downloads directory
+            let downloads_dir = std::path::PathBuf::from(
+                std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string()),
+            )
+            .join("Downloads");
+
+            if let Err(e) = tokio::fs::create_dir_all(&downloads_dir).await {
+                warn!("Failed to create downloads directory: {}", e);
+                return;
+            }
+
+            let file_path = downloads_dir.join(&filename_clone);
+
+            info!(
+                "Downloading file '{}' from {} ({}:{}) to {:?}",
+                filename_clone, device_name, host_clone, port, file_path
+            );
+
+            // Connect to payload server and download file with progress tracking
+            use crate::TlsPayloadClient;
+            use std::sync::atomic::{AtomicU64, Ordering};
+            use std::sync::Arc;
+            use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+            // Use TLS for payload transfer (required for Android compatibility)
+            if let Some(config) = tls_config {
+                match TlsPayloadClient::new(&host_clone, port, &config).await {
+                    Ok(client) => {
+                        let transfer_start = Instant::now();
+                        let last_update = Arc::new(AtomicU64::new(0));
+                        let filename_for_callback = filename_clone.clone();
+                        let device_name_for_callback = device_name.clone();
+
+                        // Add progress callback with rate limiting (update every 500ms)
+                        let client_with_progress = client.with_progress(Box::new(move |transferred, total| {
+                            let now = SystemTime::now()
+                                .duration_since(UNIX_EPOCH)
+                                .unwrap()
+                                .as_millis() as u64;
+                            let last = last_update.load(Ordering::Relaxed);
+
+                            // Only log progress every 500ms to avoid spam
+                            if now - last >= 500 {
+                                last_update.store(now, Ordering::Relaxed);
+                                let percent = (transferred as f64 / total as f64 * 100.0) as u8;
+                                let elapsed = transfer_start.elapsed().as_secs_f64();
+                                let speed = if elapsed > 0.0 {
+                                    transferred as f64 / elapsed
+                                } else {
+                                    0.0
+                                };
+
+                                info!(
+                                    "Download progress '{}' from {}: {} / {} bytes ({}%, {:.2} KB/s)",
+                                    filename_for_callback,
+                                    device_name_for_callback,
+                                    transferred,
+                                    total,
+                                    percent,
+                                    speed / 1024.0
+                                );
+
+                                // DESIGN LIMITATION: Progress packets not sent to sender device
+                                //
+                                // The current architecture spawns a detached async task for file downloads,
+                                // which doesn't have access to the device's packet sender channel. This is
+                                // intentional to avoid blocking packet processing.
+                                //
+                                // To enable progress packet sending, we would need to:
+                                // 1. Pass packet_sender channel into this spawned task
+                                // 2. Send cconnect.share.request.progress packets periodically
+                                //
+                                // Progress is currently logged locally (see lines 742-750) and could be
+                                // exposed via a callback mechanism if needed by the UI layer.
+                                //
+                                // Example implementation:
+                                //   let progress_packet = Packet::new("cconnect.share.request.progress", json!({
+                                //       "transferId": transfer_id,
+                                //       "filename": filename,
+                                //       "bytesTransferred": transferred,
+                                //       "totalBytes": total,
+                                //       "percentComplete": percent,
+                                //       "speedBytesPerSecond": speed as u64,
+                                //       "eta": eta
+                                //   }));
+                                //   packet_sender.send((device_id, progress_packet)).await;
+                            }
+
+                            true // Continue transfer
+                        }));
+
+                        let receive = client_with_progress.receive_file(&file_path, size as u64);
+                        match download_until_cancelled(receive, &file_path, cancel).await {
+                            Ok(()) => {
+                                info!(
+                                    "Successfully downloaded file '{}' from {} via TLS",
+                                    filename_clone, device_name
+                                );
+                                if let Some(reporter) = reporter {
+                                    let packet = received_file_packet(&offer, &file_path);
+                                    if let Err(e) =
+                                        reporter.send((offer.device_id.clone(), packet)).await
+                                    {
+                                        warn!("Failed to report received file: {}", e);
+                                    }
+                                }
+                            }
+                            Err(ProtocolError::Cancelled(_)) => {
+                                info!(
+                                    "Download of '{}' cancelled by {}",
+                                    filename_clone, device_name
+                                );
+                            }
+                            Err(e) => {
+                                warn!(
+                                    "Failed to download file '{}' from {} via TLS: {}",
+                                    filename_clone, device_name, e
+                                );
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Failed to connect to TLS payload server {}:{}: {}",
+                            host_clone, port, e
+                        );
+                    }
+                }
+            } else {
+                warn!(
+                    "Cannot download file '{}' from {}: TLS config not set. \
+                     Call set_tls_config() on SharePlugin before receiving files.",
+                    filename_clone, device_name
+                );
+            }
+        });
+    }
+}
+
 /// Share plugin for file, text, and URL sharing
 ///
 /// Handles `cconnect.share.request` packets for transferring content between devices.
@@ -330,6 +643,27 @@ pub struct SharePlugin {
     /// TLS configuration for secure payload transfers
     /// Required for receiving files from Android (uses TLS for payload transfers)
     tls_config: Option<Arc<crate::TlsConfig>>,
+
+    /// Packet sender for reporting file offers
+    packet_sender: Option<mpsc::Sender<(String, Packet)>>,
+
+    /// How incoming files are handled
+    receive_policy: FileReceivePolicy,
+
+    /// File offers waiting to be accepted or rejected, by transfer ID
+    pending_offers: Arc<RwLock<HashMap<String, FileOffer>>>,
+
+    /// Starts downloads for accepted files
+    downloader: Box<dyn FileDownloader>,
+
+    /// Cancel switches of started downloads, by transfer ID
+    downloads: Arc<std::sync::Mutex<HashMap<String, watch::Sender<bool>>>>,
+
+    /// Totals of the current multi-file transfer as last announced by the sender
+    announced_transfer: Arc<RwLock<Option<MultiFileInfo>>>,
+
+    /// Counters of this plugin for the device
+    metrics: Option<Arc<PluginMetrics>>,
 }
 
 // Manual Debug impl to skip tls_config (TlsConfig doesn't implement Debug)
@@ -342,6 +676,8 @@ impl std::fmt::Debug for SharePlugin {
                 "tls_config",
                 &self.tls_config.as_ref().map(|_| "<TlsConfig>"),
             )
+            .field("receive_policy", &self.receive_policy)
+            .field("pending_offers", &"<pending_offers>")
             .finish()
     }
 }
@@ -358,10 +694,22 @@ impl SharePlugin {
     /// assert_eq!(plugin.share_count(), 0);
     /// ```
     pub fn new() -> Self {
+        Self::with_downloader(Box::new(PayloadDownloader))
+    }
+
+    /// Create a share plugin with a custom downloader
+    pub fn with_downloader(downloader: Box<dyn FileDownloader>) -> Self {
         Self {
             device_id: None,
             shares: Arc::new(RwLock::new(Vec::new())),
             tls_config: None,
+            packet_sender: None,
+            receive_policy: FileReceivePolicy::default(),
+            pending_offers: Arc::new(RwLock::new(HashMap::new())),
+            downloader,
+            downloads: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            announced_transfer: Arc::new(RwLock::new(None)),
+            metrics: None,
         }
     }
 
@@ -639,10 +987,208 @@ impl SharePlugin {
         self.shares.write().await.clear();
     }
 
+    /// Set how incoming files are handled
+    pub fn set_receive_policy(&mut self, policy: FileReceivePolicy) {
+        info!("File receive policy set to {:?}", policy);
+        self.receive_policy = policy;
+    }
+
+    /// Get how incoming files are handled
+    pub fn receive_policy(&self) -> FileReceivePolicy {
+        self.receive_policy
+    }
+
+    /// Get file offers waiting to be accepted or rejected
+    pub async fn pending_offers(&self) -> Vec<FileOffer> {
+        self.pending_offers.read().await.values().cloned().collect()
+    }
+
+    /// Accept a pending file offer and start downloading it
+    ///
+    /// Returns false if no offer with this transfer ID is pending.
+    pub async fn accept_transfer(&self, transfer_id: &str) -> bool {
+        let Some(offer) = self.pending_offers.write().await.remove(transfer_id) else {
+            return false;
+        };
+
+        info!(
+            "Accepted file '{}' from {}",
+            offer.file.filename, offer.device_name
+        );
+        self.start_download(offer);
+        true
+    }
+
+    /// Start downloading an accepted file
+    fn start_download(&self, offer: FileOffer) {
+        let (cancel, cancelled) = watch::channel(false);
+        {
+            let mut downloads = self.downloads.lock().unwrap();
+            // Finished downloads have dropped their receiver
+            downloads.retain(|_, cancel| !cancel.is_closed());
+            downloads.insert(offer.transfer_id.clone(), cancel);
+        }
+
+        let reporter = self.download_reporter(offer.file.size);
+        self.downloader
+            .download(offer, self.get_tls_config(), reporter, cancelled);
+    }
+
+    /// Reporter for one download that counts its bytes once it completes
+    ///
+    /// Forwards the download's packets to the packet sender and records
+    /// `size` in the metrics when the file has been received, so failed and
+    /// cancelled downloads are not counted.
+    fn download_reporter(&self, size: i64) -> Option<mpsc::Sender<(String, Packet)>> {
+        let Some(metrics) = self.metrics.clone() else {
+            return self.packet_sender.clone();
+        };
+        let packet_sender = self.packet_sender.clone();
+
+        let (tx, mut rx) = mpsc::channel(1);
+        tokio::spawn(async move {
+            while let Some((device_id, packet)) = rx.recv().await {
+                if packet.is_type(PACKET_TYPE_SHARE_RECEIVED) {
+                    metrics.record_bytes(size.max(0) as u64);
+                }
+                if let Some(sender) = &packet_sender {
+                    if sender.send((device_id, packet)).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        Some(tx)
+    }
+
+    /// Cancel incoming transfers the sender gave up on
+    ///
+    /// Stops the download or drops the pending offer with `transfer_id`, or
+    /// all of them when it is `None`. Returns how many were cancelled.
+    pub async fn cancel_incoming(&self, transfer_id: Option<&str>) -> usize {
+        let mut cancelled = 0;
+
+        {
+            let mut downloads = self.downloads.lock().unwrap();
+            downloads.retain(|id, cancel| {
+                if transfer_id.is_some_and(|transfer_id| transfer_id != id) {
+                    return !cancel.is_closed();
+                }
+                // Only count downloads that are still running
+                if cancel.send(true).is_ok() {
+                    cancelled += 1;
+                }
+                false
+            });
+        }
+
+        self.pending_offers.write().await.retain(|id, _| {
+            let keep = transfer_id.is_some_and(|transfer_id| transfer_id != id);
+            if !keep {
+                cancelled += 1;
+            }
+            keep
+        });
+
+        cancelled
+    }
+
+    /// Totals of the current multi-file transfer as last announced by the sender
+    pub async fn announced_transfer(&self) -> Option<MultiFileInfo> {
+        self.announced_transfer.read().await.clone()
+    }
+
+    /// Reject a pending file offer without downloading it
+    ///
+    /// Returns false if no offer with this transfer ID is pending.
+    pub async fn reject_transfer(&self, transfer_id: &str) -> bool {
+        let Some(offer) = self.pending_offers.write().await.remove(transfer_id) else {
+            return false;
+        };
+
+        info!(
+            "Rejected file '{}' from {}",
+            offer.file.filename, offer.device_name
+        );
+        true
+    }
+
+    /// Build a downloadable offer from a file share packet
+    ///
+    /// Returns `None` if the packet carries no usable payload transfer info.
+    fn file_offer(packet: &Packet, device: &Device, file: &FileShareInfo) -> Option<FileOffer> {
+        let transfer_info = packet.payload_transfer_info.as_ref()?;
+
+        let Some(port_value) = transfer_info.get("port") else {
+            warn!("Cannot download file: no port in payloadTransferInfo");
+            return None;
+        };
+
+        let Some(host) = &device.host else {
+            warn!("Cannot download file: device host not available");
+            return None;
+        };
+
+        Some(FileOffer {
+            transfer_id: packet.id.to_string(),
+            device_id: device.id().to_string(),
+            device_name: device.name().to_string(),
+            host: host.clone(),
+            port: port_value.as_i64().unwrap_or(0) as u16,
+            file: file.clone(),
+        })
+    }
+
+    /// Hold a file offer until the user decides and report it to the daemon
+    async fn offer_file(&self, offer: FileOffer) {
+        info!(
+            "Holding file '{}' from {} until accepted (transfer {})",
+            offer.file.filename, offer.device_name, offer.transfer_id
+        );
+
+        let device_id = offer.device_id.clone();
+        let packet = Packet::new(
+            PACKET_TYPE_SHARE_OFFER,
+            json!({
+                "transferId": offer.transfer_id,
+                "filename": offer.file.filename,
+                "size": offer.file.size,
+            }),
+        );
+
+        self.pending_offers
+            .write()
+            .await
+            .insert(offer.transfer_id.clone(), offer);
+
+        if let Some(sender) = &self.packet_sender {
+            if let Err(e) = sender.send((device_id, packet)).await {
+                warn!("Failed to report file offer: {}", e);
+            }
+        }
+    }
+
+    /// Hand shared text to the daemon
+    async fn report_text(&self, device: &Device, text: &str) {
+        let packet = Packet::new(
+            PACKET_TYPE_SHARE_TEXT,
+            json!({
+                "text": text,
+                "deviceName": device.name(),
+            }),
+        );
+
+        if let Some(sender) = &self.packet_sender {
+            if let Err(e) = sender.send((device.id().to_string(), packet)).await {
+                warn!("Failed to report shared text: {}", e);
+            }
+        }
+    }
+
     /// Handle an incoming share request packet
     ///
     /// Processes share packets and records them in history.
-    /// For file shares, initiates download via PayloadClient.
+    /// For file shares, applies the receive policy before downloading.
     async fn handle_share_request(&self, packet: &Packet, device: &Device) {
         let device_id = device.id().to_string();
 
@@ -669,157 +1215,43 @@ impl SharePlugin {
                 file_info.size
             );
 
-            // Check if we need to download the file
-            if let Some(transfer_info) = &packet.payload_transfer_info {
-                // Extract port from payloadTransferInfo
-                if let Some(port_value) = transfer_info.get("port") {
-                    let port = port_value.as_i64().unwrap_or(0) as u16;
-
-                    // Get remote host from device
-                    if let Some(host) = &device.host {
-                        let host_clone = host.clone();
-                        let filename_clone = filename.to_string();
-                        let size = file_info.size;
-                        let device_name = device.name().to_string();
-
-                        // Get TLS config for secure payload transfer
-                        let tls_config = self.get_tls_config();
-
-                        // Spawn background task to download file
-                        tokio::spawn(async move {
-                            // Create downloads directory
-                            let downloads_dir = std::path::PathBuf::from(
-                                std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string()),
-                            )
-                            .join("Downloads");
-
-                            if let Err(e) = tokio::fs::create_dir_all(&downloads_dir).await {
-                                warn!("Failed to create downloads directory: {}", e);
-                                return;
-                            }
-
-                            let file_path = downloads_dir.join(&filename_clone);
-
-                            info!(
-                                "Downloading file '{}' from {} ({}:{}) to {:?}",
-                                filename_clone, device_name, host_clone, port, file_path
-                            );
-
-                            // Connect to payload server and download file with progress tracking
-                            use crate::TlsPayloadClient;
-                            use std::sync::atomic::{AtomicU64, Ordering};
-                            use std::sync::Arc;
-                            use std::time::{Instant, SystemTime, UNIX_EPOCH};
-
-                            // Use TLS for payload transfer (required for Android compatibility)
-                            if let Some(config) = tls_config {
-                                match TlsPayloadClient::new(&host_clone, port, &config).await {
-                                    Ok(client) => {
-                                        let transfer_start = Instant::now();
-                                        let last_update = Arc::new(AtomicU64::new(0));
-                                        let filename_for_callback = filename_clone.clone();
-                                        let device_name_for_callback = device_name.clone();
-
-                                        // Add progress callback with rate limiting (update every 500ms)
-                                        let client_with_progress = client.with_progress(Box::new(move |transferred, total| {
-                                            let now = SystemTime::now()
-                                                .duration_since(UNIX_EPOCH)
-                                                .unwrap()
-                                                .as_millis() as u64;
-                                            let last = last_update.load(Ordering::Relaxed);
-
-                                            // Only log progress every 500ms to avoid spam
-                                            if now - last >= 500 {
-                                                last_update.store(now, Ordering::Relaxed);
-                                                let percent = (transferred as f64 / total as f64 * 100.0) as u8;
-                                                let elapsed = transfer_start.elapsed().as_secs_f64();
-                                                let speed = if elapsed > 0.0 {
-                                                    transferred as f64 / elapsed
-                                                } else {
-                                                    0.0
-                                                };
-
-                                                info!(
-                                                    "Download progress '{}' from {}: {} / {} bytes ({}%, {:.2} KB/s)",
-                                                    filename_for_callback,
-                                                    device_name_for_callback,
-                                                    transferred,
-                                                    total,
-                                                    percent,
-                                                    speed / 1024.0
-                                                );
-
-                                                // DESIGN LIMITATION: Progress packets not sent to sender device
-                                                //
-                                                // The current architecture spawns a detached async task for file downloads,
-                                                // which doesn't have access to the device's packet sender channel. This is
-                                                // intentional to avoid blocking packet processing.
-                                                //
-                                                // To enable progress packet sending, we would need to:
-                                                // 1. Pass packet_sender channel into this spawned task
-                                                // 2. Send cconnect.share.request.progress packets periodically
-                                                //
-                                                // Progress is currently logged locally (see lines 742-750) and could be
-                                                // exposed via a callback mechanism if needed by the UI layer.
-                                                //
-                                                // Example implementation:
-                                                //   let progress_packet = Packet::new("cconnect.share.request.progress", json!({
-                                                //       "transferId": transfer_id,
-                                                //       "filename": filename,
-                                                //       "bytesTransferred": transferred,
-                                                //       "totalBytes": total,
-                                                //       "percentComplete": percent,
-                                                //       "speedBytesPerSecond": speed as u64,
-                                                //       "eta": eta
-                                                //   }));
-                                                //   packet_sender.send((device_id, progress_packet)).await;
-                                            }
-
-                                            true // Continue transfer
-                                        }));
-
-                                        match client_with_progress
-                                            .receive_file(&file_path, size as u64)
-                                            .await
-                                        {
-                                            Ok(()) => {
-                                                info!(
-                                                    "Successfully downloaded file '{}' from {} via TLS",
-                                                    filename_clone, device_name
-                                                );
-                                            }
-                                            Err(e) => {
-                                                warn!(
-                                                    "Failed to download file '{}' from {} via TLS: {}",
-                                                    filename_clone, device_name, e
-                                                );
-                                            }
-                                        }
-                                    }
-                                    Err(e) => {
-                                        warn!(
-                                            "Failed to connect to TLS payload server {}:{}: {}",
-                                            host_clone, port, e
-                                        );
-                                    }
-                                }
-                            } else {
-                                warn!(
-                                    "Cannot download file '{}' from {}: TLS config not set. \
-                                     Call set_tls_config() on SharePlugin before receiving files.",
-                                    filename_clone, device_name
-                                );
-                            }
-                        });
-                    } else {
-                        warn!("Cannot download file: device host not available");
+            match self.receive_policy {
+                FileReceivePolicy::Reject => {
+                    info!(
+                        "Rejected file '{}' from {}: file receive policy is reject",
+                        filename,
+                        device.name()
+                    );
+                    return;
+                }
+                FileReceivePolicy::Prompt => {
+                    if let Some(offer) = Self::file_offer(packet, device, &file_info) {
+                        self.offer_file(offer).await;
+                    }
+                }
+                FileReceivePolicy::AutoAccept => {
+                    if let Some(offer) = Self::file_offer(packet, device, &file_info) {
+                        self.start_download(offer);
                     }
-                } else {
-                    warn!("Cannot download file: no port in payloadTransferInfo");
                 }
             }
 
-            ShareContent::File(file_info)
+            match packet.body.get("text").and_then(|v| v.as_str()) {
+                Some(text) => {
+                    info!(
+                        "File share '{}' from {} carries text: {} chars",
+                        filename,
+                        device.name(),
+                        text.len()
+                    );
+                    self.report_text(device, text).await;
+                    ShareContent::FileWithText {
+                        file: file_info,
+                        text: text.to_string(),
+                    }
+                }
+                None => ShareContent::File(file_info),
+            }
         } else if let Some(text) = packet.body.get("text").and_then(|v| v.as_str()) {
             // Text share
             info!(
@@ -828,6 +1260,7 @@ impl SharePlugin {
                 device_id,
                 text.len()
             );
+            self.report_text(device, text).await;
 
             ShareContent::Text(text.to_string())
         } else if let Some(url) = packet.body.get("url").and_then(|v| v.as_str()) {
@@ -863,10 +1296,33 @@ impl SharePlugin {
         debug!("Share history size: {}", self.shares.read().await.len());
     }
 
-    /// Handle a multi-file update packet
+    /// Handle a share update packet
     ///
-    /// Logs multi-file transfer announcement.
-    fn handle_multifile_update(&self, packet: &Packet, device: &Device) {
+    /// Cancels transfers when the sender cancelled, otherwise records the
+    /// announced multi-file totals.
+    async fn handle_share_update(&self, packet: &Packet, device: &Device) {
+        let cancel = packet
+            .body
+            .get("cancel")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if cancel {
+            let transfer_id = packet.body.get("transferId").and_then(|v| match v {
+                serde_json::Value::String(id) => Some(id.clone()),
+                serde_json::Value::Number(id) => Some(id.to_string()),
+                _ => None,
+            });
+            let cancelled = self.cancel_incoming(transfer_id.as_deref()).await;
+            *self.announced_transfer.write().await = None;
+            info!(
+                "{} ({}) cancelled sharing, stopped {} transfer(s)",
+                device.name(),
+                device.id(),
+                cancelled
+            );
+            return;
+        }
+
         let number_of_files = packet
             .body
             .get("numberOfFiles")
@@ -885,6 +1341,12 @@ impl SharePlugin {
             number_of_files,
             total_size
         );
+
+        // Later updates replace the totals, e.g. when files are added
+        *self.announced_transfer.write().await = Some(MultiFileInfo {
+            number_of_files: number_of_files as i32,
+            total_payload_size: total_size,
+        });
     }
 }
 
@@ -927,13 +1389,18 @@ impl Plugin for SharePlugin {
     async fn init(
         &mut self,
         device: &Device,
-        _packet_sender: tokio::sync::mpsc::Sender<(String, Packet)>,
+        packet_sender: tokio::sync::mpsc::Sender<(String, Packet)>,
     ) -> Result<()> {
         self.device_id = Some(device.id().to_string());
+        self.packet_sender = Some(packet_sender);
         info!("Share plugin initialized for device {}", device.name());
         Ok(())
     }
 
+    fn set_metrics(&mut self, metrics: Arc<PluginMetrics>) {
+        self.metrics = Some(metrics);
+    }
+
     async fn start(&mut self) -> Result<()> {
         info!("Share plugin started");
         Ok(())
@@ -951,7 +1418,7 @@ impl Plugin for SharePlugin {
         } else if packet.is_type("cconnect.share.request.update")
             || packet.is_type("kdeconnect.share.request.update")
         {
-            self.handle_multifile_update(packet, device);
+            self.handle_share_update(packet, device).await;
         }
         Ok(())
     }
@@ -1224,6 +1691,13 @@ mod tests {
 
         // Update packets don't create share records
         assert_eq!(plugin.share_count(), 0);
+        assert_eq!(
+            plugin.announced_transfer().await,
+            Some(MultiFileInfo {
+                number_of_files: 3,
+                total_payload_size: 5242880,
+            })
+        );
     }
 
     #[tokio::test]
@@ -1337,4 +1811,299 @@ mod tests {
         // Should not create a share record
         assert_eq!(plugin.share_count(), 0);
     }
+
+    /// Downloader that records offers instead of downloading
+    #[derive(Clone, Default)]
+    struct RecordingDownloader {
+        downloads: Arc<std::sync::Mutex<Vec<FileOffer>>>,
+    }
+
+    impl FileDownloader for RecordingDownloader {
+        fn download(
+            &self,
+            offer: FileOffer,
+            _tls_config: Option<Arc<crate::TlsConfig>>,
+            _reporter: Option<mpsc::Sender<(String, Packet)>>,
+            _cancel: watch::Receiver<bool>,
+        ) {
+            self.downloads.lock().unwrap().push(offer);
+        }
+    }
+
+    /// Downloader that keeps the reporter of each download
+    #[derive(Clone, Default)]
+    struct ReportingDownloader {
+        downloads: Arc<std::sync::Mutex<Vec<(FileOffer, mpsc::Sender<(String, Packet)>)>>>,
+    }
+
+    impl FileDownloader for ReportingDownloader {
+        fn download(
+            &self,
+            offer: FileOffer,
+            _tls_config: Option<Arc<crate::TlsConfig>>,
+            reporter: Option<mpsc::Sender<(String, Packet)>>,
+            _cancel: watch::Receiver<bool>,
+        ) {
+            self.downloads
+                .lock()
+                .unwrap()
+                .push((offer, reporter.unwrap()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bytes_counted_when_download_completes() {
+        let downloader = ReportingDownloader::default();
+        let mut plugin = SharePlugin::with_downloader(Box::new(downloader.clone()));
+        let metrics = Arc::new(PluginMetrics::default());
+        plugin.set_metrics(metrics.clone());
+
+        let mut device = create_test_device();
+        device.host = Some("192.168.1.50".to_string());
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        plugin.init(&device, tx).await.unwrap();
+
+        let packet = create_file_packet_with_payload();
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        // Started but not finished
+        assert_eq!(metrics.snapshot().bytes_transferred, 0);
+
+        let (offer, reporter) = downloader.downloads.lock().unwrap().pop().unwrap();
+        let path = std::path::Path::new("/tmp/photo.jpg");
+        reporter
+            .send((device.id().to_string(), received_file_packet(&offer, path)))
+            .await
+            .unwrap();
+
+        // The report still reaches the packet sender
+        let (_, forwarded) = rx.recv().await.unwrap();
+        assert!(forwarded.is_type(PACKET_TYPE_SHARE_RECEIVED));
+        assert_eq!(metrics.snapshot().bytes_transferred, 4096);
+    }
+
+    /// Downloader that writes part of the file and then stalls
+    struct StallingDownloader {
+        dir: std::path::PathBuf,
+        tasks: Arc<std::sync::Mutex<Vec<tokio::task::JoinHandle<Result<()>>>>>,
+    }
+
+    impl FileDownloader for StallingDownloader {
+        fn download(
+            &self,
+            offer: FileOffer,
+            _tls_config: Option<Arc<crate::TlsConfig>>,
+            _reporter: Option<mpsc::Sender<(String, Packet)>>,
+            cancel: watch::Receiver<bool>,
+        ) {
+            let path = self.dir.join(&offer.file.filename);
+            std::fs::write(&path, [0u8; 1024]).unwrap();
+            let task = tokio::spawn(async move {
+                download_until_cancelled(std::future::pending(), &path, cancel).await
+            });
+            self.tasks.lock().unwrap().push(task);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sender_cancel_stops_download() {
+        let dir = tempfile::tempdir().unwrap();
+        let tasks = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut plugin = SharePlugin::with_downloader(Box::new(StallingDownloader {
+            dir: dir.path().to_path_buf(),
+            tasks: tasks.clone(),
+        }));
+
+        let mut device = create_test_device();
+        device.host = Some("192.168.1.50".to_string());
+        let (tx, _rx) = tokio::sync::mpsc::channel(10);
+        plugin.init(&device, tx).await.unwrap();
+
+        let packet = create_file_packet_with_payload();
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+        let partial = dir.path().join("photo.jpg");
+        assert!(partial.exists());
+
+        // Cancelling another transfer leaves this one running
+        let other = Packet::new(
+            "kdeconnect.share.request.update",
+            json!({ "cancel": true, "transferId": "1" }),
+        );
+        plugin.handle_packet(&other, &mut device).await.unwrap();
+        assert!(!tasks.lock().unwrap()[0].is_finished());
+
+        let cancel = Packet::new(
+            "kdeconnect.share.request.update",
+            json!({ "cancel": true, "transferId": packet.id }),
+        );
+        plugin.handle_packet(&cancel, &mut device).await.unwrap();
+
+        let task = tasks.lock().unwrap().pop().unwrap();
+        let result = task.await.unwrap();
+        assert!(matches!(result, Err(ProtocolError::Cancelled(_))));
+        assert!(!partial.exists());
+        assert_eq!(plugin.cancel_incoming(None).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_file_with_text_downloads_and_keeps_text() {
+        let (mut plugin, downloader, mut rx, mut device) =
+            create_policy_plugin(FileReceivePolicy::AutoAccept).await;
+        let mut packet = create_file_packet_with_payload();
+        packet.body["text"] = json!("Sunset at the beach");
+
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        let downloads = downloader.downloads.lock().unwrap().clone();
+        assert_eq!(downloads.len(), 1);
+        assert_eq!(downloads[0].file.filename, "photo.jpg");
+
+        let shares = plugin.get_all_shares().await;
+        assert_eq!(shares.len(), 1);
+        match &shares[0].content {
+            ShareContent::FileWithText { file, text } => {
+                assert_eq!(file.filename, "photo.jpg");
+                assert_eq!(text, "Sunset at the beach");
+            }
+            other => panic!("Expected file with text, got {:?}", other),
+        }
+
+        // The caption is handed to the daemon for the clipboard
+        let (device_id, text_packet) = rx.try_recv().unwrap();
+        assert_eq!(device_id, device.id());
+        assert!(text_packet.is_type(PACKET_TYPE_SHARE_TEXT));
+        assert_eq!(text_packet.body["text"], "Sunset at the beach");
+        assert_eq!(text_packet.body["deviceName"], device.name());
+    }
+
+    fn create_file_packet_with_payload() -> Packet {
+        let mut transfer_info = HashMap::new();
+        transfer_info.insert("port".to_string(), json!(1739));
+
+        Packet::new(
+            "kdeconnect.share.request",
+            json!({ "filename": "photo.jpg" }),
+        )
+        .with_payload_size(4096)
+        .with_payload_transfer_info(transfer_info)
+    }
+
+    async fn create_policy_plugin(
+        policy: FileReceivePolicy,
+    ) -> (
+        SharePlugin,
+        RecordingDownloader,
+        tokio::sync::mpsc::Receiver<(String, Packet)>,
+        Device,
+    ) {
+        let downloader = RecordingDownloader::default();
+        let mut plugin = SharePlugin::with_downloader(Box::new(downloader.clone()));
+        plugin.set_receive_policy(policy);
+
+        let mut device = create_test_device();
+        device.host = Some("192.168.1.50".to_string());
+
+        let (tx, rx) = tokio::sync::mpsc::channel(10);
+        plugin.init(&device, tx).await.unwrap();
+
+        (plugin, downloader, rx, device)
+    }
+
+    #[tokio::test]
+    async fn test_receive_policy_auto_accept() {
+        assert_eq!(
+            SharePlugin::new().receive_policy(),
+            FileReceivePolicy::AutoAccept
+        );
+
+        let (mut plugin, downloader, mut rx, mut device) =
+            create_policy_plugin(FileReceivePolicy::AutoAccept).await;
+        let packet = create_file_packet_with_payload();
+
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        let downloads = downloader.downloads.lock().unwrap().clone();
+        assert_eq!(downloads.len(), 1);
+        assert_eq!(downloads[0].file.filename, "photo.jpg");
+        assert_eq!(downloads[0].host, "192.168.1.50");
+        assert_eq!(downloads[0].port, 1739);
+        assert!(plugin.pending_offers().await.is_empty());
+        assert!(rx.try_recv().is_err());
+        assert_eq!(plugin.share_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_receive_policy_reject() {
+        let (mut plugin, downloader, mut rx, mut device) =
+            create_policy_plugin(FileReceivePolicy::Reject).await;
+        let mut packet = create_file_packet_with_payload();
+        packet.body["text"] = json!("Sunset at the beach");
+
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        assert!(downloader.downloads.lock().unwrap().is_empty());
+        assert!(plugin.pending_offers().await.is_empty());
+        assert!(rx.try_recv().is_err());
+        assert_eq!(plugin.share_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_receive_policy_prompt_then_accept() {
+        let (mut plugin, downloader, mut rx, mut device) =
+            create_policy_plugin(FileReceivePolicy::Prompt).await;
+        let packet = create_file_packet_with_payload();
+        let transfer_id = packet.id.to_string();
+
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        // Nothing is downloaded until the offer is accepted
+        assert!(downloader.downloads.lock().unwrap().is_empty());
+        assert_eq!(plugin.pending_offers().await.len(), 1);
+
+        let (device_id, offer_packet) = rx.try_recv().unwrap();
+        assert_eq!(device_id, device.id());
+        assert!(offer_packet.is_type(PACKET_TYPE_SHARE_OFFER));
+        assert_eq!(offer_packet.body["transferId"], transfer_id);
+        assert_eq!(offer_packet.body["filename"], "photo.jpg");
+        assert_eq!(offer_packet.body["size"], 4096);
+
+        assert!(plugin.accept_transfer(&transfer_id).await);
+
+        let downloads = downloader.downloads.lock().unwrap().clone();
+        assert_eq!(downloads.len(), 1);
+        assert_eq!(downloads[0].transfer_id, transfer_id);
+        assert!(plugin.pending_offers().await.is_empty());
+
+        // The offer can only be accepted once
+        assert!(!plugin.accept_transfer(&transfer_id).await);
+        assert_eq!(downloader.downloads.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_receive_policy_prompt_then_reject() {
+        let (mut plugin, downloader, _rx, mut device) =
+            create_policy_plugin(FileReceivePolicy::Prompt).await;
+        let packet = create_file_packet_with_payload();
+        let transfer_id = packet.id.to_string();
+
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        assert!(!plugin.reject_transfer("unknown").await);
+        assert!(plugin.reject_transfer(&transfer_id).await);
+        assert!(plugin.pending_offers().await.is_empty());
+        assert!(!plugin.accept_transfer(&transfer_id).await);
+        assert!(downloader.downloads.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_receive_policy_serialization() {
+        assert_eq!(
+            serde_json::to_value(FileReceivePolicy::AutoAccept).unwrap(),
+            json!("auto_accept")
+        );
+        assert_eq!(
+            serde_json::from_value::<FileReceivePolicy>(json!("prompt")).unwrap(),
+            FileReceivePolicy::Prompt
+        );
+    }
 }
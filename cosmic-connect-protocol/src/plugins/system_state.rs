@@ -0,0 +1,604 @@
+//! System State Plugin
+//!
+//! Lets the remote device toggle the desktop's Do Not Disturb mode and set
+//! the display brightness, e.g. to follow the phone's own DND schedule.
+//!
+//! ## Protocol
+//!
+//! **Packet Types**:
+//! - `cconnect.system_state.request` - State change or query (incoming)
+//! - `cconnect.system_state` - Current desktop state (outgoing)
+//!
+//! **Capabilities**:
+//! - Incoming: `cconnect.system_state.request`
+//! - Outgoing: `cconnect.system_state`
+//!
+//! ## Opt-In
+//!
+//! Changes are only applied for devices whose [`SystemStateSettings`] allow
+//! it, which is off by default. Requests from other devices are answered
+//! with the current state without touching anything.
+//!
+//! ## Validation
+//!
+//! Brightness must be within 0-100. A request with an out-of-range value is
+//! rejected as a whole, so a DND change in the same request is not applied
+//! either. The current state is reported after every request.
+//!
+//! ## Packet Format
+//!
+//! **Request (incoming)**:
+//! ```json
+//! {
+//!     "type": "cconnect.system_state.request",
+//!     "body": {
+//!         "dnd": true,
+//!         "brightness": 40
+//!     }
+//! }
+//! ```
+//!
+//! Both fields are optional; an empty body only queries the state.
+//!
+//! **State (outgoing)**:
+//! ```json
+//! {
+//!     "type": "cconnect.system_state",
+//!     "body": {
+//!         "dnd": true,
+//!         "brightness": 40
+//!     }
+//! }
+//! ```
+//!
+//! `brightness` is omitted when the desktop has no controllable backlight.
+
+use crate::{Device, Packet, ProtocolError, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::path::PathBuf;
+use std::process::Command;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+use super::settings::PluginSettings;
+use super::{Plugin, PluginFactory};
+
+/// Packet type for system state requests (incoming)
+pub const PACKET_TYPE_SYSTEM_STATE_REQUEST: &str = "cconnect.system_state.request";
+
+/// Packet type for system state reports (outgoing)
+pub const PACKET_TYPE_SYSTEM_STATE: &str = "cconnect.system_state";
+
+/// Highest brightness value accepted from the remote device
+pub const MAX_BRIGHTNESS: i64 = 100;
+
+/// System state request body (incoming)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SystemStateRequest {
+    /// Enable or disable Do Not Disturb
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dnd: Option<bool>,
+    /// Display brightness in percent (0-100)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub brightness: Option<i64>,
+}
+
+impl SystemStateRequest {
+    /// Parse and validate a request packet
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProtocolError::InvalidPacket`] if the body is malformed or
+    /// the brightness is outside 0-100.
+    pub fn from_packet(packet: &Packet) -> Result<Self> {
+        let request: Self = serde_json::from_value(packet.body.clone()).map_err(|e| {
+            ProtocolError::InvalidPacket(format!("Failed to parse system state request: {}", e))
+        })?;
+
+        if let Some(brightness) = request.brightness {
+            if !(0..=MAX_BRIGHTNESS).contains(&brightness) {
+                return Err(ProtocolError::InvalidPacket(format!(
+                    "Brightness {} out of range 0-{}",
+                    brightness, MAX_BRIGHTNESS
+                )));
+            }
+        }
+
+        Ok(request)
+    }
+
+    /// Whether the request asks for any change
+    pub fn has_changes(&self) -> bool {
+        self.dnd.is_some() || self.brightness.is_some()
+    }
+}
+
+/// Current desktop state (outgoing)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SystemState {
+    /// Whether Do Not Disturb is on
+    pub dnd: bool,
+    /// Display brightness in percent, `None` without a backlight
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub brightness: Option<u8>,
+}
+
+impl SystemState {
+    /// Create a state report packet
+    pub fn to_packet(&self) -> Result<Packet> {
+        Ok(Packet::new(
+            PACKET_TYPE_SYSTEM_STATE,
+            serde_json::to_value(self)?,
+        ))
+    }
+}
+
+/// System state plugin settings, stored per device
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SystemStateSettings {
+    /// Apply DND and brightness changes requested by the device
+    #[serde(default)]
+    pub allow_changes: bool,
+}
+
+/// Desktop state control used by the plugin
+pub trait SystemStateControl: Send + Sync {
+    /// Read whether Do Not Disturb is on
+    fn do_not_disturb(&self) -> bool;
+
+    /// Turn Do Not Disturb on or off
+    fn set_do_not_disturb(&self, enabled: bool) -> Result<()>;
+
+    /// Read the display brightness in percent, `None` without a backlight
+    fn brightness(&self) -> Option<u8>;
+
+    /// Set the display brightness in percent (0-100)
+    fn set_brightness(&self, percent: u8) -> Result<()>;
+
+    /// Read the current state
+    fn state(&self) -> SystemState {
+        SystemState {
+            dnd: self.do_not_disturb(),
+            brightness: self.brightness(),
+        }
+    }
+}
+
+/// Desktop backend using the COSMIC notifications config and brightnessctl
+///
+/// Do Not Disturb is the COSMIC notification daemon's own setting, which it
+/// watches for changes. Brightness goes through `brightnessctl`, which uses
+/// logind so no extra privileges are needed.
+#[derive(Debug, Default)]
+pub struct DesktopStateBackend;
+
+impl DesktopStateBackend {
+    /// Path of the COSMIC notifications Do Not Disturb setting
+    fn dnd_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| {
+            dir.join("cosmic")
+                .join("com.system76.CosmicNotifications")
+                .join("v1")
+                .join("do_not_disturb")
+        })
+    }
+
+    /// Parse `brightnessctl -m info` output into a percentage
+    ///
+    /// Output format: `device,class,current,percent%,max`
+    fn parse_brightness(output: &str) -> Option<u8> {
+        output
+            .lines()
+            .next()?
+            .split(',')
+            .nth(3)?
+            .trim_end_matches('%')
+            .parse()
+            .ok()
+    }
+}
+
+impl SystemStateControl for DesktopStateBackend {
+    fn do_not_disturb(&self) -> bool {
+        Self::dnd_path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|value| value.trim() == "true")
+            .unwrap_or(false)
+    }
+
+    fn set_do_not_disturb(&self, enabled: bool) -> Result<()> {
+        let path = Self::dnd_path().ok_or_else(|| {
+            ProtocolError::Plugin("No config directory for Do Not Disturb".to_string())
+        })?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&path, if enabled { "true" } else { "false" })?;
+        Ok(())
+    }
+
+    fn brightness(&self) -> Option<u8> {
+        let output = Command::new("brightnessctl")
+            .args(["-m", "-c", "backlight", "info"])
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        Self::parse_brightness(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    fn set_brightness(&self, percent: u8) -> Result<()> {
+        let output = Command::new("brightnessctl")
+            .args(["-c", "backlight", "set", &format!("{}%", percent.min(100))])
+            .output()
+            .map_err(|e| ProtocolError::Plugin(format!("Failed to run brightnessctl: {}", e)))?;
+
+        if output.status.success() {
+            return Ok(());
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = match stderr.trim() {
+            "" => output.status.to_string(),
+            stderr => stderr.to_string(),
+        };
+        Err(ProtocolError::Plugin(format!(
+            "brightnessctl failed: {}",
+            reason
+        )))
+    }
+}
+
+/// System State plugin
+///
+/// Applies DND and brightness requests from opted-in devices and reports the
+/// desktop state back.
+///
+/// ## Example
+///
+/// ```rust
+/// use cosmic_connect_core::plugins::system_state::SystemStatePlugin;
+/// use cosmic_connect_core::Plugin;
+///
+/// let plugin = SystemStatePlugin::new();
+/// assert_eq!(plugin.name(), "system_state");
+/// assert!(!plugin.allows_changes());
+/// ```
+pub struct SystemStatePlugin {
+    device_id: Option<String>,
+    packet_sender: Option<mpsc::Sender<(String, Packet)>>,
+    /// Per-device opt-in
+    settings: SystemStateSettings,
+    /// Desktop state controlled by the remote device
+    backend: Box<dyn SystemStateControl>,
+}
+
+impl SystemStatePlugin {
+    /// Create a new System State plugin
+    pub fn new() -> Self {
+        Self::with_backend(Box::new(DesktopStateBackend))
+    }
+
+    /// Create a System State plugin controlling a custom backend
+    pub fn with_backend(backend: Box<dyn SystemStateControl>) -> Self {
+        Self {
+            device_id: None,
+            packet_sender: None,
+            settings: SystemStateSettings::default(),
+            backend,
+        }
+    }
+
+    /// Whether the device may change the desktop state
+    pub fn allows_changes(&self) -> bool {
+        self.settings.allow_changes
+    }
+
+    /// Read the desktop state and send it to the remote device
+    async fn send_state(&self) -> Result<()> {
+        let state = self.backend.state();
+        debug!("Sending system state: {:?}", state);
+
+        if let (Some(sender), Some(device_id)) = (&self.packet_sender, &self.device_id) {
+            sender
+                .send((device_id.clone(), state.to_packet()?))
+                .await
+                .map_err(|e| ProtocolError::Transport(format!("Failed to send packet: {}", e)))?;
+        }
+
+        Ok(())
+    }
+
+    /// Handle a state request from the remote device
+    async fn handle_request(&mut self, packet: &Packet) -> Result<()> {
+        let request = match SystemStateRequest::from_packet(packet) {
+            Ok(request) => request,
+            Err(e) => {
+                self.send_state().await?;
+                return Err(e);
+            }
+        };
+
+        if request.has_changes() && !self.settings.allow_changes {
+            warn!(
+                "Ignoring system state change from {:?}: not allowed for this device",
+                self.device_id
+            );
+            return self.send_state().await;
+        }
+
+        let mut result = Ok(());
+
+        if let Some(dnd) = request.dnd {
+            info!("Setting Do Not Disturb to {}", dnd);
+            if let Err(e) = self.backend.set_do_not_disturb(dnd) {
+                warn!("Failed to set Do Not Disturb: {}", e);
+                result = Err(e);
+            }
+        }
+
+        if let Some(brightness) = request.brightness {
+            info!("Setting brightness to {}%", brightness);
+            // Validated to 0-100 when parsing
+            if let Err(e) = self.backend.set_brightness(brightness as u8) {
+                warn!("Failed to set brightness: {}", e);
+                result = result.and(Err(e));
+            }
+        }
+
+        self.send_state().await?;
+
+        result
+    }
+}
+
+impl Default for SystemStatePlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Plugin for SystemStatePlugin {
+    fn name(&self) -> &str {
+        "system_state"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn incoming_capabilities(&self) -> Vec<String> {
+        vec![PACKET_TYPE_SYSTEM_STATE_REQUEST.to_string()]
+    }
+
+    fn outgoing_capabilities(&self) -> Vec<String> {
+        vec![PACKET_TYPE_SYSTEM_STATE.to_string()]
+    }
+
+    async fn init(
+        &mut self,
+        device: &Device,
+        packet_sender: mpsc::Sender<(String, Packet)>,
+    ) -> Result<()> {
+        self.device_id = Some(device.id().to_string());
+        self.packet_sender = Some(packet_sender);
+        Ok(())
+    }
+
+    async fn start(&mut self) -> Result<()> {
+        info!(
+            "SystemState plugin started (changes {})",
+            if self.settings.allow_changes {
+                "allowed"
+            } else {
+                "not allowed"
+            }
+        );
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        info!("SystemState plugin stopped");
+        Ok(())
+    }
+
+    async fn handle_packet(&mut self, packet: &Packet, _device: &mut Device) -> Result<()> {
+        if packet.is_type(PACKET_TYPE_SYSTEM_STATE_REQUEST) {
+            self.handle_request(packet).await
+        } else {
+            Ok(())
+        }
+    }
+
+    fn set_settings(&mut self, settings: PluginSettings) {
+        if let Some(system_state) = settings.get::<SystemStateSettings>() {
+            self.settings = system_state;
+        }
+    }
+}
+
+/// Factory for creating SystemStatePlugin instances
+pub struct SystemStatePluginFactory;
+
+impl PluginFactory for SystemStatePluginFactory {
+    fn name(&self) -> &str {
+        "system_state"
+    }
+
+    fn incoming_capabilities(&self) -> Vec<String> {
+        vec![PACKET_TYPE_SYSTEM_STATE_REQUEST.to_string()]
+    }
+
+    fn outgoing_capabilities(&self) -> Vec<String> {
+        vec![PACKET_TYPE_SYSTEM_STATE.to_string()]
+    }
+
+    fn create(&self) -> Box<dyn Plugin> {
+        Box::new(SystemStatePlugin::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::settings::{MemorySettingsStore, PluginSettingsStore};
+    use crate::{DeviceInfo, DeviceType};
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    fn initial_state() -> SystemState {
+        SystemState {
+            dnd: false,
+            brightness: Some(80),
+        }
+    }
+
+    #[derive(Clone)]
+    struct MockStateBackend {
+        state: Arc<Mutex<SystemState>>,
+    }
+
+    impl MockStateBackend {
+        fn new() -> Self {
+            Self {
+                state: Arc::new(Mutex::new(initial_state())),
+            }
+        }
+    }
+
+    impl SystemStateControl for MockStateBackend {
+        fn do_not_disturb(&self) -> bool {
+            self.state.lock().unwrap().dnd
+        }
+
+        fn set_do_not_disturb(&self, enabled: bool) -> Result<()> {
+            self.state.lock().unwrap().dnd = enabled;
+            Ok(())
+        }
+
+        fn brightness(&self) -> Option<u8> {
+            self.state.lock().unwrap().brightness
+        }
+
+        fn set_brightness(&self, percent: u8) -> Result<()> {
+            self.state.lock().unwrap().brightness = Some(percent);
+            Ok(())
+        }
+    }
+
+    fn create_test_device() -> Device {
+        let info = DeviceInfo::new("Test Device", DeviceType::Phone, 1716);
+        Device::from_discovery(info)
+    }
+
+    fn request(body: serde_json::Value) -> Packet {
+        Packet::new(PACKET_TYPE_SYSTEM_STATE_REQUEST, body)
+    }
+
+    #[test]
+    fn test_request_parsing_and_validation() {
+        let parsed = SystemStateRequest::from_packet(&request(json!({"dnd": true}))).unwrap();
+        assert_eq!(parsed.dnd, Some(true));
+        assert_eq!(parsed.brightness, None);
+
+        let parsed = SystemStateRequest::from_packet(&request(json!({"brightness": 0}))).unwrap();
+        assert_eq!(parsed.brightness, Some(0));
+        let parsed = SystemStateRequest::from_packet(&request(json!({"brightness": 100}))).unwrap();
+        assert_eq!(parsed.brightness, Some(100));
+
+        let query = SystemStateRequest::from_packet(&request(json!({}))).unwrap();
+        assert!(!query.has_changes());
+
+        for body in [
+            json!({"brightness": 101}),
+            json!({"brightness": -1}),
+            json!({"brightness": "50"}),
+            json!({"dnd": "yes"}),
+        ] {
+            assert!(
+                matches!(
+                    SystemStateRequest::from_packet(&request(body.clone())),
+                    Err(ProtocolError::InvalidPacket(_))
+                ),
+                "{} should be rejected",
+                body
+            );
+        }
+    }
+
+    #[test]
+    fn test_state_report_serialization() {
+        let packet = SystemState {
+            dnd: true,
+            brightness: Some(40),
+        }
+        .to_packet()
+        .unwrap();
+        assert_eq!(packet.packet_type, PACKET_TYPE_SYSTEM_STATE);
+        assert_eq!(packet.body, json!({"dnd": true, "brightness": 40}));
+
+        let packet = SystemState {
+            dnd: false,
+            brightness: None,
+        }
+        .to_packet()
+        .unwrap();
+        assert_eq!(packet.body, json!({"dnd": false}));
+    }
+
+    #[tokio::test]
+    async fn test_changes_require_opt_in() {
+        let mut device = create_test_device();
+        let backend = MockStateBackend::new();
+        let (tx, mut rx) = mpsc::channel(10);
+
+        let mut plugin = SystemStatePlugin::with_backend(Box::new(backend.clone()));
+        plugin.init(&device, tx).await.unwrap();
+
+        let packet = request(json!({"dnd": true, "brightness": 30}));
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+        assert_eq!(*backend.state.lock().unwrap(), initial_state());
+        let (_, report) = rx.try_recv().unwrap();
+        assert_eq!(report.body, json!({"dnd": false, "brightness": 80}));
+
+        let store: Arc<dyn PluginSettingsStore> = Arc::new(MemorySettingsStore::new());
+        let mut settings = PluginSettings::load(store, device.id(), "system_state").await;
+        settings
+            .save_config(&SystemStateSettings {
+                allow_changes: true,
+            })
+            .await
+            .unwrap();
+        plugin.set_settings(settings);
+
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+        let (_, report) = rx.try_recv().unwrap();
+        assert_eq!(report.body, json!({"dnd": true, "brightness": 30}));
+
+        // Out-of-range values change nothing but still report the state
+        let packet = request(json!({"dnd": false, "brightness": 150}));
+        assert!(plugin.handle_packet(&packet, &mut device).await.is_err());
+        assert!(backend.state.lock().unwrap().dnd);
+        let (_, report) = rx.try_recv().unwrap();
+        assert_eq!(report.body, json!({"dnd": true, "brightness": 30}));
+    }
+
+    #[test]
+    fn test_parse_brightnessctl_output() {
+        assert_eq!(
+            DesktopStateBackend::parse_brightness("intel_backlight,backlight,9600,50%,19200\n"),
+            Some(50)
+        );
+        assert_eq!(DesktopStateBackend::parse_brightness(""), None);
+    }
+}
@@ -6,12 +6,35 @@
 //!
 //! **Packet Types**:
 //! - `cconnect.systemvolume.request` - Volume control request (incoming)
-//! - `cconnect.systemvolume` - Sink list update (outgoing)
+//! - `cconnect.systemvolume` - Sink list update (bidirectional)
 //!
 //! **Capabilities**:
-//! - Incoming: `cconnect.systemvolume.request`
+//! - Incoming: `cconnect.systemvolume.request`, `cconnect.systemvolume`
 //! - Outgoing: `cconnect.systemvolume`
 //!
+//! ## Remote Volume OSD
+//!
+//! Sink lists reported by the remote device are cached and fed to a
+//! [`VolumeOsd`], which decides when an on-screen display of the remote
+//! volume should be shown: whenever a sink's level or mute state changes,
+//! hiding again after [`VOLUME_OSD_TIMEOUT`] without further changes. Each
+//! change is handed out once by [`SystemVolumePlugin::take_volume_osd`], so
+//! a sink list repeating the same levels doesn't show the display again.
+//!
+//! ## Sink Requests
+//!
+//! A `requestSinks` request always enumerates the audio backend again, so
+//! the phone sees sinks plugged in or changed since the last list. The local
+//! cache is refreshed from that enumeration and only used to resolve sink
+//! names to backend IDs.
+//!
+//! ## Failed Changes
+//!
+//! The sink list is sent back after every volume request, including ones the
+//! audio backend failed to apply, so the remote slider snaps back to the real
+//! value. The failure is then returned as an error with the backend's
+//! message.
+//!
 //! ## Packet Format
 //!
 //! **Request (incoming)**:
@@ -53,10 +76,11 @@ use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, RwLock};
 use tracing::{debug, info, warn};
 
-use super::audio_backend::{AudioBackend, AudioSink};
+use super::audio_backend::{AudioBackend, AudioSink, VolumeControl};
 use super::{Plugin, PluginFactory};
 
 /// Packet type for system volume requests (incoming)
@@ -120,6 +144,116 @@ pub struct SinkListResponse {
     pub sink_list: Vec<SinkInfo>,
 }
 
+/// How long the remote volume OSD stays visible after the last change
+pub const VOLUME_OSD_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Remote volume level shown in the on-screen display
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VolumeOsdState {
+    /// Human-readable sink description
+    pub sink: String,
+    /// Current volume (0-100+)
+    pub volume: i32,
+    /// Maximum volume of the sink
+    pub max_volume: i32,
+    /// Whether the sink is muted
+    pub muted: bool,
+}
+
+impl From<&SinkInfo> for VolumeOsdState {
+    fn from(sink: &SinkInfo) -> Self {
+        Self {
+            sink: sink.description.clone(),
+            volume: sink.volume,
+            max_volume: sink.max_volume,
+            muted: sink.muted,
+        }
+    }
+}
+
+/// Show/hide logic for the remote volume OSD
+///
+/// The first sink list only establishes a baseline. Afterwards, any change of
+/// volume or mute state on a known sink shows the OSD, which hides itself once
+/// the timeout elapses without further changes.
+#[derive(Debug, Clone)]
+pub struct VolumeOsd {
+    /// How long the OSD stays visible after a change
+    timeout: Duration,
+    /// Last known (volume, muted) per sink name
+    levels: HashMap<String, (i32, bool)>,
+    /// State currently displayed
+    current: Option<VolumeOsdState>,
+    /// When the OSD was last shown
+    shown_at: Option<Instant>,
+}
+
+impl VolumeOsd {
+    /// Create OSD logic with the given auto-hide timeout
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            levels: HashMap::new(),
+            current: None,
+            shown_at: None,
+        }
+    }
+
+    /// Process a sink list from the remote device
+    ///
+    /// Returns the state to show if any known sink changed. When several sinks
+    /// changed, the active sink wins.
+    pub fn update(&mut self, sinks: &[SinkInfo], now: Instant) -> Option<VolumeOsdState> {
+        let baseline = self.levels.is_empty();
+        let mut changed: Option<&SinkInfo> = None;
+
+        for sink in sinks {
+            let level = (sink.volume, sink.muted);
+            let previous = self.levels.insert(sink.name.clone(), level);
+
+            let is_change = !baseline && previous.is_some_and(|prev| prev != level);
+            let preferred = match changed {
+                Some(current) => sink.enabled && !current.enabled,
+                None => true,
+            };
+            if is_change && preferred {
+                changed = Some(sink);
+            }
+        }
+
+        let state = changed.map(VolumeOsdState::from)?;
+        self.current = Some(state.clone());
+        self.shown_at = Some(now);
+        Some(state)
+    }
+
+    /// Check whether the OSD should currently be visible
+    pub fn is_visible(&self, now: Instant) -> bool {
+        self.shown_at
+            .is_some_and(|shown| now.saturating_duration_since(shown) < self.timeout)
+    }
+
+    /// Get the state to display, if the OSD is visible
+    pub fn visible_state(&self, now: Instant) -> Option<&VolumeOsdState> {
+        if self.is_visible(now) {
+            self.current.as_ref()
+        } else {
+            None
+        }
+    }
+
+    /// Auto-hide timeout
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+impl Default for VolumeOsd {
+    fn default() -> Self {
+        Self::new(VOLUME_OSD_TIMEOUT)
+    }
+}
+
 /// System Volume plugin
 ///
 /// Provides remote control of system volume and audio sinks.
@@ -148,6 +282,14 @@ pub struct SystemVolumePlugin {
     sinks: Arc<RwLock<HashMap<String, SinkInfo>>>,
     /// Mapping from protocol name to PipeWire sink ID
     sink_id_map: Arc<RwLock<HashMap<String, u32>>>,
+    /// Sinks last reported by the remote device
+    remote_sinks: Vec<SinkInfo>,
+    /// On-screen display logic for remote volume changes
+    osd: VolumeOsd,
+    /// Remote volume change not shown yet
+    pending_osd: Option<VolumeOsdState>,
+    /// Local audio sinks controlled by the remote device
+    backend: Box<dyn VolumeControl>,
 }
 
 impl SystemVolumePlugin {
@@ -162,14 +304,38 @@ impl SystemVolumePlugin {
     /// assert_eq!(plugin.sink_count(), 0);
     /// ```
     pub fn new() -> Self {
+        Self::with_backend(Box::new(AudioBackend))
+    }
+
+    /// Create a System Volume plugin controlling sinks through a custom backend
+    pub fn with_backend(backend: Box<dyn VolumeControl>) -> Self {
         Self {
             device_id: None,
             packet_sender: None,
             sinks: Arc::new(RwLock::new(HashMap::new())),
             sink_id_map: Arc::new(RwLock::new(HashMap::new())),
+            remote_sinks: Vec::new(),
+            osd: VolumeOsd::default(),
+            pending_osd: None,
+            backend,
         }
     }
 
+    /// Get the sinks last reported by the remote device
+    pub fn get_remote_sinks(&self) -> &[SinkInfo] {
+        &self.remote_sinks
+    }
+
+    /// Get the remote volume OSD state, if it should currently be shown
+    pub fn volume_osd_state(&self) -> Option<VolumeOsdState> {
+        self.osd.visible_state(Instant::now()).cloned()
+    }
+
+    /// Take the last remote volume change, to show it on-screen once
+    pub fn take_volume_osd(&mut self) -> Option<VolumeOsdState> {
+        self.pending_osd.take()
+    }
+
     /// Get all cached audio sinks
     ///
     /// Returns a copy of all known sinks from the last update.
@@ -326,9 +492,11 @@ impl SystemVolumePlugin {
             .and_then(|guard| guard.get(name).copied())
     }
 
-    /// Send sink list to remote device
+    /// Enumerate the backend's sinks and send them to the remote device
+    ///
+    /// Never answers from the cache, which is refreshed from the result.
     async fn send_sink_list(&mut self) -> Result<()> {
-        let sinks = AudioBackend::list_sinks();
+        let sinks = self.backend.list_sinks();
 
         // Build ID map and sink info list
         let id_map: HashMap<String, u32> = sinks.iter().map(|s| (s.id.to_string(), s.id)).collect();
@@ -377,10 +545,10 @@ impl SystemVolumePlugin {
         let sink_id = if let Some(name) = &request.name {
             // Use cached ID lookup
             self.get_sink_id(name)
-                .or_else(|| AudioBackend::find_sink_by_name(name).map(|s| s.id))
+                .or_else(|| self.backend.find_sink_by_name(name).map(|s| s.id))
         } else {
             // Use default sink if no name specified
-            AudioBackend::get_default_sink_id()
+            self.backend.default_sink_id()
         };
 
         let Some(sink_id) = sink_id else {
@@ -388,25 +556,53 @@ impl SystemVolumePlugin {
             return Ok(());
         };
 
+        let mut result = Ok(());
+
         // Apply volume change
         if let Some(volume) = request.volume {
             info!("Setting volume to {}% for sink {}", volume, sink_id);
-            if !AudioBackend::set_volume(sink_id, volume) {
-                warn!("Failed to set volume for sink {}", sink_id);
+            if let Err(e) = self.backend.set_volume(sink_id, volume) {
+                warn!("Failed to set volume for sink {}: {}", sink_id, e);
+                result = Err(e);
             }
         }
 
         // Apply mute change
         if let Some(muted) = request.muted {
             info!("Setting mute to {} for sink {}", muted, sink_id);
-            if !AudioBackend::set_mute(sink_id, muted) {
-                warn!("Failed to set mute for sink {}", sink_id);
+            if let Err(e) = self.backend.set_mute(sink_id, muted) {
+                warn!("Failed to set mute for sink {}: {}", sink_id, e);
+                result = result.and(Err(e));
             }
         }
 
-        // Send updated sink list after changes
+        // Send the real sink state, also after a failed change so the
+        // remote's controls snap back
         self.send_sink_list().await?;
 
+        result
+    }
+
+    /// Handle sink list reported by the remote device
+    fn handle_remote_sink_list(&mut self, packet: &Packet) -> Result<()> {
+        let response: SinkListResponse =
+            serde_json::from_value(packet.body.clone()).map_err(|e| {
+                crate::ProtocolError::InvalidPacket(format!("Failed to parse sink list: {}", e))
+            })?;
+
+        debug!("Remote device reported {} sinks", response.sink_list.len());
+
+        if let Some(state) = self.osd.update(&response.sink_list, Instant::now()) {
+            debug!(
+                "Remote volume changed: {} {}%{}",
+                state.sink,
+                state.volume,
+                if state.muted { " (muted)" } else { "" }
+            );
+            self.pending_osd = Some(state);
+        }
+        self.remote_sinks = response.sink_list;
+
         Ok(())
     }
 
@@ -503,6 +699,8 @@ impl Plugin for SystemVolumePlugin {
         vec![
             PACKET_TYPE_SYSTEMVOLUME_REQUEST.to_string(),
             "kdeconnect.systemvolume.request".to_string(),
+            PACKET_TYPE_SYSTEMVOLUME.to_string(),
+            "kdeconnect.systemvolume".to_string(),
         ]
     }
 
@@ -552,6 +750,8 @@ impl Plugin for SystemVolumePlugin {
             || packet.is_type("kdeconnect.systemvolume.request")
         {
             self.handle_volume_request(packet).await
+        } else if packet.is_type(PACKET_TYPE_SYSTEMVOLUME) {
+            self.handle_remote_sink_list(packet)
         } else {
             Ok(())
         }
@@ -570,6 +770,8 @@ impl PluginFactory for SystemVolumePluginFactory {
         vec![
             PACKET_TYPE_SYSTEMVOLUME_REQUEST.to_string(),
             "kdeconnect.systemvolume.request".to_string(),
+            PACKET_TYPE_SYSTEMVOLUME.to_string(),
+            "kdeconnect.systemvolume".to_string(),
         ]
     }
 
@@ -673,9 +875,11 @@ mod tests {
         let plugin = SystemVolumePlugin::new();
 
         let incoming = plugin.incoming_capabilities();
-        assert_eq!(incoming.len(), 2);
+        assert_eq!(incoming.len(), 4);
         assert!(incoming.contains(&PACKET_TYPE_SYSTEMVOLUME_REQUEST.to_string()));
         assert!(incoming.contains(&"kdeconnect.systemvolume.request".to_string()));
+        assert!(incoming.contains(&PACKET_TYPE_SYSTEMVOLUME.to_string()));
+        assert!(incoming.contains(&"kdeconnect.systemvolume".to_string()));
 
         let outgoing = plugin.outgoing_capabilities();
         assert_eq!(outgoing.len(), 2);
@@ -862,4 +1066,256 @@ mod tests {
         assert_eq!(sink_list[0]["name"], "50");
         assert_eq!(sink_list[0]["volume"], 75);
     }
+
+    fn remote_sink(name: &str, volume: i32, muted: bool, enabled: bool) -> SinkInfo {
+        SinkInfo {
+            name: name.to_string(),
+            description: format!("Sink {}", name),
+            volume,
+            muted,
+            max_volume: 100,
+            enabled,
+        }
+    }
+
+    #[test]
+    fn test_volume_osd_baseline_does_not_show() {
+        let mut osd = VolumeOsd::default();
+        let now = Instant::now();
+
+        assert!(osd
+            .update(&[remote_sink("media", 50, false, true)], now)
+            .is_none());
+        assert!(!osd.is_visible(now));
+    }
+
+    #[test]
+    fn test_volume_osd_shows_on_change() {
+        let mut osd = VolumeOsd::default();
+        let now = Instant::now();
+        osd.update(&[remote_sink("media", 50, false, true)], now);
+
+        // Unchanged list keeps the OSD hidden
+        assert!(osd
+            .update(&[remote_sink("media", 50, false, true)], now)
+            .is_none());
+
+        let state = osd
+            .update(&[remote_sink("media", 70, false, true)], now)
+            .unwrap();
+        assert_eq!(state.volume, 70);
+        assert_eq!(state.sink, "Sink media");
+        assert!(osd.is_visible(now));
+        assert_eq!(osd.visible_state(now), Some(&state));
+
+        // Mute toggles count as a change too
+        let state = osd
+            .update(&[remote_sink("media", 70, true, true)], now)
+            .unwrap();
+        assert!(state.muted);
+    }
+
+    #[test]
+    fn test_volume_osd_auto_hides_after_timeout() {
+        let timeout = Duration::from_millis(500);
+        let mut osd = VolumeOsd::new(timeout);
+        let start = Instant::now();
+        osd.update(&[remote_sink("media", 50, false, true)], start);
+        osd.update(&[remote_sink("media", 60, false, true)], start);
+
+        assert!(osd.is_visible(start + Duration::from_millis(499)));
+        assert!(!osd.is_visible(start + timeout));
+        assert!(osd.visible_state(start + timeout).is_none());
+
+        // A new change restarts the timeout
+        let later = start + Duration::from_secs(1);
+        osd.update(&[remote_sink("media", 65, false, true)], later);
+        assert!(osd.is_visible(later + Duration::from_millis(499)));
+    }
+
+    #[test]
+    fn test_volume_osd_prefers_active_sink() {
+        let mut osd = VolumeOsd::default();
+        let now = Instant::now();
+        osd.update(
+            &[
+                remote_sink("ring", 30, false, false),
+                remote_sink("media", 50, false, true),
+            ],
+            now,
+        );
+
+        let state = osd
+            .update(
+                &[
+                    remote_sink("ring", 40, false, false),
+                    remote_sink("media", 55, false, true),
+                ],
+                now,
+            )
+            .unwrap();
+        assert_eq!(state.sink, "Sink media");
+
+        // Newly appearing sinks are not a volume change
+        assert!(osd
+            .update(
+                &[
+                    remote_sink("ring", 40, false, false),
+                    remote_sink("media", 55, false, true),
+                    remote_sink("alarm", 80, false, false),
+                ],
+                now,
+            )
+            .is_none());
+    }
+
+    /// Backend with one sink whose changes can be made to fail
+    #[derive(Clone)]
+    struct MockVolumeBackend {
+        sink: Arc<std::sync::Mutex<AudioSink>>,
+        enumerations: Arc<std::sync::atomic::AtomicUsize>,
+        fail: bool,
+    }
+
+    impl MockVolumeBackend {
+        fn new(volume: i32, fail: bool) -> Self {
+            Self {
+                sink: Arc::new(std::sync::Mutex::new(AudioSink {
+                    id: 50,
+                    name: "Speakers".to_string(),
+                    volume,
+                    muted: false,
+                    is_default: true,
+                    max_volume: 150,
+                })),
+                enumerations: Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+                fail,
+            }
+        }
+    }
+
+    impl VolumeControl for MockVolumeBackend {
+        fn list_sinks(&self) -> Vec<AudioSink> {
+            self.enumerations
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            vec![self.sink.lock().unwrap().clone()]
+        }
+
+        fn set_volume(&self, _id: u32, volume: i32) -> Result<()> {
+            if self.fail {
+                return Err(crate::ProtocolError::Plugin(
+                    "wpctl set-volume 50 80% failed: Object not found".to_string(),
+                ));
+            }
+            self.sink.lock().unwrap().volume = volume;
+            Ok(())
+        }
+
+        fn set_mute(&self, _id: u32, muted: bool) -> Result<()> {
+            self.sink.lock().unwrap().muted = muted;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_set_resends_real_state() {
+        let backend = MockVolumeBackend::new(40, true);
+        let mut plugin = SystemVolumePlugin::with_backend(Box::new(backend));
+        let mut device = create_test_device();
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        plugin.init(&device, sender).await.unwrap();
+
+        let request = Packet::new(
+            "kdeconnect.systemvolume.request",
+            serde_json::json!({ "name": "50", "volume": 80 }),
+        );
+        let error = plugin
+            .handle_packet(&request, &mut device)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("Object not found"));
+
+        // The phone gets the volume that is really set
+        let (_, packet) = receiver.try_recv().unwrap();
+        assert!(packet.is_type(PACKET_TYPE_SYSTEMVOLUME));
+        let response: SinkListResponse = serde_json::from_value(packet.body).unwrap();
+        assert_eq!(response.sink_list[0].volume, 40);
+    }
+
+    #[tokio::test]
+    async fn test_request_sinks_enumerates_backend_fresh() {
+        let backend = MockVolumeBackend::new(40, false);
+        let mut plugin = SystemVolumePlugin::with_backend(Box::new(backend.clone()));
+        let mut device = create_test_device();
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        plugin.init(&device, sender).await.unwrap();
+
+        let request = Packet::new(
+            "kdeconnect.systemvolume.request",
+            serde_json::json!({ "requestSinks": true }),
+        );
+        plugin.handle_packet(&request, &mut device).await.unwrap();
+        let (_, packet) = receiver.try_recv().unwrap();
+        let response: SinkListResponse = serde_json::from_value(packet.body).unwrap();
+        assert_eq!(response.sink_list[0].volume, 40);
+
+        // Volume changed locally, outside of the plugin
+        backend.sink.lock().unwrap().volume = 65;
+        let before = backend
+            .enumerations
+            .load(std::sync::atomic::Ordering::SeqCst);
+
+        plugin.handle_packet(&request, &mut device).await.unwrap();
+        let (_, packet) = receiver.try_recv().unwrap();
+        let response: SinkListResponse = serde_json::from_value(packet.body).unwrap();
+        assert_eq!(response.sink_list[0].volume, 65);
+        assert_eq!(
+            backend
+                .enumerations
+                .load(std::sync::atomic::Ordering::SeqCst),
+            before + 1
+        );
+
+        // The cache is refreshed from the enumeration
+        assert_eq!(plugin.get_sink("50").unwrap().volume, 65);
+    }
+
+    #[tokio::test]
+    async fn test_handle_remote_sink_list() {
+        let mut plugin = SystemVolumePlugin::new();
+        let mut device = create_test_device();
+
+        let packet = |volume: i32| {
+            Packet::new(
+                "kdeconnect.systemvolume",
+                serde_json::json!({
+                    "sinkList": [{
+                        "name": "media",
+                        "description": "Media",
+                        "volume": volume,
+                        "muted": false,
+                        "maxVolume": 15,
+                        "enabled": true
+                    }]
+                }),
+            )
+        };
+
+        plugin.handle_packet(&packet(5), &mut device).await.unwrap();
+        assert_eq!(plugin.get_remote_sinks().len(), 1);
+        assert!(plugin.volume_osd_state().is_none());
+        assert!(plugin.take_volume_osd().is_none());
+
+        plugin.handle_packet(&packet(9), &mut device).await.unwrap();
+        let state = plugin.volume_osd_state().unwrap();
+        assert_eq!(state.volume, 9);
+        assert_eq!(state.max_volume, 15);
+        assert_eq!(plugin.take_volume_osd(), Some(state));
+        assert!(plugin.take_volume_osd().is_none());
+
+        // An unchanged list leaves the display visible but doesn't show it again
+        plugin.handle_packet(&packet(9), &mut device).await.unwrap();
+        assert!(plugin.volume_osd_state().is_some());
+        assert!(plugin.take_volume_osd().is_none());
+    }
 }
@@ -26,6 +26,15 @@
 //! - `phoneNumber`: Caller's phone number
 //! - `contactName`: Contact name from phone's address book (optional)
 //! - `messageBody`: SMS body (deprecated, use SMS plugin instead)
+//! - `isCancel`: Set when the ringing stopped without the call being answered
+//!
+//! With a notifier set, ringing raises an "Incoming call" notification that is
+//! closed again once the call is answered, missed, or cancelled. Missed calls
+//! raise their own notification.
+//!
+//! The incoming call notification carries a "Mute call" action that silences
+//! the phone's ringer through [`TelephonyPlugin::mute_call`]. Mute requests are
+//! only sent while the phone is ringing, never for an answered call.
 //!
 //! ## SMS Messages
 //!
@@ -51,6 +60,11 @@ use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 use tracing::{debug, info, warn};
 
+use super::notifier::{
+    ActionTarget, NotificationAction, NotificationHandle, NotificationSpec, NotificationUrgency,
+    Notifier,
+};
+use super::reply::prepare_reply;
 use super::{Plugin, PluginFactory};
 
 /// Packet type for telephony events
@@ -59,6 +73,9 @@ pub const PACKET_TYPE_TELEPHONY: &str = "cconnect.telephony";
 /// Packet type for mute ringer request
 pub const PACKET_TYPE_TELEPHONY_MUTE: &str = "cconnect.telephony.request_mute";
 
+/// Action key of the "Mute call" button on the incoming call notification
+pub const MUTE_CALL_ACTION: &str = "mute-call";
+
 /// Packet type for SMS messages
 pub const PACKET_TYPE_SMS_MESSAGES: &str = "cconnect.sms.messages";
 
@@ -242,6 +259,9 @@ pub struct SendSmsRequest {
 pub struct TelephonyPlugin {
     device_id: Option<String>,
 
+    /// Sender for packets to the device
+    packet_sender: Option<tokio::sync::mpsc::Sender<(String, Packet)>>,
+
     /// Current call state (if any)
     current_call: Arc<RwLock<Option<TelephonyEvent>>>,
 
@@ -253,6 +273,12 @@ pub struct TelephonyPlugin {
 
     /// Maximum call history entries to keep
     max_history: usize,
+
+    /// Notifier for call events
+    notifier: Option<Arc<dyn Notifier>>,
+
+    /// Notification for the call that is currently ringing
+    ringing_notification: Option<NotificationHandle>,
 }
 
 /// Default maximum call history entries
@@ -263,10 +289,13 @@ impl TelephonyPlugin {
     pub fn new() -> Self {
         Self {
             device_id: None,
+            packet_sender: None,
             current_call: Arc::new(RwLock::new(None)),
             call_history: Arc::new(RwLock::new(Vec::new())),
             conversations: Arc::new(RwLock::new(HashMap::new())),
             max_history: DEFAULT_MAX_HISTORY,
+            notifier: None,
+            ringing_notification: None,
         }
     }
 
@@ -407,6 +436,35 @@ impl TelephonyPlugin {
         Packet::new(PACKET_TYPE_TELEPHONY_MUTE, json!({}))
     }
 
+    /// Ask the phone to stop ringing
+    ///
+    /// Only sent while the phone is ringing; muting an answered call or no
+    /// call at all does nothing. Returns whether the request was sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the plugin is not initialized or the packet could
+    /// not be queued.
+    pub async fn mute_call(&self) -> Result<bool> {
+        if !self.is_ringing() {
+            debug!("Not muting, the phone is not ringing");
+            return Ok(false);
+        }
+
+        let (Some(sender), Some(device_id)) = (&self.packet_sender, &self.device_id) else {
+            return Err(ProtocolError::Plugin(
+                "Telephony plugin not initialized".to_string(),
+            ));
+        };
+        sender
+            .send((device_id.clone(), self.create_mute_request()))
+            .await
+            .map_err(|e| ProtocolError::Transport(format!("Failed to send mute request: {}", e)))?;
+
+        info!("Requested {} to mute its ringer", device_id);
+        Ok(true)
+    }
+
     /// Create a request for conversation list
     ///
     /// Requests the latest message in each thread.
@@ -468,17 +526,22 @@ impl TelephonyPlugin {
     /// # Arguments
     ///
     /// * `phone_number` - Recipient phone number
-    /// * `message` - Message body
-    pub fn create_send_sms_request(&self, phone_number: String, message: String) -> Packet {
+    /// * `message` - Message body, sanitized with [`prepare_reply`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message is empty or too long.
+    pub fn create_send_sms_request(&self, phone_number: String, message: String) -> Result<Packet> {
         debug!("Creating send SMS request to {}", phone_number);
+        let message = prepare_reply(&message)?;
 
-        Packet::new(
+        Ok(Packet::new(
             PACKET_TYPE_SMS_REQUEST,
             json!({
                 "phoneNumber": phone_number,
                 "messageBody": message,
             }),
-        )
+        ))
     }
 
     /// Handle a telephony event packet
@@ -517,6 +580,79 @@ impl TelephonyPlugin {
         Ok(())
     }
 
+    /// Raise or close call notifications for a telephony event packet
+    async fn update_call_notification(&mut self, packet: &Packet) {
+        let Some(notifier) = self.notifier.clone() else {
+            return;
+        };
+        let Ok(event) = serde_json::from_value::<TelephonyEvent>(packet.body.clone()) else {
+            return;
+        };
+        let Some(event_type) = CallEvent::from_str(&event.event) else {
+            return;
+        };
+        if event_type == CallEvent::Sms {
+            return;
+        }
+
+        // Any follow-up event means the phone has stopped ringing
+        if let Some(handle) = self.ringing_notification.take() {
+            if let Err(e) = notifier.close(handle).await {
+                warn!("Failed to close incoming call notification: {}", e);
+            }
+        }
+
+        let is_cancel = packet
+            .body
+            .get("isCancel")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if is_cancel {
+            debug!("Call event '{}' cancelled", event.event);
+            return;
+        }
+
+        let caller = event
+            .contact_name
+            .as_deref()
+            .or(event.phone_number.as_deref())
+            .unwrap_or("Unknown caller")
+            .to_string();
+
+        match event_type {
+            CallEvent::Ringing => {
+                let mut spec = NotificationSpec::new("Incoming call")
+                    .body(caller)
+                    .icon("call-start-symbolic")
+                    .urgency(NotificationUrgency::Critical)
+                    .category("call.incoming")
+                    .timeout(0)
+                    .action(NotificationAction::new(
+                        MUTE_CALL_ACTION,
+                        "Mute call",
+                        ActionTarget::MuteCall,
+                    ));
+                if let Some(device_id) = &self.device_id {
+                    spec = spec.device(device_id.clone());
+                }
+                match notifier.notify(spec).await {
+                    Ok(handle) => self.ringing_notification = Some(handle),
+                    Err(e) => warn!("Failed to show incoming call notification: {}", e),
+                }
+            }
+            CallEvent::MissedCall => {
+                let spec = NotificationSpec::new("Missed call")
+                    .body(caller)
+                    .icon("call-missed-symbolic")
+                    .category("call.unanswered");
+                if let Err(e) = notifier.notify(spec).await {
+                    warn!("Failed to show missed call notification: {}", e);
+                }
+            }
+            CallEvent::Talking | CallEvent::Sms => {}
+        }
+    }
+
     /// Handle SMS messages packet
     fn handle_sms_messages(&self, packet: &Packet) -> Result<()> {
         let messages: SmsMessages = serde_json::from_value(packet.body.clone())
@@ -593,9 +729,10 @@ impl Plugin for TelephonyPlugin {
     async fn init(
         &mut self,
         device: &Device,
-        _packet_sender: tokio::sync::mpsc::Sender<(String, Packet)>,
+        packet_sender: tokio::sync::mpsc::Sender<(String, Packet)>,
     ) -> Result<()> {
         self.device_id = Some(device.id().to_string());
+        self.packet_sender = Some(packet_sender);
         info!("Telephony plugin initialized for device {}", device.name());
         Ok(())
     }
@@ -613,7 +750,9 @@ impl Plugin for TelephonyPlugin {
     async fn handle_packet(&mut self, packet: &Packet, _device: &mut Device) -> Result<()> {
         if packet.is_type(PACKET_TYPE_TELEPHONY) || packet.is_type("kdeconnect.telephony") {
             debug!("Received telephony event");
-            self.handle_telephony_event(packet)
+            self.handle_telephony_event(packet)?;
+            self.update_call_notification(packet).await;
+            Ok(())
         } else if packet.is_type(PACKET_TYPE_SMS_MESSAGES)
             || packet.is_type("kdeconnect.sms.messages")
         {
@@ -624,6 +763,10 @@ impl Plugin for TelephonyPlugin {
             Ok(())
         }
     }
+
+    fn set_notifier(&mut self, notifier: Arc<dyn Notifier>) {
+        self.notifier = Some(notifier);
+    }
 }
 
 /// Factory for creating Telephony plugin instances
@@ -662,6 +805,7 @@ impl PluginFactory for TelephonyPluginFactory {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::plugins::notifier::MockNotifier;
     use crate::{DeviceInfo, DeviceType};
 
     fn create_test_device() -> Device {
@@ -720,12 +864,22 @@ mod tests {
     #[test]
     fn test_create_send_sms_request() {
         let plugin = TelephonyPlugin::new();
-        let packet =
-            plugin.create_send_sms_request("+1234567890".to_string(), "Hello!".to_string());
+        let packet = plugin
+            .create_send_sms_request("+1234567890".to_string(), "Hello!".to_string())
+            .unwrap();
 
         assert_eq!(packet.packet_type, "cconnect.sms.request");
         assert_eq!(packet.body["phoneNumber"], "+1234567890");
         assert_eq!(packet.body["messageBody"], "Hello!");
+
+        // Bodies are sanitized, and over-length bodies rejected
+        let packet = plugin
+            .create_send_sms_request("+1234567890".to_string(), "Hi\u{1b}\r\n".to_string())
+            .unwrap();
+        assert_eq!(packet.body["messageBody"], "Hi\n");
+        assert!(plugin
+            .create_send_sms_request("+1234567890".to_string(), "x".repeat(5000))
+            .is_err());
     }
 
     #[test]
@@ -928,4 +1082,78 @@ mod tests {
         let history = plugin.get_call_history();
         assert_eq!(history[0].phone_number.as_deref(), Some("+1234567894"));
     }
+
+    #[tokio::test]
+    async fn test_call_notifications() {
+        let notifier = Arc::new(MockNotifier::new());
+        let mut plugin = TelephonyPlugin::new();
+        let mut device = create_test_device();
+        plugin.set_notifier(notifier.clone());
+
+        let ringing = Packet::new(
+            "cconnect.telephony",
+            json!({ "event": "ringing", "phoneNumber": "+1234567890", "contactName": "Jane Doe" }),
+        );
+        plugin.handle_packet(&ringing, &mut device).await.unwrap();
+        let open = notifier.open();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].summary, "Incoming call");
+        assert_eq!(open[0].body, "Jane Doe");
+        assert_eq!(open[0].category.as_deref(), Some("call.incoming"));
+
+        // Ringing stopped without an answer: close, and don't raise anything else
+        let cancel = Packet::new(
+            "cconnect.telephony",
+            json!({ "event": "ringing", "phoneNumber": "+1234567890", "isCancel": true }),
+        );
+        plugin.handle_packet(&cancel, &mut device).await.unwrap();
+        assert!(notifier.open().is_empty());
+        assert_eq!(notifier.sent().len(), 1);
+
+        let missed = Packet::new(
+            "cconnect.telephony",
+            json!({ "event": "missedCall", "phoneNumber": "+1234567890" }),
+        );
+        plugin.handle_packet(&missed, &mut device).await.unwrap();
+        let open = notifier.open();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].summary, "Missed call");
+        assert_eq!(open[0].body, "+1234567890");
+    }
+
+    #[tokio::test]
+    async fn test_mute_action_only_while_ringing() {
+        let notifier = Arc::new(MockNotifier::new());
+        let mut plugin = TelephonyPlugin::new();
+        let mut device = create_test_device();
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(4);
+        plugin.init(&device, sender).await.unwrap();
+        plugin.set_notifier(notifier.clone());
+
+        let ringing = Packet::new(
+            "cconnect.telephony",
+            json!({ "event": "ringing", "phoneNumber": "+1234567890" }),
+        );
+        plugin.handle_packet(&ringing, &mut device).await.unwrap();
+        let open = notifier.open();
+        let action = open[0].find_action(MUTE_CALL_ACTION).unwrap();
+        assert_eq!(action.target, ActionTarget::MuteCall);
+        assert_eq!(open[0].device_id.as_deref(), Some(device.id()));
+
+        // Invoking the action while ringing mutes the phone
+        assert!(plugin.mute_call().await.unwrap());
+        let (device_id, packet) = receiver.try_recv().unwrap();
+        assert_eq!(device_id, device.id());
+        assert!(packet.is_type("kdeconnect.telephony.request_mute"));
+        assert!(receiver.try_recv().is_err());
+
+        // Once answered, muting sends nothing
+        let talking = Packet::new(
+            "cconnect.telephony",
+            json!({ "event": "talking", "phoneNumber": "+1234567890" }),
+        );
+        plugin.handle_packet(&talking, &mut device).await.unwrap();
+        assert!(!plugin.mute_call().await.unwrap());
+        assert!(receiver.try_recv().is_err());
+    }
 }
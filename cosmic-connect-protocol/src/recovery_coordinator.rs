@@ -4,7 +4,10 @@
 //! This module acts as a bridge between the ConnectionManager and RecoveryManager,
 //! listening for connection failures and triggering appropriate recovery actions.
 
-use crate::{ConnectionEvent, ConnectionManager, DeviceManager, RecoveryManager, Result};
+use crate::{
+    ConnectionEvent, ConnectionManager, DeviceManager, RecoveryManager, Result,
+    MANUAL_DISCONNECT_REASON,
+};
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -69,6 +72,15 @@ impl RecoveryCoordinator {
                             reconnect
                         );
 
+                        // The user closed the connection, leave it closed
+                        if reason.as_deref() == Some(MANUAL_DISCONNECT_REASON) {
+                            debug!(
+                                "Skipping auto-reconnect for device {} (manual disconnect)",
+                                device_id
+                            );
+                            continue;
+                        }
+
                         // Check if device is paired (only auto-reconnect to paired devices)
                         let dm = device_manager.read().await;
                         let should_reconnect = if let Some(device) = dm.get_device(&device_id) {
@@ -86,8 +98,10 @@ impl RecoveryCoordinator {
                             continue;
                         }
 
-                        // Get reconnection delay with exponential backoff
-                        if let Some(delay) = recovery_manager.should_reconnect(&device_id).await {
+                        // Get reconnection delay with exponential backoff, spread
+                        // out so devices dropped together don't retry together
+                        if let Some(backoff) = recovery_manager.should_reconnect(&device_id).await {
+                            let delay = connection_manager.reconnect_delay(backoff);
                             info!(
                                 "Scheduling reconnection for device {} after {:?}",
                                 device_id, delay
@@ -0,0 +1,541 @@
+//! End-to-End Tests with the Fake Phone
+//!
+//! These tests pair a desktop-side connection manager and pairing service with
+//! the fake phone from `examples/fake_phone.rs` over loopback, then exchange
+//! packets and payloads the way the daemon does.
+
+#[allow(dead_code)]
+#[path = "../examples/fake_phone.rs"]
+mod fake_phone;
+
+use cosmic_connect_protocol::plugins::ping::PingPlugin;
+use cosmic_connect_protocol::plugins::share::{FileShareInfo, SharePlugin};
+use cosmic_connect_protocol::{
+    ConnectionConfig, ConnectionEvent, ConnectionManager, DeviceInfo, DeviceManager, DeviceType,
+    Packet, PairingConfig, PairingService, TlsPayloadServer,
+};
+use fake_phone::{FakePhone, FAKE_BATTERY_LEVEL, FAKE_CLIPBOARD, PONG_MESSAGE};
+use serde_json::json;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::sync::{mpsc, RwLock};
+use tokio::time::timeout;
+
+/// How long to wait for the phone to answer
+const EVENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Desktop side of the connection, wired up like the daemon
+struct Desktop {
+    device_info: DeviceInfo,
+    device_manager: Arc<RwLock<DeviceManager>>,
+    connection_manager: Arc<RwLock<ConnectionManager>>,
+    pairing_service: PairingService,
+    events: mpsc::UnboundedReceiver<ConnectionEvent>,
+    _data_dir: TempDir,
+}
+
+impl Desktop {
+    async fn start() -> Self {
+        let data_dir = TempDir::new().expect("Failed to create temp dir");
+        let device_info = DeviceInfo::new("Test Desktop", DeviceType::Desktop, 0);
+
+        let pairing_config = PairingConfig {
+            cert_dir: data_dir.path().join("certs"),
+            ..Default::default()
+        };
+        let mut pairing_service = PairingService::new(&device_info.device_id, pairing_config)
+            .expect("Failed to create pairing service");
+
+        let device_manager = Arc::new(RwLock::new(
+            DeviceManager::new(data_dir.path().join("registry.json"))
+                .expect("Failed to create device manager"),
+        ));
+        let connection_manager = ConnectionManager::new(
+            pairing_service.certificate().clone(),
+            device_info.clone(),
+            device_manager.clone(),
+            ConnectionConfig::default(),
+        )
+        .expect("Failed to create connection manager");
+        let events = connection_manager.subscribe().await;
+
+        let connection_manager = Arc::new(RwLock::new(connection_manager));
+        pairing_service.set_connection_manager(connection_manager.clone());
+
+        Self {
+            device_info,
+            device_manager,
+            connection_manager,
+            pairing_service,
+            events,
+            _data_dir: data_dir,
+        }
+    }
+
+    async fn connect(&mut self, phone: &FakePhone) {
+        self.connection_manager
+            .read()
+            .await
+            .connect(&phone.device_info().device_id, phone.addr())
+            .await
+            .expect("Failed to connect to fake phone");
+
+        loop {
+            let event = timeout(EVENT_TIMEOUT, self.events.recv())
+                .await
+                .expect("Timed out waiting for connection")
+                .expect("Event channel closed");
+            if let ConnectionEvent::Connected { device_id, .. } = event {
+                assert_eq!(device_id, phone.device_info().device_id);
+                return;
+            }
+        }
+    }
+
+    async fn pair(&mut self, phone: &FakePhone) {
+        self.connect(phone).await;
+        self.pair_connected(phone).await;
+    }
+
+    async fn pair_connected(&mut self, phone: &FakePhone) {
+        self.pairing_service
+            .request_pairing(phone.device_info().clone(), phone.addr())
+            .await
+            .expect("Failed to request pairing");
+
+        // Route the phone's answer through the pairing service like the daemon,
+        // which pins the certificate the phone presented on its TLS connection
+        let accept = self.next_packet("cconnect.pair").await;
+        let presented = self.presented_certificate(phone).await;
+        let response = self
+            .pairing_service
+            .handle_pairing_packet(&accept, phone.device_info(), &presented, phone.addr())
+            .await
+            .expect("Failed to handle pairing response");
+        if let Some(response) = response {
+            self.send(phone, &response).await;
+        }
+    }
+
+    /// Certificate the phone presented during the TLS handshake
+    async fn presented_certificate(&self, phone: &FakePhone) -> Vec<u8> {
+        self.device_manager
+            .read()
+            .await
+            .get_device(&phone.device_info().device_id)
+            .and_then(|device| device.certificate_data.clone())
+            .expect("No certificate recorded for the phone's connection")
+    }
+
+    async fn send(&self, phone: &FakePhone, packet: &Packet) {
+        self.connection_manager
+            .read()
+            .await
+            .send_packet(&phone.device_info().device_id, packet)
+            .await
+            .expect("Failed to send packet");
+    }
+
+    /// Wait for the next packet of a type, skipping everything else
+    async fn next_packet(&mut self, packet_type: &str) -> Packet {
+        loop {
+            let event = timeout(EVENT_TIMEOUT, self.events.recv())
+                .await
+                .unwrap_or_else(|_| panic!("Timed out waiting for '{}'", packet_type))
+                .expect("Event channel closed");
+
+            if let ConnectionEvent::PacketReceived { packet, .. } = event {
+                let keepalive = packet.get_body_field::<bool>("keepalive").unwrap_or(false);
+                if packet.is_type(packet_type) && !keepalive {
+                    return packet;
+                }
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_pair_and_ping_fake_phone() {
+    let phone = FakePhone::start("Fake Phone")
+        .await
+        .expect("Failed to start fake phone");
+    let mut desktop = Desktop::start().await;
+
+    desktop.pair(&phone).await;
+
+    assert!(
+        desktop
+            .pairing_service
+            .is_paired(&phone.device_info().device_id)
+            .await
+    );
+    assert!(phone.is_paired(&desktop.device_info.device_id).await);
+
+    let ping = PingPlugin::new().create_ping(Some("ping".to_string()));
+    desktop.send(&phone, &ping).await;
+
+    let pong = desktop.next_packet("cconnect.ping").await;
+    assert_eq!(
+        pong.get_body_field::<String>("message").as_deref(),
+        Some(PONG_MESSAGE)
+    );
+
+    phone.stop().await;
+}
+
+#[tokio::test]
+async fn test_pair_ping_and_send_file_over_loopback_tls() {
+    let phone = FakePhone::start("Fake Phone")
+        .await
+        .expect("Failed to start fake phone");
+    let mut desktop = Desktop::start().await;
+    let phone_id = phone.device_info().device_id.clone();
+
+    desktop.pair(&phone).await;
+
+    // Both ends are paired and the phone's certificate is pinned
+    assert!(desktop.pairing_service.is_paired(&phone_id).await);
+    assert!(phone.is_paired(&desktop.device_info.device_id).await);
+    let pinned = desktop
+        .pairing_service
+        .paired_certificates()
+        .await
+        .expect("Failed to list pinned certificates");
+    assert_eq!(pinned.len(), 1);
+    assert_eq!(pinned[0].device_id, phone_id);
+    assert_eq!(pinned[0].fingerprint, phone.certificate().fingerprint);
+
+    let ping = PingPlugin::new().create_ping(Some("ping".to_string()));
+    desktop.send(&phone, &ping).await;
+    desktop.next_packet("cconnect.ping").await;
+
+    // Offer a file on a TLS payload server, like the share plugin
+    let contents: Vec<u8> = (0..64 * 1024).map(|i| (i % 251) as u8).collect();
+    let file_dir = TempDir::new().expect("Failed to create temp dir");
+    let path = file_dir.path().join("notes.bin");
+    std::fs::write(&path, &contents).expect("Failed to write file");
+
+    let tls_config = desktop.connection_manager.read().await.tls_config();
+    let server = TlsPayloadServer::new(tls_config)
+        .await
+        .expect("Failed to start payload server");
+    let port = server.port();
+    let upload = tokio::spawn(async move { server.send_file(&path).await });
+
+    let offer = SharePlugin::new().create_file_packet(
+        FileShareInfo {
+            filename: "notes.bin".to_string(),
+            size: contents.len() as i64,
+            creation_time: None,
+            last_modified: None,
+            open: false,
+        },
+        port,
+    );
+    desktop.send(&phone, &offer).await;
+
+    // The phone finishes the download before answering the next ping
+    desktop.send(&phone, &ping).await;
+    desktop.next_packet("cconnect.ping").await;
+
+    timeout(EVENT_TIMEOUT, upload)
+        .await
+        .expect("Timed out sending file")
+        .expect("Upload task panicked")
+        .expect("Failed to send file");
+    assert_eq!(phone.received_file("notes.bin").await, Some(contents));
+
+    phone.stop().await;
+}
+
+#[tokio::test]
+async fn test_fake_phone_syncs_battery_and_clipboard() {
+    let phone = FakePhone::start("Fake Phone")
+        .await
+        .expect("Failed to start fake phone");
+    let mut desktop = Desktop::start().await;
+
+    desktop.pair(&phone).await;
+
+    // The phone reports its state right after pairing
+    let battery = desktop.next_packet("cconnect.battery").await;
+    assert_eq!(
+        battery.get_body_field::<i32>("currentCharge"),
+        Some(FAKE_BATTERY_LEVEL)
+    );
+    let clipboard = desktop.next_packet("cconnect.clipboard.connect").await;
+    assert_eq!(
+        clipboard.get_body_field::<String>("content").as_deref(),
+        Some(FAKE_CLIPBOARD)
+    );
+
+    // Packets are answered in order, so the battery reply means the
+    // clipboard update was applied first
+    desktop
+        .send(
+            &phone,
+            &Packet::new(
+                "cconnect.clipboard",
+                json!({ "content": "From the desktop" }),
+            ),
+        )
+        .await;
+    desktop
+        .send(
+            &phone,
+            &Packet::new("cconnect.battery.request", json!({ "request": true })),
+        )
+        .await;
+
+    desktop.next_packet("cconnect.battery").await;
+    assert_eq!(phone.clipboard().await, "From the desktop");
+
+    phone.stop().await;
+}
+
+#[tokio::test]
+async fn test_reconnects_when_phone_moves_address() {
+    let phone = FakePhone::start("Fake Phone")
+        .await
+        .expect("Failed to start fake phone");
+    let device_id = phone.device_info().device_id.clone();
+    let mut desktop = Desktop::start().await;
+
+    desktop.connect(&phone).await;
+
+    // A beacon from elsewhere does not replace a healthy connection
+    let elsewhere = SocketAddr::from((Ipv4Addr::LOCALHOST, 1));
+    let reconnected = desktop
+        .connection_manager
+        .read()
+        .await
+        .handle_discovered_address(&device_id, elsewhere)
+        .await
+        .expect("Failed to handle announcement");
+    assert!(!reconnected);
+
+    // The phone drops off and reappears on a new port
+    let old_addr = phone.addr();
+    let phone = phone
+        .relocate()
+        .await
+        .expect("Failed to relocate fake phone");
+    assert_ne!(phone.addr(), old_addr);
+    loop {
+        let event = timeout(EVENT_TIMEOUT, desktop.events.recv())
+            .await
+            .expect("Timed out waiting for disconnect")
+            .expect("Event channel closed");
+        if matches!(event, ConnectionEvent::Disconnected { .. }) {
+            break;
+        }
+    }
+
+    let reconnected = desktop
+        .connection_manager
+        .read()
+        .await
+        .handle_discovered_address(&device_id, phone.addr())
+        .await
+        .expect("Failed to reconnect");
+    assert!(reconnected);
+
+    loop {
+        let event = timeout(EVENT_TIMEOUT, desktop.events.recv())
+            .await
+            .expect("Timed out waiting for reconnect")
+            .expect("Event channel closed");
+        if let ConnectionEvent::Connected {
+            device_id: connected,
+            remote_addr,
+        } = event
+        {
+            assert_eq!(connected, device_id);
+            assert_eq!(remote_addr, phone.addr());
+            break;
+        }
+    }
+
+    phone.stop().await;
+}
+
+#[tokio::test]
+async fn test_connects_when_pairing_completes() {
+    let phone = FakePhone::start("Fake Phone")
+        .await
+        .expect("Failed to start fake phone");
+    let mut desktop = Desktop::start().await;
+    let phone_id = phone.device_info().device_id.clone();
+
+    desktop.connect(&phone).await;
+    desktop
+        .pairing_service
+        .request_pairing(phone.device_info().clone(), phone.addr())
+        .await
+        .expect("Failed to request pairing");
+    let accept = desktop.next_packet("cconnect.pair").await;
+    let presented = desktop.presented_certificate(&phone).await;
+
+    // The acceptance arrives after the unpaired connection was dropped
+    desktop
+        .connection_manager
+        .read()
+        .await
+        .disconnect(&phone_id)
+        .await
+        .expect("Failed to disconnect");
+    assert!(
+        !desktop
+            .connection_manager
+            .read()
+            .await
+            .has_connection(&phone_id)
+            .await
+    );
+
+    // The packet came from the phone's source port, where nothing listens
+    let source_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, 1));
+    desktop
+        .pairing_service
+        .handle_pairing_packet(&accept, phone.device_info(), &presented, source_addr)
+        .await
+        .expect("Failed to handle pairing response");
+    assert!(desktop.pairing_service.is_paired(&phone_id).await);
+
+    loop {
+        let event = timeout(EVENT_TIMEOUT, desktop.events.recv())
+            .await
+            .expect("Timed out waiting for connection after pairing")
+            .expect("Event channel closed");
+        if let ConnectionEvent::Connected { device_id, .. } = event {
+            assert_eq!(device_id, phone_id);
+            break;
+        }
+    }
+    assert!(
+        desktop
+            .connection_manager
+            .read()
+            .await
+            .has_connection(&phone_id)
+            .await
+    );
+
+    phone.stop().await;
+}
+
+#[tokio::test]
+async fn test_unpair_while_offline_is_delivered_on_connect() {
+    let phone = FakePhone::start("Fake Phone")
+        .await
+        .expect("Failed to start fake phone");
+    let mut desktop = Desktop::start().await;
+    let phone_id = phone.device_info().device_id.clone();
+    let desktop_id = desktop.device_info.device_id.clone();
+
+    desktop.pair(&phone).await;
+    assert!(phone.is_paired(&desktop_id).await);
+
+    // Unpair while the phone is out of reach
+    desktop
+        .connection_manager
+        .read()
+        .await
+        .disconnect(&phone_id)
+        .await
+        .expect("Failed to disconnect");
+    desktop
+        .pairing_service
+        .unpair(&phone_id)
+        .await
+        .expect("Failed to unpair");
+    assert!(!desktop.pairing_service.is_paired(&phone_id).await);
+    assert!(desktop.pairing_service.has_deferred(&phone_id).await);
+    assert!(phone.is_paired(&desktop_id).await);
+
+    // The phone hears about it once it is back
+    desktop.connect(&phone).await;
+    let delivered = desktop
+        .pairing_service
+        .deliver_deferred(&phone_id)
+        .await
+        .expect("Failed to deliver deferred unpair");
+    assert!(delivered);
+    assert!(!desktop.pairing_service.has_deferred(&phone_id).await);
+
+    timeout(EVENT_TIMEOUT, async {
+        while phone.is_paired(&desktop_id).await {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("Timed out waiting for the phone to unpair");
+
+    // Nothing left to deliver on the next connect
+    assert!(!desktop
+        .pairing_service
+        .deliver_deferred(&phone_id)
+        .await
+        .expect("Failed to check deferred changes"));
+
+    phone.stop().await;
+}
+
+#[tokio::test]
+async fn test_add_fake_phone_by_address() {
+    let phone = FakePhone::start("Fake Phone")
+        .await
+        .expect("Failed to start fake phone");
+    let mut desktop = Desktop::start().await;
+    let phone_id = phone.device_info().device_id.clone();
+
+    // Nothing listens on a port that was just released
+    let closed = std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .and_then(|listener| listener.local_addr())
+        .expect("Failed to find a free port");
+    assert!(desktop
+        .connection_manager
+        .read()
+        .await
+        .add_device_by_address(closed)
+        .await
+        .is_err());
+
+    let added = desktop
+        .connection_manager
+        .read()
+        .await
+        .add_device_by_address(phone.addr())
+        .await
+        .expect("Failed to add device by address");
+    assert_eq!(added.device_id, phone_id);
+
+    // The phone is listed like a discovered device
+    {
+        let device_manager = desktop.device_manager.read().await;
+        let device = device_manager
+            .get_device(&phone_id)
+            .expect("Added device is not listed");
+        assert_eq!(device.info.device_name, phone.device_info().device_name);
+    }
+
+    loop {
+        let event = timeout(EVENT_TIMEOUT, desktop.events.recv())
+            .await
+            .expect("Timed out waiting for connection")
+            .expect("Event channel closed");
+        if let ConnectionEvent::Connected { device_id, .. } = event {
+            assert_eq!(device_id, phone_id);
+            break;
+        }
+    }
+
+    // And pairs normally
+    desktop.pair_connected(&phone).await;
+    assert!(desktop.pairing_service.is_paired(&phone_id).await);
+    assert!(phone.is_paired(&desktop.device_info.device_id).await);
+
+    phone.stop().await;
+}
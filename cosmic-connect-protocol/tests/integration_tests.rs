@@ -135,8 +135,8 @@ async fn test_device_pairing_state_transitions() {
     assert!(!device.is_trusted);
 
     // Request pairing
-    device.pairing_status = PairingStatus::Requested;
-    assert_eq!(device.pairing_status, PairingStatus::Requested);
+    device.pairing_status = PairingStatus::RequestedByUs;
+    assert_eq!(device.pairing_status, PairingStatus::RequestedByUs);
     assert!(!device.is_trusted);
 
     // Complete pairing
@@ -0,0 +1,126 @@
+//! Configuration Module
+//!
+//! Persists the last active messenger and the window geometry so the app
+//! reopens where it was left.
+
+use crate::MessengerType;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tracing::{debug, error, info};
+
+/// Main application configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Messenger shown on launch
+    pub last_messenger: MessengerType,
+
+    /// Window settings
+    pub window: WindowConfig,
+}
+
+/// Window geometry, in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowConfig {
+    /// Width of the window
+    pub width: f32,
+    /// Height of the window
+    pub height: f32,
+    /// Last known position, if the compositor reported one
+    pub position: Option<(f32, f32)>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            last_messenger: MessengerType::GoogleMessages,
+            window: WindowConfig::default(),
+        }
+    }
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            width: 1024.0,
+            height: 768.0,
+            position: None,
+        }
+    }
+}
+
+impl Config {
+    /// Load configuration from file
+    pub fn load() -> Option<Self> {
+        let path = Self::config_path()?;
+        debug!("Loading config from {:?}", path);
+
+        let content = match std::fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(e) => {
+                debug!("Could not read config file: {}", e);
+                return None;
+            }
+        };
+
+        match serde_json::from_str(&content) {
+            Ok(config) => {
+                info!("Loaded configuration from {:?}", path);
+                Some(config)
+            }
+            Err(e) => {
+                error!("Failed to parse config: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Save configuration to file
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = Self::config_path().ok_or_else(|| anyhow::anyhow!("No config path"))?;
+
+        // Ensure parent directory exists
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(&path, content)?;
+        debug!("Saved configuration to {:?}", path);
+        Ok(())
+    }
+
+    /// Get the config file path
+    pub fn config_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("cosmic").join("org.cosmicde.Messages.json"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialization() {
+        let config = Config {
+            last_messenger: MessengerType::Signal,
+            window: WindowConfig {
+                width: 800.0,
+                height: 600.0,
+                position: Some((40.0, 60.0)),
+            },
+        };
+
+        let serialized = serde_json::to_string(&config).unwrap();
+        let deserialized: Config = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, config);
+    }
+
+    #[test]
+    fn test_missing_fields_use_defaults() {
+        let config: Config = serde_json::from_str(r#"{"last_messenger":"WhatsApp"}"#).unwrap();
+        assert_eq!(config.last_messenger, MessengerType::WhatsApp);
+        assert_eq!(config.window, WindowConfig::default());
+    }
+}
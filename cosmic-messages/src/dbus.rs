@@ -0,0 +1,59 @@
+//! D-Bus Service Module
+//!
+//! Publishes the total unread message count so a panel applet can show it
+//! without opening the window. Clients read the `UnreadCount` property and
+//! follow its `PropertiesChanged` signal.
+
+use tracing::info;
+use zbus::{connection, interface, Connection};
+
+/// Well-known bus name of the service
+pub const SERVICE_NAME: &str = "org.cosmicde.Messages";
+
+/// Object path of the service
+pub const OBJECT_PATH: &str = "/org/cosmicde/Messages";
+
+/// D-Bus service for the messages window
+#[derive(Debug, Default)]
+pub struct MessagesService {
+    unread_count: u32,
+}
+
+#[interface(name = "org.cosmicde.Messages")]
+impl MessagesService {
+    /// Unread messages across all messengers
+    #[zbus(property)]
+    fn unread_count(&self) -> u32 {
+        self.unread_count
+    }
+}
+
+/// Start the D-Bus service
+pub async fn start_dbus_service() -> zbus::Result<Connection> {
+    let connection = connection::Builder::session()?
+        .name(SERVICE_NAME)?
+        .serve_at(OBJECT_PATH, MessagesService::default())?
+        .build()
+        .await?;
+
+    info!("D-Bus service started: {}", SERVICE_NAME);
+
+    Ok(connection)
+}
+
+/// Update the published unread count, notifying clients if it changed
+pub async fn set_unread_count(connection: &Connection, count: u32) -> zbus::Result<()> {
+    let iface_ref = connection
+        .object_server()
+        .interface::<_, MessagesService>(OBJECT_PATH)
+        .await?;
+
+    let mut service = iface_ref.get_mut().await;
+    if service.unread_count == count {
+        return Ok(());
+    }
+    service.unread_count = count;
+    service
+        .unread_count_changed(iface_ref.signal_emitter())
+        .await
+}
@@ -1,11 +1,25 @@
+mod config;
+mod dbus;
+mod page;
+
+use config::Config;
 use cosmic::app::{Core, Task};
-use cosmic::iced::Length;
-use cosmic::widget::{self, button, container, text};
+use cosmic::iced::keyboard::{Key, Modifiers};
+use cosmic::iced::{window, Length, Point, Size};
+use cosmic::widget::{self, button, container, icon, text};
 use cosmic::Element;
+use page::{LoadError, PageEvent, PageLoad};
 use serde::{Deserialize, Serialize};
-use std::sync::{Arc, Mutex};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use tokio::sync::mpsc::UnboundedSender;
+
+thread_local! {
+    /// The webview, created and used on the UI thread only
+    static WEBVIEW: RefCell<Option<wry::WebView>> = const { RefCell::new(None) };
+}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum MessengerType {
     GoogleMessages,
     WhatsApp,
@@ -16,6 +30,16 @@ pub enum MessengerType {
 }
 
 impl MessengerType {
+    /// All messengers, in Ctrl+1..6 shortcut order
+    pub const ALL: [MessengerType; 6] = [
+        Self::GoogleMessages,
+        Self::WhatsApp,
+        Self::Telegram,
+        Self::Signal,
+        Self::Discord,
+        Self::Slack,
+    ];
+
     pub fn web_url(&self) -> &'static str {
         match self {
             Self::GoogleMessages => "https://messages.google.com/web",
@@ -47,11 +71,13 @@ pub struct MessagingNotification {
     pub conversation_id: Option<String>,
 }
 
-#[allow(clippy::arc_with_non_send_sync)] // WebView is single-threaded by design
 pub struct CosmicMessages {
     core: Core,
+    config: Config,
     current_messenger: MessengerType,
-    webview: Option<Arc<Mutex<Option<wry::WebView>>>>,
+    unread: HashMap<MessengerType, u32>,
+    dbus: Option<zbus::Connection>,
+    page: PageLoad,
 }
 
 #[derive(Debug, Clone)]
@@ -59,11 +85,147 @@ pub enum Message {
     SwitchMessenger(MessengerType),
     NotificationReceived(MessagingNotification),
     WebViewCreated,
+    WindowResized(Size),
+    WindowMoved(Point),
+    DbusReady(zbus::Connection),
+    PageEventsReady(UnboundedSender<PageEvent>),
+    PageLoad(PageEvent),
+    PageLoadFailed(u64, LoadError),
+    PageLoadTimedOut(u64),
+    RetryLoad,
+    None,
+}
+
+/// Map Ctrl+1..6 to switching messenger
+fn key_shortcut(key: &Key, modifiers: Modifiers) -> Option<Message> {
+    if !modifiers.control() || modifiers.alt() || modifiers.shift() {
+        return None;
+    }
+
+    let Key::Character(c) = key else {
+        return None;
+    };
+    let index = c.as_str().parse::<usize>().ok()?.checked_sub(1)?;
+    MessengerType::ALL
+        .get(index)
+        .map(|m| Message::SwitchMessenger(*m))
+}
+
+impl CosmicMessages {
+    /// Unread messages of a messenger
+    pub fn unread_count(&self, messenger: MessengerType) -> u32 {
+        self.unread.get(&messenger).copied().unwrap_or(0)
+    }
+
+    /// Unread messages across all messengers
+    pub fn total_unread(&self) -> u32 {
+        self.unread.values().sum()
+    }
+
+    /// Publish the total unread count on D-Bus
+    fn publish_unread(&self) -> Task<Message> {
+        let Some(connection) = self.dbus.clone() else {
+            return Task::none();
+        };
+
+        let count = self.total_unread();
+        cosmic::task::future(async move {
+            if let Err(e) = dbus::set_unread_count(&connection, count).await {
+                tracing::warn!("Failed to publish unread count: {}", e);
+            }
+            Message::None
+        })
+    }
+
+    /// Tab label with the messenger's unread badge
+    fn tab_label(&self, messenger: MessengerType, name: &str) -> String {
+        match self.unread_count(messenger) {
+            0 => name.to_string(),
+            count => format!("{} ({})", name, count),
+        }
+    }
+
+    /// Build the webview in the main window, reporting its load events
+    fn create_webview(&self, events: UnboundedSender<PageEvent>) -> Task<Message> {
+        let Some(id) = self.core.main_window_id() else {
+            return Task::none();
+        };
+
+        let bounds = page::webview_bounds(self.config.window.width, self.config.window.height);
+        window::run_with_handle(id, move |handle| {
+            match page::build_webview(&handle, bounds, events) {
+                Ok(webview) => {
+                    WEBVIEW.set(Some(webview));
+                    Message::WebViewCreated
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to create WebView: {}", e);
+                    Message::None
+                }
+            }
+        })
+        .map(cosmic::Action::App)
+    }
+
+    /// Load the current messenger in the webview, if it has been created yet
+    fn load_current_messenger(&mut self) -> Task<Message> {
+        let url = self.current_messenger.web_url();
+        let loaded = WEBVIEW.with_borrow(|webview| match webview {
+            Some(wv) => wv.load_url(url).is_ok(),
+            // Loaded once the webview is created
+            None => {
+                tracing::debug!("WebView not created yet, deferring {}", url);
+                false
+            }
+        });
+
+        if !loaded {
+            return Task::none();
+        }
+        let attempt = self.page.start();
+        self.watch_load(attempt, url.to_string())
+    }
+
+    /// Check a page load for network and site errors, and time it out
+    fn watch_load(&self, attempt: u64, url: String) -> Task<Message> {
+        Task::batch([
+            cosmic::task::future(async move {
+                match page::diagnose(&url).await {
+                    Some(error) => Message::PageLoadFailed(attempt, error),
+                    None => Message::None,
+                }
+            }),
+            cosmic::task::future(async move {
+                tokio::time::sleep(page::LOAD_TIMEOUT).await;
+                Message::PageLoadTimedOut(attempt)
+            }),
+        ])
+    }
+
+    /// Error overlay with a Retry button, shown in place of the webview
+    fn error_overlay(&self, error: &LoadError) -> Element<'_, Message> {
+        let icon_name = match error {
+            LoadError::Offline => "network-offline-symbolic",
+            LoadError::Site(_) => "dialog-error-symbolic",
+        };
+
+        widget::column::with_children(vec![
+            icon::from_name(icon_name).size(64).into(),
+            text::title3(error.title()).into(),
+            text::body(error.description()).into(),
+            button::suggested("Retry")
+                .on_press(Message::RetryLoad)
+                .into(),
+        ])
+        .spacing(12)
+        .align_x(cosmic::iced::Alignment::Center)
+        .into()
+    }
 }
 
 impl cosmic::Application for CosmicMessages {
     type Executor = cosmic::executor::Default;
-    type Flags = ();
+    type Flags = Config;
     type Message = Message;
     const APP_ID: &'static str = "org.cosmicde.Messages";
 
@@ -74,75 +236,249 @@ impl cosmic::Application for CosmicMessages {
         &mut self.core
     }
 
-    #[allow(clippy::arc_with_non_send_sync)] // WebView is single-threaded by design
-    fn init(core: Core, _flags: Self::Flags) -> (Self, Task<Message>) {
+    fn init(core: Core, config: Self::Flags) -> (Self, Task<Message>) {
+        // Window size is applied through the app settings; the position
+        // can only be restored once the window exists
+        let move_task = match (core.main_window_id(), config.window.position) {
+            (Some(id), Some((x, y))) => window::move_to(id, Point::new(x, y)),
+            _ => Task::none(),
+        };
+        let dbus_task = cosmic::task::future(async {
+            match dbus::start_dbus_service().await {
+                Ok(connection) => Message::DbusReady(connection),
+                Err(e) => {
+                    tracing::warn!("Failed to start D-Bus service: {}", e);
+                    Message::None
+                }
+            }
+        });
+
         (
             Self {
                 core,
-                current_messenger: MessengerType::GoogleMessages,
-                webview: Some(Arc::new(Mutex::new(None))),
+                current_messenger: config.last_messenger,
+                config,
+                unread: HashMap::new(),
+                dbus: None,
+                page: PageLoad::default(),
             },
-            Task::none(),
+            Task::batch([move_task, dbus_task]),
         )
     }
 
+    fn on_close_requested(&self, _id: window::Id) -> Option<Message> {
+        if let Err(e) = self.config.save() {
+            tracing::warn!("Failed to save config: {}", e);
+        }
+        None
+    }
+
+    fn subscription(&self) -> cosmic::iced::Subscription<Message> {
+        struct PageEventSubscription;
+
+        // Events from the webview's navigation and page load handlers
+        let page_events = cosmic::iced::Subscription::run_with_id(
+            std::any::TypeId::of::<PageEventSubscription>(),
+            cosmic::iced::futures::stream::unfold(
+                None,
+                |receiver: Option<tokio::sync::mpsc::UnboundedReceiver<PageEvent>>| async move {
+                    match receiver {
+                        None => {
+                            let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+                            Some((Message::PageEventsReady(sender), Some(receiver)))
+                        }
+                        Some(mut receiver) => {
+                            let event = receiver.recv().await?;
+                            Some((Message::PageLoad(event), Some(receiver)))
+                        }
+                    }
+                },
+            ),
+        );
+
+        let window_events =
+            cosmic::iced::event::listen_with(|event, _status, _window_id| match event {
+                cosmic::iced::Event::Keyboard(cosmic::iced::keyboard::Event::KeyPressed {
+                    key,
+                    modifiers,
+                    ..
+                }) => key_shortcut(&key, modifiers),
+                cosmic::iced::Event::Window(window::Event::Resized(size)) => {
+                    Some(Message::WindowResized(size))
+                }
+                cosmic::iced::Event::Window(window::Event::Moved(position)) => {
+                    Some(Message::WindowMoved(position))
+                }
+                _ => None,
+            });
+
+        cosmic::iced::Subscription::batch([page_events, window_events])
+    }
+
     fn update(&mut self, message: Self::Message) -> Task<Message> {
         match message {
             Message::SwitchMessenger(m) => {
                 self.current_messenger = m;
-                if let Some(ref wv_arc) = self.webview {
-                    if let Ok(guard) = wv_arc.lock() {
-                        if let Some(ref wv) = *guard {
-                            let _ = wv.load_url(m.web_url());
-                        }
-                    }
+                self.config.last_messenger = m;
+                let load = self.load_current_messenger();
+                // Focusing a tab marks its messages as read
+                if self.unread.remove(&m).is_some() {
+                    return Task::batch([load, self.publish_unread()]);
                 }
-                Task::none()
+                load
             }
             Message::NotificationReceived(notif) => {
-                self.current_messenger = notif.messenger;
-                if let Some(ref wv_arc) = self.webview {
-                    if let Ok(guard) = wv_arc.lock() {
-                        if let Some(ref wv) = *guard {
-                            let _ = wv.load_url(notif.messenger.web_url());
+                // The messenger being used shows its messages already
+                if notif.messenger == self.current_messenger {
+                    return Task::none();
+                }
+                // Counted as unread instead of switching away from the
+                // messenger being used
+                *self.unread.entry(notif.messenger).or_insert(0) += 1;
+                self.publish_unread()
+            }
+            Message::WebViewCreated | Message::RetryLoad => self.load_current_messenger(),
+            // Config is saved when the window closes
+            Message::WindowResized(size) => {
+                self.config.window.width = size.width;
+                self.config.window.height = size.height;
+                WEBVIEW.with_borrow(|webview| {
+                    if let Some(wv) = webview {
+                        if let Err(e) = wv.set_bounds(page::webview_bounds(size.width, size.height))
+                        {
+                            tracing::warn!("Failed to resize WebView: {}", e);
                         }
                     }
-                }
+                });
+                Task::none()
+            }
+            Message::WindowMoved(position) => {
+                self.config.window.position = Some((position.x, position.y));
                 Task::none()
             }
-            Message::WebViewCreated => Task::none(),
+            Message::DbusReady(connection) => {
+                self.dbus = Some(connection);
+                self.publish_unread()
+            }
+            Message::PageEventsReady(sender) => self.create_webview(sender),
+            Message::PageLoad(event) => match (self.page.on_event(&event), event) {
+                (Some(attempt), PageEvent::Navigating(url)) => self.watch_load(attempt, url),
+                _ => Task::none(),
+            },
+            Message::PageLoadFailed(attempt, error) => {
+                tracing::warn!(
+                    "Failed to load {}: {:?}",
+                    self.current_messenger.display_name(),
+                    error
+                );
+                self.page.fail(attempt, error);
+                Task::none()
+            }
+            Message::PageLoadTimedOut(attempt) => {
+                self.page.time_out(attempt);
+                Task::none()
+            }
+            Message::None => Task::none(),
         }
     }
 
     fn view(&self) -> Element<'_, Message> {
         let tabs = widget::row::with_children(vec![
-            button::text("Google Messages")
+            button::text(self.tab_label(MessengerType::GoogleMessages, "Google Messages"))
                 .on_press(Message::SwitchMessenger(MessengerType::GoogleMessages))
                 .into(),
-            button::text("WhatsApp")
+            button::text(self.tab_label(MessengerType::WhatsApp, "WhatsApp"))
                 .on_press(Message::SwitchMessenger(MessengerType::WhatsApp))
                 .into(),
-            button::text("Telegram")
+            button::text(self.tab_label(MessengerType::Telegram, "Telegram"))
                 .on_press(Message::SwitchMessenger(MessengerType::Telegram))
                 .into(),
         ])
         .spacing(10)
         .padding(10);
 
+        let content = match self.page.error() {
+            Some(error) => self.error_overlay(error),
+            // The webview is drawn over this area once it is created
+            None => widget::horizontal_space().into(),
+        };
+
         widget::column::with_children(vec![
             tabs.into(),
-            container(text::body(
-                "WebView would be here (requires window handle integration)",
-            ))
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .center(Length::Fill)
-            .into(),
+            container(content)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center(Length::Fill)
+                .into(),
         ])
         .into()
     }
 }
 
 fn main() -> cosmic::iced::Result {
-    cosmic::app::run::<CosmicMessages>(cosmic::app::Settings::default(), ())
+    let config = Config::load().unwrap_or_default();
+    let settings =
+        cosmic::app::Settings::default().size(Size::new(config.window.width, config.window.height));
+
+    cosmic::app::run::<CosmicMessages>(settings, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmic::Application;
+
+    #[test]
+    fn test_init_restores_last_messenger() {
+        let config = Config {
+            last_messenger: MessengerType::Telegram,
+            ..Default::default()
+        };
+
+        let (app, _task) = CosmicMessages::init(Core::default(), config);
+        assert_eq!(app.current_messenger, MessengerType::Telegram);
+    }
+
+    #[test]
+    fn test_focusing_tab_clears_its_unread_count() {
+        let (mut app, _task) = CosmicMessages::init(Core::default(), Config::default());
+
+        let notification = |messenger| {
+            Message::NotificationReceived(MessagingNotification {
+                messenger,
+                sender: "Alice".to_string(),
+                message: "Hi".to_string(),
+                conversation_id: None,
+            })
+        };
+        let _ = app.update(notification(MessengerType::WhatsApp));
+        let _ = app.update(notification(MessengerType::WhatsApp));
+        let _ = app.update(notification(MessengerType::Telegram));
+        assert_eq!(app.unread_count(MessengerType::WhatsApp), 2);
+        assert_eq!(app.total_unread(), 3);
+
+        let _ = app.update(Message::SwitchMessenger(MessengerType::WhatsApp));
+        assert_eq!(app.unread_count(MessengerType::WhatsApp), 0);
+        assert_eq!(app.unread_count(MessengerType::Telegram), 1);
+        assert_eq!(app.total_unread(), 1);
+
+        // Messages of the messenger being used are not unread
+        let _ = app.update(notification(MessengerType::WhatsApp));
+        assert_eq!(app.unread_count(MessengerType::WhatsApp), 0);
+        assert_eq!(app.total_unread(), 1);
+    }
+
+    #[test]
+    fn test_key_shortcut_switches_messenger() {
+        let key = Key::Character("4".into());
+        assert!(matches!(
+            key_shortcut(&key, Modifiers::CTRL),
+            Some(Message::SwitchMessenger(MessengerType::Signal))
+        ));
+
+        // Needs Ctrl, and only 1..6 are mapped
+        assert!(key_shortcut(&key, Modifiers::empty()).is_none());
+        assert!(key_shortcut(&Key::Character("0".into()), Modifiers::CTRL).is_none());
+        assert!(key_shortcut(&Key::Character("7".into()), Modifiers::CTRL).is_none());
+    }
 }
@@ -0,0 +1,274 @@
+//! Page Load Module
+//!
+//! Tracks whether the current messenger's page loaded, so a failed load
+//! shows an error overlay with a Retry button instead of a broken page.
+//!
+//! The webview built by [`build_webview`] reports navigations and finished
+//! loads as [`PageEvent`]s. wry doesn't say why a load failed, and WebKit
+//! reports an error page as finished too, so each navigation is checked in
+//! two ways: [`diagnose`] probes the site to tell a missing network apart
+//! from a site that is down, and a load still running after [`LOAD_TIMEOUT`]
+//! counts as failed.
+
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
+use wry::dpi::{LogicalPosition, LogicalSize};
+use wry::raw_window_handle::HasWindowHandle;
+use wry::{PageLoadEvent, Rect, WebView, WebViewBuilder};
+
+/// Time after which a page still loading counts as failed
+pub const LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Height of the tab row above the webview
+const TAB_BAR_HEIGHT: f32 = 56.0;
+
+/// Time allowed for each step of [`diagnose`]
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Page load progress reported by the webview
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PageEvent {
+    /// The webview is navigating to a URL
+    Navigating(String),
+    /// The webview finished loading a page, possibly an error page
+    Finished,
+}
+
+/// Why a page failed to load
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadError {
+    /// The network is unavailable
+    Offline,
+    /// The network works but the site could not be loaded
+    Site(String),
+}
+
+impl LoadError {
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Offline => "You're offline",
+            Self::Site(_) => "Couldn't load this page",
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Self::Offline => "Check your network connection and try again.".to_string(),
+            Self::Site(reason) => format!("{}. The site may be down, try again later.", reason),
+        }
+    }
+}
+
+/// Load state of the current page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PageState {
+    /// Nothing requested yet
+    Idle,
+    Loading,
+    Loaded,
+    Failed(LoadError),
+}
+
+/// Load state together with the attempt it belongs to
+///
+/// Every load gets a new attempt number, so the results of checks started
+/// for an earlier load are ignored.
+#[derive(Debug)]
+pub struct PageLoad {
+    state: PageState,
+    attempt: u64,
+}
+
+impl Default for PageLoad {
+    fn default() -> Self {
+        Self {
+            state: PageState::Idle,
+            attempt: 0,
+        }
+    }
+}
+
+impl PageLoad {
+    pub fn state(&self) -> &PageState {
+        &self.state
+    }
+
+    /// Error to show in place of the webview, if the page failed
+    pub fn error(&self) -> Option<&LoadError> {
+        match &self.state {
+            PageState::Failed(error) => Some(error),
+            _ => None,
+        }
+    }
+
+    /// Begin a new load, returning its attempt number
+    pub fn start(&mut self) -> u64 {
+        self.attempt += 1;
+        self.state = PageState::Loading;
+        self.attempt
+    }
+
+    /// Apply a webview event
+    ///
+    /// Returns the attempt number when the event began a new load, for
+    /// navigations started from within the page.
+    pub fn on_event(&mut self, event: &PageEvent) -> Option<u64> {
+        match event {
+            PageEvent::Navigating(_) if self.state != PageState::Loading => Some(self.start()),
+            PageEvent::Navigating(_) => None,
+            PageEvent::Finished => {
+                if self.state == PageState::Loading {
+                    self.state = PageState::Loaded;
+                }
+                None
+            }
+        }
+    }
+
+    /// Mark an attempt as failed, unless a newer load replaced it
+    pub fn fail(&mut self, attempt: u64, error: LoadError) {
+        if attempt == self.attempt && matches!(self.state, PageState::Loading | PageState::Loaded) {
+            self.state = PageState::Failed(error);
+        }
+    }
+
+    /// Fail an attempt that is still loading after [`LOAD_TIMEOUT`]
+    pub fn time_out(&mut self, attempt: u64) {
+        if attempt == self.attempt && self.state == PageState::Loading {
+            self.fail(
+                attempt,
+                LoadError::Site("The page took too long to load".into()),
+            );
+        }
+    }
+}
+
+/// Host part of a URL
+pub fn host_of(url: &str) -> Option<&str> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let host = rest.split(['/', '?', '#']).next()?;
+    let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+    let host = host.split(':').next()?;
+    (!host.is_empty()).then_some(host)
+}
+
+/// Check whether a page can be reached
+///
+/// A host name that doesn't resolve means the network is down; a host that
+/// resolves but doesn't accept connections means the site is.
+pub async fn diagnose(url: &str) -> Option<LoadError> {
+    let host = host_of(url)?;
+
+    let addrs: Vec<_> =
+        match tokio::time::timeout(PROBE_TIMEOUT, tokio::net::lookup_host((host, 443))).await {
+            Ok(Ok(addrs)) => addrs.collect(),
+            _ => return Some(LoadError::Offline),
+        };
+    if addrs.is_empty() {
+        return Some(LoadError::Offline);
+    }
+
+    match tokio::time::timeout(PROBE_TIMEOUT, tokio::net::TcpStream::connect(&addrs[..])).await {
+        Ok(Ok(_)) => None,
+        Ok(Err(e)) => Some(LoadError::Site(format!(
+            "Could not connect to {}: {}",
+            host, e
+        ))),
+        Err(_) => Some(LoadError::Site(format!("{} is not responding", host))),
+    }
+}
+
+/// Area of a window of the given size below the tab row
+pub fn webview_bounds(width: f32, height: f32) -> Rect {
+    Rect {
+        position: LogicalPosition::new(0.0, TAB_BAR_HEIGHT).into(),
+        size: LogicalSize::new(width, (height - TAB_BAR_HEIGHT).max(0.0)).into(),
+    }
+}
+
+/// Build the webview in a window, forwarding its load events to the app
+pub fn build_webview(
+    window: &impl HasWindowHandle,
+    bounds: Rect,
+    events: UnboundedSender<PageEvent>,
+) -> wry::Result<WebView> {
+    let navigation_events = events.clone();
+    WebViewBuilder::new()
+        .with_bounds(bounds)
+        .with_navigation_handler(move |url| {
+            tracing::debug!("Navigating to {}", url);
+            let _ = navigation_events.send(PageEvent::Navigating(url));
+            true
+        })
+        .with_on_page_load_handler(move |event, url| {
+            tracing::debug!("Page load {:?}: {}", event, url);
+            if let PageLoadEvent::Finished = event {
+                let _ = events.send(PageEvent::Finished);
+            }
+        })
+        .build_as_child(window)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlay_shown_only_for_failed_load() {
+        let mut page = PageLoad::default();
+        assert!(page.error().is_none());
+
+        // A finished load shows the webview
+        let attempt = page.start();
+        page.on_event(&PageEvent::Finished);
+        assert_eq!(page.state(), &PageState::Loaded);
+        page.time_out(attempt);
+        assert!(page.error().is_none());
+
+        // WebKit finishes error pages too, the probe still fails the load
+        page.fail(attempt, LoadError::Offline);
+        assert_eq!(page.error(), Some(&LoadError::Offline));
+
+        // Retrying hides the overlay, and late results of the old attempt
+        // are ignored
+        let retry = page.start();
+        page.fail(attempt, LoadError::Offline);
+        page.time_out(attempt);
+        assert!(page.error().is_none());
+
+        page.time_out(retry);
+        assert!(matches!(page.error(), Some(LoadError::Site(_))));
+    }
+
+    #[test]
+    fn test_navigation_in_page_starts_new_load() {
+        let mut page = PageLoad::default();
+        let navigating = PageEvent::Navigating("https://web.whatsapp.com".into());
+
+        // The navigation of a load already started is part of it
+        let first = page.start();
+        assert_eq!(page.on_event(&navigating), None);
+
+        page.on_event(&PageEvent::Finished);
+        let second = page.on_event(&navigating).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(page.state(), &PageState::Loading);
+    }
+
+    #[test]
+    fn test_host_of() {
+        assert_eq!(
+            host_of("https://messages.google.com/web"),
+            Some("messages.google.com")
+        );
+        assert_eq!(
+            host_of("https://web.whatsapp.com"),
+            Some("web.whatsapp.com")
+        );
+        assert_eq!(
+            host_of("https://user@example.com:8443/x"),
+            Some("example.com")
+        );
+        assert_eq!(host_of("https://"), None);
+    }
+}
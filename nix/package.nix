@@ -187,6 +187,12 @@ rustPlatform.buildRustPackage rec {
     SystemdService=cosmic-connect-daemon.service
     EOF
 
+    cat > $out/share/dbus-1/services/com.system76.CosmicConnectManager.service << EOF
+    [D-BUS Service]
+    Name=com.system76.CosmicConnectManager
+    Exec=$out/bin/cosmic-connect-manager
+    EOF
+
     # Install desktop entries
     mkdir -p $out/share/applications
 